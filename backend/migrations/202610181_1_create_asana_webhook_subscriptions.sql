@@ -0,0 +1,20 @@
+-- Asana webhook subscriptions backing Asana triggers. One row per workflow;
+-- `hook_secret` is filled in (encrypted) by the X-Hook-Secret handshake and
+-- `webhook_gid` once Asana confirms the subscription.
+CREATE TABLE IF NOT EXISTS asana_webhook_subscriptions (
+  id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+  workflow_id UUID NOT NULL REFERENCES workflows(id) ON DELETE CASCADE,
+  user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+  trigger_type TEXT NOT NULL,
+  resource_gid TEXT NOT NULL,
+  connection_scope TEXT NOT NULL,
+  connection_id UUID NOT NULL,
+  webhook_gid TEXT,
+  hook_secret TEXT,
+  created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+  updated_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+  UNIQUE (workflow_id)
+);
+
+-- Rollback:
+--   DROP TABLE IF EXISTS asana_webhook_subscriptions;
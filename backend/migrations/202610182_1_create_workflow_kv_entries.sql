@@ -0,0 +1,23 @@
+-- Persistent key-value entries for the "store" action. Entries are scoped to
+-- a workspace (shared by its workflows) or to a single workflow; `workflow_id`
+-- is only set for workflow-scoped entries so they go away with the workflow.
+-- Expired entries read as absent and are purged by the worker.
+CREATE TABLE IF NOT EXISTS workflow_kv_entries (
+  id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+  scope TEXT NOT NULL CHECK (scope IN ('workspace', 'workflow')),
+  scope_id UUID NOT NULL,
+  workflow_id UUID REFERENCES workflows(id) ON DELETE CASCADE,
+  key TEXT NOT NULL,
+  value JSONB NOT NULL,
+  expires_at TIMESTAMPTZ,
+  created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+  updated_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+  UNIQUE (scope, scope_id, key)
+);
+
+CREATE INDEX IF NOT EXISTS idx_workflow_kv_entries_expires_at
+  ON workflow_kv_entries (expires_at)
+  WHERE expires_at IS NOT NULL;
+
+-- Rollback:
+--   DROP TABLE IF EXISTS workflow_kv_entries;
@@ -0,0 +1,25 @@
+-- Files produced by workflow runs (downloads, exports, generated documents).
+-- The bytes live in the configured artifact store; this table holds the
+-- metadata nodes pass around and the download endpoints serve.
+-- There are deliberately no foreign keys: when `purge_old_runs` (or a
+-- workflow delete) removes the run, the row is left behind so the worker can
+-- delete the stored object before removing the metadata.
+CREATE TABLE IF NOT EXISTS workflow_run_artifacts (
+  id UUID PRIMARY KEY,
+  workflow_id UUID NOT NULL,
+  run_id UUID NOT NULL,
+  node_id TEXT NOT NULL,
+  name TEXT NOT NULL,
+  mime_type TEXT NOT NULL,
+  size BIGINT NOT NULL,
+  sha256 TEXT NOT NULL,
+  storage TEXT NOT NULL,
+  key TEXT NOT NULL,
+  created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS idx_workflow_run_artifacts_run
+  ON workflow_run_artifacts (workflow_id, run_id, created_at);
+
+-- Rollback:
+--   DROP TABLE IF EXISTS workflow_run_artifacts;
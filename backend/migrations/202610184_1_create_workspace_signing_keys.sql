@@ -0,0 +1,12 @@
+-- Salts for the per-workspace key that signs outbound HTTP requests
+-- (`X-DSentr-Signature`). The key itself is derived from WEBHOOK_SECRET and
+-- the salt, so it is never stored; replacing the salt rotates the key. Rows
+-- are created the first time a workspace's key is used or shown.
+CREATE TABLE IF NOT EXISTS workspace_signing_keys (
+  workspace_id UUID PRIMARY KEY REFERENCES workspaces(id) ON DELETE CASCADE,
+  salt UUID NOT NULL DEFAULT gen_random_uuid(),
+  rotated_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+-- Rollback:
+--   DROP TABLE IF EXISTS workspace_signing_keys;
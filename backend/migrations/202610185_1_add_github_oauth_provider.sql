@@ -0,0 +1,6 @@
+ALTER TYPE oauth_connection_provider ADD VALUE IF NOT EXISTS 'github';
+
+-- Rollback:
+--   -- Postgres enums cannot drop values without recreating the type.
+--   -- To rollback, create a new enum without 'github', alter dependent columns
+--   -- to the new type, and drop the old enum once no longer referenced.
@@ -0,0 +1,27 @@
+-- OAuth2 providers defined per workspace. Connections made through them are
+-- stored as `custom` user_oauth_tokens / workspace_connections rows whose
+-- metadata.custom.providerId points back here. client_id and client_secret
+-- are encrypted with OAUTH_TOKEN_ENCRYPTION_KEY like the tokens themselves.
+CREATE TABLE IF NOT EXISTS oauth_custom_providers (
+  id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+  workspace_id UUID NOT NULL REFERENCES workspaces(id) ON DELETE CASCADE,
+  created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+  name TEXT NOT NULL,
+  authorize_url TEXT NOT NULL,
+  token_url TEXT NOT NULL,
+  userinfo_url TEXT,
+  scopes TEXT NOT NULL DEFAULT '',
+  use_pkce BOOLEAN NOT NULL DEFAULT true,
+  client_id TEXT NOT NULL,
+  client_secret TEXT,
+  token_auth_method TEXT NOT NULL DEFAULT 'client_secret_post'
+    CHECK (token_auth_method IN ('client_secret_post', 'client_secret_basic')),
+  created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+  updated_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS oauth_custom_providers_workspace_name_idx
+  ON oauth_custom_providers (workspace_id, lower(name));
+
+-- Rollback:
+--   DROP TABLE IF EXISTS oauth_custom_providers;
@@ -0,0 +1,18 @@
+-- Last observed watched-property values of each page a Notion
+-- property-changed trigger tracks. Kept out of workflow_schedules.config so a
+-- poll only writes the pages that were added or changed.
+CREATE TABLE IF NOT EXISTS notion_page_snapshots (
+  schedule_id UUID NOT NULL REFERENCES workflow_schedules(id) ON DELETE CASCADE,
+  page_id TEXT NOT NULL,
+  hash TEXT NOT NULL,
+  property_values JSONB NOT NULL DEFAULT '{}'::jsonb,
+  last_edited_time TIMESTAMPTZ NOT NULL,
+  updated_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+  PRIMARY KEY (schedule_id, page_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_notion_page_snapshots_edited
+  ON notion_page_snapshots (schedule_id, last_edited_time DESC);
+
+-- Rollback:
+--   DROP TABLE IF EXISTS notion_page_snapshots;
@@ -45,9 +45,107 @@ pub struct OAuthSettings {
     pub slack: OAuthProviderConfig,
     pub asana: OAuthProviderConfig,
     pub notion: OAuthProviderConfig,
+    /// Usually the same OAuth app as GitHub login, with its own redirect.
+    pub github: OAuthProviderConfig,
     pub token_encryption_key: Vec<u8>,
 }
 
+/// Base URLs for every outbound provider API. Defaults point at production;
+/// each can be overridden from the environment so integrations can be run
+/// against a local stub.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProviderBaseUrls {
+    pub asana: String,
+    pub notion: String,
+    pub github: String,
+    pub microsoft_graph: String,
+    pub slack: String,
+    pub google_sheets: String,
+    pub google_calendar: String,
+    pub google_drive: String,
+    pub google_drive_upload: String,
+    pub sendgrid: String,
+    /// Replaces the region-derived Mailgun host when set.
+    pub mailgun: Option<String>,
+    /// Replaces `https://email.{region}.amazonaws.com` when set.
+    pub ses: Option<String>,
+}
+
+impl Default for ProviderBaseUrls {
+    fn default() -> Self {
+        Self {
+            asana: "https://app.asana.com/api/1.0".into(),
+            notion: "https://api.notion.com/v1".into(),
+            github: "https://api.github.com".into(),
+            microsoft_graph: "https://graph.microsoft.com/v1.0".into(),
+            slack: "https://slack.com/api".into(),
+            google_sheets: "https://sheets.googleapis.com/v4/spreadsheets".into(),
+            google_calendar: "https://www.googleapis.com/calendar/v3".into(),
+            google_drive: "https://www.googleapis.com/drive/v3".into(),
+            google_drive_upload: "https://www.googleapis.com/upload/drive/v3".into(),
+            sendgrid: "https://api.sendgrid.com/v3".into(),
+            mailgun: None,
+            ses: None,
+        }
+    }
+}
+
+impl ProviderBaseUrls {
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            asana: base_url_env(&["ASANA_API_BASE_URL"]).unwrap_or(defaults.asana),
+            notion: base_url_env(&["NOTION_API_BASE_URL"]).unwrap_or(defaults.notion),
+            github: base_url_env(&["GITHUB_API_BASE_URL"]).unwrap_or(defaults.github),
+            microsoft_graph: base_url_env(&["MICROSOFT_GRAPH_BASE_URL"])
+                .unwrap_or(defaults.microsoft_graph),
+            slack: base_url_env(&["SLACK_API_BASE_URL", "SLACK_API_BASE"])
+                .unwrap_or(defaults.slack),
+            google_sheets: base_url_env(&["GOOGLE_SHEETS_API_BASE"])
+                .unwrap_or(defaults.google_sheets),
+            google_calendar: base_url_env(&["GOOGLE_CALENDAR_API_BASE"])
+                .unwrap_or(defaults.google_calendar),
+            google_drive: base_url_env(&["GOOGLE_DRIVE_API_BASE"]).unwrap_or(defaults.google_drive),
+            google_drive_upload: base_url_env(&["GOOGLE_DRIVE_UPLOAD_BASE"])
+                .unwrap_or(defaults.google_drive_upload),
+            sendgrid: base_url_env(&["SENDGRID_API_BASE"]).unwrap_or(defaults.sendgrid),
+            mailgun: base_url_env(&["MAILGUN_API_BASE"]),
+            ses: base_url_env(&["AWS_SES_ENDPOINT"]),
+        }
+    }
+
+    /// Points every provider at `origin`, each under its own path prefix
+    /// (`{origin}/slack`, `{origin}/notion`, ...), so one stub server can
+    /// stand in for all of them.
+    pub fn all_at(origin: &str) -> Self {
+        let origin = origin.trim_end_matches('/');
+        Self {
+            asana: format!("{origin}/asana"),
+            notion: format!("{origin}/notion"),
+            github: format!("{origin}/github"),
+            microsoft_graph: format!("{origin}/graph"),
+            slack: format!("{origin}/slack"),
+            google_sheets: format!("{origin}/sheets"),
+            google_calendar: format!("{origin}/calendar"),
+            google_drive: format!("{origin}/drive"),
+            google_drive_upload: format!("{origin}/drive-upload"),
+            sendgrid: format!("{origin}/sendgrid"),
+            mailgun: Some(format!("{origin}/mailgun")),
+            ses: Some(format!("{origin}/ses")),
+        }
+    }
+}
+
+/// First non-empty value among `names`, without a trailing slash.
+fn base_url_env(names: &[&str]) -> Option<String> {
+    names.iter().find_map(|name| {
+        env::var(name)
+            .ok()
+            .map(|value| value.trim().trim_end_matches('/').to_string())
+            .filter(|value| !value.is_empty())
+    })
+}
+
 #[derive(Clone, Debug)]
 pub struct Config {
     pub database_url: String,
@@ -64,6 +162,11 @@ pub struct Config {
     pub workspace_member_limit: i64,
     pub workspace_monthly_run_limit: i64,
     pub runaway_limit_5min: i64,
+    pub slack_signing_secret: Option<String>,
+    /// Externally reachable origin of this API (e.g. `https://api.example.com`);
+    /// required for provider webhooks such as Asana's that call back into us.
+    pub public_api_base_url: Option<String>,
+    pub provider_base_urls: ProviderBaseUrls,
 }
 
 impl Config {
@@ -147,6 +250,11 @@ impl Config {
             client_secret: require_env("NOTION_INTEGRATIONS_CLIENT_SECRET")?,
             redirect_uri: require_env("NOTION_INTEGRATIONS_REDIRECT_URI")?,
         };
+        let github = OAuthProviderConfig {
+            client_id: require_env("GITHUB_INTEGRATIONS_CLIENT_ID")?,
+            client_secret: require_env("GITHUB_INTEGRATIONS_CLIENT_SECRET")?,
+            redirect_uri: require_env("GITHUB_INTEGRATIONS_REDIRECT_URI")?,
+        };
 
         let encryption_key_b64 = require_env("OAUTH_TOKEN_ENCRYPTION_KEY")?;
         let token_encryption_key =
@@ -189,6 +297,14 @@ impl Config {
             DEFAULT_WORKSPACE_MONTHLY_RUN_LIMIT,
         )?;
         let runaway_limit_5min = parse_positive_env_i64("RUNAWAY_LIMIT_5MIN", RUNAWAY_LIMIT_5MIN)?;
+        let slack_signing_secret = env::var("SLACK_SIGNING_SECRET")
+            .ok()
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty());
+        let public_api_base_url = env::var("PUBLIC_API_BASE_URL")
+            .ok()
+            .map(|value| value.trim().trim_end_matches('/').to_string())
+            .filter(|value| !value.is_empty());
 
         Ok(Config {
             database_url,
@@ -200,6 +316,7 @@ impl Config {
                 slack,
                 asana,
                 notion,
+                github,
                 token_encryption_key,
             },
             api_secrets_encryption_key,
@@ -211,6 +328,9 @@ impl Config {
             workspace_member_limit,
             workspace_monthly_run_limit,
             runaway_limit_5min,
+            slack_signing_secret,
+            public_api_base_url,
+            provider_base_urls: ProviderBaseUrls::from_env(),
         })
     }
 }
@@ -272,15 +392,15 @@ fn parse_positive_env_i64(name: &'static str, default: i64) -> Result<i64, Confi
 #[cfg(test)]
 mod tests {
     use super::{
-        Config, ConfigError, DEFAULT_WORKSPACE_MEMBER_LIMIT, DEFAULT_WORKSPACE_MONTHLY_RUN_LIMIT,
-        RUNAWAY_LIMIT_5MIN,
+        Config, ConfigError, ProviderBaseUrls, DEFAULT_WORKSPACE_MEMBER_LIMIT,
+        DEFAULT_WORKSPACE_MONTHLY_RUN_LIMIT, RUNAWAY_LIMIT_5MIN,
     };
     use base64::Engine as _;
     use std::env;
     use std::sync::Mutex;
     use std::{panic, panic::UnwindSafe};
 
-    const REQUIRED_VARS: [&str; 25] = [
+    const REQUIRED_VARS: [&str; 28] = [
         "DATABASE_URL",
         "FRONTEND_ORIGIN",
         "GOOGLE_INTEGRATIONS_CLIENT_ID",
@@ -306,6 +426,9 @@ mod tests {
         "NOTION_INTEGRATIONS_CLIENT_ID",
         "NOTION_INTEGRATIONS_CLIENT_SECRET",
         "NOTION_INTEGRATIONS_REDIRECT_URI",
+        "GITHUB_INTEGRATIONS_CLIENT_ID",
+        "GITHUB_INTEGRATIONS_CLIENT_SECRET",
+        "GITHUB_INTEGRATIONS_REDIRECT_URI",
     ];
 
     const OPTIONAL_VARS: [&str; 5] = [
@@ -407,6 +530,12 @@ mod tests {
             "NOTION_INTEGRATIONS_REDIRECT_URI",
             "http://localhost/notion",
         );
+        env::set_var("GITHUB_INTEGRATIONS_CLIENT_ID", "github-client-id");
+        env::set_var("GITHUB_INTEGRATIONS_CLIENT_SECRET", "github-client-secret");
+        env::set_var(
+            "GITHUB_INTEGRATIONS_REDIRECT_URI",
+            "http://localhost/github",
+        );
         let key = base64::engine::general_purpose::STANDARD.encode([0u8; 32]);
         env::set_var("OAUTH_TOKEN_ENCRYPTION_KEY", key);
         env::set_var(
@@ -567,4 +696,32 @@ mod tests {
             }
         });
     }
+
+    #[test]
+    fn provider_base_urls_default_to_production_and_honor_overrides() {
+        with_env(|| {
+            populate_defaults();
+            let config = Config::from_env().expect("config should load");
+            assert_eq!(config.provider_base_urls, ProviderBaseUrls::default());
+
+            env::set_var("ASANA_API_BASE_URL", "http://127.0.0.1:9000/asana/");
+            env::set_var("AWS_SES_ENDPOINT", " http://127.0.0.1:9000/ses ");
+            let config = Config::from_env().expect("config should load");
+            env::remove_var("ASANA_API_BASE_URL");
+            env::remove_var("AWS_SES_ENDPOINT");
+
+            assert_eq!(
+                config.provider_base_urls.asana,
+                "http://127.0.0.1:9000/asana"
+            );
+            assert_eq!(
+                config.provider_base_urls.ses.as_deref(),
+                Some("http://127.0.0.1:9000/ses")
+            );
+            assert_eq!(
+                config.provider_base_urls.slack,
+                ProviderBaseUrls::default().slack
+            );
+        });
+    }
 }
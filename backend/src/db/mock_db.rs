@@ -8,11 +8,17 @@ use time::OffsetDateTime;
 use uuid::Uuid;
 
 use super::user_repository::{UserId, UserRepository};
-use crate::db::workflow_repository::{CreateWorkflowRunOutcome, WorkspaceMemberRunCount};
+use crate::db::workflow_repository::{
+    CreateWorkflowRunOutcome, WorkspaceMemberRunCount, WorkspaceSigningSalt,
+};
 use crate::db::{
     workflow_repository::WorkflowRepository,
     workspace_repository::{WorkspaceRepository, WorkspaceRunQuotaUpdate, WorkspaceRunUsage},
 };
+use crate::models::asana_webhook::{AsanaWebhookSubscription, NewAsanaWebhookSubscription};
+use crate::models::kv_store::KvScope;
+use crate::models::notion_page_snapshot::NotionPageSnapshot;
+use crate::models::run_artifact::RunArtifact;
 use crate::models::signup::SignupPayload;
 use crate::models::workflow::Workflow;
 use crate::models::workflow_node_run::WorkflowNodeRun;
@@ -738,6 +744,24 @@ impl WorkflowRepository for NoopWorkflowRepository {
         Ok(())
     }
 
+    async fn list_notion_page_snapshots(
+        &self,
+        _schedule_id: Uuid,
+        _page_ids: &[String],
+    ) -> Result<Vec<NotionPageSnapshot>, sqlx::Error> {
+        Ok(vec![])
+    }
+
+    async fn save_notion_page_snapshots(
+        &self,
+        _schedule_id: Uuid,
+        _reset: bool,
+        _snapshots: &[NotionPageSnapshot],
+        _keep: i64,
+    ) -> Result<(), sqlx::Error> {
+        Ok(())
+    }
+
     async fn claim_next_eligible_run(
         &self,
         _worker_id: &str,
@@ -816,6 +840,125 @@ impl WorkflowRepository for NoopWorkflowRepository {
         Ok(true)
     }
 
+    async fn insert_asana_webhook_subscription(
+        &self,
+        _new_subscription: NewAsanaWebhookSubscription,
+    ) -> Result<AsanaWebhookSubscription, sqlx::Error> {
+        Err(sqlx::Error::RowNotFound)
+    }
+
+    async fn find_asana_webhook_subscription(
+        &self,
+        _subscription_id: Uuid,
+    ) -> Result<Option<AsanaWebhookSubscription>, sqlx::Error> {
+        Ok(None)
+    }
+
+    async fn get_asana_webhook_subscription_for_workflow(
+        &self,
+        _workflow_id: Uuid,
+    ) -> Result<Option<AsanaWebhookSubscription>, sqlx::Error> {
+        Ok(None)
+    }
+
+    async fn set_asana_webhook_secret(
+        &self,
+        _subscription_id: Uuid,
+        _hook_secret: &str,
+    ) -> Result<bool, sqlx::Error> {
+        Ok(false)
+    }
+
+    async fn set_asana_webhook_gid(
+        &self,
+        _subscription_id: Uuid,
+        _webhook_gid: &str,
+    ) -> Result<(), sqlx::Error> {
+        Ok(())
+    }
+
+    async fn delete_asana_webhook_subscription(
+        &self,
+        _subscription_id: Uuid,
+    ) -> Result<(), sqlx::Error> {
+        Ok(())
+    }
+
+    async fn insert_run_artifact(&self, _artifact: &RunArtifact) -> Result<(), sqlx::Error> {
+        Ok(())
+    }
+
+    async fn list_run_artifacts(
+        &self,
+        _workflow_id: Uuid,
+        _run_id: Uuid,
+    ) -> Result<Vec<RunArtifact>, sqlx::Error> {
+        Ok(vec![])
+    }
+
+    async fn find_run_artifact(
+        &self,
+        _workflow_id: Uuid,
+        _artifact_id: Uuid,
+    ) -> Result<Option<RunArtifact>, sqlx::Error> {
+        Ok(None)
+    }
+
+    async fn list_orphaned_run_artifacts(
+        &self,
+        _limit: i64,
+    ) -> Result<Vec<RunArtifact>, sqlx::Error> {
+        Ok(vec![])
+    }
+
+    async fn delete_run_artifact(&self, _artifact_id: Uuid) -> Result<bool, sqlx::Error> {
+        Ok(false)
+    }
+
+    async fn kv_get(&self, _scope: KvScope, _key: &str) -> Result<Option<Value>, sqlx::Error> {
+        Ok(None)
+    }
+
+    async fn kv_set(
+        &self,
+        _scope: KvScope,
+        _key: &str,
+        _value: &Value,
+        _ttl_seconds: Option<i64>,
+        _only_if_absent: bool,
+    ) -> Result<bool, sqlx::Error> {
+        Ok(true)
+    }
+
+    async fn kv_increment(
+        &self,
+        _scope: KvScope,
+        _key: &str,
+        by: f64,
+        _ttl_seconds: Option<i64>,
+    ) -> Result<Option<Value>, sqlx::Error> {
+        Ok(serde_json::Number::from_f64(by).map(Value::Number))
+    }
+
+    async fn kv_append(
+        &self,
+        _scope: KvScope,
+        _key: &str,
+        value: &Value,
+        _ttl_seconds: Option<i64>,
+        _max_items: Option<i64>,
+    ) -> Result<Option<Value>, sqlx::Error> {
+        Ok(Some(Value::Array(vec![value.clone()])))
+    }
+
+    async fn kv_delete(&self, _scope: KvScope, _key: &str) -> Result<bool, sqlx::Error> {
+        Ok(false)
+    }
+
+    async fn purge_expired_kv_entries(&self) -> Result<u64, sqlx::Error> {
+        Ok(0)
+    }
+
     async fn try_record_webhook_signature(
         &self,
         _workflow_id: Uuid,
@@ -831,6 +974,26 @@ impl WorkflowRepository for NoopWorkflowRepository {
         Ok(0)
     }
 
+    async fn get_workspace_signing_salt(
+        &self,
+        _workspace_id: Uuid,
+    ) -> Result<WorkspaceSigningSalt, sqlx::Error> {
+        Ok(WorkspaceSigningSalt {
+            salt: Uuid::nil(),
+            rotated_at: OffsetDateTime::UNIX_EPOCH,
+        })
+    }
+
+    async fn rotate_workspace_signing_salt(
+        &self,
+        _workspace_id: Uuid,
+    ) -> Result<WorkspaceSigningSalt, sqlx::Error> {
+        Ok(WorkspaceSigningSalt {
+            salt: Uuid::new_v4(),
+            rotated_at: OffsetDateTime::now_utc(),
+        })
+    }
+
     async fn insert_egress_block_event(
         &self,
         _user_id: Uuid,
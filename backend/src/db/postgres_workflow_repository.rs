@@ -1,7 +1,11 @@
 use crate::{
     db::workflow_repository::{
-        CreateWorkflowRunOutcome, WorkflowRepository, WorkspaceMemberRunCount,
+        CreateWorkflowRunOutcome, WorkflowRepository, WorkspaceMemberRunCount, WorkspaceSigningSalt,
     },
+    models::asana_webhook::{AsanaWebhookSubscription, NewAsanaWebhookSubscription},
+    models::kv_store::KvScope,
+    models::notion_page_snapshot::NotionPageSnapshot,
+    models::run_artifact::RunArtifact,
     models::workflow::Workflow,
     models::workflow_dead_letter::WorkflowDeadLetter,
     models::workflow_log::WorkflowLog,
@@ -1407,6 +1411,45 @@ impl WorkflowRepository for PostgresWorkflowRepository {
         Ok(res.rows_affected() > 0)
     }
 
+    async fn get_workspace_signing_salt(
+        &self,
+        workspace_id: Uuid,
+    ) -> Result<WorkspaceSigningSalt, sqlx::Error> {
+        // The no-op update makes RETURNING yield the existing row.
+        let (salt, rotated_at) = sqlx::query_as::<_, (Uuid, OffsetDateTime)>(
+            r#"
+            INSERT INTO workspace_signing_keys (workspace_id)
+            VALUES ($1)
+            ON CONFLICT (workspace_id) DO UPDATE
+            SET workspace_id = EXCLUDED.workspace_id
+            RETURNING salt, rotated_at
+            "#,
+        )
+        .bind(workspace_id)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(WorkspaceSigningSalt { salt, rotated_at })
+    }
+
+    async fn rotate_workspace_signing_salt(
+        &self,
+        workspace_id: Uuid,
+    ) -> Result<WorkspaceSigningSalt, sqlx::Error> {
+        let (salt, rotated_at) = sqlx::query_as::<_, (Uuid, OffsetDateTime)>(
+            r#"
+            INSERT INTO workspace_signing_keys (workspace_id)
+            VALUES ($1)
+            ON CONFLICT (workspace_id) DO UPDATE
+            SET salt = gen_random_uuid(), rotated_at = now()
+            RETURNING salt, rotated_at
+            "#,
+        )
+        .bind(workspace_id)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(WorkspaceSigningSalt { salt, rotated_at })
+    }
+
     async fn purge_old_webhook_replays(&self, older_than_seconds: i64) -> Result<u64, sqlx::Error> {
         let res = sqlx::query(
             r#"
@@ -1420,6 +1463,381 @@ impl WorkflowRepository for PostgresWorkflowRepository {
         Ok(res.rows_affected())
     }
 
+    async fn insert_asana_webhook_subscription(
+        &self,
+        new_subscription: NewAsanaWebhookSubscription,
+    ) -> Result<AsanaWebhookSubscription, sqlx::Error> {
+        sqlx::query_as::<_, AsanaWebhookSubscription>(
+            r#"
+            INSERT INTO asana_webhook_subscriptions
+                (workflow_id, user_id, trigger_type, resource_gid, connection_scope, connection_id)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING id, workflow_id, user_id, trigger_type, resource_gid, connection_scope,
+                      connection_id, webhook_gid, hook_secret, created_at, updated_at
+            "#,
+        )
+        .bind(new_subscription.workflow_id)
+        .bind(new_subscription.user_id)
+        .bind(new_subscription.trigger_type)
+        .bind(new_subscription.resource_gid)
+        .bind(new_subscription.connection_scope)
+        .bind(new_subscription.connection_id)
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    async fn find_asana_webhook_subscription(
+        &self,
+        subscription_id: Uuid,
+    ) -> Result<Option<AsanaWebhookSubscription>, sqlx::Error> {
+        sqlx::query_as::<_, AsanaWebhookSubscription>(
+            r#"
+            SELECT id, workflow_id, user_id, trigger_type, resource_gid, connection_scope,
+                   connection_id, webhook_gid, hook_secret, created_at, updated_at
+            FROM asana_webhook_subscriptions
+            WHERE id = $1
+            "#,
+        )
+        .bind(subscription_id)
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    async fn get_asana_webhook_subscription_for_workflow(
+        &self,
+        workflow_id: Uuid,
+    ) -> Result<Option<AsanaWebhookSubscription>, sqlx::Error> {
+        sqlx::query_as::<_, AsanaWebhookSubscription>(
+            r#"
+            SELECT id, workflow_id, user_id, trigger_type, resource_gid, connection_scope,
+                   connection_id, webhook_gid, hook_secret, created_at, updated_at
+            FROM asana_webhook_subscriptions
+            WHERE workflow_id = $1
+            "#,
+        )
+        .bind(workflow_id)
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    async fn set_asana_webhook_secret(
+        &self,
+        subscription_id: Uuid,
+        hook_secret: &str,
+    ) -> Result<bool, sqlx::Error> {
+        let res = sqlx::query(
+            r#"
+            UPDATE asana_webhook_subscriptions
+            SET hook_secret = $2,
+                updated_at = now()
+            WHERE id = $1
+              AND hook_secret IS NULL
+            "#,
+        )
+        .bind(subscription_id)
+        .bind(hook_secret)
+        .execute(&self.pool)
+        .await?;
+        Ok(res.rows_affected() > 0)
+    }
+
+    async fn set_asana_webhook_gid(
+        &self,
+        subscription_id: Uuid,
+        webhook_gid: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            UPDATE asana_webhook_subscriptions
+            SET webhook_gid = $2,
+                updated_at = now()
+            WHERE id = $1
+            "#,
+        )
+        .bind(subscription_id)
+        .bind(webhook_gid)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn delete_asana_webhook_subscription(
+        &self,
+        subscription_id: Uuid,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query("DELETE FROM asana_webhook_subscriptions WHERE id = $1")
+            .bind(subscription_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn insert_run_artifact(&self, artifact: &RunArtifact) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            INSERT INTO workflow_run_artifacts
+                (id, workflow_id, run_id, node_id, name, mime_type, size, sha256, storage, key, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+            "#,
+        )
+        .bind(artifact.id)
+        .bind(artifact.workflow_id)
+        .bind(artifact.run_id)
+        .bind(&artifact.node_id)
+        .bind(&artifact.name)
+        .bind(&artifact.mime_type)
+        .bind(artifact.size)
+        .bind(&artifact.sha256)
+        .bind(&artifact.storage)
+        .bind(&artifact.key)
+        .bind(artifact.created_at)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn list_run_artifacts(
+        &self,
+        workflow_id: Uuid,
+        run_id: Uuid,
+    ) -> Result<Vec<RunArtifact>, sqlx::Error> {
+        sqlx::query_as::<_, RunArtifact>(
+            r#"
+            SELECT id, workflow_id, run_id, node_id, name, mime_type, size, sha256, storage, key, created_at
+            FROM workflow_run_artifacts
+            WHERE workflow_id = $1 AND run_id = $2
+            ORDER BY created_at, id
+            "#,
+        )
+        .bind(workflow_id)
+        .bind(run_id)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    async fn find_run_artifact(
+        &self,
+        workflow_id: Uuid,
+        artifact_id: Uuid,
+    ) -> Result<Option<RunArtifact>, sqlx::Error> {
+        sqlx::query_as::<_, RunArtifact>(
+            r#"
+            SELECT id, workflow_id, run_id, node_id, name, mime_type, size, sha256, storage, key, created_at
+            FROM workflow_run_artifacts
+            WHERE workflow_id = $1 AND id = $2
+            "#,
+        )
+        .bind(workflow_id)
+        .bind(artifact_id)
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    async fn list_orphaned_run_artifacts(
+        &self,
+        limit: i64,
+    ) -> Result<Vec<RunArtifact>, sqlx::Error> {
+        sqlx::query_as::<_, RunArtifact>(
+            r#"
+            SELECT a.id, a.workflow_id, a.run_id, a.node_id, a.name, a.mime_type, a.size,
+                   a.sha256, a.storage, a.key, a.created_at
+            FROM workflow_run_artifacts a
+            WHERE NOT EXISTS (SELECT 1 FROM workflow_runs r WHERE r.id = a.run_id)
+            ORDER BY a.created_at
+            LIMIT $1
+            "#,
+        )
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    async fn delete_run_artifact(&self, artifact_id: Uuid) -> Result<bool, sqlx::Error> {
+        let res = sqlx::query("DELETE FROM workflow_run_artifacts WHERE id = $1")
+            .bind(artifact_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(res.rows_affected() > 0)
+    }
+
+    async fn kv_get(&self, scope: KvScope, key: &str) -> Result<Option<Value>, sqlx::Error> {
+        sqlx::query_scalar::<_, Value>(
+            r#"
+            SELECT value
+            FROM workflow_kv_entries
+            WHERE scope = $1 AND scope_id = $2 AND key = $3
+              AND (expires_at IS NULL OR expires_at > now())
+            "#,
+        )
+        .bind(scope.as_str())
+        .bind(scope.scope_id())
+        .bind(key)
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    async fn kv_set(
+        &self,
+        scope: KvScope,
+        key: &str,
+        value: &Value,
+        ttl_seconds: Option<i64>,
+        only_if_absent: bool,
+    ) -> Result<bool, sqlx::Error> {
+        let stored = sqlx::query_scalar::<_, Uuid>(
+            r#"
+            INSERT INTO workflow_kv_entries (scope, scope_id, workflow_id, key, value, expires_at)
+            VALUES ($1, $2, $3, $4, $5, now() + ($6::bigint * INTERVAL '1 second'))
+            ON CONFLICT (scope, scope_id, key) DO UPDATE
+            SET value = EXCLUDED.value,
+                expires_at = EXCLUDED.expires_at,
+                updated_at = now()
+            WHERE NOT $7
+               OR (workflow_kv_entries.expires_at IS NOT NULL
+                   AND workflow_kv_entries.expires_at <= now())
+            RETURNING id
+            "#,
+        )
+        .bind(scope.as_str())
+        .bind(scope.scope_id())
+        .bind(scope.workflow_id())
+        .bind(key)
+        .bind(value)
+        .bind(ttl_seconds)
+        .bind(only_if_absent)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(stored.is_some())
+    }
+
+    async fn kv_increment(
+        &self,
+        scope: KvScope,
+        key: &str,
+        by: f64,
+        ttl_seconds: Option<i64>,
+    ) -> Result<Option<Value>, sqlx::Error> {
+        // `live` below: the existing entry has not expired. An expired entry
+        // restarts from zero and drops its old expiry.
+        sqlx::query_scalar::<_, Value>(
+            r#"
+            INSERT INTO workflow_kv_entries (scope, scope_id, workflow_id, key, value, expires_at)
+            VALUES ($1, $2, $3, $4, to_jsonb($5::float8::numeric),
+                    now() + ($6::bigint * INTERVAL '1 second'))
+            ON CONFLICT (scope, scope_id, key) DO UPDATE
+            SET value = CASE
+                    WHEN workflow_kv_entries.expires_at IS NULL
+                      OR workflow_kv_entries.expires_at > now()
+                    THEN to_jsonb((workflow_kv_entries.value #>> '{}')::numeric + $5::float8::numeric)
+                    ELSE EXCLUDED.value
+                END,
+                expires_at = CASE
+                    WHEN $6::bigint IS NOT NULL THEN EXCLUDED.expires_at
+                    WHEN workflow_kv_entries.expires_at > now() THEN workflow_kv_entries.expires_at
+                    ELSE NULL
+                END,
+                updated_at = now()
+            WHERE jsonb_typeof(workflow_kv_entries.value) = 'number'
+               OR (workflow_kv_entries.expires_at IS NOT NULL
+                   AND workflow_kv_entries.expires_at <= now())
+            RETURNING value
+            "#,
+        )
+        .bind(scope.as_str())
+        .bind(scope.scope_id())
+        .bind(scope.workflow_id())
+        .bind(key)
+        .bind(by)
+        .bind(ttl_seconds)
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    async fn kv_append(
+        &self,
+        scope: KvScope,
+        key: &str,
+        value: &Value,
+        ttl_seconds: Option<i64>,
+        max_items: Option<i64>,
+    ) -> Result<Option<Value>, sqlx::Error> {
+        sqlx::query_scalar::<_, Value>(
+            r#"
+            INSERT INTO workflow_kv_entries (scope, scope_id, workflow_id, key, value, expires_at)
+            VALUES ($1, $2, $3, $4, jsonb_build_array($5::jsonb),
+                    now() + ($6::bigint * INTERVAL '1 second'))
+            ON CONFLICT (scope, scope_id, key) DO UPDATE
+            SET value = (
+                    SELECT COALESCE(jsonb_agg(item ORDER BY idx), '[]'::jsonb)
+                    FROM jsonb_array_elements(
+                        CASE
+                            WHEN workflow_kv_entries.expires_at IS NULL
+                              OR workflow_kv_entries.expires_at > now()
+                            THEN workflow_kv_entries.value
+                            ELSE '[]'::jsonb
+                        END || jsonb_build_array($5::jsonb)
+                    ) WITH ORDINALITY AS items(item, idx)
+                    WHERE $7::bigint IS NULL
+                       OR idx > (
+                            CASE
+                                WHEN workflow_kv_entries.expires_at IS NULL
+                                  OR workflow_kv_entries.expires_at > now()
+                                THEN jsonb_array_length(workflow_kv_entries.value)
+                                ELSE 0
+                            END
+                        ) + 1 - $7::bigint
+                ),
+                expires_at = CASE
+                    WHEN $6::bigint IS NOT NULL THEN EXCLUDED.expires_at
+                    WHEN workflow_kv_entries.expires_at > now() THEN workflow_kv_entries.expires_at
+                    ELSE NULL
+                END,
+                updated_at = now()
+            WHERE jsonb_typeof(workflow_kv_entries.value) = 'array'
+               OR (workflow_kv_entries.expires_at IS NOT NULL
+                   AND workflow_kv_entries.expires_at <= now())
+            RETURNING value
+            "#,
+        )
+        .bind(scope.as_str())
+        .bind(scope.scope_id())
+        .bind(scope.workflow_id())
+        .bind(key)
+        .bind(value)
+        .bind(ttl_seconds)
+        .bind(max_items)
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    async fn kv_delete(&self, scope: KvScope, key: &str) -> Result<bool, sqlx::Error> {
+        let live = sqlx::query_scalar::<_, bool>(
+            r#"
+            DELETE FROM workflow_kv_entries
+            WHERE scope = $1 AND scope_id = $2 AND key = $3
+            RETURNING (expires_at IS NULL OR expires_at > now())
+            "#,
+        )
+        .bind(scope.as_str())
+        .bind(scope.scope_id())
+        .bind(key)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(live.unwrap_or(false))
+    }
+
+    async fn purge_expired_kv_entries(&self) -> Result<u64, sqlx::Error> {
+        let res = sqlx::query(
+            r#"
+            DELETE FROM workflow_kv_entries
+            WHERE expires_at IS NOT NULL AND expires_at <= now()
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(res.rows_affected())
+    }
+
     async fn insert_egress_block_event(
         &self,
         user_id: Uuid,
@@ -1597,4 +2015,335 @@ impl WorkflowRepository for PostgresWorkflowRepository {
         .await?;
         Ok(())
     }
+
+    async fn list_notion_page_snapshots(
+        &self,
+        schedule_id: Uuid,
+        page_ids: &[String],
+    ) -> Result<Vec<NotionPageSnapshot>, sqlx::Error> {
+        if page_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        sqlx::query_as::<_, NotionPageSnapshot>(
+            r#"
+            SELECT page_id, hash, property_values, last_edited_time
+            FROM notion_page_snapshots
+            WHERE schedule_id = $1
+              AND page_id = ANY($2)
+            "#,
+        )
+        .bind(schedule_id)
+        .bind(page_ids)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    async fn save_notion_page_snapshots(
+        &self,
+        schedule_id: Uuid,
+        reset: bool,
+        snapshots: &[NotionPageSnapshot],
+        keep: i64,
+    ) -> Result<(), sqlx::Error> {
+        if !reset && snapshots.is_empty() {
+            return Ok(());
+        }
+        let mut tx = self.pool.begin().await?;
+        if reset {
+            sqlx::query("DELETE FROM notion_page_snapshots WHERE schedule_id = $1")
+                .bind(schedule_id)
+                .execute(&mut *tx)
+                .await?;
+        }
+        for snapshot in snapshots {
+            sqlx::query(
+                r#"
+                INSERT INTO notion_page_snapshots (
+                    schedule_id, page_id, hash, property_values, last_edited_time
+                )
+                VALUES ($1, $2, $3, $4, $5)
+                ON CONFLICT (schedule_id, page_id) DO UPDATE
+                SET hash = EXCLUDED.hash,
+                    property_values = EXCLUDED.property_values,
+                    last_edited_time = EXCLUDED.last_edited_time,
+                    updated_at = now()
+                "#,
+            )
+            .bind(schedule_id)
+            .bind(&snapshot.page_id)
+            .bind(&snapshot.hash)
+            .bind(&snapshot.property_values)
+            .bind(snapshot.last_edited_time)
+            .execute(&mut *tx)
+            .await?;
+        }
+        sqlx::query(
+            r#"
+            DELETE FROM notion_page_snapshots
+            WHERE schedule_id = $1
+              AND page_id NOT IN (
+                SELECT page_id
+                FROM notion_page_snapshots
+                WHERE schedule_id = $1
+                ORDER BY last_edited_time DESC
+                LIMIT $2
+              )
+            "#,
+        )
+        .bind(schedule_id)
+        .bind(keep)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::test_pg_pool;
+    use serde_json::json;
+    use sqlx::Row;
+    use time::OffsetDateTime;
+
+    async fn insert_user(pool: &PgPool) -> Uuid {
+        let row = sqlx::query(
+            r#"
+            INSERT INTO users (
+                email,
+                password_hash,
+                first_name,
+                last_name,
+                oauth_provider,
+                is_verified,
+                role,
+                created_at
+            )
+            VALUES ($1, '', $2, $3, $4::oauth_provider, true, 'user'::user_role, $5)
+            RETURNING id
+            "#,
+        )
+        .bind(format!("kv-store-{}@example.com", Uuid::new_v4()))
+        .bind("Store")
+        .bind("Tester")
+        .bind("google")
+        .bind(OffsetDateTime::now_utc())
+        .fetch_one(pool)
+        .await
+        .expect("insert user");
+
+        row.get("id")
+    }
+
+    #[tokio::test]
+    #[ignore]
+    async fn run_artifacts_without_a_run_are_reported_as_orphans() {
+        let pool = test_pg_pool();
+        let repo = PostgresWorkflowRepository {
+            pool: (*pool).clone(),
+        };
+        let workflow_id = Uuid::new_v4();
+        let run_id = Uuid::new_v4();
+        let artifact = RunArtifact {
+            id: Uuid::new_v4(),
+            workflow_id,
+            run_id,
+            node_id: "http-1".into(),
+            name: "report.pdf".into(),
+            mime_type: "application/pdf".into(),
+            size: 3,
+            sha256: "abc".into(),
+            storage: "local".into(),
+            key: format!("{workflow_id}/{run_id}/x"),
+            created_at: OffsetDateTime::now_utc(),
+        };
+        repo.insert_run_artifact(&artifact).await.expect("insert");
+
+        let listed = repo
+            .list_run_artifacts(workflow_id, run_id)
+            .await
+            .expect("list");
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].name, "report.pdf");
+        assert!(repo
+            .find_run_artifact(Uuid::new_v4(), artifact.id)
+            .await
+            .expect("find")
+            .is_none());
+
+        // The run id was never inserted, so the artifact counts as orphaned.
+        let orphans = repo
+            .list_orphaned_run_artifacts(10_000)
+            .await
+            .expect("orphans");
+        assert!(orphans.iter().any(|orphan| orphan.id == artifact.id));
+
+        assert!(repo.delete_run_artifact(artifact.id).await.expect("delete"));
+        assert!(!repo.delete_run_artifact(artifact.id).await.expect("delete"));
+    }
+
+    #[tokio::test]
+    #[ignore]
+    async fn kv_entries_support_counters_lists_and_expiry() {
+        let pool = test_pg_pool();
+        let repo = PostgresWorkflowRepository {
+            pool: (*pool).clone(),
+        };
+        let user_id = insert_user(&pool).await;
+        let workflow = repo
+            .create_workflow(user_id, None, "KV store", None, json!({}))
+            .await
+            .expect("create workflow");
+        let scope = KvScope::Workflow(workflow.id);
+
+        assert!(repo
+            .kv_set(scope, "cursor", &json!("a"), None, true)
+            .await
+            .unwrap());
+        assert!(!repo
+            .kv_set(scope, "cursor", &json!("b"), None, true)
+            .await
+            .unwrap());
+        assert_eq!(
+            repo.kv_get(scope, "cursor").await.unwrap(),
+            Some(json!("a"))
+        );
+        // Workspace scope is a separate namespace even with the same id.
+        assert_eq!(
+            repo.kv_get(KvScope::Workspace(workflow.id), "cursor")
+                .await
+                .unwrap(),
+            None
+        );
+
+        assert_eq!(
+            repo.kv_increment(scope, "count", 2.0, None).await.unwrap(),
+            Some(json!(2))
+        );
+        assert_eq!(
+            repo.kv_increment(scope, "count", 0.5, None).await.unwrap(),
+            Some(json!(2.5))
+        );
+        assert_eq!(
+            repo.kv_increment(scope, "cursor", 1.0, None).await.unwrap(),
+            None
+        );
+
+        for item in 1..=4 {
+            repo.kv_append(scope, "recent", &json!(item), None, Some(3))
+                .await
+                .unwrap();
+        }
+        assert_eq!(
+            repo.kv_get(scope, "recent").await.unwrap(),
+            Some(json!([2, 3, 4]))
+        );
+        assert_eq!(
+            repo.kv_append(scope, "count", &json!(1), None, None)
+                .await
+                .unwrap(),
+            None
+        );
+
+        // An expired entry reads as absent and can be claimed again.
+        assert!(repo
+            .kv_set(scope, "seen", &json!(true), Some(60), false)
+            .await
+            .unwrap());
+        sqlx::query("UPDATE workflow_kv_entries SET expires_at = now() - INTERVAL '1 second' WHERE scope_id = $1 AND key = 'seen'")
+            .bind(workflow.id)
+            .execute(pool.as_ref())
+            .await
+            .unwrap();
+        assert_eq!(repo.kv_get(scope, "seen").await.unwrap(), None);
+        assert!(!repo.kv_delete(scope, "seen").await.unwrap());
+        assert!(repo
+            .kv_set(scope, "seen", &json!(1), Some(60), true)
+            .await
+            .unwrap());
+
+        assert!(repo.kv_delete(scope, "cursor").await.unwrap());
+        assert_eq!(repo.kv_get(scope, "cursor").await.unwrap(), None);
+
+        sqlx::query("DELETE FROM users WHERE id = $1")
+            .bind(user_id)
+            .execute(pool.as_ref())
+            .await
+            .ok();
+    }
+
+    #[tokio::test]
+    #[ignore]
+    async fn notion_page_snapshots_are_upserted_reset_and_pruned() {
+        let pool = test_pg_pool();
+        let repo = PostgresWorkflowRepository {
+            pool: (*pool).clone(),
+        };
+        let user_id = insert_user(&pool).await;
+        let workflow = repo
+            .create_workflow(user_id, None, "Notion snapshots", None, json!({}))
+            .await
+            .expect("create workflow");
+        repo.upsert_workflow_schedule(user_id, workflow.id, json!({}), None)
+            .await
+            .expect("schedule");
+        let schedule = repo
+            .get_schedule_for_workflow(workflow.id)
+            .await
+            .expect("get schedule")
+            .expect("schedule exists");
+        let now = OffsetDateTime::now_utc();
+        let snapshot = |page_id: &str, hash: &str, minutes_ago: i64| NotionPageSnapshot {
+            page_id: page_id.into(),
+            hash: hash.into(),
+            property_values: json!({ "Status": hash }),
+            last_edited_time: now - time::Duration::minutes(minutes_ago),
+        };
+        let ids = ["a", "b", "c"].map(String::from);
+
+        repo.save_notion_page_snapshots(
+            schedule.id,
+            false,
+            &[
+                snapshot("a", "1", 3),
+                snapshot("b", "1", 2),
+                snapshot("c", "1", 1),
+            ],
+            2,
+        )
+        .await
+        .expect("save");
+        let mut listed = repo
+            .list_notion_page_snapshots(schedule.id, &ids)
+            .await
+            .expect("list");
+        listed.sort_by(|x, y| x.page_id.cmp(&y.page_id));
+        assert_eq!(
+            listed
+                .iter()
+                .map(|s| s.page_id.as_str())
+                .collect::<Vec<_>>(),
+            ["b", "c"],
+            "the least recently edited page is pruned"
+        );
+
+        repo.save_notion_page_snapshots(schedule.id, false, &[snapshot("b", "2", 0)], 2)
+            .await
+            .expect("update");
+        let listed = repo
+            .list_notion_page_snapshots(schedule.id, &ids[1..2])
+            .await
+            .expect("list");
+        assert_eq!(listed[0].property_values, json!({ "Status": "2" }));
+
+        repo.save_notion_page_snapshots(schedule.id, true, &[snapshot("a", "3", 0)], 2)
+            .await
+            .expect("reset");
+        let listed = repo
+            .list_notion_page_snapshots(schedule.id, &ids)
+            .await
+            .expect("list");
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].page_id, "a");
+    }
 }
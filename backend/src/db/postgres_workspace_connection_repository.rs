@@ -4,13 +4,17 @@ use time::OffsetDateTime;
 use uuid::Uuid;
 
 use crate::db::workspace_connection_repository::{
-    NewWorkspaceAuditEvent, NewWorkspaceConnection, StaleWorkspaceConnection,
-    WorkspaceConnectionListing, WorkspaceConnectionRepository,
+    NewCustomOAuthProvider, NewWorkspaceAuditEvent, NewWorkspaceConnection,
+    StaleWorkspaceConnection, WorkspaceConnectionListing, WorkspaceConnectionRepository,
 };
 use crate::models::oauth_token::{
-    ConnectedOAuthProvider, WorkspaceAuditEvent, WorkspaceConnection,
+    ConnectedOAuthProvider, CustomOAuthProvider, WorkspaceAuditEvent, WorkspaceConnection,
 };
 
+const CUSTOM_PROVIDER_COLUMNS: &str = "id, workspace_id, created_by, name, authorize_url, \
+     token_url, userinfo_url, scopes, use_pkce, client_id, client_secret, token_auth_method, \
+     created_at, updated_at";
+
 pub struct PostgresWorkspaceConnectionRepository {
     pub pool: PgPool,
 }
@@ -303,6 +307,46 @@ impl WorkspaceConnectionRepository for PostgresWorkspaceConnectionRepository {
         .await
     }
 
+    async fn list_slack_by_team(
+        &self,
+        slack_team_id: &str,
+    ) -> Result<Vec<WorkspaceConnection>, sqlx::Error> {
+        let team_id = slack_team_id.trim();
+        if team_id.is_empty() || team_id.len() > 32 {
+            return Ok(Vec::new());
+        }
+
+        sqlx::query_as!(
+            WorkspaceConnection,
+            r#"
+            SELECT
+                id,
+                connection_id as "connection_id?",
+                workspace_id,
+                created_by,
+                owner_user_id,
+                user_oauth_token_id as "user_oauth_token_id?",
+                provider as "provider: _",
+                access_token,
+                refresh_token,
+                expires_at,
+                account_email,
+                created_at,
+                updated_at,
+                bot_user_id,
+                slack_team_id,
+                incoming_webhook_url,
+                metadata
+            FROM workspace_connections
+            WHERE provider = 'slack'::oauth_connection_provider
+              AND slack_team_id = $1
+            "#,
+            team_id,
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
     async fn list_for_workspace(
         &self,
         workspace_id: Uuid,
@@ -770,6 +814,147 @@ impl WorkspaceConnectionRepository for PostgresWorkspaceConnectionRepository {
         .fetch_one(&self.pool)
         .await
     }
+
+    async fn list_custom_providers(
+        &self,
+        workspace_id: Uuid,
+    ) -> Result<Vec<CustomOAuthProvider>, sqlx::Error> {
+        sqlx::query_as::<_, CustomOAuthProvider>(&format!(
+            r#"
+            SELECT {CUSTOM_PROVIDER_COLUMNS}
+            FROM oauth_custom_providers
+            WHERE workspace_id = $1
+            ORDER BY lower(name)
+            "#
+        ))
+        .bind(workspace_id)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    async fn find_custom_provider(
+        &self,
+        provider_id: Uuid,
+    ) -> Result<Option<CustomOAuthProvider>, sqlx::Error> {
+        sqlx::query_as::<_, CustomOAuthProvider>(&format!(
+            "SELECT {CUSTOM_PROVIDER_COLUMNS} FROM oauth_custom_providers WHERE id = $1"
+        ))
+        .bind(provider_id)
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    async fn insert_custom_provider(
+        &self,
+        provider: NewCustomOAuthProvider,
+    ) -> Result<CustomOAuthProvider, sqlx::Error> {
+        sqlx::query_as::<_, CustomOAuthProvider>(&format!(
+            r#"
+            INSERT INTO oauth_custom_providers (
+                workspace_id,
+                created_by,
+                name,
+                authorize_url,
+                token_url,
+                userinfo_url,
+                scopes,
+                use_pkce,
+                client_id,
+                client_secret,
+                token_auth_method
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+            RETURNING {CUSTOM_PROVIDER_COLUMNS}
+            "#
+        ))
+        .bind(provider.workspace_id)
+        .bind(provider.created_by)
+        .bind(provider.name)
+        .bind(provider.authorize_url)
+        .bind(provider.token_url)
+        .bind(provider.userinfo_url)
+        .bind(provider.scopes)
+        .bind(provider.use_pkce)
+        .bind(provider.client_id)
+        .bind(provider.client_secret)
+        .bind(provider.token_auth_method)
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    async fn update_custom_provider(
+        &self,
+        provider_id: Uuid,
+        provider: NewCustomOAuthProvider,
+    ) -> Result<CustomOAuthProvider, sqlx::Error> {
+        sqlx::query_as::<_, CustomOAuthProvider>(&format!(
+            r#"
+            UPDATE oauth_custom_providers
+            SET
+                name = $3,
+                authorize_url = $4,
+                token_url = $5,
+                userinfo_url = $6,
+                scopes = $7,
+                use_pkce = $8,
+                client_id = $9,
+                client_secret = $10,
+                token_auth_method = $11,
+                updated_at = now()
+            WHERE id = $1 AND workspace_id = $2
+            RETURNING {CUSTOM_PROVIDER_COLUMNS}
+            "#
+        ))
+        .bind(provider_id)
+        .bind(provider.workspace_id)
+        .bind(provider.name)
+        .bind(provider.authorize_url)
+        .bind(provider.token_url)
+        .bind(provider.userinfo_url)
+        .bind(provider.scopes)
+        .bind(provider.use_pkce)
+        .bind(provider.client_id)
+        .bind(provider.client_secret)
+        .bind(provider.token_auth_method)
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    async fn delete_custom_provider(&self, provider_id: Uuid) -> Result<bool, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        let provider_key = provider_id.to_string();
+
+        sqlx::query(
+            r#"
+            DELETE FROM workspace_connections
+            WHERE provider = 'custom'
+              AND metadata->'custom'->>'providerId' = $1
+            "#,
+        )
+        .bind(&provider_key)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r#"
+            DELETE FROM user_oauth_tokens
+            WHERE provider = 'custom'
+              AND metadata->'custom'->>'providerId' = $1
+            "#,
+        )
+        .bind(&provider_key)
+        .execute(&mut *tx)
+        .await?;
+
+        let deleted = sqlx::query("DELETE FROM oauth_custom_providers WHERE id = $1")
+            .bind(provider_id)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+
+        tx.commit().await?;
+        Ok(deleted > 0)
+    }
 }
 
 #[cfg(test)]
@@ -2,6 +2,10 @@ use async_trait::async_trait;
 use serde_json::Value;
 use uuid::Uuid;
 
+use crate::models::asana_webhook::{AsanaWebhookSubscription, NewAsanaWebhookSubscription};
+use crate::models::kv_store::KvScope;
+use crate::models::notion_page_snapshot::NotionPageSnapshot;
+use crate::models::run_artifact::RunArtifact;
 use crate::models::workflow::Workflow;
 use crate::models::workflow_log::WorkflowLog;
 use crate::models::workflow_node_run::WorkflowNodeRun;
@@ -16,6 +20,13 @@ pub struct CreateWorkflowRunOutcome {
     pub created: bool,
 }
 
+/// Salt behind a workspace's outbound request signing key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WorkspaceSigningSalt {
+    pub salt: Uuid,
+    pub rotated_at: OffsetDateTime,
+}
+
 #[derive(Debug, Clone)]
 pub struct WorkspaceMemberRunCount {
     pub user_id: Uuid,
@@ -294,6 +305,23 @@ pub trait WorkflowRepository: Send + Sync {
         next_run_at: Option<OffsetDateTime>,
     ) -> Result<(), sqlx::Error>;
 
+    async fn list_notion_page_snapshots(
+        &self,
+        schedule_id: Uuid,
+        page_ids: &[String],
+    ) -> Result<Vec<NotionPageSnapshot>, sqlx::Error>;
+
+    /// Upserts `snapshots` for the schedule, first clearing every stored
+    /// snapshot when `reset` is set, then keeps only the `keep` most recently
+    /// edited pages.
+    async fn save_notion_page_snapshots(
+        &self,
+        schedule_id: Uuid,
+        reset: bool,
+        snapshots: &[NotionPageSnapshot],
+        keep: i64,
+    ) -> Result<(), sqlx::Error>;
+
     async fn claim_next_eligible_run(
         &self,
         worker_id: &str,
@@ -366,6 +394,117 @@ pub trait WorkflowRepository: Send + Sync {
     #[allow(dead_code)]
     async fn purge_old_webhook_replays(&self, older_than_seconds: i64) -> Result<u64, sqlx::Error>;
 
+    /// Returns the workspace's signing salt, creating one on first use.
+    async fn get_workspace_signing_salt(
+        &self,
+        workspace_id: Uuid,
+    ) -> Result<WorkspaceSigningSalt, sqlx::Error>;
+
+    async fn rotate_workspace_signing_salt(
+        &self,
+        workspace_id: Uuid,
+    ) -> Result<WorkspaceSigningSalt, sqlx::Error>;
+
+    // Asana webhook subscriptions
+    async fn insert_asana_webhook_subscription(
+        &self,
+        new_subscription: NewAsanaWebhookSubscription,
+    ) -> Result<AsanaWebhookSubscription, sqlx::Error>;
+
+    async fn find_asana_webhook_subscription(
+        &self,
+        subscription_id: Uuid,
+    ) -> Result<Option<AsanaWebhookSubscription>, sqlx::Error>;
+
+    async fn get_asana_webhook_subscription_for_workflow(
+        &self,
+        workflow_id: Uuid,
+    ) -> Result<Option<AsanaWebhookSubscription>, sqlx::Error>;
+
+    /// Stores the handshake secret only if none was recorded yet, so a
+    /// replayed handshake cannot rotate it. Returns whether it was stored.
+    async fn set_asana_webhook_secret(
+        &self,
+        subscription_id: Uuid,
+        hook_secret: &str,
+    ) -> Result<bool, sqlx::Error>;
+
+    async fn set_asana_webhook_gid(
+        &self,
+        subscription_id: Uuid,
+        webhook_gid: &str,
+    ) -> Result<(), sqlx::Error>;
+
+    async fn delete_asana_webhook_subscription(
+        &self,
+        subscription_id: Uuid,
+    ) -> Result<(), sqlx::Error>;
+
+    // Run artifacts
+    async fn insert_run_artifact(&self, artifact: &RunArtifact) -> Result<(), sqlx::Error>;
+
+    async fn list_run_artifacts(
+        &self,
+        workflow_id: Uuid,
+        run_id: Uuid,
+    ) -> Result<Vec<RunArtifact>, sqlx::Error>;
+
+    async fn find_run_artifact(
+        &self,
+        workflow_id: Uuid,
+        artifact_id: Uuid,
+    ) -> Result<Option<RunArtifact>, sqlx::Error>;
+
+    /// Artifacts whose run no longer exists (purged by retention or deleted
+    /// with its workflow), oldest first.
+    async fn list_orphaned_run_artifacts(
+        &self,
+        limit: i64,
+    ) -> Result<Vec<RunArtifact>, sqlx::Error>;
+
+    async fn delete_run_artifact(&self, artifact_id: Uuid) -> Result<bool, sqlx::Error>;
+
+    // Key-value store ("store" action). Expired entries behave as absent.
+    async fn kv_get(&self, scope: KvScope, key: &str) -> Result<Option<Value>, sqlx::Error>;
+
+    /// Stores `value`, replacing any live entry unless `only_if_absent` is
+    /// set. Returns whether the value was written.
+    async fn kv_set(
+        &self,
+        scope: KvScope,
+        key: &str,
+        value: &Value,
+        ttl_seconds: Option<i64>,
+        only_if_absent: bool,
+    ) -> Result<bool, sqlx::Error>;
+
+    /// Adds `by` to a numeric entry (starting from zero) and returns the new
+    /// value, or `None` when the live entry is not a number.
+    async fn kv_increment(
+        &self,
+        scope: KvScope,
+        key: &str,
+        by: f64,
+        ttl_seconds: Option<i64>,
+    ) -> Result<Option<Value>, sqlx::Error>;
+
+    /// Appends `value` to a list entry (starting from an empty list), keeping
+    /// at most the newest `max_items`. Returns the new list, or `None` when
+    /// the live entry is not a list.
+    async fn kv_append(
+        &self,
+        scope: KvScope,
+        key: &str,
+        value: &Value,
+        ttl_seconds: Option<i64>,
+        max_items: Option<i64>,
+    ) -> Result<Option<Value>, sqlx::Error>;
+
+    /// Returns whether a live entry was removed.
+    async fn kv_delete(&self, scope: KvScope, key: &str) -> Result<bool, sqlx::Error>;
+
+    async fn purge_expired_kv_entries(&self) -> Result<u64, sqlx::Error>;
+
     // Egress block events
     async fn insert_egress_block_event(
         &self,
@@ -4,7 +4,7 @@ use serde_json::Value;
 use uuid::Uuid;
 
 use crate::models::oauth_token::{
-    ConnectedOAuthProvider, WorkspaceAuditEvent, WorkspaceConnection,
+    ConnectedOAuthProvider, CustomOAuthProvider, WorkspaceAuditEvent, WorkspaceConnection,
 };
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -31,6 +31,23 @@ pub struct NewWorkspaceConnection {
     pub metadata: serde_json::Value,
 }
 
+/// Insert/update payload for a custom OAuth provider. `client_id` and
+/// `client_secret` must already be encrypted.
+#[derive(Debug, Clone)]
+pub struct NewCustomOAuthProvider {
+    pub workspace_id: Uuid,
+    pub created_by: Uuid,
+    pub name: String,
+    pub authorize_url: String,
+    pub token_url: String,
+    pub userinfo_url: Option<String>,
+    pub scopes: String,
+    pub use_pkce: bool,
+    pub client_id: String,
+    pub client_secret: Option<String>,
+    pub token_auth_method: String,
+}
+
 #[derive(Debug, Clone)]
 pub struct NewWorkspaceAuditEvent {
     pub workspace_id: Uuid,
@@ -137,6 +154,13 @@ pub trait WorkspaceConnectionRepository: Send + Sync {
         Ok(matches.pop())
     }
 
+    /// Lists Slack workspace connections for a Slack team across all dsentr
+    /// workspaces. Used to route inbound Slack events to their workspaces.
+    async fn list_slack_by_team(
+        &self,
+        slack_team_id: &str,
+    ) -> Result<Vec<WorkspaceConnection>, sqlx::Error>;
+
     async fn list_for_workspace(
         &self,
         workspace_id: Uuid,
@@ -224,6 +248,47 @@ pub trait WorkspaceConnectionRepository: Send + Sync {
         &self,
         event: NewWorkspaceAuditEvent,
     ) -> Result<WorkspaceAuditEvent, sqlx::Error>;
+
+    async fn list_custom_providers(
+        &self,
+        workspace_id: Uuid,
+    ) -> Result<Vec<CustomOAuthProvider>, sqlx::Error> {
+        let _ = workspace_id;
+        Ok(Vec::new())
+    }
+
+    async fn find_custom_provider(
+        &self,
+        provider_id: Uuid,
+    ) -> Result<Option<CustomOAuthProvider>, sqlx::Error> {
+        let _ = provider_id;
+        Ok(None)
+    }
+
+    async fn insert_custom_provider(
+        &self,
+        provider: NewCustomOAuthProvider,
+    ) -> Result<CustomOAuthProvider, sqlx::Error> {
+        let _ = provider;
+        Err(sqlx::Error::RowNotFound)
+    }
+
+    /// Replaces every editable field; `created_by` and `workspace_id` are kept.
+    async fn update_custom_provider(
+        &self,
+        provider_id: Uuid,
+        provider: NewCustomOAuthProvider,
+    ) -> Result<CustomOAuthProvider, sqlx::Error> {
+        let _ = (provider_id, provider);
+        Err(sqlx::Error::RowNotFound)
+    }
+
+    /// Deletes the provider together with every personal token and workspace
+    /// connection made through it. Returns false when it did not exist.
+    async fn delete_custom_provider(&self, provider_id: Uuid) -> Result<bool, sqlx::Error> {
+        let _ = provider_id;
+        Ok(false)
+    }
 }
 
 #[derive(Default)]
@@ -266,6 +331,13 @@ impl WorkspaceConnectionRepository for NoopWorkspaceConnectionRepository {
         Ok(Vec::new())
     }
 
+    async fn list_slack_by_team(
+        &self,
+        _slack_team_id: &str,
+    ) -> Result<Vec<WorkspaceConnection>, sqlx::Error> {
+        Ok(Vec::new())
+    }
+
     async fn find_by_source_token(
         &self,
         _user_oauth_token_id: Uuid,
@@ -11,8 +11,6 @@ use crate::state::AppState;
 
 use super::{ensure_run_membership, ensure_workspace_plan};
 
-const ASANA_BASE_URL: &str = "https://app.asana.com/api/1.0";
-
 fn read_required(
     params: &Value,
     key: &str,
@@ -171,6 +169,8 @@ pub(crate) async fn execute_asana(
         }
     };
 
+    let base = state.config.provider_base_urls.asana.as_str();
+
     match operation.as_str() {
         "createproject" => {
             let workspace_gid = read_required(&params, "workspaceGid", "Workspace GID", context)?;
@@ -193,7 +193,7 @@ pub(crate) async fn execute_asana(
             payload.extend(parse_additional_fields(&params, context));
             let response = state
                 .http_client
-                .post(format!("{ASANA_BASE_URL}/projects"))
+                .post(format!("{base}/projects"))
                 .bearer_auth(&access_token)
                 .json(&json!({ "data": payload }))
                 .send()
@@ -206,7 +206,7 @@ pub(crate) async fn execute_asana(
             let project_gid = read_required(&params, "projectGid", "Project GID", context)?;
             let response = state
                 .http_client
-                .delete(format!("{ASANA_BASE_URL}/projects/{project_gid}"))
+                .delete(format!("{base}/projects/{project_gid}"))
                 .bearer_auth(&access_token)
                 .send()
                 .await
@@ -218,7 +218,7 @@ pub(crate) async fn execute_asana(
             let project_gid = read_required(&params, "projectGid", "Project GID", context)?;
             let response = state
                 .http_client
-                .get(format!("{ASANA_BASE_URL}/projects/{project_gid}"))
+                .get(format!("{base}/projects/{project_gid}"))
                 .bearer_auth(&access_token)
                 .send()
                 .await
@@ -239,7 +239,7 @@ pub(crate) async fn execute_asana(
             }
             let response = state
                 .http_client
-                .get(format!("{ASANA_BASE_URL}/projects"))
+                .get(format!("{base}/projects"))
                 .bearer_auth(&access_token)
                 .query(&query)
                 .send()
@@ -265,7 +265,7 @@ pub(crate) async fn execute_asana(
             }
             let response = state
                 .http_client
-                .put(format!("{ASANA_BASE_URL}/projects/{project_gid}"))
+                .put(format!("{base}/projects/{project_gid}"))
                 .bearer_auth(&access_token)
                 .json(&json!({ "data": payload }))
                 .send()
@@ -294,7 +294,7 @@ pub(crate) async fn execute_asana(
 
             let response = state
                 .http_client
-                .post(format!("{ASANA_BASE_URL}/tasks/{parent}/subtasks"))
+                .post(format!("{base}/tasks/{parent}/subtasks"))
                 .bearer_auth(&access_token)
                 .json(&json!({ "data": payload }))
                 .send()
@@ -311,7 +311,7 @@ pub(crate) async fn execute_asana(
             }
             let response = state
                 .http_client
-                .get(format!("{ASANA_BASE_URL}/tasks/{parent}/subtasks"))
+                .get(format!("{base}/tasks/{parent}/subtasks"))
                 .bearer_auth(&access_token)
                 .query(&query)
                 .send()
@@ -346,7 +346,7 @@ pub(crate) async fn execute_asana(
             }
             let response = state
                 .http_client
-                .post(format!("{ASANA_BASE_URL}/tasks"))
+                .post(format!("{base}/tasks"))
                 .bearer_auth(&access_token)
                 .json(&json!({ "data": payload }))
                 .send()
@@ -359,7 +359,7 @@ pub(crate) async fn execute_asana(
             let task_gid = read_required(&params, "taskGid", "Task GID", context)?;
             let response = state
                 .http_client
-                .delete(format!("{ASANA_BASE_URL}/tasks/{task_gid}"))
+                .delete(format!("{base}/tasks/{task_gid}"))
                 .bearer_auth(&access_token)
                 .send()
                 .await
@@ -371,7 +371,7 @@ pub(crate) async fn execute_asana(
             let task_gid = read_required(&params, "taskGid", "Task GID", context)?;
             let response = state
                 .http_client
-                .get(format!("{ASANA_BASE_URL}/tasks/{task_gid}"))
+                .get(format!("{base}/tasks/{task_gid}"))
                 .bearer_auth(&access_token)
                 .send()
                 .await
@@ -407,7 +407,7 @@ pub(crate) async fn execute_asana(
             }
             let response = state
                 .http_client
-                .get(format!("{ASANA_BASE_URL}/tasks"))
+                .get(format!("{base}/tasks"))
                 .bearer_auth(&access_token)
                 .query(&query)
                 .send()
@@ -421,7 +421,7 @@ pub(crate) async fn execute_asana(
             let section_gid = read_required(&params, "sectionGid", "Section GID", context)?;
             let response = state
                 .http_client
-                .post(format!("{ASANA_BASE_URL}/sections/{section_gid}/addTask"))
+                .post(format!("{base}/sections/{section_gid}/addTask"))
                 .bearer_auth(&access_token)
                 .json(&json!({ "data": { "task": task_gid } }))
                 .send()
@@ -451,9 +451,7 @@ pub(crate) async fn execute_asana(
             }
             let response = state
                 .http_client
-                .get(format!(
-                    "{ASANA_BASE_URL}/workspaces/{workspace_gid}/tasks/search"
-                ))
+                .get(format!("{base}/workspaces/{workspace_gid}/tasks/search"))
                 .bearer_auth(&access_token)
                 .query(&query)
                 .send()
@@ -488,7 +486,7 @@ pub(crate) async fn execute_asana(
             }
             let response = state
                 .http_client
-                .put(format!("{ASANA_BASE_URL}/tasks/{task_gid}"))
+                .put(format!("{base}/tasks/{task_gid}"))
                 .bearer_auth(&access_token)
                 .json(&json!({ "data": payload }))
                 .send()
@@ -502,7 +500,7 @@ pub(crate) async fn execute_asana(
             let text = read_required(&params, "notes", "Comment text", context)?;
             let response = state
                 .http_client
-                .post(format!("{ASANA_BASE_URL}/tasks/{task_gid}/stories"))
+                .post(format!("{base}/tasks/{task_gid}/stories"))
                 .bearer_auth(&access_token)
                 .json(&json!({ "data": { "text": text } }))
                 .send()
@@ -515,7 +513,7 @@ pub(crate) async fn execute_asana(
             let story_gid = read_required(&params, "storyGid", "Story GID", context)?;
             let response = state
                 .http_client
-                .delete(format!("{ASANA_BASE_URL}/stories/{story_gid}"))
+                .delete(format!("{base}/stories/{story_gid}"))
                 .bearer_auth(&access_token)
                 .send()
                 .await
@@ -530,7 +528,7 @@ pub(crate) async fn execute_asana(
             payload.insert("project".to_string(), Value::String(project_gid));
             let response = state
                 .http_client
-                .post(format!("{ASANA_BASE_URL}/tasks/{task_gid}/addProject"))
+                .post(format!("{base}/tasks/{task_gid}/addProject"))
                 .bearer_auth(&access_token)
                 .json(&json!({ "data": payload }))
                 .send()
@@ -544,7 +542,7 @@ pub(crate) async fn execute_asana(
             let project_gid = read_required(&params, "projectGid", "Project GID", context)?;
             let response = state
                 .http_client
-                .post(format!("{ASANA_BASE_URL}/tasks/{task_gid}/removeProject"))
+                .post(format!("{base}/tasks/{task_gid}/removeProject"))
                 .bearer_auth(&access_token)
                 .json(&json!({ "data": { "project": project_gid } }))
                 .send()
@@ -558,7 +556,7 @@ pub(crate) async fn execute_asana(
             let tag_gid = read_required(&params, "tagGid", "Tag GID", context)?;
             let response = state
                 .http_client
-                .post(format!("{ASANA_BASE_URL}/tasks/{task_gid}/addTag"))
+                .post(format!("{base}/tasks/{task_gid}/addTag"))
                 .bearer_auth(&access_token)
                 .json(&json!({ "data": { "tag": tag_gid } }))
                 .send()
@@ -572,7 +570,7 @@ pub(crate) async fn execute_asana(
             let tag_gid = read_required(&params, "tagGid", "Tag GID", context)?;
             let response = state
                 .http_client
-                .post(format!("{ASANA_BASE_URL}/tasks/{task_gid}/removeTag"))
+                .post(format!("{base}/tasks/{task_gid}/removeTag"))
                 .bearer_auth(&access_token)
                 .json(&json!({ "data": { "tag": tag_gid } }))
                 .send()
@@ -585,7 +583,7 @@ pub(crate) async fn execute_asana(
             let user_gid = read_required(&params, "userGid", "User GID", context)?;
             let response = state
                 .http_client
-                .get(format!("{ASANA_BASE_URL}/users/{user_gid}"))
+                .get(format!("{base}/users/{user_gid}"))
                 .bearer_auth(&access_token)
                 .send()
                 .await
@@ -604,7 +602,7 @@ pub(crate) async fn execute_asana(
             }
             let response = state
                 .http_client
-                .get(format!("{ASANA_BASE_URL}/users"))
+                .get(format!("{base}/users"))
                 .bearer_auth(&access_token)
                 .query(&query)
                 .send()
@@ -622,3 +620,110 @@ pub(crate) async fn execute_asana(
         )),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::mock_db::NoopWorkspaceRepository;
+    use crate::test_support::FakeProvider;
+    use crate::test_support::{
+        oauth_service_with_token, sample_run, test_config_with_urls, test_state,
+    };
+    use axum::http::{Method, StatusCode};
+    use std::sync::Arc;
+
+    fn asana_node(params: Value) -> Node {
+        Node {
+            id: "asana".into(),
+            kind: "action".into(),
+            data: json!({ "params": params }),
+        }
+    }
+
+    async fn state_for(provider: &FakeProvider) -> (AppState, WorkflowRun, Uuid) {
+        let user_id = Uuid::new_v4();
+        let (oauth_accounts, token_id) = oauth_service_with_token(user_id, "user@example.com");
+        let mut state = test_state(
+            oauth_accounts,
+            Arc::new(reqwest::Client::new()),
+            Arc::new(NoopWorkspaceRepository),
+        );
+        state.config = test_config_with_urls(provider.base_urls());
+        (state, sample_run(user_id), token_id)
+    }
+
+    #[tokio::test]
+    async fn create_task_posts_payload_to_configured_base_url() {
+        let provider = FakeProvider::start().await;
+        provider.respond(
+            Method::POST,
+            "/asana/tasks",
+            StatusCode::CREATED,
+            json!({ "data": { "gid": "t-1", "name": "Ship it" } }),
+        );
+        let (state, run, token_id) = state_for(&provider).await;
+
+        let node = asana_node(json!({
+            "operation": "createTask",
+            "connection": {
+                "connectionScope": "personal",
+                "connectionId": token_id.to_string()
+            },
+            "workspaceGid": "w-1",
+            "projectGid": "p-1",
+            "name": "{{ title }}",
+            "dueOn": "2026-11-01"
+        }));
+
+        let (output, _) = execute_asana(&node, &json!({ "title": "Ship it" }), &state, &run)
+            .await
+            .expect("create task should succeed");
+
+        assert_eq!(output["data"]["gid"], "t-1");
+        let requests = provider.requests_to(Method::POST, "/asana/tasks");
+        assert_eq!(requests.len(), 1);
+        assert_eq!(
+            requests[0].header("authorization").as_deref(),
+            Some("Bearer access-token")
+        );
+        let body = requests[0].json();
+        assert_eq!(body["data"]["workspace"], "w-1");
+        assert_eq!(body["data"]["name"], "Ship it");
+        assert_eq!(body["data"]["projects"], json!(["p-1"]));
+        assert_eq!(body["data"]["due_on"], "2026-11-01");
+    }
+
+    #[tokio::test]
+    async fn provider_errors_surface_status_and_body() {
+        let provider = FakeProvider::start().await;
+        provider.respond(
+            Method::GET,
+            "/asana/tasks/missing",
+            StatusCode::NOT_FOUND,
+            json!({ "errors": [{ "message": "task: Unknown object: missing" }] }),
+        );
+        let (state, run, token_id) = state_for(&provider).await;
+
+        let node = asana_node(json!({
+            "operation": "getTask",
+            "connection": {
+                "connectionScope": "personal",
+                "connectionId": token_id.to_string()
+            },
+            "taskGid": "missing"
+        }));
+
+        let err = execute_asana(&node, &Value::Null, &state, &run)
+            .await
+            .expect_err("missing task should fail");
+
+        assert!(err.contains("404"), "unexpected error: {err}");
+        assert!(err.contains("Unknown object"), "unexpected error: {err}");
+        assert_eq!(
+            provider
+                .requests_to(Method::GET, "/asana/tasks/missing")
+                .len(),
+            1
+        );
+    }
+}
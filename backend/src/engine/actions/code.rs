@@ -1,15 +1,270 @@
 use std::collections::HashSet;
+use std::fmt::Write as _;
+use std::future::Future;
+use std::pin::pin;
+use std::rc::Rc;
+use std::task::{Context as TaskContext, Poll, Waker};
+use std::time::{Duration, Instant};
 
-use boa_engine::context::Context as JsContext;
-use boa_engine::Source;
+use base64::Engine as _;
+use boa_engine::context::{Context as JsContext, ContextBuilder, HostHooks};
+use boa_engine::{
+    JsArgs, JsNativeError, JsResult, JsString, JsValue, NativeFunction, Script, Source,
+};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use hmac::{Hmac, Mac};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use serde::{Deserialize, Serialize};
 use serde_json::{json, Map, Value};
+use sha2::{Digest, Sha256, Sha512};
 
+use super::http::{egress_redirect_policy, enforce_egress_policy};
+use super::sandbox::{self, run_in_sandbox, SandboxJob};
 use crate::engine::graph::Node;
 use crate::engine::templating::templ_str;
+use crate::models::workflow_run::WorkflowRun;
+use crate::state::AppState;
 
+const DEFAULT_TIMEOUT_MS: u64 = 5_000;
+const MAX_TIMEOUT_MS: u64 = 30_000;
+/// Instructions the VM runs between budget and deadline checks.
+const INSTRUCTION_SLICE: u32 = 10_000;
+const MAX_INSTRUCTIONS: u64 = 50_000_000;
+const LOOP_ITERATION_LIMIT: u64 = 5_000_000;
+const RECURSION_LIMIT: usize = 400;
+const MEMORY_LIMIT_BYTES: usize = 64 * 1024 * 1024;
+const MAX_FETCH_REQUESTS: usize = 10;
+const MAX_FETCH_RESPONSE_BYTES: usize = 5 * 1024 * 1024;
+
+/// Helpers available to every script: `dsentr` (dates, hashes, base64),
+/// `_` (a small lodash-style toolkit) and a synchronous `fetch`.
+const PRELUDE: &str = r#"
+const dsentr = Object.freeze({
+  formatDate: (value, format, timeZone) => __dsentrFormatDate(
+    value instanceof Date ? value.toISOString() : String(value ?? ''),
+    String(format ?? '%Y-%m-%dT%H:%M:%S%:z'),
+    timeZone == null ? '' : String(timeZone)),
+  sha256: (text) => __dsentrHash('sha256', String(text)),
+  sha512: (text) => __dsentrHash('sha512', String(text)),
+  hmacSha256: (key, text) => __dsentrHmac('sha256', String(key), String(text)),
+  hmacSha512: (key, text) => __dsentrHmac('sha512', String(key), String(text)),
+  base64Encode: (text) => __dsentrBase64('encode', String(text)),
+  base64Decode: (text) => __dsentrBase64('decode', String(text)),
+  base64UrlEncode: (text) => __dsentrBase64('encodeUrl', String(text)),
+  base64UrlDecode: (text) => __dsentrBase64('decodeUrl', String(text)),
+});
+const _ = (() => {
+  const iteratee = (key) => typeof key === 'function' ? key : (item) => item?.[key];
+  const pathOf = (path) => Array.isArray(path)
+    ? path
+    : String(path).replace(/\[(\w+)\]/g, '.$1').split('.').filter(Boolean);
+  return Object.freeze({
+    get: (obj, path, fallback) => {
+      let cur = obj;
+      for (const key of pathOf(path)) {
+        if (cur == null) return fallback;
+        cur = cur[key];
+      }
+      return cur === undefined ? fallback : cur;
+    },
+    pick: (obj, keys) => Object.fromEntries(
+      keys.filter((k) => obj != null && k in Object(obj)).map((k) => [k, obj[k]])),
+    omit: (obj, keys) => Object.fromEntries(
+      Object.entries(obj ?? {}).filter(([k]) => !keys.includes(k))),
+    groupBy: (items, key) => items.reduce((acc, item) => {
+      const k = iteratee(key)(item);
+      (acc[k] ??= []).push(item);
+      return acc;
+    }, {}),
+    keyBy: (items, key) => Object.fromEntries(items.map((item) => [iteratee(key)(item), item])),
+    chunk: (items, size) => {
+      const step = Math.max(1, Math.floor(size) || 1);
+      const out = [];
+      for (let i = 0; i < items.length; i += step) out.push(items.slice(i, i + step));
+      return out;
+    },
+    uniq: (items) => [...new Set(items)],
+    uniqBy: (items, key) => {
+      const seen = new Set();
+      return items.filter((item) => {
+        const k = iteratee(key)(item);
+        if (seen.has(k)) return false;
+        seen.add(k);
+        return true;
+      });
+    },
+    sortBy: (items, key) => [...items].sort((a, b) => {
+      const x = iteratee(key)(a);
+      const y = iteratee(key)(b);
+      return x < y ? -1 : x > y ? 1 : 0;
+    }),
+    flatten: (items) => items.flat(),
+    sum: (items) => items.reduce((total, n) => total + Number(n || 0), 0),
+    isEmpty: (value) => value == null
+      || (typeof value === 'object' ? Object.keys(value).length === 0 : String(value).length === 0),
+  });
+})();
+const fetch = (resource, init) => {
+  const raw = JSON.parse(__dsentrFetch(String(resource), JSON.stringify(init ?? {})));
+  const headers = Object.fromEntries(
+    Object.entries(raw.headers).map(([k, v]) => [k.toLowerCase(), v]));
+  return Object.freeze({
+    status: raw.status,
+    ok: raw.status >= 200 && raw.status < 300,
+    url: raw.url,
+    headers: Object.freeze({
+      get: (name) => headers[String(name).toLowerCase()] ?? null,
+      entries: () => Object.entries(headers),
+    }),
+    text: () => raw.body,
+    json: () => JSON.parse(raw.body),
+  });
+};
+"#;
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub(super) struct SandboxLimits {
+    pub(super) timeout: Duration,
+    pub(super) max_instructions: u64,
+    pub(super) memory_bytes: usize,
+}
+
+impl SandboxLimits {
+    pub(super) fn from_params(params: &Value) -> Self {
+        let timeout_ms = params
+            .get("timeoutMs")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(DEFAULT_TIMEOUT_MS)
+            .clamp(1, MAX_TIMEOUT_MS);
+        Self {
+            timeout: Duration::from_millis(timeout_ms),
+            max_instructions: MAX_INSTRUCTIONS,
+            memory_bytes: MEMORY_LIMIT_BYTES,
+        }
+    }
+}
+
+/// Performs the `fetch` calls the sandbox child forwards, under the HTTP
+/// node's egress policy.
+pub(super) struct FetchBridge {
+    node: Node,
+    allowed_hosts: Vec<String>,
+    disallowed_hosts: Vec<String>,
+    default_deny: bool,
+    is_prod: bool,
+    state: AppState,
+    run: WorkflowRun,
+    deadline: Instant,
+    requests: usize,
+}
+
+impl FetchBridge {
+    pub(super) async fn fetch(&mut self, url: &str, init: &str) -> Result<String, String> {
+        if self.requests >= MAX_FETCH_REQUESTS {
+            return Err(format!(
+                "fetch is limited to {} requests per run of a code node",
+                MAX_FETCH_REQUESTS
+            ));
+        }
+        self.requests += 1;
+        let remaining = self.deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return Err("fetch called after the code node timed out".to_string());
+        }
+
+        let init: Value = serde_json::from_str(init).unwrap_or(Value::Null);
+        let method = init
+            .get("method")
+            .and_then(|v| v.as_str())
+            .unwrap_or("GET")
+            .to_ascii_uppercase();
+        let method = reqwest::Method::from_bytes(method.as_bytes())
+            .map_err(|_| format!("Unsupported fetch method: {method}"))?;
+        let mut headers = HeaderMap::new();
+        if let Some(map) = init.get("headers").and_then(|v| v.as_object()) {
+            for (key, value) in map {
+                let value = match value {
+                    Value::String(s) => s.clone(),
+                    other => other.to_string(),
+                };
+                let name = HeaderName::try_from(key.as_str())
+                    .map_err(|_| format!("Invalid header name: {key}"))?;
+                let value = HeaderValue::from_str(&value)
+                    .map_err(|_| format!("Invalid value for header {key}"))?;
+                headers.append(name, value);
+            }
+        }
+        let body = match init.get("body") {
+            None | Some(Value::Null) => None,
+            Some(Value::String(s)) => Some(s.clone()),
+            Some(other) => Some(other.to_string()),
+        };
+
+        enforce_egress_policy(
+            url,
+            &self.node,
+            &self.allowed_hosts,
+            &self.disallowed_hosts,
+            self.default_deny,
+            self.is_prod,
+            &self.state,
+            &self.run,
+        )
+        .await?;
+        let client = reqwest::Client::builder()
+            .redirect(egress_redirect_policy(
+                self.allowed_hosts.clone(),
+                self.disallowed_hosts.clone(),
+                self.default_deny,
+                self.is_prod,
+            ))
+            .timeout(remaining)
+            .build()
+            .map_err(|e| e.to_string())?;
+        let mut request = client.request(method, url).headers(headers);
+        if let Some(body) = body {
+            request = request.body(body);
+        }
+        let mut resp = request.send().await.map_err(|e| e.to_string())?;
+        let status = resp.status().as_u16();
+        let final_url = resp.url().to_string();
+        let mut header_map = Map::new();
+        for (k, v) in resp.headers() {
+            if let Ok(s) = v.to_str() {
+                header_map.insert(k.as_str().to_string(), Value::String(s.to_string()));
+            }
+        }
+        let mut bytes = Vec::new();
+        while let Some(chunk) = resp.chunk().await.map_err(|e| e.to_string())? {
+            if bytes.len() + chunk.len() > MAX_FETCH_RESPONSE_BYTES {
+                return Err(format!(
+                    "fetch response exceeds {} bytes",
+                    MAX_FETCH_RESPONSE_BYTES
+                ));
+            }
+            bytes.extend_from_slice(&chunk);
+        }
+        Ok(json!({
+            "status": status,
+            "url": final_url,
+            "headers": header_map,
+            "body": String::from_utf8_lossy(&bytes),
+        })
+        .to_string())
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
 pub(crate) async fn execute_code(
     node: &Node,
     context: &Value,
+    allowed_hosts: &[String],
+    disallowed_hosts: &[String],
+    default_deny: bool,
+    is_prod: bool,
+    state: &AppState,
+    run: &WorkflowRun,
 ) -> Result<(Value, Option<String>), String> {
     let params = node.data.get("params").cloned().unwrap_or(Value::Null);
     let code_raw = params
@@ -27,37 +282,257 @@ pub(crate) async fn execute_code(
         .map_err(|_| "Failed to serialize custom code inputs".to_string())?;
 
     let script = format!(
-        "const inputs = {};\nconst context = {};\nconst __dsentrResult = (() => {{\n{}\n}})();\nJSON.stringify(__dsentrResult);",
-        inputs_literal, context_literal, code_raw
+        "{}\nconst inputs = {};\nconst context = {};\nconst __dsentrResult = (() => {{\n{}\n}})();\nJSON.stringify(__dsentrResult);",
+        PRELUDE, inputs_literal, context_literal, code_raw
     );
 
-    let mut js_context = JsContext::default();
-    let result = js_context
-        .eval(Source::from_bytes(script.as_bytes()))
+    let limits = SandboxLimits::from_params(&params);
+    let fetch = params
+        .get("enableFetch")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false)
+        .then(|| FetchBridge {
+            node: node.clone(),
+            allowed_hosts: allowed_hosts.to_vec(),
+            disallowed_hosts: disallowed_hosts.to_vec(),
+            default_deny,
+            is_prod,
+            state: state.clone(),
+            run: run.clone(),
+            deadline: Instant::now() + limits.timeout,
+            requests: 0,
+        });
+
+    let result_value = run_in_sandbox(SandboxJob::JavaScript { script }, limits, fetch).await?;
+
+    let outputs = map_outputs(&params, result_value, &input_keys)?;
+
+    Ok((outputs, None))
+}
+
+/// Refuses `ArrayBuffer`s (and so typed arrays) larger than the memory
+/// budget up front, rather than letting the allocation hit the child's cap.
+struct SandboxHooks {
+    max_buffer_bytes: u64,
+}
+
+impl HostHooks for SandboxHooks {
+    fn max_buffer_size(&self, _context: &mut JsContext) -> u64 {
+        self.max_buffer_bytes
+    }
+}
+
+/// Evaluates a prepared script; runs inside the sandbox child.
+pub(super) fn evaluate_script(script: &str, limits: SandboxLimits) -> Result<Value, String> {
+    let started = Instant::now();
+    let mut js_context = ContextBuilder::new()
+        .host_hooks(Rc::new(SandboxHooks {
+            max_buffer_bytes: limits.memory_bytes as u64,
+        }))
+        .build()
         .map_err(format_js_error)?;
+    let runtime_limits = js_context.runtime_limits_mut();
+    runtime_limits.set_loop_iteration_limit(LOOP_ITERATION_LIMIT);
+    runtime_limits.set_recursion_limit(RECURSION_LIMIT);
+    register_helpers(&mut js_context)?;
+
+    let parsed = Script::parse(Source::from_bytes(script.as_bytes()), None, &mut js_context)
+        .map_err(format_js_error)?;
+    let result = run_with_budget(
+        parsed.evaluate_async_with_budget(&mut js_context, INSTRUCTION_SLICE),
+        limits,
+        started,
+    )?
+    .map_err(format_js_error)?;
+
+    if result.is_undefined() || result.is_null() {
+        return Ok(Value::Null);
+    }
+    let json_text = result
+        .to_string(&mut js_context)
+        .map_err(format_js_error)?
+        .to_std_string()
+        .map_err(|_| "Failed to convert custom code result to string".to_string())?;
+
+    if json_text.trim().is_empty() {
+        Ok(Value::Null)
+    } else {
+        Ok(serde_json::from_str::<Value>(&json_text).unwrap_or(Value::String(json_text.clone())))
+    }
+}
+
+/// Polls the evaluation future by hand. The VM yields every
+/// `INSTRUCTION_SLICE` instructions, which is where the instruction budget
+/// and deadline are enforced; dropping the future aborts the script.
+fn run_with_budget<F>(
+    future: F,
+    limits: SandboxLimits,
+    started: Instant,
+) -> Result<F::Output, String>
+where
+    F: Future,
+{
+    let mut future = pin!(future);
+    let mut cx = TaskContext::from_waker(Waker::noop());
+    let mut executed: u64 = 0;
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return Ok(output);
+        }
+        executed += u64::from(INSTRUCTION_SLICE);
+        if executed > limits.max_instructions {
+            return Err("Custom code exceeded its instruction budget".to_string());
+        }
+        if started.elapsed() > limits.timeout {
+            return Err(format!(
+                "Custom code timed out after {} ms",
+                limits.timeout.as_millis()
+            ));
+        }
+    }
+}
+
+fn register_helpers(js_context: &mut JsContext) -> Result<(), String> {
+    let helpers: [(&str, usize, NativeFunction); 5] = [
+        (
+            "__dsentrFormatDate",
+            3,
+            NativeFunction::from_fn_ptr(js_format_date),
+        ),
+        ("__dsentrHash", 2, NativeFunction::from_fn_ptr(js_hash)),
+        ("__dsentrHmac", 3, NativeFunction::from_fn_ptr(js_hmac)),
+        ("__dsentrBase64", 2, NativeFunction::from_fn_ptr(js_base64)),
+        ("__dsentrFetch", 2, NativeFunction::from_fn_ptr(js_fetch)),
+    ];
+    for (name, length, function) in helpers {
+        js_context
+            .register_global_builtin_callable(JsString::from(name), length, function)
+            .map_err(format_js_error)?;
+    }
+    Ok(())
+}
+
+fn string_arg(args: &[JsValue], index: usize, context: &mut JsContext) -> JsResult<String> {
+    Ok(args
+        .get_or_undefined(index)
+        .to_string(context)?
+        .to_std_string_escaped())
+}
+
+fn type_error(message: impl Into<String>) -> boa_engine::JsError {
+    JsNativeError::typ().with_message(message.into()).into()
+}
+
+fn parse_instant(raw: &str) -> Option<DateTime<Utc>> {
+    let raw = raw.trim();
+    if raw.is_empty() || raw.eq_ignore_ascii_case("now") {
+        return Some(Utc::now());
+    }
+    if let Ok(millis) = raw.parse::<i64>() {
+        return Utc.timestamp_millis_opt(millis).single();
+    }
+    if let Ok(parsed) = DateTime::parse_from_rfc3339(raw) {
+        return Some(parsed.with_timezone(&Utc));
+    }
+    if let Ok(naive) = NaiveDateTime::parse_from_str(raw, "%Y-%m-%dT%H:%M:%S%.f") {
+        return Some(naive.and_utc());
+    }
+    NaiveDate::parse_from_str(raw, "%Y-%m-%d")
+        .ok()
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+        .map(|naive| naive.and_utc())
+}
 
-    let result_value = if result.is_undefined() || result.is_null() {
-        Value::Null
+fn js_format_date(_: &JsValue, args: &[JsValue], context: &mut JsContext) -> JsResult<JsValue> {
+    let raw = string_arg(args, 0, context)?;
+    let pattern = string_arg(args, 1, context)?;
+    let time_zone = string_arg(args, 2, context)?;
+    let instant = parse_instant(&raw).ok_or_else(|| type_error(format!("Invalid date: {raw}")))?;
+    let mut out = String::new();
+    // Writing (rather than `to_string`) turns an invalid pattern into an
+    // error instead of a panic.
+    let written = if time_zone.is_empty() {
+        write!(out, "{}", instant.format(&pattern))
     } else {
-        let json_text = result
-            .to_string(&mut js_context)
-            .map_err(format_js_error)?
-            .to_std_string()
-            .map_err(|_| "Failed to convert custom code result to string".to_string())?;
+        let tz: Tz = time_zone
+            .parse()
+            .map_err(|_| type_error(format!("Unknown time zone: {time_zone}")))?;
+        write!(out, "{}", instant.with_timezone(&tz).format(&pattern))
+    };
+    written.map_err(|_| type_error(format!("Invalid date format: {pattern}")))?;
+    Ok(JsValue::from(JsString::from(out.as_str())))
+}
 
-        if json_text.trim().is_empty() {
-            Value::Null
-        } else {
-            serde_json::from_str::<Value>(&json_text).unwrap_or(Value::String(json_text.clone()))
+fn js_hash(_: &JsValue, args: &[JsValue], context: &mut JsContext) -> JsResult<JsValue> {
+    let algorithm = string_arg(args, 0, context)?;
+    let text = string_arg(args, 1, context)?;
+    let digest = match algorithm.as_str() {
+        "sha256" => hex::encode(Sha256::digest(text.as_bytes())),
+        "sha512" => hex::encode(Sha512::digest(text.as_bytes())),
+        other => return Err(type_error(format!("Unsupported hash algorithm: {other}"))),
+    };
+    Ok(JsValue::from(JsString::from(digest.as_str())))
+}
+
+fn js_hmac(_: &JsValue, args: &[JsValue], context: &mut JsContext) -> JsResult<JsValue> {
+    let algorithm = string_arg(args, 0, context)?;
+    let key = string_arg(args, 1, context)?;
+    let text = string_arg(args, 2, context)?;
+    let digest = match algorithm.as_str() {
+        "sha256" => {
+            let mut mac = Hmac::<Sha256>::new_from_slice(key.as_bytes())
+                .map_err(|_| type_error("Invalid HMAC key"))?;
+            mac.update(text.as_bytes());
+            hex::encode(mac.finalize().into_bytes())
         }
+        "sha512" => {
+            let mut mac = Hmac::<Sha512>::new_from_slice(key.as_bytes())
+                .map_err(|_| type_error("Invalid HMAC key"))?;
+            mac.update(text.as_bytes());
+            hex::encode(mac.finalize().into_bytes())
+        }
+        other => return Err(type_error(format!("Unsupported HMAC algorithm: {other}"))),
     };
+    Ok(JsValue::from(JsString::from(digest.as_str())))
+}
 
-    let outputs = map_outputs(&params, result_value, &input_keys)?;
+fn js_base64(_: &JsValue, args: &[JsValue], context: &mut JsContext) -> JsResult<JsValue> {
+    use base64::engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD};
 
-    Ok((outputs, None))
+    let mode = string_arg(args, 0, context)?;
+    let text = string_arg(args, 1, context)?;
+    let out = match mode.as_str() {
+        "encode" => STANDARD.encode(text.as_bytes()),
+        "encodeUrl" => URL_SAFE_NO_PAD.encode(text.as_bytes()),
+        "decode" | "decodeUrl" => {
+            let engine = if mode == "decode" {
+                STANDARD
+            } else {
+                URL_SAFE_NO_PAD
+            };
+            let bytes = engine
+                .decode(text.trim().as_bytes())
+                .map_err(|e| type_error(format!("Invalid base64 input: {e}")))?;
+            String::from_utf8(bytes)
+                .map_err(|_| type_error("Decoded base64 data is not valid UTF-8"))?
+        }
+        other => return Err(type_error(format!("Unsupported base64 mode: {other}"))),
+    };
+    Ok(JsValue::from(JsString::from(out.as_str())))
+}
+
+fn js_fetch(_: &JsValue, args: &[JsValue], context: &mut JsContext) -> JsResult<JsValue> {
+    let url = string_arg(args, 0, context)?;
+    let init = string_arg(args, 1, context)?;
+    sandbox::fetch(&url, &init)
+        .map(|body| JsValue::from(JsString::from(body.as_str())))
+        .map_err(type_error)
 }
 
-fn build_inputs(params: &Value, context: &Value) -> Result<(Value, HashSet<String>), String> {
+pub(super) fn build_inputs(
+    params: &Value,
+    context: &Value,
+) -> Result<(Value, HashSet<String>), String> {
     let mut inputs = Map::new();
     let mut seen = HashSet::new();
     if let Some(arr) = params.get("inputs").and_then(|v| v.as_array()) {
@@ -82,7 +557,7 @@ fn build_inputs(params: &Value, context: &Value) -> Result<(Value, HashSet<Strin
     Ok((Value::Object(inputs), seen))
 }
 
-fn map_outputs(
+pub(super) fn map_outputs(
     params: &Value,
     result_value: Value,
     input_keys: &HashSet<String>,
@@ -222,3 +697,195 @@ fn format_js_error(err: boa_engine::JsError) -> String {
         message
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::mock_db::NoopWorkspaceRepository;
+    use crate::test_support::{oauth_service_with_token, sample_run, test_state};
+    use httpmock::{Method::POST, MockServer};
+    use reqwest::Client;
+    use std::sync::Arc;
+    use uuid::Uuid;
+
+    fn code_state() -> AppState {
+        let (oauth, _) = oauth_service_with_token(Uuid::new_v4(), "ada@example.com");
+        test_state(
+            oauth,
+            Arc::new(Client::new()),
+            Arc::new(NoopWorkspaceRepository),
+        )
+    }
+
+    async fn run_code_with_policy(
+        params: Value,
+        allowed_hosts: &[String],
+        default_deny: bool,
+    ) -> Result<Value, String> {
+        let state = code_state();
+        let run = sample_run(Uuid::new_v4());
+        let node = Node {
+            id: "code-1".into(),
+            kind: "action".into(),
+            data: json!({ "params": params }),
+        };
+        execute_code(
+            &node,
+            &json!({ "trigger": { "name": "Ada" } }),
+            allowed_hosts,
+            &[],
+            default_deny,
+            false,
+            &state,
+            &run,
+        )
+        .await
+        .map(|(outputs, _)| outputs)
+    }
+
+    async fn run_code(params: Value) -> Result<Value, String> {
+        run_code_with_policy(params, &[], false).await
+    }
+
+    #[tokio::test]
+    async fn maps_inputs_and_outputs() {
+        let outputs = run_code(json!({
+            "code": "return { greeting: `hi ${inputs.name}`, count: inputs.count + 1 };",
+            "inputs": [
+                { "key": "name", "value": "{{trigger.name}}" },
+                { "key": "count", "value": "41" }
+            ],
+            "outputs": [{ "key": "total", "value": "count" }]
+        }))
+        .await
+        .expect("script runs");
+        assert_eq!(outputs, json!({ "total": 42 }));
+    }
+
+    #[tokio::test]
+    async fn helper_library_is_available() {
+        let outputs = run_code(json!({
+            "code": r#"
+                const rows = [{ team: "a", n: 2 }, { team: "b", n: 3 }, { team: "a", n: 5 }];
+                return {
+                    date: dsentr.formatDate("2024-03-01T12:30:00Z", "%Y/%m/%d %H:%M", "Europe/Paris"),
+                    sha: dsentr.sha256("abc"),
+                    hmac: dsentr.hmacSha256("key", "The quick brown fox jumps over the lazy dog"),
+                    b64: dsentr.base64Encode("hello"),
+                    plain: dsentr.base64Decode("aGVsbG8="),
+                    grouped: Object.keys(_.groupBy(rows, "team")),
+                    total: _.sum(rows.map((r) => r.n)),
+                    deep: _.get({ a: { b: [{ c: 7 }] } }, "a.b[0].c"),
+                    chunks: _.chunk([1, 2, 3], 2),
+                };
+            "#
+        }))
+        .await
+        .expect("script runs");
+        assert_eq!(outputs["date"], "2024/03/01 13:30");
+        assert_eq!(
+            outputs["sha"],
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert_eq!(
+            outputs["hmac"],
+            "f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd8"
+        );
+        assert_eq!(outputs["b64"], "aGVsbG8=");
+        assert_eq!(outputs["plain"], "hello");
+        assert_eq!(outputs["grouped"], json!(["a", "b"]));
+        assert_eq!(outputs["total"], 10);
+        assert_eq!(outputs["deep"], 7);
+        assert_eq!(outputs["chunks"], json!([[1, 2], [3]]));
+    }
+
+    #[tokio::test]
+    async fn runaway_scripts_are_stopped() {
+        let looping = run_code(json!({ "code": "while (true) {}" }))
+            .await
+            .unwrap_err();
+        assert!(!looping.is_empty());
+
+        let timed_out = run_code(json!({
+            "code": "let n = 0; for (;;) { for (let i = 0; i < 1000; i++) { n += i; } }",
+            "timeoutMs": 50
+        }))
+        .await
+        .unwrap_err();
+        assert!(
+            timed_out.contains("timed out")
+                || timed_out.contains("instruction budget")
+                || timed_out.contains("limit"),
+            "{timed_out}"
+        );
+
+        let hungry = run_code(json!({
+            "code": "const keep = []; for (;;) { keep.push('x'.repeat(1024) + keep.length); }",
+            "timeoutMs": 30000
+        }))
+        .await
+        .unwrap_err();
+        assert!(hungry.contains("memory limit"), "{hungry}");
+    }
+
+    #[tokio::test]
+    async fn single_native_allocations_are_capped() {
+        let buffer = run_code(json!({ "code": "return new Uint8Array(2 ** 30).fill(1).length;" }))
+            .await
+            .unwrap_err();
+        assert!(buffer.contains("maximum buffer size"), "{buffer}");
+
+        let string = run_code(json!({ "code": "return 'x'.repeat(2 ** 28).length;" }))
+            .await
+            .unwrap_err();
+        assert!(string.contains("memory limit"), "{string}");
+    }
+
+    #[tokio::test]
+    async fn fetch_is_disabled_unless_enabled() {
+        let err = run_code(json!({ "code": "return fetch('https://example.com').status;" }))
+            .await
+            .unwrap_err();
+        assert!(err.contains("fetch is disabled"), "{err}");
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn fetch_goes_through_the_egress_policy() {
+        let server = MockServer::start_async().await;
+        let mock = server
+            .mock_async(|when, then| {
+                when.method(POST)
+                    .path("/items")
+                    .header("x-token", "abc")
+                    .body("{\"name\":\"widget\"}");
+                then.status(201)
+                    .header("content-type", "application/json")
+                    .body("{\"id\":7}");
+            })
+            .await;
+
+        let code = format!(
+            "const res = fetch('{}', {{ method: 'POST', headers: {{ 'X-Token': 'abc' }}, body: JSON.stringify({{ name: 'widget' }}) }});\n\
+             return {{ status: res.status, ok: res.ok, id: res.json().id, type: res.headers.get('Content-Type') }};",
+            server.url("/items")
+        );
+        let outputs = run_code(json!({ "code": code, "enableFetch": true }))
+            .await
+            .expect("fetch succeeds");
+        mock.assert_async().await;
+        assert_eq!(outputs["status"], 201);
+        assert_eq!(outputs["ok"], true);
+        assert_eq!(outputs["id"], 7);
+        assert_eq!(outputs["type"], "application/json");
+
+        let denied = run_code_with_policy(
+            json!({ "code": code, "enableFetch": true }),
+            &["api.example.com".to_string()],
+            true,
+        )
+        .await
+        .unwrap_err();
+        assert!(denied.contains("default_deny"), "{denied}");
+        assert_eq!(mock.hits_async().await, 1);
+    }
+}
@@ -1,16 +1,18 @@
 use std::collections::HashSet;
 use std::time::Duration;
 
-use chrono::Utc;
-use hmac::{Hmac, Mac};
 use reqwest::Url;
 use serde_json::{json, Value};
-use sha2::{Digest, Sha256};
 
 use crate::engine::graph::Node;
 use crate::engine::templating::templ_str;
-use crate::services::smtp_mailer::{SmtpConfig, TlsMode};
+use crate::models::workflow_run::WorkflowRun;
+use crate::services::artifacts::load_artifact_param;
+use crate::services::smtp_mailer::{build_message, MailAttachment, SmtpConfig, TlsMode};
 use crate::state::AppState;
+use crate::utils::aws_sigv4::sign_aws_request;
+use crate::utils::multipart::MultipartBody;
+use base64::{engine::general_purpose::STANDARD as BASE64_STANDARD, Engine};
 use tokio::time::timeout;
 
 fn is_valid_email_address(value: &str) -> bool {
@@ -55,109 +57,15 @@ fn parse_recipient_list(raw: &str) -> Result<Vec<String>, String> {
     Ok(recipients)
 }
 
-type HmacSha256 = Hmac<Sha256>;
-
-struct AwsSignature {
-    authorization: String,
-    amz_date: String,
-    payload_hash: String,
-}
-
-fn derive_aws_signing_key(
-    secret_key: &str,
-    date_stamp: &str,
-    region: &str,
-    service: &str,
-) -> Result<Vec<u8>, String> {
-    let mut mac = HmacSha256::new_from_slice(format!("AWS4{}", secret_key).as_bytes())
-        .map_err(|_| "Invalid AWS secret key".to_string())?;
-    mac.update(date_stamp.as_bytes());
-    let k_date = mac.finalize().into_bytes();
-
-    let mut mac = HmacSha256::new_from_slice(k_date.as_slice())
-        .map_err(|_| "Invalid AWS signing key (date)".to_string())?;
-    mac.update(region.as_bytes());
-    let k_region = mac.finalize().into_bytes();
-
-    let mut mac = HmacSha256::new_from_slice(k_region.as_slice())
-        .map_err(|_| "Invalid AWS signing key (region)".to_string())?;
-    mac.update(service.as_bytes());
-    let k_service = mac.finalize().into_bytes();
-
-    let mut mac = HmacSha256::new_from_slice(k_service.as_slice())
-        .map_err(|_| "Invalid AWS signing key (service)".to_string())?;
-    mac.update(b"aws4_request");
-    Ok(mac.finalize().into_bytes().to_vec())
-}
-
-#[allow(clippy::too_many_arguments)]
-fn sign_aws_request(
-    access_key: &str,
-    secret_key: &str,
+fn determine_ses_endpoint(
     region: &str,
-    service: &str,
-    method: &str,
-    canonical_uri: &str,
-    canonical_query: &str,
-    host: &str,
-    payload: &[u8],
-) -> Result<AwsSignature, String> {
-    let now = Utc::now();
-    let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
-    let date_stamp = now.format("%Y%m%d").to_string();
-
-    let mut payload_hasher = Sha256::new();
-    payload_hasher.update(payload);
-    let payload_hash = hex::encode(payload_hasher.finalize());
-
-    let canonical_headers = format!(
-        "host:{}\nx-amz-content-sha256:{}\nx-amz-date:{}\n",
-        host.to_lowercase(),
-        payload_hash,
-        amz_date
-    );
-    let signed_headers = "host;x-amz-content-sha256;x-amz-date";
-
-    let canonical_request = format!(
-        "{}\n{}\n{}\n{}\n{}\n{}",
-        method, canonical_uri, canonical_query, canonical_headers, signed_headers, payload_hash
-    );
-
-    let mut canonical_hasher = Sha256::new();
-    canonical_hasher.update(canonical_request.as_bytes());
-    let canonical_hash = hex::encode(canonical_hasher.finalize());
-
-    let credential_scope = format!("{}/{}/{}/aws4_request", date_stamp, region, service);
-    let string_to_sign = format!(
-        "AWS4-HMAC-SHA256\n{}\n{}\n{}",
-        amz_date, credential_scope, canonical_hash
-    );
-
-    let signing_key = derive_aws_signing_key(secret_key, &date_stamp, region, service)?;
-    let mut mac = HmacSha256::new_from_slice(&signing_key)
-        .map_err(|_| "Failed to derive AWS signature".to_string())?;
-    mac.update(string_to_sign.as_bytes());
-    let signature = hex::encode(mac.finalize().into_bytes());
-
-    let authorization = format!(
-        "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
-        access_key, credential_scope, signed_headers, signature
-    );
-
-    Ok(AwsSignature {
-        authorization,
-        amz_date,
-        payload_hash,
-    })
-}
-
-fn determine_ses_endpoint(region: &str) -> Result<(String, String), String> {
-    let default = format!("https://email.{}.amazonaws.com", region);
-    let base = std::env::var("AWS_SES_ENDPOINT")
-        .ok()
-        .map(|v| v.trim().to_string())
+    endpoint_override: Option<&str>,
+) -> Result<(String, String), String> {
+    let base = endpoint_override
+        .map(str::trim)
         .filter(|v| !v.is_empty())
-        .unwrap_or(default);
+        .map(str::to_string)
+        .unwrap_or_else(|| format!("https://email.{}.amazonaws.com", region));
 
     let trimmed = base.trim_end_matches('/');
     let url = Url::parse(trimmed).map_err(|_| "Invalid AWS SES endpoint".to_string())?;
@@ -177,8 +85,19 @@ pub(crate) async fn execute_email(
     node: &Node,
     context: &Value,
     state: &AppState,
+    run: &WorkflowRun,
 ) -> Result<(Value, Option<String>), String> {
     let params = node.data.get("params").cloned().unwrap_or(Value::Null);
+    let attachments: Vec<MailAttachment> =
+        load_artifact_param(state, run, params.get("attachments"), context)
+            .await?
+            .into_iter()
+            .map(|artifact| MailAttachment {
+                file_name: artifact.name,
+                mime_type: artifact.mime_type,
+                bytes: artifact.bytes,
+            })
+            .collect();
     let provider = node
         .data
         .get("emailProvider")
@@ -306,9 +225,13 @@ pub(crate) async fn execute_email(
 
             match timeout(
                 Duration::from_millis(timeout_ms),
-                state
-                    .mailer
-                    .send_email_with_config(&config, &recipients, &subject, &body),
+                state.mailer.send_email_with_attachments(
+                    &config,
+                    &recipients,
+                    &subject,
+                    &body,
+                    &attachments,
+                ),
             )
             .await
             {
@@ -326,6 +249,7 @@ pub(crate) async fn execute_email(
                     "sent": true,
                     "service": "SMTP",
                     "recipient_count": recipients.len(),
+                    "attachment_count": attachments.len(),
                 }),
                 None,
             ))
@@ -434,12 +358,33 @@ pub(crate) async fn execute_email(
                 );
             }
 
-            let base = std::env::var("SENDGRID_API_BASE")
-                .ok()
-                .map(|v| v.trim().to_string())
-                .filter(|v| !v.is_empty())
-                .unwrap_or_else(|| "https://api.sendgrid.com/v3".to_string());
-            let url = format!("{}/mail/send", base.trim_end_matches('/'));
+            if !attachments.is_empty() {
+                request_body.insert(
+                    "attachments".to_string(),
+                    Value::Array(
+                        attachments
+                            .iter()
+                            .map(|attachment| {
+                                json!({
+                                    "content": BASE64_STANDARD.encode(&attachment.bytes),
+                                    "filename": attachment.file_name,
+                                    "type": attachment.mime_type,
+                                    "disposition": "attachment"
+                                })
+                            })
+                            .collect(),
+                    ),
+                );
+            }
+
+            let url = format!(
+                "{}/mail/send",
+                state
+                    .config
+                    .provider_base_urls
+                    .sendgrid
+                    .trim_end_matches('/')
+            );
 
             let client = reqwest::Client::new();
             let resp = client
@@ -574,18 +519,14 @@ pub(crate) async fn execute_email(
                 form_fields.push(("text".to_string(), body.clone()));
             }
 
-            let default_base = if region.to_lowercase().contains("eu") {
-                "https://api.eu.mailgun.net".to_string()
-            } else {
-                "https://api.mailgun.net".to_string()
+            let base = match state.config.provider_base_urls.mailgun.as_deref() {
+                Some(base) => base.to_string(),
+                None if region.to_lowercase().contains("eu") => {
+                    "https://api.eu.mailgun.net".to_string()
+                }
+                None => "https://api.mailgun.net".to_string(),
             };
 
-            let base = std::env::var("MAILGUN_API_BASE")
-                .ok()
-                .map(|v| v.trim().to_string())
-                .filter(|v| !v.is_empty())
-                .unwrap_or(default_base);
-
             let url = format!(
                 "{}/v3/{}/messages",
                 base.trim_end_matches('/'),
@@ -593,13 +534,27 @@ pub(crate) async fn execute_email(
             );
 
             let client = reqwest::Client::new();
-            let resp = client
-                .post(url)
-                .basic_auth("api", Some(api_key))
-                .form(&form_fields)
-                .send()
-                .await
-                .map_err(|e| e.to_string())?;
+            let request = client.post(url).basic_auth("api", Some(api_key));
+            // Mailgun only accepts files as multipart form data.
+            let request = if attachments.is_empty() {
+                request.form(&form_fields)
+            } else {
+                let mut multipart = MultipartBody::new();
+                for (name, value) in &form_fields {
+                    multipart = multipart.text(name, value);
+                }
+                for attachment in &attachments {
+                    multipart = multipart.file(
+                        "attachment",
+                        &attachment.file_name,
+                        &attachment.mime_type,
+                        &attachment.bytes,
+                    );
+                }
+                let (content_type, body) = multipart.finish();
+                request.header("content-type", content_type).body(body)
+            };
+            let resp = request.send().await.map_err(|e| e.to_string())?;
 
             let status = resp.status();
             let headers = resp.headers().clone();
@@ -736,7 +691,14 @@ pub(crate) async fn execute_email(
                 }
             }
 
-            let (base_url, host) = determine_ses_endpoint(&aws_region)?;
+            if !attachments.is_empty() && (ses_version == "v1" || template.is_some()) {
+                return Err("Amazon SES attachments require SES v2 without a template".to_string());
+            }
+
+            let (base_url, host) = determine_ses_endpoint(
+                &aws_region,
+                state.config.provider_base_urls.ses.as_deref(),
+            )?;
             let client = reqwest::Client::new();
 
             match ses_version {
@@ -858,6 +820,21 @@ pub(crate) async fn execute_email(
                                 }
                             }
                         })
+                    } else if !attachments.is_empty() {
+                        let message =
+                            build_message(&from_email, &recipients, &subject, &body, &attachments)
+                                .map_err(|e| e.to_string())?;
+                        json!({
+                            "FromEmailAddress": from_email,
+                            "Destination": {
+                                "ToAddresses": recipients,
+                            },
+                            "Content": {
+                                "Raw": {
+                                    "Data": BASE64_STANDARD.encode(message.formatted())
+                                }
+                            }
+                        })
                     } else {
                         json!({
                             "FromEmailAddress": from_email,
@@ -943,8 +920,8 @@ pub(crate) async fn execute_email(
 mod tests {
     use super::*;
     use crate::config::{
-        Config, OAuthProviderConfig, OAuthSettings, StripeSettings, DEFAULT_WORKSPACE_MEMBER_LIMIT,
-        DEFAULT_WORKSPACE_MONTHLY_RUN_LIMIT, RUNAWAY_LIMIT_5MIN,
+        Config, OAuthProviderConfig, OAuthSettings, ProviderBaseUrls, StripeSettings,
+        DEFAULT_WORKSPACE_MEMBER_LIMIT, DEFAULT_WORKSPACE_MONTHLY_RUN_LIMIT, RUNAWAY_LIMIT_5MIN,
     };
     use crate::db::{
         mock_db::{MockDb, NoopWorkflowRepository, NoopWorkspaceRepository},
@@ -956,65 +933,33 @@ mod tests {
     use crate::services::oauth::google::mock_google_oauth::MockGoogleOAuth;
     use crate::services::oauth::workspace_service::WorkspaceOAuthService;
     use crate::services::smtp_mailer::{MailError, Mailer, MockMailer, SmtpConfig, TlsMode};
+    use crate::test_support::sample_run;
     use crate::{
         state::{test_pg_pool, AppState},
         utils::jwt::JwtKeys,
     };
     use async_trait::async_trait;
-    use axum::body::{Body, Bytes};
-    use axum::extract::State;
-    use axum::http::{header, HeaderMap, Method, StatusCode, Uri};
+    use axum::body::Body;
+    use axum::http::{header, StatusCode};
     use axum::response::Response;
-    use axum::routing::post;
-    use axum::Router;
     use base64::engine::general_purpose::STANDARD as BASE64;
     use base64::Engine;
-    use once_cell::sync::Lazy;
     use reqwest::Client;
     use serde_json::{json, Value};
     use std::collections::HashMap;
-    use std::net::SocketAddr;
-    use std::sync::{Arc, Mutex, MutexGuard};
+    use std::sync::Arc;
     use std::time::Duration;
-    use tokio::net::TcpListener;
-    use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
-    use tokio::task::JoinHandle;
     use urlencoding::decode;
+    use uuid::Uuid;
 
     use crate::engine::graph::Node;
+    use crate::test_support::spawn_stub_server;
 
-    static ENV_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
-
-    struct EnvGuard {
-        key: &'static str,
-        previous: Option<String>,
-        _lock: MutexGuard<'static, ()>,
-    }
-
-    impl EnvGuard {
-        fn set(key: &'static str, value: String) -> Self {
-            let lock = ENV_LOCK.lock().expect("env mutex poisoned");
-            let previous = std::env::var(key).ok();
-            std::env::set_var(key, value);
-            Self {
-                key,
-                previous,
-                _lock: lock,
-            }
-        }
-    }
-
-    impl Drop for EnvGuard {
-        fn drop(&mut self) {
-            if let Some(previous) = self.previous.take() {
-                std::env::set_var(self.key, previous);
-            } else {
-                std::env::remove_var(self.key);
-            }
-        }
+    fn test_config() -> Arc<Config> {
+        test_config_with_urls(ProviderBaseUrls::default())
     }
 
-    fn test_config() -> Arc<Config> {
+    fn test_config_with_urls(provider_base_urls: ProviderBaseUrls) -> Arc<Config> {
         Arc::new(Config {
             database_url: String::new(),
             frontend_origin: "http://localhost".into(),
@@ -1045,6 +990,11 @@ mod tests {
                     client_secret: "stub".into(),
                     redirect_uri: "http://localhost".into(),
                 },
+                github: OAuthProviderConfig {
+                    client_id: "stub".into(),
+                    client_secret: "stub".into(),
+                    redirect_uri: "http://localhost".into(),
+                },
                 token_encryption_key: vec![0u8; 32],
             },
             api_secrets_encryption_key: vec![1u8; 32],
@@ -1060,6 +1010,9 @@ mod tests {
             workspace_member_limit: DEFAULT_WORKSPACE_MEMBER_LIMIT,
             workspace_monthly_run_limit: DEFAULT_WORKSPACE_MONTHLY_RUN_LIMIT,
             runaway_limit_5min: RUNAWAY_LIMIT_5MIN,
+            slack_signing_secret: None,
+            public_api_base_url: None,
+            provider_base_urls,
         })
     }
 
@@ -1096,6 +1049,13 @@ mod tests {
         test_state_with_mailer(Arc::new(MockMailer::default()))
     }
 
+    fn test_state_with_urls(urls: ProviderBaseUrls) -> AppState {
+        AppState {
+            config: test_config_with_urls(urls),
+            ..test_state()
+        }
+    }
+
     #[derive(Clone)]
     struct HangingMailer {
         delay: Duration,
@@ -1160,7 +1120,7 @@ mod tests {
 
         let context = json!({ "user": { "name": "Alice" } });
 
-        let (output, _) = execute_email(&node, &context, &state)
+        let (output, _) = execute_email(&node, &context, &state, &sample_run(Uuid::new_v4()))
             .await
             .expect("smtp send should succeed");
 
@@ -1212,7 +1172,7 @@ mod tests {
             }),
         };
 
-        let error = execute_email(&node, &Value::Null, &state)
+        let error = execute_email(&node, &Value::Null, &state, &sample_run(Uuid::new_v4()))
             .await
             .expect_err("plaintext SMTP configuration should be rejected");
 
@@ -1245,7 +1205,7 @@ mod tests {
             }),
         };
 
-        let (output, _) = execute_email(&node, &Value::Null, &state)
+        let (output, _) = execute_email(&node, &Value::Null, &state, &sample_run(Uuid::new_v4()))
             .await
             .expect("smtp send should succeed");
 
@@ -1283,7 +1243,7 @@ mod tests {
             }),
         };
 
-        let (output, _) = execute_email(&node, &Value::Null, &state)
+        let (output, _) = execute_email(&node, &Value::Null, &state, &sample_run(Uuid::new_v4()))
             .await
             .expect("smtp send should succeed");
 
@@ -1322,7 +1282,7 @@ mod tests {
             }),
         };
 
-        let (output, _) = execute_email(&node, &Value::Null, &state)
+        let (output, _) = execute_email(&node, &Value::Null, &state, &sample_run(Uuid::new_v4()))
             .await
             .expect("smtp send should succeed");
 
@@ -1364,7 +1324,7 @@ mod tests {
             }),
         };
 
-        let err = execute_email(&node, &Value::Null, &state)
+        let err = execute_email(&node, &Value::Null, &state, &sample_run(Uuid::new_v4()))
             .await
             .expect_err("smtp send should respect timeout");
 
@@ -1394,155 +1354,13 @@ mod tests {
             }),
         };
 
-        let err = execute_email(&node, &Value::Null, &state)
+        let err = execute_email(&node, &Value::Null, &state, &sample_run(Uuid::new_v4()))
             .await
             .expect_err("invalid from should error");
 
         assert!(err.contains("Invalid from email address"));
     }
 
-    #[derive(Debug)]
-    struct RecordedRequest {
-        uri: Uri,
-        headers: HeaderMap,
-        body: Vec<u8>,
-    }
-
-    struct StubState<F>
-    where
-        F: Fn() -> Response<Body> + Send + Sync + 'static,
-    {
-        tx: UnboundedSender<RecordedRequest>,
-        response_factory: Arc<F>,
-    }
-
-    impl<F> Clone for StubState<F>
-    where
-        F: Fn() -> Response<Body> + Send + Sync + 'static,
-    {
-        fn clone(&self) -> Self {
-            Self {
-                tx: self.tx.clone(),
-                response_factory: Arc::clone(&self.response_factory),
-            }
-        }
-    }
-
-    async fn stub_handler<F>(
-        State(state): State<StubState<F>>,
-        _method: Method,
-        uri: Uri,
-        headers: HeaderMap,
-        body: Bytes,
-    ) -> Response<Body>
-    where
-        F: Fn() -> Response<Body> + Send + Sync + 'static,
-    {
-        let record = RecordedRequest {
-            uri,
-            headers,
-            body: body.to_vec(),
-        };
-        let _ = state.tx.send(record);
-        (state.response_factory)()
-    }
-
-    async fn spawn_stub_server<F>(
-        response_factory: F,
-    ) -> (
-        SocketAddr,
-        UnboundedReceiver<RecordedRequest>,
-        JoinHandle<()>,
-    )
-    where
-        F: Fn() -> Response<Body> + Send + Sync + 'static,
-    {
-        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
-        let addr = listener.local_addr().unwrap();
-        let (tx, rx) = unbounded_channel();
-        let state = StubState {
-            tx,
-            response_factory: Arc::new(response_factory),
-        };
-
-        let app = Router::new()
-            .route("/mail/send", post(stub_handler::<F>))
-            .with_state(state);
-
-        let server = axum::serve(listener, app.into_make_service());
-        let handle = tokio::spawn(async move {
-            if let Err(err) = server.await {
-                eprintln!("stub server exited with error: {err}");
-            }
-        });
-        (addr, rx, handle)
-    }
-
-    async fn spawn_mailgun_stub_server<F>(
-        response_factory: F,
-    ) -> (
-        SocketAddr,
-        UnboundedReceiver<RecordedRequest>,
-        JoinHandle<()>,
-    )
-    where
-        F: Fn() -> Response<Body> + Send + Sync + 'static,
-    {
-        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
-        let addr = listener.local_addr().unwrap();
-        let (tx, rx) = unbounded_channel();
-        let state = StubState {
-            tx,
-            response_factory: Arc::new(response_factory),
-        };
-
-        let app = Router::new()
-            .route("/v3/{domain}/messages", post(stub_handler::<F>))
-            .with_state(state);
-
-        let server = axum::serve(listener, app.into_make_service());
-        let handle = tokio::spawn(async move {
-            if let Err(err) = server.await {
-                eprintln!("mailgun stub server exited with error: {err}");
-            }
-        });
-
-        (addr, rx, handle)
-    }
-
-    async fn spawn_ses_stub_server<F>(
-        response_factory: F,
-    ) -> (
-        SocketAddr,
-        UnboundedReceiver<RecordedRequest>,
-        JoinHandle<()>,
-    )
-    where
-        F: Fn() -> Response<Body> + Send + Sync + 'static,
-    {
-        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
-        let addr = listener.local_addr().unwrap();
-        let (tx, rx) = unbounded_channel();
-        let state = StubState {
-            tx,
-            response_factory: Arc::new(response_factory),
-        };
-
-        let app = Router::new()
-            .route("/", post(stub_handler::<F>))
-            .route("/v2/email/outbound-emails", post(stub_handler::<F>))
-            .with_state(state);
-
-        let server = axum::serve(listener, app.into_make_service());
-        let handle = tokio::spawn(async move {
-            if let Err(err) = server.await {
-                eprintln!("ses stub server exited with error: {err}");
-            }
-        });
-
-        (addr, rx, handle)
-    }
-
     fn parse_form_body(body: &[u8]) -> HashMap<String, Vec<String>> {
         let mut map: HashMap<String, Vec<String>> = HashMap::new();
         let Ok(as_str) = String::from_utf8(body.to_vec()) else {
@@ -1579,8 +1397,10 @@ mod tests {
         })
         .await;
 
-        let _guard = EnvGuard::set("SENDGRID_API_BASE", format!("http://{}", addr));
-        let state = test_state();
+        let state = test_state_with_urls(ProviderBaseUrls {
+            sendgrid: format!("http://{}", addr),
+            ..ProviderBaseUrls::default()
+        });
         let node = Node {
             id: "action-1".into(),
             kind: "action".into(),
@@ -1598,7 +1418,7 @@ mod tests {
         };
 
         let context = json!({ "user": { "name": "Alice" } });
-        let (output, next) = execute_email(&node, &context, &state)
+        let (output, next) = execute_email(&node, &context, &state, &sample_run(Uuid::new_v4()))
             .await
             .expect("sendgrid email should succeed");
 
@@ -1633,8 +1453,10 @@ mod tests {
         })
         .await;
 
-        let _guard = EnvGuard::set("SENDGRID_API_BASE", format!("http://{}", addr));
-        let state = test_state();
+        let state = test_state_with_urls(ProviderBaseUrls {
+            sendgrid: format!("http://{}", addr),
+            ..ProviderBaseUrls::default()
+        });
         let node = Node {
             id: "action-2".into(),
             kind: "action".into(),
@@ -1659,7 +1481,7 @@ mod tests {
             "account": { "id": "A-100" }
         });
 
-        let (output, _) = execute_email(&node, &context, &state)
+        let (output, _) = execute_email(&node, &context, &state, &sample_run(Uuid::new_v4()))
             .await
             .expect("sendgrid template email should succeed");
 
@@ -1698,8 +1520,10 @@ mod tests {
         })
         .await;
 
-        let _guard = EnvGuard::set("SENDGRID_API_BASE", format!("http://{}", addr));
-        let state = test_state();
+        let state = test_state_with_urls(ProviderBaseUrls {
+            sendgrid: format!("http://{}", addr),
+            ..ProviderBaseUrls::default()
+        });
         let node = Node {
             id: "action-3".into(),
             kind: "action".into(),
@@ -1716,7 +1540,7 @@ mod tests {
             }),
         };
 
-        let err = execute_email(&node, &Value::Null, &state)
+        let err = execute_email(&node, &Value::Null, &state, &sample_run(Uuid::new_v4()))
             .await
             .expect_err("sendgrid call should fail");
         assert!(err.contains("status 400"));
@@ -1745,7 +1569,7 @@ mod tests {
             }),
         };
 
-        let err = execute_email(&node, &Value::Null, &state)
+        let err = execute_email(&node, &Value::Null, &state, &sample_run(Uuid::new_v4()))
             .await
             .expect_err("duplicate recipients should fail");
         assert!(err.contains("Duplicate recipient email"));
@@ -1753,7 +1577,7 @@ mod tests {
 
     #[tokio::test]
     async fn mailgun_plain_email_succeeds() {
-        let (addr, mut rx, handle) = spawn_mailgun_stub_server(|| {
+        let (addr, mut rx, handle) = spawn_stub_server(|| {
             Response::builder()
                 .status(StatusCode::OK)
                 .header(header::CONTENT_TYPE, "application/json")
@@ -1762,8 +1586,10 @@ mod tests {
         })
         .await;
 
-        let _guard = EnvGuard::set("MAILGUN_API_BASE", format!("http://{}", addr));
-        let state = test_state();
+        let state = test_state_with_urls(ProviderBaseUrls {
+            mailgun: Some(format!("http://{}", addr)),
+            ..ProviderBaseUrls::default()
+        });
         let node = Node {
             id: "action-mailgun-1".into(),
             kind: "action".into(),
@@ -1782,9 +1608,10 @@ mod tests {
             }),
         };
 
-        let (output, next) = execute_email(&node, &Value::Null, &state)
-            .await
-            .expect("mailgun email should succeed");
+        let (output, next) =
+            execute_email(&node, &Value::Null, &state, &sample_run(Uuid::new_v4()))
+                .await
+                .expect("mailgun email should succeed");
 
         assert_eq!(output["service"], "Mailgun");
         assert_eq!(output["status"], 200);
@@ -1819,7 +1646,7 @@ mod tests {
 
     #[tokio::test]
     async fn mailgun_template_email_includes_variables() {
-        let (addr, mut rx, handle) = spawn_mailgun_stub_server(|| {
+        let (addr, mut rx, handle) = spawn_stub_server(|| {
             Response::builder()
                 .status(StatusCode::OK)
                 .header(header::CONTENT_TYPE, "application/json")
@@ -1828,8 +1655,10 @@ mod tests {
         })
         .await;
 
-        let _guard = EnvGuard::set("MAILGUN_API_BASE", format!("http://{}", addr));
-        let state = test_state();
+        let state = test_state_with_urls(ProviderBaseUrls {
+            mailgun: Some(format!("http://{}", addr)),
+            ..ProviderBaseUrls::default()
+        });
         let node = Node {
             id: "action-mailgun-2".into(),
             kind: "action".into(),
@@ -1856,7 +1685,7 @@ mod tests {
             "account": { "id": "A-100" }
         });
 
-        let (output, next) = execute_email(&node, &context, &state)
+        let (output, next) = execute_email(&node, &context, &state, &sample_run(Uuid::new_v4()))
             .await
             .expect("mailgun template email should succeed");
 
@@ -1889,7 +1718,7 @@ mod tests {
     #[tokio::test]
     async fn mailgun_error_response_is_propagated() {
         let error_body = Arc::new(json!({ "message": "Invalid domain" }).to_string());
-        let (addr, mut rx, handle) = spawn_mailgun_stub_server({
+        let (addr, mut rx, handle) = spawn_stub_server({
             let error_body = error_body.clone();
             move || {
                 Response::builder()
@@ -1901,8 +1730,10 @@ mod tests {
         })
         .await;
 
-        let _guard = EnvGuard::set("MAILGUN_API_BASE", format!("http://{}", addr));
-        let state = test_state();
+        let state = test_state_with_urls(ProviderBaseUrls {
+            mailgun: Some(format!("http://{}", addr)),
+            ..ProviderBaseUrls::default()
+        });
         let node = Node {
             id: "action-mailgun-3".into(),
             kind: "action".into(),
@@ -1921,7 +1752,7 @@ mod tests {
             }),
         };
 
-        let err = execute_email(&node, &Value::Null, &state)
+        let err = execute_email(&node, &Value::Null, &state, &sample_run(Uuid::new_v4()))
             .await
             .expect_err("mailgun call should fail");
         assert!(err.contains("status 400"));
@@ -1952,7 +1783,7 @@ mod tests {
             }),
         };
 
-        let err = execute_email(&node, &Value::Null, &state)
+        let err = execute_email(&node, &Value::Null, &state, &sample_run(Uuid::new_v4()))
             .await
             .expect_err("missing subject should fail");
         assert!(err.contains("Subject is required"));
@@ -1960,7 +1791,7 @@ mod tests {
 
     #[tokio::test]
     async fn aws_ses_v2_plain_email_succeeds() {
-        let (addr, mut rx, handle) = spawn_ses_stub_server(|| {
+        let (addr, mut rx, handle) = spawn_stub_server(|| {
             Response::builder()
                 .status(StatusCode::OK)
                 .header(header::CONTENT_TYPE, "application/json")
@@ -1969,8 +1800,10 @@ mod tests {
         })
         .await;
 
-        let _guard = EnvGuard::set("AWS_SES_ENDPOINT", format!("http://{}", addr));
-        let state = test_state();
+        let state = test_state_with_urls(ProviderBaseUrls {
+            ses: Some(format!("http://{}", addr)),
+            ..ProviderBaseUrls::default()
+        });
         let node = Node {
             id: "action-ses-v2-1".into(),
             kind: "action".into(),
@@ -1991,7 +1824,7 @@ mod tests {
         };
 
         let context = json!({ "user": { "name": "Riley" } });
-        let (output, next) = execute_email(&node, &context, &state)
+        let (output, next) = execute_email(&node, &context, &state, &sample_run(Uuid::new_v4()))
             .await
             .expect("ses v2 email should succeed");
 
@@ -2022,7 +1855,7 @@ mod tests {
 
     #[tokio::test]
     async fn aws_ses_v2_template_email_uses_template_data() {
-        let (addr, mut rx, handle) = spawn_ses_stub_server(|| {
+        let (addr, mut rx, handle) = spawn_stub_server(|| {
             Response::builder()
                 .status(StatusCode::OK)
                 .header(header::CONTENT_TYPE, "application/json")
@@ -2031,8 +1864,10 @@ mod tests {
         })
         .await;
 
-        let _guard = EnvGuard::set("AWS_SES_ENDPOINT", format!("http://{}", addr));
-        let state = test_state();
+        let state = test_state_with_urls(ProviderBaseUrls {
+            ses: Some(format!("http://{}", addr)),
+            ..ProviderBaseUrls::default()
+        });
         let node = Node {
             id: "action-ses-v2-2".into(),
             kind: "action".into(),
@@ -2060,7 +1895,7 @@ mod tests {
             "account": { "id": "ACC-9" }
         });
 
-        let (output, _) = execute_email(&node, &context, &state)
+        let (output, _) = execute_email(&node, &context, &state, &sample_run(Uuid::new_v4()))
             .await
             .expect("ses v2 template email should succeed");
 
@@ -2081,7 +1916,7 @@ mod tests {
 
     #[tokio::test]
     async fn aws_ses_v1_plain_email_succeeds() {
-        let (addr, mut rx, handle) = spawn_ses_stub_server(|| {
+        let (addr, mut rx, handle) = spawn_stub_server(|| {
             Response::builder()
                 .status(StatusCode::OK)
                 .header(header::CONTENT_TYPE, "text/xml")
@@ -2092,8 +1927,10 @@ mod tests {
         })
         .await;
 
-        let _guard = EnvGuard::set("AWS_SES_ENDPOINT", format!("http://{}", addr));
-        let state = test_state();
+        let state = test_state_with_urls(ProviderBaseUrls {
+            ses: Some(format!("http://{}", addr)),
+            ..ProviderBaseUrls::default()
+        });
         let node = Node {
             id: "action-ses-v1-1".into(),
             kind: "action".into(),
@@ -2114,7 +1951,7 @@ mod tests {
         };
 
         let context = json!({ "user": { "name": "Sam" } });
-        let (output, next) = execute_email(&node, &context, &state)
+        let (output, next) = execute_email(&node, &context, &state, &sample_run(Uuid::new_v4()))
             .await
             .expect("ses v1 email should succeed");
 
@@ -2143,7 +1980,7 @@ mod tests {
 
     #[tokio::test]
     async fn aws_ses_v1_template_email_includes_template_data() {
-        let (addr, mut rx, handle) = spawn_ses_stub_server(|| {
+        let (addr, mut rx, handle) = spawn_stub_server(|| {
             Response::builder()
                 .status(StatusCode::OK)
                 .header(header::CONTENT_TYPE, "text/xml")
@@ -2154,8 +1991,10 @@ mod tests {
         })
         .await;
 
-        let _guard = EnvGuard::set("AWS_SES_ENDPOINT", format!("http://{}", addr));
-        let state = test_state();
+        let state = test_state_with_urls(ProviderBaseUrls {
+            ses: Some(format!("http://{}", addr)),
+            ..ProviderBaseUrls::default()
+        });
         let node = Node {
             id: "action-ses-v1-2".into(),
             kind: "action".into(),
@@ -2178,7 +2017,7 @@ mod tests {
         };
 
         let context = json!({ "user": { "name": "Skyler" } });
-        let (output, _) = execute_email(&node, &context, &state)
+        let (output, _) = execute_email(&node, &context, &state, &sample_run(Uuid::new_v4()))
             .await
             .expect("ses v1 templated email should succeed");
 
@@ -2206,7 +2045,7 @@ mod tests {
 
     #[tokio::test]
     async fn aws_ses_missing_version_defaults_to_v2() {
-        let (addr, mut rx, handle) = spawn_ses_stub_server(|| {
+        let (addr, mut rx, handle) = spawn_stub_server(|| {
             Response::builder()
                 .status(StatusCode::OK)
                 .header(header::CONTENT_TYPE, "application/json")
@@ -2215,8 +2054,10 @@ mod tests {
         })
         .await;
 
-        let _guard = EnvGuard::set("AWS_SES_ENDPOINT", format!("http://{}", addr));
-        let state = test_state();
+        let state = test_state_with_urls(ProviderBaseUrls {
+            ses: Some(format!("http://{}", addr)),
+            ..ProviderBaseUrls::default()
+        });
         let node = Node {
             id: "action-ses-default-version".into(),
             kind: "action".into(),
@@ -2235,7 +2076,7 @@ mod tests {
             }),
         };
 
-        let (output, _) = execute_email(&node, &Value::Null, &state)
+        let (output, _) = execute_email(&node, &Value::Null, &state, &sample_run(Uuid::new_v4()))
             .await
             .expect("ses should default to v2");
 
@@ -2268,7 +2109,7 @@ mod tests {
             }),
         };
 
-        let err = execute_email(&node, &Value::Null, &state)
+        let err = execute_email(&node, &Value::Null, &state, &sample_run(Uuid::new_v4()))
             .await
             .expect_err("invalid version should error");
 
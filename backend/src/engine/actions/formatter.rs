@@ -1,12 +1,18 @@
-use std::collections::HashMap;
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
 use std::str::FromStr;
 
+use base64::Engine as _;
 use chrono::{DateTime, Datelike, NaiveDate, NaiveDateTime, TimeZone, Timelike, Utc};
 use chrono_tz::Tz;
+use hmac::{Hmac, Mac};
+use once_cell::sync::Lazy;
+use regex::{Regex, RegexBuilder};
 use serde::{Deserialize, Serialize};
 use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
 
-use crate::engine::actions::{lookup_path, parse_flexible_value};
+use crate::engine::actions::{evaluate_expression, lookup_path, parse_flexible_value};
 use crate::engine::graph::Node;
 use crate::engine::templating::templ_str;
 
@@ -22,6 +28,134 @@ pub struct FormatterConfig {
     pub output_key: String,
 }
 
+const SUPPORTED_OPERATIONS: &[&str] = &[
+    "string.trim",
+    "string.lowercase",
+    "string.uppercase",
+    "string.replace",
+    "string.split",
+    "string.substring",
+    "string.regex_extract",
+    "string.regex_replace",
+    "string.regex_test",
+    "string.url_encode",
+    "string.url_decode",
+    "string.base64_encode",
+    "string.base64_decode",
+    "string.html_escape",
+    "string.strip_html",
+    "string.slugify",
+    "number.add",
+    "number.subtract",
+    "number.multiply",
+    "number.divide",
+    "number.round",
+    "type.to_number",
+    "type.to_string",
+    "json.pick",
+    "json.flatten",
+    "json.merge",
+    "json.to_array",
+    "json.to_object",
+    "array.map",
+    "array.filter",
+    "array.sort",
+    "array.unique",
+    "array.join",
+    "array.length",
+    "array.first",
+    "array.last",
+    "array.chunk",
+    "array.group_by",
+    "array.sum",
+    "array.avg",
+    "array.min",
+    "array.max",
+    "array.zip",
+    "date.parse",
+    "date.format",
+    "date.adjust",
+    "date.extract",
+    "bool.to_boolean",
+    "bool.is_empty",
+    "hash.sha256",
+    "hash.hmac_sha256",
+    "csv.parse",
+    "csv.stringify",
+];
+
+/// Largest compiled regex program accepted from a workflow definition.
+const REGEX_SIZE_LIMIT: usize = 1 << 20;
+
+impl FormatterConfig {
+    /// Checks what can be known before templating: the operation and output
+    /// key, required fields, and literal (untemplated) regex patterns, flags,
+    /// hash encodings and CSV delimiters.
+    pub fn validate(&self) -> Result<(), String> {
+        let operation = self.operation.trim();
+        if operation.is_empty() {
+            return Err("Formatter operation is required.".to_string());
+        }
+        if !SUPPORTED_OPERATIONS.contains(&operation) {
+            return Err(format!("Unsupported formatter operation `{operation}`"));
+        }
+
+        let output_key = self.output_key.trim();
+        if output_key.is_empty() {
+            return Err("Output key is required.".to_string());
+        }
+        if !is_valid_output_key(output_key) {
+            return Err("Output key must start with a letter/underscore and contain only letters, numbers, or underscores.".to_string());
+        }
+
+        let literal = |key: &str| {
+            self.fields
+                .get(key)
+                .and_then(|v| v.as_str())
+                .filter(|s| !s.contains("{{"))
+        };
+        let present = |key: &str| match self.fields.get(key) {
+            Some(Value::String(s)) => !s.trim().is_empty(),
+            Some(Value::Null) | None => false,
+            Some(_) => true,
+        };
+
+        match operation {
+            "string.regex_extract" | "string.regex_replace" | "string.regex_test" => {
+                if !present("pattern") {
+                    return Err("Regex pattern is required".to_string());
+                }
+                let flags = literal("flags").unwrap_or("");
+                if let Some(pattern) = literal("pattern") {
+                    compile_regex(pattern, flags)?;
+                } else {
+                    parse_regex_flags(flags)?;
+                }
+            }
+            "hash.hmac_sha256" => {
+                if !present("key") {
+                    return Err("HMAC key is required".to_string());
+                }
+                if let Some(encoding) = literal("encoding") {
+                    parse_digest_encoding(encoding)?;
+                }
+            }
+            "hash.sha256" => {
+                if let Some(encoding) = literal("encoding") {
+                    parse_digest_encoding(encoding)?;
+                }
+            }
+            "csv.parse" | "csv.stringify" => {
+                if let Some(delimiter) = literal("delimiter") {
+                    parse_csv_delimiter(delimiter)?;
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
 fn is_valid_output_key(key: &str) -> bool {
     let mut chars = key.chars();
     match chars.next() {
@@ -172,6 +306,463 @@ fn normalized_path(path: &str) -> String {
         .to_string()
 }
 
+fn value_to_text(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Number(n) => n.to_string(),
+        Value::Bool(b) => b.to_string(),
+        Value::Null => "".to_string(),
+        other => other.to_string(),
+    }
+}
+
+fn parse_array_input(raw: &str, label: &str) -> Result<Vec<Value>, String> {
+    match parse_json_input(raw)? {
+        Value::Array(items) => Ok(items),
+        _ => Err(format!("{label} must be a JSON array")),
+    }
+}
+
+/// The value at `field` (dot/array path) within `item`, or the item itself
+/// when no field is given.
+fn item_field(item: &Value, field: Option<&str>) -> Value {
+    match field {
+        Some(path) => lookup_path(item, &normalized_path(path)).unwrap_or(Value::Null),
+        None => item.clone(),
+    }
+}
+
+fn optional_field_path(fields: &HashMap<String, Value>, context: &Value) -> Option<String> {
+    read_string_field(fields, "field", context)
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+}
+
+fn type_rank(value: &Value) -> u8 {
+    match value {
+        Value::Null => 0,
+        Value::Bool(_) => 1,
+        Value::Number(_) => 2,
+        Value::String(_) => 3,
+        Value::Array(_) => 4,
+        Value::Object(_) => 5,
+    }
+}
+
+/// Orders numbers numerically and strings lexically; mixed types sort as
+/// null < bool < number < string < array < object.
+fn compare_values(a: &Value, b: &Value) -> Ordering {
+    match (a, b) {
+        (Value::Number(x), Value::Number(y)) => x
+            .as_f64()
+            .partial_cmp(&y.as_f64())
+            .unwrap_or(Ordering::Equal),
+        (Value::String(x), Value::String(y)) => x.cmp(y),
+        (Value::Bool(x), Value::Bool(y)) => x.cmp(y),
+        _ if type_rank(a) == type_rank(b) => a.to_string().cmp(&b.to_string()),
+        _ => type_rank(a).cmp(&type_rank(b)),
+    }
+}
+
+/// Numeric values of the items (or their `field`), skipping nulls.
+fn numeric_items(items: &[Value], field: Option<&str>) -> Result<Vec<f64>, String> {
+    let mut numbers = Vec::with_capacity(items.len());
+    for (idx, item) in items.iter().enumerate() {
+        let value = item_field(item, field);
+        let number = match &value {
+            Value::Null => continue,
+            Value::Number(n) => n.as_f64(),
+            Value::String(s) => f64::from_str(s.trim()).ok(),
+            _ => None,
+        };
+        numbers.push(number.ok_or_else(|| format!("Item {idx} is not a number"))?);
+    }
+    Ok(numbers)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum DigestEncoding {
+    Hex,
+    Base64,
+}
+
+fn parse_digest_encoding(raw: &str) -> Result<DigestEncoding, String> {
+    match raw.trim().to_ascii_lowercase().as_str() {
+        "" | "hex" => Ok(DigestEncoding::Hex),
+        "base64" => Ok(DigestEncoding::Base64),
+        other => Err(format!("Unsupported digest encoding `{other}`")),
+    }
+}
+
+fn encode_digest(bytes: &[u8], encoding: DigestEncoding) -> String {
+    match encoding {
+        DigestEncoding::Hex => hex::encode(bytes),
+        DigestEncoding::Base64 => base64::engine::general_purpose::STANDARD.encode(bytes),
+    }
+}
+
+fn parse_regex_flags(flags: &str) -> Result<(bool, bool, bool, bool), String> {
+    let (mut insensitive, mut multi_line, mut dot_all, mut verbose) = (false, false, false, false);
+    for flag in flags.trim().chars() {
+        match flag {
+            'i' => insensitive = true,
+            'm' => multi_line = true,
+            's' => dot_all = true,
+            'x' => verbose = true,
+            // `g` is accepted for familiarity; use `all_matches` instead.
+            'g' => {}
+            other => return Err(format!("Unsupported regex flag `{other}`")),
+        }
+    }
+    Ok((insensitive, multi_line, dot_all, verbose))
+}
+
+/// Compiles `pattern` with JavaScript-style flags (`i`, `m`, `s`, `x`). The
+/// regex engine runs in linear time, so patterns cannot backtrack
+/// catastrophically; the size limit bounds compilation.
+fn compile_regex(pattern: &str, flags: &str) -> Result<Regex, String> {
+    let (insensitive, multi_line, dot_all, verbose) = parse_regex_flags(flags)?;
+    RegexBuilder::new(pattern)
+        .case_insensitive(insensitive)
+        .multi_line(multi_line)
+        .dot_matches_new_line(dot_all)
+        .ignore_whitespace(verbose)
+        .size_limit(REGEX_SIZE_LIMIT)
+        .build()
+        .map_err(|err| format!("Invalid regex pattern: {err}"))
+}
+
+fn regex_field(fields: &HashMap<String, Value>, context: &Value) -> Result<Regex, String> {
+    let pattern = read_string_field(fields, "pattern", context)
+        .filter(|s| !s.is_empty())
+        .ok_or_else(|| "Regex pattern is required".to_string())?;
+    let flags = read_string_field(fields, "flags", context).unwrap_or_default();
+    compile_regex(&pattern, &flags)
+}
+
+fn bool_field(fields: &HashMap<String, Value>, key: &str, default: bool) -> Result<bool, String> {
+    match fields.get(key) {
+        None | Some(Value::Null) => Ok(default),
+        Some(Value::String(s)) if s.trim().is_empty() => Ok(default),
+        Some(value) => coerce_bool(value),
+    }
+}
+
+/// Named groups become an object; otherwise the first capture group (or the
+/// whole match when there are no groups) is returned as a string.
+fn capture_value(regex: &Regex, captures: &regex::Captures<'_>) -> Value {
+    let named: Vec<&str> = regex.capture_names().flatten().collect();
+    let text = |m: Option<regex::Match<'_>>| {
+        m.map(|m| Value::String(m.as_str().to_string()))
+            .unwrap_or(Value::Null)
+    };
+    if !named.is_empty() {
+        let mut out = Map::new();
+        for name in named {
+            out.insert(name.to_string(), text(captures.name(name)));
+        }
+        return Value::Object(out);
+    }
+    if captures.len() > 1 {
+        text(captures.get(1))
+    } else {
+        text(captures.get(0))
+    }
+}
+
+fn html_escape(input: &str) -> String {
+    let mut out = String::with_capacity(input.len());
+    for ch in input.chars() {
+        match ch {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            other => out.push(other),
+        }
+    }
+    out
+}
+
+fn decode_basic_entities(input: &str) -> String {
+    input
+        .replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+/// Removes tags (and the contents of `script`/`style` elements), decodes the
+/// common entities and collapses whitespace.
+fn strip_html(input: &str) -> String {
+    static HIDDEN: Lazy<Regex> = Lazy::new(|| {
+        Regex::new(r"(?is)<(script|style)\b[^>]*>.*?</(script|style)\s*>").expect("valid regex")
+    });
+    static TAGS: once_cell::sync::Lazy<Regex> =
+        once_cell::sync::Lazy::new(|| Regex::new(r"(?s)<!--.*?-->|<[^>]*>").expect("valid regex"));
+    let visible = HIDDEN.replace_all(input, " ");
+    let text = TAGS.replace_all(&visible, " ");
+    decode_basic_entities(&text)
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn slugify(input: &str) -> String {
+    let mut slug = String::with_capacity(input.len());
+    let mut pending_dash = false;
+    for ch in input.chars() {
+        if ch.is_ascii_alphanumeric() {
+            if pending_dash && !slug.is_empty() {
+                slug.push('-');
+            }
+            pending_dash = false;
+            slug.push(ch.to_ascii_lowercase());
+        } else if ch != '\'' {
+            pending_dash = true;
+        }
+    }
+    slug
+}
+
+fn parse_csv_delimiter(raw: &str) -> Result<u8, String> {
+    match raw {
+        "" => Ok(b','),
+        "\\t" | "tab" => Ok(b'\t'),
+        other if other.len() == 1 && other.is_ascii() => Ok(other.as_bytes()[0]),
+        _ => Err("CSV delimiter must be a single ASCII character".to_string()),
+    }
+}
+
+fn csv_parse(input: &str, delimiter: u8, has_header: bool) -> Result<Value, String> {
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .has_headers(has_header)
+        .flexible(true)
+        .from_reader(input.as_bytes());
+    let headers: Vec<String> = if has_header {
+        reader
+            .headers()
+            .map_err(|err| format!("Invalid CSV: {err}"))?
+            .iter()
+            .map(str::to_string)
+            .collect()
+    } else {
+        Vec::new()
+    };
+    let mut rows = Vec::new();
+    for record in reader.records() {
+        let record = record.map_err(|err| format!("Invalid CSV: {err}"))?;
+        let row = if has_header {
+            let mut obj = Map::new();
+            for (idx, header) in headers.iter().enumerate() {
+                let cell = record.get(idx).unwrap_or("");
+                obj.insert(header.clone(), Value::String(cell.to_string()));
+            }
+            Value::Object(obj)
+        } else {
+            Value::Array(
+                record
+                    .iter()
+                    .map(|cell| Value::String(cell.to_string()))
+                    .collect(),
+            )
+        };
+        rows.push(row);
+    }
+    Ok(Value::Array(rows))
+}
+
+/// Objects become rows under a header listing every key, in the order keys
+/// first appear (JSON objects iterate their keys alphabetically); arrays are
+/// written as header-less rows.
+fn csv_stringify(items: &[Value], delimiter: u8) -> Result<String, String> {
+    let mut writer = csv::WriterBuilder::new()
+        .delimiter(delimiter)
+        .flexible(true)
+        .from_writer(Vec::new());
+    let mut headers: Vec<String> = Vec::new();
+    for item in items {
+        if let Value::Object(map) = item {
+            for key in map.keys() {
+                if !headers.contains(key) {
+                    headers.push(key.clone());
+                }
+            }
+        }
+    }
+    let write_err = |err: csv::Error| format!("Failed to write CSV: {err}");
+    if !headers.is_empty() {
+        writer.write_record(&headers).map_err(write_err)?;
+    }
+    for (idx, item) in items.iter().enumerate() {
+        let row: Vec<String> = match item {
+            Value::Object(map) => headers
+                .iter()
+                .map(|key| map.get(key).map(value_to_text).unwrap_or_default())
+                .collect(),
+            Value::Array(cells) => cells.iter().map(value_to_text).collect(),
+            _ => {
+                return Err(format!(
+                    "Item {idx} must be an object or an array to write as CSV"
+                ))
+            }
+        };
+        writer.write_record(&row).map_err(write_err)?;
+    }
+    let bytes = writer
+        .into_inner()
+        .map_err(|err| format!("Failed to write CSV: {err}"))?;
+    String::from_utf8(bytes).map_err(|_| "CSV output is not valid UTF-8".to_string())
+}
+
+fn execute_array_operation(
+    operation: &str,
+    input_value: &str,
+    fields: &HashMap<String, Value>,
+    context: &Value,
+) -> Result<Value, String> {
+    let items = parse_array_input(input_value, "Input")?;
+    let field = optional_field_path(fields, context);
+    let field = field.as_deref();
+
+    let result = match operation {
+        "array.map" => {
+            let path = required_string_field(fields, "field", context, "Field")?;
+            Value::Array(
+                items
+                    .iter()
+                    .map(|item| item_field(item, Some(&path)))
+                    .collect(),
+            )
+        }
+        "array.filter" => {
+            let expression = required_string_field(fields, "expression", context, "Expression")?;
+            let mut kept = Vec::new();
+            for (idx, item) in items.into_iter().enumerate() {
+                let scope = serde_json::json!({ "item": item, "index": idx });
+                if evaluate_expression(&expression, &scope)? {
+                    kept.push(scope["item"].clone());
+                }
+            }
+            Value::Array(kept)
+        }
+        "array.sort" => {
+            let descending = match read_string_field(fields, "direction", context)
+                .unwrap_or_default()
+                .trim()
+                .to_ascii_lowercase()
+                .as_str()
+            {
+                "" | "asc" | "ascending" => false,
+                "desc" | "descending" => true,
+                other => return Err(format!("Unsupported sort direction `{other}`")),
+            };
+            let mut keyed: Vec<(Value, Value)> = items
+                .into_iter()
+                .map(|item| (item_field(&item, field), item))
+                .collect();
+            keyed.sort_by(|(a, _), (b, _)| {
+                let ordering = compare_values(a, b);
+                if descending {
+                    ordering.reverse()
+                } else {
+                    ordering
+                }
+            });
+            Value::Array(keyed.into_iter().map(|(_, item)| item).collect())
+        }
+        "array.unique" => {
+            let mut seen = HashSet::new();
+            Value::Array(
+                items
+                    .into_iter()
+                    .filter(|item| seen.insert(item_field(item, field).to_string()))
+                    .collect(),
+            )
+        }
+        "array.join" => {
+            let delimiter =
+                read_string_field(fields, "delimiter", context).unwrap_or_else(|| ",".to_string());
+            let parts: Vec<String> = items
+                .iter()
+                .map(|item| value_to_text(&item_field(item, field)))
+                .collect();
+            Value::String(parts.join(&delimiter))
+        }
+        "array.length" => json_number(items.len() as f64)?,
+        "array.first" => items.into_iter().next().unwrap_or(Value::Null),
+        "array.last" => items.into_iter().next_back().unwrap_or(Value::Null),
+        "array.chunk" => {
+            let size = required_number_field(fields, "size", context, "Chunk size")?;
+            if size < 1.0 {
+                return Err("Chunk size must be at least 1.".to_string());
+            }
+            Value::Array(
+                items
+                    .chunks(size.floor() as usize)
+                    .map(|chunk| Value::Array(chunk.to_vec()))
+                    .collect(),
+            )
+        }
+        "array.group_by" => {
+            let path = required_string_field(fields, "field", context, "Field")?;
+            let mut groups: Map<String, Value> = Map::new();
+            for item in items {
+                let key = value_to_text(&item_field(&item, Some(&path)));
+                if let Some(Value::Array(group)) = groups.get_mut(&key) {
+                    group.push(item);
+                } else {
+                    groups.insert(key, Value::Array(vec![item]));
+                }
+            }
+            Value::Object(groups)
+        }
+        "array.sum" => json_number(numeric_items(&items, field)?.iter().sum())?,
+        "array.avg" => {
+            let numbers = numeric_items(&items, field)?;
+            if numbers.is_empty() {
+                Value::Null
+            } else {
+                json_number(numbers.iter().sum::<f64>() / numbers.len() as f64)?
+            }
+        }
+        "array.min" | "array.max" => {
+            let numbers = numeric_items(&items, field)?;
+            let picked = if operation == "array.min" {
+                numbers.into_iter().reduce(f64::min)
+            } else {
+                numbers.into_iter().reduce(f64::max)
+            };
+            match picked {
+                Some(n) => json_number(n)?,
+                None => Value::Null,
+            }
+        }
+        "array.zip" => {
+            let other_raw = required_string_field(fields, "input_two", context, "Second array")?;
+            let other = parse_array_input(&other_raw, "Second array")?;
+            let len = items.len().max(other.len());
+            Value::Array(
+                (0..len)
+                    .map(|idx| {
+                        Value::Array(vec![
+                            items.get(idx).cloned().unwrap_or(Value::Null),
+                            other.get(idx).cloned().unwrap_or(Value::Null),
+                        ])
+                    })
+                    .collect(),
+            )
+        }
+        other => return Err(format!("Unsupported formatter operation `{other}`")),
+    };
+    Ok(result)
+}
+
 fn parse_date_with_format(input: &str, format: &str) -> Result<DateTime<Utc>, String> {
     match format {
         "rfc3339" => DateTime::parse_from_rfc3339(input)
@@ -271,18 +862,9 @@ pub(crate) fn execute_formatter(
     let config: FormatterConfig = serde_json::from_value(config_value)
         .map_err(|_| "Invalid formatter configuration".to_string())?;
 
+    config.validate()?;
     let operation = config.operation.trim();
-    if operation.is_empty() {
-        return Err("Formatter operation is required.".to_string());
-    }
-
     let output_key = config.output_key.trim();
-    if output_key.is_empty() {
-        return Err("Output key is required.".to_string());
-    }
-    if !is_valid_output_key(output_key) {
-        return Err("Output key must start with a letter/underscore and contain only letters, numbers, or underscores.".to_string());
-    }
 
     let input_value = templ_str(&config.input, context);
     let fields = config.fields;
@@ -315,6 +897,103 @@ pub(crate) fn execute_formatter(
             let slice: String = input_value.chars().skip(start).take(length).collect();
             Value::String(slice)
         }
+        "string.regex_extract" => {
+            let regex = regex_field(&fields, context)?;
+            if bool_field(&fields, "all_matches", false)? {
+                Value::Array(
+                    regex
+                        .captures_iter(&input_value)
+                        .map(|caps| capture_value(&regex, &caps))
+                        .collect(),
+                )
+            } else {
+                regex
+                    .captures(&input_value)
+                    .map(|caps| capture_value(&regex, &caps))
+                    .unwrap_or(Value::Null)
+            }
+        }
+        "string.regex_replace" => {
+            let regex = regex_field(&fields, context)?;
+            let replace_with =
+                read_string_field(&fields, "replace_with", context).unwrap_or_default();
+            let replaced = if bool_field(&fields, "all_matches", true)? {
+                regex.replace_all(&input_value, replace_with.as_str())
+            } else {
+                regex.replace(&input_value, replace_with.as_str())
+            };
+            Value::String(replaced.into_owned())
+        }
+        "string.regex_test" => {
+            let regex = regex_field(&fields, context)?;
+            Value::Bool(regex.is_match(&input_value))
+        }
+        "string.url_encode" => Value::String(urlencoding::encode(&input_value).into_owned()),
+        "string.url_decode" => Value::String(
+            urlencoding::decode(&input_value.replace('+', " "))
+                .map_err(|_| "Input is not valid URL-encoded UTF-8".to_string())?
+                .into_owned(),
+        ),
+        "string.base64_encode" | "string.base64_decode" => {
+            use base64::engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD};
+            let engine = if bool_field(&fields, "url_safe", false)? {
+                URL_SAFE_NO_PAD
+            } else {
+                STANDARD
+            };
+            if operation == "string.base64_encode" {
+                Value::String(engine.encode(input_value.as_bytes()))
+            } else {
+                let bytes = engine
+                    .decode(input_value.trim().as_bytes())
+                    .map_err(|err| format!("Invalid base64 input: {err}"))?;
+                Value::String(
+                    String::from_utf8(bytes)
+                        .map_err(|_| "Decoded base64 data is not valid UTF-8".to_string())?,
+                )
+            }
+        }
+        "string.html_escape" => Value::String(html_escape(&input_value)),
+        "string.strip_html" => Value::String(strip_html(&input_value)),
+        "string.slugify" => Value::String(slugify(&input_value)),
+        "hash.sha256" => {
+            let encoding = parse_digest_encoding(
+                &read_string_field(&fields, "encoding", context).unwrap_or_default(),
+            )?;
+            Value::String(encode_digest(
+                &Sha256::digest(input_value.as_bytes()),
+                encoding,
+            ))
+        }
+        "hash.hmac_sha256" => {
+            let key = read_string_field(&fields, "key", context)
+                .filter(|s| !s.is_empty())
+                .ok_or_else(|| "HMAC key is required".to_string())?;
+            let encoding = parse_digest_encoding(
+                &read_string_field(&fields, "encoding", context).unwrap_or_default(),
+            )?;
+            let mut mac = Hmac::<Sha256>::new_from_slice(key.as_bytes())
+                .map_err(|_| "Invalid HMAC key".to_string())?;
+            mac.update(input_value.as_bytes());
+            Value::String(encode_digest(&mac.finalize().into_bytes(), encoding))
+        }
+        "csv.parse" => {
+            let delimiter = parse_csv_delimiter(
+                &read_string_field(&fields, "delimiter", context).unwrap_or_default(),
+            )?;
+            csv_parse(
+                &input_value,
+                delimiter,
+                bool_field(&fields, "has_header", true)?,
+            )?
+        }
+        "csv.stringify" => {
+            let delimiter = parse_csv_delimiter(
+                &read_string_field(&fields, "delimiter", context).unwrap_or_default(),
+            )?;
+            let items = parse_array_input(&input_value, "Input")?;
+            Value::String(csv_stringify(&items, delimiter)?)
+        }
         "number.add" | "number.subtract" | "number.multiply" | "number.divide" => {
             let operand = required_number_field(&fields, "value", context, "Value")?;
             let input_num = parse_number("Input", &input_value)?;
@@ -525,14 +1204,10 @@ pub(crate) fn execute_formatter(
         }
         "type.to_string" => {
             let parsed = parse_flexible_value(&input_value);
-            let text = match parsed {
-                Value::String(s) => s,
-                Value::Number(n) => n.to_string(),
-                Value::Bool(b) => b.to_string(),
-                Value::Null => "".to_string(),
-                other => other.to_string(),
-            };
-            Value::String(text)
+            Value::String(value_to_text(&parsed))
+        }
+        array_op if array_op.starts_with("array.") => {
+            execute_array_operation(array_op, &input_value, &fields, context)?
         }
         other => {
             return Err(format!("Unsupported formatter operation `{other}`"));
@@ -707,4 +1382,309 @@ mod tests {
         assert_eq!(obj.get("b"), Some(&Value::Number(2.into())));
         assert_eq!(obj.get("c"), Some(&Value::Number(3.into())));
     }
+
+    fn run_op(operation: &str, input: &str, fields: Value) -> Result<Value, String> {
+        let config = FormatterConfig {
+            operation: operation.into(),
+            input: input.into(),
+            fields: serde_json::from_value(fields).unwrap(),
+            output_key: "out".into(),
+        };
+        execute_formatter(&formatter_node(config), &json!({ "trigger": { "min": 2 } }))
+            .map(|(output, _)| output["out"].clone())
+    }
+
+    #[test]
+    fn array_operations_pluck_filter_sort_and_group() {
+        let rows = r#"[{"name":"b","team":"x","n":3},{"name":"a","team":"y","n":1},{"name":"c","team":"x","n":2}]"#;
+
+        assert_eq!(
+            run_op("array.map", rows, json!({ "field": "name" })).unwrap(),
+            json!(["b", "a", "c"])
+        );
+        assert_eq!(
+            run_op(
+                "array.filter",
+                rows,
+                json!({ "expression": "item.n >= {{trigger.min}}" })
+            )
+            .unwrap(),
+            json!([{"name":"b","team":"x","n":3},{"name":"c","team":"x","n":2}])
+        );
+        let sorted = run_op(
+            "array.sort",
+            rows,
+            json!({ "field": "n", "direction": "desc" }),
+        )
+        .unwrap();
+        assert_eq!(sorted[0]["name"], "b");
+        assert_eq!(sorted[2]["name"], "a");
+        assert_eq!(
+            run_op("array.sort", r#"[3, "b", 1, null, "a"]"#, json!({})).unwrap(),
+            json!([null, 1, 3, "a", "b"])
+        );
+        let grouped = run_op("array.group_by", rows, json!({ "field": "team" })).unwrap();
+        assert_eq!(grouped["x"].as_array().map(Vec::len), Some(2));
+        assert_eq!(grouped["y"][0]["name"], "a");
+        assert_eq!(
+            run_op("array.unique", rows, json!({ "field": "team" }))
+                .unwrap()
+                .as_array()
+                .map(Vec::len),
+            Some(2)
+        );
+        assert_eq!(
+            run_op(
+                "array.join",
+                rows,
+                json!({ "field": "name", "delimiter": " | " })
+            )
+            .unwrap(),
+            json!("b | a | c")
+        );
+    }
+
+    #[test]
+    fn array_operations_aggregate_and_reshape() {
+        let numbers = r#"[4, "6", null, 2]"#;
+        assert_eq!(run_op("array.sum", numbers, json!({})).unwrap(), json!(12));
+        assert_eq!(run_op("array.avg", numbers, json!({})).unwrap(), json!(4));
+        assert_eq!(run_op("array.min", numbers, json!({})).unwrap(), json!(2));
+        assert_eq!(run_op("array.max", numbers, json!({})).unwrap(), json!(6));
+        assert_eq!(run_op("array.avg", "[]", json!({})).unwrap(), Value::Null);
+        assert!(run_op("array.sum", r#"[1, "x"]"#, json!({}))
+            .unwrap_err()
+            .contains("Item 1 is not a number"));
+
+        assert_eq!(
+            run_op("array.length", numbers, json!({})).unwrap(),
+            json!(4)
+        );
+        assert_eq!(run_op("array.first", numbers, json!({})).unwrap(), json!(4));
+        assert_eq!(run_op("array.last", numbers, json!({})).unwrap(), json!(2));
+        assert_eq!(
+            run_op("array.chunk", "[1,2,3,4,5]", json!({ "size": 2 })).unwrap(),
+            json!([[1, 2], [3, 4], [5]])
+        );
+        assert_eq!(
+            run_op(
+                "array.zip",
+                "[1,2,3]",
+                json!({ "input_two": "[\"a\",\"b\"]" })
+            )
+            .unwrap(),
+            json!([[1, "a"], [2, "b"], [3, null]])
+        );
+        assert!(run_op("array.length", r#"{"a":1}"#, json!({}))
+            .unwrap_err()
+            .contains("must be a JSON array"));
+        assert!(run_op("array.chunk", "[1]", json!({ "size": 0 })).is_err());
+    }
+
+    #[test]
+    fn regex_operations_extract_named_groups_replace_and_test() {
+        let text = "Order #123 by ada@example.com; Order #456 by bob@example.com";
+        let pattern = r"Order #(?P<id>\d+) by (?P<email>\S+?@\S+?\.com)";
+        assert_eq!(
+            run_op("string.regex_extract", text, json!({ "pattern": pattern })).unwrap(),
+            json!({ "id": "123", "email": "ada@example.com" })
+        );
+        assert_eq!(
+            run_op(
+                "string.regex_extract",
+                text,
+                json!({ "pattern": r"#(\d+)", "all_matches": true })
+            )
+            .unwrap(),
+            json!(["123", "456"])
+        );
+        assert_eq!(
+            run_op(
+                "string.regex_extract",
+                text,
+                json!({ "pattern": "missing" })
+            )
+            .unwrap(),
+            Value::Null
+        );
+        assert_eq!(
+            run_op(
+                "string.regex_replace",
+                text,
+                json!({ "pattern": r"(?P<user>\w+)@example\.com", "replace_with": "${user}@redacted" })
+            )
+            .unwrap(),
+            json!("Order #123 by ada@redacted; Order #456 by bob@redacted")
+        );
+        assert_eq!(
+            run_op(
+                "string.regex_test",
+                "HELLO",
+                json!({ "pattern": "^hello$", "flags": "i" })
+            )
+            .unwrap(),
+            json!(true)
+        );
+
+        let err = run_op("string.regex_test", "x", json!({ "pattern": "(" })).unwrap_err();
+        assert!(err.contains("Invalid regex pattern"), "{err}");
+        let err = run_op(
+            "string.regex_test",
+            "x",
+            json!({ "pattern": "x", "flags": "q" }),
+        )
+        .unwrap_err();
+        assert!(err.contains("Unsupported regex flag"), "{err}");
+    }
+
+    #[test]
+    fn encoding_and_hashing_operations() {
+        assert_eq!(
+            run_op("string.url_encode", "a b&c=d/é", json!({})).unwrap(),
+            json!("a%20b%26c%3Dd%2F%C3%A9")
+        );
+        assert_eq!(
+            run_op("string.url_decode", "a+b%26c", json!({})).unwrap(),
+            json!("a b&c")
+        );
+        assert_eq!(
+            run_op("string.base64_encode", "hello?", json!({})).unwrap(),
+            json!("aGVsbG8/")
+        );
+        assert_eq!(
+            run_op(
+                "string.base64_encode",
+                "hello?",
+                json!({ "url_safe": true })
+            )
+            .unwrap(),
+            json!("aGVsbG8_")
+        );
+        assert_eq!(
+            run_op("string.base64_decode", "aGVsbG8/", json!({})).unwrap(),
+            json!("hello?")
+        );
+        assert_eq!(
+            run_op(
+                "string.html_escape",
+                r#"<a href="x">Tom & 'Jerry'</a>"#,
+                json!({})
+            )
+            .unwrap(),
+            json!("&lt;a href=&quot;x&quot;&gt;Tom &amp; &#39;Jerry&#39;&lt;/a&gt;")
+        );
+        assert_eq!(
+            run_op(
+                "string.strip_html",
+                "<p>Hello <b>world</b> &amp; co</p><script>alert(1)</script>\n<!-- note -->",
+                json!({})
+            )
+            .unwrap(),
+            json!("Hello world & co")
+        );
+        assert_eq!(
+            run_op("string.slugify", "  Hello, World! It's 2025 ", json!({})).unwrap(),
+            json!("hello-world-its-2025")
+        );
+        assert_eq!(
+            run_op("hash.sha256", "abc", json!({})).unwrap(),
+            json!("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad")
+        );
+        assert_eq!(
+            run_op(
+                "hash.hmac_sha256",
+                "The quick brown fox jumps over the lazy dog",
+                json!({ "key": "key" })
+            )
+            .unwrap(),
+            json!("f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd8")
+        );
+        assert_eq!(
+            run_op(
+                "hash.hmac_sha256",
+                "The quick brown fox jumps over the lazy dog",
+                json!({ "key": "key", "encoding": "base64" })
+            )
+            .unwrap(),
+            json!("97yD9DBThCSxMpjmqm+xQ+9NWaFJRhdZl0edvC0aPNg=")
+        );
+        assert!(run_op("hash.hmac_sha256", "x", json!({}))
+            .unwrap_err()
+            .contains("HMAC key is required"));
+    }
+
+    #[test]
+    fn csv_operations_round_trip_objects() {
+        let parsed = run_op(
+            "csv.parse",
+            "name,note\nAda,\"likes, commas\"\nBob,\n",
+            json!({}),
+        )
+        .unwrap();
+        assert_eq!(
+            parsed,
+            json!([
+                { "name": "Ada", "note": "likes, commas" },
+                { "name": "Bob", "note": "" }
+            ])
+        );
+        assert_eq!(
+            run_op(
+                "csv.parse",
+                "a;b\nc;d",
+                json!({ "delimiter": ";", "has_header": false })
+            )
+            .unwrap(),
+            json!([["a", "b"], ["c", "d"]])
+        );
+
+        let written = run_op(
+            "csv.stringify",
+            r#"[{"name":"Ada","age":36},{"name":"Bob","city":"Paris, FR"}]"#,
+            json!({}),
+        )
+        .unwrap();
+        assert_eq!(
+            written,
+            json!("age,name,city\n36,Ada,\n,Bob,\"Paris, FR\"\n")
+        );
+        assert!(run_op("csv.parse", "a", json!({ "delimiter": "::" }))
+            .unwrap_err()
+            .contains("single ASCII character"));
+    }
+
+    #[test]
+    fn config_validation_rejects_unknown_operations_and_bad_literals() {
+        let config = |operation: &str, fields: Value| FormatterConfig {
+            operation: operation.into(),
+            input: String::new(),
+            fields: serde_json::from_value(fields).unwrap(),
+            output_key: "out".into(),
+        };
+        assert!(config("string.trim", json!({})).validate().is_ok());
+        assert!(config("string.reverse", json!({}))
+            .validate()
+            .unwrap_err()
+            .contains("Unsupported formatter operation"));
+        assert!(config("string.regex_test", json!({}))
+            .validate()
+            .unwrap_err()
+            .contains("pattern is required"));
+        assert!(config("string.regex_test", json!({ "pattern": "[a-" }))
+            .validate()
+            .unwrap_err()
+            .contains("Invalid regex pattern"));
+        // Templated patterns are only checked once rendered.
+        assert!(
+            config("string.regex_test", json!({ "pattern": "{{trigger.re}}" }))
+                .validate()
+                .is_ok()
+        );
+        assert!(config("hash.sha256", json!({ "encoding": "base32" }))
+            .validate()
+            .is_err());
+        let mut bad_key = config("string.trim", json!({}));
+        bad_key.output_key = "1bad".into();
+        assert!(bad_key.validate().is_err());
+    }
 }
@@ -0,0 +1,518 @@
+use serde_json::{json, Map, Value};
+use uuid::Uuid;
+
+use crate::engine::graph::Node;
+use crate::engine::templating::templ_str;
+use crate::models::oauth_token::ConnectedOAuthProvider;
+use crate::models::workflow_run::WorkflowRun;
+use crate::services::github::{self, GitHubError};
+use crate::services::oauth::account_service::OAuthAccountError;
+use crate::services::oauth::workspace_service::WorkspaceOAuthError;
+use crate::state::AppState;
+
+use super::{ensure_run_membership, ensure_workspace_plan};
+
+fn read_required(
+    params: &Value,
+    key: &str,
+    label: &str,
+    context: &Value,
+) -> Result<String, String> {
+    read_optional(params, key, context).ok_or_else(|| format!("{label} is required"))
+}
+
+fn read_optional(params: &Value, key: &str, context: &Value) -> Option<String> {
+    params
+        .get(key)
+        .and_then(|v| v.as_str())
+        .map(|s| templ_str(s, context).trim().to_string())
+        .filter(|s| !s.is_empty())
+}
+
+fn read_issue_number(params: &Value, context: &Value) -> Result<u64, String> {
+    let raw = match params.get("issueNumber") {
+        Some(Value::Number(n)) => n.to_string(),
+        _ => read_required(params, "issueNumber", "Issue number", context)?,
+    };
+    raw.trim_start_matches('#')
+        .parse::<u64>()
+        .ok()
+        .filter(|n| *n > 0)
+        .ok_or_else(|| format!("Issue number must be a positive integer, got `{raw}`"))
+}
+
+/// Accepts a JSON array or a comma-separated string, templated either way.
+fn read_list(params: &Value, key: &str, context: &Value) -> Vec<String> {
+    let items: Vec<String> = match params.get(key) {
+        Some(Value::Array(items)) => items
+            .iter()
+            .filter_map(|v| v.as_str())
+            .map(|s| templ_str(s, context))
+            .collect(),
+        Some(Value::String(raw)) => templ_str(raw, context)
+            .split(',')
+            .map(str::to_string)
+            .collect(),
+        _ => Vec::new(),
+    };
+    items
+        .into_iter()
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+fn read_bool(params: &Value, key: &str) -> Option<bool> {
+    params.get(key).and_then(|v| v.as_bool())
+}
+
+/// `workflow_dispatch` inputs are always strings on GitHub's side.
+fn read_inputs(params: &Value, context: &Value) -> Map<String, Value> {
+    let mut map = Map::new();
+    if let Some(entries) = params.get("inputs").and_then(|v| v.as_array()) {
+        for entry in entries {
+            let key = entry
+                .get("key")
+                .and_then(|v| v.as_str())
+                .map(|s| templ_str(s, context).trim().to_string())
+                .unwrap_or_default();
+            if key.is_empty() {
+                continue;
+            }
+            let value = entry.get("value").and_then(|v| v.as_str()).unwrap_or("");
+            map.insert(key, Value::String(templ_str(value, context)));
+        }
+    }
+    map
+}
+
+fn issue_output(issue: &Value) -> Value {
+    json!({
+        "id": issue.get("id").cloned().unwrap_or(Value::Null),
+        "number": issue.get("number").cloned().unwrap_or(Value::Null),
+        "url": issue.get("html_url").cloned().unwrap_or(Value::Null),
+        "state": issue.get("state").cloned().unwrap_or(Value::Null),
+        "title": issue.get("title").cloned().unwrap_or(Value::Null),
+        "issue": issue,
+    })
+}
+
+fn map_github_error(err: GitHubError) -> String {
+    match err {
+        GitHubError::Api { status, message } if status.as_u16() == 404 => format!(
+            "GitHub returned 404: {message}. Check the repository name and that the connection has access to it."
+        ),
+        other => other.to_string(),
+    }
+}
+
+fn map_oauth_error(err: OAuthAccountError) -> String {
+    match err {
+        OAuthAccountError::NotFound => "No GitHub OAuth connection found".to_string(),
+        OAuthAccountError::TokenRevoked { .. } => {
+            "The GitHub connection was revoked. Reconnect in Settings -> Integrations.".to_string()
+        }
+        other => format!("GitHub OAuth error: {other}"),
+    }
+}
+
+fn map_workspace_oauth_error(err: WorkspaceOAuthError) -> String {
+    match err {
+        WorkspaceOAuthError::Forbidden => {
+            "You no longer have access to this workspace connection.".to_string()
+        }
+        WorkspaceOAuthError::NotFound => "GitHub workspace connection not found.".to_string(),
+        WorkspaceOAuthError::SlackInstallRequired => {
+            "Slack connections must be installed at workspace scope.".to_string()
+        }
+        WorkspaceOAuthError::OAuth(inner) => map_oauth_error(inner),
+        WorkspaceOAuthError::Database(err) => format!("Failed to load workspace connection: {err}"),
+        WorkspaceOAuthError::Encryption(err) => {
+            format!("Failed to decrypt workspace connection: {err}")
+        }
+    }
+}
+
+async fn resolve_access_token(
+    params: &Value,
+    state: &AppState,
+    run: &WorkflowRun,
+) -> Result<String, String> {
+    match super::resolve_connection_usage(params)? {
+        super::NodeConnectionUsage::Workspace(info) => {
+            let workspace_id = run.workspace_id.ok_or_else(|| {
+                "This workflow is not associated with a workspace. Promote the GitHub connection to the workspace or switch to a personal connection.".to_string()
+            })?;
+
+            ensure_run_membership(state, workspace_id, run.user_id).await?;
+            ensure_workspace_plan(state, workspace_id).await?;
+
+            let connection = state
+                .workspace_oauth
+                .ensure_valid_workspace_token(info.connection_id)
+                .await
+                .map_err(map_workspace_oauth_error)?;
+
+            if connection.workspace_id != workspace_id {
+                return Err(
+                    "The selected GitHub connection belongs to another workspace".to_string(),
+                );
+            }
+            if connection.provider != ConnectedOAuthProvider::GitHub {
+                return Err("Selected connection is not a GitHub connection".to_string());
+            }
+
+            Ok(connection.access_token)
+        }
+        super::NodeConnectionUsage::User(info) => {
+            let connection_id_str = info.connection_id.ok_or_else(|| {
+                "Personal OAuth connections require an explicit connectionId. Please select a specific OAuth connection from your integrations.".to_string()
+            })?;
+
+            let connection_id = Uuid::parse_str(&connection_id_str)
+                .map_err(|_| "Personal connectionId must be a valid UUID. Please select a valid OAuth connection.".to_string())?;
+
+            let token = state
+                .oauth_accounts
+                .ensure_valid_access_token_for_connection(run.user_id, connection_id)
+                .await
+                .map_err(map_oauth_error)?;
+
+            if token.provider != ConnectedOAuthProvider::GitHub {
+                return Err("Selected connection is not a GitHub connection".to_string());
+            }
+
+            Ok(token.access_token)
+        }
+    }
+}
+
+pub(crate) async fn execute_github(
+    node: &Node,
+    context: &Value,
+    state: &AppState,
+    run: &WorkflowRun,
+) -> Result<(Value, Option<String>), String> {
+    let params = node.data.get("params").cloned().unwrap_or(Value::Null);
+
+    let operation = params
+        .get("operation")
+        .and_then(|v| v.as_str())
+        .map(|s| s.trim().to_ascii_lowercase().replace(['_', '-'], ""))
+        .unwrap_or_default();
+    if !matches!(
+        operation.as_str(),
+        "createissue" | "createcomment" | "addlabels" | "createrelease" | "dispatchworkflow"
+    ) {
+        return Ok((
+            json!({
+                "skipped": true,
+                "reason": format!("Unsupported GitHub operation `{operation}`")
+            }),
+            None,
+        ));
+    }
+
+    let repository = read_required(&params, "repository", "Repository", context)?;
+    github::parse_repository(&repository).map_err(|err| err.to_string())?;
+    let access_token = resolve_access_token(&params, state, run).await?;
+
+    let client = state.http_client.as_ref();
+    let base = state.config.provider_base_urls.github.as_str();
+
+    match operation.as_str() {
+        "createissue" => {
+            let mut payload = Map::new();
+            payload.insert(
+                "title".into(),
+                Value::String(read_required(&params, "title", "Title", context)?),
+            );
+            if let Some(body) = read_optional(&params, "body", context) {
+                payload.insert("body".into(), Value::String(body));
+            }
+            let labels = read_list(&params, "labels", context);
+            if !labels.is_empty() {
+                payload.insert("labels".into(), json!(labels));
+            }
+            let assignees = read_list(&params, "assignees", context);
+            if !assignees.is_empty() {
+                payload.insert("assignees".into(), json!(assignees));
+            }
+            let issue = github::create_issue(
+                client,
+                base,
+                &access_token,
+                &repository,
+                &Value::Object(payload),
+            )
+            .await
+            .map_err(map_github_error)?;
+            Ok((issue_output(&issue), None))
+        }
+        "createcomment" => {
+            let number = read_issue_number(&params, context)?;
+            let body = read_required(&params, "body", "Comment body", context)?;
+            let comment = github::create_issue_comment(
+                client,
+                base,
+                &access_token,
+                &repository,
+                number,
+                &json!({ "body": body }),
+            )
+            .await
+            .map_err(map_github_error)?;
+            Ok((
+                json!({
+                    "id": comment.get("id").cloned().unwrap_or(Value::Null),
+                    "issueNumber": number,
+                    "url": comment.get("html_url").cloned().unwrap_or(Value::Null),
+                    "comment": comment,
+                }),
+                None,
+            ))
+        }
+        "addlabels" => {
+            let number = read_issue_number(&params, context)?;
+            let labels = read_list(&params, "labels", context);
+            if labels.is_empty() {
+                return Err("At least one label is required".to_string());
+            }
+            let all = github::add_labels(
+                client,
+                base,
+                &access_token,
+                &repository,
+                number,
+                &json!({ "labels": labels }),
+            )
+            .await
+            .map_err(map_github_error)?;
+            let names: Vec<Value> = all
+                .as_array()
+                .map(|labels| {
+                    labels
+                        .iter()
+                        .filter_map(|l| l.get("name").cloned())
+                        .collect()
+                })
+                .unwrap_or_default();
+            Ok((json!({ "issueNumber": number, "labels": names }), None))
+        }
+        "createrelease" => {
+            let mut payload = Map::new();
+            payload.insert(
+                "tag_name".into(),
+                Value::String(read_required(&params, "tagName", "Tag name", context)?),
+            );
+            for (param, field) in [
+                ("name", "name"),
+                ("body", "body"),
+                ("targetCommitish", "target_commitish"),
+            ] {
+                if let Some(value) = read_optional(&params, param, context) {
+                    payload.insert(field.into(), Value::String(value));
+                }
+            }
+            for (param, field) in [
+                ("draft", "draft"),
+                ("prerelease", "prerelease"),
+                ("generateReleaseNotes", "generate_release_notes"),
+            ] {
+                if let Some(flag) = read_bool(&params, param) {
+                    payload.insert(field.into(), Value::Bool(flag));
+                }
+            }
+            let release = github::create_release(
+                client,
+                base,
+                &access_token,
+                &repository,
+                &Value::Object(payload),
+            )
+            .await
+            .map_err(map_github_error)?;
+            Ok((
+                json!({
+                    "id": release.get("id").cloned().unwrap_or(Value::Null),
+                    "tagName": release.get("tag_name").cloned().unwrap_or(Value::Null),
+                    "url": release.get("html_url").cloned().unwrap_or(Value::Null),
+                    "draft": release.get("draft").cloned().unwrap_or(Value::Null),
+                    "prerelease": release.get("prerelease").cloned().unwrap_or(Value::Null),
+                    "release": release,
+                }),
+                None,
+            ))
+        }
+        _ => {
+            let workflow = read_required(&params, "workflowId", "Workflow", context)?;
+            let git_ref = read_required(&params, "ref", "Ref", context)?;
+            let inputs = read_inputs(&params, context);
+            let mut payload = json!({ "ref": git_ref });
+            if !inputs.is_empty() {
+                payload["inputs"] = Value::Object(inputs);
+            }
+            github::dispatch_workflow(
+                client,
+                base,
+                &access_token,
+                &repository,
+                &workflow,
+                &payload,
+            )
+            .await
+            .map_err(map_github_error)?;
+            Ok((
+                json!({
+                    "dispatched": true,
+                    "repository": repository,
+                    "workflowId": workflow,
+                    "ref": payload["ref"],
+                }),
+                None,
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{personal_connection_fixture, FakeProvider};
+    use axum::http::{Method, StatusCode};
+
+    fn github_node(params: Value) -> Node {
+        Node {
+            id: "github".into(),
+            kind: "action".into(),
+            data: json!({ "params": params }),
+        }
+    }
+
+    fn connection(token_id: Uuid) -> Value {
+        json!({ "connectionScope": "personal", "connectionId": token_id.to_string() })
+    }
+
+    #[tokio::test]
+    async fn create_issue_posts_templated_fields() {
+        let provider = FakeProvider::start().await;
+        provider.respond(
+            Method::POST,
+            "/github/repos/octo/app/issues",
+            StatusCode::CREATED,
+            json!({
+                "id": 7001,
+                "number": 42,
+                "title": "Deploy failed",
+                "state": "open",
+                "html_url": "https://github.com/octo/app/issues/42"
+            }),
+        );
+        let (state, run, token_id) =
+            personal_connection_fixture(ConnectedOAuthProvider::GitHub, provider.base_urls());
+
+        let node = github_node(json!({
+            "operation": "createIssue",
+            "connection": connection(token_id),
+            "repository": "octo/{{ repo }}",
+            "title": "Deploy failed",
+            "body": "Run {{ run }} failed",
+            "labels": "bug, ops",
+            "assignees": ["hubot"]
+        }));
+
+        let (output, _) = execute_github(&node, &json!({ "repo": "app", "run": 9 }), &state, &run)
+            .await
+            .expect("create issue should succeed");
+
+        assert_eq!(output["number"], 42);
+        assert_eq!(output["url"], "https://github.com/octo/app/issues/42");
+        let requests = provider.requests_to(Method::POST, "/github/repos/octo/app/issues");
+        assert_eq!(requests.len(), 1);
+        assert_eq!(
+            requests[0].header("authorization").as_deref(),
+            Some("Bearer access-token")
+        );
+        assert_eq!(
+            requests[0].header("accept").as_deref(),
+            Some("application/vnd.github+json")
+        );
+        assert_eq!(
+            requests[0].json(),
+            json!({
+                "title": "Deploy failed",
+                "body": "Run 9 failed",
+                "labels": ["bug", "ops"],
+                "assignees": ["hubot"]
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn dispatch_workflow_sends_ref_and_string_inputs() {
+        let provider = FakeProvider::start().await;
+        provider.respond(
+            Method::POST,
+            "/github/repos/octo/app/actions/workflows/deploy.yml/dispatches",
+            StatusCode::NO_CONTENT,
+            Value::Null,
+        );
+        let (state, run, token_id) =
+            personal_connection_fixture(ConnectedOAuthProvider::GitHub, provider.base_urls());
+
+        let node = github_node(json!({
+            "operation": "dispatchWorkflow",
+            "connection": connection(token_id),
+            "repository": "octo/app",
+            "workflowId": "deploy.yml",
+            "ref": "main",
+            "inputs": [{ "key": "environment", "value": "{{ env }}" }]
+        }));
+
+        let (output, _) = execute_github(&node, &json!({ "env": "prod" }), &state, &run)
+            .await
+            .expect("dispatch should succeed");
+
+        assert_eq!(output["dispatched"], true);
+        let requests = provider.requests_to(
+            Method::POST,
+            "/github/repos/octo/app/actions/workflows/deploy.yml/dispatches",
+        );
+        assert_eq!(requests.len(), 1);
+        assert_eq!(
+            requests[0].json(),
+            json!({ "ref": "main", "inputs": { "environment": "prod" } })
+        );
+    }
+
+    #[tokio::test]
+    async fn rejects_other_providers_and_bad_repositories() {
+        let provider = FakeProvider::start().await;
+        let (state, run, token_id) =
+            personal_connection_fixture(ConnectedOAuthProvider::Google, provider.base_urls());
+
+        let node = github_node(json!({
+            "operation": "createComment",
+            "connection": connection(token_id),
+            "repository": "octo/app",
+            "issueNumber": 3,
+            "body": "hi"
+        }));
+        let err = execute_github(&node, &Value::Null, &state, &run)
+            .await
+            .expect_err("google token must not be used");
+        assert!(err.contains("not a GitHub connection"), "{err}");
+
+        let node = github_node(json!({
+            "operation": "createComment",
+            "connection": connection(token_id),
+            "repository": "octo/app/../../user",
+            "issueNumber": 3,
+            "body": "hi"
+        }));
+        let err = execute_github(&node, &Value::Null, &state, &run)
+            .await
+            .expect_err("bad repository");
+        assert!(err.contains("owner/repo"), "{err}");
+    }
+}
@@ -0,0 +1,543 @@
+use serde_json::{json, Map, Value};
+use time::{format_description::well_known::Rfc3339, Duration, OffsetDateTime};
+
+use crate::engine::graph::Node;
+use crate::engine::templating::templ_str;
+use crate::models::workflow_run::WorkflowRun;
+use crate::state::AppState;
+
+use super::google::resolve_google_session;
+use super::{read_addresses, read_limit, read_optional, read_required, resolve_connection_usage};
+
+const API_LABEL: &str = "Google Calendar";
+const DEFAULT_CALENDAR_ID: &str = "primary";
+const DEFAULT_TIME_ZONE: &str = "UTC";
+const DEFAULT_SLOT_MINUTES: u32 = 30;
+const DEFAULT_MAX_SLOTS: u32 = 10;
+const MAX_FREE_BUSY_DAYS: i64 = 60;
+
+pub(crate) async fn execute_google_calendar(
+    node: &Node,
+    context: &Value,
+    state: &AppState,
+    run: &WorkflowRun,
+) -> Result<(Value, Option<String>), String> {
+    let params = node.data.get("params").cloned().unwrap_or(Value::Null);
+
+    let operation = params
+        .get("operation")
+        .and_then(|v| v.as_str())
+        .map(|s| s.trim().to_ascii_lowercase())
+        .unwrap_or_default();
+    if !matches!(
+        operation.as_str(),
+        "create_event" | "update_event" | "delete_event" | "find_free_slots"
+    ) {
+        return Err("Unsupported Google Calendar operation".to_string());
+    }
+
+    let connection_usage = resolve_connection_usage(&params)?;
+    let calendar_id = read_optional(&params, "calendarId", context)
+        .unwrap_or_else(|| DEFAULT_CALENDAR_ID.to_string());
+    let base_url = state
+        .config
+        .provider_base_urls
+        .google_calendar
+        .trim_end_matches('/');
+    let events_url = format!(
+        "{}/calendars/{}/events",
+        base_url,
+        urlencoding::encode(&calendar_id)
+    );
+
+    let mut output = match operation.as_str() {
+        "create_event" => {
+            let event = build_event_payload(&params, context, true)?;
+            let query = send_updates_query(&params, context)?;
+            let session = resolve_google_session(connection_usage, state, run).await?;
+            let request = state
+                .http_client
+                .post(&events_url)
+                .query(&query)
+                .json(&event);
+            let response = session.send(state, request, API_LABEL).await?;
+            session.with_output(event_summary(&response))
+        }
+        "update_event" => {
+            let event_id = read_required(&params, "eventId", "Event ID", context)?;
+            let patch = build_event_payload(&params, context, false)?;
+            if patch.as_object().is_none_or(Map::is_empty) {
+                return Err("At least one event field is required to update an event".into());
+            }
+            let query = send_updates_query(&params, context)?;
+            let session = resolve_google_session(connection_usage, state, run).await?;
+            let request = state
+                .http_client
+                .patch(format!("{}/{}", events_url, urlencoding::encode(&event_id)))
+                .query(&query)
+                .json(&patch);
+            let response = session.send(state, request, API_LABEL).await?;
+            session.with_output(event_summary(&response))
+        }
+        "delete_event" => {
+            let event_id = read_required(&params, "eventId", "Event ID", context)?;
+            let query = send_updates_query(&params, context)?;
+            let session = resolve_google_session(connection_usage, state, run).await?;
+            let request = state
+                .http_client
+                .delete(format!("{}/{}", events_url, urlencoding::encode(&event_id)))
+                .query(&query);
+            session.send(state, request, API_LABEL).await?;
+            session.with_output(json!({ "deleted": true, "eventId": event_id }))
+        }
+        _ => {
+            let window = FreeSlotWindow::from_params(&params, context)?;
+            let calendars = read_calendar_ids(&params, context, &calendar_id);
+            let session = resolve_google_session(connection_usage, state, run).await?;
+            let body = json!({
+                "timeMin": format_time(window.start),
+                "timeMax": format_time(window.end),
+                "timeZone": window.time_zone,
+                "items": calendars
+                    .iter()
+                    .map(|id| json!({ "id": id }))
+                    .collect::<Vec<_>>(),
+            });
+            let request = state
+                .http_client
+                .post(format!("{}/freeBusy", base_url))
+                .json(&body);
+            let response = session.send(state, request, API_LABEL).await?;
+            let busy = collect_busy_intervals(&response, &calendars)?;
+            let slots = find_free_slots(
+                window.start,
+                window.end,
+                &busy,
+                window.slot_length,
+                window.max_slots,
+            );
+            let slots_json: Vec<Value> = slots
+                .iter()
+                .map(|(start, end)| json!({ "start": format_time(*start), "end": format_time(*end) }))
+                .collect();
+            session.with_output(json!({
+                "calendars": calendars,
+                "durationMinutes": window.slot_length.whole_minutes(),
+                "count": slots_json.len(),
+                "firstSlot": slots_json.first().cloned().unwrap_or(Value::Null),
+                "slots": slots_json,
+            }))
+        }
+    };
+
+    output.insert("service".to_string(), Value::String(API_LABEL.to_string()));
+    output.insert("operation".to_string(), Value::String(operation));
+    Ok((Value::Object(output), None))
+}
+
+fn send_updates_query(
+    params: &Value,
+    context: &Value,
+) -> Result<Vec<(&'static str, String)>, String> {
+    match read_optional(params, "sendUpdates", context) {
+        None => Ok(Vec::new()),
+        Some(raw) => match raw.as_str() {
+            "all" | "externalOnly" | "none" => Ok(vec![("sendUpdates", raw)]),
+            _ => Err("sendUpdates must be one of all, externalOnly or none".to_string()),
+        },
+    }
+}
+
+/// Dates (`YYYY-MM-DD`) become all-day boundaries; anything else is passed
+/// through as a date-time in the configured time zone.
+fn event_time(raw: &str, time_zone: &str) -> Value {
+    if raw.len() == 10 && raw.as_bytes().get(4) == Some(&b'-') {
+        json!({ "date": raw })
+    } else {
+        json!({ "dateTime": raw, "timeZone": time_zone })
+    }
+}
+
+fn build_event_payload(params: &Value, context: &Value, creating: bool) -> Result<Value, String> {
+    let mut event = Map::new();
+
+    let summary = read_optional(params, "summary", context);
+    let start = read_optional(params, "start", context);
+    let end = read_optional(params, "end", context);
+    if creating {
+        if summary.is_none() {
+            return Err("Event title is required".to_string());
+        }
+        if start.is_none() {
+            return Err("Start time is required".to_string());
+        }
+        if end.is_none() {
+            return Err("End time is required".to_string());
+        }
+    }
+
+    let time_zone =
+        read_optional(params, "timeZone", context).unwrap_or_else(|| DEFAULT_TIME_ZONE.to_string());
+
+    if let Some(summary) = summary {
+        event.insert("summary".to_string(), Value::String(summary));
+    }
+    if let Some(start) = start {
+        event.insert("start".to_string(), event_time(&start, &time_zone));
+    }
+    if let Some(end) = end {
+        event.insert("end".to_string(), event_time(&end, &time_zone));
+    }
+    if let Some(description) = read_optional(params, "description", context) {
+        event.insert("description".to_string(), Value::String(description));
+    }
+    if let Some(location) = read_optional(params, "location", context) {
+        event.insert("location".to_string(), Value::String(location));
+    }
+
+    let attendees = read_addresses(params, "attendees", context)?;
+    if !attendees.is_empty() {
+        event.insert(
+            "attendees".to_string(),
+            Value::Array(
+                attendees
+                    .iter()
+                    .map(|email| json!({ "email": email }))
+                    .collect(),
+            ),
+        );
+    }
+
+    Ok(Value::Object(event))
+}
+
+fn event_summary(event: &Value) -> Value {
+    let attendees: Vec<Value> = event
+        .get("attendees")
+        .and_then(|v| v.as_array())
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|item| item.get("email").cloned())
+                .collect()
+        })
+        .unwrap_or_default();
+
+    json!({
+        "eventId": event.get("id").cloned().unwrap_or(Value::Null),
+        "summary": event.get("summary").cloned().unwrap_or(Value::Null),
+        "start": event.get("start").cloned().unwrap_or(Value::Null),
+        "end": event.get("end").cloned().unwrap_or(Value::Null),
+        "location": event.get("location").cloned().unwrap_or(Value::Null),
+        "attendees": attendees,
+        "htmlLink": event.get("htmlLink").cloned().unwrap_or(Value::Null),
+        "status": event.get("status").cloned().unwrap_or(Value::Null),
+    })
+}
+
+struct FreeSlotWindow {
+    start: OffsetDateTime,
+    end: OffsetDateTime,
+    time_zone: String,
+    slot_length: Duration,
+    max_slots: usize,
+}
+
+impl FreeSlotWindow {
+    fn from_params(params: &Value, context: &Value) -> Result<Self, String> {
+        let start = parse_time(&read_required(params, "timeMin", "Search start", context)?)
+            .ok_or_else(|| "Search start must be an RFC 3339 timestamp".to_string())?;
+        let end = parse_time(&read_required(params, "timeMax", "Search end", context)?)
+            .ok_or_else(|| "Search end must be an RFC 3339 timestamp".to_string())?;
+        if end <= start {
+            return Err("Search end must be after search start".to_string());
+        }
+        if end - start > Duration::days(MAX_FREE_BUSY_DAYS) {
+            return Err(format!(
+                "Free slot searches are limited to {MAX_FREE_BUSY_DAYS} days"
+            ));
+        }
+
+        let minutes =
+            read_limit(params, "durationMinutes", context).unwrap_or(DEFAULT_SLOT_MINUTES);
+        let max_slots = read_limit(params, "maxResults", context).unwrap_or(DEFAULT_MAX_SLOTS);
+
+        Ok(Self {
+            start,
+            end,
+            time_zone: read_optional(params, "timeZone", context)
+                .unwrap_or_else(|| DEFAULT_TIME_ZONE.to_string()),
+            slot_length: Duration::minutes(i64::from(minutes)),
+            max_slots: max_slots as usize,
+        })
+    }
+}
+
+fn read_calendar_ids(params: &Value, context: &Value, fallback: &str) -> Vec<String> {
+    let ids = read_addresses_lenient(params, "calendars", context);
+    if ids.is_empty() {
+        vec![fallback.to_string()]
+    } else {
+        ids
+    }
+}
+
+/// Calendar ids are usually addresses but may also be `primary` or opaque
+/// group calendar ids, so only split and trim them.
+fn read_addresses_lenient(params: &Value, key: &str, context: &Value) -> Vec<String> {
+    let raw: Vec<String> = match params.get(key) {
+        Some(Value::Array(items)) => items
+            .iter()
+            .filter_map(|item| item.as_str())
+            .map(|item| templ_str(item, context))
+            .collect(),
+        Some(Value::String(value)) => vec![templ_str(value, context)],
+        _ => Vec::new(),
+    };
+    raw.iter()
+        .flat_map(|entry| entry.split([',', ';']))
+        .map(str::trim)
+        .filter(|id| !id.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+fn collect_busy_intervals(
+    response: &Value,
+    calendars: &[String],
+) -> Result<Vec<(OffsetDateTime, OffsetDateTime)>, String> {
+    let mut busy = Vec::new();
+    for calendar in calendars {
+        let Some(entry) = response.get("calendars").and_then(|c| c.get(calendar)) else {
+            continue;
+        };
+        if let Some(reason) = entry
+            .get("errors")
+            .and_then(|errors| errors.as_array())
+            .and_then(|errors| errors.first())
+            .and_then(|error| error.get("reason"))
+            .and_then(|reason| reason.as_str())
+        {
+            return Err(format!(
+                "Google Calendar could not read availability for `{calendar}`: {reason}"
+            ));
+        }
+        for interval in entry
+            .get("busy")
+            .and_then(|b| b.as_array())
+            .into_iter()
+            .flatten()
+        {
+            let start = interval
+                .get("start")
+                .and_then(|v| v.as_str())
+                .and_then(parse_time);
+            let end = interval
+                .get("end")
+                .and_then(|v| v.as_str())
+                .and_then(parse_time);
+            if let (Some(start), Some(end)) = (start, end) {
+                busy.push((start, end));
+            }
+        }
+    }
+    Ok(busy)
+}
+
+/// Walks the busy intervals of every calendar in start order and returns the
+/// gaps between them that can hold a meeting of `slot_length`.
+fn find_free_slots(
+    window_start: OffsetDateTime,
+    window_end: OffsetDateTime,
+    busy: &[(OffsetDateTime, OffsetDateTime)],
+    slot_length: Duration,
+    max_slots: usize,
+) -> Vec<(OffsetDateTime, OffsetDateTime)> {
+    let mut busy = busy.to_vec();
+    busy.sort_by_key(|(start, _)| *start);
+
+    let mut slots = Vec::new();
+    let mut cursor = window_start;
+    for (start, end) in busy {
+        if slots.len() >= max_slots {
+            break;
+        }
+        let gap_end = start.min(window_end);
+        if gap_end - cursor >= slot_length {
+            slots.push((cursor, gap_end));
+        }
+        cursor = cursor.max(end);
+        if cursor >= window_end {
+            break;
+        }
+    }
+    if slots.len() < max_slots && window_end - cursor >= slot_length {
+        slots.push((cursor, window_end));
+    }
+    slots
+}
+
+fn parse_time(raw: &str) -> Option<OffsetDateTime> {
+    OffsetDateTime::parse(raw.trim(), &Rfc3339).ok()
+}
+
+fn format_time(value: OffsetDateTime) -> String {
+    value.format(&Rfc3339).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::oauth_token::ConnectedOAuthProvider;
+    use crate::test_support::{personal_connection_fixture, FakeProvider};
+    use axum::http::{Method, StatusCode};
+
+    fn at(raw: &str) -> OffsetDateTime {
+        parse_time(raw).unwrap()
+    }
+
+    #[test]
+    fn event_payload_supports_all_day_and_attendees() {
+        let params = json!({
+            "summary": "Offsite for {{ trigger.team }}",
+            "start": "2024-06-03",
+            "end": "2024-06-04",
+            "attendees": ["ada@example.com", "{{ trigger.lead }}"]
+        });
+        let context = json!({ "trigger": { "team": "Ops", "lead": "grace@example.com" } });
+        let event = build_event_payload(&params, &context, true).expect("event");
+        assert_eq!(event["summary"], "Offsite for Ops");
+        assert_eq!(event["start"], json!({ "date": "2024-06-03" }));
+        assert_eq!(event["attendees"][1]["email"], "grace@example.com");
+
+        let timed = json!({
+            "summary": "Sync",
+            "start": "2024-06-03T10:00:00",
+            "end": "2024-06-03T10:30:00",
+            "timeZone": "Europe/Berlin"
+        });
+        let event = build_event_payload(&timed, &Value::Null, true).expect("event");
+        assert_eq!(event["start"]["timeZone"], "Europe/Berlin");
+
+        let missing = json!({ "summary": "Sync", "start": "2024-06-03T10:00:00" });
+        assert_eq!(
+            build_event_payload(&missing, &Value::Null, true).unwrap_err(),
+            "End time is required"
+        );
+    }
+
+    #[test]
+    fn free_slots_skip_overlapping_busy_blocks() {
+        let busy = vec![
+            (at("2024-06-03T10:00:00Z"), at("2024-06-03T11:00:00Z")),
+            (at("2024-06-03T09:00:00Z"), at("2024-06-03T09:20:00Z")),
+            (at("2024-06-03T10:30:00Z"), at("2024-06-03T11:30:00Z")),
+        ];
+        let slots = find_free_slots(
+            at("2024-06-03T09:00:00Z"),
+            at("2024-06-03T13:00:00Z"),
+            &busy,
+            Duration::minutes(30),
+            10,
+        );
+        assert_eq!(
+            slots,
+            vec![
+                (at("2024-06-03T09:20:00Z"), at("2024-06-03T10:00:00Z")),
+                (at("2024-06-03T11:30:00Z"), at("2024-06-03T13:00:00Z")),
+            ]
+        );
+
+        let limited = find_free_slots(
+            at("2024-06-03T09:00:00Z"),
+            at("2024-06-03T13:00:00Z"),
+            &busy,
+            Duration::minutes(45),
+            1,
+        );
+        assert_eq!(
+            limited,
+            vec![(at("2024-06-03T11:30:00Z"), at("2024-06-03T13:00:00Z"))]
+        );
+    }
+
+    #[test]
+    fn busy_intervals_surface_calendar_errors() {
+        let response = json!({
+            "calendars": {
+                "primary": { "busy": [{ "start": "2024-06-03T10:00:00Z", "end": "2024-06-03T11:00:00Z" }] },
+                "team@example.com": { "errors": [{ "domain": "global", "reason": "notFound" }] }
+            }
+        });
+        let err = collect_busy_intervals(
+            &response,
+            &["primary".to_string(), "team@example.com".to_string()],
+        )
+        .unwrap_err();
+        assert!(err.contains("notFound"));
+
+        let busy = collect_busy_intervals(&response, &["primary".to_string()]).expect("busy");
+        assert_eq!(busy.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn find_free_slots_posts_free_busy_query_to_stub_server() {
+        let provider = FakeProvider::start().await;
+        provider.respond(
+            Method::POST,
+            "/calendar/freeBusy",
+            StatusCode::OK,
+            json!({
+                "calendars": {
+                    "primary": { "busy": [{ "start": "2024-06-03T09:00:00Z", "end": "2024-06-03T10:00:00Z" }] },
+                    "ada@example.com": { "busy": [{ "start": "2024-06-03T10:15:00Z", "end": "2024-06-03T11:00:00Z" }] }
+                }
+            }),
+        );
+        let (state, run, token_id) =
+            personal_connection_fixture(ConnectedOAuthProvider::Google, provider.base_urls());
+        let node = Node {
+            id: "calendar".into(),
+            kind: "action".into(),
+            data: json!({
+                "params": {
+                    "operation": "find_free_slots",
+                    "calendars": "primary, ada@example.com",
+                    "timeMin": "2024-06-03T09:00:00Z",
+                    "timeMax": "2024-06-03T12:00:00Z",
+                    "durationMinutes": 30,
+                    "connection": {
+                        "connectionScope": "personal",
+                        "connectionId": token_id.to_string()
+                    }
+                }
+            }),
+        };
+
+        let (output, _) = execute_google_calendar(&node, &Value::Null, &state, &run)
+            .await
+            .expect("free slots");
+
+        let requests = provider.requests_to(Method::POST, "/calendar/freeBusy");
+        assert_eq!(requests.len(), 1);
+        assert_eq!(
+            requests[0].header("authorization").as_deref(),
+            Some("Bearer access-token")
+        );
+        assert_eq!(
+            requests[0].json(),
+            json!({
+                "timeMin": "2024-06-03T09:00:00Z",
+                "timeMax": "2024-06-03T12:00:00Z",
+                "timeZone": "UTC",
+                "items": [{ "id": "primary" }, { "id": "ada@example.com" }]
+            })
+        );
+        assert_eq!(output["count"], 1);
+        assert_eq!(
+            output["firstSlot"],
+            json!({ "start": "2024-06-03T11:00:00Z", "end": "2024-06-03T12:00:00Z" })
+        );
+        assert_eq!(output["operation"], "find_free_slots");
+    }
+}
@@ -0,0 +1,378 @@
+use base64::Engine;
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+use crate::engine::graph::Node;
+use crate::engine::templating::templ_str;
+use crate::models::workflow_run::WorkflowRun;
+use crate::state::AppState;
+
+use super::google::resolve_google_session;
+use super::{read_limit, read_optional, read_required, resolve_connection_usage};
+
+const API_LABEL: &str = "Google Drive";
+const FOLDER_MIME_TYPE: &str = "application/vnd.google-apps.folder";
+const FILE_FIELDS: &str = "id,name,mimeType,parents,webViewLink,size,modifiedTime";
+/// Google only accepts simple multipart uploads up to 5 MB.
+const MAX_UPLOAD_BYTES: usize = 5 * 1024 * 1024;
+const DEFAULT_LIST_LIMIT: u32 = 50;
+const MAX_LIST_LIMIT: u32 = 100;
+
+pub(crate) async fn execute_google_drive(
+    node: &Node,
+    context: &Value,
+    state: &AppState,
+    run: &WorkflowRun,
+) -> Result<(Value, Option<String>), String> {
+    let params = node.data.get("params").cloned().unwrap_or(Value::Null);
+
+    let operation = params
+        .get("operation")
+        .and_then(|v| v.as_str())
+        .map(|s| s.trim().to_ascii_lowercase())
+        .unwrap_or_default();
+    if !matches!(
+        operation.as_str(),
+        "upload_file" | "create_folder" | "share_file" | "list_files"
+    ) {
+        return Err("Unsupported Google Drive operation".to_string());
+    }
+
+    let connection_usage = resolve_connection_usage(&params)?;
+    let base_url = state
+        .config
+        .provider_base_urls
+        .google_drive
+        .trim_end_matches('/');
+
+    let mut output = match operation.as_str() {
+        "upload_file" => {
+            let upload = FileUpload::from_params(&params, context)?;
+            let (content_type, body) = upload.multipart_body();
+            let session = resolve_google_session(connection_usage, state, run).await?;
+            let request = state
+                .http_client
+                .post(format!(
+                    "{}/files",
+                    state
+                        .config
+                        .provider_base_urls
+                        .google_drive_upload
+                        .trim_end_matches('/')
+                ))
+                .query(&[
+                    ("uploadType", "multipart"),
+                    ("fields", FILE_FIELDS),
+                    ("supportsAllDrives", "true"),
+                ])
+                .header(reqwest::header::CONTENT_TYPE, content_type)
+                .body(body);
+            let response = session.send(state, request, API_LABEL).await?;
+            session.with_output(file_summary(&response))
+        }
+        "create_folder" => {
+            let name = read_required(&params, "name", "Folder name", context)?;
+            let mut metadata = json!({ "name": name, "mimeType": FOLDER_MIME_TYPE });
+            if let Some(parent) = read_optional(&params, "parentFolderId", context) {
+                metadata["parents"] = json!([parent]);
+            }
+            let session = resolve_google_session(connection_usage, state, run).await?;
+            let request = state
+                .http_client
+                .post(format!("{}/files", base_url))
+                .query(&[("fields", FILE_FIELDS), ("supportsAllDrives", "true")])
+                .json(&metadata);
+            let response = session.send(state, request, API_LABEL).await?;
+            session.with_output(file_summary(&response))
+        }
+        "share_file" => {
+            let file_id = read_required(&params, "fileId", "File ID", context)?;
+            let permission = build_permission(&params, context)?;
+            let notify = params
+                .get("sendNotification")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(true);
+            let session = resolve_google_session(connection_usage, state, run).await?;
+            let mut request = state
+                .http_client
+                .post(format!(
+                    "{}/files/{}/permissions",
+                    base_url,
+                    urlencoding::encode(&file_id)
+                ))
+                .query(&[
+                    ("fields", "id,role,type,emailAddress,domain"),
+                    ("supportsAllDrives", "true"),
+                ]);
+            // Drive rejects the notification flag for domain and anyone grants.
+            if matches!(permission["type"].as_str(), Some("user" | "group")) {
+                request = request.query(&[("sendNotificationEmail", notify.to_string())]);
+            }
+            let response = session
+                .send(state, request.json(&permission), API_LABEL)
+                .await?;
+            session.with_output(json!({
+                "fileId": file_id,
+                "permissionId": response.get("id").cloned().unwrap_or(Value::Null),
+                "role": response.get("role").cloned().unwrap_or(Value::Null),
+                "type": response.get("type").cloned().unwrap_or(Value::Null),
+                "emailAddress": response.get("emailAddress").cloned().unwrap_or(Value::Null),
+            }))
+        }
+        _ => {
+            let folder_id = read_required(&params, "folderId", "Folder ID", context)?;
+            let limit = read_limit(&params, "limit", context)
+                .unwrap_or(DEFAULT_LIST_LIMIT)
+                .min(MAX_LIST_LIMIT);
+            let query = format!(
+                "'{}' in parents and trashed = false",
+                folder_id.replace('\\', "\\\\").replace('\'', "\\'")
+            );
+            let session = resolve_google_session(connection_usage, state, run).await?;
+            let request = state
+                .http_client
+                .get(format!("{}/files", base_url))
+                .query(&[
+                    ("q", query.as_str()),
+                    ("pageSize", &limit.to_string()),
+                    ("orderBy", "modifiedTime desc"),
+                    ("fields", &format!("files({FILE_FIELDS}),nextPageToken")),
+                    ("supportsAllDrives", "true"),
+                    ("includeItemsFromAllDrives", "true"),
+                ]);
+            let response = session.send(state, request, API_LABEL).await?;
+            let files: Vec<Value> = response
+                .get("files")
+                .and_then(|v| v.as_array())
+                .map(|items| items.iter().map(file_summary).collect())
+                .unwrap_or_default();
+            session.with_output(json!({
+                "folderId": folder_id,
+                "count": files.len(),
+                "files": files,
+                "hasMore": response
+                    .get("nextPageToken")
+                    .and_then(|v| v.as_str())
+                    .is_some(),
+            }))
+        }
+    };
+
+    output.insert("service".to_string(), Value::String(API_LABEL.to_string()));
+    output.insert("operation".to_string(), Value::String(operation));
+    Ok((Value::Object(output), None))
+}
+
+struct FileUpload {
+    metadata: Value,
+    mime_type: String,
+    content: Vec<u8>,
+}
+
+impl FileUpload {
+    /// `content` is templated from the run context; set `contentEncoding` to
+    /// `base64` when an upstream node produced binary data.
+    fn from_params(params: &Value, context: &Value) -> Result<Self, String> {
+        let file_name = read_required(params, "fileName", "File name", context)?;
+        let raw = params
+            .get("content")
+            .and_then(|v| v.as_str())
+            .map(|s| templ_str(s, context))
+            .unwrap_or_default();
+
+        let encoding = read_optional(params, "contentEncoding", context)
+            .map(|s| s.to_ascii_lowercase())
+            .unwrap_or_else(|| "text".to_string());
+        let (content, default_mime) = match encoding.as_str() {
+            "text" => (raw.into_bytes(), "text/plain"),
+            "base64" => (
+                base64::engine::general_purpose::STANDARD
+                    .decode(raw.trim())
+                    .map_err(|_| "File content is not valid base64".to_string())?,
+                "application/octet-stream",
+            ),
+            _ => return Err("Content encoding must be text or base64".to_string()),
+        };
+        if content.len() > MAX_UPLOAD_BYTES {
+            return Err("File content exceeds the 5 MB upload limit".to_string());
+        }
+
+        let mime_type =
+            read_optional(params, "mimeType", context).unwrap_or_else(|| default_mime.to_string());
+        let mut metadata = json!({ "name": file_name, "mimeType": mime_type });
+        if let Some(parent) = read_optional(params, "parentFolderId", context) {
+            metadata["parents"] = json!([parent]);
+        }
+
+        Ok(Self {
+            metadata,
+            mime_type,
+            content,
+        })
+    }
+
+    /// Returns the `Content-Type` header value and the `multipart/related`
+    /// body. The boundary is random per upload so file content cannot end a
+    /// part early.
+    fn multipart_body(&self) -> (String, Vec<u8>) {
+        let boundary = format!("dsentr-{}", Uuid::new_v4().simple());
+        let mut body = Vec::with_capacity(self.content.len() + 512);
+        body.extend_from_slice(
+            format!(
+                "--{boundary}\r\nContent-Type: application/json; charset=UTF-8\r\n\r\n{}\r\n--{boundary}\r\nContent-Type: {}\r\n\r\n",
+                self.metadata, self.mime_type
+            )
+            .as_bytes(),
+        );
+        body.extend_from_slice(&self.content);
+        body.extend_from_slice(format!("\r\n--{boundary}--").as_bytes());
+        (format!("multipart/related; boundary={boundary}"), body)
+    }
+}
+
+fn build_permission(params: &Value, context: &Value) -> Result<Value, String> {
+    let role = read_optional(params, "role", context).unwrap_or_else(|| "reader".to_string());
+    if !matches!(role.as_str(), "reader" | "commenter" | "writer") {
+        return Err("Role must be reader, commenter or writer".to_string());
+    }
+    let grantee = read_optional(params, "type", context).unwrap_or_else(|| "user".to_string());
+
+    let mut permission = json!({ "role": role, "type": grantee });
+    match grantee.as_str() {
+        "user" | "group" => {
+            let email = read_required(params, "emailAddress", "Email address", context)?;
+            if !email.contains('@') {
+                return Err(format!("Invalid email address `{email}`"));
+            }
+            permission["emailAddress"] = Value::String(email);
+        }
+        "domain" => {
+            permission["domain"] =
+                Value::String(read_required(params, "domain", "Domain", context)?);
+        }
+        "anyone" => {}
+        _ => return Err("Share type must be user, group, domain or anyone".to_string()),
+    }
+    Ok(permission)
+}
+
+fn file_summary(file: &Value) -> Value {
+    json!({
+        "fileId": file.get("id").cloned().unwrap_or(Value::Null),
+        "name": file.get("name").cloned().unwrap_or(Value::Null),
+        "mimeType": file.get("mimeType").cloned().unwrap_or(Value::Null),
+        "parents": file.get("parents").cloned().unwrap_or(Value::Null),
+        "webViewLink": file.get("webViewLink").cloned().unwrap_or(Value::Null),
+        "size": file.get("size").cloned().unwrap_or(Value::Null),
+        "modifiedTime": file.get("modifiedTime").cloned().unwrap_or(Value::Null),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::oauth_token::ConnectedOAuthProvider;
+    use crate::test_support::{personal_connection_fixture, FakeProvider};
+    use axum::http::{Method, StatusCode};
+
+    #[test]
+    fn upload_decodes_base64_content_into_multipart_body() {
+        let params = json!({
+            "fileName": "{{ trigger.name }}.bin",
+            "content": "{{ trigger.data }}",
+            "contentEncoding": "base64",
+            "parentFolderId": "folder-1"
+        });
+        let context = json!({ "trigger": { "name": "report", "data": "AAEC" } });
+        let upload = FileUpload::from_params(&params, &context).expect("upload");
+        assert_eq!(upload.content, vec![0, 1, 2]);
+        assert_eq!(upload.metadata["name"], "report.bin");
+        assert_eq!(upload.metadata["parents"], json!(["folder-1"]));
+
+        let (content_type, body) = upload.multipart_body();
+        let boundary = content_type
+            .strip_prefix("multipart/related; boundary=")
+            .expect("boundary");
+        let text = String::from_utf8_lossy(&body);
+        assert!(text.starts_with(&format!("--{boundary}\r\n")));
+        assert!(text.contains("Content-Type: application/octet-stream\r\n\r\n"));
+        assert!(text.ends_with(&format!("\r\n--{boundary}--")));
+        assert_ne!(upload.multipart_body().0, content_type);
+
+        let invalid = json!({ "fileName": "x", "content": "***", "contentEncoding": "base64" });
+        assert!(FileUpload::from_params(&invalid, &Value::Null).is_err());
+    }
+
+    #[test]
+    fn permission_requires_grantee_details() {
+        let params = json!({ "fileId": "f1", "role": "writer", "emailAddress": "ada@example.com" });
+        let permission = build_permission(&params, &Value::Null).expect("permission");
+        assert_eq!(
+            permission,
+            json!({ "role": "writer", "type": "user", "emailAddress": "ada@example.com" })
+        );
+
+        let domain = json!({ "type": "domain" });
+        assert_eq!(
+            build_permission(&domain, &Value::Null).unwrap_err(),
+            "Domain is required"
+        );
+        let owner = json!({ "role": "owner", "type": "anyone" });
+        assert!(build_permission(&owner, &Value::Null).is_err());
+    }
+
+    #[tokio::test]
+    async fn list_files_queries_folder_on_stub_server() {
+        let provider = FakeProvider::start().await;
+        provider.respond(
+            Method::GET,
+            "/drive/files",
+            StatusCode::OK,
+            json!({
+                "files": [
+                    { "id": "f2", "name": "b.txt", "mimeType": "text/plain" },
+                    { "id": "f1", "name": "a.txt", "mimeType": "text/plain" }
+                ],
+                "nextPageToken": "next"
+            }),
+        );
+        let (state, run, token_id) =
+            personal_connection_fixture(ConnectedOAuthProvider::Google, provider.base_urls());
+        let node = Node {
+            id: "drive".into(),
+            kind: "action".into(),
+            data: json!({
+                "params": {
+                    "operation": "list_files",
+                    "folderId": "folder-1",
+                    "limit": 2,
+                    "connection": {
+                        "connectionScope": "personal",
+                        "connectionId": token_id.to_string()
+                    }
+                }
+            }),
+        };
+
+        let (output, _) = execute_google_drive(&node, &Value::Null, &state, &run)
+            .await
+            .expect("list files");
+
+        let requests = provider.requests_to(Method::GET, "/drive/files");
+        assert_eq!(requests.len(), 1);
+        assert_eq!(
+            requests[0].header("authorization").as_deref(),
+            Some("Bearer access-token")
+        );
+        assert_eq!(
+            requests[0].query_param("q").as_deref(),
+            Some("'folder-1' in parents and trashed = false")
+        );
+        assert_eq!(requests[0].query_param("pageSize").as_deref(), Some("2"));
+        assert_eq!(output["count"], 2);
+        assert_eq!(output["files"][0]["fileId"], "f2");
+        assert_eq!(output["hasMore"], true);
+        assert_eq!(output["connectionScope"], "user");
+        assert_eq!(output["accountEmail"], "user@example.com");
+    }
+}
@@ -0,0 +1,405 @@
+//! Authentication schemes for the HTTP action beyond inline basic/bearer
+//! credentials: API keys sent in a header or query parameter, OAuth2
+//! client-credentials tokens cached per workspace until they expire, and
+//! access tokens from a connection to a workspace-defined OAuth2 provider.
+//!
+//! Secret parameters (`clientSecret`, `apiKey`) are stored as
+//! `group:service:name` references that the executor hydrates from the
+//! encrypted secret store before the node runs.
+
+use std::time::{Duration, Instant};
+
+use dashmap::DashMap;
+use once_cell::sync::Lazy;
+use reqwest::header::{HeaderName, HeaderValue};
+use serde::Deserialize;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use crate::engine::templating::templ_str;
+use crate::models::oauth_token::ConnectedOAuthProvider;
+use crate::models::workflow_run::WorkflowRun;
+use crate::services::oauth::account_service::OAuthAccountError;
+use crate::services::oauth::workspace_service::WorkspaceOAuthError;
+use crate::state::AppState;
+
+use super::{ensure_run_membership, ensure_workspace_plan, NodeConnectionUsage};
+
+/// Tokens are refreshed this long before the reported expiry so a request
+/// never goes out with a token that lapses in flight.
+const TOKEN_EXPIRY_SKEW: Duration = Duration::from_secs(30);
+
+static CLIENT_CREDENTIALS_TOKENS: Lazy<DashMap<TokenCacheKey, CachedToken>> =
+    Lazy::new(DashMap::new);
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+struct TokenCacheKey {
+    workspace: Uuid,
+    token_url: String,
+    client_id: String,
+    scopes: String,
+    audience: String,
+    secret_fingerprint: String,
+}
+
+#[derive(Clone, Debug)]
+struct CachedToken {
+    access_token: String,
+    expires_at: Instant,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    #[serde(default)]
+    expires_in: Option<u64>,
+}
+
+#[derive(Clone, Debug)]
+pub(super) enum RequestAuth {
+    None,
+    Basic {
+        username: String,
+        password: String,
+    },
+    Bearer(String),
+    Header {
+        name: HeaderName,
+        value: HeaderValue,
+    },
+    Query {
+        name: String,
+        value: String,
+    },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ClientAuthMethod {
+    Basic,
+    Body,
+}
+
+#[derive(Clone, Debug)]
+pub(super) struct ClientCredentials {
+    pub(super) token_url: String,
+    client_id: String,
+    client_secret: String,
+    scopes: String,
+    audience: Option<String>,
+    auth_method: ClientAuthMethod,
+}
+
+fn param_str(params: &Value, key: &str, context: &Value) -> Option<String> {
+    params
+        .get(key)
+        .and_then(|v| v.as_str())
+        .map(|s| templ_str(s, context).trim().to_string())
+        .filter(|s| !s.is_empty())
+}
+
+fn scopes_param(params: &Value, context: &Value) -> String {
+    let raw: Vec<String> = match params.get("scopes") {
+        Some(Value::Array(items)) => items
+            .iter()
+            .filter_map(|v| v.as_str())
+            .map(|s| templ_str(s, context))
+            .collect(),
+        Some(Value::String(s)) => vec![templ_str(s, context)],
+        _ => Vec::new(),
+    };
+    raw.iter()
+        .flat_map(|s| s.split([',', ' ']))
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+impl ClientCredentials {
+    pub(super) fn from_params(params: &Value, context: &Value) -> Result<Self, String> {
+        let token_url = param_str(params, "tokenUrl", context)
+            .ok_or_else(|| "OAuth2 token URL is required".to_string())?;
+        let client_id = param_str(params, "clientId", context)
+            .ok_or_else(|| "OAuth2 client ID is required".to_string())?;
+        let client_secret = param_str(params, "clientSecret", context)
+            .ok_or_else(|| "OAuth2 client secret is required".to_string())?;
+        let auth_method = match params
+            .get("clientAuthMethod")
+            .and_then(|v| v.as_str())
+            .unwrap_or("basic")
+            .trim()
+            .to_ascii_lowercase()
+            .as_str()
+        {
+            "" | "basic" => ClientAuthMethod::Basic,
+            "body" | "post" => ClientAuthMethod::Body,
+            other => return Err(format!("Unsupported OAuth2 client auth method: {other}")),
+        };
+        Ok(Self {
+            token_url,
+            client_id,
+            client_secret,
+            scopes: scopes_param(params, context),
+            audience: param_str(params, "audience", context),
+            auth_method,
+        })
+    }
+
+    fn cache_key(&self, run: &WorkflowRun) -> TokenCacheKey {
+        TokenCacheKey {
+            workspace: run.workspace_id.unwrap_or(run.user_id),
+            token_url: self.token_url.clone(),
+            client_id: self.client_id.clone(),
+            scopes: self.scopes.clone(),
+            audience: self.audience.clone().unwrap_or_default(),
+            // Rotating the secret must not keep serving tokens minted with
+            // the old one.
+            secret_fingerprint: hex::encode(Sha256::digest(self.client_secret.as_bytes())),
+        }
+    }
+
+    /// Returns a cached token for the run's workspace, requesting a new one
+    /// from the token endpoint when none is cached or it is about to expire.
+    pub(super) async fn access_token(
+        &self,
+        client: &reqwest::Client,
+        run: &WorkflowRun,
+    ) -> Result<String, String> {
+        let key = self.cache_key(run);
+        if let Some(cached) = CLIENT_CREDENTIALS_TOKENS.get(&key) {
+            if cached.expires_at > Instant::now() {
+                return Ok(cached.access_token.clone());
+            }
+        }
+
+        let mut form = vec![("grant_type", "client_credentials".to_string())];
+        if !self.scopes.is_empty() {
+            form.push(("scope", self.scopes.clone()));
+        }
+        if let Some(audience) = &self.audience {
+            form.push(("audience", audience.clone()));
+        }
+        let mut request = client.post(&self.token_url);
+        match self.auth_method {
+            ClientAuthMethod::Basic => {
+                request = request.basic_auth(&self.client_id, Some(&self.client_secret));
+            }
+            ClientAuthMethod::Body => {
+                form.push(("client_id", self.client_id.clone()));
+                form.push(("client_secret", self.client_secret.clone()));
+            }
+        }
+
+        let response = request
+            .form(&form)
+            .send()
+            .await
+            .map_err(|e| format!("OAuth2 token request failed: {}", e.without_url()))?;
+        let status = response.status();
+        let body: Value = response
+            .json()
+            .await
+            .map_err(|e| format!("OAuth2 token endpoint returned an invalid response: {e}"))?;
+        if !status.is_success() {
+            let reason = body
+                .get("error_description")
+                .or_else(|| body.get("error"))
+                .and_then(|v| v.as_str())
+                .unwrap_or("unknown error");
+            return Err(format!(
+                "OAuth2 token request failed with status {}: {}",
+                status.as_u16(),
+                reason
+            ));
+        }
+        let token: TokenResponse = serde_json::from_value(body)
+            .map_err(|e| format!("OAuth2 token endpoint returned an invalid response: {e}"))?;
+
+        if let Some(lifetime) = token
+            .expires_in
+            .map(Duration::from_secs)
+            .and_then(|lifetime| lifetime.checked_sub(TOKEN_EXPIRY_SKEW))
+            .filter(|lifetime| !lifetime.is_zero())
+        {
+            cache_token(
+                &CLIENT_CREDENTIALS_TOKENS,
+                key,
+                CachedToken {
+                    access_token: token.access_token.clone(),
+                    expires_at: Instant::now() + lifetime,
+                },
+            );
+        }
+        Ok(token.access_token)
+    }
+
+    /// Drops the cached token, e.g. after the API rejected it with a 401.
+    pub(super) fn invalidate(&self, run: &WorkflowRun) {
+        CLIENT_CREDENTIALS_TOKENS.remove(&self.cache_key(run));
+    }
+}
+
+/// Stores `token` under `key`, first evicting every expired entry so keys that
+/// are never requested again do not stay cached for the life of the process.
+fn cache_token(
+    cache: &DashMap<TokenCacheKey, CachedToken>,
+    key: TokenCacheKey,
+    token: CachedToken,
+) {
+    let now = Instant::now();
+    cache.retain(|_, cached| cached.expires_at > now);
+    cache.insert(key, token);
+}
+
+/// Builds the API key auth for `authType: "api_key"`: `apiKeyIn` selects a
+/// header (default `X-API-Key`) or a query parameter (default `api_key`).
+pub(super) fn api_key_auth(params: &Value, context: &Value) -> Result<RequestAuth, String> {
+    let key =
+        param_str(params, "apiKey", context).ok_or_else(|| "API key is required".to_string())?;
+    let location = params
+        .get("apiKeyIn")
+        .and_then(|v| v.as_str())
+        .unwrap_or("header")
+        .trim()
+        .to_ascii_lowercase();
+    match location.as_str() {
+        "" | "header" => {
+            let name =
+                param_str(params, "apiKeyName", context).unwrap_or_else(|| "X-API-Key".to_string());
+            let name = HeaderName::try_from(name.as_str())
+                .map_err(|_| format!("Invalid API key header name: {name}"))?;
+            let mut value = HeaderValue::from_str(&key)
+                .map_err(|_| "API key contains characters not allowed in a header".to_string())?;
+            value.set_sensitive(true);
+            Ok(RequestAuth::Header { name, value })
+        }
+        "query" => Ok(RequestAuth::Query {
+            name: param_str(params, "apiKeyName", context).unwrap_or_else(|| "api_key".to_string()),
+            value: key,
+        }),
+        other => Err(format!("Unsupported API key location: {other}")),
+    }
+}
+
+fn map_connection_error(err: OAuthAccountError) -> String {
+    match err {
+        OAuthAccountError::NotFound => "OAuth connection not found".to_string(),
+        OAuthAccountError::TokenRevoked { .. } => {
+            "The OAuth connection was revoked. Reconnect in Settings -> Integrations.".to_string()
+        }
+        other => format!("OAuth connection error: {other}"),
+    }
+}
+
+fn map_workspace_connection_error(err: WorkspaceOAuthError) -> String {
+    match err {
+        WorkspaceOAuthError::Forbidden => {
+            "You no longer have access to this workspace connection.".to_string()
+        }
+        WorkspaceOAuthError::NotFound => "Workspace OAuth connection not found.".to_string(),
+        WorkspaceOAuthError::OAuth(inner) => map_connection_error(inner),
+        other => format!("Failed to load workspace connection: {other}"),
+    }
+}
+
+/// Resolves `authType: "oauth2_connection"`: the `connection` parameter
+/// selects a personal or workspace connection made through a custom OAuth2
+/// provider, refreshed through the OAuth services when it is about to expire.
+pub(super) async fn connection_access_token(
+    params: &Value,
+    state: &AppState,
+    run: &WorkflowRun,
+) -> Result<String, String> {
+    let (provider, access_token) = match super::resolve_connection_usage(params)? {
+        NodeConnectionUsage::Workspace(info) => {
+            let workspace_id = run.workspace_id.ok_or_else(|| {
+                "This workflow is not associated with a workspace. Switch to a personal OAuth connection.".to_string()
+            })?;
+
+            ensure_run_membership(state, workspace_id, run.user_id).await?;
+            ensure_workspace_plan(state, workspace_id).await?;
+
+            let connection = state
+                .workspace_oauth
+                .ensure_valid_workspace_token(info.connection_id)
+                .await
+                .map_err(map_workspace_connection_error)?;
+            if connection.workspace_id != workspace_id {
+                return Err(
+                    "The selected OAuth connection belongs to another workspace".to_string()
+                );
+            }
+            (connection.provider, connection.access_token)
+        }
+        NodeConnectionUsage::User(info) => {
+            let connection_id = info
+                .connection_id
+                .as_deref()
+                .and_then(|raw| Uuid::parse_str(raw).ok())
+                .ok_or_else(|| {
+                    "Select an OAuth connection for the request's authentication.".to_string()
+                })?;
+            let token = state
+                .oauth_accounts
+                .ensure_valid_access_token_for_connection(run.user_id, connection_id)
+                .await
+                .map_err(map_connection_error)?;
+            (token.provider, token.access_token)
+        }
+    };
+
+    if provider != ConnectedOAuthProvider::Custom {
+        return Err("Selected connection is not a custom OAuth2 connection".to_string());
+    }
+    Ok(access_token)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(client_id: &str) -> TokenCacheKey {
+        TokenCacheKey {
+            workspace: Uuid::nil(),
+            token_url: "https://auth.example.com/token".into(),
+            client_id: client_id.into(),
+            scopes: String::new(),
+            audience: String::new(),
+            secret_fingerprint: String::new(),
+        }
+    }
+
+    #[test]
+    fn caching_a_token_evicts_expired_entries() {
+        let cache = DashMap::new();
+        let now = Instant::now();
+        cache.insert(
+            key("expired"),
+            CachedToken {
+                access_token: "old".into(),
+                expires_at: now,
+            },
+        );
+        cache.insert(
+            key("live"),
+            CachedToken {
+                access_token: "live".into(),
+                expires_at: now + Duration::from_secs(60),
+            },
+        );
+
+        cache_token(
+            &cache,
+            key("new"),
+            CachedToken {
+                access_token: "new".into(),
+                expires_at: now + Duration::from_secs(60),
+            },
+        );
+
+        assert!(!cache.contains_key(&key("expired")));
+        assert!(cache.contains_key(&key("live")));
+        assert!(cache.contains_key(&key("new")));
+    }
+}
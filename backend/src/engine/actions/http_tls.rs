@@ -0,0 +1,73 @@
+//! Client certificates and private CA bundles for the HTTP action. The PEM
+//! blobs usually arrive as `group:service:name` secret references hydrated by
+//! the executor, so they never sit in the workflow definition itself.
+
+use reqwest::{Certificate, ClientBuilder, Identity};
+use serde_json::Value;
+
+#[derive(Clone, Debug)]
+pub(super) struct TlsOptions {
+    client_certificate: Option<String>,
+    client_key: Option<String>,
+    ca_bundle: Option<String>,
+    ca_bundle_only: bool,
+}
+
+fn pem_param(params: &Value, key: &str) -> Option<String> {
+    params
+        .get(key)
+        .and_then(|v| v.as_str())
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+}
+
+impl TlsOptions {
+    /// Reads `clientCertificate`/`clientKey` (PEM, PKCS#8 key), `caBundle`
+    /// (one or more PEM certificates) and `caBundleOnly`, which stops
+    /// trusting the built-in roots. Returns `None` when nothing is set.
+    pub(super) fn from_params(params: &Value) -> Result<Option<Self>, String> {
+        let options = Self {
+            client_certificate: pem_param(params, "clientCertificate"),
+            client_key: pem_param(params, "clientKey"),
+            ca_bundle: pem_param(params, "caBundle"),
+            ca_bundle_only: params
+                .get("caBundleOnly")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false),
+        };
+        if options.client_certificate.is_some() != options.client_key.is_some() {
+            return Err("Client certificate and client key must be provided together".to_string());
+        }
+        if options.ca_bundle_only && options.ca_bundle.is_none() {
+            return Err("A CA bundle is required when only the CA bundle is trusted".to_string());
+        }
+        if options.client_certificate.is_none() && options.ca_bundle.is_none() {
+            return Ok(None);
+        }
+        Ok(Some(options))
+    }
+
+    pub(super) fn apply(&self, builder: ClientBuilder) -> Result<ClientBuilder, String> {
+        let mut builder = builder;
+        if let (Some(cert), Some(key)) = (&self.client_certificate, &self.client_key) {
+            let identity = Identity::from_pkcs8_pem(cert.as_bytes(), key.as_bytes())
+                .map_err(|e| format!("Invalid client certificate or key: {e}"))?;
+            builder = builder.identity(identity);
+        }
+        if let Some(bundle) = &self.ca_bundle {
+            let certificates = Certificate::from_pem_bundle(bundle.as_bytes())
+                .map_err(|e| format!("Invalid CA bundle: {e}"))?;
+            if certificates.is_empty() {
+                return Err("CA bundle does not contain any certificates".to_string());
+            }
+            for certificate in certificates {
+                builder = builder.add_root_certificate(certificate);
+            }
+            if self.ca_bundle_only {
+                builder = builder.tls_built_in_root_certs(false);
+            }
+        }
+        Ok(builder)
+    }
+}
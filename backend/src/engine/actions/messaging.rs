@@ -3,6 +3,8 @@ use crate::engine::graph::Node;
 use crate::engine::templating::templ_str;
 use crate::models::oauth_token::ConnectedOAuthProvider;
 use crate::models::workflow_run::WorkflowRun;
+use crate::services::artifacts::load_artifact_param;
+use crate::services::microsoft::{self, MicrosoftGraphError};
 use crate::services::oauth::account_service::{is_revocation_signal, OAuthAccountError};
 use crate::services::oauth::workspace_service::WorkspaceOAuthError;
 use crate::state::AppState;
@@ -20,8 +22,6 @@ use uuid::Uuid;
 
 use super::{ensure_run_membership, resolve_connection_usage, NodeConnectionUsage};
 
-const DEFAULT_MICROSOFT_GRAPH_BASE_URL: &str = "https://graph.microsoft.com/v1.0";
-
 #[inline]
 fn gating_enabled() -> bool {
     #[cfg(test)]
@@ -540,53 +540,234 @@ async fn send_slack(
         }
     };
 
-    let channel_raw = extract_required_str(params, "channel", "Slack channel")?;
-    let message_raw = extract_required_str(params, "message", "Message")?;
+    let operation = params
+        .get("operation")
+        .and_then(|v| v.as_str())
+        .map(|s| s.trim().to_ascii_lowercase())
+        .filter(|s| !s.is_empty())
+        .unwrap_or_else(|| "send_message".to_string());
+
+    let base = state.config.provider_base_urls.slack.clone();
+
+    let mut session = SlackSession {
+        state,
+        base,
+        access_token: token,
+        refreshed_once: false,
+        connection_context,
+    };
+
+    let (status, mut output) = match operation.as_str() {
+        "send_message" | "post_message" => {
+            let channel = templated_required(params, "channel", "Slack channel", context)?;
+            let mut payload = build_slack_message_payload(params, context)?;
+            payload.insert("channel".to_string(), Value::String(channel));
+            apply_thread_options(&mut payload, params, context);
+
+            let (status, parsed) = session
+                .call(
+                    "chat.postMessage",
+                    SlackRequest::Json(Value::Object(payload)),
+                )
+                .await?;
+            let mut output = json!({ "sent": true });
+            copy_message_reference(&mut output, &parsed);
+            (status, output)
+        }
+        "send_direct_message" => {
+            let user_id = resolve_slack_user_id(&mut session, params, context).await?;
+            let (_, opened) = session
+                .call(
+                    "conversations.open",
+                    SlackRequest::Json(json!({ "users": user_id })),
+                )
+                .await?;
+            let channel = opened
+                .get("channel")
+                .and_then(|v| v.get("id"))
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string())
+                .ok_or_else(|| "Slack did not return a direct message channel".to_string())?;
 
-    let channel = templ_str(channel_raw, context);
-    if channel.trim().is_empty() {
-        return Err("Slack channel is required".to_string());
+            let mut payload = build_slack_message_payload(params, context)?;
+            payload.insert("channel".to_string(), Value::String(channel));
+
+            let (status, parsed) = session
+                .call(
+                    "chat.postMessage",
+                    SlackRequest::Json(Value::Object(payload)),
+                )
+                .await?;
+            let mut output = json!({ "sent": true, "userId": user_id });
+            copy_message_reference(&mut output, &parsed);
+            (status, output)
+        }
+        "update_message" => {
+            let channel = templated_required(params, "channel", "Slack channel", context)?;
+            let ts = templated_required(params, "messageTs", "Message timestamp", context)?;
+            let mut payload = build_slack_message_payload(params, context)?;
+            payload.insert("channel".to_string(), Value::String(channel));
+            payload.insert("ts".to_string(), Value::String(ts));
+
+            let (status, parsed) = session
+                .call("chat.update", SlackRequest::Json(Value::Object(payload)))
+                .await?;
+            let mut output = json!({ "updated": true });
+            copy_message_reference(&mut output, &parsed);
+            (status, output)
+        }
+        "delete_message" => {
+            let channel = templated_required(params, "channel", "Slack channel", context)?;
+            let ts = templated_required(params, "messageTs", "Message timestamp", context)?;
+
+            let (status, parsed) = session
+                .call(
+                    "chat.delete",
+                    SlackRequest::Json(json!({ "channel": channel, "ts": ts })),
+                )
+                .await?;
+            let mut output = json!({ "deleted": true });
+            copy_message_reference(&mut output, &parsed);
+            (status, output)
+        }
+        "add_reaction" => {
+            let channel = templated_required(params, "channel", "Slack channel", context)?;
+            let ts = templated_required(params, "messageTs", "Message timestamp", context)?;
+            let reaction = templated_required(params, "reaction", "Reaction", context)?;
+            let reaction = reaction.trim().trim_matches(':').to_string();
+            if reaction.is_empty() {
+                return Err("Reaction is required".to_string());
+            }
+
+            let (status, _) = session
+                .call(
+                    "reactions.add",
+                    SlackRequest::Json(json!({
+                        "channel": channel,
+                        "timestamp": ts,
+                        "name": reaction,
+                    })),
+                )
+                .await?;
+            let mut output = json!({ "reacted": true, "reaction": reaction });
+            set_message_reference(&mut output, &ts, &channel);
+            (status, output)
+        }
+        "upload_file" => upload_slack_file(&mut session, params, context, run).await?,
+        "lookup_user_by_email" => {
+            let email = templated_required(params, "email", "Email", context)?;
+            let (status, parsed) = session
+                .call(
+                    "users.lookupByEmail",
+                    SlackRequest::Form(vec![("email", email.trim().to_string())]),
+                )
+                .await?;
+            let user = parsed.get("user").cloned().unwrap_or(Value::Null);
+            let user_id = user
+                .get("id")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| "Slack did not return a user id".to_string())?;
+            let output = json!({
+                "found": true,
+                "userId": user_id,
+                "name": user.get("name").cloned().unwrap_or(Value::Null),
+                "realName": user.get("real_name").cloned().unwrap_or(Value::Null),
+                "teamId": user.get("team_id").cloned().unwrap_or(Value::Null),
+            });
+            (status, output)
+        }
+        other => return Err(format!("Unsupported Slack operation: {}", other)),
+    };
+
+    output["service"] = Value::String("Slack".to_string());
+    output["platform"] = Value::String("Slack".to_string());
+    output["operation"] = Value::String(operation);
+    output["status"] = json!(status.as_u16());
+
+    if let Some(email) = token_email {
+        output["oauthAccountEmail"] = Value::String(email);
     }
 
-    let message = templ_str(message_raw, context);
-    if message.trim().is_empty() {
-        return Err("Message is required".to_string());
+    if output_connection_scope.is_none() || output_connection_id.is_none() {
+        if let Some(context) = &session.connection_context {
+            match context {
+                SlackConnectionContext::Personal { connection_id, .. } => {
+                    output_connection_scope = Some("user".to_string());
+                    output_connection_id = Some(*connection_id);
+                }
+                SlackConnectionContext::Workspace { connection_id, .. } => {
+                    output_connection_scope = Some("workspace".to_string());
+                    output_connection_id = Some(*connection_id);
+                }
+            }
+        }
+    }
+
+    if let Some(scope) = output_connection_scope {
+        output["connectionScope"] = Value::String(scope);
+    }
+    if let Some(connection_id) = output_connection_id {
+        output["connectionId"] = Value::String(connection_id.to_string());
     }
 
-    let base = std::env::var("SLACK_API_BASE")
-        .ok()
-        .map(|v| v.trim().to_string())
-        .filter(|v| !v.is_empty())
-        .unwrap_or_else(|| "https://slack.com/api".to_string());
-    let url = format!("{}/chat.postMessage", base.trim_end_matches('/'));
+    Ok((output, None))
+}
 
-    let mut access_token = token;
-    let mut refreshed_once = false;
+/// Maximum number of Block Kit blocks Slack accepts in a single message.
+const SLACK_MAX_BLOCKS: usize = 50;
 
-    let (status, parsed) = loop {
-        let response = state
-            .http_client
-            .post(&url)
-            .bearer_auth(&access_token)
-            .json(&json!({ "channel": channel, "text": message }))
-            .send()
-            .await
-            .map_err(|e| format!("Slack request failed: {e}"))?;
+enum SlackRequest {
+    Json(Value),
+    Form(Vec<(&'static str, String)>),
+}
 
-        let status = response.status();
-        let body_text = response
-            .text()
-            .await
-            .map_err(|e| format!("Slack response read failed: {e}"))?;
-        let parsed: Option<Value> = serde_json::from_str(&body_text).ok();
+/// Holds the resolved Slack token for a single node execution so follow-up
+/// API calls (lookups, uploads) share the same refresh/revocation handling.
+struct SlackSession<'a> {
+    state: &'a AppState,
+    base: String,
+    access_token: String,
+    refreshed_once: bool,
+    connection_context: Option<SlackConnectionContext>,
+}
 
-        let is_ok = parsed
-            .as_ref()
-            .and_then(|v| v.get("ok"))
-            .and_then(|v| v.as_bool())
-            .unwrap_or_else(|| status.is_success());
+impl SlackSession<'_> {
+    async fn call(
+        &mut self,
+        method: &str,
+        request: SlackRequest,
+    ) -> Result<(reqwest::StatusCode, Value), String> {
+        let url = format!("{}/{}", self.base.trim_end_matches('/'), method);
+        let state = self.state;
+
+        loop {
+            let builder = state.http_client.post(&url).bearer_auth(&self.access_token);
+            let builder = match &request {
+                SlackRequest::Json(body) => builder.json(body),
+                SlackRequest::Form(fields) => builder.form(fields),
+            };
+            let response = builder
+                .send()
+                .await
+                .map_err(|e| format!("Slack request failed: {e}"))?;
+
+            let status = response.status();
+            let body_text = response
+                .text()
+                .await
+                .map_err(|e| format!("Slack response read failed: {e}"))?;
+            let parsed: Option<Value> = serde_json::from_str(&body_text).ok();
+
+            let is_ok = parsed
+                .as_ref()
+                .and_then(|v| v.get("ok"))
+                .and_then(|v| v.as_bool())
+                .unwrap_or_else(|| status.is_success());
+
+            if status.is_success() && is_ok {
+                return Ok((status, parsed.unwrap_or(Value::Null)));
+            }
 
-        if !status.is_success() || !is_ok {
             let slack_error = parsed
                 .as_ref()
                 .and_then(|v| v.get("error"))
@@ -594,64 +775,43 @@ async fn send_slack(
                 .map(|s| s.trim().to_string());
 
             if slack_error.as_deref() == Some("token_expired") {
-                if refreshed_once {
-                    if let Some(context) = &connection_context {
-                        let connection_id = match context {
-                            SlackConnectionContext::Personal { connection_id, .. } => {
-                                *connection_id
-                            }
-                            SlackConnectionContext::Workspace { connection_id, .. } => {
-                                *connection_id
-                            }
-                        };
-                        return Err(slack_auth_expired_error(connection_id));
-                    }
+                let Some(context) = &self.connection_context else {
                     return Err("Slack token expired".to_string());
+                };
+                let connection_id = context.connection_id();
+                if self.refreshed_once {
+                    return Err(slack_auth_expired_error(connection_id));
                 }
 
-                if let Some(context) = &connection_context {
-                    let refresh_result: Result<(String, Uuid), ()> = match context {
-                        SlackConnectionContext::Personal {
-                            user_id,
-                            connection_id,
-                        } => state
-                            .oauth_accounts
-                            .refresh_access_token_for_connection(*user_id, *connection_id)
-                            .await
-                            .map(|token| (token.access_token, *connection_id))
-                            .map_err(|_| ()),
-                        SlackConnectionContext::Workspace { connection_id, .. } => state
-                            .workspace_oauth
-                            .refresh_slack_workspace_token(*connection_id)
-                            .await
-                            .map(|conn| (conn.access_token, *connection_id))
-                            .map_err(|_| ()),
-                    };
+                let refresh_result: Result<String, ()> = match context {
+                    SlackConnectionContext::Personal {
+                        user_id,
+                        connection_id,
+                    } => state
+                        .oauth_accounts
+                        .refresh_access_token_for_connection(*user_id, *connection_id)
+                        .await
+                        .map(|token| token.access_token)
+                        .map_err(|_| ()),
+                    SlackConnectionContext::Workspace { connection_id, .. } => state
+                        .workspace_oauth
+                        .refresh_slack_workspace_token(*connection_id)
+                        .await
+                        .map(|conn| conn.access_token)
+                        .map_err(|_| ()),
+                };
 
-                    match refresh_result {
-                        Ok((new_token, _)) => {
-                            access_token = new_token;
-                            refreshed_once = true;
-                            continue;
-                        }
-                        Err(_) => {
-                            let connection_id = match context {
-                                SlackConnectionContext::Personal { connection_id, .. } => {
-                                    *connection_id
-                                }
-                                SlackConnectionContext::Workspace { connection_id, .. } => {
-                                    *connection_id
-                                }
-                            };
-                            return Err(slack_auth_expired_error(connection_id));
-                        }
+                match refresh_result {
+                    Ok(new_token) => {
+                        self.access_token = new_token;
+                        self.refreshed_once = true;
+                        continue;
                     }
-                } else {
-                    return Err("Slack token expired".to_string());
+                    Err(_) => return Err(slack_auth_expired_error(connection_id)),
                 }
             }
 
-            if let Some(context) = &connection_context {
+            if let Some(context) = &self.connection_context {
                 if is_revocation_signal(Some(status), &body_text) {
                     match context {
                         SlackConnectionContext::Personal {
@@ -705,7 +865,7 @@ async fn send_slack(
                     }
                 })
                 .unwrap_or_else(|| "Unknown Slack API error".to_string());
-            if let Some(context) = &connection_context {
+            if let Some(context) = &self.connection_context {
                 let prefix = match context {
                     SlackConnectionContext::Personal { .. } => "personal_user error: ",
                     SlackConnectionContext::Workspace { .. } => "workspace_bot error: ",
@@ -724,60 +884,294 @@ async fn send_slack(
                 detail
             ));
         }
+    }
+}
 
-        break (status, parsed);
+impl SlackConnectionContext {
+    fn connection_id(&self) -> Uuid {
+        match self {
+            SlackConnectionContext::Personal { connection_id, .. } => *connection_id,
+            SlackConnectionContext::Workspace { connection_id, .. } => *connection_id,
+        }
+    }
+}
+
+fn templated_required(
+    params: &Value,
+    key: &str,
+    field: &str,
+    context: &Value,
+) -> Result<String, String> {
+    let raw = extract_required_str(params, key, field)?;
+    let templated = templ_str(raw, context);
+    let trimmed = templated.trim();
+    if trimmed.is_empty() {
+        return Err(format!("{} is required", field));
+    }
+    Ok(trimmed.to_string())
+}
+
+/// Builds the `text`/`blocks` portion of a chat.postMessage or chat.update
+/// payload. Either a message or Block Kit blocks must be supplied; when both
+/// are present the message is used as the notification fallback text.
+fn build_slack_message_payload(
+    params: &Value,
+    context: &Value,
+) -> Result<Map<String, Value>, String> {
+    let mut payload = Map::new();
+
+    let message = extract_optional_templated_string(params, "message", context);
+    let blocks = match params.get("blocksJson") {
+        Some(Value::String(raw)) if raw.trim().is_empty() => None,
+        Some(raw @ (Value::String(_) | Value::Array(_) | Value::Object(_))) => {
+            Some(parse_slack_blocks(raw, context)?)
+        }
+        _ => None,
     };
 
-    let mut output = json!({
-        "sent": true,
-        "service": "Slack",
-        "platform": "Slack",
-        "status": status.as_u16(),
-    });
+    if message.is_none() && blocks.is_none() {
+        return Err("Message is required".to_string());
+    }
 
-    if let Some(ts) = parsed
-        .as_ref()
-        .and_then(|v| v.get("ts"))
-        .and_then(|v| v.as_str())
-    {
-        output["messageTs"] = Value::String(ts.to_string());
+    if let Some(message) = message {
+        payload.insert("text".to_string(), Value::String(message));
+    }
+    if let Some(blocks) = blocks {
+        payload.insert("blocks".to_string(), blocks);
+    }
+
+    Ok(payload)
+}
+
+/// Parses `blocksJson` (a JSON string or an inline value) and only then
+/// templates its string values, so context values cannot break out of the
+/// strings they are placed in.
+fn parse_slack_blocks(raw: &Value, context: &Value) -> Result<Value, String> {
+    let parsed = match raw {
+        Value::String(text) => serde_json::from_str(text.trim())
+            .map_err(|err| format!("Blocks JSON must be valid JSON: {err}"))?,
+        other => other.clone(),
+    };
+    let parsed = template_string_values(parsed, context);
+
+    // Accept either a bare array or the Block Kit Builder export shape
+    // (`{ "blocks": [...] }`).
+    let blocks = match parsed {
+        Value::Array(items) => items,
+        Value::Object(mut obj) => match obj.remove("blocks") {
+            Some(Value::Array(items)) => items,
+            _ => return Err("Blocks JSON must be an array or include a 'blocks' array".to_string()),
+        },
+        _ => return Err("Blocks JSON must be an array or include a 'blocks' array".to_string()),
+    };
+
+    if blocks.is_empty() {
+        return Err("Blocks JSON must contain at least one block".to_string());
+    }
+    if blocks.len() > SLACK_MAX_BLOCKS {
+        return Err(format!(
+            "Slack messages support at most {} blocks",
+            SLACK_MAX_BLOCKS
+        ));
+    }
+
+    for (idx, block) in blocks.iter().enumerate() {
+        let has_type = block
+            .get("type")
+            .and_then(|v| v.as_str())
+            .map(|s| !s.trim().is_empty())
+            .unwrap_or(false);
+        if !has_type {
+            return Err(format!(
+                "Block {} must be an object with a 'type' field",
+                idx + 1
+            ));
+        }
+    }
+
+    Ok(Value::Array(blocks))
+}
+
+fn template_string_values(value: Value, context: &Value) -> Value {
+    match value {
+        Value::String(text) => Value::String(templ_str(&text, context)),
+        Value::Array(items) => Value::Array(
+            items
+                .into_iter()
+                .map(|item| template_string_values(item, context))
+                .collect(),
+        ),
+        Value::Object(map) => Value::Object(
+            map.into_iter()
+                .map(|(key, item)| (key, template_string_values(item, context)))
+                .collect(),
+        ),
+        other => other,
     }
+}
 
-    if let Some(channel_id) = parsed
-        .as_ref()
-        .and_then(|v| v.get("channel"))
+fn apply_thread_options(payload: &mut Map<String, Value>, params: &Value, context: &Value) {
+    if let Some(thread_ts) = extract_optional_templated_string(params, "threadTs", context) {
+        payload.insert("thread_ts".to_string(), Value::String(thread_ts));
+        if params
+            .get("replyBroadcast")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false)
+        {
+            payload.insert("reply_broadcast".to_string(), Value::Bool(true));
+        }
+    }
+}
+
+fn set_message_reference(output: &mut Value, ts: &str, channel: &str) {
+    output["ts"] = Value::String(ts.to_string());
+    output["messageTs"] = Value::String(ts.to_string());
+    output["channel"] = Value::String(channel.to_string());
+    output["channelId"] = Value::String(channel.to_string());
+}
+
+fn copy_message_reference(output: &mut Value, parsed: &Value) {
+    if let Some(ts) = parsed.get("ts").and_then(|v| v.as_str()) {
+        output["ts"] = Value::String(ts.to_string());
+        output["messageTs"] = Value::String(ts.to_string());
+    }
+    if let Some(channel) = parsed.get("channel").and_then(|v| v.as_str()) {
+        output["channel"] = Value::String(channel.to_string());
+        output["channelId"] = Value::String(channel.to_string());
+    }
+    if let Some(thread_ts) = parsed
+        .get("message")
+        .and_then(|m| m.get("thread_ts"))
         .and_then(|v| v.as_str())
     {
-        output["channelId"] = Value::String(channel_id.to_string());
+        output["threadTs"] = Value::String(thread_ts.to_string());
     }
+}
 
-    if let Some(email) = token_email {
-        output["oauthAccountEmail"] = Value::String(email);
+async fn resolve_slack_user_id(
+    session: &mut SlackSession<'_>,
+    params: &Value,
+    context: &Value,
+) -> Result<String, String> {
+    if let Some(user_id) = extract_optional_templated_string(params, "userId", context) {
+        return Ok(user_id);
     }
 
-    if output_connection_scope.is_none() || output_connection_id.is_none() {
-        if let Some(context) = &connection_context {
-            match context {
-                SlackConnectionContext::Personal { connection_id, .. } => {
-                    output_connection_scope = Some("user".to_string());
-                    output_connection_id = Some(*connection_id);
-                }
-                SlackConnectionContext::Workspace { connection_id, .. } => {
-                    output_connection_scope = Some("workspace".to_string());
-                    output_connection_id = Some(*connection_id);
-                }
-            }
+    let email = templated_required(params, "email", "Email or user id", context)?;
+    let (_, parsed) = session
+        .call(
+            "users.lookupByEmail",
+            SlackRequest::Form(vec![("email", email)]),
+        )
+        .await?;
+
+    parsed
+        .get("user")
+        .and_then(|u| u.get("id"))
+        .and_then(|v| v.as_str())
+        .map(|s| s.to_string())
+        .ok_or_else(|| "Slack did not return a user id".to_string())
+}
+
+/// Uploads a file using Slack's external upload flow: reserve an upload URL,
+/// send the bytes, then complete the upload and share it to the channel.
+async fn upload_slack_file(
+    session: &mut SlackSession<'_>,
+    params: &Value,
+    context: &Value,
+    run: &WorkflowRun,
+) -> Result<(reqwest::StatusCode, Value), String> {
+    let channel = templated_required(params, "channel", "Slack channel", context)?;
+    let mut artifacts =
+        load_artifact_param(session.state, run, params.get("artifact"), context).await?;
+    if artifacts.len() > 1 {
+        return Err("Slack uploads accept a single artifact".to_string());
+    }
+    let (file_name, bytes) = match artifacts.pop() {
+        // An artifact supplies the content; the file name defaults to its own.
+        Some(artifact) => (
+            extract_optional_templated_string(params, "fileName", context).unwrap_or(artifact.name),
+            artifact.bytes,
+        ),
+        None => {
+            let file_name = templated_required(params, "fileName", "File name", context)?;
+            let content = params
+                .get("fileContent")
+                .and_then(|v| v.as_str())
+                .map(|raw| templ_str(raw, context))
+                .filter(|s| !s.is_empty())
+                .ok_or_else(|| "File content is required".to_string())?;
+            (file_name, content.into_bytes())
         }
+    };
+
+    let (_, reserved) = session
+        .call(
+            "files.getUploadURLExternal",
+            SlackRequest::Form(vec![
+                ("filename", file_name.clone()),
+                ("length", bytes.len().to_string()),
+            ]),
+        )
+        .await?;
+
+    let upload_url = reserved
+        .get("upload_url")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| "Slack did not return an upload URL".to_string())?;
+    let file_id = reserved
+        .get("file_id")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| "Slack did not return a file id".to_string())?
+        .to_string();
+
+    let upload_response = session
+        .state
+        .http_client
+        .post(upload_url)
+        .body(bytes)
+        .send()
+        .await
+        .map_err(|e| format!("Slack file upload failed: {e}"))?;
+    if !upload_response.status().is_success() {
+        return Err(format!(
+            "Slack file upload failed with status {}",
+            upload_response.status().as_u16()
+        ));
     }
 
-    if let Some(scope) = output_connection_scope {
-        output["connectionScope"] = Value::String(scope);
+    let title =
+        extract_optional_templated_string(params, "title", context).unwrap_or(file_name.clone());
+    let mut complete = Map::new();
+    complete.insert(
+        "files".to_string(),
+        json!([{ "id": file_id, "title": title }]),
+    );
+    complete.insert("channel_id".to_string(), Value::String(channel.clone()));
+    if let Some(comment) = extract_optional_templated_string(params, "initialComment", context) {
+        complete.insert("initial_comment".to_string(), Value::String(comment));
     }
-    if let Some(connection_id) = output_connection_id {
-        output["connectionId"] = Value::String(connection_id.to_string());
+    if let Some(thread_ts) = extract_optional_templated_string(params, "threadTs", context) {
+        complete.insert("thread_ts".to_string(), Value::String(thread_ts));
     }
 
-    Ok((output, None))
+    let (status, _) = session
+        .call(
+            "files.completeUploadExternal",
+            SlackRequest::Json(Value::Object(complete)),
+        )
+        .await?;
+
+    Ok((
+        status,
+        json!({
+            "uploaded": true,
+            "fileId": file_id,
+            "fileName": file_name,
+            "channel": channel,
+            "channelId": channel,
+        }),
+    ))
 }
 
 async fn send_teams(
@@ -843,6 +1237,15 @@ async fn send_teams(
 
     let sanitized = sanitize_teams_params(&effective_params);
 
+    if TeamsOperation::from_params(params)? != TeamsOperation::SendMessage
+        && normalize_identifier(&delivery_method) != "delegatedoauthpostasuser"
+    {
+        return Err(
+            "Reading or replying to Teams messages requires a delegated Microsoft connection"
+                .to_string(),
+        );
+    }
+
     match normalize_identifier(&delivery_method).as_str() {
         "incomingwebhook" => send_teams_incoming_webhook(&sanitized, context).await,
         "delegatedoauthpostasuser" => {
@@ -1249,6 +1652,18 @@ fn sanitize_teams_params(params: &Value) -> Value {
             if let Some(channel_name) = optional_string(params, "channelName") {
                 map.insert("channelName".into(), Value::String(channel_name));
             }
+            if let Some(operation) = optional_string(params, "operation") {
+                map.insert("operation".into(), Value::String(operation));
+            }
+            if let Some(message_id) = optional_string(params, "messageId") {
+                map.insert("messageId".into(), Value::String(message_id));
+            }
+            if let Some(limit) = params
+                .get("limit")
+                .filter(|v| v.is_number() || v.is_string())
+            {
+                map.insert("limit".into(), limit.clone());
+            }
 
             let message_type_raw = params
                 .get("messageType")
@@ -1392,14 +1807,6 @@ fn extract_mentions(params: &Value) -> Vec<TeamsMentionEntry> {
         .unwrap_or_default()
 }
 
-fn microsoft_graph_base_url() -> String {
-    std::env::var("MICROSOFT_GRAPH_BASE_URL")
-        .ok()
-        .map(|value| value.trim().to_string())
-        .filter(|value| !value.is_empty())
-        .unwrap_or_else(|| DEFAULT_MICROSOFT_GRAPH_BASE_URL.to_string())
-}
-
 fn escape_html(input: &str) -> String {
     let mut escaped = String::with_capacity(input.len());
     for ch in input.chars() {
@@ -1591,65 +1998,83 @@ fn build_card_payload(card_json: &str) -> Result<Value, String> {
     }))
 }
 
-async fn send_teams_delegated_oauth(
-    params: &Value,
-    context: &Value,
-    state: &AppState,
-    run: &WorkflowRun,
-) -> Result<(Value, Option<String>), String> {
-    let provider = params
-        .get("oauthProvider")
-        .and_then(|v| v.as_str())
-        .unwrap_or("microsoft");
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TeamsOperation {
+    SendMessage,
+    ReplyToMessage,
+    ListMessages,
+}
 
-    if normalize_identifier(provider) != "microsoft" {
-        return Err(
-            "Only Microsoft delegated OAuth connections are supported for Teams messaging"
-                .to_string(),
-        );
+impl TeamsOperation {
+    fn from_params(params: &Value) -> Result<Self, String> {
+        let raw = params
+            .get("operation")
+            .and_then(|v| v.as_str())
+            .map(|s| s.trim())
+            .filter(|s| !s.is_empty())
+            .unwrap_or("send_message");
+        match normalize_identifier(raw).as_str() {
+            "sendmessage" | "send" | "postmessage" => Ok(Self::SendMessage),
+            "replytomessage" | "reply" => Ok(Self::ReplyToMessage),
+            "listmessages" | "readmessages" => Ok(Self::ListMessages),
+            _ => Err(format!("Teams operation '{}' is not supported", raw)),
+        }
     }
 
-    let connection_usage = resolve_connection_usage(params)?;
-
-    let team_id = params
-        .get("teamId")
-        .and_then(|v| v.as_str())
-        .map(|s| s.trim())
-        .filter(|s| !s.is_empty())
-        .ok_or_else(|| "Team ID is required for delegated Teams messaging".to_string())?;
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::SendMessage => "send_message",
+            Self::ReplyToMessage => "reply_to_message",
+            Self::ListMessages => "list_messages",
+        }
+    }
+}
 
-    let channel_id = params
-        .get("channelId")
-        .and_then(|v| v.as_str())
-        .map(|s| s.trim())
-        .filter(|s| !s.is_empty())
-        .ok_or_else(|| "Channel ID is required for delegated Teams messaging".to_string())?;
+const DEFAULT_TEAMS_LIST_LIMIT: u32 = 20;
 
-    let message_type_raw = params
-        .get("messageType")
-        .and_then(|v| v.as_str())
-        .unwrap_or("Text");
-    let message_type = if normalize_identifier(message_type_raw) == "card" {
-        "Card"
-    } else {
-        "Text"
-    };
+enum TeamsConnectionContext {
+    Personal {
+        user_id: Uuid,
+        connection_id: Uuid,
+        account_email: Option<String>,
+    },
+    Workspace {
+        workspace_id: Uuid,
+        connection_id: Uuid,
+        created_by: Uuid,
+        account_email: Option<String>,
+    },
+}
 
-    enum ConnectionContext {
-        Personal {
-            user_id: Uuid,
-            connection_id: Uuid,
-            account_email: Option<String>,
-        },
-        Workspace {
-            workspace_id: Uuid,
-            connection_id: Uuid,
-            created_by: Uuid,
-            account_email: Option<String>,
-        },
+impl TeamsConnectionContext {
+    fn apply_metadata(&self, output: &mut Value) {
+        match self {
+            Self::Personal { connection_id, .. } => {
+                output["connectionScope"] = Value::String("user".to_string());
+                output["connectionId"] = Value::String(connection_id.to_string());
+            }
+            Self::Workspace { connection_id, .. } => {
+                output["connectionScope"] = Value::String("workspace".to_string());
+                output["connectionId"] = Value::String(connection_id.to_string());
+            }
+        }
     }
+}
+
+struct TeamsDelegatedSession {
+    access_token: String,
+    account_email: String,
+    context: TeamsConnectionContext,
+}
 
-    let (access_token, token_email, connection_context) = match connection_usage {
+async fn resolve_teams_delegated_session(
+    params: &Value,
+    state: &AppState,
+    run: &WorkflowRun,
+) -> Result<TeamsDelegatedSession, String> {
+    let connection_usage = resolve_connection_usage(params)?;
+
+    match connection_usage {
         NodeConnectionUsage::Workspace(info) => {
             let workspace_id = run.workspace_id.ok_or_else(|| {
                 "This workflow run is not associated with a workspace. Promote the Microsoft connection to the workspace or switch the action back to a personal connection.".to_string()
@@ -1672,16 +2097,16 @@ async fn send_teams_delegated_oauth(
                 return Err("Selected connection is not a Microsoft connection".to_string());
             }
 
-            (
-                connection.access_token.clone(),
-                connection.account_email.clone(),
-                ConnectionContext::Workspace {
+            Ok(TeamsDelegatedSession {
+                access_token: connection.access_token.clone(),
+                account_email: connection.account_email.clone(),
+                context: TeamsConnectionContext::Workspace {
                     workspace_id,
                     connection_id: connection.id,
                     created_by: connection.owner_user_id,
                     account_email: Some(connection.account_email.clone()),
                 },
-            )
+            })
         }
         NodeConnectionUsage::User(info) => {
             let connection_id_str = info.connection_id.ok_or_else(|| {
@@ -1706,25 +2131,172 @@ async fn send_teams_delegated_oauth(
                     other => format!("Failed to refresh Microsoft OAuth token: {other}"),
                 })?;
 
-            (
-                token.access_token.clone(),
-                token.account_email.clone(),
-                ConnectionContext::Personal {
+            Ok(TeamsDelegatedSession {
+                access_token: token.access_token.clone(),
+                account_email: token.account_email.clone(),
+                context: TeamsConnectionContext::Personal {
                     user_id: run.user_id,
                     connection_id: token.id,
                     account_email: Some(token.account_email.clone()),
                 },
+            })
+        }
+    }
+}
+
+/// Purges the revoked Microsoft connection behind `context` and returns the
+/// user-facing error for the failed step.
+async fn handle_teams_revocation(
+    state: &AppState,
+    context: &TeamsConnectionContext,
+    status: reqwest::StatusCode,
+    body_text: &str,
+) -> String {
+    let (account_email, message) = match context {
+        TeamsConnectionContext::Personal {
+            user_id,
+            account_email,
+            ..
+        } => {
+            if let Err(err) = state
+                .oauth_accounts
+                .handle_revoked_token(*user_id, ConnectedOAuthProvider::Microsoft)
+                .await
+            {
+                warn!(
+                    user_id = %user_id,
+                    error = %err,
+                    "failed to purge revoked personal microsoft token"
+                );
+            }
+
+            (
+                account_email.clone(),
+                "Microsoft revoked the connected account. Reconnect it from Settings → Integrations.".to_string(),
+            )
+        }
+        TeamsConnectionContext::Workspace {
+            workspace_id,
+            connection_id,
+            created_by,
+            account_email,
+        } => {
+            if let Err(err) = state
+                .workspace_oauth
+                .handle_revoked_connection(*workspace_id, *connection_id)
+                .await
+            {
+                warn!(
+                    workspace_id = %workspace_id,
+                    connection_id = %connection_id,
+                    error = %err,
+                    "failed to remove revoked workspace microsoft connection"
+                );
+            }
+
+            if let Err(err) = state
+                .oauth_accounts
+                .handle_revoked_token(*created_by, ConnectedOAuthProvider::Microsoft)
+                .await
+            {
+                warn!(
+                    created_by = %created_by,
+                    error = %err,
+                    "failed to purge creator's personal microsoft token after workspace revocation"
+                );
+            }
+
+            (
+                account_email.clone(),
+                "Microsoft revoked the shared workspace connection. Ask the owner to reconnect it from Settings → Integrations.".to_string(),
             )
         }
     };
 
-    let base_url = microsoft_graph_base_url();
-    let target = format!(
+    warn!(
+        status = %status,
+        account_email = account_email.as_deref().unwrap_or("unknown"),
+        body = %body_text,
+        "microsoft graph returned revocation signal"
+    );
+
+    message
+}
+
+async fn send_teams_delegated_oauth(
+    params: &Value,
+    context: &Value,
+    state: &AppState,
+    run: &WorkflowRun,
+) -> Result<(Value, Option<String>), String> {
+    let provider = params
+        .get("oauthProvider")
+        .and_then(|v| v.as_str())
+        .unwrap_or("microsoft");
+
+    if normalize_identifier(provider) != "microsoft" {
+        return Err(
+            "Only Microsoft delegated OAuth connections are supported for Teams messaging"
+                .to_string(),
+        );
+    }
+
+    let operation = TeamsOperation::from_params(params)?;
+
+    let team_id = params
+        .get("teamId")
+        .and_then(|v| v.as_str())
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty())
+        .ok_or_else(|| "Team ID is required for delegated Teams messaging".to_string())?;
+
+    let channel_id = params
+        .get("channelId")
+        .and_then(|v| v.as_str())
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty())
+        .ok_or_else(|| "Channel ID is required for delegated Teams messaging".to_string())?;
+
+    let reply_to = if operation == TeamsOperation::ReplyToMessage {
+        let raw = extract_required_str(params, "messageId", "Message ID")?;
+        let templated = templ_str(raw, context).trim().to_string();
+        if templated.is_empty() {
+            return Err("Message ID is required to reply to a Teams message".to_string());
+        }
+        Some(templated)
+    } else {
+        None
+    };
+
+    let message_type_raw = params
+        .get("messageType")
+        .and_then(|v| v.as_str())
+        .unwrap_or("Text");
+    let message_type = if normalize_identifier(message_type_raw) == "card" {
+        "Card"
+    } else {
+        "Text"
+    };
+
+    let session = resolve_teams_delegated_session(params, state, run).await?;
+
+    if operation == TeamsOperation::ListMessages {
+        return list_teams_channel_messages(params, state, &session, team_id, channel_id).await;
+    }
+
+    let access_token = &session.access_token;
+    let token_email = &session.account_email;
+
+    let base_url = &state.config.provider_base_urls.microsoft_graph;
+    let mut target = format!(
         "{}/teams/{}/channels/{}/messages",
         base_url.trim_end_matches('/'),
         encode(team_id),
         encode(channel_id)
     );
+    if let Some(message_id) = reply_to.as_deref() {
+        target = format!("{}/{}/replies", target, encode(message_id));
+    }
 
     let (payload, mention_count) = if message_type == "Card" {
         let card_raw = params
@@ -1759,7 +2331,7 @@ async fn send_teams_delegated_oauth(
     let response = state
         .http_client
         .post(&target)
-        .bearer_auth(&access_token)
+        .bearer_auth(access_token)
         .json(&payload)
         .send()
         .await
@@ -1774,75 +2346,7 @@ async fn send_teams_delegated_oauth(
 
     if !status.is_success() {
         if is_revocation_signal(Some(status), &body_text) {
-            let (account_email, message) = match &connection_context {
-                ConnectionContext::Personal {
-                    user_id,
-                    account_email,
-                    ..
-                } => {
-                    if let Err(err) = state
-                        .oauth_accounts
-                        .handle_revoked_token(*user_id, ConnectedOAuthProvider::Microsoft)
-                        .await
-                    {
-                        warn!(
-                            user_id = %user_id,
-                            error = %err,
-                            "failed to purge revoked personal microsoft token"
-                        );
-                    }
-
-                    (
-                        account_email.clone(),
-                        "Microsoft revoked the connected account. Reconnect it from Settings → Integrations.".to_string(),
-                    )
-                }
-                ConnectionContext::Workspace {
-                    workspace_id,
-                    connection_id,
-                    created_by,
-                    account_email,
-                } => {
-                    if let Err(err) = state
-                        .workspace_oauth
-                        .handle_revoked_connection(*workspace_id, *connection_id)
-                        .await
-                    {
-                        warn!(
-                            workspace_id = %workspace_id,
-                            connection_id = %connection_id,
-                            error = %err,
-                            "failed to remove revoked workspace microsoft connection"
-                        );
-                    }
-
-                    if let Err(err) = state
-                        .oauth_accounts
-                        .handle_revoked_token(*created_by, ConnectedOAuthProvider::Microsoft)
-                        .await
-                    {
-                        warn!(
-                            created_by = %created_by,
-                            error = %err,
-                            "failed to purge creator's personal microsoft token after workspace revocation"
-                        );
-                    }
-
-                    (
-                        account_email.clone(),
-                        "Microsoft revoked the shared workspace connection. Ask the owner to reconnect it from Settings → Integrations.".to_string(),
-                    )
-                }
-            };
-
-            warn!(
-                status = %status,
-                account_email = account_email.as_deref().unwrap_or("unknown"),
-                body = %body_text,
-                "microsoft graph returned revocation signal"
-            );
-
-            return Err(message);
+            return Err(handle_teams_revocation(state, &session.context, status, &body_text).await);
         }
 
         let message = graph_error_message(parsed.as_ref(), &body_text);
@@ -1858,12 +2362,17 @@ async fn send_teams_delegated_oauth(
         "service": "Teams",
         "platform": "Teams",
         "deliveryMethod": "Delegated OAuth (Post as user)",
+        "operation": operation.as_str(),
         "status": status.as_u16(),
         "teamId": team_id,
         "channelId": channel_id,
         "messageType": message_type,
     });
 
+    if let Some(message_id) = reply_to {
+        output["replyToId"] = Value::String(message_id);
+    }
+
     if mention_count > 0 {
         output["mentionsAdded"] = Value::Number(serde_json::Number::from(mention_count as u64));
     }
@@ -1891,16 +2400,7 @@ async fn send_teams_delegated_oauth(
     }
 
     // Surface connection metadata to help detect stale selections
-    match connection_context {
-        ConnectionContext::Personal { connection_id, .. } => {
-            output["connectionScope"] = Value::String("user".to_string());
-            output["connectionId"] = Value::String(connection_id.to_string());
-        }
-        ConnectionContext::Workspace { connection_id, .. } => {
-            output["connectionScope"] = Value::String("workspace".to_string());
-            output["connectionId"] = Value::String(connection_id.to_string());
-        }
-    }
+    session.context.apply_metadata(&mut output);
 
     if let Some(Value::Object(obj)) = parsed.as_ref() {
         if let Some(Value::String(id)) = obj.get("id") {
@@ -1914,6 +2414,66 @@ async fn send_teams_delegated_oauth(
     Ok((output, None))
 }
 
+async fn list_teams_channel_messages(
+    params: &Value,
+    state: &AppState,
+    session: &TeamsDelegatedSession,
+    team_id: &str,
+    channel_id: &str,
+) -> Result<(Value, Option<String>), String> {
+    let limit = match params.get("limit") {
+        Some(Value::Number(num)) => num.as_u64().map(|v| v.min(50) as u32),
+        Some(Value::String(raw)) => raw.trim().parse::<u32>().ok(),
+        _ => None,
+    }
+    .filter(|v| *v > 0)
+    .unwrap_or(DEFAULT_TEAMS_LIST_LIMIT);
+
+    let messages = match microsoft::fetch_channel_messages(
+        &state.http_client,
+        &state.config.provider_base_urls.microsoft_graph,
+        &session.access_token,
+        team_id,
+        channel_id,
+        limit,
+    )
+    .await
+    {
+        Ok(messages) => messages,
+        Err(MicrosoftGraphError::UnexpectedStatus { status, message }) => {
+            let status = reqwest::StatusCode::from_u16(status.as_u16())
+                .unwrap_or(reqwest::StatusCode::BAD_GATEWAY);
+            if is_revocation_signal(Some(status), &message) {
+                return Err(
+                    handle_teams_revocation(state, &session.context, status, &message).await,
+                );
+            }
+            return Err(format!(
+                "Microsoft Graph returned status {}: {}",
+                status.as_u16(),
+                message
+            ));
+        }
+        Err(err) => return Err(err.to_string()),
+    };
+
+    let mut output = json!({
+        "service": "Teams",
+        "platform": "Teams",
+        "operation": TeamsOperation::ListMessages.as_str(),
+        "teamId": team_id,
+        "channelId": channel_id,
+        "count": messages.len(),
+        "messages": serde_json::to_value(&messages).unwrap_or(Value::Array(Vec::new())),
+    });
+    if !session.account_email.trim().is_empty() {
+        output["oauthAccountEmail"] = Value::String(session.account_email.clone());
+    }
+    session.context.apply_metadata(&mut output);
+
+    Ok((output, None))
+}
+
 async fn post_webhook_payload(
     webhook_raw: &str,
     payload: Value,
@@ -1976,30 +2536,24 @@ mod tests {
     use super::*;
     use async_trait::async_trait;
     use axum::{
-        body::{to_bytes, Body},
-        extract::State,
-        http::{header, Request, Response, StatusCode},
-        routing::post,
-        Router,
+        body::Body,
+        http::{header, Method, Response, StatusCode},
     };
-    use once_cell::sync::Lazy;
     use reqwest::Client;
     use serde_json::{json, Value};
     use std::sync::{
         atomic::{AtomicUsize, Ordering},
-        Arc, Mutex, MutexGuard,
+        Arc, Mutex,
     };
     use time::{Duration, OffsetDateTime};
-    use tokio::{
-        net::TcpListener,
-        sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender},
-        task::JoinHandle,
-    };
 
     use crate::engine::graph::Node;
+    use crate::test_support::{
+        spawn_stub_server, workspace_connection_fixture, FakeProvider, RecordedRequest,
+    };
     use crate::{
         config::{
-            Config, OAuthProviderConfig, OAuthSettings, StripeSettings,
+            Config, OAuthProviderConfig, OAuthSettings, ProviderBaseUrls, StripeSettings,
             DEFAULT_WORKSPACE_MEMBER_LIMIT, DEFAULT_WORKSPACE_MONTHLY_RUN_LIMIT,
             RUNAWAY_LIMIT_5MIN,
         },
@@ -2035,37 +2589,6 @@ mod tests {
     use sqlx::Error as SqlxError;
     use uuid::Uuid;
 
-    static ENV_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
-
-    struct EnvGuard {
-        key: &'static str,
-        previous: Option<String>,
-        _lock: MutexGuard<'static, ()>,
-    }
-
-    impl EnvGuard {
-        fn set(key: &'static str, value: String) -> Self {
-            let lock = ENV_LOCK.lock().expect("env mutex poisoned");
-            let previous = std::env::var(key).ok();
-            std::env::set_var(key, value);
-            Self {
-                key,
-                previous,
-                _lock: lock,
-            }
-        }
-    }
-
-    impl Drop for EnvGuard {
-        fn drop(&mut self) {
-            if let Some(previous) = self.previous.take() {
-                std::env::set_var(self.key, previous);
-            } else {
-                std::env::remove_var(self.key);
-            }
-        }
-    }
-
     #[derive(Default)]
     struct NoopUserTokenRepo;
 
@@ -2204,6 +2727,13 @@ mod tests {
                 .await
         }
 
+        async fn list_slack_by_team(
+            &self,
+            _slack_team_id: &str,
+        ) -> Result<Vec<WorkspaceConnection>, SqlxError> {
+            Ok(Vec::new())
+        }
+
         async fn list_for_workspace(
             &self,
             _workspace_id: Uuid,
@@ -2383,91 +2913,11 @@ mod tests {
         (service, repo)
     }
 
-    #[derive(Clone, Debug)]
-    struct RecordedRequest {
-        method: String,
-        uri: String,
-        headers: Vec<(String, String)>,
-        body: Vec<u8>,
-    }
-
-    struct StubState<F>
-    where
-        F: Fn() -> Response<Body> + Send + Sync + 'static,
-    {
-        tx: UnboundedSender<RecordedRequest>,
-        response_factory: Arc<F>,
-    }
-
-    impl<F> Clone for StubState<F>
-    where
-        F: Fn() -> Response<Body> + Send + Sync + 'static,
-    {
-        fn clone(&self) -> Self {
-            Self {
-                tx: self.tx.clone(),
-                response_factory: Arc::clone(&self.response_factory),
-            }
-        }
-    }
-
-    async fn stub_handler<F>(
-        State(state): State<StubState<F>>,
-        request: Request<Body>,
-    ) -> Response<Body>
-    where
-        F: Fn() -> Response<Body> + Send + Sync + 'static,
-    {
-        let (parts, body) = request.into_parts();
-        let bytes = to_bytes(body, 1024 * 1024).await.unwrap_or_default();
-        let headers = parts
-            .headers
-            .iter()
-            .map(|(k, v)| (k.to_string(), v.to_str().unwrap_or_default().to_string()))
-            .collect();
-        let record = RecordedRequest {
-            method: parts.method.to_string(),
-            uri: parts.uri.to_string(),
-            headers,
-            body: bytes.to_vec(),
-        };
-        let _ = state.tx.send(record);
-        (state.response_factory)()
-    }
-
-    async fn spawn_stub_server<F>(
-        response_factory: F,
-    ) -> (
-        std::net::SocketAddr,
-        UnboundedReceiver<RecordedRequest>,
-        JoinHandle<()>,
-    )
-    where
-        F: Fn() -> Response<Body> + Send + Sync + 'static,
-    {
-        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
-        let addr = listener.local_addr().unwrap();
-        let (tx, rx) = unbounded_channel();
-        let state = StubState {
-            tx,
-            response_factory: Arc::new(response_factory),
-        };
-
-        let app = Router::new()
-            .route("/{*path}", post(stub_handler::<F>))
-            .with_state(state);
-
-        let server = axum::serve(listener, app.into_make_service());
-        let handle = tokio::spawn(async move {
-            if let Err(err) = server.await {
-                eprintln!("stub server exited with error: {err}");
-            }
-        });
-
-        (addr, rx, handle)
+    fn test_config() -> Arc<Config> {
+        test_config_with_urls(ProviderBaseUrls::default())
     }
 
-    fn test_config() -> Arc<Config> {
+    fn test_config_with_urls(provider_base_urls: ProviderBaseUrls) -> Arc<Config> {
         Arc::new(Config {
             database_url: String::new(),
             frontend_origin: "http://localhost".into(),
@@ -2498,6 +2948,11 @@ mod tests {
                     client_secret: "stub".into(),
                     redirect_uri: "http://localhost".into(),
                 },
+                github: OAuthProviderConfig {
+                    client_id: "stub".into(),
+                    client_secret: "stub".into(),
+                    redirect_uri: "http://localhost".into(),
+                },
                 token_encryption_key: vec![0u8; 32],
             },
             api_secrets_encryption_key: vec![1u8; 32],
@@ -2513,6 +2968,9 @@ mod tests {
             workspace_member_limit: DEFAULT_WORKSPACE_MEMBER_LIMIT,
             workspace_monthly_run_limit: DEFAULT_WORKSPACE_MONTHLY_RUN_LIMIT,
             runaway_limit_5min: RUNAWAY_LIMIT_5MIN,
+            slack_signing_secret: None,
+            public_api_base_url: None,
+            provider_base_urls,
         })
     }
 
@@ -2788,19 +3246,8 @@ mod tests {
         }
     }
 
-    #[tokio::test]
-    async fn slack_requires_explicit_connection() {
-        let (addr, _, _) = spawn_stub_server(|| {
-            Response::builder()
-                .status(StatusCode::OK)
-                .header(header::CONTENT_TYPE, "application/json")
-                .body(Body::from(r#"{"ok":true,"ts":"123.456","channel":"C123"}"#))
-                .unwrap()
-        })
-        .await;
-
-        let _guard = EnvGuard::set("SLACK_API_BASE", format!("http://{}/api", addr));
-
+    #[tokio::test]
+    async fn slack_requires_explicit_connection() {
         let node = Node {
             id: "action-1".into(),
             kind: "action".into(),
@@ -2825,17 +3272,6 @@ mod tests {
 
     #[tokio::test]
     async fn slack_error_is_reported() {
-        let (addr, _rx, handle) = spawn_stub_server(|| {
-            Response::builder()
-                .status(StatusCode::OK)
-                .header(header::CONTENT_TYPE, "application/json")
-                .body(Body::from(r#"{"ok":false,"error":"channel_not_found"}"#))
-                .unwrap()
-        })
-        .await;
-
-        let _guard = EnvGuard::set("SLACK_API_BASE", format!("http://{}/api", addr));
-
         let node = Node {
             id: "action-2".into(),
             kind: "action".into(),
@@ -2852,7 +3288,6 @@ mod tests {
         let err = execute_default(&node, &Value::Null)
             .await
             .expect_err("missing identity should fail");
-        handle.abort();
         assert!(err.contains("identity selection error"));
     }
 
@@ -2950,9 +3385,10 @@ mod tests {
         })
         .await;
 
-        let _guard = EnvGuard::set("SLACK_API_BASE", format!("http://{}/api", addr));
-
-        let config = test_config();
+        let config = test_config_with_urls(ProviderBaseUrls {
+            slack: format!("http://{}/api", addr),
+            ..ProviderBaseUrls::default()
+        });
         let encryption_key = Arc::new(config.oauth.token_encryption_key.clone());
         let user_id = Uuid::new_v4();
         let workspace_id = Uuid::new_v4();
@@ -3041,9 +3477,10 @@ mod tests {
         })
         .await;
 
-        let _guard = EnvGuard::set("SLACK_API_BASE", format!("http://{}/api", addr));
-
-        let config = test_config();
+        let config = test_config_with_urls(ProviderBaseUrls {
+            slack: format!("http://{}/api", addr),
+            ..ProviderBaseUrls::default()
+        });
         let encryption_key = Arc::new(config.oauth.token_encryption_key.clone());
         let workspace_id = Uuid::new_v4();
         let connection_id = Uuid::new_v4();
@@ -3137,9 +3574,10 @@ mod tests {
         })
         .await;
 
-        let _guard = EnvGuard::set("SLACK_API_BASE", format!("http://{}/api", addr));
-
-        let config = test_config();
+        let config = test_config_with_urls(ProviderBaseUrls {
+            slack: format!("http://{}/api", addr),
+            ..ProviderBaseUrls::default()
+        });
         let encryption_key = Arc::new(config.oauth.token_encryption_key.clone());
         let workspace_id = Uuid::new_v4();
         let connection_id = Uuid::new_v4();
@@ -3249,13 +3687,7 @@ mod tests {
         let first = rx.recv().await.expect("first request");
         let second = rx.recv().await.expect("second request");
 
-        let auth_header = |request: &RecordedRequest| {
-            request
-                .headers
-                .iter()
-                .find(|(name, _)| name.eq_ignore_ascii_case("authorization"))
-                .map(|(_, value)| value.clone())
-        };
+        let auth_header = |request: &RecordedRequest| request.header("authorization");
 
         assert_eq!(
             auth_header(&first),
@@ -3298,9 +3730,10 @@ mod tests {
         })
         .await;
 
-        let _guard = EnvGuard::set("SLACK_API_BASE", format!("http://{}/api", addr));
-
-        let config = test_config();
+        let config = test_config_with_urls(ProviderBaseUrls {
+            slack: format!("http://{}/api", addr),
+            ..ProviderBaseUrls::default()
+        });
         let encryption_key = Arc::new(config.oauth.token_encryption_key.clone());
         let user_id = Uuid::new_v4();
         let workspace_id = Uuid::new_v4();
@@ -3426,13 +3859,7 @@ mod tests {
         let first = rx.recv().await.expect("first request");
         let second = rx.recv().await.expect("second request");
 
-        let auth_header = |request: &RecordedRequest| {
-            request
-                .headers
-                .iter()
-                .find(|(name, _)| name.eq_ignore_ascii_case("authorization"))
-                .map(|(_, value)| value.clone())
-        };
+        let auth_header = |request: &RecordedRequest| request.header("authorization");
 
         assert_eq!(
             auth_header(&first),
@@ -3539,9 +3966,10 @@ mod tests {
         })
         .await;
 
-        let _guard = EnvGuard::set("SLACK_API_BASE", format!("http://{}/api", addr));
-
-        let config = test_config();
+        let config = test_config_with_urls(ProviderBaseUrls {
+            slack: format!("http://{}/api", addr),
+            ..ProviderBaseUrls::default()
+        });
         let encryption_key = Arc::new(config.oauth.token_encryption_key.clone());
         let user_id = Uuid::new_v4();
         let workspace_id = Uuid::new_v4();
@@ -3744,6 +4172,13 @@ mod tests {
             ) -> Result<Vec<WorkspaceConnection>, SqlxError> {
                 Ok(vec![self.0.clone(), self.1.clone()])
             }
+
+            async fn list_slack_by_team(
+                &self,
+                _slack_team_id: &str,
+            ) -> Result<Vec<WorkspaceConnection>, SqlxError> {
+                Ok(Vec::new())
+            }
             async fn list_for_workspace(
                 &self,
                 _workspace_id: Uuid,
@@ -3997,6 +4432,13 @@ mod tests {
             ) -> Result<Vec<WorkspaceConnection>, SqlxError> {
                 Ok(vec![self.0.clone(), self.1.clone()])
             }
+
+            async fn list_slack_by_team(
+                &self,
+                _slack_team_id: &str,
+            ) -> Result<Vec<WorkspaceConnection>, SqlxError> {
+                Ok(Vec::new())
+            }
             async fn list_for_workspace(
                 &self,
                 _workspace_id: Uuid,
@@ -4401,10 +4843,7 @@ mod tests {
         handle.abort();
 
         let header = req
-            .headers
-            .iter()
-            .find(|(name, _)| name.eq_ignore_ascii_case("X-Workflow-Secret"))
-            .map(|(_, value)| value.clone())
+            .header("X-Workflow-Secret")
             .expect("header should exist");
         assert_eq!(header, "super-secret");
     }
@@ -4717,12 +5156,7 @@ mod tests {
         let req = rx.recv().await.expect("request recorded");
         handle.abort();
         assert_eq!(req.method, "POST");
-        let content_type = req
-            .headers
-            .iter()
-            .find(|(name, _)| name.eq_ignore_ascii_case("content-type"))
-            .map(|(_, value)| value.clone())
-            .unwrap_or_default();
+        let content_type = req.header("content-type").unwrap_or_default();
         assert!(content_type.contains("application/json"));
         let body: Value = serde_json::from_slice(&req.body).expect("json body");
         assert_eq!(body["text"], "Hello Integrations");
@@ -4745,9 +5179,10 @@ mod tests {
         })
         .await;
 
-        let _guard = EnvGuard::set("MICROSOFT_GRAPH_BASE_URL", format!("http://{}", addr));
-
-        let config = test_config();
+        let config = test_config_with_urls(ProviderBaseUrls {
+            microsoft_graph: format!("http://{}", addr),
+            ..ProviderBaseUrls::default()
+        });
         let encryption_key = Arc::new(config.oauth.token_encryption_key.clone());
         let user_id = Uuid::new_v4();
 
@@ -4837,15 +5272,10 @@ mod tests {
         handle.abort();
 
         assert!(req
-            .uri
+            .path()
             .ends_with("/teams/team-1/channels/channel-1/messages"));
 
-        let auth_header = req
-            .headers
-            .iter()
-            .find(|(name, _)| name.eq_ignore_ascii_case("authorization"))
-            .map(|(_, value)| value.clone())
-            .expect("authorization header");
+        let auth_header = req.header("authorization").expect("authorization header");
         assert_eq!(auth_header, format!("Bearer {}", access_token));
 
         let body: Value = serde_json::from_slice(&req.body).expect("graph payload");
@@ -4877,9 +5307,10 @@ mod tests {
         })
         .await;
 
-        let _guard = EnvGuard::set("MICROSOFT_GRAPH_BASE_URL", format!("http://{}", addr));
-
-        let config = test_config();
+        let config = test_config_with_urls(ProviderBaseUrls {
+            microsoft_graph: format!("http://{}", addr),
+            ..ProviderBaseUrls::default()
+        });
         let encryption_key = Arc::new(config.oauth.token_encryption_key.clone());
         let user_id = Uuid::new_v4();
 
@@ -5014,12 +5445,7 @@ mod tests {
         let req = rx.recv().await.expect("request recorded");
         handle.abort();
         assert_eq!(req.method, "POST");
-        let content_type = req
-            .headers
-            .iter()
-            .find(|(name, _)| name.eq_ignore_ascii_case("content-type"))
-            .map(|(_, value)| value.clone())
-            .unwrap_or_default();
+        let content_type = req.header("content-type").unwrap_or_default();
         assert!(content_type.contains("application/json"));
 
         let body: Value = serde_json::from_slice(&req.body).expect("json body");
@@ -5168,9 +5594,10 @@ mod tests {
         })
         .await;
 
-        let _guard = EnvGuard::set("MICROSOFT_GRAPH_BASE_URL", format!("http://{}", addr));
-
-        let config = test_config();
+        let config = test_config_with_urls(ProviderBaseUrls {
+            microsoft_graph: format!("http://{}", addr),
+            ..ProviderBaseUrls::default()
+        });
         let encryption_key = Arc::new(config.oauth.token_encryption_key.clone());
         let workspace_id = Uuid::new_v4();
         let connection_id = Uuid::new_v4();
@@ -5243,10 +5670,7 @@ mod tests {
         let request = rx.recv().await.expect("graph request recorded");
         handle.abort();
         let auth_header = request
-            .headers
-            .iter()
-            .find(|(name, _)| name.eq_ignore_ascii_case("authorization"))
-            .map(|(_, value)| value.clone())
+            .header("authorization")
             .expect("authorization header");
         assert_eq!(auth_header, "Bearer workspace-access");
 
@@ -5557,4 +5981,451 @@ mod tests {
 
         assert_eq!(repo.find_calls(), vec![connection_id]);
     }
+
+    fn slack_workspace_params(connection_id: Uuid, extra: Value) -> Value {
+        let mut params = json!({
+            "platform": "Slack",
+            "identity": "workspace_bot",
+            "connection": {
+                "connectionScope": "workspace",
+                "connectionId": connection_id.to_string(),
+                "accountEmail": "workspace@example.com"
+            }
+        });
+        if let (Some(target), Value::Object(extra)) = (params.as_object_mut(), extra) {
+            target.extend(extra);
+        }
+        params
+    }
+
+    #[tokio::test]
+    async fn slack_blocks_thread_reply_returns_message_reference() {
+        let (addr, mut rx, handle) = spawn_stub_server(|| {
+            Response::builder()
+                .status(StatusCode::OK)
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(
+                    r#"{"ok":true,"ts":"222.333","channel":"C123","message":{"thread_ts":"111.000"}}"#,
+                ))
+                .unwrap()
+        })
+        .await;
+        let (state, run, connection_id) = workspace_connection_fixture(
+            ConnectedOAuthProvider::Slack,
+            ProviderBaseUrls {
+                slack: format!("http://{}/api", addr),
+                ..ProviderBaseUrls::default()
+            },
+        );
+        let node = Node {
+            id: "slack-blocks".into(),
+            kind: "action".into(),
+            data: json!({
+                "params": slack_workspace_params(connection_id, json!({
+                    "channel": "#team",
+                    "message": "Deploy {{ deploy.version }}",
+                    "blocksJson": "{\"blocks\":[{\"type\":\"section\",\"text\":{\"type\":\"mrkdwn\",\"text\":\"*{{ deploy.version }}* shipped\"}}]}",
+                    "threadTs": "{{ previous.ts }}",
+                    "replyBroadcast": true
+                }))
+            }),
+        };
+        let context = json!({
+            "deploy": { "version": "v1.2.3" },
+            "previous": { "ts": "111.000" }
+        });
+
+        let (output, _) = execute_messaging(&node, &context, &state, &run)
+            .await
+            .expect("block kit thread reply should succeed");
+
+        assert_eq!(output["ts"], "222.333");
+        assert_eq!(output["channel"], "C123");
+        assert_eq!(output["messageTs"], "222.333");
+        assert_eq!(output["threadTs"], "111.000");
+        assert_eq!(output["operation"], "send_message");
+
+        let request = rx.recv().await.expect("slack request");
+        assert!(request.path().ends_with("/api/chat.postMessage"));
+        let body: Value = serde_json::from_slice(&request.body).unwrap();
+        assert_eq!(body["text"], "Deploy v1.2.3");
+        assert_eq!(body["thread_ts"], "111.000");
+        assert_eq!(body["reply_broadcast"], true);
+        assert_eq!(body["blocks"][0]["text"]["text"], "*v1.2.3* shipped");
+
+        handle.abort();
+    }
+
+    #[test]
+    fn slack_blocks_require_typed_objects() {
+        let parse = |raw: &str| parse_slack_blocks(&Value::String(raw.into()), &Value::Null);
+
+        let err = parse(r#"[{"text":"missing type"}]"#).unwrap_err();
+        assert!(err.contains("'type'"));
+
+        let err = parse(r#"{"text":"no blocks"}"#).unwrap_err();
+        assert!(err.contains("'blocks' array"));
+
+        let err = parse("not json").unwrap_err();
+        assert!(err.contains("valid JSON"));
+
+        let too_many: Vec<Value> = (0..=SLACK_MAX_BLOCKS)
+            .map(|_| json!({ "type": "divider" }))
+            .collect();
+        let err = parse_slack_blocks(&Value::Array(too_many), &Value::Null).unwrap_err();
+        assert!(err.contains("at most"));
+    }
+
+    #[test]
+    fn slack_block_templates_cannot_inject_json() {
+        let note = "say \"hi\"\\\n\"}},{\"type\":\"image\"";
+        let context = json!({ "note": note });
+        let raw = r#"[{"type":"section","text":{"type":"mrkdwn","text":"Note: {{ note }}"}}]"#;
+
+        let blocks = parse_slack_blocks(&Value::String(raw.into()), &context).unwrap();
+
+        assert_eq!(blocks.as_array().map(Vec::len), Some(1));
+        assert_eq!(blocks[0]["text"]["text"], format!("Note: {note}"));
+    }
+
+    #[tokio::test]
+    async fn slack_update_and_reaction_target_existing_message() {
+        let (addr, mut rx, handle) = spawn_stub_server(|| {
+            Response::builder()
+                .status(StatusCode::OK)
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(r#"{"ok":true,"ts":"111.000","channel":"C123"}"#))
+                .unwrap()
+        })
+        .await;
+        let (state, run, connection_id) = workspace_connection_fixture(
+            ConnectedOAuthProvider::Slack,
+            ProviderBaseUrls {
+                slack: format!("http://{}/api", addr),
+                ..ProviderBaseUrls::default()
+            },
+        );
+        let context = json!({ "post": { "ts": "111.000", "channel": "C123" } });
+
+        let update = Node {
+            id: "slack-update".into(),
+            kind: "action".into(),
+            data: json!({
+                "params": slack_workspace_params(connection_id, json!({
+                    "operation": "update_message",
+                    "channel": "{{ post.channel }}",
+                    "messageTs": "{{ post.ts }}",
+                    "message": "Resolved"
+                }))
+            }),
+        };
+        let (output, _) = execute_messaging(&update, &context, &state, &run)
+            .await
+            .expect("update should succeed");
+        assert_eq!(output["updated"], true);
+        assert_eq!(output["ts"], "111.000");
+
+        let request = rx.recv().await.expect("update request");
+        assert!(request.path().ends_with("/api/chat.update"));
+        let body: Value = serde_json::from_slice(&request.body).unwrap();
+        assert_eq!(body["ts"], "111.000");
+        assert_eq!(body["channel"], "C123");
+        assert_eq!(body["text"], "Resolved");
+
+        let reaction = Node {
+            id: "slack-react".into(),
+            kind: "action".into(),
+            data: json!({
+                "params": slack_workspace_params(connection_id, json!({
+                    "operation": "add_reaction",
+                    "channel": "{{ post.channel }}",
+                    "messageTs": "{{ post.ts }}",
+                    "reaction": ":white_check_mark:"
+                }))
+            }),
+        };
+        let (output, _) = execute_messaging(&reaction, &context, &state, &run)
+            .await
+            .expect("reaction should succeed");
+        assert_eq!(output["reaction"], "white_check_mark");
+        assert_eq!(output["channel"], "C123");
+
+        let request = rx.recv().await.expect("reaction request");
+        assert!(request.path().ends_with("/api/reactions.add"));
+        let body: Value = serde_json::from_slice(&request.body).unwrap();
+        assert_eq!(body["name"], "white_check_mark");
+        assert_eq!(body["timestamp"], "111.000");
+
+        handle.abort();
+    }
+
+    #[tokio::test]
+    async fn slack_direct_message_looks_up_user_by_email() {
+        let call_count = Arc::new(AtomicUsize::new(0));
+        let call_count_clone = Arc::clone(&call_count);
+        let (addr, mut rx, handle) = spawn_stub_server(move || {
+            let body = match call_count_clone.fetch_add(1, Ordering::SeqCst) {
+                0 => r#"{"ok":true,"user":{"id":"U42","name":"alice"}}"#,
+                1 => r#"{"ok":true,"channel":{"id":"D42"}}"#,
+                _ => r#"{"ok":true,"ts":"555.666","channel":"D42"}"#,
+            };
+            Response::builder()
+                .status(StatusCode::OK)
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(body))
+                .unwrap()
+        })
+        .await;
+        let (state, run, connection_id) = workspace_connection_fixture(
+            ConnectedOAuthProvider::Slack,
+            ProviderBaseUrls {
+                slack: format!("http://{}/api", addr),
+                ..ProviderBaseUrls::default()
+            },
+        );
+        let node = Node {
+            id: "slack-dm".into(),
+            kind: "action".into(),
+            data: json!({
+                "params": slack_workspace_params(connection_id, json!({
+                    "operation": "send_direct_message",
+                    "email": "{{ trigger.email }}",
+                    "message": "Your ticket was closed"
+                }))
+            }),
+        };
+        let context = json!({ "trigger": { "email": "alice@example.com" } });
+
+        let (output, _) = execute_messaging(&node, &context, &state, &run)
+            .await
+            .expect("direct message should succeed");
+        assert_eq!(output["userId"], "U42");
+        assert_eq!(output["channel"], "D42");
+        assert_eq!(output["ts"], "555.666");
+
+        let lookup = rx.recv().await.expect("lookup request");
+        assert!(lookup.path().ends_with("/api/users.lookupByEmail"));
+        assert_eq!(
+            String::from_utf8(lookup.body).unwrap(),
+            "email=alice%40example.com"
+        );
+        let open = rx.recv().await.expect("open request");
+        assert!(open.path().ends_with("/api/conversations.open"));
+        let post = rx.recv().await.expect("post request");
+        let body: Value = serde_json::from_slice(&post.body).unwrap();
+        assert_eq!(body["channel"], "D42");
+
+        handle.abort();
+    }
+
+    #[tokio::test]
+    async fn slack_upload_file_completes_external_upload() {
+        let upload_url = Arc::new(Mutex::new(String::new()));
+        let upload_url_clone = Arc::clone(&upload_url);
+        let call_count = Arc::new(AtomicUsize::new(0));
+        let call_count_clone = Arc::clone(&call_count);
+        let (addr, mut rx, handle) = spawn_stub_server(move || {
+            let body = match call_count_clone.fetch_add(1, Ordering::SeqCst) {
+                0 => json!({
+                    "ok": true,
+                    "upload_url": upload_url_clone.lock().unwrap().clone(),
+                    "file_id": "F123"
+                })
+                .to_string(),
+                1 => "OK - 11".to_string(),
+                _ => r#"{"ok":true,"files":[{"id":"F123"}]}"#.to_string(),
+            };
+            Response::builder()
+                .status(StatusCode::OK)
+                .body(Body::from(body))
+                .unwrap()
+        })
+        .await;
+        *upload_url.lock().unwrap() = format!("http://{}/upload/F123", addr);
+        let (state, run, connection_id) = workspace_connection_fixture(
+            ConnectedOAuthProvider::Slack,
+            ProviderBaseUrls {
+                slack: format!("http://{}/api", addr),
+                ..ProviderBaseUrls::default()
+            },
+        );
+        let node = Node {
+            id: "slack-upload".into(),
+            kind: "action".into(),
+            data: json!({
+                "params": slack_workspace_params(connection_id, json!({
+                    "operation": "upload_file",
+                    "channel": "C123",
+                    "fileName": "report.csv",
+                    "fileContent": "a,b\n{{ row.a }},2",
+                    "initialComment": "Nightly report"
+                }))
+            }),
+        };
+        let context = json!({ "row": { "a": "1" } });
+
+        let (output, _) = execute_messaging(&node, &context, &state, &run)
+            .await
+            .expect("upload should succeed");
+        assert_eq!(output["uploaded"], true);
+        assert_eq!(output["fileId"], "F123");
+
+        let reserve = rx.recv().await.expect("reserve request");
+        assert!(reserve.path().ends_with("/api/files.getUploadURLExternal"));
+        assert_eq!(
+            String::from_utf8(reserve.body).unwrap(),
+            "filename=report.csv&length=7"
+        );
+        let upload = rx.recv().await.expect("upload request");
+        assert_eq!(upload.path(), "/upload/F123");
+        assert_eq!(upload.body, b"a,b\n1,2".to_vec());
+        let complete = rx.recv().await.expect("complete request");
+        assert!(complete
+            .path()
+            .ends_with("/api/files.completeUploadExternal"));
+        let body: Value = serde_json::from_slice(&complete.body).unwrap();
+        assert_eq!(body["channel_id"], "C123");
+        assert_eq!(body["files"][0]["id"], "F123");
+        assert_eq!(body["initial_comment"], "Nightly report");
+
+        handle.abort();
+    }
+
+    #[tokio::test]
+    async fn teams_reply_posts_to_message_replies() {
+        let (addr, mut rx, handle) = spawn_stub_server(|| {
+            Response::builder()
+                .status(StatusCode::CREATED)
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(json!({ "id": "reply-1" }).to_string()))
+                .unwrap()
+        })
+        .await;
+        let (state, run, connection_id) = workspace_connection_fixture(
+            ConnectedOAuthProvider::Microsoft,
+            ProviderBaseUrls {
+                microsoft_graph: format!("http://{}", addr),
+                ..ProviderBaseUrls::default()
+            },
+        );
+
+        let node = Node {
+            id: "teams-reply".into(),
+            kind: "action".into(),
+            data: json!({
+                "params": {
+                    "platform": "Teams",
+                    "operation": "reply_to_message",
+                    "connection": {
+                        "connectionScope": "workspace",
+                        "connectionId": connection_id
+                    },
+                    "teamId": "team-1",
+                    "channelId": "channel-1",
+                    "messageId": "{{ trigger.messageId }}",
+                    "message": "On it"
+                }
+            }),
+        };
+        let context = json!({ "trigger": { "messageId": "root-42" } });
+
+        let (output, _) = execute_messaging(&node, &context, &state, &run)
+            .await
+            .expect("reply succeeds");
+
+        assert_eq!(output["operation"], "reply_to_message");
+        assert_eq!(output["replyToId"], "root-42");
+        assert_eq!(output["messageId"], "reply-1");
+
+        let request = rx.recv().await.expect("graph request recorded");
+        handle.abort();
+        assert_eq!(
+            request.path(),
+            "/teams/team-1/channels/channel-1/messages/root-42/replies"
+        );
+    }
+
+    #[tokio::test]
+    async fn teams_list_messages_returns_recent_channel_messages() {
+        let provider = FakeProvider::start().await;
+        provider.respond(
+            Method::GET,
+            "/graph/teams/team-1/channels/channel-1/messages",
+            StatusCode::OK,
+            json!({
+                "value": [{
+                    "id": "m1",
+                    "messageType": "message",
+                    "createdDateTime": "2024-05-01T10:00:00Z",
+                    "body": { "contentType": "text", "content": "Deploy done" },
+                    "from": { "user": { "id": "u1", "displayName": "Ada" } }
+                }]
+            }),
+        );
+        let (state, run, connection_id) =
+            workspace_connection_fixture(ConnectedOAuthProvider::Microsoft, provider.base_urls());
+
+        let node = Node {
+            id: "teams-list".into(),
+            kind: "action".into(),
+            data: json!({
+                "params": {
+                    "platform": "Teams",
+                    "operation": "list_messages",
+                    "connection": {
+                        "connectionScope": "workspace",
+                        "connectionId": connection_id
+                    },
+                    "teamId": "team-1",
+                    "channelId": "channel-1",
+                    "limit": 5
+                }
+            }),
+        };
+
+        let (output, _) = execute_messaging(&node, &Value::Null, &state, &run)
+            .await
+            .expect("list succeeds");
+
+        let requests = provider.requests_to(
+            Method::GET,
+            "/graph/teams/team-1/channels/channel-1/messages",
+        );
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].query_param("$top").as_deref(), Some("5"));
+        assert_eq!(
+            requests[0].header("authorization").as_deref(),
+            Some("Bearer workspace-access")
+        );
+        assert_eq!(output["count"], 1);
+        assert_eq!(output["messages"][0]["id"], "m1");
+        assert_eq!(output["messages"][0]["bodyContent"], "Deploy done");
+        assert_eq!(output["messages"][0]["fromDisplayName"], "Ada");
+        assert_eq!(output["connectionScope"], "workspace");
+    }
+
+    #[tokio::test]
+    async fn teams_list_messages_rejects_incoming_webhook() {
+        let (state, run, _) = workspace_connection_fixture(
+            ConnectedOAuthProvider::Microsoft,
+            ProviderBaseUrls::default(),
+        );
+        let node = Node {
+            id: "teams-list-webhook".into(),
+            kind: "action".into(),
+            data: json!({
+                "params": {
+                    "platform": "Teams",
+                    "operation": "list_messages",
+                    "deliveryMethod": "Incoming Webhook",
+                    "webhookUrl": "https://example.com/hook"
+                }
+            }),
+        };
+
+        let err = execute_messaging(&node, &Value::Null, &state, &run)
+            .await
+            .expect_err("webhook delivery cannot read messages");
+        assert!(err.contains("requires a delegated Microsoft connection"));
+    }
 }
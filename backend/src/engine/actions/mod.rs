@@ -1,12 +1,24 @@
 mod asana;
 mod code;
+mod database;
 pub(crate) mod delay;
 mod email;
 pub(crate) mod formatter;
-mod google;
-mod http;
+mod github;
+pub(crate) mod google;
+mod google_calendar;
+mod google_drive;
+pub(crate) mod http;
+mod http_auth;
+mod http_tls;
 mod messaging;
 mod notion;
+mod outlook;
+mod python;
+mod queue;
+pub mod sandbox;
+pub(crate) mod sftp;
+mod store;
 
 use serde_json::{json, Value};
 use uuid::Uuid;
@@ -174,7 +186,7 @@ fn parse_input_value(raw: &str) -> Value {
     parse_flexible_value(raw)
 }
 
-fn evaluate_expression(expression: &str, context: &Value) -> Result<bool, String> {
+pub(crate) fn evaluate_expression(expression: &str, context: &Value) -> Result<bool, String> {
     let trimmed = expression.trim();
     if trimmed.is_empty() {
         return Err("Condition expression is required".to_string());
@@ -322,6 +334,65 @@ pub(crate) fn lookup_path(context: &Value, path: &str) -> Option<Value> {
     Some(current.clone())
 }
 
+pub(crate) fn read_required(
+    params: &Value,
+    key: &str,
+    label: &str,
+    context: &Value,
+) -> Result<String, String> {
+    read_optional(params, key, context).ok_or_else(|| format!("{label} is required"))
+}
+
+pub(crate) fn read_optional(params: &Value, key: &str, context: &Value) -> Option<String> {
+    params
+        .get(key)
+        .and_then(|v| v.as_str())
+        .map(|s| templ_str(s, context).trim().to_string())
+        .filter(|s| !s.is_empty())
+}
+
+pub(crate) fn read_limit(params: &Value, key: &str, context: &Value) -> Option<u32> {
+    match params.get(key)? {
+        Value::Number(num) => num.as_u64().and_then(|v| u32::try_from(v).ok()),
+        Value::String(raw) => templ_str(raw, context).trim().parse::<u32>().ok(),
+        _ => None,
+    }
+    .filter(|val| *val > 0)
+}
+
+/// Accepts either an array of addresses or a comma/semicolon separated
+/// string, templating each entry.
+pub(crate) fn read_addresses(
+    params: &Value,
+    key: &str,
+    context: &Value,
+) -> Result<Vec<String>, String> {
+    let raw: Vec<String> = match params.get(key) {
+        Some(Value::Array(items)) => items
+            .iter()
+            .filter_map(|item| item.as_str())
+            .map(|item| templ_str(item, context))
+            .collect(),
+        Some(Value::String(value)) => vec![templ_str(value, context)],
+        _ => Vec::new(),
+    };
+
+    let mut addresses = Vec::new();
+    for entry in raw {
+        for address in entry.split([',', ';']) {
+            let address = address.trim();
+            if address.is_empty() {
+                continue;
+            }
+            if !address.contains('@') {
+                return Err(format!("Invalid email address `{address}` in {key}"));
+            }
+            addresses.push(address.to_string());
+        }
+    }
+    Ok(addresses)
+}
+
 pub(crate) fn parse_flexible_value(raw: &str) -> Value {
     let trimmed = raw.trim();
     if trimmed.is_empty() {
@@ -426,7 +497,7 @@ pub(crate) async fn execute_action(
             )
             .await
         }
-        "email" => email::execute_email(node, context, state).await,
+        "email" => email::execute_email(node, context, state, run).await,
         // Backward/forward compatibility: provider-specific action types
         // like "teams", "slack", or "googlechat" are routed through the
         // messaging executor, which will detect the platform from params.
@@ -434,9 +505,68 @@ pub(crate) async fn execute_action(
             messaging::execute_messaging(node, context, state, run).await
         }
         "sheets" => google::execute_sheets(node, context, state, run).await,
+        "googlecalendar" => {
+            google_calendar::execute_google_calendar(node, context, state, run).await
+        }
+        "googledrive" => google_drive::execute_google_drive(node, context, state, run).await,
         "notion" => notion::execute_notion(node, context, state, run).await,
-        "code" => code::execute_code(node, context).await,
+        "outlook" => outlook::execute_outlook(node, context, state, run).await,
+        "code" => {
+            code::execute_code(
+                node,
+                context,
+                allowed_hosts,
+                disallowed_hosts,
+                default_deny,
+                is_prod,
+                state,
+                run,
+            )
+            .await
+        }
+        "database" => {
+            database::execute_database(
+                node,
+                context,
+                allowed_hosts,
+                disallowed_hosts,
+                default_deny,
+                is_prod,
+                state,
+                run,
+            )
+            .await
+        }
+        "python" => python::execute_python(node, context).await,
         "asana" => asana::execute_asana(node, context, state, run).await,
+        "github" => github::execute_github(node, context, state, run).await,
+        "store" => store::execute_store(node, context, state, run).await,
+        "sftp" => {
+            sftp::execute_sftp(
+                node,
+                context,
+                allowed_hosts,
+                disallowed_hosts,
+                default_deny,
+                is_prod,
+                state,
+                run,
+            )
+            .await
+        }
+        "queue" => {
+            queue::execute_queue(
+                node,
+                context,
+                allowed_hosts,
+                disallowed_hosts,
+                default_deny,
+                is_prod,
+                state,
+                run,
+            )
+            .await
+        }
         _ => Ok((
             json!({"skipped": true, "reason": "unsupported actionType"}),
             None,
@@ -14,6 +14,11 @@ use crate::state::AppState;
 use super::{ensure_run_membership, ensure_workspace_plan, resolve_connection_usage};
 
 const DEFAULT_QUERY_PAGE_SIZE: u32 = 25;
+const DEFAULT_PAGINATED_LIMIT: u32 = 500;
+const MAX_PAGINATED_LIMIT: u32 = 5000;
+const NOTION_MAX_PAGE_SIZE: u32 = 100;
+/// Notion accepts compound filters nested at most two levels deep.
+const MAX_FILTER_DEPTH: usize = 2;
 
 pub(crate) async fn execute_notion(
     node: &Node,
@@ -32,6 +37,7 @@ pub(crate) async fn execute_notion(
     let connection_usage = resolve_connection_usage(&params)?;
     let access_token = resolve_access_token(state, run, &connection_usage).await?;
     let client = &state.http_client;
+    let base_url = state.config.provider_base_urls.notion.as_str();
 
     match operation.as_str() {
         "create_database_row" => {
@@ -42,6 +48,7 @@ pub(crate) async fn execute_notion(
             }
             let response = notion::create_page(
                 client,
+                base_url,
                 &access_token,
                 Some(&database_id),
                 None,
@@ -57,10 +64,15 @@ pub(crate) async fn execute_notion(
             if properties.is_empty() {
                 return Err("At least one property value is required".to_string());
             }
-            let response =
-                notion::update_page(client, &access_token, &page_id, Value::Object(properties))
-                    .await
-                    .map_err(map_notion_error)?;
+            let response = notion::update_page(
+                client,
+                base_url,
+                &access_token,
+                &page_id,
+                Value::Object(properties),
+            )
+            .await
+            .map_err(map_notion_error)?;
             Ok((page_summary(&response), None))
         }
         "create_page" => {
@@ -81,6 +93,7 @@ pub(crate) async fn execute_notion(
             }
             let response = notion::create_page(
                 client,
+                base_url,
                 &access_token,
                 parent_database_id.as_deref(),
                 parent_page_id.as_deref(),
@@ -93,33 +106,140 @@ pub(crate) async fn execute_notion(
         "query_database" => {
             let database_id = read_required(&params, "databaseId", "Database ID", context)?;
             let filter = build_query_filter(&params, context)?;
-            let limit = read_limit(&params, context).unwrap_or(DEFAULT_QUERY_PAGE_SIZE);
-            let response = notion::query_database(
-                client,
-                &access_token,
-                &database_id,
-                filter,
-                None,
-                None,
-                Some(limit),
-            )
-            .await
-            .map_err(map_notion_error)?;
+            let sorts = build_query_sorts(&params, context)?;
+            let fetch_all = match params.get("fetchAll") {
+                Some(value) if !value.is_null() => parse_bool(value, context)?,
+                _ => false,
+            };
+            let start_cursor = read_optional(&params, "startCursor", context);
+
+            if !fetch_all {
+                let limit = read_limit(&params, context).unwrap_or(DEFAULT_QUERY_PAGE_SIZE);
+                let response = notion::query_database(
+                    client,
+                    base_url,
+                    &access_token,
+                    &database_id,
+                    filter,
+                    sorts,
+                    start_cursor.as_deref(),
+                    Some(limit),
+                )
+                .await
+                .map_err(map_notion_error)?;
+
+                let results = response
+                    .results
+                    .into_iter()
+                    .map(|page| page_summary(&page))
+                    .collect::<Vec<_>>();
+                return Ok((
+                    json!({
+                        "results": results,
+                        "has_more": response.has_more,
+                        "next_cursor": response.next_cursor
+                    }),
+                    None,
+                ));
+            }
+
+            // Follow `next_cursor` until the database is exhausted or `limit`
+            // results have been collected.
+            let limit = read_limit(&params, context)
+                .unwrap_or(DEFAULT_PAGINATED_LIMIT)
+                .min(MAX_PAGINATED_LIMIT) as usize;
+            let mut results = Vec::new();
+            let mut cursor = start_cursor;
+            let mut pages_fetched = 0u32;
+            let has_more = loop {
+                let remaining = limit - results.len();
+                let page_size = remaining.min(NOTION_MAX_PAGE_SIZE as usize) as u32;
+                let response = notion::query_database(
+                    client,
+                    base_url,
+                    &access_token,
+                    &database_id,
+                    filter.clone(),
+                    sorts.clone(),
+                    cursor.as_deref(),
+                    Some(page_size),
+                )
+                .await
+                .map_err(map_notion_error)?;
+                pages_fetched += 1;
+
+                results.extend(response.results.iter().take(remaining).map(page_summary));
+                cursor = response.next_cursor;
+                if !response.has_more || cursor.is_none() || results.len() >= limit {
+                    break response.has_more && cursor.is_some();
+                }
+            };
 
-            let results = response
-                .results
-                .into_iter()
-                .map(|page| page_summary(&page))
-                .collect::<Vec<_>>();
             Ok((
                 json!({
                     "results": results,
-                    "has_more": response.has_more,
-                    "next_cursor": response.next_cursor
+                    "has_more": has_more,
+                    "next_cursor": if has_more { cursor } else { None },
+                    "pages_fetched": pages_fetched
+                }),
+                None,
+            ))
+        }
+        "append_blocks" => {
+            let page_id = read_required(&params, "pageId", "Page ID", context)?;
+            let children = build_blocks(&params, context)?;
+            if children.is_empty() {
+                return Err("At least one block with content is required".to_string());
+            }
+            let mut block_ids = Vec::new();
+            for chunk in children.chunks(notion::MAX_BLOCK_CHILDREN_PER_REQUEST) {
+                let response =
+                    notion::append_block_children(client, base_url, &access_token, &page_id, chunk)
+                        .await
+                        .map_err(map_notion_error)?;
+                block_ids.extend(
+                    response
+                        .results
+                        .iter()
+                        .filter_map(|block| block.get("id").cloned()),
+                );
+            }
+            Ok((
+                json!({
+                    "page_id": page_id,
+                    "appended": children.len(),
+                    "block_ids": block_ids
                 }),
                 None,
             ))
         }
+        "archive_page" | "restore_page" => {
+            let page_id = read_required(&params, "pageId", "Page ID", context)?;
+            let archived = operation == "archive_page";
+            let response =
+                notion::set_page_archived(client, base_url, &access_token, &page_id, archived)
+                    .await
+                    .map_err(map_notion_error)?;
+            let mut output = page_summary(&response);
+            output["archived"] = response
+                .get("archived")
+                .cloned()
+                .unwrap_or(Value::Bool(archived));
+            Ok((output, None))
+        }
+        "retrieve_page" => {
+            let page_id = read_required(&params, "pageId", "Page ID", context)?;
+            let response = notion::retrieve_page(client, base_url, &access_token, &page_id)
+                .await
+                .map_err(map_notion_error)?;
+            let mut output = page_summary(&response);
+            output["archived"] = response
+                .get("archived")
+                .cloned()
+                .unwrap_or(Value::Bool(false));
+            output["properties"] = Value::Object(notion::flatten_page_properties(&response));
+            Ok((output, None))
+        }
         _ => Err("Unsupported Notion operation".to_string()),
     }
 }
@@ -207,11 +327,50 @@ fn read_limit(params: &Value, context: &Value) -> Option<u32> {
 }
 
 fn build_query_filter(params: &Value, context: &Value) -> Result<Option<Value>, String> {
-    let filter_obj = match params.get("filter") {
-        Some(value) => value.as_object().cloned().unwrap_or_default(),
-        None => return Ok(None),
-    };
+    match params.get("filter") {
+        Some(value) if !value.is_null() => build_filter_node(value, context, 0).map(Some),
+        _ => Ok(None),
+    }
+}
 
+/// Builds either a compound filter (`{"and": [...]}` / `{"or": [...]}`) or a
+/// single property condition.
+fn build_filter_node(value: &Value, context: &Value, depth: usize) -> Result<Value, String> {
+    let filter_obj = value
+        .as_object()
+        .ok_or_else(|| "Filter must be an object".to_string())?;
+
+    for combinator in ["and", "or"] {
+        let Some(children) = filter_obj.get(combinator) else {
+            continue;
+        };
+        if depth >= MAX_FILTER_DEPTH {
+            return Err(format!(
+                "Compound filters can be nested at most {MAX_FILTER_DEPTH} levels deep"
+            ));
+        }
+        let children = children
+            .as_array()
+            .ok_or_else(|| format!("`{combinator}` filter must be a list of conditions"))?;
+        if children.is_empty() {
+            return Err(format!(
+                "`{combinator}` filter needs at least one condition"
+            ));
+        }
+        let built = children
+            .iter()
+            .map(|child| build_filter_node(child, context, depth + 1))
+            .collect::<Result<Vec<_>, _>>()?;
+        return Ok(json!({ combinator: built }));
+    }
+
+    build_property_filter(filter_obj, context)
+}
+
+fn build_property_filter(
+    filter_obj: &Map<String, Value>,
+    context: &Value,
+) -> Result<Value, String> {
     let property = filter_obj
         .get("propertyId")
         .or_else(|| filter_obj.get("property"))
@@ -242,10 +401,62 @@ fn build_query_filter(params: &Value, context: &Value) -> Result<Option<Value>,
         _ => return Err("Unsupported filter operator".to_string()),
     };
 
-    Ok(Some(json!({
+    Ok(json!({
         "property": property,
         property_type: filter
-    })))
+    }))
+}
+
+/// Sorts are a list of `{property | timestamp, direction}` entries, where
+/// `timestamp` is `created_time` or `last_edited_time`.
+fn build_query_sorts(params: &Value, context: &Value) -> Result<Option<Value>, String> {
+    let entries = match params.get("sorts") {
+        Some(Value::Array(entries)) => entries,
+        Some(Value::Null) | None => return Ok(None),
+        Some(_) => return Err("Sorts must be a list".to_string()),
+    };
+
+    let mut sorts = Vec::new();
+    for entry in entries {
+        let direction = match entry
+            .get("direction")
+            .and_then(|v| v.as_str())
+            .map(|s| s.trim().to_ascii_lowercase())
+            .as_deref()
+        {
+            None | Some("") | Some("ascending") | Some("asc") => "ascending",
+            Some("descending") | Some("desc") => "descending",
+            Some(other) => return Err(format!("Unsupported sort direction `{other}`")),
+        };
+        let timestamp = entry
+            .get("timestamp")
+            .and_then(|v| v.as_str())
+            .map(|s| s.trim().to_ascii_lowercase())
+            .filter(|s| !s.is_empty());
+        if let Some(timestamp) = timestamp {
+            if timestamp != "created_time" && timestamp != "last_edited_time" {
+                return Err(format!("Unsupported sort timestamp `{timestamp}`"));
+            }
+            sorts.push(json!({ "timestamp": timestamp, "direction": direction }));
+            continue;
+        }
+        let property = entry
+            .get("propertyId")
+            .or_else(|| entry.get("property"))
+            .and_then(|v| v.as_str())
+            .map(|s| templ_str(s, context).trim().to_string())
+            .filter(|s| !s.is_empty());
+        match property {
+            Some(property) => sorts.push(json!({ "property": property, "direction": direction })),
+            None => continue,
+        }
+    }
+
+    Ok(if sorts.is_empty() {
+        None
+    } else {
+        Some(Value::Array(sorts))
+    })
 }
 
 fn build_filter_equals(
@@ -377,6 +588,66 @@ fn build_properties(params: &Value, context: &Value) -> Result<Map<String, Value
     Ok(output)
 }
 
+/// Turns the `blocks` list (`{type, text, checked}`) into Notion block
+/// objects. List-style blocks whose rendered text spans several lines become
+/// one item per non-empty line, so a templated list expands naturally.
+fn build_blocks(params: &Value, context: &Value) -> Result<Vec<Value>, String> {
+    let entries = params
+        .get("blocks")
+        .and_then(|v| v.as_array())
+        .ok_or_else(|| "Blocks must be a list".to_string())?;
+
+    let mut blocks = Vec::new();
+    for entry in entries {
+        let block_type = entry
+            .get("type")
+            .and_then(|v| v.as_str())
+            .map(|s| s.trim().to_ascii_lowercase())
+            .unwrap_or_else(|| "paragraph".to_string());
+        let text = entry
+            .get("text")
+            .and_then(|v| v.as_str())
+            .map(|s| templ_str(s, context))
+            .unwrap_or_default();
+        let checked = match entry.get("checked") {
+            Some(value) if !value.is_null() => parse_bool(value, context)?,
+            _ => false,
+        };
+
+        match block_type.as_str() {
+            "paragraph" | "heading_1" | "heading_2" | "heading_3" => {
+                let text = text.trim();
+                if text.is_empty() {
+                    continue;
+                }
+                blocks.push(text_block(&block_type, text, None));
+            }
+            "bulleted_list_item" | "numbered_list_item" | "to_do" => {
+                let is_todo = block_type == "to_do";
+                for line in text.lines().map(str::trim).filter(|line| !line.is_empty()) {
+                    blocks.push(text_block(&block_type, line, is_todo.then_some(checked)));
+                }
+            }
+            other => return Err(format!("Unsupported Notion block type `{other}`")),
+        }
+    }
+    Ok(blocks)
+}
+
+fn text_block(block_type: &str, text: &str, checked: Option<bool>) -> Value {
+    let mut content = json!({
+        "rich_text": [{ "type": "text", "text": { "content": text } }]
+    });
+    if let Some(checked) = checked {
+        content["checked"] = Value::Bool(checked);
+    }
+    json!({
+        "object": "block",
+        "type": block_type,
+        block_type: content
+    })
+}
+
 fn should_skip_property(value: &Value) -> bool {
     match value {
         Value::Null => true,
@@ -577,334 +848,27 @@ fn map_notion_error(err: NotionError) -> String {
 mod tests {
     use super::*;
 
-    use async_trait::async_trait;
-    use httpmock::MockServer;
-    use once_cell::sync::Lazy;
-    use reqwest::Client;
-    use std::sync::Arc;
-    use std::sync::MutexGuard;
-    use time::{Duration as TimeDuration, OffsetDateTime};
-
-    use crate::config::{
-        Config, OAuthProviderConfig, OAuthSettings, StripeSettings, DEFAULT_WORKSPACE_MEMBER_LIMIT,
-        DEFAULT_WORKSPACE_MONTHLY_RUN_LIMIT, RUNAWAY_LIMIT_5MIN,
-    };
-    use crate::db::mock_db::{MockDb, NoopWorkflowRepository, NoopWorkspaceRepository};
-    use crate::db::mock_stripe_event_log_repository::MockStripeEventLogRepository;
-    use crate::db::oauth_token_repository::{NewUserOAuthToken, UserOAuthTokenRepository};
-    use crate::db::workspace_connection_repository::NoopWorkspaceConnectionRepository;
-    use crate::models::oauth_token::UserOAuthToken;
-    use crate::services::oauth::account_service::OAuthAccountService;
-    use crate::services::oauth::github::mock_github_oauth::MockGitHubOAuth;
-    use crate::services::oauth::google::mock_google_oauth::MockGoogleOAuth;
-    use crate::services::oauth::workspace_service::WorkspaceOAuthService;
-    use crate::services::smtp_mailer::MockMailer;
-    use crate::state::{test_pg_pool, AppState};
-    use crate::utils::encryption::encrypt_secret;
-    use crate::utils::jwt::JwtKeys;
-
-    static ENV_LOCK: Lazy<std::sync::Mutex<()>> = Lazy::new(|| std::sync::Mutex::new(()));
-
-    struct EnvGuard {
-        key: &'static str,
-        previous: Option<String>,
-        _lock: MutexGuard<'static, ()>,
-    }
-
-    impl EnvGuard {
-        fn set(key: &'static str, value: String) -> Self {
-            let lock = ENV_LOCK.lock().expect("env mutex poisoned");
-            let previous = std::env::var(key).ok();
-            std::env::set_var(key, value);
-            Self {
-                key,
-                previous,
-                _lock: lock,
-            }
-        }
-    }
-
-    impl Drop for EnvGuard {
-        fn drop(&mut self) {
-            if let Some(previous) = self.previous.take() {
-                std::env::set_var(self.key, previous);
-            } else {
-                std::env::remove_var(self.key);
-            }
-        }
-    }
-
-    fn test_config() -> Arc<Config> {
-        Arc::new(Config {
-            database_url: String::new(),
-            frontend_origin: "http://localhost".into(),
-            admin_origin: "http://localhost".into(),
-            oauth: OAuthSettings {
-                google: OAuthProviderConfig {
-                    client_id: "stub".into(),
-                    client_secret: "stub".into(),
-                    redirect_uri: "http://localhost".into(),
-                },
-                microsoft: OAuthProviderConfig {
-                    client_id: "stub".into(),
-                    client_secret: "stub".into(),
-                    redirect_uri: "http://localhost".into(),
-                },
-                slack: OAuthProviderConfig {
-                    client_id: "stub".into(),
-                    client_secret: "stub".into(),
-                    redirect_uri: "http://localhost".into(),
-                },
-                asana: OAuthProviderConfig {
-                    client_id: "stub".into(),
-                    client_secret: "stub".into(),
-                    redirect_uri: "http://localhost".into(),
-                },
-                notion: OAuthProviderConfig {
-                    client_id: "stub".into(),
-                    client_secret: "stub".into(),
-                    redirect_uri: "http://localhost".into(),
-                },
-                token_encryption_key: vec![1u8; 32],
-            },
-            api_secrets_encryption_key: vec![1u8; 32],
-            stripe: StripeSettings {
-                client_id: "stub".into(),
-                secret_key: "stub".into(),
-                webhook_secret: "0123456789abcdef0123456789ABCDEF".into(),
-            },
-            auth_cookie_secure: true,
-            webhook_secret: "0123456789abcdef0123456789ABCDEF".into(),
-            jwt_issuer: "test-issuer".into(),
-            jwt_audience: "test-audience".into(),
-            workspace_member_limit: DEFAULT_WORKSPACE_MEMBER_LIMIT,
-            workspace_monthly_run_limit: DEFAULT_WORKSPACE_MONTHLY_RUN_LIMIT,
-            runaway_limit_5min: RUNAWAY_LIMIT_5MIN,
-        })
-    }
-
-    fn test_jwt_keys() -> Arc<JwtKeys> {
-        Arc::new(
-            JwtKeys::from_secret("0123456789abcdef0123456789abcdef")
-                .expect("test JWT secret should be valid"),
-        )
-    }
-
-    fn sample_run(user_id: Uuid) -> WorkflowRun {
-        let now = OffsetDateTime::now_utc();
-        WorkflowRun {
-            id: Uuid::new_v4(),
-            user_id,
-            workflow_id: Uuid::new_v4(),
-            workspace_id: None,
-            snapshot: json!({}),
-            status: "pending".to_string(),
-            error: None,
-            idempotency_key: None,
-            started_at: now,
-            resume_at: now,
-            finished_at: None,
-            created_at: now,
-            updated_at: now,
-        }
-    }
-
-    fn test_state(oauth_accounts: Arc<OAuthAccountService>, http_client: Arc<Client>) -> AppState {
-        AppState {
-            db: Arc::new(MockDb::default()),
-            workflow_repo: Arc::new(NoopWorkflowRepository),
-            workspace_repo: Arc::new(NoopWorkspaceRepository),
-            workspace_connection_repo: Arc::new(NoopWorkspaceConnectionRepository),
-            stripe_event_log_repo: Arc::new(MockStripeEventLogRepository::default()),
-            db_pool: test_pg_pool(),
-            mailer: Arc::new(MockMailer::default()),
-            google_oauth: Arc::new(MockGoogleOAuth::default()),
-            github_oauth: Arc::new(MockGitHubOAuth::default()),
-            oauth_accounts,
-            workspace_oauth: WorkspaceOAuthService::test_stub(),
-            stripe: Arc::new(crate::services::stripe::MockStripeService::new()),
-            http_client,
-            config: test_config(),
-            worker_id: Arc::new("worker".to_string()),
-            worker_lease_seconds: 30,
-            jwt_keys: test_jwt_keys(),
-        }
-    }
-
-    fn oauth_service_with_token(user_id: Uuid) -> (Arc<OAuthAccountService>, Uuid) {
-        #[derive(Clone)]
-        struct StaticRepo {
-            record: UserOAuthToken,
-        }
-
-        #[async_trait]
-        impl UserOAuthTokenRepository for StaticRepo {
-            async fn upsert_token(
-                &self,
-                _new_token: NewUserOAuthToken,
-            ) -> Result<UserOAuthToken, sqlx::Error> {
-                Ok(self.record.clone())
-            }
-
-            async fn find_by_id(
-                &self,
-                token_id: Uuid,
-            ) -> Result<Option<UserOAuthToken>, sqlx::Error> {
-                if token_id == self.record.id {
-                    Ok(Some(self.record.clone()))
-                } else {
-                    Ok(None)
-                }
-            }
-
-            async fn find_by_user_and_provider(
-                &self,
-                user_id: Uuid,
-                provider: ConnectedOAuthProvider,
-            ) -> Result<Option<UserOAuthToken>, sqlx::Error> {
-                if provider == self.record.provider && user_id == self.record.user_id {
-                    Ok(Some(self.record.clone()))
-                } else {
-                    Ok(None)
-                }
-            }
-
-            async fn delete_token(
-                &self,
-                _user_id: Uuid,
-                _provider: ConnectedOAuthProvider,
-            ) -> Result<(), sqlx::Error> {
-                Ok(())
-            }
+    use axum::http::{Method, StatusCode};
 
-            async fn list_tokens_for_user(
-                &self,
-                user_id: Uuid,
-            ) -> Result<Vec<UserOAuthToken>, sqlx::Error> {
-                if user_id == self.record.user_id {
-                    Ok(vec![self.record.clone()])
-                } else {
-                    Ok(vec![])
-                }
-            }
-
-            async fn mark_shared(
-                &self,
-                _user_id: Uuid,
-                _provider: ConnectedOAuthProvider,
-                _is_shared: bool,
-            ) -> Result<UserOAuthToken, sqlx::Error> {
-                Ok(self.record.clone())
-            }
-
-            async fn list_by_user_and_provider(
-                &self,
-                user_id: Uuid,
-                provider: ConnectedOAuthProvider,
-            ) -> Result<Vec<UserOAuthToken>, sqlx::Error> {
-                if provider == self.record.provider && user_id == self.record.user_id {
-                    Ok(vec![self.record.clone()])
-                } else {
-                    Ok(vec![])
-                }
-            }
-        }
-
-        let key = Arc::new(vec![1u8; 32]);
-        let encrypted_access = encrypt_secret(&key, "access-token").unwrap();
-        let encrypted_refresh = encrypt_secret(&key, "refresh-token").unwrap();
-        let now = OffsetDateTime::now_utc();
-
-        let record_id = Uuid::new_v4();
-        let record = UserOAuthToken {
-            id: record_id,
-            user_id,
-            workspace_id: None,
-            provider: ConnectedOAuthProvider::Notion,
-            access_token: encrypted_access,
-            refresh_token: encrypted_refresh,
-            expires_at: now + TimeDuration::hours(2),
-            account_email: "notion@example.com".into(),
-            metadata: serde_json::json!({}),
-            is_shared: false,
-            created_at: now,
-            updated_at: now,
-        };
-
-        let repo = Arc::new(StaticRepo { record });
-        let workspace_repo: Arc<
-            dyn crate::db::workspace_connection_repository::WorkspaceConnectionRepository,
-        > = Arc::new(NoopWorkspaceConnectionRepository);
-        let client = Arc::new(Client::new());
-        let settings = OAuthSettings {
-            google: OAuthProviderConfig {
-                client_id: "stub".into(),
-                client_secret: "stub".into(),
-                redirect_uri: "http://localhost".into(),
-            },
-            microsoft: OAuthProviderConfig {
-                client_id: "stub".into(),
-                client_secret: "stub".into(),
-                redirect_uri: "http://localhost".into(),
-            },
-            slack: OAuthProviderConfig {
-                client_id: "stub".into(),
-                client_secret: "stub".into(),
-                redirect_uri: "http://localhost".into(),
-            },
-            asana: OAuthProviderConfig {
-                client_id: "stub".into(),
-                client_secret: "stub".into(),
-                redirect_uri: "http://localhost".into(),
-            },
-            notion: OAuthProviderConfig {
-                client_id: "stub".into(),
-                client_secret: "stub".into(),
-                redirect_uri: "http://localhost".into(),
-            },
-            token_encryption_key: (*key).clone(),
-        };
-
-        (
-            Arc::new(OAuthAccountService::new(
-                repo,
-                workspace_repo,
-                key,
-                client,
-                &settings,
-            )),
-            record_id,
-        )
-    }
+    use crate::test_support::{personal_connection_fixture, FakeProvider};
 
     #[tokio::test]
     async fn create_database_row_builds_properties_payload() {
-        let server = MockServer::start();
-        let _env = EnvGuard::set("NOTION_API_BASE_URL", server.url(""));
-        let mock = server.mock(|when, then| {
-            when.method(httpmock::Method::POST)
-                .path("/pages")
-                .json_body(json!({
-                    "parent": { "database_id": "db-1" },
-                    "properties": {
-                        "prop-title": {
-                            "title": [{ "text": { "content": "Hello" } }]
-                        },
-                        "prop-number": { "number": 42.0 }
-                    }
-                }));
-            then.status(200).json_body(json!({
+        let provider = FakeProvider::start().await;
+        provider.respond(
+            Method::POST,
+            "/notion/pages",
+            StatusCode::OK,
+            json!({
                 "id": "page-1",
                 "url": "https://notion.so/page-1",
                 "created_time": "2024-01-01T00:00:00Z",
                 "last_edited_time": "2024-01-01T00:00:00Z"
-            }));
-        });
+            }),
+        );
 
-        let user_id = Uuid::new_v4();
-        let (oauth_accounts, connection_id) = oauth_service_with_token(user_id);
-        let state = test_state(oauth_accounts, Arc::new(Client::new()));
-        let run = sample_run(user_id);
+        let (state, run, connection_id) =
+            personal_connection_fixture(ConnectedOAuthProvider::Notion, provider.base_urls());
 
         let node = Node {
             id: "notion-create".into(),
@@ -927,7 +891,20 @@ mod tests {
             .await
             .expect("create page");
 
-        mock.assert();
+        let requests = provider.requests_to(Method::POST, "/notion/pages");
+        assert_eq!(requests.len(), 1);
+        assert_eq!(
+            requests[0].json(),
+            json!({
+                "parent": { "database_id": "db-1" },
+                "properties": {
+                    "prop-title": {
+                        "title": [{ "text": { "content": "Hello" } }]
+                    },
+                    "prop-number": { "number": 42.0 }
+                }
+            })
+        );
         assert_eq!(
             output.get("page_id").and_then(|v| v.as_str()),
             Some("page-1")
@@ -936,19 +913,12 @@ mod tests {
 
     #[tokio::test]
     async fn query_database_builds_filter_payload() {
-        let server = MockServer::start();
-        let _env = EnvGuard::set("NOTION_API_BASE_URL", server.url(""));
-        let mock = server.mock(|when, then| {
-            when.method(httpmock::Method::POST)
-                .path("/databases/db-2/query")
-                .json_body(json!({
-                    "filter": {
-                        "property": "Status",
-                        "select": { "equals": "Done" }
-                    },
-                    "page_size": 5
-                }));
-            then.status(200).json_body(json!({
+        let provider = FakeProvider::start().await;
+        provider.respond(
+            Method::POST,
+            "/notion/databases/db-2/query",
+            StatusCode::OK,
+            json!({
                 "results": [{
                     "id": "page-2",
                     "url": "https://notion.so/page-2",
@@ -957,13 +927,11 @@ mod tests {
                 }],
                 "has_more": false,
                 "next_cursor": null
-            }));
-        });
+            }),
+        );
 
-        let user_id = Uuid::new_v4();
-        let (oauth_accounts, connection_id) = oauth_service_with_token(user_id);
-        let state = test_state(oauth_accounts, Arc::new(Client::new()));
-        let run = sample_run(user_id);
+        let (state, run, connection_id) =
+            personal_connection_fixture(ConnectedOAuthProvider::Notion, provider.base_urls());
 
         let node = Node {
             id: "notion-query".into(),
@@ -989,7 +957,18 @@ mod tests {
             .await
             .expect("query database");
 
-        mock.assert();
+        let requests = provider.requests_to(Method::POST, "/notion/databases/db-2/query");
+        assert_eq!(requests.len(), 1);
+        assert_eq!(
+            requests[0].json(),
+            json!({
+                "filter": {
+                    "property": "Status",
+                    "select": { "equals": "Done" }
+                },
+                "page_size": 5
+            })
+        );
         let results = output.get("results").and_then(|v| v.as_array()).unwrap();
         assert_eq!(results.len(), 1);
         assert_eq!(
@@ -997,4 +976,217 @@ mod tests {
             Some("page-2")
         );
     }
+
+    #[tokio::test]
+    async fn query_database_fetch_all_follows_cursor_with_compound_filter_and_sorts() {
+        let provider = FakeProvider::start().await;
+        provider.respond_with(Method::POST, "/notion/databases/db-3/query", |request| {
+            let body = if request.json().get("start_cursor").is_none() {
+                json!({
+                    "results": [{ "id": "p-1" }, { "id": "p-2" }],
+                    "has_more": true,
+                    "next_cursor": "cursor-2"
+                })
+            } else {
+                json!({
+                    "results": [{ "id": "p-3" }],
+                    "has_more": true,
+                    "next_cursor": "cursor-3"
+                })
+            };
+            (StatusCode::OK, body)
+        });
+        let (state, run, connection_id) =
+            personal_connection_fixture(ConnectedOAuthProvider::Notion, provider.base_urls());
+
+        let node = Node {
+            id: "notion-query-all".into(),
+            kind: "action".into(),
+            data: json!({
+                "params": {
+                    "operation": "query_database",
+                    "connectionScope": "personal",
+                    "connectionId": connection_id.to_string(),
+                    "databaseId": "db-3",
+                    "fetchAll": true,
+                    "limit": "3",
+                    "filter": {
+                        "or": [
+                            { "propertyId": "Status", "propertyType": "select", "value": "Done" },
+                            {
+                                "and": [
+                                    {
+                                        "propertyId": "Owner",
+                                        "operator": "contains",
+                                        "value": "{{ owner }}"
+                                    },
+                                    { "propertyId": "Urgent", "propertyType": "checkbox", "value": "true" }
+                                ]
+                            }
+                        ]
+                    },
+                    "sorts": [
+                        { "property": "Priority", "direction": "desc" },
+                        { "timestamp": "created_time" }
+                    ]
+                }
+            }),
+        };
+
+        let (output, _) = execute_notion(&node, &json!({ "owner": "Ana" }), &state, &run)
+            .await
+            .expect("query database");
+
+        let expected_filter = json!({
+            "or": [
+                { "property": "Status", "select": { "equals": "Done" } },
+                {
+                    "and": [
+                        { "property": "Owner", "rich_text": { "contains": "Ana" } },
+                        { "property": "Urgent", "checkbox": { "equals": true } }
+                    ]
+                }
+            ]
+        });
+        let expected_sorts = json!([
+            { "property": "Priority", "direction": "descending" },
+            { "timestamp": "created_time", "direction": "ascending" }
+        ]);
+        let bodies: Vec<Value> = provider
+            .requests_to(Method::POST, "/notion/databases/db-3/query")
+            .iter()
+            .map(|request| request.json())
+            .collect();
+        assert_eq!(
+            bodies,
+            vec![
+                json!({
+                    "filter": expected_filter.clone(),
+                    "sorts": expected_sorts.clone(),
+                    "page_size": 3
+                }),
+                json!({
+                    "filter": expected_filter,
+                    "sorts": expected_sorts,
+                    "start_cursor": "cursor-2",
+                    "page_size": 1
+                }),
+            ]
+        );
+        let ids: Vec<_> = output["results"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|page| page["page_id"].as_str().unwrap().to_string())
+            .collect();
+        assert_eq!(ids, vec!["p-1", "p-2", "p-3"]);
+        assert_eq!(output["has_more"], true);
+        assert_eq!(output["next_cursor"], "cursor-3");
+        assert_eq!(output["pages_fetched"], 2);
+    }
+
+    #[test]
+    fn compound_filters_reject_excessive_nesting() {
+        let leaf = json!({ "propertyId": "Name", "value": "x" });
+        let params = json!({
+            "filter": { "and": [{ "or": [{ "and": [leaf] }] }] }
+        });
+        let err = build_query_filter(&params, &json!({})).expect_err("too deep");
+        assert!(err.contains("nested at most 2"), "unexpected error: {err}");
+    }
+
+    #[test]
+    fn build_blocks_expands_list_lines_and_skips_empty_text() {
+        let params = json!({
+            "blocks": [
+                { "type": "heading_2", "text": "Summary for {{ name }}" },
+                { "type": "paragraph", "text": "   " },
+                { "type": "bulleted_list_item", "text": "{{ items }}" },
+                { "type": "to_do", "text": "Follow up", "checked": "true" }
+            ]
+        });
+        let context = json!({ "name": "Acme", "items": "one\n\ntwo" });
+
+        let blocks = build_blocks(&params, &context).expect("blocks");
+
+        assert_eq!(blocks.len(), 4);
+        assert_eq!(blocks[0]["type"], "heading_2");
+        assert_eq!(
+            blocks[0]["heading_2"]["rich_text"][0]["text"]["content"],
+            "Summary for Acme"
+        );
+        assert_eq!(
+            blocks[1]["bulleted_list_item"]["rich_text"][0]["text"]["content"],
+            "one"
+        );
+        assert_eq!(
+            blocks[2]["bulleted_list_item"]["rich_text"][0]["text"]["content"],
+            "two"
+        );
+        assert_eq!(blocks[3]["to_do"]["checked"], true);
+        assert!(blocks[0]["heading_2"].get("checked").is_none());
+
+        let bad = json!({ "blocks": [{ "type": "table", "text": "x" }] });
+        assert!(build_blocks(&bad, &context).is_err());
+    }
+
+    #[tokio::test]
+    async fn retrieve_page_flattens_properties() {
+        let provider = FakeProvider::start().await;
+        provider.respond(
+            Method::GET,
+            "/notion/pages/page-9",
+            StatusCode::OK,
+            json!({
+                "id": "page-9",
+                "url": "https://notion.so/page-9",
+                "archived": false,
+                "properties": {
+                    "Name": { "type": "title", "title": [{ "plain_text": "Launch" }] },
+                    "Status": { "type": "status", "status": { "name": "Done" } },
+                    "Tags": {
+                        "type": "multi_select",
+                        "multi_select": [{ "name": "a" }, { "name": "b" }]
+                    },
+                    "Due": { "type": "date", "date": { "start": "2026-10-01", "end": null } }
+                }
+            }),
+        );
+
+        let (state, run, connection_id) =
+            personal_connection_fixture(ConnectedOAuthProvider::Notion, provider.base_urls());
+        let node = Node {
+            id: "notion-retrieve".into(),
+            kind: "action".into(),
+            data: json!({
+                "params": {
+                    "operation": "retrieve_page",
+                    "connectionScope": "personal",
+                    "connectionId": connection_id.to_string(),
+                    "pageId": "page-9"
+                }
+            }),
+        };
+
+        let (output, _) = execute_notion(&node, &json!({}), &state, &run)
+            .await
+            .expect("retrieve page");
+
+        let requests = provider.requests_to(Method::GET, "/notion/pages/page-9");
+        assert_eq!(requests.len(), 1);
+        assert_eq!(
+            requests[0].header("authorization").as_deref(),
+            Some("Bearer access-token")
+        );
+        assert_eq!(output["archived"], false);
+        assert_eq!(
+            output["properties"],
+            json!({
+                "Name": "Launch",
+                "Status": "Done",
+                "Tags": ["a", "b"],
+                "Due": "2026-10-01"
+            })
+        );
+    }
 }
@@ -0,0 +1,460 @@
+use serde_json::{json, Map, Value};
+use time::{format_description::well_known::Rfc3339, Duration, OffsetDateTime};
+use uuid::Uuid;
+
+use crate::engine::graph::Node;
+use crate::engine::templating::templ_str;
+use crate::models::oauth_token::ConnectedOAuthProvider;
+use crate::models::workflow_run::WorkflowRun;
+use crate::services::microsoft::{outlook, MicrosoftGraphError};
+use crate::services::oauth::account_service::OAuthAccountError;
+use crate::services::oauth::workspace_service::WorkspaceOAuthError;
+use crate::state::AppState;
+
+use super::{
+    ensure_run_membership, ensure_workspace_plan, read_addresses, read_limit, read_optional,
+    read_required, resolve_connection_usage,
+};
+
+const DEFAULT_EVENT_LIST_LIMIT: u32 = 10;
+const DEFAULT_EVENT_LIST_DAYS: i64 = 7;
+const MAX_EVENT_LIST_DAYS: i64 = 90;
+const DEFAULT_TIME_ZONE: &str = "UTC";
+
+pub(crate) async fn execute_outlook(
+    node: &Node,
+    context: &Value,
+    state: &AppState,
+    run: &WorkflowRun,
+) -> Result<(Value, Option<String>), String> {
+    let params = node.data.get("params").cloned().unwrap_or(Value::Null);
+
+    let operation = params
+        .get("operation")
+        .and_then(|v| v.as_str())
+        .map(|s| s.trim().to_ascii_lowercase())
+        .unwrap_or_default();
+
+    let connection_usage = resolve_connection_usage(&params)?;
+    let access_token = resolve_access_token(state, run, &connection_usage).await?;
+    let client = &state.http_client;
+    let base_url = state.config.provider_base_urls.microsoft_graph.as_str();
+
+    match operation.as_str() {
+        "send_mail" => {
+            let message = build_mail_message(&params, context)?;
+            let save_to_sent_items = params
+                .get("saveToSentItems")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(true);
+            let recipients = message["toRecipients"].as_array().map_or(0, Vec::len);
+            outlook::send_mail(client, base_url, &access_token, message, save_to_sent_items)
+                .await
+                .map_err(map_graph_error)?;
+            Ok((
+                json!({
+                    "sent": true,
+                    "service": "Outlook",
+                    "operation": "send_mail",
+                    "recipients": recipients,
+                }),
+                None,
+            ))
+        }
+        "create_event" => {
+            let event = build_event_payload(&params, context, true)?;
+            let calendar_id = read_optional(&params, "calendarId", context);
+            let response = outlook::create_event(
+                client,
+                base_url,
+                &access_token,
+                calendar_id.as_deref(),
+                &event,
+            )
+            .await
+            .map_err(map_graph_error)?;
+            Ok((event_summary(&response), None))
+        }
+        "update_event" => {
+            let event_id = read_required(&params, "eventId", "Event ID", context)?;
+            let patch = build_event_payload(&params, context, false)?;
+            if patch.as_object().is_none_or(Map::is_empty) {
+                return Err("At least one event field is required to update an event".into());
+            }
+            let response =
+                outlook::update_event(client, base_url, &access_token, &event_id, &patch)
+                    .await
+                    .map_err(map_graph_error)?;
+            Ok((event_summary(&response), None))
+        }
+        "list_events" => {
+            let limit = read_limit(&params, "limit", context)
+                .unwrap_or(DEFAULT_EVENT_LIST_LIMIT)
+                .min(outlook::MAX_PAGE_SIZE);
+            let days = read_limit(&params, "days", context)
+                .map(i64::from)
+                .unwrap_or(DEFAULT_EVENT_LIST_DAYS)
+                .clamp(1, MAX_EVENT_LIST_DAYS);
+            let start = OffsetDateTime::now_utc();
+            let end = start + Duration::days(days);
+            let events = outlook::list_calendar_view(
+                client,
+                base_url,
+                &access_token,
+                &start.format(&Rfc3339).unwrap_or_default(),
+                &end.format(&Rfc3339).unwrap_or_default(),
+                limit,
+            )
+            .await
+            .map_err(map_graph_error)?;
+            let events = events.iter().map(event_summary).collect::<Vec<_>>();
+            Ok((
+                json!({
+                    "service": "Outlook",
+                    "operation": "list_events",
+                    "count": events.len(),
+                    "events": events,
+                }),
+                None,
+            ))
+        }
+        _ => Err("Unsupported Outlook operation".to_string()),
+    }
+}
+
+async fn resolve_access_token(
+    state: &AppState,
+    run: &WorkflowRun,
+    usage: &super::NodeConnectionUsage,
+) -> Result<String, String> {
+    match usage {
+        super::NodeConnectionUsage::Workspace(info) => {
+            let workspace_id = run.workspace_id.ok_or_else(|| {
+                "This workflow is not associated with a workspace. Promote the Microsoft connection to the workspace or switch to a personal connection.".to_string()
+            })?;
+            ensure_run_membership(state, workspace_id, run.user_id).await?;
+            ensure_workspace_plan(state, workspace_id).await?;
+
+            let connection = state
+                .workspace_oauth
+                .ensure_valid_workspace_token(info.connection_id)
+                .await
+                .map_err(map_workspace_oauth_error)?;
+
+            if connection.workspace_id != workspace_id {
+                return Err(
+                    "The selected Microsoft connection belongs to another workspace".into(),
+                );
+            }
+            if connection.provider != ConnectedOAuthProvider::Microsoft {
+                return Err("Selected connection is not a Microsoft connection".into());
+            }
+
+            Ok(connection.access_token)
+        }
+        super::NodeConnectionUsage::User(info) => {
+            let connection_id = info.connection_id.as_ref().ok_or_else(|| {
+                "Personal OAuth connections require an explicit connectionId. Please select a specific OAuth connection from your integrations.".to_string()
+            })?;
+
+            let parsed = Uuid::parse_str(connection_id).map_err(|_| {
+                "Personal connectionId must be a valid UUID. Please select a valid OAuth connection.".to_string()
+            })?;
+
+            let token = state
+                .oauth_accounts
+                .ensure_valid_access_token_for_connection(run.user_id, parsed)
+                .await
+                .map_err(map_oauth_error)?;
+
+            if token.provider != ConnectedOAuthProvider::Microsoft {
+                return Err("Selected connection is not a Microsoft connection".into());
+            }
+
+            Ok(token.access_token)
+        }
+    }
+}
+
+fn recipients(addresses: &[String]) -> Value {
+    Value::Array(
+        addresses
+            .iter()
+            .map(|address| json!({ "emailAddress": { "address": address } }))
+            .collect(),
+    )
+}
+
+fn body_content_type(params: &Value) -> &'static str {
+    match params
+        .get("bodyType")
+        .and_then(|v| v.as_str())
+        .map(|s| s.trim().to_ascii_lowercase())
+        .as_deref()
+    {
+        Some("html") => "HTML",
+        _ => "Text",
+    }
+}
+
+fn build_mail_message(params: &Value, context: &Value) -> Result<Value, String> {
+    let to = read_addresses(params, "to", context)?;
+    if to.is_empty() {
+        return Err("At least one recipient is required".to_string());
+    }
+    let cc = read_addresses(params, "cc", context)?;
+    let bcc = read_addresses(params, "bcc", context)?;
+    let subject = read_required(params, "subject", "Subject", context)?;
+    let body = params
+        .get("body")
+        .and_then(|v| v.as_str())
+        .map(|s| templ_str(s, context))
+        .unwrap_or_default();
+
+    let mut message = json!({
+        "subject": subject,
+        "body": { "contentType": body_content_type(params), "content": body },
+        "toRecipients": recipients(&to),
+    });
+    if !cc.is_empty() {
+        message["ccRecipients"] = recipients(&cc);
+    }
+    if !bcc.is_empty() {
+        message["bccRecipients"] = recipients(&bcc);
+    }
+    Ok(message)
+}
+
+/// Builds a Graph `event` resource. On create, subject/start/end are
+/// required; on update only the supplied fields are included.
+fn build_event_payload(params: &Value, context: &Value, creating: bool) -> Result<Value, String> {
+    let mut event = Map::new();
+    let time_zone =
+        read_optional(params, "timeZone", context).unwrap_or_else(|| DEFAULT_TIME_ZONE.to_string());
+
+    match read_optional(params, "subject", context) {
+        Some(subject) => {
+            event.insert("subject".into(), Value::String(subject));
+        }
+        None if creating => return Err("Subject is required".to_string()),
+        None => {}
+    }
+
+    if let Some(body) = read_optional(params, "body", context) {
+        event.insert(
+            "body".into(),
+            json!({ "contentType": body_content_type(params), "content": body }),
+        );
+    }
+
+    for (key, label) in [("start", "Start time"), ("end", "End time")] {
+        match read_optional(params, key, context) {
+            Some(value) => {
+                event.insert(
+                    key.into(),
+                    json!({ "dateTime": value, "timeZone": time_zone }),
+                );
+            }
+            None if creating => return Err(format!("{label} is required")),
+            None => {}
+        }
+    }
+
+    if let Some(location) = read_optional(params, "location", context) {
+        event.insert("location".into(), json!({ "displayName": location }));
+    }
+
+    let attendee_type = match params
+        .get("attendeeType")
+        .and_then(|v| v.as_str())
+        .map(|s| s.trim().to_ascii_lowercase())
+        .as_deref()
+    {
+        Some("optional") => "optional",
+        _ => "required",
+    };
+    let attendees = read_addresses(params, "attendees", context)?;
+    if !attendees.is_empty() {
+        event.insert(
+            "attendees".into(),
+            Value::Array(
+                attendees
+                    .iter()
+                    .map(|address| {
+                        json!({
+                            "emailAddress": { "address": address },
+                            "type": attendee_type,
+                        })
+                    })
+                    .collect(),
+            ),
+        );
+    }
+
+    if let Some(online) = params.get("teamsMeeting").and_then(|v| v.as_bool()) {
+        event.insert("isOnlineMeeting".into(), Value::Bool(online));
+        if online {
+            event.insert(
+                "onlineMeetingProvider".into(),
+                Value::String("teamsForBusiness".into()),
+            );
+        }
+    }
+
+    Ok(Value::Object(event))
+}
+
+fn event_summary(event: &Value) -> Value {
+    json!({
+        "eventId": event.get("id").cloned().unwrap_or(Value::Null),
+        "subject": event.get("subject").cloned().unwrap_or(Value::Null),
+        "start": event.get("start").cloned().unwrap_or(Value::Null),
+        "end": event.get("end").cloned().unwrap_or(Value::Null),
+        "location": event
+            .get("location")
+            .and_then(|l| l.get("displayName"))
+            .cloned()
+            .unwrap_or(Value::Null),
+        "attendees": event
+            .get("attendees")
+            .and_then(|a| a.as_array())
+            .map(|items| {
+                items
+                    .iter()
+                    .filter_map(|a| a.get("emailAddress").and_then(|e| e.get("address")))
+                    .cloned()
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default(),
+        "teamsMeetingUrl": event
+            .get("onlineMeeting")
+            .and_then(|m| m.get("joinUrl"))
+            .cloned()
+            .unwrap_or(Value::Null),
+        "webLink": event.get("webLink").cloned().unwrap_or(Value::Null),
+    })
+}
+
+fn map_oauth_error(err: OAuthAccountError) -> String {
+    match err {
+        OAuthAccountError::NotFound => "No Microsoft OAuth connection found".to_string(),
+        OAuthAccountError::TokenRevoked { .. } => {
+            "The Microsoft connection was revoked. Reconnect in Settings -> Integrations."
+                .to_string()
+        }
+        other => format!("Microsoft OAuth error: {other}"),
+    }
+}
+
+fn map_workspace_oauth_error(err: WorkspaceOAuthError) -> String {
+    match err {
+        WorkspaceOAuthError::Forbidden => {
+            "You no longer have access to this workspace connection.".to_string()
+        }
+        WorkspaceOAuthError::NotFound => "Microsoft workspace connection not found.".to_string(),
+        WorkspaceOAuthError::SlackInstallRequired => {
+            "Slack connections must be installed at workspace scope.".to_string()
+        }
+        WorkspaceOAuthError::OAuth(inner) => map_oauth_error(inner),
+        WorkspaceOAuthError::Database(err) => format!("Failed to load workspace connection: {err}"),
+        WorkspaceOAuthError::Encryption(err) => {
+            format!("Failed to decrypt workspace connection: {err}")
+        }
+    }
+}
+
+fn map_graph_error(err: MicrosoftGraphError) -> String {
+    match err {
+        MicrosoftGraphError::UnexpectedStatus { status, .. }
+            if status.as_u16() == 401 || status.as_u16() == 403 =>
+        {
+            "Microsoft authentication failed. Reconnect the integration.".to_string()
+        }
+        MicrosoftGraphError::UnexpectedStatus { status, message } => {
+            format!("Microsoft Graph error ({}): {}", status.as_u16(), message)
+        }
+        other => format!("Microsoft Graph error: {other}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mail_message_splits_and_validates_recipients() {
+        let params = json!({
+            "to": "ada@example.com; {{ trigger.email }}",
+            "cc": ["ops@example.com"],
+            "subject": "Report for {{ trigger.name }}",
+            "body": "<b>Done</b>",
+            "bodyType": "HTML"
+        });
+        let context = json!({ "trigger": { "email": "grace@example.com", "name": "May" } });
+
+        let message = build_mail_message(&params, &context).expect("message");
+        assert_eq!(message["subject"], "Report for May");
+        assert_eq!(message["body"]["contentType"], "HTML");
+        assert_eq!(
+            message["toRecipients"][1]["emailAddress"]["address"],
+            "grace@example.com"
+        );
+        assert_eq!(
+            message["ccRecipients"][0]["emailAddress"]["address"],
+            "ops@example.com"
+        );
+        assert!(message.get("bccRecipients").is_none());
+
+        let invalid = json!({ "to": "not-an-address", "subject": "Hi" });
+        assert!(build_mail_message(&invalid, &Value::Null)
+            .unwrap_err()
+            .contains("Invalid email address"));
+    }
+
+    #[test]
+    fn create_event_requires_times_and_adds_teams_meeting() {
+        let params = json!({
+            "subject": "Sync",
+            "start": "2024-06-01T10:00:00",
+            "end": "2024-06-01T10:30:00",
+            "timeZone": "Europe/Berlin",
+            "attendees": "ada@example.com,grace@example.com",
+            "attendeeType": "optional",
+            "teamsMeeting": true
+        });
+        let event = build_event_payload(&params, &Value::Null, true).expect("event");
+        assert_eq!(event["start"]["timeZone"], "Europe/Berlin");
+        assert_eq!(event["attendees"][1]["type"], "optional");
+        assert_eq!(event["isOnlineMeeting"], true);
+        assert_eq!(event["onlineMeetingProvider"], "teamsForBusiness");
+
+        let missing_end = json!({ "subject": "Sync", "start": "2024-06-01T10:00:00" });
+        assert_eq!(
+            build_event_payload(&missing_end, &Value::Null, true).unwrap_err(),
+            "End time is required"
+        );
+    }
+
+    #[test]
+    fn update_event_only_includes_supplied_fields() {
+        let params = json!({ "eventId": "evt-1", "location": "Room 4" });
+        let patch = build_event_payload(&params, &Value::Null, false).expect("patch");
+        assert_eq!(patch, json!({ "location": { "displayName": "Room 4" } }));
+    }
+
+    #[test]
+    fn event_summary_surfaces_teams_join_url() {
+        let summary = event_summary(&json!({
+            "id": "evt-1",
+            "subject": "Sync",
+            "attendees": [{ "emailAddress": { "address": "ada@example.com" } }],
+            "onlineMeeting": { "joinUrl": "https://teams.microsoft.com/l/meetup-join/1" }
+        }));
+        assert_eq!(summary["eventId"], "evt-1");
+        assert_eq!(summary["attendees"], json!(["ada@example.com"]));
+        assert_eq!(
+            summary["teamsMeetingUrl"],
+            "https://teams.microsoft.com/l/meetup-join/1"
+        );
+    }
+}
@@ -0,0 +1,228 @@
+//! Python-flavoured code node backed by an embedded Starlark interpreter.
+//!
+//! Scripts get the same `inputs`/`context` bindings and `outputs` mapping as
+//! the JavaScript `code` node. Starlark has no filesystem, network or clock
+//! access and iterates dicts in insertion order, so a script's result depends
+//! only on its inputs. The body runs inside a function, so `return` works as
+//! it does in the JavaScript node.
+
+use std::time::Instant;
+
+use serde_json::Value;
+use starlark::codemap::FileSpanRef;
+use starlark::environment::{GlobalsBuilder, LibraryExtension, Module};
+use starlark::eval::{BeforeStmtFuncDyn, Evaluator};
+use starlark::syntax::{AstModule, Dialect};
+
+use super::code::{build_inputs, map_outputs, SandboxLimits};
+use super::sandbox::{run_in_sandbox, SandboxJob};
+use crate::engine::graph::Node;
+
+const ENTRYPOINT: &str = "__dsentr_main";
+
+/// Enforces the statement budget and deadline; returning an error from the
+/// hook aborts evaluation.
+struct StatementBudget {
+    limits: SandboxLimits,
+    started: Instant,
+    executed: u64,
+}
+
+impl<'a, 'e: 'a> BeforeStmtFuncDyn<'a, 'e> for StatementBudget {
+    fn call<'v>(
+        &mut self,
+        _span: FileSpanRef,
+        _eval: &mut Evaluator<'v, 'a, 'e>,
+    ) -> starlark::Result<()> {
+        self.executed += 1;
+        let abort = if self.executed > self.limits.max_instructions {
+            Some("Python code exceeded its statement budget".to_string())
+        } else if self.started.elapsed() > self.limits.timeout {
+            Some(format!(
+                "Python code timed out after {} ms",
+                self.limits.timeout.as_millis()
+            ))
+        } else {
+            None
+        };
+        match abort {
+            Some(message) => Err(starlark::Error::new_other(anyhow::anyhow!(message))),
+            None => Ok(()),
+        }
+    }
+}
+
+pub(crate) async fn execute_python(
+    node: &Node,
+    context: &Value,
+) -> Result<(Value, Option<String>), String> {
+    let params = node.data.get("params").cloned().unwrap_or(Value::Null);
+    let code_raw = params
+        .get("code")
+        .and_then(|v| v.as_str())
+        .filter(|s| !s.trim().is_empty())
+        .ok_or_else(|| "Custom code is required".to_string())?;
+
+    let (inputs_value, input_keys) = build_inputs(&params, context)?;
+    let script = wrap_script(code_raw);
+    let limits = SandboxLimits::from_params(&params);
+    let job = SandboxJob::Python {
+        script,
+        inputs: inputs_value,
+        context: context.clone(),
+    };
+    let result_value = run_in_sandbox(job, limits, None).await?;
+
+    let outputs = map_outputs(&params, result_value, &input_keys)?;
+
+    Ok((outputs, None))
+}
+
+/// Indents the user's code into a function body so it can `return` a
+/// result, then calls it as the module's final expression.
+fn wrap_script(code: &str) -> String {
+    let mut script = format!("def {ENTRYPOINT}():\n");
+    let mut has_statement = false;
+    for line in code.trim_matches('\n').lines() {
+        if !line.trim().is_empty() {
+            has_statement = true;
+        }
+        script.push_str("    ");
+        script.push_str(line);
+        script.push('\n');
+    }
+    if !has_statement {
+        script.push_str("    pass\n");
+    }
+    script.push_str(&format!("{ENTRYPOINT}()\n"));
+    script
+}
+
+/// Evaluates a wrapped script; runs inside the sandbox child.
+pub(super) fn evaluate_script(
+    script: String,
+    inputs: &Value,
+    context: &Value,
+    limits: SandboxLimits,
+) -> Result<Value, String> {
+    let ast =
+        AstModule::parse("code.star", script, &Dialect::Standard).map_err(format_starlark_error)?;
+    let globals = GlobalsBuilder::extended_by(&[
+        LibraryExtension::StructType,
+        LibraryExtension::Map,
+        LibraryExtension::Filter,
+        LibraryExtension::Partial,
+        LibraryExtension::Json,
+        LibraryExtension::SetType,
+    ])
+    .build();
+
+    let module = Module::new();
+    let heap = module.heap();
+    module.set("inputs", heap.alloc(inputs));
+    module.set("context", heap.alloc(context));
+
+    let mut eval = Evaluator::new(&module);
+    // The statement hook is the interpreter's only interruption point.
+    let budget: Box<dyn BeforeStmtFuncDyn> = Box::new(StatementBudget {
+        limits,
+        started: Instant::now(),
+        executed: 0,
+    });
+    eval.before_stmt_for_dap(budget.into());
+    let result = eval
+        .eval_module(ast, &globals)
+        .map_err(format_starlark_error)?;
+    if result.is_none() {
+        return Ok(Value::Null);
+    }
+    result
+        .to_json_value()
+        .map_err(|e| format!("Python result is not JSON serializable: {e}"))
+}
+
+fn format_starlark_error(err: starlark::Error) -> String {
+    let message = err.to_string();
+    if message.trim().is_empty() {
+        "Python execution error".to_string()
+    } else {
+        message
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    async fn run_python(params: Value) -> Result<Value, String> {
+        let node = Node {
+            id: "python-1".into(),
+            kind: "action".into(),
+            data: json!({ "params": params }),
+        };
+        execute_python(
+            &node,
+            &json!({ "trigger": { "name": "Ada", "tags": ["a", "b"] } }),
+        )
+        .await
+        .map(|(outputs, _)| outputs)
+    }
+
+    #[tokio::test]
+    async fn shares_input_and_output_semantics_with_the_js_node() {
+        let outputs = run_python(json!({
+            "code": "names = [t.upper() for t in context[\"trigger\"][\"tags\"]]\nreturn {\"greeting\": \"hi \" + inputs[\"name\"], \"count\": inputs[\"count\"] + 1, \"tags\": names}",
+            "inputs": [
+                { "key": "name", "value": "{{trigger.name}}" },
+                { "key": "count", "value": "41" }
+            ],
+            "outputs": [
+                { "key": "total", "value": "count" },
+                { "key": "firstTag", "value": "tags[0]" }
+            ]
+        }))
+        .await
+        .expect("script runs");
+        assert_eq!(outputs, json!({ "total": 42, "firstTag": "A" }));
+
+        let unmapped = run_python(json!({ "code": "return 3 * 7" }))
+            .await
+            .expect("script runs");
+        assert_eq!(unmapped, json!({ "result": 21 }));
+
+        let encoded = run_python(json!({
+            "code": "return json.decode(json.encode({\"ok\": True}))"
+        }))
+        .await
+        .expect("json module is available");
+        assert_eq!(encoded, json!({ "ok": true }));
+    }
+
+    #[tokio::test]
+    async fn errors_and_runaway_scripts_are_reported() {
+        let syntax = run_python(json!({ "code": "return (" })).await.unwrap_err();
+        assert!(syntax.contains("code.star"), "{syntax}");
+
+        let no_io = run_python(json!({ "code": "return open(\"/etc/passwd\")" }))
+            .await
+            .unwrap_err();
+        assert!(no_io.contains("open"), "{no_io}");
+
+        let timed_out = run_python(json!({
+            "code": "n = 0\nfor i in range(1000000000):\n    n += i\nreturn n",
+            "timeoutMs": 50
+        }))
+        .await
+        .unwrap_err();
+        assert!(timed_out.contains("timed out"), "{timed_out}");
+
+        let hungry = run_python(json!({
+            "code": "keep = []\nfor i in range(1000000000):\n    keep.append(\"x\" * 1024 + str(i))\nreturn len(keep)",
+            "timeoutMs": 30000
+        }))
+        .await
+        .unwrap_err();
+        assert!(hungry.contains("memory limit"), "{hungry}");
+    }
+}
@@ -0,0 +1,464 @@
+//! `queue` action: publishes a message to RabbitMQ (AMQP 0.9.1) or to a
+//! Redis Stream or Pub/Sub channel.
+//!
+//! The broker URL comes from the secret store and is not templated. The
+//! broker host goes through the workflow egress policy before connecting.
+//! RabbitMQ publishes wait for a publisher confirm, so a successful node
+//! means the broker has taken responsibility for the message.
+
+use serde_json::{json, Value};
+
+use super::http::enforce_host_egress_policy;
+use super::{read_limit, read_optional, read_required};
+use crate::engine::graph::Node;
+use crate::engine::templating::templ_str;
+use crate::models::workflow_run::WorkflowRun;
+use crate::services::amqp::{self, OutgoingMessage};
+use crate::services::redis_streams::{self, RedisSession};
+use crate::state::AppState;
+
+/// Largest message body (or sum of stream field sizes) the node will send.
+pub(crate) const MAX_MESSAGE_BYTES: usize = 1024 * 1024;
+const MAX_HEADERS: usize = 50;
+
+#[derive(Debug, PartialEq)]
+enum QueueRequest {
+    RabbitMq {
+        url: String,
+        message: OutgoingMessage,
+    },
+    RedisStream {
+        url: String,
+        stream: String,
+        fields: Vec<(String, String)>,
+        max_len: Option<u64>,
+    },
+    RedisPublish {
+        url: String,
+        channel: String,
+        message: String,
+    },
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn execute_queue(
+    node: &Node,
+    context: &Value,
+    allowed_hosts: &[String],
+    disallowed_hosts: &[String],
+    default_deny: bool,
+    is_prod: bool,
+    state: &AppState,
+    run: &WorkflowRun,
+) -> Result<(Value, Option<String>), String> {
+    let params = node.data.get("params").cloned().unwrap_or(Value::Null);
+    let request = parse_request(&params, context)?;
+
+    let (protocol, scheme, (host, port)) = match &request {
+        QueueRequest::RabbitMq { url, .. } => (
+            "AMQP",
+            "amqp",
+            amqp::broker_address(url).map_err(|e| e.to_string())?,
+        ),
+        QueueRequest::RedisStream { url, .. } | QueueRequest::RedisPublish { url, .. } => (
+            "Redis",
+            "redis",
+            redis_streams::redis_address(url).map_err(|e| e.to_string())?,
+        ),
+    };
+    enforce_host_egress_policy(
+        protocol,
+        &format!("{scheme}://{host}:{port}"),
+        &host,
+        node,
+        allowed_hosts,
+        disallowed_hosts,
+        default_deny,
+        is_prod,
+        state,
+        run,
+    )
+    .await?;
+
+    let outputs = match request {
+        QueueRequest::RabbitMq { url, message } => {
+            amqp::publish(&url, &message)
+                .await
+                .map_err(|e| e.to_string())?;
+            json!({
+                "service": "rabbitmq",
+                "exchange": message.exchange,
+                "routingKey": message.routing_key,
+                "messageId": message.message_id,
+                "size": message.payload.len(),
+                "confirmed": true,
+            })
+        }
+        QueueRequest::RedisStream {
+            url,
+            stream,
+            fields,
+            max_len,
+        } => {
+            let mut session = RedisSession::connect(&url)
+                .await
+                .map_err(|e| e.to_string())?;
+            let id = session
+                .xadd(&stream, &fields, max_len)
+                .await
+                .map_err(|e| e.to_string())?;
+            json!({ "service": "redis", "mode": "stream", "stream": stream, "id": id })
+        }
+        QueueRequest::RedisPublish {
+            url,
+            channel,
+            message,
+        } => {
+            let mut session = RedisSession::connect(&url)
+                .await
+                .map_err(|e| e.to_string())?;
+            let receivers = session
+                .publish(&channel, &message)
+                .await
+                .map_err(|e| e.to_string())?;
+            json!({
+                "service": "redis",
+                "mode": "pubsub",
+                "channel": channel,
+                "receivers": receivers,
+            })
+        }
+    };
+    Ok((outputs, None))
+}
+
+fn parse_request(params: &Value, context: &Value) -> Result<QueueRequest, String> {
+    let service = params
+        .get("service")
+        .and_then(Value::as_str)
+        .map(|s| s.trim().to_ascii_lowercase())
+        .unwrap_or_default();
+    let url = params
+        .get("connectionUrl")
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .ok_or_else(|| "Broker connection secret is required".to_string())?
+        .to_string();
+
+    match service.as_str() {
+        "rabbitmq" | "amqp" => {
+            let exchange = read_optional(params, "exchange", context).unwrap_or_default();
+            let routing_key = read_optional(params, "routingKey", context).unwrap_or_default();
+            if exchange.is_empty() && routing_key.is_empty() {
+                return Err(
+                    "Routing key (the queue name) is required when publishing to the default exchange"
+                        .to_string(),
+                );
+            }
+            let payload = read_message(params, context)?;
+            let content_type = read_optional(params, "contentType", context).or_else(|| {
+                Some(
+                    if serde_json::from_str::<Value>(&payload).is_ok() {
+                        "application/json"
+                    } else {
+                        "text/plain"
+                    }
+                    .to_string(),
+                )
+            });
+            Ok(QueueRequest::RabbitMq {
+                url,
+                message: OutgoingMessage {
+                    exchange,
+                    routing_key,
+                    payload: payload.into_bytes(),
+                    content_type,
+                    message_id: read_optional(params, "messageId", context),
+                    headers: read_pairs(params, "headers", context)?,
+                    persistent: read_flag(params, "persistent", context, true),
+                    mandatory: read_flag(params, "mandatory", context, false),
+                },
+            })
+        }
+        "redis" => {
+            let mode = read_optional(params, "mode", context)
+                .unwrap_or_else(|| "stream".to_string())
+                .to_ascii_lowercase();
+            match mode.as_str() {
+                "stream" => {
+                    let stream = read_required(params, "stream", "Stream key", context)?;
+                    let mut fields = read_pairs(params, "fields", context)?;
+                    if fields.is_empty() {
+                        fields.push(("payload".to_string(), read_message(params, context)?));
+                    }
+                    let size: usize = fields.iter().map(|(k, v)| k.len() + v.len()).sum();
+                    if size > MAX_MESSAGE_BYTES {
+                        return Err(too_large());
+                    }
+                    Ok(QueueRequest::RedisStream {
+                        url,
+                        stream,
+                        fields,
+                        max_len: read_limit(params, "maxLen", context).map(u64::from),
+                    })
+                }
+                "pubsub" | "publish" => Ok(QueueRequest::RedisPublish {
+                    url,
+                    channel: read_required(params, "channel", "Channel", context)?,
+                    message: read_message(params, context)?,
+                }),
+                other => Err(format!("Unsupported Redis mode `{other}`")),
+            }
+        }
+        "" => Err("Queue service is required".to_string()),
+        other => Err(format!("Unsupported queue service `{other}`")),
+    }
+}
+
+fn read_message(params: &Value, context: &Value) -> Result<String, String> {
+    let message = match params.get("message") {
+        Some(Value::String(raw)) => templ_str(raw, context),
+        Some(Value::Null) | None => String::new(),
+        // Structured messages are sent as JSON.
+        Some(other) => other.to_string(),
+    };
+    if message.trim().is_empty() {
+        return Err("Message is required".to_string());
+    }
+    if message.len() > MAX_MESSAGE_BYTES {
+        return Err(too_large());
+    }
+    Ok(message)
+}
+
+/// Reads a `[{ key, value }]` list, templating values. Entries without a
+/// key are skipped.
+fn read_pairs(params: &Value, key: &str, context: &Value) -> Result<Vec<(String, String)>, String> {
+    let Some(items) = params.get(key).and_then(Value::as_array) else {
+        return Ok(Vec::new());
+    };
+    let pairs: Vec<(String, String)> = items
+        .iter()
+        .filter_map(|item| {
+            let name = item.get("key")?.as_str()?.trim();
+            if name.is_empty() {
+                return None;
+            }
+            let value = item
+                .get("value")
+                .and_then(Value::as_str)
+                .map(|v| templ_str(v, context))
+                .unwrap_or_default();
+            Some((name.to_string(), value))
+        })
+        .collect();
+    if pairs.len() > MAX_HEADERS {
+        return Err(format!(
+            "At most {MAX_HEADERS} entries are allowed in {key}"
+        ));
+    }
+    Ok(pairs)
+}
+
+fn read_flag(params: &Value, key: &str, context: &Value, default: bool) -> bool {
+    match params.get(key) {
+        Some(Value::Bool(flag)) => *flag,
+        Some(Value::String(raw)) => match templ_str(raw, context).trim().to_ascii_lowercase() {
+            s if s == "true" => true,
+            s if s == "false" => false,
+            _ => default,
+        },
+        _ => default,
+    }
+}
+
+fn too_large() -> String {
+    format!("Message exceeds the {} KB limit", MAX_MESSAGE_BYTES / 1024)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::mock_db::NoopWorkspaceRepository;
+    use crate::test_support::{oauth_service_with_token, sample_run, test_state};
+    use reqwest::Client;
+    use std::sync::Arc;
+    use uuid::Uuid;
+
+    fn context() -> Value {
+        json!({ "trigger": { "order": { "id": 42, "total": "19.99" } } })
+    }
+
+    #[test]
+    fn rabbitmq_requests_template_the_message_and_headers() {
+        let request = parse_request(
+            &json!({
+                "service": "RabbitMQ",
+                "connectionUrl": "amqps://svc:pw@mq.example.com/prod",
+                "exchange": "orders",
+                "routingKey": "order.{{trigger.order.id}}",
+                "message": "{\"id\": {{trigger.order.id}}}",
+                "headers": [
+                    { "key": "x-total", "value": "{{trigger.order.total}}" },
+                    { "key": " ", "value": "ignored" }
+                ],
+                "mandatory": "true"
+            }),
+            &context(),
+        )
+        .unwrap();
+        assert_eq!(
+            request,
+            QueueRequest::RabbitMq {
+                url: "amqps://svc:pw@mq.example.com/prod".into(),
+                message: OutgoingMessage {
+                    exchange: "orders".into(),
+                    routing_key: "order.42".into(),
+                    payload: br#"{"id": 42}"#.to_vec(),
+                    content_type: Some("application/json".into()),
+                    message_id: None,
+                    headers: vec![("x-total".into(), "19.99".into())],
+                    persistent: true,
+                    mandatory: true,
+                },
+            }
+        );
+
+        let err = parse_request(
+            &json!({
+                "service": "rabbitmq",
+                "connectionUrl": "amqp://mq.example.com",
+                "message": "hi"
+            }),
+            &context(),
+        )
+        .unwrap_err();
+        assert!(err.contains("Routing key"), "{err}");
+    }
+
+    #[test]
+    fn redis_requests_build_stream_fields_or_pubsub_messages() {
+        let stream = parse_request(
+            &json!({
+                "service": "redis",
+                "connectionUrl": "redis://cache.example.com",
+                "stream": "orders",
+                "fields": [{ "key": "id", "value": "{{trigger.order.id}}" }],
+                "maxLen": "1000"
+            }),
+            &context(),
+        )
+        .unwrap();
+        assert_eq!(
+            stream,
+            QueueRequest::RedisStream {
+                url: "redis://cache.example.com".into(),
+                stream: "orders".into(),
+                fields: vec![("id".into(), "42".into())],
+                max_len: Some(1000),
+            }
+        );
+
+        let payload_only = parse_request(
+            &json!({
+                "service": "redis",
+                "connectionUrl": "redis://cache.example.com",
+                "stream": "orders",
+                "message": { "id": 1 }
+            }),
+            &context(),
+        )
+        .unwrap();
+        assert!(matches!(
+            payload_only,
+            QueueRequest::RedisStream { fields, .. } if fields == vec![("payload".to_string(), "{\"id\":1}".to_string())]
+        ));
+
+        let pubsub = parse_request(
+            &json!({
+                "service": "redis",
+                "mode": "pubsub",
+                "connectionUrl": "redis://cache.example.com",
+                "channel": "alerts",
+                "message": "order {{trigger.order.id}} paid"
+            }),
+            &context(),
+        )
+        .unwrap();
+        assert_eq!(
+            pubsub,
+            QueueRequest::RedisPublish {
+                url: "redis://cache.example.com".into(),
+                channel: "alerts".into(),
+                message: "order 42 paid".into(),
+            }
+        );
+
+        let oversized = parse_request(
+            &json!({
+                "service": "redis",
+                "mode": "pubsub",
+                "connectionUrl": "redis://cache.example.com",
+                "channel": "alerts",
+                "message": "x".repeat(MAX_MESSAGE_BYTES + 1)
+            }),
+            &context(),
+        )
+        .unwrap_err();
+        assert!(oversized.contains("limit"), "{oversized}");
+    }
+
+    #[tokio::test]
+    async fn blocked_broker_hosts_fail_before_connecting() {
+        let user_id = Uuid::new_v4();
+        let (oauth, _) = oauth_service_with_token(user_id, "queue@example.com");
+        let state = test_state(
+            oauth,
+            Arc::new(Client::new()),
+            Arc::new(NoopWorkspaceRepository),
+        );
+        let node = Node {
+            id: "queue-1".into(),
+            kind: "action".into(),
+            data: json!({
+                "actionType": "queue",
+                "params": {
+                    "service": "redis",
+                    "connectionUrl": "redis://10.0.0.5:6379",
+                    "stream": "orders",
+                    "message": "hi"
+                }
+            }),
+        };
+        let err = execute_queue(
+            &node,
+            &json!({}),
+            &[],
+            &[],
+            false,
+            true,
+            &state,
+            &sample_run(user_id),
+        )
+        .await
+        .unwrap_err();
+        assert!(err.contains("ssrf_hardening"), "{err}");
+
+        let mut socket = node.clone();
+        socket.data["params"]["connectionUrl"] = json!("redis+unix:///tmp/redis.sock");
+        let err = execute_queue(
+            &socket,
+            &json!({}),
+            &[],
+            &[],
+            false,
+            true,
+            &state,
+            &sample_run(user_id),
+        )
+        .await
+        .unwrap_err();
+        assert!(err.contains("Unix socket"), "{err}");
+    }
+}
@@ -0,0 +1,277 @@
+//! Runs code-node scripts in a child process so their memory can be capped
+//! by the kernel instead of by bookkeeping in this process.
+//!
+//! The child is this same executable started with [`CHILD_FLAG`]. It reads
+//! one [`SandboxRequest`] line from stdin, lowers its `RLIMIT_DATA` to the
+//! current usage plus the script's budget, evaluates the script and writes
+//! [`ChildMessage`] lines to stdout. `fetch` calls are sent back to the
+//! parent, which performs them under the egress policy and answers on stdin.
+//! A script that outgrows the cap fails to allocate and the child aborts,
+//! which the parent reports as a memory limit error.
+
+use std::io::{BufRead, Write};
+use std::process::Stdio;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::process::Command;
+
+use super::code::{FetchBridge, SandboxLimits};
+
+/// Argument that makes the executable act as a sandbox child.
+pub const CHILD_FLAG: &str = "--code-sandbox";
+/// Extra time the parent allows past the script's own deadline before it
+/// kills a child that stopped responding.
+const KILL_GRACE: Duration = Duration::from_secs(2);
+/// What the child prints when an allocation fails: the Rust runtime's abort
+/// message, and the panic raised by the arena allocator Starlark uses.
+const ALLOCATION_FAILURES: [&str; 2] = ["memory allocation of", "out of memory"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "language", rename_all = "camelCase")]
+pub(super) enum SandboxJob {
+    JavaScript {
+        script: String,
+    },
+    Python {
+        script: String,
+        inputs: Value,
+        context: Value,
+    },
+}
+
+impl SandboxJob {
+    fn label(&self) -> &'static str {
+        match self {
+            SandboxJob::JavaScript { .. } => "Custom code",
+            SandboxJob::Python { .. } => "Python code",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct SandboxRequest {
+    job: SandboxJob,
+    limits: SandboxLimits,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+enum ChildMessage {
+    Fetch { url: String, init: String },
+    Finished { result: Result<Value, String> },
+}
+
+/// Runs `job` in a child process and returns the script's result. `fetch`
+/// serves the script's `fetch` calls; without it they are rejected.
+pub(super) async fn run_in_sandbox(
+    job: SandboxJob,
+    limits: SandboxLimits,
+    mut fetch: Option<FetchBridge>,
+) -> Result<Value, String> {
+    let label = job.label();
+    let stopped = || format!("{label} execution stopped unexpectedly");
+    let mut child = child_command()
+        .map_err(|_| stopped())?
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|_| stopped())?;
+    let (Some(mut stdin), Some(stdout), Some(mut stderr)) =
+        (child.stdin.take(), child.stdout.take(), child.stderr.take())
+    else {
+        return Err(stopped());
+    };
+    let stderr_task = tokio::spawn(async move {
+        let mut text = String::new();
+        let _ = stderr.read_to_string(&mut text).await;
+        text
+    });
+
+    let mut request = serde_json::to_string(&SandboxRequest { job, limits })
+        .map_err(|_| format!("Failed to serialize {}", label.to_lowercase()))?;
+    request.push('\n');
+
+    let exchange = async {
+        stdin.write_all(request.as_bytes()).await.ok()?;
+        let mut lines = BufReader::new(stdout).lines();
+        while let Some(line) = lines.next_line().await.ok()? {
+            // Under the test harness the child prints a banner before it
+            // takes over stdout.
+            if !line.starts_with('{') {
+                continue;
+            }
+            match serde_json::from_str::<ChildMessage>(&line).ok()? {
+                ChildMessage::Fetch { url, init } => {
+                    let reply = match fetch.as_mut() {
+                        Some(bridge) => bridge.fetch(&url, &init).await,
+                        None => Err("fetch is disabled for this code node".to_string()),
+                    };
+                    let mut reply = serde_json::to_string(&reply).ok()?;
+                    reply.push('\n');
+                    stdin.write_all(reply.as_bytes()).await.ok()?;
+                }
+                ChildMessage::Finished { result } => return Some(result),
+            }
+        }
+        None
+    };
+
+    match tokio::time::timeout(limits.timeout + KILL_GRACE, exchange).await {
+        Ok(Some(result)) => result,
+        Ok(None) => {
+            let _ = child.wait().await;
+            let diagnostics = stderr_task.await.unwrap_or_default();
+            if ALLOCATION_FAILURES
+                .iter()
+                .any(|failure| diagnostics.contains(failure))
+            {
+                Err(format!(
+                    "{label} exceeded its memory limit of {} MB",
+                    limits.memory_bytes / (1024 * 1024)
+                ))
+            } else {
+                Err(stopped())
+            }
+        }
+        Err(_) => {
+            let _ = child.kill().await;
+            Err(format!(
+                "{label} timed out after {} ms",
+                limits.timeout.as_millis()
+            ))
+        }
+    }
+}
+
+#[cfg(not(test))]
+fn child_command() -> std::io::Result<Command> {
+    let mut command = Command::new(std::env::current_exe()?);
+    command.arg(CHILD_FLAG);
+    Ok(command)
+}
+
+/// Test binaries have no `main` to intercept, so the child re-runs the test
+/// binary filtered down to [`tests::child_process_entrypoint`].
+#[cfg(test)]
+fn child_command() -> std::io::Result<Command> {
+    let module = module_path!()
+        .split_once("::")
+        .map(|(_, path)| path)
+        .unwrap_or(module_path!());
+    let mut command = Command::new(std::env::current_exe()?);
+    command
+        .arg(format!("{module}::tests::child_process_entrypoint"))
+        .args(["--exact", "--nocapture", "--test-threads=1", "-q"])
+        .env(tests::CHILD_ENV, "1");
+    Ok(command)
+}
+
+/// Entry point of the sandbox child; never returns.
+pub fn run_child() -> ! {
+    let mut line = String::new();
+    let request = std::io::stdin()
+        .lock()
+        .read_line(&mut line)
+        .ok()
+        .and_then(|_| serde_json::from_str::<SandboxRequest>(&line).ok());
+    let Some(SandboxRequest { job, limits }) = request else {
+        std::process::exit(2);
+    };
+    drop(line);
+
+    // The default hook may capture a backtrace, which needs memory the
+    // script has just used up; the parent only looks for the message.
+    std::panic::set_hook(Box::new(|info| eprintln!("{info}")));
+    cap_data_segment(limits.memory_bytes);
+    let result = match job {
+        SandboxJob::JavaScript { script } => super::code::evaluate_script(&script, limits),
+        SandboxJob::Python {
+            script,
+            inputs,
+            context,
+        } => super::python::evaluate_script(script, &inputs, &context, limits),
+    };
+    let code = if send(&ChildMessage::Finished { result }).is_ok() {
+        0
+    } else {
+        1
+    };
+    std::process::exit(code);
+}
+
+/// Asks the parent to perform a `fetch`; called from the child's script.
+pub(super) fn fetch(url: &str, init: &str) -> Result<String, String> {
+    send(&ChildMessage::Fetch {
+        url: url.to_string(),
+        init: init.to_string(),
+    })
+    .map_err(|_| "fetch could not reach the workflow engine".to_string())?;
+    let mut line = String::new();
+    std::io::stdin()
+        .lock()
+        .read_line(&mut line)
+        .map_err(|_| "fetch could not reach the workflow engine".to_string())?;
+    serde_json::from_str::<Result<String, String>>(&line)
+        .map_err(|_| "fetch could not reach the workflow engine".to_string())?
+}
+
+fn send(message: &ChildMessage) -> std::io::Result<()> {
+    let mut line = serde_json::to_string(message)?;
+    line.push('\n');
+    let mut stdout = std::io::stdout().lock();
+    stdout.write_all(line.as_bytes())?;
+    stdout.flush()
+}
+
+/// Limits the child's data segment (heap and private mappings) to what it
+/// already uses plus `budget` bytes.
+#[cfg(target_os = "linux")]
+fn cap_data_segment(budget: usize) {
+    let in_use = std::fs::read_to_string("/proc/self/status")
+        .ok()
+        .and_then(|status| {
+            status
+                .lines()
+                .find_map(|line| line.strip_prefix("VmData:"))
+                .and_then(|rest| {
+                    rest.trim()
+                        .trim_end_matches("kB")
+                        .trim()
+                        .parse::<u64>()
+                        .ok()
+                })
+        })
+        .map(|kb| kb * 1024)
+        .unwrap_or(0);
+    let cap = in_use.saturating_add(budget as u64) as libc::rlim_t;
+    let limit = libc::rlimit {
+        rlim_cur: cap,
+        rlim_max: cap,
+    };
+    // SAFETY: `limit` is a valid rlimit that outlives the call.
+    unsafe {
+        libc::setrlimit(libc::RLIMIT_DATA, &limit);
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn cap_data_segment(_budget: usize) {}
+
+#[cfg(test)]
+pub(super) mod tests {
+    pub(super) const CHILD_ENV: &str = "DSENTR_CODE_SANDBOX_CHILD";
+
+    /// Not a test: the body of the sandbox child when the test binary is
+    /// re-run by [`super::child_command`]. A no-op in a normal test run.
+    #[test]
+    fn child_process_entrypoint() {
+        if std::env::var_os(CHILD_ENV).is_some() {
+            super::run_child();
+        }
+    }
+}
@@ -0,0 +1,382 @@
+//! `sftp` action: list, download, upload, move and delete files on an SFTP
+//! server, plus the run-time half of the `sftp.new_file` trigger.
+//!
+//! Connection details live in the node params; the password or private key
+//! is a secret-store reference hydrated before the run starts. Downloads are
+//! stored as run artifacts and uploads can read from one.
+
+use serde_json::{json, Map, Value};
+
+use super::http::enforce_host_egress_policy;
+use super::{execute_trigger, read_optional, read_required};
+use crate::engine::graph::Node;
+use crate::engine::templating::templ_str;
+use crate::models::workflow_run::WorkflowRun;
+use crate::services::artifacts::{load_artifact_param, store_run_artifact};
+use crate::services::sftp::{self, RemoteEntry, RemoteEntryKind, SftpTarget, SFTP_NEW_FILE};
+use crate::state::AppState;
+
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn execute_sftp(
+    node: &Node,
+    context: &Value,
+    allowed_hosts: &[String],
+    disallowed_hosts: &[String],
+    default_deny: bool,
+    is_prod: bool,
+    state: &AppState,
+    run: &WorkflowRun,
+) -> Result<(Value, Option<String>), String> {
+    let params = node.data.get("params").cloned().unwrap_or(Value::Null);
+    let target = SftpTarget::from_params(&params).map_err(|e| e.to_string())?;
+    enforce_sftp_egress(
+        &target,
+        node,
+        allowed_hosts,
+        disallowed_hosts,
+        default_deny,
+        is_prod,
+        state,
+        run,
+    )
+    .await?;
+
+    let operation = read_optional(&params, "operation", context)
+        .unwrap_or_else(|| "list".to_string())
+        .to_ascii_lowercase();
+    let outputs = match operation.as_str() {
+        "list" => {
+            let dir = read_optional(&params, "path", context).unwrap_or_else(|| ".".to_string());
+            let pattern = read_optional(&params, "pattern", context);
+            let entries: Vec<Value> = sftp::list_dir(&target, &dir)
+                .await
+                .map_err(|e| e.to_string())?
+                .iter()
+                .filter(|entry| {
+                    pattern
+                        .as_deref()
+                        .is_none_or(|p| sftp::matches_pattern(p, &entry.name))
+                })
+                .map(entry_to_json)
+                .collect();
+            json!({ "path": dir, "count": entries.len(), "entries": entries })
+        }
+        "download" => {
+            let path = read_required(&params, "path", "Remote path", context)?;
+            let bytes = sftp::download(&target, &path)
+                .await
+                .map_err(|e| e.to_string())?;
+            let name = read_optional(&params, "fileName", context)
+                .unwrap_or_else(|| remote_file_name(&path).to_string());
+            let mime_type = read_optional(&params, "mimeType", context)
+                .unwrap_or_else(|| mime_type_for(&name).to_string());
+            let artifact =
+                store_run_artifact(state, run, &node.id, &name, &mime_type, &bytes).await?;
+            json!({
+                "path": path,
+                "size": bytes.len(),
+                "artifact": serde_json::to_value(artifact).map_err(|e| e.to_string())?,
+            })
+        }
+        "upload" => {
+            let mut path = read_required(&params, "path", "Remote path", context)?;
+            let mut artifacts =
+                load_artifact_param(state, run, params.get("artifact"), context).await?;
+            if artifacts.len() > 1 {
+                return Err("SFTP uploads accept a single artifact".to_string());
+            }
+            let (name, bytes) = match artifacts.pop() {
+                Some(artifact) => (Some(artifact.name), artifact.bytes),
+                None => {
+                    let content = params
+                        .get("content")
+                        .and_then(Value::as_str)
+                        .map(|s| templ_str(s, context))
+                        .ok_or_else(|| "Upload requires an artifact or content".to_string())?;
+                    (None, content.into_bytes())
+                }
+            };
+            // A trailing slash uploads into the directory under the artifact's name.
+            if path.ends_with('/') {
+                let name = name.ok_or_else(|| {
+                    "Remote path must include a file name when uploading content".to_string()
+                })?;
+                path = sftp::join_remote(&path, &name);
+            }
+            sftp::upload(&target, &path, &bytes)
+                .await
+                .map_err(|e| e.to_string())?;
+            json!({ "path": path, "size": bytes.len() })
+        }
+        "move" | "rename" => {
+            let from = read_required(&params, "path", "Remote path", context)?;
+            let to = read_required(&params, "destination", "Destination path", context)?;
+            sftp::rename(&target, &from, &to)
+                .await
+                .map_err(|e| e.to_string())?;
+            json!({ "from": from, "to": to })
+        }
+        "delete" => {
+            let path = read_required(&params, "path", "Remote path", context)?;
+            sftp::remove(&target, &path)
+                .await
+                .map_err(|e| e.to_string())?;
+            json!({ "path": path, "deleted": true })
+        }
+        other => return Err(format!("Unsupported SFTP operation `{other}`")),
+    };
+
+    Ok((outputs, None))
+}
+
+pub(crate) fn is_new_file_trigger(node: &Node) -> bool {
+    node.data
+        .get("triggerType")
+        .and_then(Value::as_str)
+        .is_some_and(|t| t.trim().eq_ignore_ascii_case(SFTP_NEW_FILE))
+}
+
+/// Trigger node for `sftp.new_file`. The worker only lists the directory;
+/// the file is fetched here, once the run exists to own the artifact. Runs
+/// that were not started by the poller (e.g. manual test runs) behave like
+/// any other trigger.
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn execute_new_file_trigger(
+    node: &Node,
+    context: &Value,
+    event: Option<&Value>,
+    allowed_hosts: &[String],
+    disallowed_hosts: &[String],
+    default_deny: bool,
+    is_prod: bool,
+    state: &AppState,
+    run: &WorkflowRun,
+) -> Result<(Value, Option<String>), String> {
+    let (inputs, next) = execute_trigger(node, context).await?;
+    let Some((event, path)) = event
+        .filter(|e| e.get("trigger").and_then(Value::as_str) == Some(SFTP_NEW_FILE))
+        .and_then(|e| Some((e, e.get("path")?.as_str()?)))
+    else {
+        return Ok((inputs, next));
+    };
+
+    let target = SftpTarget::from_params(&node.data).map_err(|e| e.to_string())?;
+    enforce_sftp_egress(
+        &target,
+        node,
+        allowed_hosts,
+        disallowed_hosts,
+        default_deny,
+        is_prod,
+        state,
+        run,
+    )
+    .await?;
+    let bytes = sftp::download(&target, path)
+        .await
+        .map_err(|e| format!("Failed to download {path}: {e}"))?;
+    let name = event
+        .get("name")
+        .and_then(Value::as_str)
+        .unwrap_or_else(|| remote_file_name(path));
+    let artifact =
+        store_run_artifact(state, run, &node.id, name, mime_type_for(name), &bytes).await?;
+
+    let mut outputs = Map::new();
+    for key in [
+        "trigger",
+        "host",
+        "directory",
+        "path",
+        "name",
+        "size",
+        "modified",
+    ] {
+        if let Some(value) = event.get(key) {
+            outputs.insert(key.to_string(), value.clone());
+        }
+    }
+    outputs.insert(
+        "artifact".to_string(),
+        serde_json::to_value(artifact).map_err(|e| e.to_string())?,
+    );
+    if let Value::Object(inputs) = inputs {
+        outputs.extend(inputs);
+    }
+    Ok((Value::Object(outputs), next))
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn enforce_sftp_egress(
+    target: &SftpTarget,
+    node: &Node,
+    allowed_hosts: &[String],
+    disallowed_hosts: &[String],
+    default_deny: bool,
+    is_prod: bool,
+    state: &AppState,
+    run: &WorkflowRun,
+) -> Result<(), String> {
+    enforce_host_egress_policy(
+        "SFTP",
+        &format!("sftp://{}:{}", target.host, target.port),
+        &target.host,
+        node,
+        allowed_hosts,
+        disallowed_hosts,
+        default_deny,
+        is_prod,
+        state,
+        run,
+    )
+    .await
+}
+
+fn entry_to_json(entry: &RemoteEntry) -> Value {
+    json!({
+        "name": entry.name,
+        "path": entry.path,
+        "size": entry.size,
+        "modified": entry.modified,
+        "type": entry.kind.as_str(),
+        "isFile": entry.kind == RemoteEntryKind::File,
+    })
+}
+
+fn remote_file_name(path: &str) -> &str {
+    path.trim_end_matches('/')
+        .rsplit('/')
+        .next()
+        .filter(|name| !name.is_empty())
+        .unwrap_or("download")
+}
+
+fn mime_type_for(name: &str) -> &'static str {
+    let ext = name
+        .rsplit_once('.')
+        .map(|(_, ext)| ext.to_ascii_lowercase())
+        .unwrap_or_default();
+    match ext.as_str() {
+        "csv" => "text/csv",
+        "tsv" => "text/tab-separated-values",
+        "txt" | "log" => "text/plain",
+        "json" => "application/json",
+        "xml" => "application/xml",
+        "pdf" => "application/pdf",
+        "zip" => "application/zip",
+        "gz" => "application/gzip",
+        "xlsx" => "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+        _ => "application/octet-stream",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::mock_db::NoopWorkspaceRepository;
+    use crate::test_support::{oauth_service_with_token, sample_run, test_state};
+    use reqwest::Client;
+    use std::sync::Arc;
+    use uuid::Uuid;
+
+    fn sftp_state(user_id: Uuid) -> AppState {
+        let (oauth, _) = oauth_service_with_token(user_id, "sftp@example.com");
+        test_state(
+            oauth,
+            Arc::new(Client::new()),
+            Arc::new(NoopWorkspaceRepository),
+        )
+    }
+
+    fn action(params: Value) -> Node {
+        Node {
+            id: "sftp-1".into(),
+            kind: "action".into(),
+            data: json!({ "actionType": "sftp", "params": params }),
+        }
+    }
+
+    fn connection() -> Value {
+        json!({
+            "host": "sftp.partner.example",
+            "username": "dsentr",
+            "password": "hunter2",
+            "hostKey": "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIH0k",
+        })
+    }
+
+    async fn run_action(
+        params: Value,
+        allowed: &[String],
+        default_deny: bool,
+    ) -> Result<Value, String> {
+        let user_id = Uuid::new_v4();
+        execute_sftp(
+            &action(params),
+            &json!({}),
+            allowed,
+            &[],
+            default_deny,
+            true,
+            &sftp_state(user_id),
+            &sample_run(user_id),
+        )
+        .await
+        .map(|(outputs, _)| outputs)
+    }
+
+    #[tokio::test]
+    async fn invalid_connections_and_blocked_hosts_fail_before_connecting() {
+        let mut no_key = connection();
+        no_key["hostKey"] = json!("");
+        let err = run_action(no_key, &[], false).await.unwrap_err();
+        assert!(err.contains("host key"), "{err}");
+
+        let mut private_ip = connection();
+        private_ip["host"] = json!("10.0.0.5");
+        let err = run_action(private_ip, &[], false).await.unwrap_err();
+        assert!(err.contains("ssrf_hardening"), "{err}");
+
+        let err = run_action(connection(), &["files.example.com".to_string()], true)
+            .await
+            .unwrap_err();
+        assert!(err.contains("default_deny"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn new_file_trigger_without_an_event_returns_its_inputs() {
+        let user_id = Uuid::new_v4();
+        let trigger = Node {
+            id: "trigger-1".into(),
+            kind: "trigger".into(),
+            data: json!({
+                "triggerType": "sftp.new_file",
+                "inputs": [{ "key": "source", "value": "partner" }],
+            }),
+        };
+        assert!(is_new_file_trigger(&trigger));
+        let (outputs, _) = execute_new_file_trigger(
+            &trigger,
+            &json!({}),
+            None,
+            &[],
+            &[],
+            false,
+            false,
+            &sftp_state(user_id),
+            &sample_run(user_id),
+        )
+        .await
+        .unwrap();
+        assert_eq!(outputs, json!({ "source": "partner" }));
+    }
+
+    #[test]
+    fn file_names_and_mime_types_come_from_the_remote_path() {
+        assert_eq!(remote_file_name("/in/orders.csv"), "orders.csv");
+        assert_eq!(remote_file_name("orders.CSV"), "orders.CSV");
+        assert_eq!(remote_file_name("/"), "download");
+        assert_eq!(mime_type_for("orders.CSV"), "text/csv");
+        assert_eq!(mime_type_for("blob"), "application/octet-stream");
+    }
+}
@@ -0,0 +1,352 @@
+//! Key-value "store" action for state that outlives a single run: dedupe
+//! markers, counters, the last processed id and short rolling lists.
+//!
+//! Entries are scoped to the workflow (default) or shared across the
+//! workspace, and may expire after `ttlSeconds`.
+
+use serde_json::{json, Value};
+
+use super::{parse_flexible_value, read_optional, read_required};
+use crate::engine::graph::Node;
+use crate::engine::templating::templ_str;
+use crate::models::kv_store::KvScope;
+use crate::models::workflow_run::WorkflowRun;
+use crate::state::AppState;
+
+const MAX_KEY_LENGTH: usize = 256;
+const MAX_VALUE_BYTES: usize = 256 * 1024;
+const MAX_LIST_ITEMS: i64 = 10_000;
+
+#[derive(Debug, Clone, PartialEq)]
+enum StoreOperation {
+    Get {
+        default: Value,
+    },
+    Set {
+        value: Value,
+        if_not_exists: bool,
+    },
+    Increment {
+        by: f64,
+    },
+    Append {
+        value: Value,
+        max_items: Option<i64>,
+    },
+    Delete,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct StoreRequest {
+    operation: StoreOperation,
+    key: String,
+    scope: KvScope,
+    ttl_seconds: Option<i64>,
+}
+
+pub(crate) async fn execute_store(
+    node: &Node,
+    context: &Value,
+    state: &AppState,
+    run: &WorkflowRun,
+) -> Result<(Value, Option<String>), String> {
+    let params = node.data.get("params").cloned().unwrap_or(Value::Null);
+    let request = parse_request(&params, context, run)?;
+    let repo = &state.workflow_repo;
+    let key = request.key.as_str();
+    let scope = request.scope;
+    let scope_name = scope.as_str();
+    let db_error = |err: sqlx::Error| format!("Store operation failed: {err}");
+
+    let outputs = match request.operation {
+        StoreOperation::Get { default } => {
+            let found = repo.kv_get(scope, key).await.map_err(db_error)?;
+            json!({
+                "key": key,
+                "scope": scope_name,
+                "found": found.is_some(),
+                "value": found.unwrap_or(default),
+            })
+        }
+        StoreOperation::Set {
+            value,
+            if_not_exists,
+        } => {
+            let stored = repo
+                .kv_set(scope, key, &value, request.ttl_seconds, if_not_exists)
+                .await
+                .map_err(db_error)?;
+            json!({ "key": key, "scope": scope_name, "stored": stored, "value": value })
+        }
+        StoreOperation::Increment { by } => {
+            let value = repo
+                .kv_increment(scope, key, by, request.ttl_seconds)
+                .await
+                .map_err(db_error)?
+                .ok_or_else(|| format!("Stored value for `{key}` is not a number"))?;
+            json!({ "key": key, "scope": scope_name, "value": value })
+        }
+        StoreOperation::Append { value, max_items } => {
+            let list = repo
+                .kv_append(scope, key, &value, request.ttl_seconds, max_items)
+                .await
+                .map_err(db_error)?
+                .ok_or_else(|| format!("Stored value for `{key}` is not a list"))?;
+            let length = list.as_array().map(|items| items.len()).unwrap_or(0);
+            json!({ "key": key, "scope": scope_name, "value": list, "length": length })
+        }
+        StoreOperation::Delete => {
+            let deleted = repo.kv_delete(scope, key).await.map_err(db_error)?;
+            json!({ "key": key, "scope": scope_name, "deleted": deleted })
+        }
+    };
+
+    Ok((outputs, None))
+}
+
+fn parse_request(
+    params: &Value,
+    context: &Value,
+    run: &WorkflowRun,
+) -> Result<StoreRequest, String> {
+    let key = read_required(params, "key", "Key", context)?;
+    if key.chars().count() > MAX_KEY_LENGTH {
+        return Err(format!("Key must be at most {MAX_KEY_LENGTH} characters"));
+    }
+
+    let scope = match read_optional(params, "scope", context)
+        .unwrap_or_else(|| "workflow".to_string())
+        .to_ascii_lowercase()
+        .as_str()
+    {
+        "workflow" => KvScope::Workflow(run.workflow_id),
+        "workspace" => KvScope::Workspace(run.workspace_id.unwrap_or(run.user_id)),
+        other => return Err(format!("Unsupported store scope `{other}`")),
+    };
+
+    let ttl_seconds = read_integer(params, "ttlSeconds", context)?;
+    if matches!(ttl_seconds, Some(ttl) if ttl <= 0) {
+        return Err("TTL must be a positive number of seconds".to_string());
+    }
+
+    let operation = match read_optional(params, "operation", context)
+        .unwrap_or_else(|| "get".to_string())
+        .to_ascii_lowercase()
+        .as_str()
+    {
+        "get" => StoreOperation::Get {
+            default: read_value(params, "default", context)?,
+        },
+        "set" => StoreOperation::Set {
+            value: read_value(params, "value", context)?,
+            if_not_exists: read_bool(params, "ifNotExists", context),
+        },
+        "increment" => {
+            let by = match params.get("by") {
+                None | Some(Value::Null) => 1.0,
+                Some(Value::Number(num)) => num.as_f64().unwrap_or(1.0),
+                Some(Value::String(raw)) => {
+                    let rendered = templ_str(raw, context);
+                    let trimmed = rendered.trim();
+                    if trimmed.is_empty() {
+                        1.0
+                    } else {
+                        trimmed
+                            .parse::<f64>()
+                            .map_err(|_| "Increment amount must be a number".to_string())?
+                    }
+                }
+                Some(_) => return Err("Increment amount must be a number".to_string()),
+            };
+            if !by.is_finite() {
+                return Err("Increment amount must be a number".to_string());
+            }
+            StoreOperation::Increment { by }
+        }
+        "append" => {
+            let max_items = read_integer(params, "maxItems", context)?;
+            if matches!(max_items, Some(max) if !(1..=MAX_LIST_ITEMS).contains(&max)) {
+                return Err(format!("Max items must be between 1 and {MAX_LIST_ITEMS}"));
+            }
+            StoreOperation::Append {
+                value: read_value(params, "value", context)?,
+                max_items,
+            }
+        }
+        "delete" => StoreOperation::Delete,
+        other => return Err(format!("Unsupported store operation `{other}`")),
+    };
+
+    Ok(StoreRequest {
+        operation,
+        key,
+        scope,
+        ttl_seconds,
+    })
+}
+
+/// Non-string JSON is stored as-is; strings are templated and then parsed
+/// so `{{trigger.id}}` keeps the type of the value it renders to.
+fn read_value(params: &Value, key: &str, context: &Value) -> Result<Value, String> {
+    let value = match params.get(key) {
+        None => Value::Null,
+        Some(Value::String(raw)) => parse_flexible_value(&templ_str(raw, context)),
+        Some(other) => other.clone(),
+    };
+    let size = serde_json::to_vec(&value)
+        .map(|bytes| bytes.len())
+        .unwrap_or(0);
+    if size > MAX_VALUE_BYTES {
+        return Err(format!(
+            "Stored values must be at most {} KB",
+            MAX_VALUE_BYTES / 1024
+        ));
+    }
+    Ok(value)
+}
+
+fn read_integer(params: &Value, key: &str, context: &Value) -> Result<Option<i64>, String> {
+    match params.get(key) {
+        None | Some(Value::Null) => Ok(None),
+        Some(Value::Number(num)) => num
+            .as_i64()
+            .map(Some)
+            .ok_or_else(|| format!("{key} must be a whole number")),
+        Some(Value::String(raw)) => {
+            let rendered = templ_str(raw, context);
+            let trimmed = rendered.trim();
+            if trimmed.is_empty() {
+                return Ok(None);
+            }
+            trimmed
+                .parse::<i64>()
+                .map(Some)
+                .map_err(|_| format!("{key} must be a whole number"))
+        }
+        Some(_) => Err(format!("{key} must be a whole number")),
+    }
+}
+
+fn read_bool(params: &Value, key: &str, context: &Value) -> bool {
+    match params.get(key) {
+        Some(Value::Bool(flag)) => *flag,
+        Some(Value::String(raw)) => templ_str(raw, context).trim().eq_ignore_ascii_case("true"),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::mock_db::NoopWorkspaceRepository;
+    use crate::test_support::{oauth_service_with_token, sample_run, test_state};
+    use reqwest::Client;
+    use std::sync::Arc;
+    use uuid::Uuid;
+
+    #[test]
+    fn parses_operations_scopes_and_templated_values() {
+        let mut run = sample_run(Uuid::new_v4());
+        run.workspace_id = Some(Uuid::new_v4());
+        let context = json!({ "trigger": { "id": 42, "email": "ada@example.com" } });
+
+        let set = parse_request(
+            &json!({
+                "operation": "set",
+                "key": "seen:{{trigger.email}}",
+                "value": "{{trigger.id}}",
+                "scope": "workspace",
+                "ttlSeconds": "3600",
+                "ifNotExists": true
+            }),
+            &context,
+            &run,
+        )
+        .expect("valid set");
+        assert_eq!(set.key, "seen:ada@example.com");
+        assert_eq!(set.scope, KvScope::Workspace(run.workspace_id.unwrap()));
+        assert_eq!(set.ttl_seconds, Some(3600));
+        assert_eq!(
+            set.operation,
+            StoreOperation::Set {
+                value: json!(42),
+                if_not_exists: true
+            }
+        );
+
+        let increment = parse_request(
+            &json!({ "operation": "increment", "key": "count" }),
+            &context,
+            &run,
+        )
+        .expect("valid increment");
+        assert_eq!(increment.scope, KvScope::Workflow(run.workflow_id));
+        assert_eq!(increment.operation, StoreOperation::Increment { by: 1.0 });
+
+        let reject = |params: Value| parse_request(&params, &context, &run).unwrap_err();
+        assert!(reject(json!({ "operation": "get" })).contains("Key"));
+        assert!(reject(json!({ "operation": "pop", "key": "k" })).contains("pop"));
+        assert!(reject(json!({ "key": "k", "scope": "global" })).contains("global"));
+        assert!(reject(json!({ "key": "k", "ttlSeconds": 0 })).contains("TTL"));
+        assert!(reject(json!({ "key": "k".repeat(300) })).contains("256"));
+        assert!(
+            reject(json!({ "operation": "increment", "key": "k", "by": "lots" }))
+                .contains("number")
+        );
+        assert!(
+            reject(json!({ "operation": "append", "key": "k", "maxItems": 0 }))
+                .contains("Max items")
+        );
+        assert!(reject(json!({
+            "operation": "set",
+            "key": "k",
+            "value": "x".repeat(MAX_VALUE_BYTES + 1)
+        }))
+        .contains("KB"));
+    }
+
+    #[tokio::test]
+    async fn reports_operation_results() {
+        let user_id = Uuid::new_v4();
+        let (oauth, _) = oauth_service_with_token(user_id, "store@example.com");
+        let state = test_state(
+            oauth,
+            Arc::new(Client::new()),
+            Arc::new(NoopWorkspaceRepository),
+        );
+        let run = sample_run(user_id);
+        let run_store = |params: Value| {
+            let node = Node {
+                id: "store-1".into(),
+                kind: "action".into(),
+                data: json!({ "actionType": "store", "params": params }),
+            };
+            let state = state.clone();
+            let run = run.clone();
+            async move {
+                execute_store(&node, &json!({}), &state, &run)
+                    .await
+                    .map(|(outputs, _)| outputs)
+            }
+        };
+
+        let missing = run_store(json!({ "key": "cursor", "default": "0" }))
+            .await
+            .unwrap();
+        assert_eq!(
+            missing,
+            json!({ "key": "cursor", "scope": "workflow", "found": false, "value": 0 })
+        );
+
+        let counter = run_store(json!({ "operation": "increment", "key": "n", "by": 5 }))
+            .await
+            .unwrap();
+        assert_eq!(counter["value"], json!(5.0));
+
+        let list = run_store(json!({ "operation": "append", "key": "ids", "value": "a" }))
+            .await
+            .unwrap();
+        assert_eq!(list["value"], json!(["a"]));
+        assert_eq!(list["length"], json!(1));
+    }
+}
@@ -423,6 +423,22 @@ pub async fn execute_run(
                 "formatter" | "logicformatter" | "transform" => Ok(NodeExecResult::Normal(
                     super::actions::formatter::execute_formatter(node, &context_value)?,
                 )),
+                "trigger" if super::actions::sftp::is_new_file_trigger(node) => {
+                    Ok(NodeExecResult::Normal(
+                        super::actions::sftp::execute_new_file_trigger(
+                            node,
+                            &context_value,
+                            context.get(&context_keys(node).0),
+                            &allowed_hosts,
+                            &disallowed_hosts,
+                            default_deny,
+                            is_prod,
+                            &state,
+                            &run,
+                        )
+                        .await?,
+                    ))
+                }
                 "trigger" => Ok(NodeExecResult::Normal(
                     execute_trigger(node, &context_value).await?,
                 )),
@@ -994,8 +1010,8 @@ pub(crate) async fn complete_run_with_retry(
 mod tests {
     use super::*;
     use crate::config::{
-        Config, OAuthProviderConfig, OAuthSettings, StripeSettings, DEFAULT_WORKSPACE_MEMBER_LIMIT,
-        DEFAULT_WORKSPACE_MONTHLY_RUN_LIMIT, RUNAWAY_LIMIT_5MIN,
+        Config, OAuthProviderConfig, OAuthSettings, ProviderBaseUrls, StripeSettings,
+        DEFAULT_WORKSPACE_MEMBER_LIMIT, DEFAULT_WORKSPACE_MONTHLY_RUN_LIMIT, RUNAWAY_LIMIT_5MIN,
     };
     use crate::db::mock_db::{MockDb, NoopWorkspaceRepository};
     use crate::db::mock_stripe_event_log_repository::MockStripeEventLogRepository;
@@ -1056,6 +1072,11 @@ mod tests {
                     client_secret: "stub".into(),
                     redirect_uri: "http://localhost".into(),
                 },
+                github: OAuthProviderConfig {
+                    client_id: "stub".into(),
+                    client_secret: "stub".into(),
+                    redirect_uri: "http://localhost".into(),
+                },
                 token_encryption_key: vec![0u8; 32],
             },
             api_secrets_encryption_key: vec![1u8; 32],
@@ -1071,6 +1092,9 @@ mod tests {
             workspace_member_limit: DEFAULT_WORKSPACE_MEMBER_LIMIT,
             workspace_monthly_run_limit: DEFAULT_WORKSPACE_MONTHLY_RUN_LIMIT,
             runaway_limit_5min: RUNAWAY_LIMIT_5MIN,
+            slack_signing_secret: None,
+            public_api_base_url: None,
+            provider_base_urls: ProviderBaseUrls::default(),
         });
 
         let workflow_repo: Arc<dyn WorkflowRepository> = Arc::new(repo);
@@ -2,7 +2,7 @@ pub mod actions;
 mod executor;
 pub(crate) mod graph;
 pub(crate) mod nodes;
-mod templating;
+pub(crate) mod templating;
 
 pub(crate) use executor::complete_run_with_retry;
 pub use executor::{execute_run, ExecutorError};
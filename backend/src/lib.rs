@@ -8,6 +8,8 @@ pub mod runaway_protection;
 pub mod services;
 pub mod session;
 pub mod state;
+#[cfg(test)]
+pub(crate) mod test_support;
 pub mod utils;
 pub mod worker;
 
@@ -8,6 +8,8 @@ mod runaway_protection;
 mod services;
 mod session;
 mod state;
+#[cfg(test)]
+mod test_support;
 pub mod utils;
 mod worker;
 
@@ -62,14 +64,18 @@ use routes::{
     },
     microsoft::{list_channel_members, list_team_channels, list_teams},
     oauth::{
-        asana_connect_callback, asana_connect_start, disconnect_connection, get_connection_by_id,
-        google_connect_callback, google_connect_start, list_connections, list_provider_connections,
-        microsoft_connect_callback, microsoft_connect_start, notion_connect_callback,
-        notion_connect_start, refresh_connection, revoke_connection, slack_connect_callback,
-        slack_connect_start,
+        asana_connect_callback, asana_connect_start, create_custom_provider,
+        custom_connect_callback, custom_connect_start, delete_custom_provider,
+        disconnect_connection, get_connection_by_id, github_connect_callback, github_connect_start,
+        google_connect_callback, google_connect_start, list_connections, list_custom_providers,
+        list_provider_connections, microsoft_connect_callback, microsoft_connect_start,
+        notion_connect_callback, notion_connect_start, refresh_connection, revoke_connection,
+        slack_connect_callback, slack_connect_start, update_custom_provider,
     },
     options::{
-        secrets::{delete_secret, list_secrets, upsert_secret},
+        secrets::{
+            delete_secret, get_signing_key, list_secrets, rotate_signing_key, upsert_secret,
+        },
         user_settings::{get_user_settings, update_user_settings},
     },
     slack::list_channels as list_slack_channels,
@@ -116,8 +122,17 @@ use crate::services::stripe::{LiveStripeService, StripeService};
 use crate::session::SESSION_CACHE;
 use crate::state::AppState;
 
+fn main() -> Result<()> {
+    // Code nodes run their scripts in a copy of this binary; see
+    // `engine::actions::sandbox`.
+    if std::env::args().nth(1).as_deref() == Some(engine::actions::sandbox::CHILD_FLAG) {
+        engine::actions::sandbox::run_child();
+    }
+    serve()
+}
+
 #[tokio::main]
-async fn main() -> Result<()> {
+async fn serve() -> Result<()> {
     let _guard = sentry::init(("https://a94598e7b27ba11a160c9576b24e3f6f@o4510324922449920.ingest.us.sentry.io/4510324924874752", sentry::ClientOptions {
     release: sentry::release_name!(),
     // Capture user IPs and potentially sensitive headers when using HTTP server integrations
@@ -304,13 +319,16 @@ async fn main() -> Result<()> {
     let stripe_event_log_repo =
         Arc::new(PostgresStripeEventLogRepository {}) as Arc<dyn StripeEventLogRepository>;
     let encryption_key = Arc::new(config.oauth.token_encryption_key.clone());
-    let oauth_accounts = Arc::new(OAuthAccountService::new(
+    let mut oauth_accounts = OAuthAccountService::new(
         oauth_repo.clone(),
         workspace_connection_repo.clone(),
         encryption_key.clone(),
         http_client_arc.clone(),
         &config.oauth,
-    ));
+    );
+    oauth_accounts.set_slack_api_base(config.provider_base_urls.slack.clone());
+    oauth_accounts.set_github_api_base(config.provider_base_urls.github.clone());
+    let oauth_accounts = Arc::new(oauth_accounts);
     let workspace_token_refresher: Arc<dyn WorkspaceTokenRefresher> =
         oauth_accounts.clone() as Arc<dyn WorkspaceTokenRefresher>;
     let workspace_oauth = Arc::new(WorkspaceOAuthService::new(
@@ -486,6 +504,14 @@ async fn main() -> Result<()> {
             "/{workflow_id}/runs/{run_id}/download",
             get(download_run_json),
         )
+        .route(
+            "/{workflow_id}/runs/{run_id}/artifacts",
+            get(routes::workflows::list_run_artifacts),
+        )
+        .route(
+            "/{workflow_id}/runs/{run_id}/artifacts/{artifact_id}",
+            get(routes::workflows::download_run_artifact),
+        )
         .route(
             "/{workflow_id}/events",
             get(routes::workflows::sse_workflow_updates),
@@ -508,6 +534,10 @@ async fn main() -> Result<()> {
             "/{workflow_id}/webhook/signing-key/regenerate",
             post(routes::workflows::regenerate_webhook_signing_key),
         )
+        .route(
+            "/{workflow_id}/github-webhook",
+            get(routes::github_webhooks::get_github_webhook),
+        )
         .route(
             "/{workflow_id}/egress",
             get(get_egress_allowlist).post(set_egress_allowlist),
@@ -602,6 +632,8 @@ async fn main() -> Result<()> {
 
     let options_routes = Router::new()
         .route("/secrets", get(list_secrets))
+        .route("/secrets/signing-key", get(get_signing_key))
+        .route("/secrets/signing-key/rotate", post(rotate_signing_key))
         .route(
             "/secrets/{group}/{service}/{name}",
             put(upsert_secret).delete(delete_secret),
@@ -624,6 +656,10 @@ async fn main() -> Result<()> {
         .route("/asana/callback", get(asana_connect_callback))
         .route("/notion/start", get(notion_connect_start))
         .route("/notion/callback", get(notion_connect_callback))
+        .route("/github/start", get(github_connect_start))
+        .route("/github/callback", get(github_connect_callback))
+        .route("/custom/{provider_id}/start", get(custom_connect_start))
+        .route("/custom/callback", get(custom_connect_callback))
         .layer(session_guard.clone());
 
     let oauth_private_routes = Router::new()
@@ -633,6 +669,14 @@ async fn main() -> Result<()> {
         .route("/{provider}/refresh", post(refresh_connection))
         .route("/{provider}/disconnect", delete(disconnect_connection))
         .route("/{provider}/revoke", post(revoke_connection))
+        .route(
+            "/custom-providers",
+            get(list_custom_providers).post(create_custom_provider),
+        )
+        .route(
+            "/custom-providers/{provider_id}",
+            put(update_custom_provider).delete(delete_custom_provider),
+        )
         .layer(csrf_layer.clone())
         .layer(session_guard.clone());
 
@@ -653,6 +697,14 @@ async fn main() -> Result<()> {
         .layer(csrf_layer.clone())
         .layer(session_guard.clone());
 
+    // Slack Events API and interactivity callbacks: public, verified by signing secret
+    let public_slack_routes = Router::new()
+        .route("/events", post(routes::slack_events::slack_events))
+        .route(
+            "/interactions",
+            post(routes::slack_events::slack_interactions),
+        );
+
     let asana_routes = Router::new()
         .route("/workspaces", get(list_asana_workspaces))
         .route(
@@ -669,6 +721,18 @@ async fn main() -> Result<()> {
         .layer(csrf_layer.clone())
         .layer(session_guard.clone());
 
+    // Asana webhook deliveries: public, verified by the X-Hook-Signature HMAC
+    let public_asana_routes = Router::new().route(
+        "/webhooks/{subscription_id}",
+        post(routes::asana_webhooks::asana_webhook),
+    );
+
+    // GitHub webhook deliveries: public, verified by X-Hub-Signature-256
+    let public_github_routes = Router::new().route(
+        "/webhooks/{workflow_id}",
+        post(routes::github_webhooks::github_webhook),
+    );
+
     let integrations_routes = Router::new()
         .route("/notion/databases", get(list_notion_databases))
         .route(
@@ -766,8 +830,9 @@ async fn main() -> Result<()> {
         .nest("/api/oauth", oauth_routes)
         .nest("/api/google", google_routes)
         .nest("/api/microsoft", microsoft_routes)
-        .nest("/api/slack", slack_routes)
-        .nest("/api/asana", asana_routes)
+        .nest("/api/slack", slack_routes.merge(public_slack_routes))
+        .nest("/api/asana", asana_routes.merge(public_asana_routes))
+        .nest("/api/github", public_github_routes)
         .nest("/api/integrations", integrations_routes)
         .nest("/api/options", options_routes)
         .nest("/api/admin", admin_routes)
@@ -0,0 +1,33 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct AsanaWebhookSubscription {
+    pub id: Uuid,
+    pub workflow_id: Uuid,
+    pub user_id: Uuid,
+    pub trigger_type: String,
+    pub resource_gid: String,
+    pub connection_scope: String,
+    pub connection_id: Uuid,
+    pub webhook_gid: Option<String>,
+    /// Encrypted with the API secrets key; never serialized to clients.
+    #[serde(skip_serializing)]
+    pub hook_secret: Option<String>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339")]
+    pub updated_at: OffsetDateTime,
+}
+
+#[derive(Debug, Clone)]
+pub struct NewAsanaWebhookSubscription {
+    pub workflow_id: Uuid,
+    pub user_id: Uuid,
+    pub trigger_type: String,
+    pub resource_gid: String,
+    pub connection_scope: String,
+    pub connection_id: Uuid,
+}
@@ -0,0 +1,32 @@
+use uuid::Uuid;
+
+/// Where a "store" action entry lives: shared by every workflow in a
+/// workspace, or private to one workflow.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KvScope {
+    Workspace(Uuid),
+    Workflow(Uuid),
+}
+
+impl KvScope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            KvScope::Workspace(_) => "workspace",
+            KvScope::Workflow(_) => "workflow",
+        }
+    }
+
+    pub fn scope_id(&self) -> Uuid {
+        match self {
+            KvScope::Workspace(id) | KvScope::Workflow(id) => *id,
+        }
+    }
+
+    /// Set for workflow-scoped entries so they are deleted with the workflow.
+    pub fn workflow_id(&self) -> Option<Uuid> {
+        match self {
+            KvScope::Workspace(_) => None,
+            KvScope::Workflow(id) => Some(*id),
+        }
+    }
+}
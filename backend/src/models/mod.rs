@@ -1,10 +1,14 @@
 pub mod account_deletion;
+pub mod asana_webhook;
 pub mod early_access;
 pub mod egress_block_event;
 pub mod issue_report;
+pub mod kv_store;
 pub mod login_activity;
+pub mod notion_page_snapshot;
 pub mod oauth_token;
 pub mod plan;
+pub mod run_artifact;
 pub mod signup;
 pub mod user;
 pub mod workflow;
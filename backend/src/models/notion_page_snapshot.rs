@@ -0,0 +1,13 @@
+use serde_json::Value;
+use sqlx::FromRow;
+use time::OffsetDateTime;
+
+/// Last observed values of the watched properties of one Notion page, kept
+/// per schedule by the property-changed trigger.
+#[derive(Debug, Clone, PartialEq, FromRow)]
+pub struct NotionPageSnapshot {
+    pub page_id: String,
+    pub hash: String,
+    pub property_values: Value,
+    pub last_edited_time: OffsetDateTime,
+}
@@ -11,6 +11,10 @@ pub enum ConnectedOAuthProvider {
     Slack,
     Asana,
     Notion,
+    GitHub,
+    /// A provider defined at runtime in `oauth_custom_providers`; the token
+    /// metadata names which one.
+    Custom,
 }
 
 #[allow(dead_code)]
@@ -61,6 +65,25 @@ pub struct WorkspaceConnection {
     pub metadata: serde_json::Value,
 }
 
+#[derive(Debug, Clone, sqlx::FromRow)]
+/// Workspace-defined OAuth2 provider. `client_id` and `client_secret` hold ciphertext.
+pub struct CustomOAuthProvider {
+    pub id: Uuid,
+    pub workspace_id: Uuid,
+    pub created_by: Option<Uuid>,
+    pub name: String,
+    pub authorize_url: String,
+    pub token_url: String,
+    pub userinfo_url: Option<String>,
+    pub scopes: String,
+    pub use_pkce: bool,
+    pub client_id: String,
+    pub client_secret: Option<String>,
+    pub token_auth_method: String,
+    pub created_at: OffsetDateTime,
+    pub updated_at: OffsetDateTime,
+}
+
 #[derive(Debug, Clone, sqlx::FromRow)]
 #[allow(dead_code)]
 pub struct WorkspaceAuditEvent {
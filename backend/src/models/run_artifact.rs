@@ -0,0 +1,26 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+/// Metadata for a file produced by a workflow run. The bytes live in the
+/// artifact store under `key`.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct RunArtifact {
+    pub id: Uuid,
+    pub workflow_id: Uuid,
+    pub run_id: Uuid,
+    pub node_id: String,
+    pub name: String,
+    pub mime_type: String,
+    pub size: i64,
+    pub sha256: String,
+    /// Store backend the object was written to (`local` or `s3`).
+    #[serde(skip_serializing)]
+    pub storage: String,
+    #[serde(skip_serializing)]
+    pub key: String,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+}
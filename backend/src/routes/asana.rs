@@ -20,8 +20,6 @@ use crate::services::oauth::workspace_service::WorkspaceOAuthError;
 use crate::state::AppState;
 use crate::utils::plan_limits::NormalizedPlanTier;
 
-const ASANA_BASE_URL: &str = "https://app.asana.com/api/1.0";
-
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub struct ConnectionQuery {
@@ -530,7 +528,7 @@ pub async fn get_task_details(
 
     // Build Asana URL for a single task
     let task_url = format!(
-        "{ASANA_BASE_URL}/tasks/{}?opt_fields=name,notes,due_on,due_at,completed,assignee.gid,assignee.name,assignee.email,custom_fields,custom_fields.name,custom_fields.type,custom_fields.text_value,custom_fields.number_value,custom_fields.enum_value.name",
+        "{}/tasks/{}?opt_fields=name,notes,due_on,due_at,completed,assignee.gid,assignee.name,assignee.email,custom_fields,custom_fields.name,custom_fields.type,custom_fields.text_value,custom_fields.number_value,custom_fields.enum_value.name", state.config.provider_base_urls.asana,
         urlencoding::encode(&task_gid)
     );
 
@@ -693,7 +691,7 @@ async fn fetch_workspaces(
     state: &AppState,
     access_token: &str,
 ) -> Result<Vec<WorkspacePayload>, Response> {
-    let url = format!("{ASANA_BASE_URL}/workspaces");
+    let url = format!("{}/workspaces", state.config.provider_base_urls.asana);
     let records: ListResponse<WorkspaceRecord> = get_json(state, access_token, &url).await?;
 
     Ok(records
@@ -722,7 +720,10 @@ async fn fetch_projects(
         return Ok(Vec::new());
     }
 
-    let url = format!("{ASANA_BASE_URL}/workspaces/{workspace_gid}/projects");
+    let url = format!(
+        "{}/workspaces/{workspace_gid}/projects",
+        state.config.provider_base_urls.asana
+    );
     let records: ListResponse<ProjectRecord> = get_json(state, access_token, &url).await?;
 
     Ok(records
@@ -751,7 +752,10 @@ async fn fetch_tags(
         return Ok(Vec::new());
     }
 
-    let url = format!("{ASANA_BASE_URL}/workspaces/{workspace_gid}/tags");
+    let url = format!(
+        "{}/workspaces/{workspace_gid}/tags",
+        state.config.provider_base_urls.asana
+    );
     let records: ListResponse<TagRecord> = get_json(state, access_token, &url).await?;
 
     Ok(records
@@ -780,7 +784,10 @@ async fn fetch_sections(
         return Ok(Vec::new());
     }
 
-    let url = format!("{ASANA_BASE_URL}/projects/{project_gid}/sections");
+    let url = format!(
+        "{}/projects/{project_gid}/sections",
+        state.config.provider_base_urls.asana
+    );
     let records: ListResponse<SectionRecord> = get_json(state, access_token, &url).await?;
 
     Ok(records
@@ -810,7 +817,10 @@ async fn fetch_teams(
     }
 
     // Organizations expose teams; some personal workspaces may return an error, which we treat as empty.
-    let url = format!("{ASANA_BASE_URL}/organizations/{workspace_gid}/teams");
+    let url = format!(
+        "{}/organizations/{workspace_gid}/teams",
+        state.config.provider_base_urls.asana
+    );
     match get_json::<ListResponse<TeamRecord>>(state, access_token, &url).await {
         Ok(records) => Ok(records
             .data
@@ -847,7 +857,10 @@ async fn fetch_users(
         return Ok(Vec::new());
     }
 
-    let mut url = format!("{ASANA_BASE_URL}/users?workspace={workspace_gid}");
+    let mut url = format!(
+        "{}/users?workspace={workspace_gid}",
+        state.config.provider_base_urls.asana
+    );
     if let Some(team) = team_gid {
         if !team.trim().is_empty() {
             url.push_str(&format!("&team={}", urlencoding::encode(team.trim())));
@@ -891,7 +904,7 @@ async fn fetch_tasks(
     }
 
     let url = format!(
-        "{ASANA_BASE_URL}/tasks?opt_fields=name,notes,due_on,due_at,completed,assignee.gid,assignee.name,assignee.emailcustom_fields,custom_fields.name,custom_fields.type,custom_fields.text_value,custom_fields.number_value,custom_fields.enum_value.name&limit=50&project={}",
+        "{}/tasks?opt_fields=name,notes,due_on,due_at,completed,assignee.gid,assignee.name,assignee.emailcustom_fields,custom_fields.name,custom_fields.type,custom_fields.text_value,custom_fields.number_value,custom_fields.enum_value.name&limit=50&project={}", state.config.provider_base_urls.asana,
         urlencoding::encode(trimmed)
     );
 
@@ -929,7 +942,10 @@ async fn fetch_task_stories(
         return Ok(Vec::new());
     }
 
-    let url = format!("{ASANA_BASE_URL}/tasks/{task_gid}/stories?opt_fields=text,resource_subtype");
+    let url = format!(
+        "{}/tasks/{task_gid}/stories?opt_fields=text,resource_subtype",
+        state.config.provider_base_urls.asana
+    );
     let records: ListResponse<StoryRecord> = get_json(state, access_token, &url).await?;
 
     Ok(records
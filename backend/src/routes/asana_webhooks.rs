@@ -0,0 +1,655 @@
+use axum::{
+    body::Bytes,
+    extract::{Path, State},
+    http::{HeaderMap, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+};
+use hmac::{Hmac, Mac};
+use serde_json::Value;
+use sha2::Sha256;
+use subtle::ConstantTimeEq;
+use tracing::{error, warn};
+use uuid::Uuid;
+
+use crate::models::asana_webhook::{AsanaWebhookSubscription, NewAsanaWebhookSubscription};
+use crate::models::oauth_token::ConnectedOAuthProvider;
+use crate::models::workflow::Workflow;
+use crate::responses::JsonResponse;
+use crate::routes::workflows::enqueue_external_trigger_run;
+use crate::services::asana::{self, AsanaTriggerKind};
+use crate::state::AppState;
+use crate::utils::encryption::{decrypt_secret, encrypt_secret};
+
+type HmacSha256 = Hmac<Sha256>;
+
+const HOOK_SECRET_HEADER: &str = "X-Hook-Secret";
+const HOOK_SIGNATURE_HEADER: &str = "X-Hook-Signature";
+
+/// Receives Asana webhook traffic for one subscription: the initial
+/// `X-Hook-Secret` handshake, then signed event batches.
+pub async fn asana_webhook(
+    State(app_state): State<AppState>,
+    Path(subscription_id): Path<Uuid>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    let subscription = match app_state
+        .workflow_repo
+        .find_asana_webhook_subscription(subscription_id)
+        .await
+    {
+        Ok(Some(subscription)) => subscription,
+        Ok(None) => return JsonResponse::not_found("Unknown Asana webhook").into_response(),
+        Err(err) => {
+            error!(?err, %subscription_id, "failed to load Asana webhook subscription");
+            return JsonResponse::server_error("Failed to process Asana webhook").into_response();
+        }
+    };
+
+    if let Some(secret) = headers
+        .get(HOOK_SECRET_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+    {
+        return complete_handshake(&app_state, &subscription, secret).await;
+    }
+
+    let Some(encrypted) = subscription.hook_secret.as_deref() else {
+        return JsonResponse::unauthorized("Asana webhook handshake has not completed")
+            .into_response();
+    };
+    let secret = match decrypt_secret(&app_state.config.api_secrets_encryption_key, encrypted) {
+        Ok(secret) => secret,
+        Err(err) => {
+            error!(?err, %subscription_id, "failed to decrypt Asana hook secret");
+            return JsonResponse::server_error("Failed to process Asana webhook").into_response();
+        }
+    };
+    let signature = headers
+        .get(HOOK_SIGNATURE_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default();
+    if !verify_asana_signature(&secret, signature, &body) {
+        return JsonResponse::unauthorized("Invalid Asana signature").into_response();
+    }
+
+    let payload: Value = match serde_json::from_slice(&body) {
+        Ok(payload) => payload,
+        Err(_) => return JsonResponse::bad_request("Invalid Asana payload").into_response(),
+    };
+    let events = payload
+        .get("events")
+        .and_then(|v| v.as_array())
+        .cloned()
+        .unwrap_or_default();
+
+    // Asana expects a quick acknowledgement and retries slow deliveries, so
+    // enrichment and enqueueing happen off the request path.
+    if !events.is_empty() {
+        tokio::spawn(async move {
+            dispatch_asana_events(&app_state, &subscription, &events).await;
+        });
+    }
+
+    StatusCode::OK.into_response()
+}
+
+async fn complete_handshake(
+    app_state: &AppState,
+    subscription: &AsanaWebhookSubscription,
+    secret: &str,
+) -> Response {
+    let Ok(header_value) = HeaderValue::from_str(secret) else {
+        return JsonResponse::bad_request("Invalid X-Hook-Secret").into_response();
+    };
+    let encrypted = match encrypt_secret(&app_state.config.api_secrets_encryption_key, secret) {
+        Ok(value) => value,
+        Err(err) => {
+            error!(?err, subscription_id = %subscription.id, "failed to encrypt Asana hook secret");
+            return JsonResponse::server_error("Failed to complete Asana handshake")
+                .into_response();
+        }
+    };
+
+    match app_state
+        .workflow_repo
+        .set_asana_webhook_secret(subscription.id, &encrypted)
+        .await
+    {
+        Ok(true) => {
+            let mut response = StatusCode::OK.into_response();
+            response
+                .headers_mut()
+                .insert(HOOK_SECRET_HEADER, header_value);
+            response
+        }
+        Ok(false) => {
+            warn!(subscription_id = %subscription.id, "rejected repeated Asana handshake");
+            JsonResponse::forbidden("Asana webhook handshake already completed").into_response()
+        }
+        Err(err) => {
+            error!(?err, subscription_id = %subscription.id, "failed to store Asana hook secret");
+            JsonResponse::server_error("Failed to complete Asana handshake").into_response()
+        }
+    }
+}
+
+/// `X-Hook-Signature` is the hex HMAC-SHA256 of the raw body keyed with the
+/// handshake secret.
+pub(crate) fn verify_asana_signature(secret: &str, signature: &str, body: &[u8]) -> bool {
+    let signature = signature.trim();
+    if signature.is_empty() {
+        return false;
+    }
+    let mut mac = HmacSha256::new_from_slice(secret.as_bytes()).expect("HMAC");
+    mac.update(body);
+    let expected = hex::encode(mac.finalize().into_bytes());
+    expected
+        .as_bytes()
+        .ct_eq(signature.to_ascii_lowercase().as_bytes())
+        .unwrap_u8()
+        == 1
+}
+
+/// What an Asana event points at once it is known to match the trigger.
+#[derive(Debug, PartialEq, Eq)]
+struct AsanaEventTarget {
+    task_gid: String,
+    story_gid: Option<String>,
+}
+
+fn match_webhook_event(kind: AsanaTriggerKind, event: &Value) -> Option<AsanaEventTarget> {
+    let action = event.get("action")?.as_str()?;
+    let resource = event.get("resource")?;
+    let resource_type = resource.get("resource_type")?.as_str()?;
+    let resource_gid = resource.get("gid")?.as_str()?.to_string();
+
+    match kind {
+        AsanaTriggerKind::NewTask => {
+            let parent_type = event
+                .get("parent")
+                .and_then(|p| p.get("resource_type"))
+                .and_then(|v| v.as_str());
+            (action == "added" && resource_type == "task" && parent_type == Some("project"))
+                .then_some(AsanaEventTarget {
+                    task_gid: resource_gid,
+                    story_gid: None,
+                })
+        }
+        AsanaTriggerKind::TaskCompleted => {
+            let field = event
+                .get("change")
+                .and_then(|c| c.get("field"))
+                .and_then(|v| v.as_str());
+            (action == "changed" && resource_type == "task" && field == Some("completed"))
+                .then_some(AsanaEventTarget {
+                    task_gid: resource_gid,
+                    story_gid: None,
+                })
+        }
+        AsanaTriggerKind::CommentAdded => {
+            let subtype = resource.get("resource_subtype").and_then(|v| v.as_str());
+            if action != "added" || resource_type != "story" || subtype != Some("comment_added") {
+                return None;
+            }
+            let task_gid = event.get("parent")?.get("gid")?.as_str()?.to_string();
+            Some(AsanaEventTarget {
+                task_gid,
+                story_gid: Some(resource_gid),
+            })
+        }
+    }
+}
+
+fn find_asana_trigger_node(
+    snapshot: &Value,
+    kind: AsanaTriggerKind,
+    project_gid: &str,
+) -> Option<(String, String)> {
+    snapshot.get("nodes")?.as_array()?.iter().find_map(|node| {
+        if node.get("type")?.as_str()? != "trigger" {
+            return None;
+        }
+        let data = node.get("data")?;
+        let trigger_type = data.get("triggerType")?.as_str()?;
+        if AsanaTriggerKind::parse(trigger_type)? != kind {
+            return None;
+        }
+        if data.get("projectGid")?.as_str()?.trim() != project_gid {
+            return None;
+        }
+        let id = node.get("id")?.as_str()?.to_string();
+        let label = data
+            .get("label")
+            .and_then(|v| v.as_str())
+            .map(str::to_string)
+            .unwrap_or_else(|| id.clone());
+        Some((id, label))
+    })
+}
+
+async fn dispatch_asana_events(
+    app_state: &AppState,
+    subscription: &AsanaWebhookSubscription,
+    events: &[Value],
+) {
+    let Some(kind) = AsanaTriggerKind::parse(&subscription.trigger_type) else {
+        return;
+    };
+    let workflow = match app_state
+        .workflow_repo
+        .find_workflow_by_id_public(subscription.workflow_id)
+        .await
+    {
+        Ok(Some(workflow)) => workflow,
+        Ok(None) => return,
+        Err(err) => {
+            error!(?err, workflow_id = %subscription.workflow_id, "failed to load workflow for Asana event");
+            return;
+        }
+    };
+    let Some((node_id, label)) =
+        find_asana_trigger_node(&workflow.data, kind, &subscription.resource_gid)
+    else {
+        return;
+    };
+
+    let targets: Vec<(AsanaEventTarget, String)> = events
+        .iter()
+        .filter_map(|event| {
+            let target = match_webhook_event(kind, event)?;
+            let created_at = event
+                .get("created_at")
+                .and_then(|v| v.as_str())
+                .unwrap_or_default()
+                .to_string();
+            Some((target, created_at))
+        })
+        .collect();
+    if targets.is_empty() {
+        return;
+    }
+
+    let access_token = match resolve_connection_token(
+        app_state,
+        &workflow,
+        &subscription.connection_scope,
+        subscription.connection_id,
+    )
+    .await
+    {
+        Ok(token) => token,
+        Err(err) => {
+            warn!(workflow_id = %workflow.id, %err, "cannot resolve Asana token for webhook event");
+            return;
+        }
+    };
+    let base_url = &app_state.config.provider_base_urls.asana;
+    let client = &app_state.http_client;
+
+    for (target, created_at) in targets {
+        let dedupe_key = format!(
+            "asana:{}:{}:{}",
+            kind.as_str(),
+            target.story_gid.as_deref().unwrap_or(&target.task_gid),
+            created_at
+        );
+        match app_state
+            .workflow_repo
+            .try_record_webhook_signature(workflow.id, &dedupe_key)
+            .await
+        {
+            Ok(true) => {}
+            Ok(false) => continue,
+            Err(err) => {
+                warn!(?err, workflow_id = %workflow.id, "failed to record Asana event id");
+                continue;
+            }
+        }
+
+        let task = match asana::get_task(client, base_url, &access_token, &target.task_gid).await {
+            Ok(task) => task,
+            Err(err) => {
+                warn!(workflow_id = %workflow.id, %err, "failed to load Asana task for event");
+                continue;
+            }
+        };
+        if kind == AsanaTriggerKind::TaskCompleted
+            && !task
+                .get("completed")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false)
+        {
+            continue;
+        }
+        let story = match target.story_gid.as_deref() {
+            Some(story_gid) => {
+                match asana::get_story(client, base_url, &access_token, story_gid).await {
+                    Ok(story) => Some(story),
+                    Err(err) => {
+                        warn!(workflow_id = %workflow.id, %err, "failed to load Asana comment");
+                        continue;
+                    }
+                }
+            }
+            None => None,
+        };
+
+        let context = asana::build_trigger_event(
+            kind,
+            &subscription.resource_gid,
+            &task,
+            story.as_ref(),
+            "webhook",
+        );
+        if let Err(response) = enqueue_external_trigger_run(
+            app_state,
+            &workflow,
+            Some(context),
+            Some((node_id.as_str(), label.as_str())),
+        )
+        .await
+        {
+            warn!(
+                workflow_id = %workflow.id,
+                status = %response.status(),
+                "failed to enqueue Asana-triggered run"
+            );
+        }
+    }
+}
+
+async fn resolve_connection_token(
+    app_state: &AppState,
+    workflow: &Workflow,
+    connection_scope: &str,
+    connection_id: Uuid,
+) -> Result<String, String> {
+    match connection_scope.trim().to_ascii_lowercase().as_str() {
+        "workspace" => {
+            let workspace_id = workflow
+                .workspace_id
+                .ok_or_else(|| "workspace connection requires a workspace workflow".to_string())?;
+            let connection = app_state
+                .workspace_oauth
+                .ensure_valid_workspace_token(connection_id)
+                .await
+                .map_err(|err| err.to_string())?;
+            if connection.workspace_id != workspace_id
+                || connection.provider != ConnectedOAuthProvider::Asana
+            {
+                return Err("connection is not an Asana connection of this workspace".into());
+            }
+            Ok(connection.access_token)
+        }
+        "user" | "personal" => {
+            let token = app_state
+                .oauth_accounts
+                .ensure_valid_access_token_for_connection(workflow.user_id, connection_id)
+                .await
+                .map_err(|err| err.to_string())?;
+            if token.provider != ConnectedOAuthProvider::Asana {
+                return Err("connection is not an Asana connection".into());
+            }
+            Ok(token.access_token)
+        }
+        other => Err(format!("unsupported connection scope `{other}`")),
+    }
+}
+
+/// Ensures an Asana webhook exists for the workflow's Asana trigger config.
+/// Returns `false` when webhooks are unavailable (no public API URL, or
+/// registration failed) so the caller falls back to polling.
+pub(crate) async fn sync_asana_webhook(
+    app_state: &AppState,
+    workflow: &Workflow,
+    config: &Value,
+) -> bool {
+    let read = |key: &str| {
+        config
+            .get(key)
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+    };
+    let kind = read("triggerType").and_then(AsanaTriggerKind::parse);
+    let project_gid = read("projectGid");
+    let connection_scope = read("connectionScope");
+    let connection_id = read("connectionId").and_then(|raw| Uuid::parse_str(raw).ok());
+    let (Some(kind), Some(project_gid), Some(connection_scope), Some(connection_id)) =
+        (kind, project_gid, connection_scope, connection_id)
+    else {
+        remove_asana_webhook(app_state, workflow).await;
+        return false;
+    };
+    let Some(public_base) = app_state.config.public_api_base_url.as_deref() else {
+        remove_asana_webhook(app_state, workflow).await;
+        return false;
+    };
+
+    if let Ok(Some(existing)) = app_state
+        .workflow_repo
+        .get_asana_webhook_subscription_for_workflow(workflow.id)
+        .await
+    {
+        if existing.webhook_gid.is_some()
+            && existing.trigger_type == kind.as_str()
+            && existing.resource_gid == project_gid
+            && existing.connection_scope == connection_scope
+            && existing.connection_id == connection_id
+        {
+            return true;
+        }
+    }
+    remove_asana_webhook(app_state, workflow).await;
+
+    let subscription = match app_state
+        .workflow_repo
+        .insert_asana_webhook_subscription(NewAsanaWebhookSubscription {
+            workflow_id: workflow.id,
+            user_id: workflow.user_id,
+            trigger_type: kind.as_str().to_string(),
+            resource_gid: project_gid.to_string(),
+            connection_scope: connection_scope.to_string(),
+            connection_id,
+        })
+        .await
+    {
+        Ok(subscription) => subscription,
+        Err(err) => {
+            error!(?err, workflow_id = %workflow.id, "failed to store Asana webhook subscription");
+            return false;
+        }
+    };
+
+    let registered = async {
+        let token =
+            resolve_connection_token(app_state, workflow, connection_scope, connection_id).await?;
+        let target = format!("{}/api/asana/webhooks/{}", public_base, subscription.id);
+        let webhook_gid = asana::create_webhook(
+            &app_state.http_client,
+            &app_state.config.provider_base_urls.asana,
+            &token,
+            project_gid,
+            &target,
+            kind.webhook_filters(),
+        )
+        .await
+        .map_err(|err| err.to_string())?;
+        app_state
+            .workflow_repo
+            .set_asana_webhook_gid(subscription.id, &webhook_gid)
+            .await
+            .map_err(|err| err.to_string())
+    }
+    .await;
+
+    match registered {
+        Ok(()) => true,
+        Err(err) => {
+            warn!(
+                workflow_id = %workflow.id,
+                %err,
+                "Asana webhook registration failed; falling back to polling"
+            );
+            if let Err(err) = app_state
+                .workflow_repo
+                .delete_asana_webhook_subscription(subscription.id)
+                .await
+            {
+                error!(?err, workflow_id = %workflow.id, "failed to drop Asana webhook subscription");
+            }
+            false
+        }
+    }
+}
+
+/// Whether the workflow currently receives Asana events through a
+/// registered webhook rather than polling.
+pub(crate) async fn has_asana_webhook(app_state: &AppState, workflow: &Workflow) -> bool {
+    match app_state
+        .workflow_repo
+        .get_asana_webhook_subscription_for_workflow(workflow.id)
+        .await
+    {
+        Ok(subscription) => subscription.is_some_and(|sub| sub.webhook_gid.is_some()),
+        Err(err) => {
+            error!(?err, workflow_id = %workflow.id, "failed to load Asana webhook subscription");
+            false
+        }
+    }
+}
+
+/// Deletes the workflow's Asana webhook (remotely, best effort) and its
+/// subscription row. Safe to call when none exists.
+pub(crate) async fn remove_asana_webhook(app_state: &AppState, workflow: &Workflow) {
+    let subscription = match app_state
+        .workflow_repo
+        .get_asana_webhook_subscription_for_workflow(workflow.id)
+        .await
+    {
+        Ok(Some(subscription)) => subscription,
+        Ok(None) => return,
+        Err(err) => {
+            error!(?err, workflow_id = %workflow.id, "failed to load Asana webhook subscription");
+            return;
+        }
+    };
+
+    if let Some(webhook_gid) = subscription.webhook_gid.as_deref() {
+        match resolve_connection_token(
+            app_state,
+            workflow,
+            &subscription.connection_scope,
+            subscription.connection_id,
+        )
+        .await
+        {
+            Ok(token) => {
+                if let Err(err) = asana::delete_webhook(
+                    &app_state.http_client,
+                    &app_state.config.provider_base_urls.asana,
+                    &token,
+                    webhook_gid,
+                )
+                .await
+                {
+                    if !err.is_not_found() {
+                        warn!(workflow_id = %workflow.id, %err, "failed to delete Asana webhook");
+                    }
+                }
+            }
+            Err(err) => {
+                warn!(workflow_id = %workflow.id, %err, "cannot resolve Asana token to delete webhook");
+            }
+        }
+    }
+
+    if let Err(err) = app_state
+        .workflow_repo
+        .delete_asana_webhook_subscription(subscription.id)
+        .await
+    {
+        error!(?err, workflow_id = %workflow.id, "failed to delete Asana webhook subscription");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn sign(secret: &str, body: &[u8]) -> String {
+        let mut mac = HmacSha256::new_from_slice(secret.as_bytes()).unwrap();
+        mac.update(body);
+        hex::encode(mac.finalize().into_bytes())
+    }
+
+    #[test]
+    fn verify_asana_signature_accepts_valid_and_rejects_tampered() {
+        let body = br#"{"events":[]}"#;
+        let signature = sign("hook-secret", body);
+        assert!(verify_asana_signature("hook-secret", &signature, body));
+        assert!(verify_asana_signature(
+            "hook-secret",
+            &signature.to_ascii_uppercase(),
+            body
+        ));
+        assert!(!verify_asana_signature("hook-secret", &signature, b"{}"));
+        assert!(!verify_asana_signature("other", &signature, body));
+        assert!(!verify_asana_signature("hook-secret", "", body));
+    }
+
+    #[test]
+    fn webhook_events_match_trigger_kinds() {
+        let added = json!({
+            "action": "added",
+            "resource": { "gid": "t1", "resource_type": "task" },
+            "parent": { "gid": "p1", "resource_type": "project" }
+        });
+        let completed = json!({
+            "action": "changed",
+            "resource": { "gid": "t1", "resource_type": "task" },
+            "change": { "field": "completed", "action": "changed" }
+        });
+        let comment = json!({
+            "action": "added",
+            "resource": { "gid": "s1", "resource_type": "story", "resource_subtype": "comment_added" },
+            "parent": { "gid": "t1", "resource_type": "task" }
+        });
+
+        assert_eq!(
+            match_webhook_event(AsanaTriggerKind::NewTask, &added),
+            Some(AsanaEventTarget {
+                task_gid: "t1".into(),
+                story_gid: None
+            })
+        );
+        assert!(match_webhook_event(AsanaTriggerKind::NewTask, &completed).is_none());
+        assert!(match_webhook_event(AsanaTriggerKind::TaskCompleted, &completed).is_some());
+        assert!(match_webhook_event(AsanaTriggerKind::TaskCompleted, &added).is_none());
+        assert_eq!(
+            match_webhook_event(AsanaTriggerKind::CommentAdded, &comment),
+            Some(AsanaEventTarget {
+                task_gid: "t1".into(),
+                story_gid: Some("s1".into())
+            })
+        );
+    }
+
+    #[test]
+    fn trigger_node_lookup_requires_matching_project() {
+        let snapshot = json!({
+            "nodes": [
+                { "id": "n1", "type": "trigger", "data": { "triggerType": "asana.new_task", "projectGid": "p2" } },
+                { "id": "n2", "type": "trigger", "data": { "triggerType": "asana.new_task", "projectGid": "p1", "label": "New task" } }
+            ]
+        });
+        assert_eq!(
+            find_asana_trigger_node(&snapshot, AsanaTriggerKind::NewTask, "p1"),
+            Some(("n2".to_string(), "New task".to_string()))
+        );
+        assert!(find_asana_trigger_node(&snapshot, AsanaTriggerKind::CommentAdded, "p1").is_none());
+    }
+}
@@ -379,7 +379,7 @@ mod tests {
 
     use crate::{
         config::{
-            Config, OAuthProviderConfig, OAuthSettings, StripeSettings,
+            Config, OAuthProviderConfig, OAuthSettings, ProviderBaseUrls, StripeSettings,
             DEFAULT_WORKSPACE_MEMBER_LIMIT, DEFAULT_WORKSPACE_MONTHLY_RUN_LIMIT,
             RUNAWAY_LIMIT_5MIN,
         },
@@ -471,6 +471,11 @@ mod tests {
                     client_secret: "stub".into(),
                     redirect_uri: "http://localhost".into(),
                 },
+                github: OAuthProviderConfig {
+                    client_id: "stub".into(),
+                    client_secret: "stub".into(),
+                    redirect_uri: "http://localhost".into(),
+                },
                 token_encryption_key: vec![0u8; 32],
             },
             api_secrets_encryption_key: vec![1u8; 32],
@@ -486,6 +491,9 @@ mod tests {
             workspace_member_limit: DEFAULT_WORKSPACE_MEMBER_LIMIT,
             workspace_monthly_run_limit: DEFAULT_WORKSPACE_MONTHLY_RUN_LIMIT,
             runaway_limit_5min: RUNAWAY_LIMIT_5MIN,
+            slack_signing_secret: None,
+            public_api_base_url: None,
+            provider_base_urls: ProviderBaseUrls::default(),
         });
         let app_state = AppState {
             db: Arc::new(db),
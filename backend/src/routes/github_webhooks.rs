@@ -0,0 +1,397 @@
+use axum::{
+    body::Bytes,
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use base64::Engine as _;
+use hmac::{Hmac, Mac};
+use serde_json::{json, Value};
+use sha2::Sha256;
+use tracing::{error, warn};
+use uuid::Uuid;
+
+use crate::responses::JsonResponse;
+use crate::routes::auth::session::AuthSession;
+use crate::routes::workflows::enqueue_external_trigger_run;
+use crate::services::github::{
+    verify_signature, DELIVERY_HEADER, EVENT_HEADER, GITHUB_WEBHOOK, SIGNATURE_HEADER,
+};
+use crate::state::AppState;
+use crate::utils::webhook_signing::webhook_secret;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Secret to paste into the GitHub webhook settings. It is derived from the
+/// workflow's webhook salt, so regenerating the workflow webhook rotates it.
+fn compute_github_webhook_secret(
+    secret: &str,
+    user_id: Uuid,
+    workflow_id: Uuid,
+    salt: Uuid,
+) -> String {
+    let mut mac =
+        HmacSha256::new_from_slice(secret.as_bytes()).expect("HMAC can take key of any size");
+    mac.update(user_id.as_bytes());
+    mac.update(workflow_id.as_bytes());
+    mac.update(salt.as_bytes());
+    mac.update(b"github");
+    let res = mac.finalize().into_bytes();
+    base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(res)
+}
+
+/// Delivery URL and secret for the workflow's `github.webhook` trigger.
+pub async fn get_github_webhook(
+    State(app_state): State<AppState>,
+    AuthSession(claims): AuthSession,
+    Path(workflow_id): Path<Uuid>,
+) -> Response {
+    let user_id = match Uuid::parse_str(&claims.id) {
+        Ok(id) => id,
+        Err(_) => return JsonResponse::unauthorized("Invalid user ID").into_response(),
+    };
+    let wf = match app_state
+        .workflow_repo
+        .find_workflow_for_member(user_id, workflow_id)
+        .await
+    {
+        Ok(Some(wf)) => wf,
+        Ok(None) => return JsonResponse::not_found("Workflow not found").into_response(),
+        Err(err) => {
+            error!(?err, %workflow_id, "failed to load workflow for GitHub webhook");
+            return JsonResponse::server_error("Failed to load GitHub webhook").into_response();
+        }
+    };
+    let Some(secret) = webhook_secret(app_state.config.as_ref()) else {
+        return JsonResponse::server_error(
+            "Webhook secret is not configured; contact an administrator.",
+        )
+        .into_response();
+    };
+
+    let path = format!("/api/github/webhooks/{}", wf.id);
+    let url = match app_state.config.public_api_base_url.as_deref() {
+        Some(base) => format!("{}{}", base.trim_end_matches('/'), path),
+        None => path,
+    };
+    (
+        StatusCode::OK,
+        Json(json!({
+            "success": true,
+            "url": url,
+            "contentType": "application/json",
+            "secret": compute_github_webhook_secret(&secret, wf.user_id, wf.id, wf.webhook_salt),
+        })),
+    )
+        .into_response()
+}
+
+/// Receives GitHub webhook deliveries for one workflow. Deliveries must be
+/// signed with the workflow's GitHub secret; `ping` is acknowledged without
+/// starting a run.
+pub async fn github_webhook(
+    State(app_state): State<AppState>,
+    Path(workflow_id): Path<Uuid>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    let workflow = match app_state
+        .workflow_repo
+        .find_workflow_by_id_public(workflow_id)
+        .await
+    {
+        Ok(Some(workflow)) => workflow,
+        Ok(None) => return JsonResponse::not_found("Unknown GitHub webhook").into_response(),
+        Err(err) => {
+            error!(?err, %workflow_id, "failed to load workflow for GitHub webhook");
+            return JsonResponse::server_error("Failed to process GitHub webhook").into_response();
+        }
+    };
+    if !has_github_trigger(&workflow.data) {
+        return JsonResponse::not_found("Unknown GitHub webhook").into_response();
+    }
+
+    let Some(secret) = webhook_secret(app_state.config.as_ref()) else {
+        return JsonResponse::server_error("Failed to process GitHub webhook").into_response();
+    };
+    let hook_secret = compute_github_webhook_secret(
+        &secret,
+        workflow.user_id,
+        workflow.id,
+        workflow.webhook_salt,
+    );
+    let header = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .map(str::trim)
+            .unwrap_or_default()
+    };
+    if !verify_signature(&hook_secret, header(SIGNATURE_HEADER), &body) {
+        return JsonResponse::unauthorized("Invalid GitHub signature").into_response();
+    }
+
+    let event = header(EVENT_HEADER).to_ascii_lowercase();
+    if event == "ping" {
+        return (StatusCode::OK, Json(json!({ "success": true }))).into_response();
+    }
+    if event.is_empty() {
+        return JsonResponse::bad_request("Missing X-GitHub-Event header").into_response();
+    }
+    let payload: Value = match serde_json::from_slice(&body) {
+        Ok(payload) => payload,
+        Err(_) => {
+            return JsonResponse::bad_request(
+                "GitHub webhooks must use the application/json content type",
+            )
+            .into_response()
+        }
+    };
+
+    let event_context = build_trigger_event(&event, header(DELIVERY_HEADER), &payload);
+    let Some((node_id, label)) = find_github_trigger_node(&workflow.data, &event_context) else {
+        return (
+            StatusCode::OK,
+            Json(json!({ "success": true, "ignored": true })),
+        )
+            .into_response();
+    };
+
+    let delivery = header(DELIVERY_HEADER);
+    if !delivery.is_empty() {
+        match app_state
+            .workflow_repo
+            .try_record_webhook_signature(workflow.id, &format!("github:{delivery}"))
+            .await
+        {
+            Ok(true) => {}
+            Ok(false) => {
+                return (
+                    StatusCode::OK,
+                    Json(json!({ "success": true, "duplicate": true })),
+                )
+                    .into_response()
+            }
+            Err(err) => {
+                warn!(?err, workflow_id = %workflow.id, "failed to record GitHub delivery id");
+            }
+        }
+    }
+
+    match enqueue_external_trigger_run(
+        &app_state,
+        &workflow,
+        Some(event_context),
+        Some((node_id.as_str(), label.as_str())),
+    )
+    .await
+    {
+        Ok(run) => (
+            StatusCode::ACCEPTED,
+            Json(json!({ "success": true, "runId": run.id })),
+        )
+            .into_response(),
+        Err(response) => response,
+    }
+}
+
+fn build_trigger_event(event: &str, delivery: &str, payload: &Value) -> Value {
+    let field = |key: &str| payload.get(key).cloned().unwrap_or(Value::Null);
+    json!({
+        "trigger": GITHUB_WEBHOOK,
+        "event": event,
+        "action": field("action"),
+        "delivery": delivery,
+        "repository": payload
+            .get("repository")
+            .and_then(|r| r.get("full_name"))
+            .cloned()
+            .unwrap_or(Value::Null),
+        "sender": payload
+            .get("sender")
+            .and_then(|s| s.get("login"))
+            .cloned()
+            .unwrap_or(Value::Null),
+        "payload": payload,
+    })
+}
+
+/// Reads a filter written either as a JSON array or a comma-separated string.
+fn read_filter(data: &Value, key: &str) -> Vec<String> {
+    let items: Vec<String> = match data.get(key) {
+        Some(Value::Array(items)) => items
+            .iter()
+            .filter_map(|v| v.as_str())
+            .map(str::to_string)
+            .collect(),
+        Some(Value::String(raw)) => raw.split(',').map(str::to_string).collect(),
+        _ => Vec::new(),
+    };
+    items
+        .into_iter()
+        .map(|s| s.trim().to_ascii_lowercase())
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+fn github_trigger_nodes(snapshot: &Value) -> impl Iterator<Item = &Value> {
+    snapshot
+        .get("nodes")
+        .and_then(|v| v.as_array())
+        .into_iter()
+        .flatten()
+        .filter(|node| {
+            node.get("type").and_then(|v| v.as_str()) == Some("trigger")
+                && node
+                    .get("data")
+                    .and_then(|d| d.get("triggerType"))
+                    .and_then(|v| v.as_str())
+                    .is_some_and(|t| t.trim().eq_ignore_ascii_case(GITHUB_WEBHOOK))
+        })
+}
+
+fn has_github_trigger(snapshot: &Value) -> bool {
+    github_trigger_nodes(snapshot).next().is_some()
+}
+
+/// First `github.webhook` trigger whose `events`, `actions` and `repository`
+/// filters all accept the delivery. Empty filters accept everything.
+fn find_github_trigger_node(snapshot: &Value, event: &Value) -> Option<(String, String)> {
+    let name = event.get("event")?.as_str()?;
+    let action = event
+        .get("action")
+        .and_then(|v| v.as_str())
+        .map(str::to_ascii_lowercase);
+    let repository = event
+        .get("repository")
+        .and_then(|v| v.as_str())
+        .map(str::to_ascii_lowercase);
+
+    github_trigger_nodes(snapshot).find_map(|node| {
+        let data = node.get("data")?;
+        let events = read_filter(data, "events");
+        if !events.is_empty() && !events.iter().any(|e| e == "*" || e == name) {
+            return None;
+        }
+        let actions = read_filter(data, "actions");
+        if !actions.is_empty() && !action.as_ref().is_some_and(|a| actions.contains(a)) {
+            return None;
+        }
+        if let Some(wanted) = data
+            .get("repository")
+            .and_then(|v| v.as_str())
+            .map(|s| s.trim().to_ascii_lowercase())
+            .filter(|s| !s.is_empty())
+        {
+            if repository.as_deref() != Some(wanted.as_str()) {
+                return None;
+            }
+        }
+        let id = node.get("id")?.as_str()?.to_string();
+        let label = data
+            .get("label")
+            .and_then(|v| v.as_str())
+            .map(str::to_string)
+            .unwrap_or_else(|| id.clone());
+        Some((id, label))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot() -> Value {
+        json!({
+            "nodes": [
+                { "id": "w", "type": "trigger", "data": { "triggerType": "webhook" } },
+                {
+                    "id": "g1",
+                    "type": "trigger",
+                    "data": {
+                        "triggerType": "github.webhook",
+                        "label": "Opened issues",
+                        "events": ["issues"],
+                        "actions": "opened, reopened",
+                        "repository": "Octo/App"
+                    }
+                },
+                {
+                    "id": "g2",
+                    "type": "trigger",
+                    "data": { "triggerType": "github.webhook", "events": "push,release" }
+                }
+            ]
+        })
+    }
+
+    fn event(name: &str, action: Option<&str>, repo: &str) -> Value {
+        let mut payload = json!({
+            "repository": { "full_name": repo },
+            "sender": { "login": "hubot" }
+        });
+        if let Some(action) = action {
+            payload["action"] = json!(action);
+        }
+        build_trigger_event(name, "d-1", &payload)
+    }
+
+    #[test]
+    fn trigger_events_expose_common_fields() {
+        let context = event("issues", Some("opened"), "octo/app");
+        assert_eq!(context["trigger"], "github.webhook");
+        assert_eq!(context["event"], "issues");
+        assert_eq!(context["action"], "opened");
+        assert_eq!(context["delivery"], "d-1");
+        assert_eq!(context["repository"], "octo/app");
+        assert_eq!(context["sender"], "hubot");
+        assert_eq!(context["payload"]["sender"]["login"], "hubot");
+    }
+
+    #[test]
+    fn trigger_nodes_filter_by_event_action_and_repository() {
+        let snapshot = snapshot();
+        assert!(has_github_trigger(&snapshot));
+        assert_eq!(
+            find_github_trigger_node(&snapshot, &event("issues", Some("reopened"), "octo/app")),
+            Some(("g1".to_string(), "Opened issues".to_string()))
+        );
+        assert!(
+            find_github_trigger_node(&snapshot, &event("issues", Some("closed"), "octo/app"))
+                .is_none()
+        );
+        assert!(find_github_trigger_node(
+            &snapshot,
+            &event("issues", Some("opened"), "octo/other")
+        )
+        .is_none());
+        assert_eq!(
+            find_github_trigger_node(&snapshot, &event("push", None, "octo/other")),
+            Some(("g2".to_string(), "g2".to_string()))
+        );
+        assert!(find_github_trigger_node(&snapshot, &event("star", None, "octo/app")).is_none());
+        assert!(!has_github_trigger(&json!({ "nodes": [] })));
+    }
+
+    #[test]
+    fn webhook_secrets_rotate_with_the_salt() {
+        let secret = "0123456789abcdef0123456789ABCDEF";
+        let (user, workflow, salt) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let hook = compute_github_webhook_secret(secret, user, workflow, salt);
+        assert_eq!(
+            hook,
+            compute_github_webhook_secret(secret, user, workflow, salt)
+        );
+        assert_ne!(
+            hook,
+            compute_github_webhook_secret(secret, user, workflow, Uuid::new_v4())
+        );
+
+        let body = br#"{"action":"opened"}"#;
+        let mut mac = HmacSha256::new_from_slice(hook.as_bytes()).unwrap();
+        mac.update(body);
+        let header = format!("sha256={}", hex::encode(mac.finalize().into_bytes()));
+        assert!(verify_signature(&hook, &header, body));
+    }
+}
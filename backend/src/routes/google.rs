@@ -79,7 +79,12 @@ pub async fn list_spreadsheet_sheets(
     }
 
     let url = format!(
-        "https://sheets.googleapis.com/v4/spreadsheets/{}?fields=sheets.properties.sheetId,sheets.properties.title",
+        "{}/{}?fields=sheets.properties.sheetId,sheets.properties.title",
+        state
+            .config
+            .provider_base_urls
+            .google_sheets
+            .trim_end_matches('/'),
         urlencoding::encode(trimmed)
     );
 
@@ -425,8 +430,8 @@ mod tests {
     use uuid::Uuid;
 
     use crate::config::{
-        Config, OAuthProviderConfig, OAuthSettings, StripeSettings, DEFAULT_WORKSPACE_MEMBER_LIMIT,
-        DEFAULT_WORKSPACE_MONTHLY_RUN_LIMIT, RUNAWAY_LIMIT_5MIN,
+        Config, OAuthProviderConfig, OAuthSettings, ProviderBaseUrls, StripeSettings,
+        DEFAULT_WORKSPACE_MEMBER_LIMIT, DEFAULT_WORKSPACE_MONTHLY_RUN_LIMIT, RUNAWAY_LIMIT_5MIN,
     };
     use crate::db::mock_db::{MockDb, NoopWorkflowRepository, NoopWorkspaceRepository};
     use crate::db::mock_stripe_event_log_repository::MockStripeEventLogRepository;
@@ -556,6 +561,11 @@ mod tests {
                     client_secret: "secret".into(),
                     redirect_uri: "http://localhost/asana".into(),
                 },
+                github: OAuthProviderConfig {
+                    client_id: "client".into(),
+                    client_secret: "secret".into(),
+                    redirect_uri: "http://localhost/asana".into(),
+                },
                 token_encryption_key: vec![1u8; 32],
             },
             api_secrets_encryption_key: vec![2u8; 32],
@@ -571,6 +581,9 @@ mod tests {
             workspace_member_limit: DEFAULT_WORKSPACE_MEMBER_LIMIT,
             workspace_monthly_run_limit: DEFAULT_WORKSPACE_MONTHLY_RUN_LIMIT,
             runaway_limit_5min: RUNAWAY_LIMIT_5MIN,
+            slack_signing_secret: None,
+            public_api_base_url: None,
+            provider_base_urls: ProviderBaseUrls::default(),
         })
     }
 
@@ -100,6 +100,7 @@ pub async fn list_notion_databases(
 
     let response = match notion::search_databases(
         &state.http_client,
+        &state.config.provider_base_urls.notion,
         &access_token,
         query.search.as_deref(),
         query.start_cursor.as_deref(),
@@ -143,11 +144,17 @@ pub async fn get_notion_database_schema(
         Err(resp) => return resp,
     };
 
-    let database =
-        match notion::retrieve_database(&state.http_client, &access_token, &database_id).await {
-            Ok(db) => db,
-            Err(err) => return map_notion_error(err),
-        };
+    let database = match notion::retrieve_database(
+        &state.http_client,
+        &state.config.provider_base_urls.notion,
+        &access_token,
+        &database_id,
+    )
+    .await
+    {
+        Ok(db) => db,
+        Err(err) => return map_notion_error(err),
+    };
 
     let mut properties = Vec::new();
     let mut title_property_id = None;
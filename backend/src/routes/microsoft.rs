@@ -122,7 +122,13 @@ pub async fn list_teams(
         Err(resp) => return resp,
     };
 
-    let teams = match fetch_joined_teams(state.http_client.as_ref(), &token.access_token).await {
+    let teams = match fetch_joined_teams(
+        state.http_client.as_ref(),
+        &state.config.provider_base_urls.microsoft_graph,
+        &token.access_token,
+    )
+    .await
+    {
         Ok(items) => items,
         Err(err) => return graph_error_response(err),
     };
@@ -158,6 +164,7 @@ pub async fn list_team_channels(
 
     let channels = match fetch_team_channels(
         state.http_client.as_ref(),
+        &state.config.provider_base_urls.microsoft_graph,
         &token.access_token,
         encoded_team.as_ref(),
     )
@@ -253,6 +260,7 @@ pub async fn list_channel_members(
 
     let members = match fetch_channel_members(
         state.http_client.as_ref(),
+        &state.config.provider_base_urls.microsoft_graph,
         &token.access_token,
         encoded_team.as_ref(),
         encoded_channel.as_ref(),
@@ -422,6 +430,7 @@ async fn ensure_workspace_token(
                 account_email: connection.account_email,
                 is_shared: true,
                 updated_at: connection.updated_at,
+                custom_provider_id: None,
             })
         })
 }
@@ -463,8 +472,8 @@ mod tests {
     use uuid::Uuid;
 
     use crate::config::{
-        Config, OAuthProviderConfig, OAuthSettings, StripeSettings, DEFAULT_WORKSPACE_MEMBER_LIMIT,
-        DEFAULT_WORKSPACE_MONTHLY_RUN_LIMIT, RUNAWAY_LIMIT_5MIN,
+        Config, OAuthProviderConfig, OAuthSettings, ProviderBaseUrls, StripeSettings,
+        DEFAULT_WORKSPACE_MEMBER_LIMIT, DEFAULT_WORKSPACE_MONTHLY_RUN_LIMIT, RUNAWAY_LIMIT_5MIN,
     };
     use crate::db::{
         mock_db::{MockDb, NoopWorkflowRepository, NoopWorkspaceRepository},
@@ -645,6 +654,13 @@ mod tests {
             Ok(Vec::new())
         }
 
+        async fn list_slack_by_team(
+            &self,
+            _slack_team_id: &str,
+        ) -> Result<Vec<WorkspaceConnection>, sqlx::Error> {
+            Ok(Vec::new())
+        }
+
         async fn list_for_workspace(
             &self,
             _workspace_id: Uuid,
@@ -802,6 +818,11 @@ mod tests {
                     client_secret: "secret".into(),
                     redirect_uri: "http://localhost/asana".into(),
                 },
+                github: OAuthProviderConfig {
+                    client_id: "client".into(),
+                    client_secret: "secret".into(),
+                    redirect_uri: "http://localhost/asana".into(),
+                },
                 token_encryption_key: vec![0u8; 32],
             },
             api_secrets_encryption_key: vec![1u8; 32],
@@ -817,6 +838,9 @@ mod tests {
             workspace_member_limit: DEFAULT_WORKSPACE_MEMBER_LIMIT,
             workspace_monthly_run_limit: DEFAULT_WORKSPACE_MONTHLY_RUN_LIMIT,
             runaway_limit_5min: RUNAWAY_LIMIT_5MIN,
+            slack_signing_secret: None,
+            public_api_base_url: None,
+            provider_base_urls: ProviderBaseUrls::default(),
         })
     }
 
@@ -1,10 +1,12 @@
 pub mod account;
 pub mod admin;
 pub mod asana;
+pub mod asana_webhooks;
 pub mod auth;
 pub mod billing;
 pub mod dashboard;
 pub mod early_access;
+pub mod github_webhooks;
 pub mod google;
 pub mod integrations;
 pub mod issues;
@@ -13,6 +15,7 @@ pub mod oauth;
 pub mod options;
 pub mod plan_limits;
 pub mod slack;
+pub mod slack_events;
 pub mod stripe;
 pub mod workflows;
 pub mod workspaces;
@@ -9,8 +9,10 @@ use super::{
 use crate::db::workspace_connection_repository::WorkspaceConnectionListing;
 use crate::models::workspace::WorkspaceMembershipSummary;
 use crate::routes::auth::claims::Claims;
+use crate::services::oauth::account_service::parse_token_metadata;
 use crate::services::oauth::account_service::StoredOAuthToken;
 use axum::http::StatusCode;
+use std::collections::HashMap;
 
 #[derive(Debug, Deserialize)]
 pub struct ListConnectionsQuery {
@@ -64,7 +66,7 @@ struct RevokeResponse {
     connection_id: Option<Uuid>,
 }
 
-async fn ensure_workspace_membership(
+pub(super) async fn ensure_workspace_membership(
     app_state: &AppState,
     user_id: Uuid,
     workspace_id: Uuid,
@@ -294,11 +296,12 @@ pub async fn list_connections(
         );
     }
 
+    let custom_ids = custom_provider_ids(&state, &workspace_connections).await;
     let mut workspace = ProviderGroupedConnections::default();
     for connection in workspace_connections {
         workspace.push(
             connection.provider,
-            workspace_payload_from_listing(connection),
+            workspace_payload_from_listing(connection, &custom_ids),
         );
     }
 
@@ -373,9 +376,10 @@ pub async fn list_provider_connections(
         .into_iter()
         .map(|token| personal_payload_from_token(token, &personal_owner))
         .collect::<Vec<_>>();
+    let custom_ids = custom_provider_ids(&state, &workspace_connections).await;
     let workspace = workspace_connections
         .into_iter()
-        .map(workspace_payload_from_listing)
+        .map(|connection| workspace_payload_from_listing(connection, &custom_ids))
         .collect::<Vec<_>>();
 
     Json(ProviderConnectionsResponse {
@@ -449,11 +453,12 @@ pub async fn get_connection_by_id(
         return resp;
     }
 
+    let custom_ids = custom_provider_ids(&state, std::slice::from_ref(&target)).await;
     Json(ConnectionLookupResponse {
         success: true,
         connection_id: target.connection_id,
         personal: None,
-        workspace: Some(workspace_payload_from_listing(target)),
+        workspace: Some(workspace_payload_from_listing(target, &custom_ids)),
     })
     .into_response()
 }
@@ -545,11 +550,49 @@ fn personal_payload_from_token(
         last_refreshed_at: token.updated_at,
         requires_reconnect: false,
         owner: owner.clone(),
+        custom_provider_id: token.custom_provider_id,
     }
 }
 
+/// Maps custom workspace connections to their provider definitions. Listings
+/// carry no metadata, so the connections are loaded once per workspace.
+async fn custom_provider_ids(
+    state: &AppState,
+    listings: &[WorkspaceConnectionListing],
+) -> HashMap<Uuid, Uuid> {
+    let mut workspace_ids: Vec<Uuid> = listings
+        .iter()
+        .filter(|listing| listing.provider == ConnectedOAuthProvider::Custom)
+        .map(|listing| listing.workspace_id)
+        .collect();
+    workspace_ids.sort();
+    workspace_ids.dedup();
+
+    let mut ids = HashMap::new();
+    for workspace_id in workspace_ids {
+        match state
+            .workspace_connection_repo
+            .list_by_workspace_and_provider(workspace_id, ConnectedOAuthProvider::Custom)
+            .await
+        {
+            Ok(connections) => {
+                ids.extend(connections.into_iter().filter_map(|connection| {
+                    parse_token_metadata(&connection.metadata)
+                        .custom
+                        .map(|custom| (connection.id, custom.provider_id))
+                }));
+            }
+            Err(err) => {
+                error!(%workspace_id, ?err, "Failed to load custom OAuth connection metadata");
+            }
+        }
+    }
+    ids
+}
+
 fn workspace_payload_from_listing(
     connection: WorkspaceConnectionListing,
+    custom_ids: &HashMap<Uuid, Uuid>,
 ) -> WorkspaceConnectionPayload {
     let shared_by_name = format_shared_name(
         &connection.shared_by_first_name,
@@ -577,6 +620,7 @@ fn workspace_payload_from_listing(
         requires_reconnect: connection.requires_reconnect,
         has_incoming_webhook: connection.has_incoming_webhook,
         owner,
+        custom_provider_id: custom_ids.get(&connection.id).copied(),
     }
 }
 
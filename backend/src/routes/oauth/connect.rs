@@ -3,10 +3,10 @@ use super::{
         build_slack_state, build_state_cookie, clear_state_cookie, error_message_for_redirect,
         handle_callback, parse_slack_state, provider_to_key, redirect_success_with_workspace,
         redirect_with_error, redirect_with_error_for_provider, redirect_with_error_with_workspace,
-        CallbackQuery, ASANA_AUTH_URL, ASANA_STATE_COOKIE, GOOGLE_AUTH_URL, GOOGLE_STATE_COOKIE,
-        MICROSOFT_AUTH_URL, MICROSOFT_STATE_COOKIE, NOTION_AUTH_URL, NOTION_STATE_COOKIE,
-        OAUTH_PLAN_RESTRICTION_MESSAGE, SLACK_AUTH_URL, SLACK_STATE_COOKIE,
-        SLACK_WORKSPACE_REQUIRED_MESSAGE,
+        CallbackQuery, ASANA_AUTH_URL, ASANA_STATE_COOKIE, GITHUB_AUTH_URL, GITHUB_STATE_COOKIE,
+        GOOGLE_AUTH_URL, GOOGLE_STATE_COOKIE, MICROSOFT_AUTH_URL, MICROSOFT_STATE_COOKIE,
+        NOTION_AUTH_URL, NOTION_STATE_COOKIE, OAUTH_PLAN_RESTRICTION_MESSAGE, SLACK_AUTH_URL,
+        SLACK_STATE_COOKIE, SLACK_WORKSPACE_REQUIRED_MESSAGE,
     },
     prelude::*,
 };
@@ -57,7 +57,7 @@ fn decode_notion_state(raw: &str) -> Option<NotionState> {
     serde_json::from_slice(&decoded).ok()
 }
 
-fn normalize_notion_scope(
+pub(super) fn normalize_connection_scope(
     raw: Option<&str>,
     workspace_id: Option<Uuid>,
 ) -> Result<String, Box<Response>> {
@@ -92,7 +92,7 @@ const OAUTH_VIEWER_RESTRICTION_MESSAGE: &str =
 const OAUTH_WORKSPACE_ACCESS_ERROR_MESSAGE: &str =
     "We couldn't verify your access to this workspace. Please try again.";
 
-async fn ensure_oauth_permissions(
+pub(super) async fn ensure_oauth_permissions(
     state: &AppState,
     user_id: Uuid,
     claims_plan: Option<&str>,
@@ -653,12 +653,10 @@ async fn slack_auth_test(
     access_token: &str,
     require_scopes: bool,
 ) -> Result<SlackAuthTestResult, String> {
-    let base = std::env::var("SLACK_API_BASE_URL")
-        .ok()
-        .or_else(|| std::env::var("SLACK_API_BASE").ok())
-        .filter(|value| !value.trim().is_empty())
-        .unwrap_or_else(|| "https://slack.com/api".to_string());
-    let url = format!("{}/auth.test", base.trim_end_matches('/'));
+    let url = format!(
+        "{}/auth.test",
+        state.config.provider_base_urls.slack.trim_end_matches('/')
+    );
 
     let response = state
         .http_client
@@ -831,6 +829,69 @@ pub async fn asana_connect_callback(
     .await
 }
 
+pub async fn github_connect_start(
+    State(state): State<AppState>,
+    AuthSession(claims): AuthSession,
+    Query(params): Query<ConnectQuery>,
+    jar: CookieJar,
+) -> Response {
+    match Uuid::parse_str(&claims.id) {
+        Ok(user_id) => {
+            if let Err(response) = ensure_oauth_permissions(
+                &state,
+                user_id,
+                claims.plan.as_deref(),
+                params.workspace,
+                ConnectedOAuthProvider::GitHub,
+            )
+            .await
+            {
+                return response;
+            }
+        }
+        Err(_) => {
+            let plan_tier = NormalizedPlanTier::from_option(claims.plan.as_deref());
+            if plan_tier.is_solo() {
+                return redirect_with_error(
+                    &state.config,
+                    ConnectedOAuthProvider::GitHub,
+                    OAUTH_PLAN_RESTRICTION_MESSAGE,
+                );
+            }
+        }
+    }
+
+    let state_token = generate_csrf_token();
+    let cookie = build_state_cookie(GITHUB_STATE_COOKIE, &state_token);
+    let jar = jar.add(cookie);
+
+    let mut url = Url::parse(GITHUB_AUTH_URL).expect("valid github auth url");
+    url.query_pairs_mut()
+        .append_pair("client_id", &state.config.oauth.github.client_id)
+        .append_pair("redirect_uri", &state.config.oauth.github.redirect_uri)
+        .append_pair("scope", state.oauth_accounts.github_scopes())
+        .append_pair("state", &state_token);
+
+    (jar, Redirect::to(url.as_str())).into_response()
+}
+
+pub async fn github_connect_callback(
+    State(state): State<AppState>,
+    AuthSession(claims): AuthSession,
+    jar: CookieJar,
+    Query(query): Query<CallbackQuery>,
+) -> Response {
+    handle_callback(
+        state,
+        claims,
+        jar,
+        query,
+        ConnectedOAuthProvider::GitHub,
+        GITHUB_STATE_COOKIE,
+    )
+    .await
+}
+
 pub async fn notion_connect_start(
     State(state): State<AppState>,
     AuthSession(claims): AuthSession,
@@ -849,7 +910,7 @@ pub async fn notion_connect_start(
     };
 
     let connection_scope =
-        match normalize_notion_scope(params.connection_scope.as_deref(), params.workspace) {
+        match normalize_connection_scope(params.connection_scope.as_deref(), params.workspace) {
             Ok(scope) => scope,
             Err(resp) => return *resp,
         };
@@ -0,0 +1,664 @@
+use super::{
+    accounts::ensure_workspace_membership,
+    connect::{ensure_oauth_permissions, normalize_connection_scope, ConnectQuery},
+    helpers::{
+        build_state_cookie, clear_state_cookie, error_message_for_redirect,
+        redirect_success_with_workspace, redirect_with_error, redirect_with_error_with_workspace,
+        CallbackQuery, CUSTOM_STATE_COOKIE,
+    },
+    prelude::*,
+};
+use crate::db::workspace_connection_repository::NewCustomOAuthProvider;
+use crate::engine::actions::http::TriggerEgress;
+use crate::models::oauth_token::CustomOAuthProvider;
+use crate::models::workspace::WorkspaceRole;
+use crate::routes::workflows::is_unique_violation;
+use crate::services::oauth::custom_provider::{
+    normalize_scopes, CustomOAuthClient, TokenAuthMethod,
+};
+use crate::services::oauth::workspace_service::WorkspaceOAuthError;
+use base64::Engine as _;
+
+const PROVIDER: ConnectedOAuthProvider = ConnectedOAuthProvider::Custom;
+const MAX_NAME_LENGTH: usize = 100;
+
+#[derive(Debug, Deserialize)]
+pub struct CustomProvidersQuery {
+    pub workspace: Uuid,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CustomProviderRequest {
+    name: String,
+    authorize_url: String,
+    token_url: String,
+    #[serde(default)]
+    userinfo_url: Option<String>,
+    #[serde(default)]
+    scopes: Option<String>,
+    #[serde(default)]
+    use_pkce: Option<bool>,
+    client_id: String,
+    /// Left empty on update to keep the stored secret.
+    #[serde(default)]
+    client_secret: Option<String>,
+    #[serde(default)]
+    token_auth_method: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CustomProviderPayload {
+    id: Uuid,
+    workspace_id: Uuid,
+    name: String,
+    authorize_url: String,
+    token_url: String,
+    userinfo_url: Option<String>,
+    scopes: String,
+    use_pkce: bool,
+    client_id: String,
+    has_client_secret: bool,
+    token_auth_method: &'static str,
+    redirect_uri: String,
+    #[serde(with = "time::serde::rfc3339")]
+    created_at: OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339")]
+    updated_at: OffsetDateTime,
+}
+
+#[derive(Serialize)]
+struct CustomProvidersResponse {
+    success: bool,
+    providers: Vec<CustomProviderPayload>,
+}
+
+#[derive(Serialize)]
+struct CustomProviderResponse {
+    success: bool,
+    provider: CustomProviderPayload,
+}
+
+/// Carried in the state cookie between start and callback. Only `nonce`
+/// travels through the provider as the `state` parameter, so the PKCE
+/// verifier never leaves the browser/server pair.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CustomOAuthState {
+    nonce: String,
+    provider_id: Uuid,
+    user_id: Uuid,
+    connection_scope: String,
+    #[serde(default)]
+    code_verifier: Option<String>,
+}
+
+/// All custom providers share one callback; the state cookie says which
+/// provider the code belongs to.
+pub(crate) fn custom_redirect_uri(config: &Config) -> String {
+    let base = config
+        .public_api_base_url
+        .as_deref()
+        .unwrap_or(config.frontend_origin.as_str());
+    format!("{}/api/oauth/custom/callback", base.trim_end_matches('/'))
+}
+
+fn payload_from_record(
+    state: &AppState,
+    record: &CustomOAuthProvider,
+) -> Result<CustomProviderPayload, Box<Response>> {
+    let client = CustomOAuthClient::decrypt(record, &state.config.oauth.token_encryption_key)
+        .map_err(|err| {
+            error!(provider_id = %record.id, ?err, "Failed to decrypt custom OAuth provider");
+            JsonResponse::server_error("Failed to load OAuth provider").into_response()
+        })?;
+    Ok(CustomProviderPayload {
+        id: record.id,
+        workspace_id: record.workspace_id,
+        name: record.name.clone(),
+        authorize_url: record.authorize_url.clone(),
+        token_url: record.token_url.clone(),
+        userinfo_url: record.userinfo_url.clone(),
+        scopes: record.scopes.clone(),
+        use_pkce: record.use_pkce,
+        client_id: client.client_id,
+        has_client_secret: client.client_secret.is_some(),
+        token_auth_method: client.token_auth_method.as_str(),
+        redirect_uri: custom_redirect_uri(&state.config),
+        created_at: record.created_at,
+        updated_at: record.updated_at,
+    })
+}
+
+/// Endpoints must be https and pass the egress rules the server applies
+/// again before each token or userinfo request.
+fn validate_endpoint(
+    egress: &TriggerEgress,
+    label: &str,
+    raw: &str,
+) -> Result<String, Box<Response>> {
+    let trimmed = raw.trim();
+    let Some(url) = Url::parse(trimmed)
+        .ok()
+        .filter(|url| url.scheme() == "https" && url.host_str().is_some())
+    else {
+        return Err(Box::new(
+            JsonResponse::bad_request(&format!("{label} must be an https URL")).into_response(),
+        ));
+    };
+    egress.check(&url).map_err(|err| {
+        Box::new(
+            JsonResponse::bad_request(&format!("{label} is not allowed: {err}")).into_response(),
+        )
+    })?;
+    Ok(trimmed.to_string())
+}
+
+/// Validates the request and encrypts the client credentials. `existing`
+/// supplies the stored secret when an update leaves it blank.
+fn build_provider(
+    state: &AppState,
+    workspace_id: Uuid,
+    actor_id: Uuid,
+    request: CustomProviderRequest,
+    existing: Option<&CustomOAuthProvider>,
+) -> Result<NewCustomOAuthProvider, Box<Response>> {
+    let name = request.name.trim().to_string();
+    if name.is_empty() || name.chars().count() > MAX_NAME_LENGTH {
+        return Err(Box::new(
+            JsonResponse::bad_request(&format!(
+                "Name is required and must be at most {MAX_NAME_LENGTH} characters"
+            ))
+            .into_response(),
+        ));
+    }
+    let egress = TriggerEgress::from_env();
+    let authorize_url = validate_endpoint(&egress, "Authorize URL", &request.authorize_url)?;
+    let token_url = validate_endpoint(&egress, "Token URL", &request.token_url)?;
+    let userinfo_url = request
+        .userinfo_url
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(|value| validate_endpoint(&egress, "Userinfo URL", value))
+        .transpose()?;
+
+    let client_id = request.client_id.trim();
+    if client_id.is_empty() {
+        return Err(Box::new(
+            JsonResponse::bad_request("Client ID is required").into_response(),
+        ));
+    }
+    let token_auth_method = match request.token_auth_method.as_deref() {
+        None => TokenAuthMethod::ClientSecretPost,
+        Some(raw) => TokenAuthMethod::parse(raw).ok_or_else(|| {
+            Box::new(
+                JsonResponse::bad_request(
+                    "Token auth method must be client_secret_post or client_secret_basic",
+                )
+                .into_response(),
+            )
+        })?,
+    };
+    let use_pkce = request.use_pkce.unwrap_or(true);
+
+    let encrypt = |value: &str| {
+        state
+            .oauth_accounts
+            .encrypt_provider_credential(value)
+            .map_err(|err| {
+                error!(?err, "Failed to encrypt custom OAuth client credentials");
+                Box::new(
+                    JsonResponse::server_error("Failed to secure client credentials")
+                        .into_response(),
+                )
+            })
+    };
+    let client_secret = match request
+        .client_secret
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+    {
+        Some(secret) => Some(encrypt(secret)?),
+        None => existing.and_then(|record| record.client_secret.clone()),
+    };
+    if client_secret.is_none() && !use_pkce {
+        return Err(Box::new(
+            JsonResponse::bad_request("Providers without a client secret must use PKCE")
+                .into_response(),
+        ));
+    }
+
+    Ok(NewCustomOAuthProvider {
+        workspace_id,
+        created_by: actor_id,
+        name,
+        authorize_url,
+        token_url,
+        userinfo_url,
+        scopes: normalize_scopes(request.scopes.as_deref().unwrap_or_default()),
+        use_pkce,
+        client_id: encrypt(client_id)?,
+        client_secret,
+        token_auth_method: token_auth_method.as_str().to_string(),
+    })
+}
+
+fn parse_user_id(claims: &crate::routes::auth::claims::Claims) -> Result<Uuid, Box<Response>> {
+    Uuid::parse_str(&claims.id).map_err(|_| {
+        Box::new(JsonResponse::server_error("Invalid user identifier").into_response())
+    })
+}
+
+/// Members may list providers; owners and admins manage them.
+async fn ensure_provider_admin(
+    state: &AppState,
+    user_id: Uuid,
+    workspace_id: Uuid,
+) -> Result<(), Box<Response>> {
+    let membership = ensure_workspace_membership(state, user_id, workspace_id).await?;
+    if matches!(membership.role, WorkspaceRole::Owner | WorkspaceRole::Admin) {
+        Ok(())
+    } else {
+        Err(Box::new(
+            JsonResponse::forbidden("Only workspace owners and admins can manage OAuth providers")
+                .into_response(),
+        ))
+    }
+}
+
+async fn load_provider_record(
+    state: &AppState,
+    provider_id: Uuid,
+) -> Result<CustomOAuthProvider, Box<Response>> {
+    match state
+        .workspace_connection_repo
+        .find_custom_provider(provider_id)
+        .await
+    {
+        Ok(Some(record)) => Ok(record),
+        Ok(None) => Err(Box::new(
+            JsonResponse::not_found("OAuth provider not found").into_response(),
+        )),
+        Err(err) => {
+            error!(%provider_id, ?err, "Failed to load custom OAuth provider");
+            Err(Box::new(
+                JsonResponse::server_error("Failed to load OAuth provider").into_response(),
+            ))
+        }
+    }
+}
+
+fn map_save_error(err: sqlx::Error) -> Response {
+    if is_unique_violation(&err) {
+        return JsonResponse::conflict("An OAuth provider with this name already exists")
+            .into_response();
+    }
+    error!(?err, "Failed to save custom OAuth provider");
+    JsonResponse::server_error("Failed to save OAuth provider").into_response()
+}
+
+pub async fn list_custom_providers(
+    State(state): State<AppState>,
+    AuthSession(claims): AuthSession,
+    Query(params): Query<CustomProvidersQuery>,
+) -> Response {
+    let user_id = match parse_user_id(&claims) {
+        Ok(id) => id,
+        Err(resp) => return *resp,
+    };
+    if let Err(resp) = ensure_workspace_membership(&state, user_id, params.workspace).await {
+        return resp;
+    }
+
+    let records = match state
+        .workspace_connection_repo
+        .list_custom_providers(params.workspace)
+        .await
+    {
+        Ok(records) => records,
+        Err(err) => {
+            error!(workspace_id = %params.workspace, ?err, "Failed to list custom OAuth providers");
+            return JsonResponse::server_error("Failed to load OAuth providers").into_response();
+        }
+    };
+
+    let mut providers = Vec::with_capacity(records.len());
+    for record in &records {
+        match payload_from_record(&state, record) {
+            Ok(payload) => providers.push(payload),
+            Err(resp) => return *resp,
+        }
+    }
+
+    Json(CustomProvidersResponse {
+        success: true,
+        providers,
+    })
+    .into_response()
+}
+
+pub async fn create_custom_provider(
+    State(state): State<AppState>,
+    AuthSession(claims): AuthSession,
+    Query(params): Query<CustomProvidersQuery>,
+    Json(request): Json<CustomProviderRequest>,
+) -> Response {
+    let user_id = match parse_user_id(&claims) {
+        Ok(id) => id,
+        Err(resp) => return *resp,
+    };
+    if let Err(resp) = ensure_provider_admin(&state, user_id, params.workspace).await {
+        return *resp;
+    }
+
+    let new_provider = match build_provider(&state, params.workspace, user_id, request, None) {
+        Ok(provider) => provider,
+        Err(resp) => return *resp,
+    };
+    let record = match state
+        .workspace_connection_repo
+        .insert_custom_provider(new_provider)
+        .await
+    {
+        Ok(record) => record,
+        Err(err) => return map_save_error(err),
+    };
+
+    match payload_from_record(&state, &record) {
+        Ok(provider) => (
+            axum::http::StatusCode::CREATED,
+            Json(CustomProviderResponse {
+                success: true,
+                provider,
+            }),
+        )
+            .into_response(),
+        Err(resp) => *resp,
+    }
+}
+
+pub async fn update_custom_provider(
+    State(state): State<AppState>,
+    AuthSession(claims): AuthSession,
+    Path(provider_id): Path<Uuid>,
+    Json(request): Json<CustomProviderRequest>,
+) -> Response {
+    let user_id = match parse_user_id(&claims) {
+        Ok(id) => id,
+        Err(resp) => return *resp,
+    };
+    let existing = match load_provider_record(&state, provider_id).await {
+        Ok(record) => record,
+        Err(resp) => return *resp,
+    };
+    if let Err(resp) = ensure_provider_admin(&state, user_id, existing.workspace_id).await {
+        return *resp;
+    }
+
+    let updated = match build_provider(
+        &state,
+        existing.workspace_id,
+        user_id,
+        request,
+        Some(&existing),
+    ) {
+        Ok(provider) => provider,
+        Err(resp) => return *resp,
+    };
+    let record = match state
+        .workspace_connection_repo
+        .update_custom_provider(provider_id, updated)
+        .await
+    {
+        Ok(record) => record,
+        Err(err) => return map_save_error(err),
+    };
+
+    match payload_from_record(&state, &record) {
+        Ok(provider) => Json(CustomProviderResponse {
+            success: true,
+            provider,
+        })
+        .into_response(),
+        Err(resp) => *resp,
+    }
+}
+
+/// Deleting a provider also removes every connection made through it.
+pub async fn delete_custom_provider(
+    State(state): State<AppState>,
+    AuthSession(claims): AuthSession,
+    Path(provider_id): Path<Uuid>,
+) -> Response {
+    let user_id = match parse_user_id(&claims) {
+        Ok(id) => id,
+        Err(resp) => return *resp,
+    };
+    let existing = match load_provider_record(&state, provider_id).await {
+        Ok(record) => record,
+        Err(resp) => return *resp,
+    };
+    if let Err(resp) = ensure_provider_admin(&state, user_id, existing.workspace_id).await {
+        return *resp;
+    }
+
+    match state
+        .workspace_connection_repo
+        .delete_custom_provider(provider_id)
+        .await
+    {
+        Ok(true) => JsonResponse::success("OAuth provider deleted").into_response(),
+        Ok(false) => JsonResponse::not_found("OAuth provider not found").into_response(),
+        Err(err) => {
+            error!(%provider_id, ?err, "Failed to delete custom OAuth provider");
+            JsonResponse::server_error("Failed to delete OAuth provider").into_response()
+        }
+    }
+}
+
+fn encode_state(payload: &CustomOAuthState) -> Option<String> {
+    serde_json::to_vec(payload)
+        .ok()
+        .map(|bytes| base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(bytes))
+}
+
+fn decode_state(raw: &str) -> Option<CustomOAuthState> {
+    let bytes = base64::engine::general_purpose::URL_SAFE_NO_PAD
+        .decode(raw)
+        .ok()?;
+    serde_json::from_slice(&bytes).ok()
+}
+
+pub async fn custom_connect_start(
+    State(state): State<AppState>,
+    AuthSession(claims): AuthSession,
+    Path(provider_id): Path<Uuid>,
+    Query(params): Query<ConnectQuery>,
+    jar: CookieJar,
+) -> Response {
+    let user_id = match Uuid::parse_str(&claims.id) {
+        Ok(id) => id,
+        Err(_) => return redirect_with_error(&state.config, PROVIDER, "Invalid user"),
+    };
+
+    let provider = match state.oauth_accounts.load_custom_provider(provider_id).await {
+        Ok(provider) => provider,
+        Err(OAuthAccountError::NotFound) => {
+            return redirect_with_error(&state.config, PROVIDER, "OAuth provider not found");
+        }
+        Err(err) => {
+            error!(%provider_id, ?err, "Failed to load custom OAuth provider");
+            return redirect_with_error(&state.config, PROVIDER, &error_message_for_redirect(&err));
+        }
+    };
+
+    // The definition belongs to one workspace, so that is the workspace whose
+    // membership and plan gate the connection, whatever the query says.
+    if let Err(response) = ensure_oauth_permissions(
+        &state,
+        user_id,
+        claims.plan.as_deref(),
+        Some(provider.workspace_id),
+        PROVIDER,
+    )
+    .await
+    {
+        return response;
+    }
+
+    let connection_scope = match params.connection_scope.as_deref() {
+        None => "personal".to_string(),
+        Some(raw) => match normalize_connection_scope(Some(raw), Some(provider.workspace_id)) {
+            Ok(scope) => scope,
+            Err(resp) => return *resp,
+        },
+    };
+
+    let payload = CustomOAuthState {
+        nonce: generate_csrf_token(),
+        provider_id,
+        user_id,
+        connection_scope,
+        code_verifier: provider.use_pkce.then(generate_csrf_token),
+    };
+    let Some(cookie_value) = encode_state(&payload) else {
+        return JsonResponse::server_error("Failed to start OAuth flow").into_response();
+    };
+
+    let url = match provider.authorization_url(
+        &custom_redirect_uri(&state.config),
+        &payload.nonce,
+        payload.code_verifier.as_deref(),
+    ) {
+        Ok(url) => url,
+        Err(err) => {
+            error!(%provider_id, %err, "Custom OAuth provider has an invalid authorize URL");
+            return redirect_with_error(&state.config, PROVIDER, &err);
+        }
+    };
+
+    let jar = jar.add(build_state_cookie(CUSTOM_STATE_COOKIE, &cookie_value));
+    (jar, Redirect::to(url.as_str())).into_response()
+}
+
+pub async fn custom_connect_callback(
+    State(state): State<AppState>,
+    AuthSession(claims): AuthSession,
+    jar: CookieJar,
+    Query(query): Query<CallbackQuery>,
+) -> Response {
+    if let Some(error) = query.error.clone().or(query.error_description.clone()) {
+        return redirect_with_error(&state.config, PROVIDER, &error);
+    }
+    let Some(code) = query.code.clone() else {
+        return redirect_with_error(&state.config, PROVIDER, "Missing code");
+    };
+    let Some(payload) = jar
+        .get(CUSTOM_STATE_COOKIE)
+        .and_then(|cookie| decode_state(cookie.value()))
+    else {
+        return redirect_with_error(&state.config, PROVIDER, "Missing state");
+    };
+    if query.state.as_deref() != Some(payload.nonce.as_str()) {
+        return redirect_with_error(&state.config, PROVIDER, "Invalid state");
+    }
+
+    let jar = clear_state_cookie(jar, CUSTOM_STATE_COOKIE);
+    let fail = |jar: CookieJar, message: &str| {
+        (jar, redirect_with_error(&state.config, PROVIDER, message)).into_response()
+    };
+
+    match Uuid::parse_str(&claims.id) {
+        Ok(id) if id == payload.user_id => {}
+        _ => return fail(jar, "Invalid state"),
+    }
+    let user_id = payload.user_id;
+
+    let provider = match state
+        .oauth_accounts
+        .load_custom_provider(payload.provider_id)
+        .await
+    {
+        Ok(provider) => provider,
+        Err(err) => return fail(jar, &error_message_for_redirect(&err)),
+    };
+
+    if let Err(response) = ensure_oauth_permissions(
+        &state,
+        user_id,
+        claims.plan.as_deref(),
+        Some(provider.workspace_id),
+        PROVIDER,
+    )
+    .await
+    {
+        return (jar, response).into_response();
+    }
+
+    let tokens = match state
+        .oauth_accounts
+        .exchange_custom_code(
+            &provider,
+            &code,
+            &custom_redirect_uri(&state.config),
+            payload.code_verifier.as_deref(),
+        )
+        .await
+    {
+        Ok(tokens) => tokens,
+        Err(err) => {
+            error!(provider_id = %provider.id, "Custom OAuth authorization exchange failed: {err}");
+            return fail(jar, &error_message_for_redirect(&err));
+        }
+    };
+
+    let stored = match state
+        .oauth_accounts
+        .save_custom_authorization(user_id, &provider, tokens)
+        .await
+    {
+        Ok(stored) => stored,
+        Err(err) => {
+            error!(provider_id = %provider.id, "Saving custom OAuth authorization failed: {err}");
+            return fail(jar, &error_message_for_redirect(&err));
+        }
+    };
+
+    if payload.connection_scope == "workspace" {
+        if let Err(err) = state
+            .workspace_oauth
+            .promote_connection_with_token(
+                provider.workspace_id,
+                user_id,
+                PROVIDER,
+                Some(stored.id),
+            )
+            .await
+        {
+            error!(provider_id = %provider.id, "Saving custom workspace connection failed: {err}");
+            let message = match err {
+                WorkspaceOAuthError::Forbidden => {
+                    "Not authorized to share this connection with the workspace".to_string()
+                }
+                WorkspaceOAuthError::OAuth(inner) => error_message_for_redirect(&inner),
+                _ => "Failed to share the connection with the workspace".to_string(),
+            };
+            let response = redirect_with_error_with_workspace(
+                &state.config,
+                PROVIDER,
+                &message,
+                Some(provider.workspace_id),
+            );
+            return (jar, response).into_response();
+        }
+    }
+
+    (
+        jar,
+        redirect_success_with_workspace(&state.config, PROVIDER, provider.workspace_id),
+    )
+        .into_response()
+}
@@ -5,11 +5,14 @@ pub(crate) const MICROSOFT_AUTH_URL: &str =
 pub(crate) const SLACK_AUTH_URL: &str = "https://slack.com/oauth/v2/authorize";
 pub(crate) const ASANA_AUTH_URL: &str = "https://app.asana.com/-/oauth_authorize";
 pub(crate) const NOTION_AUTH_URL: &str = "https://api.notion.com/v1/oauth/authorize";
+pub(crate) const GITHUB_AUTH_URL: &str = "https://github.com/login/oauth/authorize";
 pub(crate) const GOOGLE_STATE_COOKIE: &str = "oauth_google_state";
 pub(crate) const MICROSOFT_STATE_COOKIE: &str = "oauth_microsoft_state";
 pub(crate) const SLACK_STATE_COOKIE: &str = "oauth_slack_state";
 pub(crate) const ASANA_STATE_COOKIE: &str = "oauth_asana_state";
 pub(crate) const NOTION_STATE_COOKIE: &str = "oauth_notion_state";
+pub(crate) const GITHUB_STATE_COOKIE: &str = "oauth_github_state";
+pub(crate) const CUSTOM_STATE_COOKIE: &str = "oauth_custom_state";
 pub(crate) const STATE_COOKIE_MAX_MINUTES: i64 = 10;
 pub(crate) const OAUTH_PLAN_RESTRICTION_MESSAGE: &str =
     "OAuth integrations are available on workspace plans and above. Upgrade to connect accounts.";
@@ -48,6 +51,8 @@ pub(crate) struct PersonalConnectionPayload {
     pub(crate) last_refreshed_at: OffsetDateTime,
     pub(crate) requires_reconnect: bool,
     pub(crate) owner: ConnectionOwnerPayload,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) custom_provider_id: Option<Uuid>,
 }
 
 #[derive(Serialize)]
@@ -70,6 +75,8 @@ pub(crate) struct WorkspaceConnectionPayload {
     #[serde(default, skip_serializing_if = "std::ops::Not::not")]
     pub(crate) has_incoming_webhook: bool,
     pub(crate) owner: ConnectionOwnerPayload,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) custom_provider_id: Option<Uuid>,
 }
 
 #[derive(Serialize)]
@@ -79,6 +86,8 @@ pub(crate) struct ProviderGroupedConnections<T> {
     pub(crate) slack: Vec<T>,
     pub(crate) asana: Vec<T>,
     pub(crate) notion: Vec<T>,
+    pub(crate) github: Vec<T>,
+    pub(crate) custom: Vec<T>,
 }
 
 impl<T> Default for ProviderGroupedConnections<T> {
@@ -89,6 +98,8 @@ impl<T> Default for ProviderGroupedConnections<T> {
             slack: Vec::new(),
             asana: Vec::new(),
             notion: Vec::new(),
+            github: Vec::new(),
+            custom: Vec::new(),
         }
     }
 }
@@ -101,6 +112,8 @@ impl<T> ProviderGroupedConnections<T> {
             ConnectedOAuthProvider::Slack => self.slack.push(payload),
             ConnectedOAuthProvider::Asana => self.asana.push(payload),
             ConnectedOAuthProvider::Notion => self.notion.push(payload),
+            ConnectedOAuthProvider::GitHub => self.github.push(payload),
+            ConnectedOAuthProvider::Custom => self.custom.push(payload),
         }
     }
 }
@@ -249,6 +262,8 @@ pub(crate) fn parse_provider(raw: &str) -> Option<ConnectedOAuthProvider> {
         "slack" => Some(ConnectedOAuthProvider::Slack),
         "asana" => Some(ConnectedOAuthProvider::Asana),
         "notion" => Some(ConnectedOAuthProvider::Notion),
+        "github" => Some(ConnectedOAuthProvider::GitHub),
+        "custom" => Some(ConnectedOAuthProvider::Custom),
         _ => None,
     }
 }
@@ -260,6 +275,8 @@ pub(crate) fn provider_to_key(provider: ConnectedOAuthProvider) -> &'static str
         ConnectedOAuthProvider::Slack => "slack",
         ConnectedOAuthProvider::Asana => "asana",
         ConnectedOAuthProvider::Notion => "notion",
+        ConnectedOAuthProvider::GitHub => "github",
+        ConnectedOAuthProvider::Custom => "custom",
     }
 }
 
@@ -365,6 +382,8 @@ pub fn map_oauth_error(err: OAuthAccountError) -> Response {
                 ConnectedOAuthProvider::Slack => "Slack",
                 ConnectedOAuthProvider::Asana => "Asana",
                 ConnectedOAuthProvider::Notion => "Notion",
+                ConnectedOAuthProvider::GitHub => "GitHub",
+                ConnectedOAuthProvider::Custom => "OAuth provider",
             };
             JsonResponse::bad_request(&format!(
                 "The {provider_name} account email must be verified before connecting."
@@ -379,6 +398,7 @@ pub fn map_oauth_error(err: OAuthAccountError) -> Response {
         OAuthAccountError::MissingRefreshToken => {
             JsonResponse::server_error("Provider did not return a refresh token").into_response()
         }
+        OAuthAccountError::EgressBlocked(msg) => JsonResponse::bad_request(&msg).into_response(),
     }
 }
 
@@ -402,5 +422,8 @@ pub(crate) fn error_message_for_redirect(err: &OAuthAccountError) -> String {
         OAuthAccountError::MissingRefreshToken => {
             "The OAuth provider did not return a refresh token.".to_string()
         }
+        OAuthAccountError::EgressBlocked(_) => {
+            "The OAuth provider's endpoint is blocked by the outbound network policy.".to_string()
+        }
     }
 }
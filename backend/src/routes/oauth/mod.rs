@@ -1,5 +1,6 @@
 mod accounts;
 mod connect;
+mod custom;
 mod helpers;
 mod prelude;
 
@@ -11,8 +12,13 @@ pub use accounts::{
     refresh_connection, revoke_connection,
 };
 pub use connect::{
-    asana_connect_callback, asana_connect_start, google_connect_callback, google_connect_start,
-    microsoft_connect_callback, microsoft_connect_start, notion_connect_callback,
-    notion_connect_start, slack_connect_callback, slack_connect_start,
+    asana_connect_callback, asana_connect_start, github_connect_callback, github_connect_start,
+    google_connect_callback, google_connect_start, microsoft_connect_callback,
+    microsoft_connect_start, notion_connect_callback, notion_connect_start, slack_connect_callback,
+    slack_connect_start,
+};
+pub use custom::{
+    create_custom_provider, custom_connect_callback, custom_connect_start, delete_custom_provider,
+    list_custom_providers, update_custom_provider,
 };
 pub use helpers::map_oauth_error;
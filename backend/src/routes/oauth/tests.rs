@@ -9,15 +9,14 @@ use axum::{
 use axum_extra::extract::cookie::CookieJar;
 use base64::engine::general_purpose::URL_SAFE_NO_PAD;
 use base64::Engine as _;
-use once_cell::sync::Lazy;
 use std::{
     collections::HashMap,
     sync::{Arc, Mutex},
 };
 
 use crate::config::{
-    Config, OAuthProviderConfig, OAuthSettings, StripeSettings, DEFAULT_WORKSPACE_MEMBER_LIMIT,
-    DEFAULT_WORKSPACE_MONTHLY_RUN_LIMIT, RUNAWAY_LIMIT_5MIN,
+    Config, OAuthProviderConfig, OAuthSettings, ProviderBaseUrls, StripeSettings,
+    DEFAULT_WORKSPACE_MEMBER_LIMIT, DEFAULT_WORKSPACE_MONTHLY_RUN_LIMIT, RUNAWAY_LIMIT_5MIN,
 };
 use crate::db::{
     mock_db::{MockDb, NoopWorkflowRepository, NoopWorkspaceRepository},
@@ -111,6 +110,11 @@ fn stub_config() -> Arc<Config> {
                 client_secret: "secret".into(),
                 redirect_uri: "http://localhost/notion".into(),
             },
+            github: OAuthProviderConfig {
+                client_id: "client".into(),
+                client_secret: "secret".into(),
+                redirect_uri: "http://localhost/github".into(),
+            },
             token_encryption_key: vec![0u8; 32],
         },
         api_secrets_encryption_key: vec![1u8; 32],
@@ -126,6 +130,9 @@ fn stub_config() -> Arc<Config> {
         workspace_member_limit: DEFAULT_WORKSPACE_MEMBER_LIMIT,
         workspace_monthly_run_limit: DEFAULT_WORKSPACE_MONTHLY_RUN_LIMIT,
         runaway_limit_5min: RUNAWAY_LIMIT_5MIN,
+        slack_signing_secret: None,
+        public_api_base_url: None,
+        provider_base_urls: ProviderBaseUrls::default(),
     })
 }
 
@@ -151,37 +158,6 @@ fn stub_state(config: Arc<Config>) -> AppState {
     }
 }
 
-static ENV_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
-
-struct EnvGuard {
-    key: &'static str,
-    previous: Option<String>,
-    _lock: std::sync::MutexGuard<'static, ()>,
-}
-
-impl EnvGuard {
-    fn set(key: &'static str, value: String) -> Self {
-        let lock = ENV_LOCK.lock().expect("env mutex poisoned");
-        let previous = std::env::var(key).ok();
-        std::env::set_var(key, value);
-        Self {
-            key,
-            previous,
-            _lock: lock,
-        }
-    }
-}
-
-impl Drop for EnvGuard {
-    fn drop(&mut self) {
-        if let Some(previous) = self.previous.take() {
-            std::env::set_var(self.key, previous);
-        } else {
-            std::env::remove_var(self.key);
-        }
-    }
-}
-
 fn test_jwt_keys() -> Arc<JwtKeys> {
     Arc::new(
         JwtKeys::from_secret("0123456789abcdef0123456789abcdef")
@@ -341,8 +317,7 @@ fn workspace_connection_fixture(
     }
 }
 
-const SLACK_BOT_SCOPES: &str =
-    "channels:read,groups:read,im:read,mpim:read,chat:write,chat:write.public,incoming-webhook";
+const SLACK_BOT_SCOPES: &str = "channels:read,groups:read,im:read,mpim:read,chat:write,chat:write.public,incoming-webhook,reactions:write,files:write,im:write,users:read,users:read.email,channels:history,groups:history,im:history,app_mentions:read,reactions:read";
 const NOTION_TOKEN_RESPONSE: &str = r#"{
     "access_token": "notion-access",
     "bot_id": "bot-123",
@@ -834,6 +809,13 @@ impl WorkspaceConnectionRepository for WorkspaceConnectionsStub {
         Ok(Vec::new())
     }
 
+    async fn list_slack_by_team(
+        &self,
+        _slack_team_id: &str,
+    ) -> Result<Vec<WorkspaceConnection>, sqlx::Error> {
+        Ok(Vec::new())
+    }
+
     async fn list_for_workspace(
         &self,
         workspace_id: Uuid,
@@ -1063,6 +1045,13 @@ impl WorkspaceConnectionRepository for RecordingWorkspaceConnectionRepo {
             .await
     }
 
+    async fn list_slack_by_team(
+        &self,
+        _slack_team_id: &str,
+    ) -> Result<Vec<WorkspaceConnection>, sqlx::Error> {
+        Ok(Vec::new())
+    }
+
     async fn list_for_workspace(
         &self,
         _workspace_id: Uuid,
@@ -3334,7 +3323,6 @@ async fn notion_callback_promotes_workspace_connection() {
 
 #[tokio::test]
 async fn slack_callback_persists_team_id_from_auth_test() {
-    let config = stub_config();
     let workspace_id = Uuid::new_v4();
     let user_id = Uuid::new_v4();
 
@@ -3343,7 +3331,10 @@ async fn slack_callback_persists_team_id_from_auth_test() {
         Arc::new(MembershipWorkspaceRepo::new(vec![(user_id, membership)]));
 
     let (addr, handle) = spawn_slack_stub_server().await;
-    let _env = EnvGuard::set("SLACK_API_BASE_URL", format!("http://{}/api", addr));
+    let slack_api_base = format!("http://{}/api", addr);
+    let mut config = (*stub_config()).clone();
+    config.provider_base_urls.slack = slack_api_base.clone();
+    let config = Arc::new(config);
 
     let token_repo = Arc::new(RecordingTokenRepo::default());
     let workspace_connections = Arc::new(RecordingWorkspaceConnectionRepo::default());
@@ -3360,6 +3351,7 @@ async fn slack_callback_persists_team_id_from_auth_test() {
         format!("http://{}/api/oauth.v2.access", addr),
         format!("http://{}/api/users.info", addr),
     );
+    oauth_service.set_slack_api_base(slack_api_base);
     let oauth_service = Arc::new(oauth_service);
 
     let oauth_refresher: Arc<dyn WorkspaceTokenRefresher> = oauth_service.clone();
@@ -6,10 +6,12 @@ use axum::{
 };
 use serde::Deserialize;
 use serde_json::{json, Value};
+use time::format_description::well_known::Rfc3339;
 use uuid::Uuid;
 
 use crate::{
-    models::workspace::WorkspaceRole,
+    db::workflow_repository::WorkspaceSigningSalt,
+    models::{plan::PlanTier, workspace::WorkspaceRole},
     responses::JsonResponse,
     routes::auth::session::AuthSession,
     state::AppState,
@@ -20,6 +22,7 @@ use crate::{
         SecretResponseStore, SecretStore, SecretStoreRead, SecretUpsertOutcome,
         SecretValidationError,
     },
+    utils::webhook_signing::{webhook_secret, workspace_signing_key},
 };
 
 #[derive(Deserialize)]
@@ -584,6 +587,130 @@ pub async fn delete_secret(
         .into_response()
 }
 
+/// Resolves the workspace whose request signing key is being read or rotated
+/// and checks that the caller may manage it.
+async fn signing_key_workspace(
+    app_state: &AppState,
+    claims_id: &str,
+    params: &SecretsQuery,
+    admin_only: bool,
+) -> Result<(Uuid, Uuid), Response> {
+    let user_id = Uuid::parse_str(claims_id)
+        .map_err(|_| JsonResponse::unauthorized("Invalid user ID").into_response())?;
+    let workspace_id = params
+        .workspace
+        .ok_or_else(|| JsonResponse::bad_request("Workspace is required").into_response())?;
+
+    let role = ensure_workspace_membership(app_state, user_id, workspace_id).await?;
+    let allowed = if admin_only {
+        matches!(role, WorkspaceRole::Owner | WorkspaceRole::Admin)
+    } else {
+        role != WorkspaceRole::Viewer
+    };
+    if !allowed {
+        return Err(JsonResponse::forbidden(
+            "You do not have permission to manage the signing key for this workspace",
+        )
+        .into_response());
+    }
+
+    let plan = app_state
+        .workspace_repo
+        .get_plan(workspace_id)
+        .await
+        .map_err(|err| {
+            eprintln!("Failed to load workspace plan for signing key: {:?}", err);
+            JsonResponse::server_error("Failed to load signing key").into_response()
+        })?;
+    if plan != PlanTier::Workspace {
+        return Err(
+            JsonResponse::forbidden("Request signing requires the Workspace plan.").into_response(),
+        );
+    }
+
+    Ok((user_id, workspace_id))
+}
+
+fn signing_key_response(
+    app_state: &AppState,
+    workspace_id: Uuid,
+    salt: WorkspaceSigningSalt,
+) -> Response {
+    let Some(secret) = webhook_secret(&app_state.config) else {
+        return JsonResponse::server_error("Request signing is not configured").into_response();
+    };
+    (
+        StatusCode::OK,
+        Json(json!({
+            "success": true,
+            "signingKey": workspace_signing_key(&secret, workspace_id, salt.salt),
+            "rotatedAt": salt.rotated_at.format(&Rfc3339).unwrap_or_default(),
+        })),
+    )
+        .into_response()
+}
+
+/// Returns the key the workspace uses to sign outbound HTTP requests, so
+/// receivers can verify `X-DSentr-Signature`.
+pub async fn get_signing_key(
+    State(app_state): State<AppState>,
+    AuthSession(claims): AuthSession,
+    Query(params): Query<SecretsQuery>,
+) -> Response {
+    let (_, workspace_id) =
+        match signing_key_workspace(&app_state, &claims.id, &params, false).await {
+            Ok(ids) => ids,
+            Err(resp) => return resp,
+        };
+
+    match app_state
+        .workflow_repo
+        .get_workspace_signing_salt(workspace_id)
+        .await
+    {
+        Ok(salt) => signing_key_response(&app_state, workspace_id, salt),
+        Err(err) => {
+            eprintln!("Failed to load workspace signing key: {:?}", err);
+            JsonResponse::server_error("Failed to load signing key").into_response()
+        }
+    }
+}
+
+/// Replaces the workspace signing key. Requests signed from now on use the
+/// new key; the old one stops being used immediately.
+pub async fn rotate_signing_key(
+    State(app_state): State<AppState>,
+    AuthSession(claims): AuthSession,
+    Query(params): Query<SecretsQuery>,
+) -> Response {
+    let (user_id, workspace_id) =
+        match signing_key_workspace(&app_state, &claims.id, &params, true).await {
+            Ok(ids) => ids,
+            Err(resp) => return resp,
+        };
+
+    let salt = match app_state
+        .workflow_repo
+        .rotate_workspace_signing_salt(workspace_id)
+        .await
+    {
+        Ok(salt) => salt,
+        Err(err) => {
+            eprintln!("Failed to rotate workspace signing key: {:?}", err);
+            return JsonResponse::server_error("Failed to rotate signing key").into_response();
+        }
+    };
+
+    let event = vec![json!({
+        "path": "workspace.signing_key.rotated",
+        "from": Value::Null,
+        "to": salt.rotated_at.format(&Rfc3339).unwrap_or_default(),
+    })];
+    log_workspace_history_event(&app_state, workspace_id, user_id, event).await;
+
+    signing_key_response(&app_state, workspace_id, salt)
+}
+
 /// Helper used by workflow routes to sync secrets.
 pub async fn sync_secrets_from_workflow(
     app_state: &AppState,
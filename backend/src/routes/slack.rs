@@ -304,11 +304,8 @@ async fn fetch_slack_channels(
     let mut cursor: Option<String> = None;
 
     let base = base_override
-        .map(|value| value.to_string())
-        .or_else(|| std::env::var("SLACK_API_BASE_URL").ok())
-        .or_else(|| std::env::var("SLACK_API_BASE").ok())
         .filter(|v| !v.trim().is_empty())
-        .unwrap_or_else(|| "https://slack.com/api".to_string());
+        .unwrap_or(&state.config.provider_base_urls.slack);
     let list_url = format!("{}/conversations.list", base.trim_end_matches('/'));
 
     loop {
@@ -488,8 +485,8 @@ mod tests {
     use time::{Duration, OffsetDateTime};
 
     use crate::config::{
-        Config, OAuthProviderConfig, OAuthSettings, StripeSettings, DEFAULT_WORKSPACE_MEMBER_LIMIT,
-        DEFAULT_WORKSPACE_MONTHLY_RUN_LIMIT, RUNAWAY_LIMIT_5MIN,
+        Config, OAuthProviderConfig, OAuthSettings, ProviderBaseUrls, StripeSettings,
+        DEFAULT_WORKSPACE_MEMBER_LIMIT, DEFAULT_WORKSPACE_MONTHLY_RUN_LIMIT, RUNAWAY_LIMIT_5MIN,
     };
     use crate::db::mock_db::{MockDb, NoopWorkflowRepository, StaticWorkspaceMembershipRepository};
     use crate::db::mock_stripe_event_log_repository::MockStripeEventLogRepository;
@@ -653,6 +650,13 @@ mod tests {
             Ok(Vec::new())
         }
 
+        async fn list_slack_by_team(
+            &self,
+            _slack_team_id: &str,
+        ) -> Result<Vec<WorkspaceConnection>, sqlx::Error> {
+            Ok(Vec::new())
+        }
+
         async fn list_for_workspace(
             &self,
             _workspace_id: Uuid,
@@ -824,6 +828,11 @@ mod tests {
                     client_secret: "secret".into(),
                     redirect_uri: "http://localhost/asana".into(),
                 },
+                github: OAuthProviderConfig {
+                    client_id: "client".into(),
+                    client_secret: "secret".into(),
+                    redirect_uri: "http://localhost/asana".into(),
+                },
                 token_encryption_key: vec![0u8; 32],
             },
             api_secrets_encryption_key: vec![1u8; 32],
@@ -839,6 +848,9 @@ mod tests {
             workspace_member_limit: DEFAULT_WORKSPACE_MEMBER_LIMIT,
             workspace_monthly_run_limit: DEFAULT_WORKSPACE_MONTHLY_RUN_LIMIT,
             runaway_limit_5min: RUNAWAY_LIMIT_5MIN,
+            slack_signing_secret: None,
+            public_api_base_url: None,
+            provider_base_urls: ProviderBaseUrls::default(),
         })
     }
 
@@ -1340,6 +1352,13 @@ mod tests {
                 Ok(Vec::new())
             }
 
+            async fn list_slack_by_team(
+                &self,
+                _slack_team_id: &str,
+            ) -> Result<Vec<WorkspaceConnection>, sqlx::Error> {
+                Ok(Vec::new())
+            }
+
             async fn list_for_workspace(
                 &self,
                 _workspace_id: Uuid,
@@ -1730,6 +1749,13 @@ mod tests {
                     .filter(|c| c.workspace_id == workspace_id && c.provider == provider)
                     .collect())
             }
+
+            async fn list_slack_by_team(
+                &self,
+                _slack_team_id: &str,
+            ) -> Result<Vec<WorkspaceConnection>, sqlx::Error> {
+                Ok(Vec::new())
+            }
             async fn list_for_workspace(
                 &self,
                 _workspace_id: Uuid,
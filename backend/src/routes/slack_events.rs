@@ -0,0 +1,680 @@
+use std::collections::{HashMap, HashSet};
+
+use axum::{
+    body::Bytes,
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use hmac::{Hmac, Mac};
+use serde_json::{json, Value};
+use sha2::Sha256;
+use subtle::ConstantTimeEq;
+use tracing::{error, warn};
+use uuid::Uuid;
+
+use crate::models::plan::PlanTier;
+use crate::models::workflow::Workflow;
+use crate::responses::JsonResponse;
+use crate::routes::workflows::enqueue_external_trigger_run;
+use crate::state::AppState;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Slack rejects replays older than five minutes; we apply the same window.
+const MAX_TIMESTAMP_SKEW_SECONDS: i64 = 60 * 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SlackTriggerEvent {
+    Message,
+    AppMention,
+    ReactionAdded,
+    Interactive,
+    SlashCommand,
+}
+
+impl SlackTriggerEvent {
+    fn from_str(raw: &str) -> Option<Self> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "message" => Some(Self::Message),
+            "app_mention" => Some(Self::AppMention),
+            "reaction_added" => Some(Self::ReactionAdded),
+            "interactive" | "block_actions" => Some(Self::Interactive),
+            "slash_command" => Some(Self::SlashCommand),
+            _ => None,
+        }
+    }
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Message => "message",
+            Self::AppMention => "app_mention",
+            Self::ReactionAdded => "reaction_added",
+            Self::Interactive => "interactive",
+            Self::SlashCommand => "slash_command",
+        }
+    }
+}
+
+/// An inbound Slack payload normalized to the fields trigger nodes filter on.
+#[derive(Debug, Clone)]
+struct InboundSlackEvent {
+    kind: SlackTriggerEvent,
+    team_id: String,
+    channel: Option<String>,
+    command: Option<String>,
+    dedupe_key: Option<String>,
+    context: Value,
+}
+
+#[derive(Debug, Clone)]
+struct SlackTrigger {
+    id: String,
+    label: String,
+    event: SlackTriggerEvent,
+    channel: Option<String>,
+    command: Option<String>,
+    connection_id: Option<Uuid>,
+}
+
+pub async fn slack_events(
+    State(app_state): State<AppState>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    if let Err(response) = verify_request(&app_state, &headers, &body) {
+        return *response;
+    }
+
+    let payload: Value = match serde_json::from_slice(&body) {
+        Ok(value) => value,
+        Err(_) => return JsonResponse::bad_request("Invalid Slack payload").into_response(),
+    };
+
+    match payload.get("type").and_then(|v| v.as_str()) {
+        Some("url_verification") => {
+            let challenge = payload.get("challenge").cloned().unwrap_or(Value::Null);
+            return (StatusCode::OK, Json(json!({ "challenge": challenge }))).into_response();
+        }
+        Some("event_callback") => {}
+        _ => return StatusCode::OK.into_response(),
+    }
+
+    // Slack retries events that are not acknowledged within three seconds,
+    // so workflow lookup and enqueueing happen off the request path.
+    if let Some(event) = parse_event_callback(&payload) {
+        tokio::spawn(async move {
+            dispatch_slack_event(&app_state, &event).await;
+        });
+    }
+
+    // Always acknowledge so Slack does not retry events we chose to ignore.
+    StatusCode::OK.into_response()
+}
+
+pub async fn slack_interactions(
+    State(app_state): State<AppState>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    if let Err(response) = verify_request(&app_state, &headers, &body) {
+        return *response;
+    }
+
+    let form = parse_form(&body);
+    let event = if let Some(raw) = form.get("payload") {
+        match serde_json::from_str::<Value>(raw) {
+            Ok(payload) => parse_interaction(&payload),
+            Err(_) => return JsonResponse::bad_request("Invalid Slack payload").into_response(),
+        }
+    } else {
+        parse_slash_command(&form)
+    };
+
+    let Some(event) = event else {
+        return StatusCode::OK.into_response();
+    };
+
+    // Only slash commands report back how many runs started; everything else
+    // is acknowledged right away like the Events API.
+    if event.kind != SlackTriggerEvent::SlashCommand {
+        tokio::spawn(async move {
+            dispatch_slack_event(&app_state, &event).await;
+        });
+        return StatusCode::OK.into_response();
+    }
+
+    let text = match dispatch_slack_event(&app_state, &event).await {
+        0 => "No workflows are listening for this command.".to_string(),
+        1 => "Started 1 workflow.".to_string(),
+        count => format!("Started {count} workflows."),
+    };
+    (
+        StatusCode::OK,
+        Json(json!({ "response_type": "ephemeral", "text": text })),
+    )
+        .into_response()
+}
+
+fn verify_request(
+    app_state: &AppState,
+    headers: &HeaderMap,
+    body: &[u8],
+) -> Result<(), Box<Response>> {
+    let Some(secret) = app_state.config.slack_signing_secret.as_deref() else {
+        error!("SLACK_SIGNING_SECRET is not configured; rejecting Slack event");
+        return Err(Box::new(
+            JsonResponse::server_error(
+                "Slack events are not configured; contact an administrator.",
+            )
+            .into_response(),
+        ));
+    };
+
+    let timestamp = headers
+        .get("X-Slack-Request-Timestamp")
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default();
+    let signature = headers
+        .get("X-Slack-Signature")
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default();
+
+    let now = time::OffsetDateTime::now_utc().unix_timestamp();
+    verify_slack_signature(secret, timestamp, signature, body, now)
+        .map_err(|msg| Box::new(JsonResponse::unauthorized(msg).into_response()))
+}
+
+pub(crate) fn verify_slack_signature(
+    secret: &str,
+    timestamp: &str,
+    signature: &str,
+    body: &[u8],
+    now: i64,
+) -> Result<(), &'static str> {
+    if timestamp.is_empty() || signature.is_empty() {
+        return Err("Missing Slack signature");
+    }
+
+    let ts = timestamp.trim().parse::<i64>().unwrap_or(0);
+    if ts <= 0 || (now - ts).abs() > MAX_TIMESTAMP_SKEW_SECONDS {
+        return Err("Stale or invalid timestamp");
+    }
+
+    let mut mac = HmacSha256::new_from_slice(secret.as_bytes()).expect("HMAC");
+    mac.update(b"v0:");
+    mac.update(timestamp.trim().as_bytes());
+    mac.update(b":");
+    mac.update(body);
+    let expected = format!("v0={}", hex::encode(mac.finalize().into_bytes()));
+
+    if expected
+        .as_bytes()
+        .ct_eq(signature.trim().as_bytes())
+        .unwrap_u8()
+        == 0
+    {
+        return Err("Invalid Slack signature");
+    }
+
+    Ok(())
+}
+
+fn parse_form(body: &[u8]) -> HashMap<String, String> {
+    let raw = String::from_utf8_lossy(body);
+    raw.split('&')
+        .filter_map(|pair| {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            let key = urlencoding::decode(&key.replace('+', " "))
+                .ok()?
+                .into_owned();
+            let value = urlencoding::decode(&value.replace('+', " "))
+                .ok()?
+                .into_owned();
+            Some((key, value))
+        })
+        .collect()
+}
+
+fn read_str(value: Option<&Value>) -> Option<String> {
+    value
+        .and_then(|v| v.as_str())
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty())
+        .map(|s| s.to_string())
+}
+
+fn parse_event_callback(payload: &Value) -> Option<InboundSlackEvent> {
+    let team_id = read_str(payload.get("team_id"))?;
+    let event = payload.get("event")?;
+    let kind = SlackTriggerEvent::from_str(event.get("type")?.as_str()?)?;
+    if matches!(
+        kind,
+        SlackTriggerEvent::Interactive | SlackTriggerEvent::SlashCommand
+    ) {
+        return None;
+    }
+
+    // Ignore edits, deletions and bot posts (including our own Slack actions)
+    // so workflows that reply in a channel do not trigger themselves.
+    if kind == SlackTriggerEvent::Message
+        && (event.get("subtype").is_some() || event.get("bot_id").is_some())
+    {
+        return None;
+    }
+
+    let channel = if kind == SlackTriggerEvent::ReactionAdded {
+        read_str(event.get("item").and_then(|item| item.get("channel")))
+    } else {
+        read_str(event.get("channel"))
+    };
+
+    let context = json!({
+        "type": kind.as_str(),
+        "teamId": team_id,
+        "eventId": payload.get("event_id").cloned().unwrap_or(Value::Null),
+        "channel": channel,
+        "user": event.get("user").cloned().unwrap_or(Value::Null),
+        "text": event.get("text").cloned().unwrap_or(Value::Null),
+        "ts": event.get("ts").or_else(|| event.get("event_ts")).cloned().unwrap_or(Value::Null),
+        "threadTs": event.get("thread_ts").cloned().unwrap_or(Value::Null),
+        "reaction": event.get("reaction").cloned().unwrap_or(Value::Null),
+        "itemTs": event
+            .get("item")
+            .and_then(|item| item.get("ts"))
+            .cloned()
+            .unwrap_or(Value::Null),
+        "event": event.clone(),
+    });
+
+    Some(InboundSlackEvent {
+        kind,
+        team_id,
+        channel,
+        command: None,
+        dedupe_key: read_str(payload.get("event_id")),
+        context,
+    })
+}
+
+fn parse_interaction(payload: &Value) -> Option<InboundSlackEvent> {
+    let team_id = read_str(payload.get("team").and_then(|team| team.get("id")))?;
+    let channel = read_str(payload.get("channel").and_then(|c| c.get("id")))
+        .or_else(|| read_str(payload.get("container").and_then(|c| c.get("channel_id"))));
+    let action = payload
+        .get("actions")
+        .and_then(|actions| actions.as_array())
+        .and_then(|actions| actions.first());
+
+    let context = json!({
+        "type": SlackTriggerEvent::Interactive.as_str(),
+        "interactionType": payload.get("type").cloned().unwrap_or(Value::Null),
+        "teamId": team_id,
+        "channel": channel,
+        "user": payload
+            .get("user")
+            .and_then(|user| user.get("id"))
+            .cloned()
+            .unwrap_or(Value::Null),
+        "actionId": action
+            .and_then(|a| a.get("action_id"))
+            .cloned()
+            .unwrap_or(Value::Null),
+        "value": action
+            .and_then(|a| a.get("value").or_else(|| a.get("selected_option")))
+            .cloned()
+            .unwrap_or(Value::Null),
+        "callbackId": payload.get("callback_id").cloned().unwrap_or(Value::Null),
+        "messageTs": payload
+            .get("message")
+            .and_then(|m| m.get("ts"))
+            .or_else(|| payload.get("container").and_then(|c| c.get("message_ts")))
+            .cloned()
+            .unwrap_or(Value::Null),
+        "responseUrl": payload.get("response_url").cloned().unwrap_or(Value::Null),
+        "triggerId": payload.get("trigger_id").cloned().unwrap_or(Value::Null),
+        "payload": payload.clone(),
+    });
+
+    Some(InboundSlackEvent {
+        kind: SlackTriggerEvent::Interactive,
+        team_id,
+        channel,
+        command: None,
+        dedupe_key: read_str(payload.get("trigger_id")),
+        context,
+    })
+}
+
+fn parse_slash_command(form: &HashMap<String, String>) -> Option<InboundSlackEvent> {
+    let read = |key: &str| {
+        form.get(key)
+            .map(|s| s.trim())
+            .filter(|s| !s.is_empty())
+            .map(|s| s.to_string())
+    };
+    let team_id = read("team_id")?;
+    let command = read("command")?;
+    let channel = read("channel_id");
+
+    let context = json!({
+        "type": SlackTriggerEvent::SlashCommand.as_str(),
+        "teamId": team_id,
+        "channel": channel,
+        "user": read("user_id"),
+        "userName": read("user_name"),
+        "command": command,
+        "text": form.get("text").cloned().unwrap_or_default(),
+        "responseUrl": read("response_url"),
+        "triggerId": read("trigger_id"),
+    });
+
+    Some(InboundSlackEvent {
+        kind: SlackTriggerEvent::SlashCommand,
+        team_id,
+        channel,
+        command: Some(command),
+        dedupe_key: read("trigger_id"),
+        context,
+    })
+}
+
+fn collect_slack_triggers(snapshot: &Value) -> Vec<SlackTrigger> {
+    let Some(nodes) = snapshot.get("nodes").and_then(|n| n.as_array()) else {
+        return Vec::new();
+    };
+
+    nodes
+        .iter()
+        .filter_map(|node| {
+            if node.get("type")?.as_str()? != "trigger" {
+                return None;
+            }
+            let data = node.get("data")?;
+            let trigger_type = data.get("triggerType")?.as_str()?;
+            if !trigger_type.trim().eq_ignore_ascii_case("slack") {
+                return None;
+            }
+            let event = SlackTriggerEvent::from_str(data.get("slackEventType")?.as_str()?)?;
+            let id = node.get("id")?.as_str()?.to_string();
+            let label = read_str(data.get("label")).unwrap_or_else(|| id.clone());
+            let command = read_str(data.get("command")).map(|c| {
+                if c.starts_with('/') {
+                    c.to_ascii_lowercase()
+                } else {
+                    format!("/{}", c.to_ascii_lowercase())
+                }
+            });
+
+            Some(SlackTrigger {
+                id,
+                label,
+                event,
+                channel: read_str(data.get("channelId")),
+                command,
+                connection_id: read_str(data.get("connectionId"))
+                    .and_then(|raw| Uuid::parse_str(&raw).ok()),
+            })
+        })
+        .collect()
+}
+
+fn trigger_matches(
+    trigger: &SlackTrigger,
+    event: &InboundSlackEvent,
+    workspace_connections: &[Uuid],
+) -> bool {
+    if trigger.event != event.kind {
+        return false;
+    }
+    if let Some(expected) = trigger.channel.as_deref() {
+        if event.channel.as_deref() != Some(expected) {
+            return false;
+        }
+    }
+    if let Some(expected) = trigger.command.as_deref() {
+        let actual = event.command.as_deref().map(|c| c.to_ascii_lowercase());
+        if actual.as_deref() != Some(expected) {
+            return false;
+        }
+    }
+    if let Some(connection_id) = trigger.connection_id {
+        if !workspace_connections.contains(&connection_id) {
+            return false;
+        }
+    }
+    true
+}
+
+/// Starts a run for every workflow whose Slack trigger matches the event and
+/// belongs to a workspace connected to the event's Slack team. Returns the
+/// number of runs enqueued.
+async fn dispatch_slack_event(app_state: &AppState, event: &InboundSlackEvent) -> usize {
+    let connections = match app_state
+        .workspace_connection_repo
+        .list_slack_by_team(&event.team_id)
+        .await
+    {
+        Ok(connections) => connections,
+        Err(err) => {
+            error!(?err, team_id = %event.team_id, "failed to resolve Slack team connections");
+            return 0;
+        }
+    };
+
+    let mut connections_by_workspace: HashMap<Uuid, Vec<Uuid>> = HashMap::new();
+    for connection in connections {
+        connections_by_workspace
+            .entry(connection.workspace_id)
+            .or_default()
+            .push(connection.id);
+    }
+
+    let mut workspace_ids = Vec::new();
+    for workspace_id in connections_by_workspace.keys() {
+        match app_state.workspace_repo.get_plan(*workspace_id).await {
+            Ok(PlanTier::Workspace) => workspace_ids.push(*workspace_id),
+            Ok(_) => {}
+            Err(err) => {
+                warn!(?err, %workspace_id, "failed to load workspace plan for Slack event");
+            }
+        }
+    }
+    if workspace_ids.is_empty() {
+        return 0;
+    }
+
+    let workflows: Vec<Workflow> = match app_state
+        .workflow_repo
+        .list_workflows_by_workspace_ids(&workspace_ids)
+        .await
+    {
+        Ok(workflows) => workflows,
+        Err(err) => {
+            error!(?err, team_id = %event.team_id, "failed to list workflows for Slack event");
+            return 0;
+        }
+    };
+
+    let mut started = 0usize;
+    let mut seen: HashSet<Uuid> = HashSet::new();
+    for workflow in workflows {
+        if !seen.insert(workflow.id) {
+            continue;
+        }
+        let Some(workspace_id) = workflow.workspace_id else {
+            continue;
+        };
+        let workspace_connections = connections_by_workspace
+            .get(&workspace_id)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+
+        let triggers = collect_slack_triggers(&workflow.data);
+        let Some(trigger) = triggers
+            .iter()
+            .find(|trigger| trigger_matches(trigger, event, workspace_connections))
+        else {
+            continue;
+        };
+
+        if let Some(key) = event.dedupe_key.as_deref() {
+            match app_state
+                .workflow_repo
+                .try_record_webhook_signature(workflow.id, &format!("slack:{key}"))
+                .await
+            {
+                Ok(true) => {}
+                Ok(false) => continue,
+                Err(err) => {
+                    warn!(?err, workflow_id = %workflow.id, "failed to record Slack event id");
+                    continue;
+                }
+            }
+        }
+
+        match enqueue_external_trigger_run(
+            app_state,
+            &workflow,
+            Some(event.context.clone()),
+            Some((trigger.id.as_str(), trigger.label.as_str())),
+        )
+        .await
+        {
+            Ok(_) => started += 1,
+            Err(response) => {
+                warn!(
+                    workflow_id = %workflow.id,
+                    status = %response.status(),
+                    "failed to enqueue Slack-triggered run"
+                );
+            }
+        }
+    }
+
+    started
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sign(secret: &str, ts: &str, body: &[u8]) -> String {
+        let mut mac = HmacSha256::new_from_slice(secret.as_bytes()).unwrap();
+        mac.update(format!("v0:{ts}:").as_bytes());
+        mac.update(body);
+        format!("v0={}", hex::encode(mac.finalize().into_bytes()))
+    }
+
+    #[test]
+    fn verify_slack_signature_accepts_valid_and_rejects_tampered() {
+        let secret = "8f742231b10e8888abcd99yyyzzz85a5";
+        let body = br#"{"type":"event_callback"}"#;
+        let signature = sign(secret, "1531420618", body);
+
+        assert!(verify_slack_signature(secret, "1531420618", &signature, body, 1531420700).is_ok());
+        assert_eq!(
+            verify_slack_signature(secret, "1531420618", &signature, b"{}", 1531420700),
+            Err("Invalid Slack signature")
+        );
+        assert_eq!(
+            verify_slack_signature(secret, "1531420618", &signature, body, 1531420618 + 301),
+            Err("Stale or invalid timestamp")
+        );
+        assert_eq!(
+            verify_slack_signature(secret, "", "", body, 1531420700),
+            Err("Missing Slack signature")
+        );
+    }
+
+    #[test]
+    fn parse_event_callback_skips_bot_messages() {
+        let human = json!({
+            "type": "event_callback",
+            "team_id": "T123",
+            "event_id": "Ev1",
+            "event": { "type": "message", "channel": "C1", "user": "U1", "text": "hi", "ts": "1.0" }
+        });
+        let parsed = parse_event_callback(&human).expect("human message parsed");
+        assert_eq!(parsed.kind, SlackTriggerEvent::Message);
+        assert_eq!(parsed.channel.as_deref(), Some("C1"));
+        assert_eq!(parsed.context["text"], "hi");
+        assert_eq!(parsed.dedupe_key.as_deref(), Some("Ev1"));
+
+        let bot = json!({
+            "type": "event_callback",
+            "team_id": "T123",
+            "event": { "type": "message", "channel": "C1", "bot_id": "B1", "text": "hi" }
+        });
+        assert!(parse_event_callback(&bot).is_none());
+    }
+
+    #[test]
+    fn parse_event_callback_reads_reaction_item_channel() {
+        let payload = json!({
+            "type": "event_callback",
+            "team_id": "T123",
+            "event": {
+                "type": "reaction_added",
+                "user": "U1",
+                "reaction": "eyes",
+                "item": { "type": "message", "channel": "C9", "ts": "42.0" }
+            }
+        });
+        let parsed = parse_event_callback(&payload).expect("reaction parsed");
+        assert_eq!(parsed.kind, SlackTriggerEvent::ReactionAdded);
+        assert_eq!(parsed.channel.as_deref(), Some("C9"));
+        assert_eq!(parsed.context["itemTs"], "42.0");
+    }
+
+    #[test]
+    fn slash_command_matches_trigger_by_command() {
+        let form = parse_form(
+            b"team_id=T123&channel_id=C1&user_id=U1&command=%2Fdeploy&text=api+v2&trigger_id=tr1",
+        );
+        let event = parse_slash_command(&form).expect("slash command parsed");
+        assert_eq!(event.context["text"], "api v2");
+
+        let snapshot = json!({
+            "nodes": [
+                {"id": "t1", "type": "trigger", "data": {"triggerType": "Slack", "slackEventType": "slash_command", "command": "rollback"}},
+                {"id": "t2", "type": "trigger", "data": {"triggerType": "Slack", "slackEventType": "slash_command", "command": "deploy", "label": "Deploy"}}
+            ]
+        });
+        let triggers = collect_slack_triggers(&snapshot);
+        let matched: Vec<&str> = triggers
+            .iter()
+            .filter(|t| trigger_matches(t, &event, &[]))
+            .map(|t| t.id.as_str())
+            .collect();
+        assert_eq!(matched, vec!["t2"]);
+    }
+
+    #[test]
+    fn trigger_requires_matching_workspace_connection_when_pinned() {
+        let connection_id = Uuid::new_v4();
+        let snapshot = json!({
+            "nodes": [{
+                "id": "t1",
+                "type": "trigger",
+                "data": {
+                    "triggerType": "Slack",
+                    "slackEventType": "app_mention",
+                    "channelId": "C1",
+                    "connectionId": connection_id.to_string()
+                }
+            }]
+        });
+        let trigger = collect_slack_triggers(&snapshot).remove(0);
+        let event = parse_event_callback(&json!({
+            "type": "event_callback",
+            "team_id": "T123",
+            "event": { "type": "app_mention", "channel": "C1", "user": "U1", "text": "<@B> hi" }
+        }))
+        .unwrap();
+
+        assert!(trigger_matches(&trigger, &event, &[connection_id]));
+        assert!(!trigger_matches(&trigger, &event, &[Uuid::new_v4()]));
+    }
+}
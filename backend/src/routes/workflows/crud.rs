@@ -125,7 +125,7 @@ pub async fn create_workflow(
 
     match result {
         Ok(workflow) => {
-            sync_workflow_schedule(&app_state, &workflow).await;
+            sync_workflow_schedule(&app_state, &workflow, None).await;
             sync_secrets_from_workflow(&app_state, user_id, &workflow.data).await;
             (
                 StatusCode::CREATED,
@@ -512,7 +512,7 @@ pub async fn update_workflow(
                     return plan_violation_response(vec![violation]);
                 }
             }
-            sync_workflow_schedule(&app_state, &workflow).await;
+            sync_workflow_schedule(&app_state, &workflow, Some(&before)).await;
             let diffs = diff_user_nodes_only(&before.data, &workflow.data);
             if let Err(e) = app_state
                 .workflow_repo
@@ -791,6 +791,10 @@ pub async fn delete_workflow(
         .into_response();
     }
 
+    // The subscription row cascades with the workflow, but the remote Asana
+    // webhook has to be removed while its connection is still resolvable.
+    crate::routes::asana_webhooks::remove_asana_webhook(&app_state, &workflow).await;
+
     match app_state
         .workflow_repo
         .delete_workflow(user_id, workflow_id)
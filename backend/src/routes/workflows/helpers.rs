@@ -4,6 +4,7 @@ use chrono::Duration as ChronoDuration;
 
 use super::prelude::*;
 use crate::models::workflow_schedule::WorkflowSchedule;
+use crate::routes::asana_webhooks::{has_asana_webhook, remove_asana_webhook, sync_asana_webhook};
 
 pub(crate) fn is_unique_violation(err: &sqlx::Error) -> bool {
     if let sqlx::Error::Database(db_err) = err {
@@ -97,6 +98,24 @@ pub(crate) fn extract_schedule_config(graph: &Value) -> Option<Value> {
             if is_notion_trigger_type(trigger_type) {
                 return build_notion_trigger_config(data, trigger_type);
             }
+            if is_teams_trigger_type(trigger_type) {
+                return build_teams_trigger_config(data, trigger_type);
+            }
+            if is_outlook_trigger_type(trigger_type) {
+                return build_outlook_trigger_config(data, trigger_type);
+            }
+            if is_asana_trigger_type(trigger_type) {
+                return build_asana_trigger_config(data, trigger_type);
+            }
+            if is_rss_trigger_type(trigger_type) {
+                return build_rss_trigger_config(data, trigger_type);
+            }
+            if is_sftp_trigger_type(trigger_type) {
+                return build_sftp_trigger_config(data, trigger_type);
+            }
+            if is_queue_trigger_type(trigger_type) {
+                return build_queue_trigger_config(data, trigger_type);
+            }
             continue;
         }
         if let Some(cfg) = data.get("scheduleConfig") {
@@ -106,8 +125,15 @@ pub(crate) fn extract_schedule_config(graph: &Value) -> Option<Value> {
     None
 }
 
-pub(crate) async fn sync_workflow_schedule(state: &AppState, workflow: &Workflow) {
-    if let Err(error) = sync_workflow_schedule_inner(state, workflow).await {
+/// Syncs the workflow's schedule (and Asana webhook) after a save.
+/// `previous` is the workflow as it was before the save, if it existed, so
+/// saves that leave the Asana trigger untouched never reach Asana.
+pub(crate) async fn sync_workflow_schedule(
+    state: &AppState,
+    workflow: &Workflow,
+    previous: Option<&Workflow>,
+) {
+    if let Err(error) = sync_workflow_schedule_inner(state, workflow, previous).await {
         eprintln!(
             "Failed to sync schedule for workflow {}: {:?}",
             workflow.id, error
@@ -115,9 +141,19 @@ pub(crate) async fn sync_workflow_schedule(state: &AppState, workflow: &Workflow
     }
 }
 
+/// The trigger config of `data` when its trigger is an Asana one.
+fn asana_trigger_config(data: &Value) -> Option<Value> {
+    extract_schedule_config(data).filter(|cfg| {
+        cfg.get("triggerType")
+            .and_then(|value| value.as_str())
+            .is_some_and(is_asana_trigger_type)
+    })
+}
+
 async fn sync_workflow_schedule_inner(
     state: &AppState,
     workflow: &Workflow,
+    previous: Option<&Workflow>,
 ) -> Result<(), sqlx::Error> {
     let schedule_value = extract_schedule_config(&workflow.data);
     let existing = state
@@ -125,11 +161,40 @@ async fn sync_workflow_schedule_inner(
         .get_schedule_for_workflow(workflow.id)
         .await?;
 
+    let previous_asana = previous.and_then(|before| asana_trigger_config(&before.data));
+    let is_asana = schedule_value
+        .as_ref()
+        .and_then(|cfg| cfg.get("triggerType"))
+        .and_then(|value| value.as_str())
+        .is_some_and(is_asana_trigger_type);
+    // Only a workflow that had an Asana trigger before this save can own a
+    // webhook that now needs removing.
+    if !is_asana && previous_asana.is_some() {
+        remove_asana_webhook(state, workflow).await;
+    }
+
     match schedule_value {
         Some(cfg_value) => {
-            if is_notion_trigger_config(&cfg_value) {
-                let merged_config = merge_notion_state(cfg_value, existing.as_ref());
-                let next_offset = compute_notion_next_run(
+            // Asana prefers push delivery; the polling schedule only runs
+            // when no webhook could be registered. An unchanged trigger keeps
+            // whichever mode it already had.
+            let webhook_active = if !is_asana {
+                false
+            } else if previous_asana.as_ref() == Some(&cfg_value) {
+                has_asana_webhook(state, workflow).await
+            } else {
+                sync_asana_webhook(state, workflow, &cfg_value).await
+            };
+            if webhook_active {
+                state
+                    .workflow_repo
+                    .disable_workflow_schedule(workflow.id)
+                    .await?;
+                return Ok(());
+            }
+            if is_polling_trigger_config(&cfg_value) {
+                let merged_config = merge_trigger_state(cfg_value, existing.as_ref());
+                let next_offset = compute_polling_next_run(
                     existing.as_ref().and_then(|s| s.next_run_at),
                     &merged_config,
                 );
@@ -198,22 +263,68 @@ async fn sync_workflow_schedule_inner(
     Ok(())
 }
 
-const DEFAULT_NOTION_POLL_INTERVAL_SECONDS: i64 = 300;
-const MIN_NOTION_POLL_INTERVAL_SECONDS: i64 = 30;
-const MAX_NOTION_POLL_INTERVAL_SECONDS: i64 = 3600;
+const DEFAULT_POLL_INTERVAL_SECONDS: i64 = 300;
+const DEFAULT_QUEUE_POLL_INTERVAL_SECONDS: i64 = 30;
+const MIN_POLL_INTERVAL_SECONDS: i64 = 30;
+const MAX_POLL_INTERVAL_SECONDS: i64 = 3600;
 
 fn is_notion_trigger_type(trigger_type: &str) -> bool {
     matches!(
         trigger_type.trim().to_ascii_lowercase().as_str(),
-        "notion.new_database_row" | "notion.updated_database_row"
+        "notion.new_database_row" | "notion.updated_database_row" | "notion.property_changed"
+    )
+}
+
+fn is_teams_trigger_type(trigger_type: &str) -> bool {
+    trigger_type
+        .trim()
+        .eq_ignore_ascii_case("teams.new_channel_message")
+}
+
+fn is_outlook_trigger_type(trigger_type: &str) -> bool {
+    matches!(
+        trigger_type.trim().to_ascii_lowercase().as_str(),
+        "outlook.new_mail" | "outlook.new_calendar_event"
     )
 }
 
-fn is_notion_trigger_config(config: &Value) -> bool {
+fn is_asana_trigger_type(trigger_type: &str) -> bool {
+    matches!(
+        trigger_type.trim().to_ascii_lowercase().as_str(),
+        "asana.new_task" | "asana.task_completed" | "asana.comment_added"
+    )
+}
+
+fn is_rss_trigger_type(trigger_type: &str) -> bool {
+    trigger_type.trim().eq_ignore_ascii_case("rss.new_item")
+}
+
+fn is_sftp_trigger_type(trigger_type: &str) -> bool {
+    trigger_type.trim().eq_ignore_ascii_case("sftp.new_file")
+}
+
+fn is_queue_trigger_type(trigger_type: &str) -> bool {
+    matches!(
+        trigger_type.trim().to_ascii_lowercase().as_str(),
+        "rabbitmq.message" | "redis.stream_message"
+    )
+}
+
+/// Polling triggers (Notion, Teams, Outlook, Asana, RSS, SFTP, queues) are stored as schedules whose config
+/// carries the trigger identity plus a `state` cursor owned by the worker.
+fn is_polling_trigger_config(config: &Value) -> bool {
     config
         .get("triggerType")
         .and_then(|value| value.as_str())
-        .map(is_notion_trigger_type)
+        .map(|trigger_type| {
+            is_notion_trigger_type(trigger_type)
+                || is_teams_trigger_type(trigger_type)
+                || is_outlook_trigger_type(trigger_type)
+                || is_asana_trigger_type(trigger_type)
+                || is_rss_trigger_type(trigger_type)
+                || is_sftp_trigger_type(trigger_type)
+                || is_queue_trigger_type(trigger_type)
+        })
         .unwrap_or(false)
 }
 
@@ -235,6 +346,14 @@ fn build_notion_trigger_config(data: &Value, trigger_type: &str) -> Option<Value
     out.insert("connectionId".to_string(), Value::String(connection_id));
     out.insert("databaseId".to_string(), Value::String(database_id));
 
+    let watched = read_string_list(map.get("watchedProperties"));
+    if !watched.is_empty() {
+        out.insert(
+            "watchedProperties".to_string(),
+            Value::Array(watched.into_iter().map(Value::String).collect()),
+        );
+    }
+
     if let Some(page_size) = read_page_size(map.get("pageSize")) {
         out.insert(
             "pageSize".to_string(),
@@ -245,7 +364,210 @@ fn build_notion_trigger_config(data: &Value, trigger_type: &str) -> Option<Value
     Some(Value::Object(out))
 }
 
-fn merge_notion_state(config: Value, existing: Option<&WorkflowSchedule>) -> Value {
+fn build_teams_trigger_config(data: &Value, trigger_type: &str) -> Option<Value> {
+    let map = data.as_object()?;
+    let connection_scope = read_string(map.get("connectionScope"))?;
+    let connection_id = read_string(map.get("connectionId"))?;
+    let team_id = read_string(map.get("teamId"))?;
+    let channel_id = read_string(map.get("channelId"))?;
+
+    let mut out = serde_json::Map::new();
+    out.insert(
+        "triggerType".to_string(),
+        Value::String(trigger_type.to_string()),
+    );
+    out.insert(
+        "connectionScope".to_string(),
+        Value::String(connection_scope),
+    );
+    out.insert("connectionId".to_string(), Value::String(connection_id));
+    out.insert("teamId".to_string(), Value::String(team_id));
+    out.insert("channelId".to_string(), Value::String(channel_id));
+
+    if let Some(interval) = read_page_size(map.get("pollIntervalSeconds")) {
+        out.insert(
+            "pollIntervalSeconds".to_string(),
+            Value::Number(serde_json::Number::from(interval)),
+        );
+    }
+
+    Some(Value::Object(out))
+}
+
+fn build_outlook_trigger_config(data: &Value, trigger_type: &str) -> Option<Value> {
+    let map = data.as_object()?;
+    let connection_scope = read_string(map.get("connectionScope"))?;
+    let connection_id = read_string(map.get("connectionId"))?;
+
+    let mut out = serde_json::Map::new();
+    out.insert(
+        "triggerType".to_string(),
+        Value::String(trigger_type.to_string()),
+    );
+    out.insert(
+        "connectionScope".to_string(),
+        Value::String(connection_scope),
+    );
+    out.insert("connectionId".to_string(), Value::String(connection_id));
+
+    if trigger_type.trim().eq_ignore_ascii_case("outlook.new_mail") {
+        if let Some(folder_id) = read_string(map.get("folderId")) {
+            out.insert("folderId".to_string(), Value::String(folder_id));
+        }
+    }
+
+    if let Some(interval) = read_page_size(map.get("pollIntervalSeconds")) {
+        out.insert(
+            "pollIntervalSeconds".to_string(),
+            Value::Number(serde_json::Number::from(interval)),
+        );
+    }
+
+    Some(Value::Object(out))
+}
+
+fn build_asana_trigger_config(data: &Value, trigger_type: &str) -> Option<Value> {
+    let map = data.as_object()?;
+    let connection_scope = read_string(map.get("connectionScope"))?;
+    let connection_id = read_string(map.get("connectionId"))?;
+    let project_gid = read_string(map.get("projectGid"))?;
+
+    let mut out = serde_json::Map::new();
+    out.insert(
+        "triggerType".to_string(),
+        Value::String(trigger_type.to_string()),
+    );
+    out.insert(
+        "connectionScope".to_string(),
+        Value::String(connection_scope),
+    );
+    out.insert("connectionId".to_string(), Value::String(connection_id));
+    out.insert("projectGid".to_string(), Value::String(project_gid));
+
+    if let Some(interval) = read_page_size(map.get("pollIntervalSeconds")) {
+        out.insert(
+            "pollIntervalSeconds".to_string(),
+            Value::Number(serde_json::Number::from(interval)),
+        );
+    }
+
+    Some(Value::Object(out))
+}
+
+fn build_rss_trigger_config(data: &Value, trigger_type: &str) -> Option<Value> {
+    let map = data.as_object()?;
+    let feed_url = read_string(map.get("feedUrl"))?;
+
+    let mut out = serde_json::Map::new();
+    out.insert(
+        "triggerType".to_string(),
+        Value::String(trigger_type.to_string()),
+    );
+    out.insert("feedUrl".to_string(), Value::String(feed_url));
+
+    if let Some(interval) = read_page_size(map.get("pollIntervalSeconds")) {
+        out.insert(
+            "pollIntervalSeconds".to_string(),
+            Value::Number(serde_json::Number::from(interval)),
+        );
+    }
+    if let Some(max_items) = read_page_size(map.get("maxItemsPerPoll")) {
+        out.insert(
+            "maxItemsPerPoll".to_string(),
+            Value::Number(serde_json::Number::from(max_items)),
+        );
+    }
+
+    Some(Value::Object(out))
+}
+
+/// Credentials stay as secret-store references here; the worker hydrates
+/// them from the owner's secret store on each poll.
+fn build_sftp_trigger_config(data: &Value, trigger_type: &str) -> Option<Value> {
+    let map = data.as_object()?;
+    let host = read_string(map.get("host"))?;
+    let directory = read_string(map.get("directory"))?;
+
+    let mut out = serde_json::Map::new();
+    out.insert(
+        "triggerType".to_string(),
+        Value::String(trigger_type.to_string()),
+    );
+    out.insert("host".to_string(), Value::String(host));
+    out.insert("directory".to_string(), Value::String(directory));
+    for key in [
+        "username",
+        "password",
+        "privateKey",
+        "privateKeyPassphrase",
+        "hostKey",
+        "pattern",
+    ] {
+        if let Some(value) = read_string(map.get(key)) {
+            out.insert(key.to_string(), Value::String(value));
+        }
+    }
+    if let Some(port) = read_page_size(map.get("port")) {
+        out.insert(
+            "port".to_string(),
+            Value::Number(serde_json::Number::from(port)),
+        );
+    }
+    if let Some(interval) = read_page_size(map.get("pollIntervalSeconds")) {
+        out.insert(
+            "pollIntervalSeconds".to_string(),
+            Value::Number(serde_json::Number::from(interval)),
+        );
+    }
+    if let Some(max_files) = read_page_size(map.get("maxFilesPerPoll")) {
+        out.insert(
+            "maxFilesPerPoll".to_string(),
+            Value::Number(serde_json::Number::from(max_files)),
+        );
+    }
+
+    Some(Value::Object(out))
+}
+
+/// The broker URL stays a secret-store reference; the worker hydrates it on
+/// each poll. RabbitMQ triggers read `queue`, Redis triggers read `stream`.
+fn build_queue_trigger_config(data: &Value, trigger_type: &str) -> Option<Value> {
+    let map = data.as_object()?;
+    let source_key = if trigger_type.trim().eq_ignore_ascii_case("rabbitmq.message") {
+        "queue"
+    } else {
+        "stream"
+    };
+    let source = read_string(map.get(source_key))?;
+
+    let mut out = serde_json::Map::new();
+    out.insert(
+        "triggerType".to_string(),
+        Value::String(trigger_type.to_string()),
+    );
+    out.insert(source_key.to_string(), Value::String(source));
+    for key in ["connectionUrl", "consumerGroup"] {
+        if let Some(value) = read_string(map.get(key)) {
+            out.insert(key.to_string(), Value::String(value));
+        }
+    }
+    if let Some(interval) = read_page_size(map.get("pollIntervalSeconds")) {
+        out.insert(
+            "pollIntervalSeconds".to_string(),
+            Value::Number(serde_json::Number::from(interval)),
+        );
+    }
+    if let Some(max_messages) = read_page_size(map.get("maxMessagesPerPoll")) {
+        out.insert(
+            "maxMessagesPerPoll".to_string(),
+            Value::Number(serde_json::Number::from(max_messages)),
+        );
+    }
+
+    Some(Value::Object(out))
+}
+
+fn merge_trigger_state(config: Value, existing: Option<&WorkflowSchedule>) -> Value {
     let Some(existing) = existing else {
         return config;
     };
@@ -262,7 +584,7 @@ fn merge_notion_state(config: Value, existing: Option<&WorkflowSchedule>) -> Val
     updated
 }
 
-fn compute_notion_next_run(
+fn compute_polling_next_run(
     existing_next: Option<OffsetDateTime>,
     config: &Value,
 ) -> Option<OffsetDateTime> {
@@ -273,24 +595,36 @@ fn compute_notion_next_run(
         }
     }
 
-    let interval = notion_poll_interval_seconds(config).max(1);
+    let interval = poll_interval_seconds(config).max(1);
     let next_dt = Utc::now().checked_add_signed(ChronoDuration::seconds(interval))?;
     utc_to_offset(next_dt)
 }
 
-fn notion_poll_interval_seconds(config: &Value) -> i64 {
+fn poll_interval_seconds(config: &Value) -> i64 {
     let from_config = read_page_size(config.get("pollIntervalSeconds")).map(|value| value as i64);
-    let from_env = std::env::var("NOTION_POLL_INTERVAL_SECONDS")
+    let env_key = match config.get("triggerType").and_then(|value| value.as_str()) {
+        Some(trigger_type) if is_teams_trigger_type(trigger_type) => "TEAMS_POLL_INTERVAL_SECONDS",
+        Some(trigger_type) if is_outlook_trigger_type(trigger_type) => {
+            "OUTLOOK_POLL_INTERVAL_SECONDS"
+        }
+        Some(trigger_type) if is_asana_trigger_type(trigger_type) => "ASANA_POLL_INTERVAL_SECONDS",
+        Some(trigger_type) if is_rss_trigger_type(trigger_type) => "RSS_POLL_INTERVAL_SECONDS",
+        Some(trigger_type) if is_sftp_trigger_type(trigger_type) => "SFTP_POLL_INTERVAL_SECONDS",
+        Some(trigger_type) if is_queue_trigger_type(trigger_type) => "QUEUE_POLL_INTERVAL_SECONDS",
+        _ => "NOTION_POLL_INTERVAL_SECONDS",
+    };
+    let default = match config.get("triggerType").and_then(|value| value.as_str()) {
+        Some(trigger_type) if is_queue_trigger_type(trigger_type) => {
+            DEFAULT_QUEUE_POLL_INTERVAL_SECONDS
+        }
+        _ => DEFAULT_POLL_INTERVAL_SECONDS,
+    };
+    let from_env = std::env::var(env_key)
         .ok()
         .and_then(|value| value.parse::<i64>().ok());
 
-    let raw = from_config
-        .or(from_env)
-        .unwrap_or(DEFAULT_NOTION_POLL_INTERVAL_SECONDS);
-    raw.clamp(
-        MIN_NOTION_POLL_INTERVAL_SECONDS,
-        MAX_NOTION_POLL_INTERVAL_SECONDS,
-    )
+    let raw = from_config.or(from_env).unwrap_or(default);
+    raw.clamp(MIN_POLL_INTERVAL_SECONDS, MAX_POLL_INTERVAL_SECONDS)
 }
 
 fn read_page_size(value: Option<&Value>) -> Option<u32> {
@@ -301,6 +635,24 @@ fn read_page_size(value: Option<&Value>) -> Option<u32> {
     }
 }
 
+/// Accepts either a JSON list of strings or a comma-separated string.
+fn read_string_list(value: Option<&Value>) -> Vec<String> {
+    let items: Vec<String> = match value {
+        Some(Value::Array(items)) => items
+            .iter()
+            .filter_map(|item| item.as_str())
+            .map(str::to_string)
+            .collect(),
+        Some(Value::String(raw)) => raw.split(',').map(str::to_string).collect(),
+        _ => Vec::new(),
+    };
+    items
+        .into_iter()
+        .map(|item| item.trim().to_string())
+        .filter(|item| !item.is_empty())
+        .collect()
+}
+
 fn read_string(value: Option<&Value>) -> Option<String> {
     value
         .and_then(|v| v.as_str())
@@ -592,4 +944,150 @@ mod tests {
             &roles
         ));
     }
+
+    #[test]
+    fn notion_property_trigger_config_keeps_watched_properties() {
+        let graph = serde_json::json!({
+            "nodes": [{
+                "type": "trigger",
+                "data": {
+                    "triggerType": "notion.property_changed",
+                    "connectionScope": "personal",
+                    "connectionId": "conn-1",
+                    "databaseId": "db-1",
+                    "watchedProperties": "Status, Owner ,"
+                }
+            }]
+        });
+
+        let config = extract_schedule_config(&graph).expect("polling config");
+
+        assert!(is_polling_trigger_config(&config));
+        assert_eq!(
+            config["watchedProperties"],
+            serde_json::json!(["Status", "Owner"])
+        );
+    }
+
+    #[test]
+    fn asana_trigger_config_ignores_other_triggers() {
+        let asana = |project: &str| {
+            serde_json::json!({
+                "nodes": [{
+                    "type": "trigger",
+                    "data": {
+                        "triggerType": "asana.new_task",
+                        "connectionScope": "personal",
+                        "connectionId": "c1",
+                        "projectGid": project
+                    }
+                }]
+            })
+        };
+        let rss = serde_json::json!({
+            "nodes": [{
+                "type": "trigger",
+                "data": { "triggerType": "rss.new_item", "feedUrl": "https://example.com/feed" }
+            }]
+        });
+
+        assert!(asana_trigger_config(&rss).is_none());
+        assert_eq!(
+            asana_trigger_config(&asana("p1")),
+            asana_trigger_config(&asana("p1"))
+        );
+        assert_ne!(
+            asana_trigger_config(&asana("p1")),
+            asana_trigger_config(&asana("p2"))
+        );
+        assert_eq!(
+            asana_trigger_config(&asana("p1")).unwrap()["projectGid"],
+            "p1"
+        );
+    }
+
+    #[test]
+    fn rss_trigger_config_is_a_polling_schedule() {
+        let graph = serde_json::json!({
+            "nodes": [{
+                "type": "trigger",
+                "data": {
+                    "triggerType": "rss.new_item",
+                    "feedUrl": " https://example.com/feed.xml ",
+                    "pollIntervalSeconds": "600",
+                    "maxItemsPerPoll": 5
+                }
+            }]
+        });
+
+        let config = extract_schedule_config(&graph).expect("polling config");
+
+        assert!(is_polling_trigger_config(&config));
+        assert_eq!(config["feedUrl"], "https://example.com/feed.xml");
+        assert_eq!(config["maxItemsPerPoll"], 5);
+        assert_eq!(poll_interval_seconds(&config), 600);
+
+        let missing_url = serde_json::json!({
+            "nodes": [{ "type": "trigger", "data": { "triggerType": "rss.new_item" } }]
+        });
+        assert!(extract_schedule_config(&missing_url).is_none());
+    }
+
+    #[test]
+    fn sftp_trigger_config_keeps_secret_references() {
+        let graph = serde_json::json!({
+            "nodes": [{
+                "type": "trigger",
+                "data": {
+                    "triggerType": "sftp.new_file",
+                    "host": "sftp.partner.example",
+                    "port": "2222",
+                    "username": "dsentr",
+                    "privateKey": "sftp:partner:deploy_key",
+                    "hostKey": "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIH0k",
+                    "directory": "/outbound",
+                    "pattern": "*.csv"
+                }
+            }]
+        });
+
+        let config = extract_schedule_config(&graph).expect("polling config");
+
+        assert!(is_polling_trigger_config(&config));
+        assert_eq!(config["port"], 2222);
+        assert_eq!(config["privateKey"], "sftp:partner:deploy_key");
+        assert_eq!(config["directory"], "/outbound");
+        assert!(config.get("password").is_none());
+    }
+
+    #[test]
+    fn queue_trigger_configs_read_queue_or_stream() {
+        let rabbit = serde_json::json!({
+            "nodes": [{
+                "type": "trigger",
+                "data": {
+                    "triggerType": "rabbitmq.message",
+                    "connectionUrl": "queue:rabbitmq:prod",
+                    "queue": "orders",
+                    "stream": "ignored",
+                    "maxMessagesPerPoll": "25"
+                }
+            }]
+        });
+        let config = extract_schedule_config(&rabbit).expect("polling config");
+        assert!(is_polling_trigger_config(&config));
+        assert_eq!(config["queue"], "orders");
+        assert_eq!(config["connectionUrl"], "queue:rabbitmq:prod");
+        assert_eq!(config["maxMessagesPerPoll"], 25);
+        assert!(config.get("stream").is_none());
+        assert_eq!(poll_interval_seconds(&config), 30);
+
+        let redis = serde_json::json!({
+            "nodes": [{
+                "type": "trigger",
+                "data": { "triggerType": "redis.stream_message", "queue": "orders" }
+            }]
+        });
+        assert!(extract_schedule_config(&redis).is_none());
+    }
 }
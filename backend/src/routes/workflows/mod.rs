@@ -19,14 +19,16 @@ pub use dead_letters::{clear_dead_letters_api, list_dead_letters, requeue_dead_l
 pub use egress::{
     clear_egress_block_events, get_egress_allowlist, list_egress_block_events, set_egress_allowlist,
 };
+pub(crate) use helpers::is_unique_violation;
 pub use logs::{clear_workflow_logs, delete_workflow_log_entry, list_workflow_logs};
 pub use plan::get_plan_usage;
 pub use runs::{
-    cancel_all_runs_for_workflow, cancel_workflow_run, download_run_json, get_workflow_run_status,
-    list_active_runs, list_runs_for_workflow, rerun_from_failed_node, rerun_workflow_run,
-    start_workflow_run,
+    cancel_all_runs_for_workflow, cancel_workflow_run, download_run_artifact, download_run_json,
+    get_workflow_run_status, list_active_runs, list_run_artifacts, list_runs_for_workflow,
+    rerun_from_failed_node, rerun_workflow_run, start_workflow_run,
 };
 pub use sse::{sse_global_runs, sse_run_events, sse_workflow_runs, sse_workflow_updates};
+pub(crate) use webhooks::enqueue_external_trigger_run;
 pub use webhooks::{
     get_webhook_config, get_webhook_url, regenerate_webhook_signing_key, regenerate_webhook_token,
     set_webhook_config, webhook_trigger,
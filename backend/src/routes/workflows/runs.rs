@@ -8,6 +8,7 @@ use crate::{
     runaway_protection::{
         enforce_runaway_protection, RunawayProtectionError, RUNAWAY_PROTECTION_ERROR,
     },
+    services::artifacts::read_artifact,
     state::WorkspaceRunQuotaTicket,
     utils::{secrets::hydrate_secrets_into_snapshot, workflow_connection_metadata},
 };
@@ -836,12 +837,154 @@ pub async fn download_run_json(
     }
 }
 
+/// Confirms the run belongs to a workflow the caller can see.
+async fn ensure_run_for_member(
+    app_state: &AppState,
+    user_id: Uuid,
+    workflow_id: Uuid,
+    run_id: Uuid,
+    server_error_message: &'static str,
+) -> Result<Workflow, Response> {
+    let workflow =
+        fetch_workflow_for_member(app_state, user_id, workflow_id, server_error_message).await?;
+    match app_state
+        .workflow_repo
+        .get_workflow_run(workflow.user_id, workflow_id, run_id)
+        .await
+    {
+        Ok(Some(_)) => Ok(workflow),
+        Ok(None) => Err(JsonResponse::not_found("Run not found").into_response()),
+        Err(e) => {
+            eprintln!("DB error fetching run: {:?}", e);
+            Err(JsonResponse::server_error(server_error_message).into_response())
+        }
+    }
+}
+
+// List files produced by a run
+pub async fn list_run_artifacts(
+    State(app_state): State<AppState>,
+    AuthSession(claims): AuthSession,
+    Path((workflow_id, run_id)): Path<(Uuid, Uuid)>,
+) -> Response {
+    let user_id = match Uuid::parse_str(&claims.id) {
+        Ok(id) => id,
+        Err(_) => return JsonResponse::unauthorized("Invalid user ID").into_response(),
+    };
+
+    if let Err(response) = ensure_run_for_member(
+        &app_state,
+        user_id,
+        workflow_id,
+        run_id,
+        "Failed to list artifacts",
+    )
+    .await
+    {
+        return response;
+    }
+
+    match app_state
+        .workflow_repo
+        .list_run_artifacts(workflow_id, run_id)
+        .await
+    {
+        Ok(artifacts) => (
+            StatusCode::OK,
+            Json(json!({ "success": true, "artifacts": artifacts })),
+        )
+            .into_response(),
+        Err(e) => {
+            eprintln!("DB error listing run artifacts: {:?}", e);
+            JsonResponse::server_error("Failed to list artifacts").into_response()
+        }
+    }
+}
+
+// Download a single run artifact
+pub async fn download_run_artifact(
+    State(app_state): State<AppState>,
+    AuthSession(claims): AuthSession,
+    Path((workflow_id, run_id, artifact_id)): Path<(Uuid, Uuid, Uuid)>,
+) -> Response {
+    let user_id = match Uuid::parse_str(&claims.id) {
+        Ok(id) => id,
+        Err(_) => return JsonResponse::unauthorized("Invalid user ID").into_response(),
+    };
+
+    if let Err(response) = ensure_run_for_member(
+        &app_state,
+        user_id,
+        workflow_id,
+        run_id,
+        "Failed to download artifact",
+    )
+    .await
+    {
+        return response;
+    }
+
+    let artifact = match app_state
+        .workflow_repo
+        .find_run_artifact(workflow_id, artifact_id)
+        .await
+    {
+        Ok(Some(artifact)) if artifact.run_id == run_id => artifact,
+        Ok(_) => return JsonResponse::not_found("Artifact not found").into_response(),
+        Err(e) => {
+            eprintln!("DB error fetching run artifact: {:?}", e);
+            return JsonResponse::server_error("Failed to download artifact").into_response();
+        }
+    };
+
+    let bytes = match read_artifact(&artifact).await {
+        Ok(bytes) => bytes,
+        Err(err) => {
+            eprintln!("Failed to read artifact {}: {}", artifact.id, err);
+            return JsonResponse::server_error("Failed to download artifact").into_response();
+        }
+    };
+
+    // Artifacts hold arbitrary third-party content, so they are always served
+    // as downloads and never sniffed or rendered inline.
+    let content_type = axum::http::HeaderValue::from_str(&artifact.mime_type)
+        .unwrap_or_else(|_| axum::http::HeaderValue::from_static("application/octet-stream"));
+    let ascii_name: String = artifact
+        .name
+        .chars()
+        .map(|c| {
+            if (c.is_ascii_graphic() && c != '"' && c != '\\') || c == ' ' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let disposition = format!(
+        "attachment; filename=\"{}\"; filename*=UTF-8''{}",
+        ascii_name,
+        urlencoding::encode(&artifact.name)
+    );
+
+    let mut resp = (StatusCode::OK, bytes).into_response();
+    let headers = resp.headers_mut();
+    headers.insert(axum::http::header::CONTENT_TYPE, content_type);
+    if let Ok(value) = axum::http::HeaderValue::from_str(&disposition) {
+        headers.insert(axum::http::header::CONTENT_DISPOSITION, value);
+    }
+    headers.insert(
+        axum::http::header::X_CONTENT_TYPE_OPTIONS,
+        axum::http::HeaderValue::from_static("nosniff"),
+    );
+    resp
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::config::{
-        Config, OAuthProviderConfig, OAuthSettings, StripeSettings, DEFAULT_WORKSPACE_MEMBER_LIMIT,
-        DEFAULT_WORKSPACE_MONTHLY_RUN_LIMIT, RUNAWAY_LIMIT_5MIN,
+        Config, OAuthProviderConfig, OAuthSettings, ProviderBaseUrls, StripeSettings,
+        DEFAULT_WORKSPACE_MEMBER_LIMIT, DEFAULT_WORKSPACE_MONTHLY_RUN_LIMIT, RUNAWAY_LIMIT_5MIN,
     };
     use crate::db::{
         mock_db::{MockDb, StaticWorkspaceMembershipRepository},
@@ -903,6 +1046,11 @@ mod tests {
                     client_secret: "secret".into(),
                     redirect_uri: "https://app.example.com/oauth/asana".into(),
                 },
+                github: OAuthProviderConfig {
+                    client_id: "client".into(),
+                    client_secret: "secret".into(),
+                    redirect_uri: "https://app.example.com/oauth/asana".into(),
+                },
                 token_encryption_key: vec![0; 32],
             },
             api_secrets_encryption_key: vec![1; 32],
@@ -918,6 +1066,9 @@ mod tests {
             workspace_member_limit: DEFAULT_WORKSPACE_MEMBER_LIMIT,
             workspace_monthly_run_limit: DEFAULT_WORKSPACE_MONTHLY_RUN_LIMIT,
             runaway_limit_5min: RUNAWAY_LIMIT_5MIN,
+            slack_signing_secret: None,
+            public_api_base_url: None,
+            provider_base_urls: ProviderBaseUrls::default(),
         })
     }
 
@@ -1400,4 +1551,85 @@ mod tests {
         assert_eq!(recorded.len(), 1);
         assert_eq!(recorded[0], period_end);
     }
+
+    #[tokio::test]
+    async fn download_run_artifact_serves_stored_file_as_attachment() {
+        use crate::models::run_artifact::RunArtifact;
+        use crate::services::artifacts::{artifacts_root, ArtifactStore, LocalArtifactStore};
+        use sha2::{Digest, Sha256};
+
+        let owner_id = Uuid::new_v4();
+        let workflow = workflow_fixture(Uuid::new_v4(), owner_id);
+        let run = run_fixture(&workflow);
+        let bytes = b"%PDF-1.7 quarterly".to_vec();
+        let artifact = RunArtifact {
+            id: Uuid::new_v4(),
+            workflow_id: workflow.id,
+            run_id: run.id,
+            node_id: "http-1".into(),
+            name: "Q3 résumé.pdf".into(),
+            mime_type: "application/pdf".into(),
+            size: bytes.len() as i64,
+            sha256: hex::encode(Sha256::digest(&bytes)),
+            storage: "local".into(),
+            key: format!("{}/{}/{}", workflow.id, run.id, Uuid::new_v4()),
+            created_at: run.created_at,
+        };
+        LocalArtifactStore::new(artifacts_root())
+            .put(&artifact.key, &artifact.mime_type, &bytes)
+            .await
+            .expect("store artifact");
+
+        let mut repo = MockWorkflowRepository::new();
+        let workflow_for_find = workflow.clone();
+        repo.expect_find_workflow_for_member()
+            .returning(move |_, _| {
+                let wf = workflow_for_find.clone();
+                Box::pin(async move { Ok(Some(wf)) })
+            });
+        let run_for_get = run.clone();
+        repo.expect_get_workflow_run().returning(move |_, _, _| {
+            let run = run_for_get.clone();
+            Box::pin(async move { Ok(Some(run)) })
+        });
+        let artifact_for_find = artifact.clone();
+        repo.expect_find_run_artifact().returning(move |_, _| {
+            let artifact = artifact_for_find.clone();
+            Box::pin(async move { Ok(Some(artifact)) })
+        });
+        let state = test_state(
+            Arc::new(repo),
+            Arc::new(StaticWorkspaceMembershipRepository::with_plan(
+                PlanTier::Workspace,
+            )),
+        );
+
+        let response = download_run_artifact(
+            State(state.clone()),
+            AuthSession(claims_fixture(owner_id, "owner@example.com")),
+            Path((workflow.id, run.id, artifact.id)),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let headers = response.headers();
+        assert_eq!(headers["content-type"], "application/pdf");
+        assert_eq!(headers["x-content-type-options"], "nosniff");
+        assert_eq!(
+            headers["content-disposition"],
+            "attachment; filename=\"Q3 r_sum_.pdf\"; filename*=UTF-8''Q3%20r%C3%A9sum%C3%A9.pdf"
+        );
+        let body = to_bytes(response.into_body(), 1024).await.unwrap();
+        assert_eq!(body.as_ref(), bytes.as_slice());
+
+        // The artifact belongs to a different run than the one in the path.
+        let response = download_run_artifact(
+            State(state),
+            AuthSession(claims_fixture(owner_id, "owner@example.com")),
+            Path((workflow.id, Uuid::new_v4(), artifact.id)),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let _ = std::fs::remove_file(artifacts_root().join(&artifact.key));
+    }
 }
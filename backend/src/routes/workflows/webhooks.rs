@@ -1,12 +1,15 @@
 use super::{prelude::*, runs::redact_run};
-use crate::config::MIN_WEBHOOK_SECRET_LENGTH;
 use crate::{
+    models::workflow_run::WorkflowRun,
     routes::plan_limits::workspace_limit_error_response,
     runaway_protection::{
         enforce_runaway_protection, RunawayProtectionError, RUNAWAY_PROTECTION_ERROR,
     },
     state::WorkspaceRunQuotaTicket,
     utils::plan_limits::NormalizedPlanTier,
+    utils::webhook_signing::{
+        compute_signature, webhook_secret, SIGNATURE_HEADER, TIMESTAMP_HEADER,
+    },
 };
 use axum::http::HeaderMap;
 use tracing::error;
@@ -40,15 +43,6 @@ fn compute_webhook_signing_key(
     base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(res)
 }
 
-fn webhook_secret(config: &crate::config::Config) -> Option<String> {
-    let secret = config.webhook_secret.clone();
-    if secret.len() < MIN_WEBHOOK_SECRET_LENGTH {
-        error!("WEBHOOK_SECRET is not configured with sufficient entropy");
-        return None;
-    }
-    Some(secret)
-}
-
 fn missing_webhook_secret_response() -> Response {
     JsonResponse::server_error("Webhook secret is not configured; contact an administrator.")
         .into_response()
@@ -259,11 +253,11 @@ pub async fn webhook_trigger(
                 (ts, sg, true)
             } else {
                 let ts_h = headers
-                    .get("X-DSentr-Timestamp")
+                    .get(TIMESTAMP_HEADER)
                     .and_then(|v| v.to_str().ok())
                     .map(|s| s.to_string());
                 let sg_h = headers
-                    .get("X-DSentr-Signature")
+                    .get(SIGNATURE_HEADER)
                     .and_then(|v| v.to_str().ok())
                     .map(|s| s.to_string());
                 if let (Some(ts), Some(sg)) = (ts_h, sg_h) {
@@ -311,13 +305,7 @@ pub async fn webhook_trigger(
         } else {
             String::new()
         };
-        let payload = format!("{}.{}", ts_str, raw_body);
-        let key_bytes = base64::engine::general_purpose::URL_SAFE_NO_PAD
-            .decode(signing_key_b64.as_bytes())
-            .unwrap_or_default();
-        let mut mac = HmacSha256::new_from_slice(&key_bytes).expect("HMAC");
-        mac.update(payload.as_bytes());
-        let expected = hex::encode(mac.finalize().into_bytes());
+        let expected = compute_signature(&signing_key_b64, &ts_str, raw_body.as_bytes());
         let provided = sig_str.strip_prefix("v1=").unwrap_or(sig_str.as_str());
         if subtle::ConstantTimeEq::ct_eq(expected.as_bytes(), provided.as_bytes()).unwrap_u8()
             == 0u8
@@ -333,23 +321,47 @@ pub async fn webhook_trigger(
         }
     }
 
+    let start_node = selected_trigger.map(|target| (target.id.as_str(), target.label.as_str()));
+    match enqueue_external_trigger_run(&app_state, &wf, body.map(|Json(ctx)| ctx), start_node).await
+    {
+        Ok(run) => {
+            let safe_run = redact_run(run);
+            (
+                StatusCode::ACCEPTED,
+                Json(json!({"success": true, "run": safe_run})),
+            )
+                .into_response()
+        }
+        Err(response) => response,
+    }
+}
+
+/// Enqueues a run for a workflow started by an inbound request (signed
+/// webhooks, provider events). Runaway protection and the workspace run quota
+/// are applied identically for every inbound trigger source.
+pub(crate) async fn enqueue_external_trigger_run(
+    app_state: &AppState,
+    wf: &Workflow,
+    trigger_context: Option<Value>,
+    start_node: Option<(&str, &str)>,
+) -> Result<WorkflowRun, Response> {
     let settings = match app_state.db.get_user_settings(wf.user_id).await {
         Ok(val) => val,
         Err(err) => {
             error!(?err, user_id = %wf.user_id, "failed to load user settings");
-            return JsonResponse::server_error("Failed to enqueue").into_response();
+            return Err(JsonResponse::server_error("Failed to enqueue").into_response());
         }
     };
 
     if let Some(workspace_id) = wf.workspace_id {
-        if let Err(err) = enforce_runaway_protection(&app_state, workspace_id, &settings).await {
+        if let Err(err) = enforce_runaway_protection(app_state, workspace_id, &settings).await {
             match err {
                 RunawayProtectionError::RunawayProtectionTriggered { .. } => {
-                    return (
+                    return Err((
                         StatusCode::TOO_MANY_REQUESTS,
                         Json(json!({ "error": RUNAWAY_PROTECTION_ERROR })),
                     )
-                        .into_response();
+                        .into_response());
                 }
                 RunawayProtectionError::Database(db_err) => {
                     error!(
@@ -357,19 +369,19 @@ pub async fn webhook_trigger(
                         %workspace_id,
                         "failed to enforce runaway protection"
                     );
-                    return JsonResponse::server_error("Failed to enqueue").into_response();
+                    return Err(JsonResponse::server_error("Failed to enqueue").into_response());
                 }
             }
         }
     }
 
     let mut snapshot = wf.data.clone();
-    if let Some(Json(ctx)) = body {
+    if let Some(ctx) = trigger_context {
         snapshot["_trigger_context"] = ctx;
     }
-    if let Some(target) = selected_trigger {
-        snapshot["_start_from_node"] = serde_json::Value::String(target.id.clone());
-        snapshot["_start_trigger_label"] = serde_json::Value::String(target.label.clone());
+    if let Some((id, label)) = start_node {
+        snapshot["_start_from_node"] = serde_json::Value::String(id.to_string());
+        snapshot["_start_trigger_label"] = serde_json::Value::String(label.to_string());
     }
     snapshot["_egress_allowlist"] = serde_json::Value::Array(
         wf.egress_allowlist
@@ -394,7 +406,7 @@ pub async fn webhook_trigger(
                 workspace_quota = Some(ticket);
             }
             Ok(None) => {}
-            Err(err) => return workspace_limit_error_response(err),
+            Err(err) => return Err(workspace_limit_error_response(err)),
         }
     }
 
@@ -407,19 +419,14 @@ pub async fn webhook_trigger(
             if let (Some(ticket), false) = (&workspace_quota, outcome.created) {
                 let _ = app_state.release_workspace_run_quota(*ticket).await;
             }
-            let safe_run = redact_run(outcome.run);
-            (
-                StatusCode::ACCEPTED,
-                Json(json!({"success": true, "run": safe_run})),
-            )
-                .into_response()
+            Ok(outcome.run)
         }
         Err(e) => {
             if let Some(ticket) = workspace_quota {
                 let _ = app_state.release_workspace_run_quota(ticket).await;
             }
             eprintln!("DB error creating run: {:?}", e);
-            JsonResponse::server_error("Failed to enqueue run").into_response()
+            Err(JsonResponse::server_error("Failed to enqueue run").into_response())
         }
     }
 }
@@ -652,8 +659,8 @@ pub async fn regenerate_webhook_signing_key(
 mod tests {
     use super::*;
     use crate::config::{
-        Config, OAuthProviderConfig, OAuthSettings, StripeSettings, DEFAULT_WORKSPACE_MEMBER_LIMIT,
-        DEFAULT_WORKSPACE_MONTHLY_RUN_LIMIT, RUNAWAY_LIMIT_5MIN,
+        Config, OAuthProviderConfig, OAuthSettings, ProviderBaseUrls, StripeSettings,
+        DEFAULT_WORKSPACE_MEMBER_LIMIT, DEFAULT_WORKSPACE_MONTHLY_RUN_LIMIT, RUNAWAY_LIMIT_5MIN,
     };
     use crate::db::{
         mock_db::{MockDb, StaticWorkspaceMembershipRepository},
@@ -713,6 +720,11 @@ mod tests {
                     client_secret: "secret".into(),
                     redirect_uri: "https://app.example.com/oauth/asana".into(),
                 },
+                github: OAuthProviderConfig {
+                    client_id: "client".into(),
+                    client_secret: "secret".into(),
+                    redirect_uri: "https://app.example.com/oauth/asana".into(),
+                },
                 token_encryption_key: vec![0; 32],
             },
             api_secrets_encryption_key: vec![1; 32],
@@ -728,6 +740,9 @@ mod tests {
             workspace_member_limit: DEFAULT_WORKSPACE_MEMBER_LIMIT,
             workspace_monthly_run_limit: DEFAULT_WORKSPACE_MONTHLY_RUN_LIMIT,
             runaway_limit_5min: RUNAWAY_LIMIT_5MIN,
+            slack_signing_secret: None,
+            public_api_base_url: None,
+            provider_base_urls: ProviderBaseUrls::default(),
         })
     }
 
@@ -2126,7 +2126,9 @@ mod tests {
         PromoteWorkspaceConnectionPayload, RevokeWorkspaceMemberPayload,
         WorkspaceToSoloExecutePayload,
     };
-    use crate::config::{Config, OAuthProviderConfig, OAuthSettings, StripeSettings};
+    use crate::config::{
+        Config, OAuthProviderConfig, OAuthSettings, ProviderBaseUrls, StripeSettings,
+    };
     use crate::db::{
         mock_db::{MockDb, NoopWorkflowRepository},
         mock_stripe_event_log_repository::MockStripeEventLogRepository,
@@ -2489,6 +2491,13 @@ mod tests {
                 .await
         }
 
+        async fn list_slack_by_team(
+            &self,
+            _slack_team_id: &str,
+        ) -> Result<Vec<WorkspaceConnection>, sqlx::Error> {
+            Ok(Vec::new())
+        }
+
         async fn list_for_workspace(
             &self,
             workspace_id: Uuid,
@@ -3725,6 +3734,11 @@ mod tests {
                     client_secret: "secret".into(),
                     redirect_uri: "https://app.example.com/oauth/asana".into(),
                 },
+                github: OAuthProviderConfig {
+                    client_id: "client".into(),
+                    client_secret: "secret".into(),
+                    redirect_uri: "https://app.example.com/oauth/asana".into(),
+                },
                 token_encryption_key: vec![0; 32],
             },
             api_secrets_encryption_key: vec![1; 32],
@@ -3740,6 +3754,9 @@ mod tests {
             workspace_member_limit: crate::config::DEFAULT_WORKSPACE_MEMBER_LIMIT,
             workspace_monthly_run_limit: crate::config::DEFAULT_WORKSPACE_MONTHLY_RUN_LIMIT,
             runaway_limit_5min: crate::config::RUNAWAY_LIMIT_5MIN,
+            slack_signing_secret: None,
+            public_api_base_url: None,
+            provider_base_urls: ProviderBaseUrls::default(),
         })
     }
 
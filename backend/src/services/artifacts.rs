@@ -0,0 +1,630 @@
+//! Run artifact storage. Nodes that produce files (for example an HTTP
+//! download) write the bytes here and pass a small reference through the run
+//! context instead of inlining the content.
+//!
+//! Bytes go to a pluggable [`ArtifactStore`]: a local directory by default or
+//! any S3-compatible bucket (AWS S3, MinIO) when `ARTIFACT_STORE=s3`. Metadata
+//! is recorded in `workflow_run_artifacts` so artifacts can be listed,
+//! downloaded and attached by later nodes, and removed once retention purges
+//! the run.
+
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use crate::engine::actions::parse_flexible_value;
+use crate::engine::templating::templ_str;
+use crate::models::run_artifact::RunArtifact;
+use crate::models::workflow_run::WorkflowRun;
+use crate::state::AppState;
+use crate::utils::aws_sigv4::sign_aws_request;
+
+const DEFAULT_ARTIFACTS_DIR: &str = "dsentr-artifacts";
+const DEFAULT_S3_REGION: &str = "us-east-1";
+/// Upper bound on artifacts attached to a single message or request.
+const MAX_ATTACHMENTS: usize = 10;
+const ORPHAN_PURGE_BATCH: i64 = 500;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArtifactRef {
+    pub id: Uuid,
+    pub name: String,
+    pub mime_type: String,
+    pub size: u64,
+    pub sha256: String,
+    pub storage: String,
+    pub key: String,
+}
+
+impl From<&RunArtifact> for ArtifactRef {
+    fn from(artifact: &RunArtifact) -> Self {
+        Self {
+            id: artifact.id,
+            name: artifact.name.clone(),
+            mime_type: artifact.mime_type.clone(),
+            size: artifact.size.max(0) as u64,
+            sha256: artifact.sha256.clone(),
+            storage: artifact.storage.clone(),
+            key: artifact.key.clone(),
+        }
+    }
+}
+
+/// An artifact resolved for use by a node, with its verified bytes.
+#[derive(Debug, Clone)]
+pub struct LoadedArtifact {
+    pub name: String,
+    pub mime_type: String,
+    pub bytes: Vec<u8>,
+}
+
+#[async_trait]
+pub trait ArtifactStore: Send + Sync {
+    /// Identifier recorded with each artifact so reads go to the store that
+    /// wrote it, even after the configured store changes.
+    fn kind(&self) -> &'static str;
+
+    async fn put(&self, key: &str, mime_type: &str, bytes: &[u8]) -> Result<(), String>;
+
+    async fn get(&self, key: &str) -> Result<Vec<u8>, String>;
+
+    /// Deleting an object that is already gone is not an error.
+    async fn delete(&self, key: &str) -> Result<(), String>;
+}
+
+/// Root directory for locally stored artifacts, `ARTIFACTS_DIR` when set and
+/// a directory under the system temp dir otherwise.
+pub fn artifacts_root() -> PathBuf {
+    std::env::var("ARTIFACTS_DIR")
+        .ok()
+        .map(|dir| dir.trim().to_string())
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .unwrap_or_else(|| std::env::temp_dir().join(DEFAULT_ARTIFACTS_DIR))
+}
+
+pub struct LocalArtifactStore {
+    root: PathBuf,
+}
+
+impl LocalArtifactStore {
+    pub fn new(root: PathBuf) -> Self {
+        Self { root }
+    }
+
+    fn path(&self, key: &str) -> Result<PathBuf, String> {
+        let relative = Path::new(key);
+        if relative
+            .components()
+            .any(|c| !matches!(c, Component::Normal(_)))
+        {
+            return Err(format!("Invalid artifact key `{key}`"));
+        }
+        Ok(self.root.join(relative))
+    }
+}
+
+#[async_trait]
+impl ArtifactStore for LocalArtifactStore {
+    fn kind(&self) -> &'static str {
+        "local"
+    }
+
+    async fn put(&self, key: &str, _mime_type: &str, bytes: &[u8]) -> Result<(), String> {
+        let path = self.path(key)?;
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .map_err(|e| format!("Failed to create artifact directory: {e}"))?;
+        }
+        tokio::fs::write(&path, bytes)
+            .await
+            .map_err(|e| format!("Failed to write artifact: {e}"))
+    }
+
+    async fn get(&self, key: &str) -> Result<Vec<u8>, String> {
+        tokio::fs::read(self.path(key)?)
+            .await
+            .map_err(|e| format!("Failed to read artifact: {e}"))
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), String> {
+        let path = self.path(key)?;
+        match tokio::fs::remove_file(&path).await {
+            Ok(()) => {}
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+            Err(err) => return Err(format!("Failed to delete artifact: {err}")),
+        }
+        // Drop the now-empty `{workflow}/{run}` directories; failures just
+        // mean something else still lives there.
+        let mut dir = path.parent();
+        while let Some(current) = dir {
+            if current == self.root || tokio::fs::remove_dir(current).await.is_err() {
+                break;
+            }
+            dir = current.parent();
+        }
+        Ok(())
+    }
+}
+
+/// S3-compatible object store using path-style addressing
+/// (`{endpoint}/{bucket}/{key}`), which AWS S3 and MinIO both accept.
+pub struct S3ArtifactStore {
+    client: reqwest::Client,
+    endpoint: reqwest::Url,
+    bucket: String,
+    region: String,
+    access_key: String,
+    secret_key: String,
+}
+
+impl S3ArtifactStore {
+    pub fn new(
+        endpoint: &str,
+        bucket: &str,
+        region: &str,
+        access_key: &str,
+        secret_key: &str,
+    ) -> Result<Self, String> {
+        let endpoint = reqwest::Url::parse(endpoint.trim())
+            .map_err(|e| format!("Invalid artifact S3 endpoint: {e}"))?;
+        if !matches!(endpoint.scheme(), "http" | "https") || endpoint.host_str().is_none() {
+            return Err("Artifact S3 endpoint must be an http(s) URL".to_string());
+        }
+        let bucket = bucket.trim();
+        if bucket.is_empty() || bucket.contains('/') {
+            return Err("Artifact S3 bucket name is invalid".to_string());
+        }
+        if access_key.trim().is_empty() || secret_key.trim().is_empty() {
+            return Err("Artifact S3 credentials are required".to_string());
+        }
+        Ok(Self {
+            client: reqwest::Client::new(),
+            endpoint,
+            bucket: bucket.to_string(),
+            region: region.trim().to_string(),
+            access_key: access_key.trim().to_string(),
+            secret_key: secret_key.trim().to_string(),
+        })
+    }
+
+    /// Reads `ARTIFACTS_S3_*`; the endpoint defaults to AWS for the region.
+    fn from_env() -> Result<Self, String> {
+        let var = |name: &str| {
+            std::env::var(name)
+                .ok()
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty())
+        };
+        let region = var("ARTIFACTS_S3_REGION").unwrap_or_else(|| DEFAULT_S3_REGION.to_string());
+        let endpoint = var("ARTIFACTS_S3_ENDPOINT")
+            .unwrap_or_else(|| format!("https://s3.{region}.amazonaws.com"));
+        let bucket = var("ARTIFACTS_S3_BUCKET").ok_or_else(|| {
+            "ARTIFACTS_S3_BUCKET is required for the s3 artifact store".to_string()
+        })?;
+        Self::new(
+            &endpoint,
+            &bucket,
+            &region,
+            &var("ARTIFACTS_S3_ACCESS_KEY_ID").unwrap_or_default(),
+            &var("ARTIFACTS_S3_SECRET_ACCESS_KEY").unwrap_or_default(),
+        )
+    }
+
+    async fn send(
+        &self,
+        method: reqwest::Method,
+        key: &str,
+        body: Option<(&str, &[u8])>,
+    ) -> Result<reqwest::Response, String> {
+        let encoded_key = key
+            .split('/')
+            .map(|segment| urlencoding::encode(segment).into_owned())
+            .collect::<Vec<_>>()
+            .join("/");
+        let canonical_uri = format!(
+            "{}/{}/{}",
+            self.endpoint.path().trim_end_matches('/'),
+            urlencoding::encode(&self.bucket),
+            encoded_key
+        );
+        let mut url = self.endpoint.clone();
+        url.set_path(&canonical_uri);
+        let host = match url.port() {
+            Some(port) => format!("{}:{port}", url.host_str().unwrap_or_default()),
+            None => url.host_str().unwrap_or_default().to_string(),
+        };
+
+        let payload = body.map(|(_, bytes)| bytes).unwrap_or_default();
+        let signature = sign_aws_request(
+            &self.access_key,
+            &self.secret_key,
+            &self.region,
+            "s3",
+            method.as_str(),
+            &canonical_uri,
+            "",
+            &host,
+            payload,
+        )?;
+
+        let mut request = self
+            .client
+            .request(method, url)
+            .header("x-amz-date", signature.amz_date)
+            .header("x-amz-content-sha256", signature.payload_hash)
+            .header("authorization", signature.authorization);
+        if let Some((mime_type, bytes)) = body {
+            request = request
+                .header("content-type", mime_type)
+                .body(bytes.to_vec());
+        }
+        request
+            .send()
+            .await
+            .map_err(|e| format!("Artifact store request failed: {e}"))
+    }
+}
+
+#[async_trait]
+impl ArtifactStore for S3ArtifactStore {
+    fn kind(&self) -> &'static str {
+        "s3"
+    }
+
+    async fn put(&self, key: &str, mime_type: &str, bytes: &[u8]) -> Result<(), String> {
+        let resp = self
+            .send(reqwest::Method::PUT, key, Some((mime_type, bytes)))
+            .await?;
+        if !resp.status().is_success() {
+            return Err(format!(
+                "Failed to upload artifact (status {})",
+                resp.status().as_u16()
+            ));
+        }
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> Result<Vec<u8>, String> {
+        let resp = self.send(reqwest::Method::GET, key, None).await?;
+        if !resp.status().is_success() {
+            return Err(format!(
+                "Failed to download artifact (status {})",
+                resp.status().as_u16()
+            ));
+        }
+        resp.bytes()
+            .await
+            .map(|b| b.to_vec())
+            .map_err(|e| format!("Failed to download artifact: {e}"))
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), String> {
+        let resp = self.send(reqwest::Method::DELETE, key, None).await?;
+        if !resp.status().is_success() && resp.status() != reqwest::StatusCode::NOT_FOUND {
+            return Err(format!(
+                "Failed to delete artifact (status {})",
+                resp.status().as_u16()
+            ));
+        }
+        Ok(())
+    }
+}
+
+static S3_STORE: Lazy<Result<Arc<S3ArtifactStore>, String>> =
+    Lazy::new(|| S3ArtifactStore::from_env().map(Arc::new));
+
+/// The store new artifacts are written to, selected by `ARTIFACT_STORE`
+/// (`local`, the default, or `s3`).
+pub fn artifact_store() -> Result<Arc<dyn ArtifactStore>, String> {
+    let kind = std::env::var("ARTIFACT_STORE").unwrap_or_default();
+    store_for(match kind.trim() {
+        "" => "local",
+        other => other,
+    })
+}
+
+fn store_for(storage: &str) -> Result<Arc<dyn ArtifactStore>, String> {
+    match storage.to_ascii_lowercase().as_str() {
+        "local" => Ok(Arc::new(LocalArtifactStore::new(artifacts_root()))),
+        "s3" => match &*S3_STORE {
+            Ok(store) => Ok(store.clone() as Arc<dyn ArtifactStore>),
+            Err(err) => Err(err.clone()),
+        },
+        other => Err(format!("Unsupported artifact store `{other}`")),
+    }
+}
+
+/// Stores `bytes` under `{workflow_id}/{run_id}/{artifact_id}`, records the
+/// artifact against the run and returns the reference that nodes place in
+/// their outputs.
+pub async fn store_run_artifact(
+    state: &AppState,
+    run: &WorkflowRun,
+    node_id: &str,
+    name: &str,
+    mime_type: &str,
+    bytes: &[u8],
+) -> Result<ArtifactRef, String> {
+    store_run_artifact_in(
+        artifact_store()?.as_ref(),
+        state,
+        run,
+        node_id,
+        name,
+        mime_type,
+        bytes,
+    )
+    .await
+}
+
+async fn store_run_artifact_in(
+    store: &dyn ArtifactStore,
+    state: &AppState,
+    run: &WorkflowRun,
+    node_id: &str,
+    name: &str,
+    mime_type: &str,
+    bytes: &[u8],
+) -> Result<ArtifactRef, String> {
+    let id = Uuid::new_v4();
+    let key = format!("{}/{}/{id}", run.workflow_id, run.id);
+    store.put(&key, mime_type, bytes).await?;
+
+    let artifact = RunArtifact {
+        id,
+        workflow_id: run.workflow_id,
+        run_id: run.id,
+        node_id: node_id.to_string(),
+        name: name.to_string(),
+        mime_type: mime_type.to_string(),
+        size: bytes.len() as i64,
+        sha256: hex::encode(Sha256::digest(bytes)),
+        storage: store.kind().to_string(),
+        key,
+        created_at: OffsetDateTime::now_utc(),
+    };
+    if let Err(err) = state.workflow_repo.insert_run_artifact(&artifact).await {
+        let _ = store.delete(&artifact.key).await;
+        return Err(format!("Failed to record artifact: {err}"));
+    }
+    Ok(ArtifactRef::from(&artifact))
+}
+
+/// Reads an artifact's bytes and checks them against the recorded checksum.
+pub async fn read_artifact(artifact: &RunArtifact) -> Result<Vec<u8>, String> {
+    let bytes = store_for(&artifact.storage)?.get(&artifact.key).await?;
+    if hex::encode(Sha256::digest(&bytes)) != artifact.sha256 {
+        return Err(format!(
+            "Artifact `{}` failed its checksum verification",
+            artifact.name
+        ));
+    }
+    Ok(bytes)
+}
+
+/// Resolves artifact references from a node parameter and loads their bytes.
+///
+/// The parameter may be an artifact object (as emitted in node outputs), a
+/// list of them, an artifact id, or a templated string rendering to any of
+/// those. Artifacts are looked up by id within the run's workflow, so a
+/// reference cannot reach files produced by other workflows.
+pub async fn load_artifact_param(
+    state: &AppState,
+    run: &WorkflowRun,
+    param: Option<&Value>,
+    context: &Value,
+) -> Result<Vec<LoadedArtifact>, String> {
+    let mut ids = Vec::new();
+    if let Some(param) = param {
+        collect_artifact_ids(param, context, &mut ids)?;
+    }
+    let mut seen = std::collections::HashSet::new();
+    ids.retain(|id| seen.insert(*id));
+    if ids.len() > MAX_ATTACHMENTS {
+        return Err(format!(
+            "At most {MAX_ATTACHMENTS} artifacts can be attached"
+        ));
+    }
+
+    let mut loaded = Vec::with_capacity(ids.len());
+    for id in ids {
+        let artifact = state
+            .workflow_repo
+            .find_run_artifact(run.workflow_id, id)
+            .await
+            .map_err(|e| format!("Failed to look up artifact: {e}"))?
+            .ok_or_else(|| format!("Artifact {id} was not found for this workflow"))?;
+        let bytes = read_artifact(&artifact).await?;
+        loaded.push(LoadedArtifact {
+            name: artifact.name,
+            mime_type: artifact.mime_type,
+            bytes,
+        });
+    }
+    Ok(loaded)
+}
+
+fn collect_artifact_ids(value: &Value, context: &Value, ids: &mut Vec<Uuid>) -> Result<(), String> {
+    match value {
+        Value::Null => Ok(()),
+        Value::Array(items) => items
+            .iter()
+            .try_for_each(|item| collect_artifact_ids(item, context, ids)),
+        Value::Object(map) => {
+            let id = map
+                .get("id")
+                .and_then(Value::as_str)
+                .and_then(|raw| Uuid::parse_str(raw).ok())
+                .ok_or_else(|| "Artifact references need an `id`".to_string())?;
+            ids.push(id);
+            Ok(())
+        }
+        Value::String(raw) => match parse_flexible_value(&templ_str(raw, context)) {
+            Value::String(text) => {
+                for part in text.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+                    let id = Uuid::parse_str(part)
+                        .map_err(|_| format!("`{part}` is not an artifact reference"))?;
+                    ids.push(id);
+                }
+                Ok(())
+            }
+            Value::Null => Ok(()),
+            parsed @ (Value::Array(_) | Value::Object(_)) => {
+                collect_artifact_ids(&parsed, &Value::Null, ids)
+            }
+            other => Err(format!("`{other}` is not an artifact reference")),
+        },
+        other => Err(format!("`{other}` is not an artifact reference")),
+    }
+}
+
+/// Deletes stored objects for artifacts whose run is gone, then their
+/// metadata. Runs after `purge_old_runs`, so artifact retention follows run
+/// retention.
+pub async fn purge_orphaned_artifacts(state: &AppState) -> Result<u64, String> {
+    let orphans = state
+        .workflow_repo
+        .list_orphaned_run_artifacts(ORPHAN_PURGE_BATCH)
+        .await
+        .map_err(|e| format!("Failed to list orphaned artifacts: {e}"))?;
+    let mut purged = 0u64;
+    for artifact in orphans {
+        let store = store_for(&artifact.storage)?;
+        store.delete(&artifact.key).await?;
+        if state
+            .workflow_repo
+            .delete_run_artifact(artifact.id)
+            .await
+            .map_err(|e| format!("Failed to delete artifact record: {e}"))?
+        {
+            purged += 1;
+        }
+    }
+    Ok(purged)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use httpmock::{
+        Method::{DELETE, GET, PUT},
+        MockServer,
+    };
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn local_store_round_trips_and_rejects_escaping_keys() {
+        let root = std::env::temp_dir().join(format!("dsentr-artifacts-test-{}", Uuid::new_v4()));
+        let store = LocalArtifactStore::new(root.clone());
+
+        store
+            .put("wf/run/a", "text/plain", b"hello")
+            .await
+            .expect("write");
+        assert_eq!(store.get("wf/run/a").await.unwrap(), b"hello");
+        store.delete("wf/run/a").await.expect("delete");
+        store.delete("wf/run/a").await.expect("missing is fine");
+        assert!(!root.join("wf").exists(), "empty run dirs are removed");
+
+        assert!(store
+            .get("../etc/passwd")
+            .await
+            .unwrap_err()
+            .contains("Invalid"));
+        assert!(store.put("/tmp/x", "text/plain", b"x").await.is_err());
+        let _ = std::fs::remove_dir_all(root);
+    }
+
+    #[tokio::test]
+    async fn s3_store_signs_path_style_requests() {
+        let server = MockServer::start();
+        let put = server.mock(|when, then| {
+            when.method(PUT)
+                .path("/artifacts/wf/run/report%20v1.csv")
+                .header("content-type", "text/csv")
+                .header_exists("x-amz-date")
+                .header(
+                    "x-amz-content-sha256",
+                    hex::encode(Sha256::digest(b"a,b\n")),
+                )
+                .matches(|req| {
+                    req.headers.as_ref().is_some_and(|headers| {
+                        headers.iter().any(|(name, value)| {
+                            name == "authorization"
+                                && value.starts_with("AWS4-HMAC-SHA256 Credential=minio/")
+                                && value.contains("/us-east-1/s3/aws4_request")
+                        })
+                    })
+                })
+                .body("a,b\n");
+            then.status(200);
+        });
+        let get = server.mock(|when, then| {
+            when.method(GET).path("/artifacts/wf/run/report%20v1.csv");
+            then.status(200).body("a,b\n");
+        });
+        let delete = server.mock(|when, then| {
+            when.method(DELETE)
+                .path("/artifacts/wf/run/report%20v1.csv");
+            then.status(404);
+        });
+
+        let store = S3ArtifactStore::new(
+            &server.base_url(),
+            "artifacts",
+            "us-east-1",
+            "minio",
+            "secret",
+        )
+        .expect("store");
+        store
+            .put("wf/run/report v1.csv", "text/csv", b"a,b\n")
+            .await
+            .expect("upload");
+        assert_eq!(store.get("wf/run/report v1.csv").await.unwrap(), b"a,b\n");
+        store
+            .delete("wf/run/report v1.csv")
+            .await
+            .expect("missing objects delete cleanly");
+        put.assert();
+        get.assert();
+        delete.assert();
+    }
+
+    #[test]
+    fn artifact_references_accept_outputs_ids_and_templates() {
+        let first = Uuid::new_v4();
+        let second = Uuid::new_v4();
+        let context = json!({
+            "Download": { "artifact": { "id": first.to_string(), "name": "a.pdf" } },
+            "Export": { "files": [{ "id": second.to_string() }] }
+        });
+
+        let mut ids = Vec::new();
+        collect_artifact_ids(
+            &json!([
+                "{{Download.artifact}}",
+                "{{Export.files}}",
+                second.to_string()
+            ]),
+            &context,
+            &mut ids,
+        )
+        .expect("references resolve");
+        assert_eq!(ids, vec![first, second, second]);
+
+        let mut ids = Vec::new();
+        assert!(collect_artifact_ids(&json!("not-an-id"), &context, &mut ids).is_err());
+        assert!(collect_artifact_ids(&json!({ "name": "x" }), &context, &mut ids).is_err());
+    }
+}
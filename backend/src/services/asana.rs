@@ -0,0 +1,384 @@
+//! Asana REST calls used by the Asana triggers: webhook subscription
+//! management plus the task/story lookups that enrich webhook events and
+//! drive the polling fallback. Every function takes the API base URL so the
+//! worker and tests can point at a stub server.
+
+use http::StatusCode;
+use reqwest::{Client, Method};
+use serde_json::{json, Value};
+use thiserror::Error;
+
+pub const ASANA_NEW_TASK: &str = "asana.new_task";
+pub const ASANA_TASK_COMPLETED: &str = "asana.task_completed";
+pub const ASANA_COMMENT_ADDED: &str = "asana.comment_added";
+
+const TASK_OPT_FIELDS: &str = "name,notes,completed,completed_at,created_at,modified_at,due_on,due_at,assignee.gid,assignee.name,assignee.email,permalink_url,memberships.project.gid";
+const STORY_OPT_FIELDS: &str =
+    "created_at,resource_subtype,text,created_by.gid,created_by.name,target.gid";
+const PAGE_LIMIT: u32 = 100;
+
+#[derive(Debug, Error)]
+pub enum AsanaError {
+    #[error("Asana API request failed: {0}")]
+    Http(#[from] reqwest::Error),
+    #[error("Asana API responded with status {status}: {message}")]
+    Api { status: StatusCode, message: String },
+    #[error("Asana API returned an invalid response: {0}")]
+    InvalidResponse(String),
+}
+
+impl AsanaError {
+    pub fn is_not_found(&self) -> bool {
+        matches!(self, AsanaError::Api { status, .. } if *status == StatusCode::NOT_FOUND)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AsanaTriggerKind {
+    NewTask,
+    TaskCompleted,
+    CommentAdded,
+}
+
+impl AsanaTriggerKind {
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            ASANA_NEW_TASK => Some(Self::NewTask),
+            ASANA_TASK_COMPLETED => Some(Self::TaskCompleted),
+            ASANA_COMMENT_ADDED => Some(Self::CommentAdded),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::NewTask => ASANA_NEW_TASK,
+            Self::TaskCompleted => ASANA_TASK_COMPLETED,
+            Self::CommentAdded => ASANA_COMMENT_ADDED,
+        }
+    }
+
+    /// Webhook filters so Asana only delivers the events this trigger cares
+    /// about for the subscribed project.
+    pub fn webhook_filters(&self) -> Vec<Value> {
+        match self {
+            Self::NewTask => vec![json!({ "resource_type": "task", "action": "added" })],
+            Self::TaskCompleted => vec![json!({
+                "resource_type": "task",
+                "action": "changed",
+                "fields": ["completed"],
+            })],
+            Self::CommentAdded => vec![json!({
+                "resource_type": "story",
+                "resource_subtype": "comment_added",
+                "action": "added",
+            })],
+        }
+    }
+}
+
+fn build_url(base: &str, path: &str) -> String {
+    format!(
+        "{}/{}",
+        base.trim_end_matches('/'),
+        path.trim_start_matches('/')
+    )
+}
+
+async fn asana_request(
+    client: &Client,
+    method: Method,
+    base_url: &str,
+    path: &str,
+    access_token: &str,
+    query: &[(&str, &str)],
+    body: Option<&Value>,
+) -> Result<Value, AsanaError> {
+    let mut request = client
+        .request(method, build_url(base_url, path))
+        .bearer_auth(access_token)
+        .header(reqwest::header::ACCEPT, "application/json")
+        .query(query);
+    if let Some(body) = body {
+        request = request.json(body);
+    }
+    let response = request.send().await?;
+    let status = response.status();
+    let text = response.text().await.unwrap_or_default();
+
+    if !status.is_success() {
+        let message = serde_json::from_str::<Value>(&text)
+            .ok()
+            .and_then(|value| {
+                value
+                    .get("errors")?
+                    .as_array()?
+                    .first()?
+                    .get("message")?
+                    .as_str()
+                    .map(str::to_string)
+            })
+            .unwrap_or_else(|| {
+                let trimmed = text.trim();
+                if trimmed.is_empty() {
+                    "Asana API request failed".to_string()
+                } else {
+                    trimmed.to_string()
+                }
+            });
+        let status =
+            StatusCode::from_u16(status.as_u16()).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+        return Err(AsanaError::Api { status, message });
+    }
+
+    if text.trim().is_empty() {
+        return Ok(Value::Null);
+    }
+    let mut parsed: Value =
+        serde_json::from_str(&text).map_err(|err| AsanaError::InvalidResponse(err.to_string()))?;
+    Ok(parsed
+        .get_mut("data")
+        .map(Value::take)
+        .unwrap_or(Value::Null))
+}
+
+/// Creates a webhook on `resource` delivering to `target`. Asana performs the
+/// `X-Hook-Secret` handshake against `target` before this call returns.
+pub async fn create_webhook(
+    client: &Client,
+    base_url: &str,
+    access_token: &str,
+    resource: &str,
+    target: &str,
+    filters: Vec<Value>,
+) -> Result<String, AsanaError> {
+    let body = json!({
+        "data": {
+            "resource": resource,
+            "target": target,
+            "filters": filters,
+        }
+    });
+    let data = asana_request(
+        client,
+        Method::POST,
+        base_url,
+        "/webhooks",
+        access_token,
+        &[],
+        Some(&body),
+    )
+    .await?;
+    data.get("gid")
+        .and_then(|gid| gid.as_str())
+        .map(str::to_string)
+        .ok_or_else(|| AsanaError::InvalidResponse("webhook response is missing a gid".into()))
+}
+
+pub async fn delete_webhook(
+    client: &Client,
+    base_url: &str,
+    access_token: &str,
+    webhook_gid: &str,
+) -> Result<(), AsanaError> {
+    let path = format!("/webhooks/{}", urlencoding::encode(webhook_gid));
+    asana_request(
+        client,
+        Method::DELETE,
+        base_url,
+        &path,
+        access_token,
+        &[],
+        None,
+    )
+    .await?;
+    Ok(())
+}
+
+pub async fn get_task(
+    client: &Client,
+    base_url: &str,
+    access_token: &str,
+    task_gid: &str,
+) -> Result<Value, AsanaError> {
+    let path = format!("/tasks/{}", urlencoding::encode(task_gid));
+    asana_request(
+        client,
+        Method::GET,
+        base_url,
+        &path,
+        access_token,
+        &[("opt_fields", TASK_OPT_FIELDS)],
+        None,
+    )
+    .await
+}
+
+pub async fn get_story(
+    client: &Client,
+    base_url: &str,
+    access_token: &str,
+    story_gid: &str,
+) -> Result<Value, AsanaError> {
+    let path = format!("/stories/{}", urlencoding::encode(story_gid));
+    asana_request(
+        client,
+        Method::GET,
+        base_url,
+        &path,
+        access_token,
+        &[("opt_fields", STORY_OPT_FIELDS)],
+        None,
+    )
+    .await
+}
+
+/// Tasks of `project_gid` created or changed after `modified_since`
+/// (RFC 3339); the basis of the polling fallback.
+pub async fn list_tasks_modified_since(
+    client: &Client,
+    base_url: &str,
+    access_token: &str,
+    project_gid: &str,
+    modified_since: &str,
+) -> Result<Vec<Value>, AsanaError> {
+    let limit = PAGE_LIMIT.to_string();
+    let data = asana_request(
+        client,
+        Method::GET,
+        base_url,
+        "/tasks",
+        access_token,
+        &[
+            ("project", project_gid),
+            ("modified_since", modified_since),
+            ("opt_fields", TASK_OPT_FIELDS),
+            ("limit", &limit),
+        ],
+        None,
+    )
+    .await?;
+    Ok(into_list(data))
+}
+
+pub async fn list_task_stories(
+    client: &Client,
+    base_url: &str,
+    access_token: &str,
+    task_gid: &str,
+) -> Result<Vec<Value>, AsanaError> {
+    let path = format!("/tasks/{}/stories", urlencoding::encode(task_gid));
+    let limit = PAGE_LIMIT.to_string();
+    let data = asana_request(
+        client,
+        Method::GET,
+        base_url,
+        &path,
+        access_token,
+        &[("opt_fields", STORY_OPT_FIELDS), ("limit", &limit)],
+        None,
+    )
+    .await?;
+    Ok(into_list(data))
+}
+
+fn into_list(data: Value) -> Vec<Value> {
+    match data {
+        Value::Array(items) => items,
+        _ => Vec::new(),
+    }
+}
+
+/// Trigger payload shared by webhook deliveries and polling so downstream
+/// nodes see the same shape either way.
+pub fn build_trigger_event(
+    kind: AsanaTriggerKind,
+    project_gid: &str,
+    task: &Value,
+    story: Option<&Value>,
+    source: &str,
+) -> Value {
+    let mut event = json!({
+        "trigger": kind.as_str(),
+        "source": source,
+        "projectGid": project_gid,
+        "taskGid": task.get("gid").cloned().unwrap_or(Value::Null),
+        "taskName": task.get("name").cloned().unwrap_or(Value::Null),
+        "completed": task.get("completed").cloned().unwrap_or(Value::Null),
+        "assignee": task
+            .get("assignee")
+            .and_then(|a| a.get("name"))
+            .cloned()
+            .unwrap_or(Value::Null),
+        "permalinkUrl": task.get("permalink_url").cloned().unwrap_or(Value::Null),
+        "task": task,
+    });
+    if let Some(story) = story {
+        event["commentGid"] = story.get("gid").cloned().unwrap_or(Value::Null);
+        event["commentText"] = story.get("text").cloned().unwrap_or(Value::Null);
+        event["commentAuthor"] = story
+            .get("created_by")
+            .and_then(|a| a.get("name"))
+            .cloned()
+            .unwrap_or(Value::Null);
+        event["comment"] = story.clone();
+    }
+    event
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn create_webhook_posts_filters_and_returns_gid() {
+        let server = httpmock::MockServer::start();
+        let mock = server.mock(|when, then| {
+            when.method(httpmock::Method::POST)
+                .path("/webhooks")
+                .header("authorization", "Bearer token")
+                .json_body(json!({
+                    "data": {
+                        "resource": "proj-1",
+                        "target": "https://hooks.example/api/asana/webhooks/1",
+                        "filters": [{ "resource_type": "task", "action": "added" }]
+                    }
+                }));
+            then.status(201)
+                .header("content-type", "application/json")
+                .body(json!({ "data": { "gid": "wh-1", "active": true } }).to_string());
+        });
+
+        let gid = create_webhook(
+            &Client::new(),
+            &server.url(""),
+            "token",
+            "proj-1",
+            "https://hooks.example/api/asana/webhooks/1",
+            AsanaTriggerKind::NewTask.webhook_filters(),
+        )
+        .await
+        .expect("create webhook");
+
+        mock.assert();
+        assert_eq!(gid, "wh-1");
+    }
+
+    #[tokio::test]
+    async fn api_errors_surface_first_error_message() {
+        let server = httpmock::MockServer::start();
+        server.mock(|when, then| {
+            when.method(httpmock::Method::DELETE).path("/webhooks/wh-9");
+            then.status(404)
+                .header("content-type", "application/json")
+                .body(json!({ "errors": [{ "message": "webhook: Unknown object" }] }).to_string());
+        });
+
+        let err = delete_webhook(&Client::new(), &server.url(""), "token", "wh-9")
+            .await
+            .expect_err("missing webhook");
+
+        assert!(err.is_not_found());
+        assert!(err.to_string().contains("webhook: Unknown object"));
+    }
+}
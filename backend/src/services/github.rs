@@ -0,0 +1,295 @@
+//! GitHub REST calls used by the GitHub action node, plus `X-Hub-Signature-256`
+//! verification for the `github.webhook` trigger. Every function takes the API
+//! base URL so GitHub Enterprise hosts and tests can point elsewhere.
+
+use hmac::{Hmac, Mac};
+use http::StatusCode;
+use reqwest::{Client, Method};
+use serde_json::Value;
+use sha2::Sha256;
+use subtle::ConstantTimeEq;
+use thiserror::Error;
+
+pub const GITHUB_WEBHOOK: &str = "github.webhook";
+pub const SIGNATURE_HEADER: &str = "X-Hub-Signature-256";
+pub const EVENT_HEADER: &str = "X-GitHub-Event";
+pub const DELIVERY_HEADER: &str = "X-GitHub-Delivery";
+
+const API_VERSION: &str = "2022-11-28";
+const USER_AGENT: &str = "dsentr";
+
+type HmacSha256 = Hmac<Sha256>;
+
+#[derive(Debug, Error)]
+pub enum GitHubError {
+    #[error("GitHub API request failed: {0}")]
+    Http(#[from] reqwest::Error),
+    #[error("GitHub API responded with status {status}: {message}")]
+    Api { status: StatusCode, message: String },
+    #[error("GitHub API returned an invalid response: {0}")]
+    InvalidResponse(String),
+    #[error("{0}")]
+    InvalidInput(String),
+}
+
+/// Splits `owner/repo` and rejects anything that would change the request path.
+pub fn parse_repository(raw: &str) -> Result<(String, String), GitHubError> {
+    let invalid = || {
+        GitHubError::InvalidInput(format!(
+            "Repository must look like `owner/repo`, got `{}`",
+            raw.trim()
+        ))
+    };
+    let (owner, repo) = raw.trim().split_once('/').ok_or_else(invalid)?;
+    let valid = |part: &str| {
+        !part.is_empty()
+            && part != "."
+            && part != ".."
+            && part
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+    };
+    if !valid(owner) || !valid(repo) {
+        return Err(invalid());
+    }
+    Ok((owner.to_string(), repo.to_string()))
+}
+
+fn repo_path(repository: &str, suffix: &str) -> Result<String, GitHubError> {
+    let (owner, repo) = parse_repository(repository)?;
+    Ok(format!("/repos/{owner}/{repo}{suffix}"))
+}
+
+async fn github_request(
+    client: &Client,
+    method: Method,
+    base_url: &str,
+    path: &str,
+    access_token: &str,
+    body: &Value,
+) -> Result<Value, GitHubError> {
+    let url = format!("{}{}", base_url.trim_end_matches('/'), path);
+    let response = client
+        .request(method, url)
+        .bearer_auth(access_token)
+        .header(reqwest::header::ACCEPT, "application/vnd.github+json")
+        .header(reqwest::header::USER_AGENT, USER_AGENT)
+        .header("X-GitHub-Api-Version", API_VERSION)
+        .json(body)
+        .send()
+        .await?;
+    let status = response.status();
+    let text = response.text().await.unwrap_or_default();
+
+    if !status.is_success() {
+        let message = serde_json::from_str::<Value>(&text)
+            .ok()
+            .and_then(|value| {
+                let message = value.get("message")?.as_str()?.to_string();
+                let details: Vec<String> = value
+                    .get("errors")
+                    .and_then(|v| v.as_array())
+                    .map(|errors| {
+                        errors
+                            .iter()
+                            .filter_map(|e| {
+                                e.get("message")
+                                    .or_else(|| e.get("code"))
+                                    .and_then(|v| v.as_str())
+                                    .map(str::to_string)
+                            })
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                Some(if details.is_empty() {
+                    message
+                } else {
+                    format!("{message} ({})", details.join(", "))
+                })
+            })
+            .unwrap_or_else(|| {
+                let trimmed = text.trim();
+                if trimmed.is_empty() {
+                    "GitHub API request failed".to_string()
+                } else {
+                    trimmed.to_string()
+                }
+            });
+        let status =
+            StatusCode::from_u16(status.as_u16()).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+        return Err(GitHubError::Api { status, message });
+    }
+
+    if text.trim().is_empty() {
+        return Ok(Value::Null);
+    }
+    serde_json::from_str(&text).map_err(|err| GitHubError::InvalidResponse(err.to_string()))
+}
+
+pub async fn create_issue(
+    client: &Client,
+    base_url: &str,
+    access_token: &str,
+    repository: &str,
+    body: &Value,
+) -> Result<Value, GitHubError> {
+    let path = repo_path(repository, "/issues")?;
+    github_request(client, Method::POST, base_url, &path, access_token, body).await
+}
+
+pub async fn create_issue_comment(
+    client: &Client,
+    base_url: &str,
+    access_token: &str,
+    repository: &str,
+    issue_number: u64,
+    body: &Value,
+) -> Result<Value, GitHubError> {
+    let path = repo_path(repository, &format!("/issues/{issue_number}/comments"))?;
+    github_request(client, Method::POST, base_url, &path, access_token, body).await
+}
+
+/// Adds labels to an issue or pull request and returns all of its labels.
+pub async fn add_labels(
+    client: &Client,
+    base_url: &str,
+    access_token: &str,
+    repository: &str,
+    issue_number: u64,
+    body: &Value,
+) -> Result<Value, GitHubError> {
+    let path = repo_path(repository, &format!("/issues/{issue_number}/labels"))?;
+    github_request(client, Method::POST, base_url, &path, access_token, body).await
+}
+
+pub async fn create_release(
+    client: &Client,
+    base_url: &str,
+    access_token: &str,
+    repository: &str,
+    body: &Value,
+) -> Result<Value, GitHubError> {
+    let path = repo_path(repository, "/releases")?;
+    github_request(client, Method::POST, base_url, &path, access_token, body).await
+}
+
+/// Starts a `workflow_dispatch` run. `workflow` is a workflow file name
+/// (`ci.yml`) or numeric id. GitHub answers 204 without a run id.
+pub async fn dispatch_workflow(
+    client: &Client,
+    base_url: &str,
+    access_token: &str,
+    repository: &str,
+    workflow: &str,
+    body: &Value,
+) -> Result<(), GitHubError> {
+    let workflow = workflow.trim();
+    if workflow.is_empty() || workflow.contains('/') {
+        return Err(GitHubError::InvalidInput(
+            "Workflow must be a workflow file name or id".to_string(),
+        ));
+    }
+    let path = repo_path(
+        repository,
+        &format!(
+            "/actions/workflows/{}/dispatches",
+            urlencoding::encode(workflow)
+        ),
+    )?;
+    github_request(client, Method::POST, base_url, &path, access_token, body).await?;
+    Ok(())
+}
+
+/// `X-Hub-Signature-256` is `sha256=` plus the hex HMAC-SHA256 of the raw
+/// body keyed with the webhook secret.
+pub fn verify_signature(secret: &str, header: &str, body: &[u8]) -> bool {
+    let Some(signature) = header.trim().strip_prefix("sha256=") else {
+        return false;
+    };
+    if signature.is_empty() {
+        return false;
+    }
+    let mut mac = HmacSha256::new_from_slice(secret.as_bytes()).expect("HMAC");
+    mac.update(body);
+    let expected = hex::encode(mac.finalize().into_bytes());
+    expected
+        .as_bytes()
+        .ct_eq(signature.to_ascii_lowercase().as_bytes())
+        .unwrap_u8()
+        == 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn repositories_must_be_owner_and_name() {
+        assert_eq!(
+            parse_repository(" octo-org/hello.world ").unwrap(),
+            ("octo-org".to_string(), "hello.world".to_string())
+        );
+        for bad in [
+            "octo",
+            "octo/",
+            "/repo",
+            "octo/repo/issues",
+            "../repo",
+            "octo/re po",
+        ] {
+            assert!(parse_repository(bad).is_err(), "{bad} should be rejected");
+        }
+    }
+
+    #[test]
+    fn signatures_use_the_sha256_prefix() {
+        let body = br#"{"zen":"Keep it logically awesome."}"#;
+        let mut mac = HmacSha256::new_from_slice(b"secret").unwrap();
+        mac.update(body);
+        let hex = hex::encode(mac.finalize().into_bytes());
+
+        assert!(verify_signature("secret", &format!("sha256={hex}"), body));
+        assert!(!verify_signature("secret", &hex, body));
+        assert!(!verify_signature("secret", &format!("sha256={hex}"), b"{}"));
+        assert!(!verify_signature("other", &format!("sha256={hex}"), body));
+        assert!(!verify_signature("secret", "sha256=", body));
+    }
+
+    #[tokio::test]
+    async fn api_errors_include_validation_details() {
+        let server = httpmock::MockServer::start();
+        let mock = server.mock(|when, then| {
+            when.method(httpmock::Method::POST)
+                .path("/repos/o/r/releases")
+                .header("authorization", "Bearer token")
+                .header("x-github-api-version", API_VERSION);
+            then.status(422)
+                .header("content-type", "application/json")
+                .body(
+                    json!({
+                        "message": "Validation Failed",
+                        "errors": [{ "resource": "Release", "code": "already_exists", "field": "tag_name" }]
+                    })
+                    .to_string(),
+                );
+        });
+
+        let err = create_release(
+            &Client::new(),
+            &server.url(""),
+            "token",
+            "o/r",
+            &json!({ "tag_name": "v1" }),
+        )
+        .await
+        .expect_err("duplicate release");
+
+        mock.assert();
+        assert!(
+            err.to_string()
+                .contains("Validation Failed (already_exists)"),
+            "unexpected error: {err}"
+        );
+    }
+}
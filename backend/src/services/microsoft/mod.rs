@@ -1,11 +1,11 @@
+pub mod outlook;
+
 use http::StatusCode;
 use reqwest::Client;
 use serde::de::DeserializeOwned;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
-const GRAPH_BASE_URL: &str = "https://graph.microsoft.com/v1.0";
-
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct MicrosoftTeam {
     pub id: String,
@@ -27,6 +27,28 @@ pub struct MicrosoftChannelMember {
     pub email: Option<String>,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MicrosoftChannelMessage {
+    pub id: String,
+    pub reply_to_id: Option<String>,
+    pub message_type: Option<String>,
+    pub created_date_time: Option<String>,
+    pub last_modified_date_time: Option<String>,
+    pub subject: Option<String>,
+    pub body_content_type: Option<String>,
+    pub body_content: String,
+    pub from_user_id: Option<String>,
+    pub from_display_name: Option<String>,
+    pub web_url: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MicrosoftChannelMessagePage {
+    pub messages: Vec<MicrosoftChannelMessage>,
+    pub next_link: Option<String>,
+}
+
 #[derive(Debug, Error)]
 pub enum MicrosoftGraphError {
     #[error("failed to perform Microsoft Graph request: {0}")]
@@ -37,31 +59,10 @@ pub enum MicrosoftGraphError {
     InvalidResponse(String),
 }
 
-pub async fn fetch_joined_teams(
-    client: &Client,
-    access_token: &str,
-) -> Result<Vec<MicrosoftTeam>, MicrosoftGraphError> {
-    fetch_joined_teams_with_base(client, GRAPH_BASE_URL, access_token).await
-}
-
-pub async fn fetch_team_channels(
-    client: &Client,
-    access_token: &str,
-    team_id: &str,
-) -> Result<Vec<MicrosoftChannel>, MicrosoftGraphError> {
-    fetch_team_channels_with_base(client, GRAPH_BASE_URL, access_token, team_id).await
-}
-
-pub async fn fetch_channel_members(
-    client: &Client,
-    access_token: &str,
-    team_id: &str,
-    channel_id: &str,
-) -> Result<Vec<MicrosoftChannelMember>, MicrosoftGraphError> {
-    fetch_channel_members_with_base(client, GRAPH_BASE_URL, access_token, team_id, channel_id).await
-}
-
 fn build_url(base: &str, path: &str) -> String {
+    if path.starts_with("https://") || path.starts_with("http://") {
+        return path.to_string();
+    }
     let trimmed_base = base.trim_end_matches('/');
     if path.is_empty() {
         trimmed_base.to_string()
@@ -106,6 +107,8 @@ async fn graph_get<T: DeserializeOwned>(
 struct GraphListResponse<T> {
     #[serde(default)]
     value: Vec<T>,
+    #[serde(rename = "@odata.nextLink", default)]
+    next_link: Option<String>,
 }
 
 #[derive(Default, Deserialize)]
@@ -132,6 +135,40 @@ struct RawChannelMember {
     email: Option<String>,
 }
 
+#[derive(Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawMessageBody {
+    content_type: Option<String>,
+    content: Option<String>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawMessageUser {
+    id: Option<String>,
+    display_name: Option<String>,
+}
+
+#[derive(Default, Deserialize)]
+struct RawMessageFrom {
+    user: Option<RawMessageUser>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawChannelMessage {
+    id: Option<String>,
+    reply_to_id: Option<String>,
+    message_type: Option<String>,
+    created_date_time: Option<String>,
+    last_modified_date_time: Option<String>,
+    deleted_date_time: Option<String>,
+    subject: Option<String>,
+    body: Option<RawMessageBody>,
+    from: Option<RawMessageFrom>,
+    web_url: Option<String>,
+}
+
 fn extract_error_message(body: &str) -> String {
     #[derive(Deserialize)]
     struct GraphErrorBody {
@@ -159,7 +196,7 @@ fn extract_error_message(body: &str) -> String {
     }
 }
 
-async fn fetch_joined_teams_with_base(
+pub(crate) async fn fetch_joined_teams(
     client: &Client,
     base_url: &str,
     access_token: &str,
@@ -198,7 +235,7 @@ async fn fetch_joined_teams_with_base(
     Ok(teams)
 }
 
-async fn fetch_team_channels_with_base(
+pub(crate) async fn fetch_team_channels(
     client: &Client,
     base_url: &str,
     access_token: &str,
@@ -249,7 +286,7 @@ async fn fetch_team_channels_with_base(
     Ok(channels)
 }
 
-async fn fetch_channel_members_with_base(
+pub(crate) async fn fetch_channel_members(
     client: &Client,
     base_url: &str,
     access_token: &str,
@@ -317,6 +354,110 @@ async fn fetch_channel_members_with_base(
     Ok(members)
 }
 
+/// Lists the most recent root messages of a channel. Deleted messages and
+/// system events are skipped; Graph caps `top` at 50.
+pub(crate) async fn fetch_channel_messages(
+    client: &Client,
+    base_url: &str,
+    access_token: &str,
+    team_id: &str,
+    channel_id: &str,
+    top: u32,
+) -> Result<Vec<MicrosoftChannelMessage>, MicrosoftGraphError> {
+    fetch_channel_messages_page(
+        client,
+        base_url,
+        access_token,
+        team_id,
+        channel_id,
+        top,
+        None,
+    )
+    .await
+    .map(|page| page.messages)
+}
+
+/// Fetches one page of root messages, starting from `next_link` (a previous
+/// page's `@odata.nextLink`) when given. The link must point back at
+/// `base_url` so the access token is never sent elsewhere.
+pub(crate) async fn fetch_channel_messages_page(
+    client: &Client,
+    base_url: &str,
+    access_token: &str,
+    team_id: &str,
+    channel_id: &str,
+    top: u32,
+    next_link: Option<&str>,
+) -> Result<MicrosoftChannelMessagePage, MicrosoftGraphError> {
+    let path = match next_link {
+        Some(link) => {
+            if !link.starts_with(base_url.trim_end_matches('/')) {
+                return Err(MicrosoftGraphError::InvalidResponse(
+                    "next page link points outside Microsoft Graph".into(),
+                ));
+            }
+            link.to_string()
+        }
+        None => format!(
+            "/teams/{}/channels/{}/messages?$top={}",
+            urlencoding::encode(team_id),
+            urlencoding::encode(channel_id),
+            top.clamp(1, 50)
+        ),
+    };
+    let response: GraphListResponse<RawChannelMessage> =
+        graph_get(client, base_url, &path, access_token).await?;
+
+    let non_empty = |value: Option<String>| {
+        value.and_then(|raw| {
+            let trimmed = raw.trim();
+            if trimmed.is_empty() {
+                None
+            } else {
+                Some(trimmed.to_string())
+            }
+        })
+    };
+
+    let messages = response
+        .value
+        .into_iter()
+        .filter_map(|message| {
+            let id = non_empty(message.id)?;
+            if message.deleted_date_time.is_some() {
+                return None;
+            }
+            let message_type = non_empty(message.message_type);
+            if message_type
+                .as_deref()
+                .is_some_and(|kind| !kind.eq_ignore_ascii_case("message"))
+            {
+                return None;
+            }
+            let body = message.body.unwrap_or_default();
+            let user = message.from.and_then(|from| from.user).unwrap_or_default();
+            Some(MicrosoftChannelMessage {
+                id,
+                reply_to_id: non_empty(message.reply_to_id),
+                message_type,
+                created_date_time: non_empty(message.created_date_time),
+                last_modified_date_time: non_empty(message.last_modified_date_time),
+                subject: non_empty(message.subject),
+                body_content_type: non_empty(body.content_type),
+                body_content: body.content.unwrap_or_default(),
+                from_user_id: non_empty(user.id),
+                from_display_name: non_empty(user.display_name),
+                web_url: non_empty(message.web_url),
+            })
+        })
+        .collect();
+
+    Ok(MicrosoftChannelMessagePage {
+        messages,
+        next_link: response.next_link,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -354,7 +495,7 @@ mod tests {
                 .body(body.to_string());
         });
 
-        let teams = fetch_joined_teams_with_base(&client, &server.url(""), "token")
+        let teams = fetch_joined_teams(&client, &server.url(""), "token")
             .await
             .expect("teams");
 
@@ -392,8 +533,7 @@ mod tests {
                 );
         });
 
-        let result =
-            fetch_team_channels_with_base(&client, &server.url(""), "token", "team-1").await;
+        let result = fetch_team_channels(&client, &server.url(""), "token", "team-1").await;
 
         mock.assert();
         match result {
@@ -425,17 +565,59 @@ mod tests {
                 .body("{\"value\": []}");
         });
 
-        let members = fetch_channel_members_with_base(
+        let members =
+            fetch_channel_members(&client, &server.url(""), "token", "team-1", "channel-1")
+                .await
+                .expect("members fetch");
+
+        mock.assert();
+        assert!(members.is_empty());
+    }
+
+    #[tokio::test]
+    async fn fetch_channel_messages_skips_deleted_and_system_messages() {
+        let client = CLIENT.lock().await;
+        let server = httpmock::MockServer::start();
+        let mock = server.mock(|when, then| {
+            when.method(httpmock::Method::GET)
+                .path("/teams/team-1/channels/channel-1/messages")
+                .query_param("$top", "50");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(
+                    serde_json::json!({
+                        "value": [
+                            {
+                                "id": "m1",
+                                "messageType": "message",
+                                "createdDateTime": "2024-05-01T10:00:00Z",
+                                "body": { "contentType": "html", "content": "<p>Hello</p>" },
+                                "from": { "user": { "id": "u1", "displayName": "Ada" } },
+                                "webUrl": "https://teams.example/m1"
+                            },
+                            { "id": "m2", "messageType": "message", "deletedDateTime": "2024-05-01T11:00:00Z" },
+                            { "id": "m3", "messageType": "systemEventMessage" }
+                        ]
+                    })
+                    .to_string(),
+                );
+        });
+
+        let messages = fetch_channel_messages(
             &client,
             &server.url(""),
             "token",
             "team-1",
             "channel-1",
+            200,
         )
         .await
-        .expect("members fetch");
+        .expect("messages fetch");
 
         mock.assert();
-        assert!(members.is_empty());
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].id, "m1");
+        assert_eq!(messages[0].body_content, "<p>Hello</p>");
+        assert_eq!(messages[0].from_display_name.as_deref(), Some("Ada"));
     }
 }
@@ -0,0 +1,298 @@
+//! Outlook mail and calendar calls on Microsoft Graph (`/me/...`), sharing the
+//! Teams helpers' error handling. Every function takes the Graph base URL so
+//! background pollers and tests can point at a stub server.
+
+use http::StatusCode;
+use reqwest::{Client, Method};
+use serde_json::{json, Value};
+
+use super::{build_url, extract_error_message, MicrosoftGraphError};
+
+pub const MAX_PAGE_SIZE: u32 = 50;
+
+const MESSAGE_SELECT: &str =
+    "id,subject,bodyPreview,from,toRecipients,ccRecipients,receivedDateTime,webLink,conversationId,hasAttachments,isRead";
+const EVENT_SELECT: &str =
+    "id,subject,bodyPreview,start,end,location,attendees,organizer,isOnlineMeeting,onlineMeeting,webLink,createdDateTime,lastModifiedDateTime";
+
+async fn graph_request(
+    client: &Client,
+    method: Method,
+    base_url: &str,
+    path: &str,
+    access_token: &str,
+    body: Option<&Value>,
+) -> Result<Option<Value>, MicrosoftGraphError> {
+    let url = build_url(base_url, path);
+    let mut request = client
+        .request(method, url)
+        .bearer_auth(access_token)
+        .header(reqwest::header::ACCEPT, "application/json");
+    if let Some(body) = body {
+        request = request.json(body);
+    }
+    let response = request.send().await?;
+
+    let status = response.status();
+    let text = response.text().await.unwrap_or_default();
+    if !status.is_success() {
+        let status =
+            StatusCode::from_u16(status.as_u16()).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+        return Err(MicrosoftGraphError::UnexpectedStatus {
+            status,
+            message: extract_error_message(&text),
+        });
+    }
+
+    if text.trim().is_empty() {
+        return Ok(None);
+    }
+    serde_json::from_str(&text)
+        .map(Some)
+        .map_err(|err| MicrosoftGraphError::InvalidResponse(err.to_string()))
+}
+
+fn list_values(response: Option<Value>) -> Vec<Value> {
+    response
+        .and_then(|mut value| value.get_mut("value").map(Value::take))
+        .and_then(|value| match value {
+            Value::Array(items) => Some(items),
+            _ => None,
+        })
+        .unwrap_or_default()
+}
+
+/// One page of a Graph collection plus its `@odata.nextLink`, if any.
+#[derive(Debug, Default)]
+pub struct GraphPage {
+    pub items: Vec<Value>,
+    pub next_link: Option<String>,
+}
+
+fn list_page(response: Option<Value>) -> GraphPage {
+    let next_link = response
+        .as_ref()
+        .and_then(|value| value.get("@odata.nextLink"))
+        .and_then(Value::as_str)
+        .map(str::to_string);
+    GraphPage {
+        items: list_values(response),
+        next_link,
+    }
+}
+
+fn expect_object(response: Option<Value>) -> Result<Value, MicrosoftGraphError> {
+    response
+        .filter(|value| value.is_object())
+        .ok_or_else(|| MicrosoftGraphError::InvalidResponse("expected a JSON object".into()))
+}
+
+/// Sends `message` (a Graph `message` resource) from the signed-in mailbox.
+/// Graph answers 202 with no body, so there is nothing to return.
+pub async fn send_mail(
+    client: &Client,
+    base_url: &str,
+    access_token: &str,
+    message: Value,
+    save_to_sent_items: bool,
+) -> Result<(), MicrosoftGraphError> {
+    let payload = json!({
+        "message": message,
+        "saveToSentItems": save_to_sent_items,
+    });
+    graph_request(
+        client,
+        Method::POST,
+        base_url,
+        "/me/sendMail",
+        access_token,
+        Some(&payload),
+    )
+    .await?;
+    Ok(())
+}
+
+pub async fn create_event(
+    client: &Client,
+    base_url: &str,
+    access_token: &str,
+    calendar_id: Option<&str>,
+    event: &Value,
+) -> Result<Value, MicrosoftGraphError> {
+    let path = match calendar_id {
+        Some(id) => format!("/me/calendars/{}/events", urlencoding::encode(id)),
+        None => "/me/events".to_string(),
+    };
+    let response = graph_request(
+        client,
+        Method::POST,
+        base_url,
+        &path,
+        access_token,
+        Some(event),
+    )
+    .await?;
+    expect_object(response)
+}
+
+pub async fn update_event(
+    client: &Client,
+    base_url: &str,
+    access_token: &str,
+    event_id: &str,
+    patch: &Value,
+) -> Result<Value, MicrosoftGraphError> {
+    let path = format!("/me/events/{}", urlencoding::encode(event_id));
+    let response = graph_request(
+        client,
+        Method::PATCH,
+        base_url,
+        &path,
+        access_token,
+        Some(patch),
+    )
+    .await?;
+    expect_object(response)
+}
+
+/// Expands recurring events between `start` and `end` (RFC 3339), ordered by
+/// start time.
+pub async fn list_calendar_view(
+    client: &Client,
+    base_url: &str,
+    access_token: &str,
+    start: &str,
+    end: &str,
+    top: u32,
+) -> Result<Vec<Value>, MicrosoftGraphError> {
+    let path = format!(
+        "/me/calendarView?startDateTime={}&endDateTime={}&$orderby=start/dateTime&$top={}&$select={}",
+        urlencoding::encode(start),
+        urlencoding::encode(end),
+        top.clamp(1, MAX_PAGE_SIZE),
+        EVENT_SELECT
+    );
+    let response = graph_request(client, Method::GET, base_url, &path, access_token, None).await?;
+    Ok(list_values(response))
+}
+
+/// Most recently created events first; used by the new-event trigger.
+pub async fn list_recent_events(
+    client: &Client,
+    base_url: &str,
+    access_token: &str,
+    top: u32,
+) -> Result<GraphPage, MicrosoftGraphError> {
+    let path = format!(
+        "/me/events?$orderby={}&$top={}&$select={}",
+        urlencoding::encode("createdDateTime desc"),
+        top.clamp(1, MAX_PAGE_SIZE),
+        EVENT_SELECT
+    );
+    let response = graph_request(client, Method::GET, base_url, &path, access_token, None).await?;
+    Ok(list_page(response))
+}
+
+/// Most recently received messages of a mail folder (a well-known name such
+/// as `inbox` or a folder id) first; used by the new-mail trigger.
+pub async fn list_recent_messages(
+    client: &Client,
+    base_url: &str,
+    access_token: &str,
+    folder: &str,
+    top: u32,
+) -> Result<GraphPage, MicrosoftGraphError> {
+    let path = format!(
+        "/me/mailFolders/{}/messages?$orderby={}&$top={}&$select={}",
+        urlencoding::encode(folder),
+        urlencoding::encode("receivedDateTime desc"),
+        top.clamp(1, MAX_PAGE_SIZE),
+        MESSAGE_SELECT
+    );
+    let response = graph_request(client, Method::GET, base_url, &path, access_token, None).await?;
+    Ok(list_page(response))
+}
+
+/// Follows a `next_link` returned by [`list_recent_events`] or
+/// [`list_recent_messages`]. The link must point back at `base_url` so the
+/// access token is never sent elsewhere.
+pub async fn list_next_page(
+    client: &Client,
+    base_url: &str,
+    access_token: &str,
+    next_link: &str,
+) -> Result<GraphPage, MicrosoftGraphError> {
+    if !next_link.starts_with(base_url.trim_end_matches('/')) {
+        return Err(MicrosoftGraphError::InvalidResponse(
+            "next page link points outside Microsoft Graph".into(),
+        ));
+    }
+    let response =
+        graph_request(client, Method::GET, base_url, next_link, access_token, None).await?;
+    Ok(list_page(response))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn send_mail_accepts_empty_accepted_response() {
+        let server = httpmock::MockServer::start();
+        let mock = server.mock(|when, then| {
+            when.method(httpmock::Method::POST)
+                .path("/me/sendMail")
+                .header("authorization", "Bearer token")
+                .json_body(json!({
+                    "message": { "subject": "Hi" },
+                    "saveToSentItems": false
+                }));
+            then.status(202);
+        });
+
+        send_mail(
+            &Client::new(),
+            &server.url(""),
+            "token",
+            json!({ "subject": "Hi" }),
+            false,
+        )
+        .await
+        .expect("send mail");
+
+        mock.assert();
+    }
+
+    #[tokio::test]
+    async fn update_event_surfaces_graph_errors() {
+        let server = httpmock::MockServer::start();
+        server.mock(|when, then| {
+            when.method(httpmock::Method::PATCH)
+                .path("/me/events/evt-1");
+            then.status(404)
+                .header("content-type", "application/json")
+                .body(
+                    json!({ "error": { "code": "ErrorItemNotFound", "message": "Not found" } })
+                        .to_string(),
+                );
+        });
+
+        let err = update_event(
+            &Client::new(),
+            &server.url(""),
+            "token",
+            "evt-1",
+            &json!({ "subject": "Moved" }),
+        )
+        .await
+        .expect_err("missing event");
+
+        match err {
+            MicrosoftGraphError::UnexpectedStatus { status, message } => {
+                assert_eq!(status, StatusCode::NOT_FOUND);
+                assert_eq!(message, "Not found");
+            }
+            other => panic!("unexpected error: {other:?}"),
+        }
+    }
+}
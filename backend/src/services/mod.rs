@@ -1,8 +1,14 @@
+pub mod amqp;
+pub mod artifacts;
+pub mod asana;
+pub mod github;
 pub mod mailjet_mailer;
 pub mod microsoft;
 pub mod notion;
 pub mod oauth;
 pub mod pluggable_mailer;
+pub mod redis_streams;
 pub mod sendgrid_mailer;
+pub mod sftp;
 pub mod smtp_mailer;
 pub mod stripe;
@@ -7,11 +7,11 @@ use std::time::Duration;
 use thiserror::Error;
 use tokio::time::sleep;
 
-pub const NOTION_BASE_URL: &str = "https://api.notion.com/v1";
 pub const NOTION_VERSION: &str = "2022-06-28";
 const NOTION_MAX_RETRIES: usize = 3;
 const NOTION_BACKOFF_BASE_MS: u64 = 250;
 const NOTION_BACKOFF_MAX_MS: u64 = 2000;
+pub const MAX_BLOCK_CHILDREN_PER_REQUEST: usize = 100;
 
 #[derive(Debug, Error)]
 pub enum NotionError {
@@ -116,18 +116,6 @@ pub fn rich_text_plain_text(items: &[NotionRichText]) -> String {
     out.trim().to_string()
 }
 
-fn notion_base_url() -> String {
-    #[cfg(test)]
-    {
-        if let Ok(value) = std::env::var("NOTION_API_BASE_URL") {
-            if !value.trim().is_empty() {
-                return value;
-            }
-        }
-    }
-    NOTION_BASE_URL.to_string()
-}
-
 fn build_url(base: &str, path: &str) -> String {
     let trimmed_base = base.trim_end_matches('/');
     let trimmed_path = path.trim_start_matches('/');
@@ -136,12 +124,12 @@ fn build_url(base: &str, path: &str) -> String {
 
 fn build_request(
     client: &Client,
+    base_url: &str,
     access_token: &str,
     method: Method,
     path: &str,
 ) -> RequestBuilder {
-    let base_url = notion_base_url();
-    let url = build_url(&base_url, path);
+    let url = build_url(base_url, path);
     client
         .request(method, url)
         .bearer_auth(access_token)
@@ -221,6 +209,7 @@ async fn send_request<T: DeserializeOwned>(request: RequestBuilder) -> Result<T,
 
 pub async fn search_databases(
     client: &Client,
+    base_url: &str,
     access_token: &str,
     query: Option<&str>,
     start_cursor: Option<&str>,
@@ -253,23 +242,27 @@ pub async fn search_databases(
         payload["page_size"] = Value::Number(size.into());
     }
 
-    let request = build_request(client, access_token, Method::POST, "/search").json(&payload);
+    let request =
+        build_request(client, base_url, access_token, Method::POST, "/search").json(&payload);
     send_request(request).await
 }
 
 pub async fn retrieve_database(
     client: &Client,
+    base_url: &str,
     access_token: &str,
     database_id: &str,
 ) -> Result<NotionDatabase, NotionError> {
     let trimmed = database_id.trim();
     let path = format!("/databases/{}", urlencoding::encode(trimmed));
-    let request = build_request(client, access_token, Method::GET, &path);
+    let request = build_request(client, base_url, access_token, Method::GET, &path);
     send_request(request).await
 }
 
+#[allow(clippy::too_many_arguments)]
 pub async fn query_database(
     client: &Client,
+    base_url: &str,
     access_token: &str,
     database_id: &str,
     filter: Option<Value>,
@@ -300,12 +293,13 @@ pub async fn query_database(
         payload["page_size"] = Value::Number(size.into());
     }
 
-    let request = build_request(client, access_token, Method::POST, &path).json(&payload);
+    let request = build_request(client, base_url, access_token, Method::POST, &path).json(&payload);
     send_request(request).await
 }
 
 pub async fn create_page(
     client: &Client,
+    base_url: &str,
     access_token: &str,
     parent_database_id: Option<&str>,
     parent_page_id: Option<&str>,
@@ -324,12 +318,14 @@ pub async fn create_page(
         "properties": properties
     });
 
-    let request = build_request(client, access_token, Method::POST, "/pages").json(&payload);
+    let request =
+        build_request(client, base_url, access_token, Method::POST, "/pages").json(&payload);
     send_request(request).await
 }
 
 pub async fn update_page(
     client: &Client,
+    base_url: &str,
     access_token: &str,
     page_id: &str,
     properties: Value,
@@ -339,16 +335,182 @@ pub async fn update_page(
     let payload = json!({
         "properties": properties
     });
-    let request = build_request(client, access_token, Method::PATCH, &path).json(&payload);
+    let request =
+        build_request(client, base_url, access_token, Method::PATCH, &path).json(&payload);
+    send_request(request).await
+}
+
+pub async fn retrieve_page(
+    client: &Client,
+    base_url: &str,
+    access_token: &str,
+    page_id: &str,
+) -> Result<Value, NotionError> {
+    let trimmed = page_id.trim();
+    let path = format!("/pages/{}", urlencoding::encode(trimmed));
+    let request = build_request(client, base_url, access_token, Method::GET, &path);
+    send_request(request).await
+}
+
+/// Moves a page to the trash (`archived = true`) or restores it.
+pub async fn set_page_archived(
+    client: &Client,
+    base_url: &str,
+    access_token: &str,
+    page_id: &str,
+    archived: bool,
+) -> Result<Value, NotionError> {
+    let trimmed = page_id.trim();
+    let path = format!("/pages/{}", urlencoding::encode(trimmed));
+    let payload = json!({ "archived": archived });
+    let request =
+        build_request(client, base_url, access_token, Method::PATCH, &path).json(&payload);
+    send_request(request).await
+}
+
+/// Appends block children to a page or block. Notion accepts at most
+/// [`MAX_BLOCK_CHILDREN_PER_REQUEST`] children per call; callers chunk.
+pub async fn append_block_children(
+    client: &Client,
+    base_url: &str,
+    access_token: &str,
+    block_id: &str,
+    children: &[Value],
+) -> Result<NotionListResponse<Value>, NotionError> {
+    let trimmed = block_id.trim();
+    let path = format!("/blocks/{}/children", urlencoding::encode(trimmed));
+    let payload = json!({ "children": children });
+    let request =
+        build_request(client, base_url, access_token, Method::PATCH, &path).json(&payload);
     send_request(request).await
 }
 
+/// Reduces a page property object (`{"type": "select", "select": {...}}`) to
+/// a plain JSON value: text becomes a string, selects their option name,
+/// multi-selects and people a list of names, dates their start (or
+/// `{start, end}` for ranges), formulas and rollups their computed value.
+pub fn property_plain_value(property: &Value) -> Value {
+    let Some(kind) = property.get("type").and_then(|v| v.as_str()) else {
+        return Value::Null;
+    };
+    let inner = property.get(kind).unwrap_or(&Value::Null);
+    match kind {
+        "title" | "rich_text" => Value::String(plain_text_of(inner)),
+        "number" | "checkbox" | "url" | "email" | "phone_number" | "created_time"
+        | "last_edited_time" => inner.clone(),
+        "select" | "status" => inner.get("name").cloned().unwrap_or(Value::Null),
+        "multi_select" => Value::Array(
+            inner
+                .as_array()
+                .map(|options| {
+                    options
+                        .iter()
+                        .filter_map(|option| option.get("name").cloned())
+                        .collect()
+                })
+                .unwrap_or_default(),
+        ),
+        "date" => match (inner.get("start"), inner.get("end")) {
+            (Some(start), Some(end)) if !end.is_null() => json!({ "start": start, "end": end }),
+            (Some(start), _) => start.clone(),
+            _ => Value::Null,
+        },
+        "people" => Value::Array(
+            inner
+                .as_array()
+                .map(|people| {
+                    people
+                        .iter()
+                        .filter_map(|person| {
+                            person.get("name").or_else(|| person.get("id")).cloned()
+                        })
+                        .collect()
+                })
+                .unwrap_or_default(),
+        ),
+        "relation" => Value::Array(
+            inner
+                .as_array()
+                .map(|pages| pages.iter().filter_map(|p| p.get("id").cloned()).collect())
+                .unwrap_or_default(),
+        ),
+        "files" => Value::Array(
+            inner
+                .as_array()
+                .map(|files| {
+                    files
+                        .iter()
+                        .filter_map(|file| {
+                            let kind = file.get("type").and_then(|v| v.as_str())?;
+                            file.get(kind).and_then(|f| f.get("url")).cloned()
+                        })
+                        .collect()
+                })
+                .unwrap_or_default(),
+        ),
+        "created_by" | "last_edited_by" => inner
+            .get("name")
+            .or_else(|| inner.get("id"))
+            .cloned()
+            .unwrap_or(Value::Null),
+        "unique_id" => match inner.get("number") {
+            Some(number) if !number.is_null() => {
+                match inner.get("prefix").and_then(|v| v.as_str()) {
+                    Some(prefix) => Value::String(format!("{prefix}-{number}")),
+                    None => number.clone(),
+                }
+            }
+            _ => Value::Null,
+        },
+        "formula" => {
+            let formula_kind = inner.get("type").and_then(|v| v.as_str()).unwrap_or("");
+            inner.get(formula_kind).cloned().unwrap_or(Value::Null)
+        }
+        "rollup" => match inner.get("type").and_then(|v| v.as_str()) {
+            Some("array") => Value::Array(
+                inner
+                    .get("array")
+                    .and_then(|v| v.as_array())
+                    .map(|items| items.iter().map(property_plain_value).collect())
+                    .unwrap_or_default(),
+            ),
+            Some(other) => inner.get(other).cloned().unwrap_or(Value::Null),
+            None => Value::Null,
+        },
+        _ => inner.clone(),
+    }
+}
+
+/// Flattens every property of a page into `{name: plain value}`.
+pub fn flatten_page_properties(page: &Value) -> serde_json::Map<String, Value> {
+    page.get("properties")
+        .and_then(|v| v.as_object())
+        .map(|properties| {
+            properties
+                .iter()
+                .map(|(name, property)| (name.clone(), property_plain_value(property)))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn plain_text_of(items: &Value) -> String {
+    items
+        .as_array()
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|item| item.get("plain_text").and_then(|v| v.as_str()))
+                .collect::<String>()
+        })
+        .unwrap_or_default()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     use once_cell::sync::Lazy;
-    use std::sync::MutexGuard;
     use tokio::sync::Mutex;
 
     static CLIENT: Lazy<Mutex<Client>> = Lazy::new(|| {
@@ -359,42 +521,11 @@ mod tests {
                 .expect("client"),
         )
     });
-    static ENV_LOCK: Lazy<std::sync::Mutex<()>> = Lazy::new(|| std::sync::Mutex::new(()));
-
-    struct EnvGuard {
-        key: &'static str,
-        previous: Option<String>,
-        _lock: MutexGuard<'static, ()>,
-    }
-
-    impl EnvGuard {
-        fn set(key: &'static str, value: String) -> Self {
-            let lock = ENV_LOCK.lock().expect("env mutex poisoned");
-            let previous = std::env::var(key).ok();
-            std::env::set_var(key, value);
-            Self {
-                key,
-                previous,
-                _lock: lock,
-            }
-        }
-    }
-
-    impl Drop for EnvGuard {
-        fn drop(&mut self) {
-            if let Some(previous) = self.previous.take() {
-                std::env::set_var(self.key, previous);
-            } else {
-                std::env::remove_var(self.key);
-            }
-        }
-    }
 
     #[tokio::test]
     async fn search_databases_sets_headers_and_path() {
         let client = CLIENT.lock().await;
         let server = httpmock::MockServer::start();
-        let _env = EnvGuard::set("NOTION_API_BASE_URL", server.url(""));
         let mock = server.mock(|when, then| {
             when.method(httpmock::Method::POST)
                 .path("/search")
@@ -416,9 +547,16 @@ mod tests {
                 );
         });
 
-        let response = search_databases(&client, "test-token", Some("Acme"), None, None)
-            .await
-            .expect("search");
+        let response = search_databases(
+            &client,
+            &server.url(""),
+            "test-token",
+            Some("Acme"),
+            None,
+            None,
+        )
+        .await
+        .expect("search");
 
         mock.assert();
         assert!(response.results.is_empty());
@@ -429,7 +567,6 @@ mod tests {
     async fn retrieve_database_uses_expected_path() {
         let client = CLIENT.lock().await;
         let server = httpmock::MockServer::start();
-        let _env = EnvGuard::set("NOTION_API_BASE_URL", server.url(""));
         let mock = server.mock(|when, then| {
             when.method(httpmock::Method::GET)
                 .path("/databases/db-123")
@@ -447,7 +584,7 @@ mod tests {
                 );
         });
 
-        let response = retrieve_database(&client, "test-token", "db-123")
+        let response = retrieve_database(&client, &server.url(""), "test-token", "db-123")
             .await
             .expect("database");
 
@@ -459,7 +596,6 @@ mod tests {
     async fn error_mapping_surfaces_status_message_and_request_id() {
         let client = CLIENT.lock().await;
         let server = httpmock::MockServer::start();
-        let _env = EnvGuard::set("NOTION_API_BASE_URL", server.url(""));
         let mock = server.mock(|when, then| {
             when.method(httpmock::Method::GET).path("/databases/db-401");
             then.status(401)
@@ -473,7 +609,7 @@ mod tests {
                 );
         });
 
-        let result = retrieve_database(&client, "token", "db-401").await;
+        let result = retrieve_database(&client, &server.url(""), "token", "db-401").await;
 
         mock.assert();
         match result {
@@ -9,16 +9,21 @@ use time::{Duration, OffsetDateTime};
 use tracing::{info, warn};
 use uuid::Uuid;
 
-use crate::config::{OAuthProviderConfig, OAuthSettings};
+use crate::config::{OAuthProviderConfig, OAuthSettings, ProviderBaseUrls};
 use crate::db::oauth_token_repository::{NewUserOAuthToken, UserOAuthTokenRepository};
 #[cfg(test)]
 use crate::db::postgres_oauth_token_repository::PostgresUserOAuthTokenRepository;
 #[cfg(test)]
 use crate::db::workspace_connection_repository::NoopWorkspaceConnectionRepository;
 use crate::db::workspace_connection_repository::WorkspaceConnectionRepository;
+use crate::engine::actions::http::TriggerEgress;
 use crate::models::oauth_token::{ConnectedOAuthProvider, UserOAuthToken};
 #[cfg(test)]
 use crate::models::oauth_token::{WorkspaceAuditEvent, WorkspaceConnection};
+use crate::services::oauth::custom_provider::{
+    identity_from_userinfo, CustomOAuthClient, CustomOAuthMetadata, CustomTokenResponse,
+    CUSTOM_REQUEST_TIMEOUT,
+};
 #[cfg(test)]
 use crate::state::test_pg_pool;
 use crate::utils::encryption::{decrypt_secret, encrypt_secret, EncryptionError};
@@ -40,6 +45,10 @@ const ASANA_USERINFO_URL: &str = "https://app.asana.com/api/1.0/users/me";
 const ASANA_REVOCATION_URL: &str = "https://app.asana.com/-/oauth_revoke";
 const NOTION_TOKEN_URL: &str = "https://api.notion.com/v1/oauth/token";
 const NOTION_ACCESS_TTL_DAYS: i64 = 3650;
+const GITHUB_TOKEN_URL: &str = "https://github.com/login/oauth/access_token";
+/// OAuth app tokens do not expire unless the app opts into expiring
+/// user tokens, in which case GitHub returns `expires_in` and a refresh token.
+const GITHUB_ACCESS_TTL_DAYS: i64 = 3650;
 
 #[derive(Debug, Clone)]
 pub struct StoredOAuthToken {
@@ -51,6 +60,8 @@ pub struct StoredOAuthToken {
     pub account_email: String,
     pub is_shared: bool,
     pub updated_at: OffsetDateTime,
+    /// Set for `Custom` connections: the provider definition they belong to.
+    pub custom_provider_id: Option<Uuid>,
 }
 
 #[derive(Debug, Clone)]
@@ -102,6 +113,8 @@ pub struct OAuthTokenMetadata {
     pub provider_user_id: Option<String>,
     #[serde(default)]
     pub notion: Option<NotionOAuthMetadata>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub custom: Option<CustomOAuthMetadata>,
 }
 
 #[derive(Debug, Clone)]
@@ -143,6 +156,8 @@ pub enum OAuthAccountError {
     EmailNotVerified { provider: ConnectedOAuthProvider },
     #[error("oauth token revoked for {provider:?}")]
     TokenRevoked { provider: ConnectedOAuthProvider },
+    #[error("outbound request blocked: {0}")]
+    EgressBlocked(String),
 }
 
 #[derive(Clone)]
@@ -156,6 +171,12 @@ pub struct OAuthAccountService {
     slack: OAuthProviderConfig,
     asana: OAuthProviderConfig,
     notion: OAuthProviderConfig,
+    github: OAuthProviderConfig,
+    slack_api_base: String,
+    github_api_base: String,
+    /// Deny-list and SSRF rules for the admin-supplied endpoints of custom
+    /// providers.
+    custom_egress: Arc<TriggerEgress>,
     #[cfg(test)]
     refresh_override: Option<Arc<RefreshOverride>>,
     #[cfg(test)]
@@ -182,6 +203,10 @@ impl OAuthAccountService {
             slack: settings.slack.clone(),
             asana: settings.asana.clone(),
             notion: settings.notion.clone(),
+            github: settings.github.clone(),
+            slack_api_base: ProviderBaseUrls::default().slack,
+            github_api_base: ProviderBaseUrls::default().github,
+            custom_egress: Arc::new(TriggerEgress::from_env()),
             #[cfg(test)]
             refresh_override: None,
             #[cfg(test)]
@@ -191,6 +216,16 @@ impl OAuthAccountService {
         }
     }
 
+    /// Points Slack Web API calls (e.g. `auth.test`) at the configured base.
+    pub fn set_slack_api_base(&mut self, base_url: impl Into<String>) {
+        self.slack_api_base = base_url.into();
+    }
+
+    /// Points GitHub REST calls (user lookup, grant revocation) at the configured base.
+    pub fn set_github_api_base(&mut self, base_url: impl Into<String>) {
+        self.github_api_base = base_url.into();
+    }
+
     #[cfg(test)]
     fn google_token_url(&self) -> &str {
         self.endpoint_overrides
@@ -269,6 +304,19 @@ impl OAuthAccountService {
         NOTION_TOKEN_URL
     }
 
+    #[cfg(test)]
+    fn github_token_url(&self) -> &str {
+        self.endpoint_overrides
+            .github_token_url
+            .as_deref()
+            .unwrap_or(GITHUB_TOKEN_URL)
+    }
+
+    #[cfg(not(test))]
+    fn github_token_url(&self) -> &str {
+        GITHUB_TOKEN_URL
+    }
+
     #[cfg(test)]
     pub fn set_refresh_override<F>(&mut self, override_fn: Option<Arc<F>>)
     where
@@ -321,28 +369,43 @@ impl OAuthAccountService {
         self.endpoint_overrides.notion_token_url = Some(token_url.into());
     }
 
+    #[cfg(test)]
+    pub(crate) fn set_custom_egress(&mut self, egress: TriggerEgress) {
+        self.custom_egress = Arc::new(egress);
+    }
+
+    #[cfg(test)]
+    pub fn set_github_endpoint_override(&mut self, token_url: impl Into<String>) {
+        self.endpoint_overrides.github_token_url = Some(token_url.into());
+    }
+
     pub fn google_scopes(&self) -> &'static str {
         // `openid email` lets us call the Google OpenID Connect userinfo endpoint and confirm the
-        // caller's verified email address. The Sheets scope is required by workflow actions that
-        // append rows via the Google Sheets API.
-        "openid email https://www.googleapis.com/auth/spreadsheets"
+        // caller's verified email address. The Sheets, Calendar and Drive scopes are required by
+        // the workflow actions that append rows, manage events and manage files.
+        "openid email https://www.googleapis.com/auth/spreadsheets https://www.googleapis.com/auth/calendar https://www.googleapis.com/auth/drive"
     }
 
     pub fn microsoft_scopes(&self) -> &'static str {
         // `offline_access` gives refresh tokens, `User.Read` satisfies Microsoft Graph sign-in,
         // and the Teams scopes cover listing joined teams, channels, channel members, and sending
-        // delegated channel messages from workflow actions.
-        "offline_access User.Read Team.ReadBasic.All Channel.ReadBasic.All ChannelMember.Read.All ChannelMessage.Send"
+        // and reading delegated channel messages from workflow actions and the new-message
+        // trigger. The Mail and Calendars scopes back the Outlook actions and polling triggers.
+        "offline_access User.Read Team.ReadBasic.All Channel.ReadBasic.All ChannelMember.Read.All ChannelMessage.Send ChannelMessage.Read.All Mail.Send Mail.Read Calendars.ReadWrite"
     }
 
     pub fn slack_bot_scopes(&self) -> &'static str {
-        // Bot token scopes for listing channels and sending messages as the app bot user.
-        "channels:read,groups:read,im:read,mpim:read,chat:write,chat:write.public,incoming-webhook"
+        // Bot token scopes for listing channels and sending messages as the app bot user;
+        // reactions, file uploads and DMs need their own write scopes, and DMs look up the
+        // recipient by email. The history, `app_mentions:read` and `reactions:read` scopes are
+        // what make the Events API deliver `message`, `app_mention` and `reaction_added`.
+        "channels:read,groups:read,im:read,mpim:read,chat:write,chat:write.public,incoming-webhook,reactions:write,files:write,im:write,users:read,users:read.email,channels:history,groups:history,im:history,app_mentions:read,reactions:read"
     }
 
     pub fn slack_scopes(&self) -> &'static str {
-        // User token scopes for delegated post-as-user and user resolution.
-        "chat:write,channels:read,groups:read,im:read,mpim:read,users:read,users:read.email"
+        // User token scopes for delegated post-as-user, reactions, file uploads, DMs and user
+        // resolution.
+        "chat:write,channels:read,groups:read,im:read,mpim:read,users:read,users:read.email,reactions:write,files:write,im:write"
     }
 
     pub fn asana_scopes(&self) -> &'static str {
@@ -351,13 +414,19 @@ impl OAuthAccountService {
         "default email"
     }
 
+    pub fn github_scopes(&self) -> &'static str {
+        // `repo` covers issues, comments, labels, releases and workflow dispatches on public
+        // and private repositories; `read:user user:email` identify the connected account.
+        "repo read:user user:email"
+    }
+
     pub async fn save_authorization(
         &self,
         user_id: Uuid,
         provider: ConnectedOAuthProvider,
         tokens: AuthorizationTokens,
     ) -> Result<StoredOAuthToken, OAuthAccountError> {
-        self.store_authorization(user_id, provider, None, tokens)
+        self.store_authorization(user_id, provider, None, tokens, None)
             .await
     }
 
@@ -412,7 +481,7 @@ impl OAuthAccountService {
             return Err(OAuthAccountError::NotFound);
         }
 
-        self.store_authorization(user_id, provider, Some(existing), tokens)
+        self.store_authorization(user_id, provider, Some(existing), tokens, None)
             .await
     }
 
@@ -422,6 +491,7 @@ impl OAuthAccountService {
         provider: ConnectedOAuthProvider,
         existing: Option<UserOAuthToken>,
         tokens: AuthorizationTokens,
+        custom: Option<CustomOAuthMetadata>,
     ) -> Result<StoredOAuthToken, OAuthAccountError> {
         let existing_id = existing.as_ref().map(|record| record.id);
         if let Some(existing) = existing.as_ref() {
@@ -456,6 +526,7 @@ impl OAuthAccountService {
             encrypted_slack.clone(),
             provider_user_id,
             tokens.notion.clone(),
+            custom,
         );
 
         let stored = if let Some(existing) = existing {
@@ -511,6 +582,10 @@ impl OAuthAccountService {
             account_email: stored.account_email,
             is_shared: stored.is_shared,
             updated_at: stored.updated_at,
+            custom_provider_id: merged_metadata
+                .custom
+                .as_ref()
+                .map(|custom| custom.provider_id),
         })
     }
 
@@ -757,7 +832,7 @@ impl OAuthAccountService {
         mut decrypted: StoredOAuthToken,
     ) -> Result<StoredOAuthToken, OAuthAccountError> {
         let refreshed = match self
-            .refresh_access_token(record.provider, &decrypted.refresh_token)
+            .refresh_connection_tokens(record.provider, &decrypted.refresh_token, &record.metadata)
             .await
         {
             Ok(tokens) => tokens,
@@ -791,6 +866,7 @@ impl OAuthAccountService {
             encrypted_slack.clone(),
             refreshed.provider_user_id.clone(),
             refreshed.notion.clone(),
+            None,
         );
 
         let updated = self
@@ -1020,6 +1096,10 @@ impl OAuthAccountService {
             }
             ConnectedOAuthProvider::Asana => self.exchange_asana_code(code).await,
             ConnectedOAuthProvider::Notion => self.exchange_notion_code(code).await,
+            ConnectedOAuthProvider::GitHub => self.exchange_github_code(code).await,
+            ConnectedOAuthProvider::Custom => Err(OAuthAccountError::InvalidResponse(
+                "Custom providers exchange codes through their definition".into(),
+            )),
         }
     }
 
@@ -1608,6 +1688,101 @@ impl OAuthAccountService {
         })
     }
 
+    async fn exchange_github_code(
+        &self,
+        code: &str,
+    ) -> Result<AuthorizationTokens, OAuthAccountError> {
+        #[derive(Deserialize)]
+        struct GitHubUser {
+            id: Option<i64>,
+            login: Option<String>,
+            email: Option<String>,
+        }
+
+        #[derive(Deserialize)]
+        struct GitHubEmail {
+            email: String,
+            #[serde(default)]
+            primary: bool,
+            #[serde(default)]
+            verified: bool,
+        }
+
+        let response = self
+            .client
+            .post(self.github_token_url())
+            .header("Accept", "application/json")
+            .form(&[
+                ("client_id", self.github.client_id.as_str()),
+                ("client_secret", self.github.client_secret.as_str()),
+                ("redirect_uri", self.github.redirect_uri.as_str()),
+                ("code", code),
+            ])
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<GitHubTokenResponse>()
+            .await?;
+        let (access_token, refresh_token, expires_at) = response.into_tokens(None)?;
+
+        let api = self.github_api_base.trim_end_matches('/');
+        let user: GitHubUser = self
+            .client
+            .get(format!("{api}/user"))
+            .bearer_auth(&access_token)
+            .header("Accept", "application/vnd.github+json")
+            .header("User-Agent", "dsentr")
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        // The emails endpoint needs `user:email`; fall back to the public
+        // profile email or the login when it is unavailable.
+        let primary_email = match self
+            .client
+            .get(format!("{api}/user/emails"))
+            .bearer_auth(&access_token)
+            .header("Accept", "application/vnd.github+json")
+            .header("User-Agent", "dsentr")
+            .send()
+            .await
+        {
+            Ok(resp) if resp.status().is_success() => resp
+                .json::<Vec<GitHubEmail>>()
+                .await
+                .ok()
+                .and_then(|emails| {
+                    emails
+                        .into_iter()
+                        .find(|e| e.primary && e.verified)
+                        .map(|e| e.email)
+                }),
+            _ => None,
+        };
+
+        let account_email = primary_email
+            .or(user.email)
+            .or(user.login)
+            .ok_or_else(|| OAuthAccountError::InvalidResponse("Missing GitHub account".into()))?;
+        let provider_user_id = user
+            .id
+            .map(|id| id.to_string())
+            .as_deref()
+            .and_then(normalize_provider_user_id);
+
+        Ok(AuthorizationTokens {
+            access_token,
+            refresh_token,
+            expires_at,
+            account_email,
+            provider_user_id,
+            slack: None,
+            notion: None,
+        })
+    }
+
     pub async fn refresh_access_token(
         &self,
         provider: ConnectedOAuthProvider,
@@ -1623,6 +1798,10 @@ impl OAuthAccountService {
             ConnectedOAuthProvider::Slack => self.refresh_slack_token(refresh_token).await,
             ConnectedOAuthProvider::Asana => self.refresh_asana_token(refresh_token).await,
             ConnectedOAuthProvider::Notion => self.refresh_notion_token(refresh_token).await,
+            ConnectedOAuthProvider::GitHub => self.refresh_github_token(refresh_token).await,
+            ConnectedOAuthProvider::Custom => Err(OAuthAccountError::InvalidResponse(
+                "Custom providers refresh through the connection metadata".into(),
+            )),
         }
     }
 
@@ -1970,6 +2149,283 @@ impl OAuthAccountService {
         Err(OAuthAccountError::MissingRefreshToken)
     }
 
+    async fn refresh_github_token(
+        &self,
+        refresh_token: &str,
+    ) -> Result<AuthorizationTokens, OAuthAccountError> {
+        // Tokens from apps without expiring user tokens never need refreshing.
+        if refresh_token.trim().is_empty() {
+            return Err(OAuthAccountError::MissingRefreshToken);
+        }
+
+        let response = self
+            .client
+            .post(self.github_token_url())
+            .header("Accept", "application/json")
+            .form(&[
+                ("grant_type", "refresh_token"),
+                ("refresh_token", refresh_token),
+                ("client_id", self.github.client_id.as_str()),
+                ("client_secret", self.github.client_secret.as_str()),
+            ])
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<GitHubTokenResponse>()
+            .await
+            .map_err(|err| OAuthAccountError::InvalidResponse(err.to_string()))?;
+
+        if response.error.as_deref() == Some("bad_refresh_token") {
+            warn!(provider = "github", "github oauth refresh token revoked");
+            return Err(OAuthAccountError::TokenRevoked {
+                provider: ConnectedOAuthProvider::GitHub,
+            });
+        }
+        let (access_token, refresh_token, expires_at) =
+            response.into_tokens(Some(refresh_token))?;
+
+        Ok(AuthorizationTokens {
+            access_token,
+            refresh_token,
+            expires_at,
+            account_email: String::new(),
+            provider_user_id: None,
+            slack: None,
+            notion: None,
+        })
+    }
+
+    /// Refreshes a stored token. Custom connections name their provider
+    /// definition in `metadata`; other providers ignore it.
+    pub async fn refresh_connection_tokens(
+        &self,
+        provider: ConnectedOAuthProvider,
+        refresh_token: &str,
+        metadata: &Value,
+    ) -> Result<AuthorizationTokens, OAuthAccountError> {
+        if provider != ConnectedOAuthProvider::Custom {
+            return self.refresh_access_token(provider, refresh_token).await;
+        }
+        #[cfg(test)]
+        if let Some(override_fn) = &self.refresh_override {
+            return override_fn(provider, refresh_token);
+        }
+
+        let custom = parse_token_metadata(metadata).custom.ok_or_else(|| {
+            OAuthAccountError::InvalidResponse(
+                "Custom OAuth connection is missing its provider".into(),
+            )
+        })?;
+        let definition = self.load_custom_provider(custom.provider_id).await?;
+        self.refresh_custom_token(&definition, refresh_token).await
+    }
+
+    pub async fn load_custom_provider(
+        &self,
+        provider_id: Uuid,
+    ) -> Result<CustomOAuthClient, OAuthAccountError> {
+        let record = self
+            .workspace_connections
+            .find_custom_provider(provider_id)
+            .await?
+            .ok_or(OAuthAccountError::NotFound)?;
+        Ok(CustomOAuthClient::decrypt(&record, &self.encryption_key)?)
+    }
+
+    /// Encrypts a custom provider's client id or secret for storage.
+    pub fn encrypt_provider_credential(&self, value: &str) -> Result<String, OAuthAccountError> {
+        Ok(encrypt_secret(&self.encryption_key, value)?)
+    }
+
+    pub async fn exchange_custom_code(
+        &self,
+        provider: &CustomOAuthClient,
+        code: &str,
+        redirect_uri: &str,
+        code_verifier: Option<&str>,
+    ) -> Result<AuthorizationTokens, OAuthAccountError> {
+        let mut form = vec![
+            ("grant_type", "authorization_code".to_string()),
+            ("code", code.to_string()),
+            ("redirect_uri", redirect_uri.to_string()),
+        ];
+        if let Some(verifier) = code_verifier {
+            form.push(("code_verifier", verifier.to_string()));
+        }
+        let response = self.custom_token_request(provider, form, false).await?;
+        let expires_at = response.expires_at();
+        let access_token = response
+            .access_token
+            .filter(|token| !token.trim().is_empty())
+            .ok_or_else(|| {
+                OAuthAccountError::InvalidResponse(format!(
+                    "{} did not return an access token",
+                    provider.name
+                ))
+            })?;
+
+        let (account_label, provider_user_id) = match provider.userinfo_url.as_deref() {
+            Some(url) => {
+                let (client, url) = self.custom_endpoint_client(url)?;
+                let body: Value = client
+                    .get(url)
+                    .bearer_auth(&access_token)
+                    .header("Accept", "application/json")
+                    .send()
+                    .await?
+                    .error_for_status()?
+                    .json()
+                    .await?;
+                identity_from_userinfo(&body)
+            }
+            None => (None, None),
+        };
+
+        Ok(AuthorizationTokens {
+            access_token,
+            refresh_token: response.refresh_token.unwrap_or_default(),
+            expires_at,
+            account_email: account_label.unwrap_or_else(|| provider.name.clone()),
+            provider_user_id: provider_user_id
+                .as_deref()
+                .and_then(normalize_provider_user_id),
+            slack: None,
+            notion: None,
+        })
+    }
+
+    /// Saves a custom connection, replacing the user's earlier connection to
+    /// the same provider account when the userinfo id matches.
+    pub async fn save_custom_authorization(
+        &self,
+        user_id: Uuid,
+        provider: &CustomOAuthClient,
+        tokens: AuthorizationTokens,
+    ) -> Result<StoredOAuthToken, OAuthAccountError> {
+        let existing = self
+            .find_matching_personal_tokens(user_id, ConnectedOAuthProvider::Custom, &tokens)
+            .await?
+            .into_iter()
+            .filter(|record| {
+                parse_token_metadata(&record.metadata)
+                    .custom
+                    .is_some_and(|custom| custom.provider_id == provider.id)
+            })
+            .max_by_key(|record| record.updated_at);
+
+        self.store_authorization(
+            user_id,
+            ConnectedOAuthProvider::Custom,
+            existing,
+            tokens,
+            Some(provider.metadata()),
+        )
+        .await
+    }
+
+    async fn refresh_custom_token(
+        &self,
+        provider: &CustomOAuthClient,
+        refresh_token: &str,
+    ) -> Result<AuthorizationTokens, OAuthAccountError> {
+        if refresh_token.trim().is_empty() {
+            return Err(OAuthAccountError::MissingRefreshToken);
+        }
+
+        let form = vec![
+            ("grant_type", "refresh_token".to_string()),
+            ("refresh_token", refresh_token.to_string()),
+        ];
+        let response = self.custom_token_request(provider, form, true).await?;
+        let expires_at = response.expires_at();
+        let access_token = response
+            .access_token
+            .filter(|token| !token.trim().is_empty())
+            .ok_or_else(|| {
+                OAuthAccountError::InvalidResponse(format!(
+                    "{} did not return an access token",
+                    provider.name
+                ))
+            })?;
+
+        Ok(AuthorizationTokens {
+            access_token,
+            // Providers that do not rotate refresh tokens omit them here.
+            refresh_token: response
+                .refresh_token
+                .filter(|token| !token.trim().is_empty())
+                .unwrap_or_else(|| refresh_token.to_string()),
+            expires_at,
+            account_email: String::new(),
+            provider_user_id: None,
+            slack: None,
+            notion: None,
+        })
+    }
+
+    /// Posts to the definition's token endpoint. With `refreshing`, an
+    /// `invalid_grant` answer means the grant is gone and maps to `TokenRevoked`.
+    async fn custom_token_request(
+        &self,
+        provider: &CustomOAuthClient,
+        mut form: Vec<(&'static str, String)>,
+        refreshing: bool,
+    ) -> Result<CustomTokenResponse, OAuthAccountError> {
+        let (client, url) = self.custom_endpoint_client(&provider.token_url)?;
+        let request = provider.authenticate(
+            client.post(url).header("Accept", "application/json"),
+            &mut form,
+        );
+        let response = request.form(&form).send().await?;
+        let status = response.status();
+        let text = response.text().await?;
+        let body: CustomTokenResponse = serde_json::from_str(&text).map_err(|_| {
+            OAuthAccountError::InvalidResponse(format!(
+                "{} token endpoint returned an unreadable response ({status})",
+                provider.name
+            ))
+        })?;
+
+        if refreshing && body.error.as_deref() == Some("invalid_grant") {
+            warn!(provider_id = %provider.id, "custom oauth refresh token revoked");
+            return Err(OAuthAccountError::TokenRevoked {
+                provider: ConnectedOAuthProvider::Custom,
+            });
+        }
+        if !status.is_success() || body.error.is_some() {
+            let reason = body
+                .error_description
+                .or(body.error)
+                .unwrap_or_else(|| status.to_string());
+            return Err(OAuthAccountError::InvalidResponse(format!(
+                "{} token request failed: {reason}",
+                provider.name
+            )));
+        }
+        Ok(body)
+    }
+
+    /// Custom token and userinfo endpoints are admin-supplied, so each request
+    /// to them, and every redirect hop, is checked against the egress rules
+    /// polling triggers use. A client secret never follows a redirect to a
+    /// blocked host.
+    fn custom_endpoint_client(
+        &self,
+        raw_url: &str,
+    ) -> Result<(Client, reqwest::Url), OAuthAccountError> {
+        let url = reqwest::Url::parse(raw_url).map_err(|err| {
+            OAuthAccountError::InvalidResponse(format!("invalid endpoint URL: {err}"))
+        })?;
+        self.custom_egress
+            .check(&url)
+            .map_err(OAuthAccountError::EgressBlocked)?;
+        let client = Client::builder()
+            .redirect(self.custom_egress.redirect_policy())
+            .timeout(CUSTOM_REQUEST_TIMEOUT)
+            .build()?;
+        Ok((client, url))
+    }
+
     fn encrypt_slack_metadata(
         &self,
         slack: &SlackOAuthMetadata,
@@ -1983,7 +2439,9 @@ impl OAuthAccountService {
         provider: ConnectedOAuthProvider,
         stored: &StoredOAuthToken,
     ) {
-        if !stored.refresh_token.trim().is_empty() {
+        // GitHub revokes a whole grant through its access token, so only the
+        // access token is sent there.
+        if provider != ConnectedOAuthProvider::GitHub && !stored.refresh_token.trim().is_empty() {
             if let Err(err) = self
                 .revoke_provider_token(provider, &stored.refresh_token)
                 .await
@@ -1997,8 +2455,10 @@ impl OAuthAccountService {
             }
         }
 
-        if matches!(provider, ConnectedOAuthProvider::Slack)
-            && !stored.access_token.trim().is_empty()
+        if matches!(
+            provider,
+            ConnectedOAuthProvider::Slack | ConnectedOAuthProvider::GitHub
+        ) && !stored.access_token.trim().is_empty()
         {
             if let Err(err) = self
                 .revoke_provider_token(provider, &stored.access_token)
@@ -2124,7 +2584,33 @@ impl OAuthAccountService {
                     )))
                 }
             }
-            ConnectedOAuthProvider::Notion => Ok(()),
+            // Revocation endpoints are not part of a custom definition.
+            ConnectedOAuthProvider::Notion | ConnectedOAuthProvider::Custom => Ok(()),
+            ConnectedOAuthProvider::GitHub => {
+                let response = self
+                    .client
+                    .delete(format!(
+                        "{}/applications/{}/grant",
+                        self.github_api_base.trim_end_matches('/'),
+                        self.github.client_id
+                    ))
+                    .basic_auth(&self.github.client_id, Some(&self.github.client_secret))
+                    .header("Accept", "application/vnd.github+json")
+                    .header("User-Agent", "dsentr")
+                    .json(&json!({ "access_token": token }))
+                    .send()
+                    .await?;
+
+                // 404 means the grant is already gone.
+                if response.status().is_success() || response.status() == StatusCode::NOT_FOUND {
+                    Ok(())
+                } else {
+                    Err(OAuthAccountError::InvalidResponse(format!(
+                        "Failed to revoke token: {}",
+                        response.status()
+                    )))
+                }
+            }
         }
     }
 
@@ -2140,13 +2626,7 @@ impl OAuthAccountService {
             error: Option<String>,
         }
 
-        let base = std::env::var("SLACK_API_BASE_URL")
-            .ok()
-            .or_else(|| std::env::var("SLACK_API_BASE").ok())
-            .map(|value| value.trim().to_string())
-            .filter(|value| !value.is_empty())
-            .unwrap_or_else(|| "https://slack.com/api".to_string());
-        let url = format!("{}/auth.test", base.trim_end_matches('/'));
+        let url = format!("{}/auth.test", self.slack_api_base.trim_end_matches('/'));
 
         let response = self
             .client
@@ -2249,6 +2729,9 @@ impl OAuthAccountService {
     ) -> Result<StoredOAuthToken, OAuthAccountError> {
         let access_token = decrypt_secret(&self.encryption_key, &record.access_token)?;
         let refresh_token = decrypt_secret(&self.encryption_key, &record.refresh_token)?;
+        let custom_provider_id = parse_token_metadata(&record.metadata)
+            .custom
+            .map(|custom| custom.provider_id);
         Ok(StoredOAuthToken {
             id: record.id,
             provider: record.provider,
@@ -2258,6 +2741,7 @@ impl OAuthAccountService {
             account_email: record.account_email,
             is_shared: record.is_shared,
             updated_at: record.updated_at,
+            custom_provider_id,
         })
     }
 
@@ -2372,6 +2856,13 @@ impl OAuthAccountService {
                 Ok(Vec::new())
             }
 
+            async fn list_slack_by_team(
+                &self,
+                _slack_team_id: &str,
+            ) -> Result<Vec<WorkspaceConnection>, sqlx::Error> {
+                Ok(Vec::new())
+            }
+
             async fn list_for_workspace(
                 &self,
                 _workspace_id: Uuid,
@@ -2527,6 +3018,11 @@ impl OAuthAccountService {
                 client_secret: "stub".into(),
                 redirect_uri: "http://localhost".into(),
             },
+            github: OAuthProviderConfig {
+                client_id: "stub".into(),
+                client_secret: "stub".into(),
+                redirect_uri: "http://localhost".into(),
+            },
             token_encryption_key: vec![0u8; 32],
         };
         Arc::new(Self::new(
@@ -2652,6 +3148,51 @@ pub(crate) fn merge_notion_metadata(
     has_notion_fields(&merged).then_some(merged)
 }
 
+/// GitHub's token endpoint answers errors with HTTP 200 and an `error` field.
+#[derive(Deserialize)]
+struct GitHubTokenResponse {
+    #[serde(default)]
+    access_token: Option<String>,
+    #[serde(default)]
+    refresh_token: Option<String>,
+    #[serde(default)]
+    expires_in: Option<i64>,
+    #[serde(default)]
+    error: Option<String>,
+    #[serde(default)]
+    error_description: Option<String>,
+}
+
+impl GitHubTokenResponse {
+    /// `(access token, refresh token, expiry)`. `previous_refresh` is kept
+    /// when a refresh response does not rotate the refresh token.
+    fn into_tokens(
+        self,
+        previous_refresh: Option<&str>,
+    ) -> Result<(String, String, OffsetDateTime), OAuthAccountError> {
+        if let Some(error) = self.error {
+            return Err(OAuthAccountError::InvalidResponse(
+                self.error_description.unwrap_or(error),
+            ));
+        }
+        let access_token = self
+            .access_token
+            .filter(|token| !token.trim().is_empty())
+            .ok_or_else(|| {
+                OAuthAccountError::InvalidResponse("GitHub response missing access_token".into())
+            })?;
+        let refresh_token = self
+            .refresh_token
+            .or_else(|| previous_refresh.map(str::to_string))
+            .unwrap_or_default();
+        let expires_at = match self.expires_in {
+            Some(seconds) => OffsetDateTime::now_utc() + Duration::seconds(seconds),
+            None => OffsetDateTime::now_utc() + Duration::days(GITHUB_ACCESS_TTL_DAYS),
+        };
+        Ok((access_token, refresh_token, expires_at))
+    }
+}
+
 fn normalize_provider_user_id(value: &str) -> Option<String> {
     let trimmed = value.trim();
     if trimmed.is_empty() {
@@ -2673,6 +3214,7 @@ fn merge_metadata_value(
     slack: Option<EncryptedSlackOAuthMetadata>,
     provider_user_id: Option<String>,
     notion: Option<NotionOAuthMetadata>,
+    custom: Option<CustomOAuthMetadata>,
 ) -> (OAuthTokenMetadata, Value) {
     let mut metadata = existing.map(parse_token_metadata).unwrap_or_default();
 
@@ -2680,6 +3222,9 @@ fn merge_metadata_value(
     metadata.provider_user_id =
         merge_provider_user_id(metadata.provider_user_id.clone(), provider_user_id);
     metadata.notion = merge_notion_metadata(metadata.notion.clone(), notion);
+    if custom.is_some() {
+        metadata.custom = custom;
+    }
 
     let value = serialize_token_metadata(metadata.clone());
     (metadata, value)
@@ -2741,6 +3286,7 @@ struct TestEndpointOverrides {
     slack_token_url: Option<String>,
     slack_userinfo_url: Option<String>,
     notion_token_url: Option<String>,
+    github_token_url: Option<String>,
 }
 
 #[cfg(test)]
@@ -2902,6 +3448,13 @@ mod tests {
             Ok(Vec::new())
         }
 
+        async fn list_slack_by_team(
+            &self,
+            _slack_team_id: &str,
+        ) -> Result<Vec<WorkspaceConnection>, sqlx::Error> {
+            Ok(Vec::new())
+        }
+
         async fn list_for_workspace(
             &self,
             _workspace_id: Uuid,
@@ -3058,20 +3611,25 @@ mod tests {
                 client_secret: "secret".into(),
                 redirect_uri: "http://localhost".into(),
             },
+            github: OAuthProviderConfig {
+                client_id: "id".into(),
+                client_secret: "secret".into(),
+                redirect_uri: "http://localhost".into(),
+            },
             token_encryption_key: vec![0u8; 32],
         };
         let service = OAuthAccountService::new(repo, workspace_repo, key, client, &settings);
         assert_eq!(
             service.google_scopes(),
-            "openid email https://www.googleapis.com/auth/spreadsheets"
+            "openid email https://www.googleapis.com/auth/spreadsheets https://www.googleapis.com/auth/calendar https://www.googleapis.com/auth/drive"
         );
         assert_eq!(
             service.microsoft_scopes(),
-            "offline_access User.Read Team.ReadBasic.All Channel.ReadBasic.All ChannelMember.Read.All ChannelMessage.Send"
+            "offline_access User.Read Team.ReadBasic.All Channel.ReadBasic.All ChannelMember.Read.All ChannelMessage.Send ChannelMessage.Read.All Mail.Send Mail.Read Calendars.ReadWrite"
         );
         assert_eq!(
             service.slack_scopes(),
-            "chat:write,channels:read,groups:read,im:read,mpim:read,users:read,users:read.email"
+            "chat:write,channels:read,groups:read,im:read,mpim:read,users:read,users:read.email,reactions:write,files:write,im:write"
         );
         assert_eq!(service.asana_scopes(), "default email");
     }
@@ -3129,6 +3687,11 @@ mod tests {
                 client_secret: "secret".into(),
                 redirect_uri: "http://localhost/asana".into(),
             },
+            github: OAuthProviderConfig {
+                client_id: "client".into(),
+                client_secret: "secret".into(),
+                redirect_uri: "http://localhost/asana".into(),
+            },
             token_encryption_key: vec![0u8; 32],
         };
 
@@ -3204,6 +3767,11 @@ mod tests {
                 client_secret: "secret".into(),
                 redirect_uri: "http://localhost/asana".into(),
             },
+            github: OAuthProviderConfig {
+                client_id: "client".into(),
+                client_secret: "secret".into(),
+                redirect_uri: "http://localhost/asana".into(),
+            },
             token_encryption_key: vec![0u8; 32],
         };
 
@@ -3734,6 +4302,13 @@ mod tests {
             Ok(Vec::new())
         }
 
+        async fn list_slack_by_team(
+            &self,
+            _slack_team_id: &str,
+        ) -> Result<Vec<WorkspaceConnection>, sqlx::Error> {
+            Ok(Vec::new())
+        }
+
         async fn list_for_workspace(
             &self,
             _workspace_id: Uuid,
@@ -3936,6 +4511,11 @@ mod tests {
                     client_secret: "secret".into(),
                     redirect_uri: "http://localhost/asana".into(),
                 },
+                github: OAuthProviderConfig {
+                    client_id: "client".into(),
+                    client_secret: "secret".into(),
+                    redirect_uri: "http://localhost/asana".into(),
+                },
                 token_encryption_key: (*key).clone(),
             },
         );
@@ -4026,6 +4606,11 @@ mod tests {
                     client_secret: "secret".into(),
                     redirect_uri: "http://localhost/asana".into(),
                 },
+                github: OAuthProviderConfig {
+                    client_id: "client".into(),
+                    client_secret: "secret".into(),
+                    redirect_uri: "http://localhost/asana".into(),
+                },
                 token_encryption_key: vec![0u8; 32],
             },
         );
@@ -4092,6 +4677,11 @@ mod tests {
                     client_secret: "secret".into(),
                     redirect_uri: "http://localhost/asana".into(),
                 },
+                github: OAuthProviderConfig {
+                    client_id: "client".into(),
+                    client_secret: "secret".into(),
+                    redirect_uri: "http://localhost/asana".into(),
+                },
                 token_encryption_key: vec![0u8; 32],
             },
         );
@@ -4171,6 +4761,11 @@ mod tests {
                     client_secret: "secret".into(),
                     redirect_uri: "http://localhost/asana".into(),
                 },
+                github: OAuthProviderConfig {
+                    client_id: "client".into(),
+                    client_secret: "secret".into(),
+                    redirect_uri: "http://localhost/asana".into(),
+                },
                 token_encryption_key: (*key).clone(),
             },
         );
@@ -4265,6 +4860,11 @@ mod tests {
                     client_secret: "secret".into(),
                     redirect_uri: "http://localhost/asana".into(),
                 },
+                github: OAuthProviderConfig {
+                    client_id: "client".into(),
+                    client_secret: "secret".into(),
+                    redirect_uri: "http://localhost/asana".into(),
+                },
                 token_encryption_key: (*key).clone(),
             },
         );
@@ -4322,6 +4922,7 @@ mod tests {
         let encrypted_access = encrypt_secret(&key, "old-access").expect("encrypt access");
         let encrypted_refresh = encrypt_secret(&key, "old-refresh").expect("encrypt refresh");
         let metadata = serialize_token_metadata(OAuthTokenMetadata {
+            custom: None,
             slack: None,
             notion: None,
             provider_user_id: Some("google-123".into()),
@@ -4400,6 +5001,11 @@ mod tests {
                     client_secret: "secret".into(),
                     redirect_uri: "http://localhost/asana".into(),
                 },
+                github: OAuthProviderConfig {
+                    client_id: "client".into(),
+                    client_secret: "secret".into(),
+                    redirect_uri: "http://localhost/asana".into(),
+                },
                 token_encryption_key: (*key).clone(),
             },
         );
@@ -4447,6 +5053,7 @@ mod tests {
         let encrypted_access = encrypt_secret(&key, "old-access").expect("encrypt access");
         let encrypted_refresh = encrypt_secret(&key, "old-refresh").expect("encrypt refresh");
         let metadata = serialize_token_metadata(OAuthTokenMetadata {
+            custom: None,
             slack: Some(EncryptedSlackOAuthMetadata {
                 team_id: Some(encrypt_secret(&key, "T123").expect("encrypt team id")),
                 bot_user_id: None,
@@ -4542,6 +5149,11 @@ mod tests {
                     client_secret: "secret".into(),
                     redirect_uri: "http://localhost/asana".into(),
                 },
+                github: OAuthProviderConfig {
+                    client_id: "client".into(),
+                    client_secret: "secret".into(),
+                    redirect_uri: "http://localhost/asana".into(),
+                },
                 token_encryption_key: (*key).clone(),
             },
         )
@@ -4561,6 +5173,7 @@ mod tests {
         let key = Arc::new(vec![26u8; 32]);
 
         let metadata = serialize_token_metadata(OAuthTokenMetadata {
+            custom: None,
             slack: Some(EncryptedSlackOAuthMetadata {
                 team_id: Some(encrypt_secret(&key, "T123").expect("encrypt team id")),
                 bot_user_id: None,
@@ -4618,6 +5231,11 @@ mod tests {
                     client_secret: "secret".into(),
                     redirect_uri: "http://localhost/asana".into(),
                 },
+                github: OAuthProviderConfig {
+                    client_id: "client".into(),
+                    client_secret: "secret".into(),
+                    redirect_uri: "http://localhost/asana".into(),
+                },
                 token_encryption_key: (*key).clone(),
             },
         );
@@ -4664,6 +5282,7 @@ mod tests {
             expires_at: now,
             account_email: "old@example.com".into(),
             metadata: serialize_token_metadata(OAuthTokenMetadata {
+                custom: None,
                 slack: None,
                 notion: None,
                 provider_user_id: Some("google-456".into()),
@@ -4707,6 +5326,11 @@ mod tests {
                     client_secret: "secret".into(),
                     redirect_uri: "http://localhost/asana".into(),
                 },
+                github: OAuthProviderConfig {
+                    client_id: "client".into(),
+                    client_secret: "secret".into(),
+                    redirect_uri: "http://localhost/asana".into(),
+                },
                 token_encryption_key: (*key).clone(),
             },
         );
@@ -4808,6 +5432,11 @@ mod tests {
                     client_secret: "secret".into(),
                     redirect_uri: "http://localhost/asana".into(),
                 },
+                github: OAuthProviderConfig {
+                    client_id: "client".into(),
+                    client_secret: "secret".into(),
+                    redirect_uri: "http://localhost/asana".into(),
+                },
                 token_encryption_key: (*key).clone(),
             },
         );
@@ -4913,6 +5542,11 @@ mod tests {
                     client_secret: "secret".into(),
                     redirect_uri: "http://localhost/asana".into(),
                 },
+                github: OAuthProviderConfig {
+                    client_id: "client".into(),
+                    client_secret: "secret".into(),
+                    redirect_uri: "http://localhost/asana".into(),
+                },
                 token_encryption_key: (*key).clone(),
             },
         );
@@ -5001,6 +5635,7 @@ mod tests {
         let encrypted_access = encrypt_secret(&key, "slack-old-access").unwrap();
         let encrypted_refresh = encrypt_secret(&key, "slack-old-refresh").unwrap();
         let metadata = serialize_token_metadata(OAuthTokenMetadata {
+            custom: None,
             slack: None,
             notion: None,
             provider_user_id: Some("U123".into()),
@@ -5078,6 +5713,11 @@ mod tests {
                     client_secret: "secret".into(),
                     redirect_uri: "http://localhost/asana".into(),
                 },
+                github: OAuthProviderConfig {
+                    client_id: "client".into(),
+                    client_secret: "secret".into(),
+                    redirect_uri: "http://localhost/asana".into(),
+                },
                 token_encryption_key: (*key).clone(),
             },
         );
@@ -5151,6 +5791,11 @@ mod tests {
                     client_secret: "secret".into(),
                     redirect_uri: "http://localhost/asana".into(),
                 },
+                github: OAuthProviderConfig {
+                    client_id: "client".into(),
+                    client_secret: "secret".into(),
+                    redirect_uri: "http://localhost/asana".into(),
+                },
                 token_encryption_key: (*key).clone(),
             },
         );
@@ -5176,4 +5821,156 @@ mod tests {
         ));
         assert!(is_revocation_signal(None, "Token revoked by admin"));
     }
+
+    fn custom_client(
+        server: &httpmock::MockServer,
+        method: crate::services::oauth::custom_provider::TokenAuthMethod,
+    ) -> CustomOAuthClient {
+        CustomOAuthClient {
+            id: Uuid::new_v4(),
+            workspace_id: Uuid::new_v4(),
+            name: "Acme".into(),
+            authorize_url: server.url("/authorize"),
+            token_url: server.url("/token"),
+            userinfo_url: Some(server.url("/userinfo")),
+            scopes: "read".into(),
+            use_pkce: true,
+            client_id: "client-1".into(),
+            client_secret: Some("s3cret".into()),
+            token_auth_method: method,
+        }
+    }
+
+    #[tokio::test]
+    async fn custom_exchange_sends_verifier_and_reads_userinfo() {
+        use crate::services::oauth::custom_provider::TokenAuthMethod;
+
+        let server = httpmock::MockServer::start();
+        let token_mock = server.mock(|when, then| {
+            when.method(httpmock::Method::POST)
+                .path("/token")
+                .x_www_form_urlencoded_tuple("grant_type", "authorization_code")
+                .x_www_form_urlencoded_tuple("code", "auth-code")
+                .x_www_form_urlencoded_tuple("code_verifier", "verifier")
+                .x_www_form_urlencoded_tuple("client_id", "client-1")
+                .x_www_form_urlencoded_tuple("client_secret", "s3cret");
+            then.status(200).json_body(serde_json::json!({
+                "access_token": "access",
+                "refresh_token": "refresh",
+                "expires_in": "3600"
+            }));
+        });
+        let userinfo_mock = server.mock(|when, then| {
+            when.method(httpmock::Method::GET)
+                .path("/userinfo")
+                .header("authorization", "Bearer access");
+            then.status(200)
+                .json_body(serde_json::json!({ "sub": "u-1", "email": "a@acme.test" }));
+        });
+
+        let service = OAuthAccountService::test_stub();
+        let provider = custom_client(&server, TokenAuthMethod::ClientSecretPost);
+        let tokens = service
+            .exchange_custom_code(
+                &provider,
+                "auth-code",
+                "https://app.test/cb",
+                Some("verifier"),
+            )
+            .await
+            .expect("exchange succeeds");
+
+        token_mock.assert();
+        userinfo_mock.assert();
+        assert_eq!(tokens.access_token, "access");
+        assert_eq!(tokens.refresh_token, "refresh");
+        assert_eq!(tokens.account_email, "a@acme.test");
+        assert_eq!(tokens.provider_user_id.as_deref(), Some("u-1"));
+        assert!(tokens.expires_at < OffsetDateTime::now_utc() + Duration::hours(2));
+    }
+
+    #[tokio::test]
+    async fn custom_refresh_uses_basic_auth_and_maps_invalid_grant() {
+        use crate::services::oauth::custom_provider::TokenAuthMethod;
+
+        let server = httpmock::MockServer::start();
+        let ok_mock = server.mock(|when, then| {
+            when.method(httpmock::Method::POST)
+                .path("/token")
+                .header_exists("authorization")
+                .x_www_form_urlencoded_tuple("refresh_token", "good");
+            then.status(200)
+                .json_body(serde_json::json!({ "access_token": "fresh" }));
+        });
+        let revoked_mock = server.mock(|when, then| {
+            when.method(httpmock::Method::POST)
+                .path("/token")
+                .x_www_form_urlencoded_tuple("refresh_token", "stale");
+            then.status(400)
+                .json_body(serde_json::json!({ "error": "invalid_grant" }));
+        });
+
+        let service = OAuthAccountService::test_stub();
+        let provider = custom_client(&server, TokenAuthMethod::ClientSecretBasic);
+
+        let tokens = service
+            .refresh_custom_token(&provider, "good")
+            .await
+            .expect("refresh succeeds");
+        ok_mock.assert();
+        assert_eq!(tokens.access_token, "fresh");
+        assert_eq!(tokens.refresh_token, "good");
+
+        let err = service
+            .refresh_custom_token(&provider, "stale")
+            .await
+            .expect_err("revoked refresh token");
+        revoked_mock.assert();
+        assert!(matches!(
+            err,
+            OAuthAccountError::TokenRevoked {
+                provider: ConnectedOAuthProvider::Custom
+            }
+        ));
+    }
+
+    #[tokio::test]
+    async fn custom_endpoints_follow_egress_rules_on_every_hop() {
+        use crate::services::oauth::custom_provider::TokenAuthMethod;
+
+        let server = httpmock::MockServer::start();
+        let redirect = server.mock(|when, then| {
+            when.method(httpmock::Method::POST).path("/token");
+            then.status(307)
+                .header("location", server.url("/internal/token"));
+        });
+        let internal = server.mock(|when, then| {
+            when.path("/internal/token");
+            then.status(200)
+                .json_body(serde_json::json!({ "access_token": "leaked" }));
+        });
+
+        let mut service = (*OAuthAccountService::test_stub()).clone();
+        service.set_custom_egress(TriggerEgress::with_denylist(&["127.0.0.1"], false));
+
+        let mut provider = custom_client(&server, TokenAuthMethod::ClientSecretPost);
+        let err = service
+            .refresh_custom_token(&provider, "refresh")
+            .await
+            .expect_err("denylisted token endpoint");
+        assert!(
+            matches!(err, OAuthAccountError::EgressBlocked(_)),
+            "{err:?}"
+        );
+
+        // Reached through a hostname, the endpoint itself passes but its
+        // redirect to the blocked address is not followed.
+        provider.token_url = format!("http://localhost:{}/token", server.port());
+        service
+            .refresh_custom_token(&provider, "refresh")
+            .await
+            .expect_err("redirect to a denylisted host");
+        redirect.assert_hits(1);
+        internal.assert_hits(0);
+    }
 }
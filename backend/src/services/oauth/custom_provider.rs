@@ -0,0 +1,309 @@
+//! Workspace-defined OAuth2 providers. A definition carries the endpoints,
+//! scopes and client credentials that the built-in providers read from
+//! `Config`; connections made through one are stored as
+//! `ConnectedOAuthProvider::Custom` tokens whose metadata names the definition.
+
+use base64::Engine as _;
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use time::{Duration, OffsetDateTime};
+use uuid::Uuid;
+
+use crate::models::oauth_token::CustomOAuthProvider;
+use crate::utils::encryption::{decrypt_secret, EncryptionError};
+
+/// Used when the token endpoint omits `expires_in`: such tokens are treated
+/// as long-lived and never refreshed proactively.
+pub const CUSTOM_ACCESS_TTL_DAYS: i64 = 3650;
+
+/// Timeout for token and userinfo requests to a custom provider.
+pub const CUSTOM_REQUEST_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TokenAuthMethod {
+    /// `client_id`/`client_secret` in the form body.
+    ClientSecretPost,
+    /// HTTP basic auth with the client credentials.
+    ClientSecretBasic,
+}
+
+impl TokenAuthMethod {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            TokenAuthMethod::ClientSecretPost => "client_secret_post",
+            TokenAuthMethod::ClientSecretBasic => "client_secret_basic",
+        }
+    }
+
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "" | "client_secret_post" | "post" => Some(TokenAuthMethod::ClientSecretPost),
+            "client_secret_basic" | "basic" => Some(TokenAuthMethod::ClientSecretBasic),
+            _ => None,
+        }
+    }
+}
+
+/// Stored in `OAuthTokenMetadata.custom` for every custom connection so
+/// refreshes can find the definition and promotion can check its workspace.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CustomOAuthMetadata {
+    pub provider_id: Uuid,
+    pub workspace_id: Uuid,
+}
+
+/// A provider definition with its client credentials decrypted.
+#[derive(Debug, Clone)]
+pub struct CustomOAuthClient {
+    pub id: Uuid,
+    pub workspace_id: Uuid,
+    pub name: String,
+    pub authorize_url: String,
+    pub token_url: String,
+    pub userinfo_url: Option<String>,
+    pub scopes: String,
+    pub use_pkce: bool,
+    pub client_id: String,
+    pub client_secret: Option<String>,
+    pub token_auth_method: TokenAuthMethod,
+}
+
+impl CustomOAuthClient {
+    pub fn decrypt(record: &CustomOAuthProvider, key: &[u8]) -> Result<Self, EncryptionError> {
+        Ok(Self {
+            id: record.id,
+            workspace_id: record.workspace_id,
+            name: record.name.clone(),
+            authorize_url: record.authorize_url.clone(),
+            token_url: record.token_url.clone(),
+            userinfo_url: record.userinfo_url.clone(),
+            scopes: record.scopes.clone(),
+            use_pkce: record.use_pkce,
+            client_id: decrypt_secret(key, &record.client_id)?,
+            client_secret: record
+                .client_secret
+                .as_deref()
+                .map(|secret| decrypt_secret(key, secret))
+                .transpose()?,
+            token_auth_method: TokenAuthMethod::parse(&record.token_auth_method)
+                .unwrap_or(TokenAuthMethod::ClientSecretPost),
+        })
+    }
+
+    pub fn metadata(&self) -> CustomOAuthMetadata {
+        CustomOAuthMetadata {
+            provider_id: self.id,
+            workspace_id: self.workspace_id,
+        }
+    }
+
+    /// Builds the authorization redirect. `code_verifier` is required when
+    /// the provider uses PKCE; only its S256 challenge leaves the server.
+    pub fn authorization_url(
+        &self,
+        redirect_uri: &str,
+        state: &str,
+        code_verifier: Option<&str>,
+    ) -> Result<Url, String> {
+        let mut url = Url::parse(&self.authorize_url)
+            .map_err(|err| format!("Invalid authorize URL: {err}"))?;
+        {
+            let mut query = url.query_pairs_mut();
+            query
+                .append_pair("response_type", "code")
+                .append_pair("client_id", &self.client_id)
+                .append_pair("redirect_uri", redirect_uri)
+                .append_pair("state", state);
+            if !self.scopes.is_empty() {
+                query.append_pair("scope", &self.scopes);
+            }
+            if let Some(verifier) = code_verifier {
+                query
+                    .append_pair("code_challenge", &pkce_challenge(verifier))
+                    .append_pair("code_challenge_method", "S256");
+            }
+        }
+        Ok(url)
+    }
+
+    /// Applies the configured client authentication to a token request.
+    pub(crate) fn authenticate(
+        &self,
+        request: reqwest::RequestBuilder,
+        form: &mut Vec<(&'static str, String)>,
+    ) -> reqwest::RequestBuilder {
+        match (self.token_auth_method, self.client_secret.as_deref()) {
+            (TokenAuthMethod::ClientSecretBasic, Some(secret)) => {
+                request.basic_auth(&self.client_id, Some(secret))
+            }
+            (_, secret) => {
+                form.push(("client_id", self.client_id.clone()));
+                if let Some(secret) = secret {
+                    form.push(("client_secret", secret.to_string()));
+                }
+                request
+            }
+        }
+    }
+}
+
+/// Normalizes a scope list given as spaces and/or commas to the
+/// space-separated form OAuth2 expects.
+pub fn normalize_scopes(raw: &str) -> String {
+    raw.split([',', ' '])
+        .map(str::trim)
+        .filter(|scope| !scope.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// RFC 7636 S256 challenge for a verifier.
+pub fn pkce_challenge(verifier: &str) -> String {
+    base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes()))
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct CustomTokenResponse {
+    #[serde(default)]
+    pub(crate) access_token: Option<String>,
+    #[serde(default)]
+    pub(crate) refresh_token: Option<String>,
+    #[serde(default)]
+    pub(crate) expires_in: Option<Value>,
+    #[serde(default)]
+    pub(crate) error: Option<String>,
+    #[serde(default)]
+    pub(crate) error_description: Option<String>,
+}
+
+impl CustomTokenResponse {
+    /// `expires_in` is a number in the spec, but some providers send a string.
+    pub(crate) fn expires_at(&self) -> OffsetDateTime {
+        let seconds = match &self.expires_in {
+            Some(Value::Number(n)) => n.as_i64(),
+            Some(Value::String(s)) => s.trim().parse().ok(),
+            _ => None,
+        }
+        .filter(|seconds| *seconds > 0);
+        OffsetDateTime::now_utc()
+            + seconds
+                .map(Duration::seconds)
+                .unwrap_or_else(|| Duration::days(CUSTOM_ACCESS_TTL_DAYS))
+    }
+}
+
+/// Picks an account label and a stable user id out of a userinfo response.
+pub(crate) fn identity_from_userinfo(body: &Value) -> (Option<String>, Option<String>) {
+    let label = ["email", "preferred_username", "login", "username", "name"]
+        .iter()
+        .find_map(|key| body.get(*key).and_then(|v| v.as_str()))
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(str::to_string);
+    let id = ["sub", "id", "user_id"]
+        .iter()
+        .find_map(|key| match body.get(*key) {
+            Some(Value::String(s)) => Some(s.clone()),
+            Some(Value::Number(n)) => Some(n.to_string()),
+            _ => None,
+        });
+    (label, id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn client(method: TokenAuthMethod, secret: Option<&str>) -> CustomOAuthClient {
+        CustomOAuthClient {
+            id: Uuid::new_v4(),
+            workspace_id: Uuid::new_v4(),
+            name: "Acme".into(),
+            authorize_url: "https://auth.acme.test/authorize?prompt=consent".into(),
+            token_url: "https://auth.acme.test/token".into(),
+            userinfo_url: None,
+            scopes: "read write".into(),
+            use_pkce: true,
+            client_id: "client-1".into(),
+            client_secret: secret.map(str::to_string),
+            token_auth_method: method,
+        }
+    }
+
+    #[test]
+    fn pkce_challenge_matches_rfc_7636_example() {
+        assert_eq!(
+            pkce_challenge("dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk"),
+            "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM"
+        );
+    }
+
+    #[test]
+    fn authorization_url_keeps_existing_query_and_sends_only_the_challenge() {
+        let provider = client(TokenAuthMethod::ClientSecretPost, Some("secret"));
+        let url = provider
+            .authorization_url("https://app.test/cb", "nonce", Some("verifier"))
+            .unwrap();
+        let pairs: Vec<(String, String)> = url.query_pairs().into_owned().collect();
+        let get = |key: &str| {
+            pairs
+                .iter()
+                .find(|(k, _)| k == key)
+                .map(|(_, v)| v.as_str())
+        };
+
+        assert_eq!(get("prompt"), Some("consent"));
+        assert_eq!(get("client_id"), Some("client-1"));
+        assert_eq!(get("scope"), Some("read write"));
+        assert_eq!(get("state"), Some("nonce"));
+        assert_eq!(
+            get("code_challenge"),
+            Some(pkce_challenge("verifier").as_str())
+        );
+        assert_eq!(get("code_challenge_method"), Some("S256"));
+        assert!(!url.as_str().contains("verifier"));
+        assert!(!url.as_str().contains("secret"));
+    }
+
+    #[test]
+    fn public_clients_fall_back_to_client_id_in_the_body() {
+        let http = reqwest::Client::new();
+        let mut form = Vec::new();
+        let request = client(TokenAuthMethod::ClientSecretBasic, None)
+            .authenticate(http.post("https://auth.acme.test/token"), &mut form)
+            .build()
+            .unwrap();
+        assert!(request.headers().get("authorization").is_none());
+        assert_eq!(form, vec![("client_id", "client-1".to_string())]);
+
+        let mut form = Vec::new();
+        let request = client(TokenAuthMethod::ClientSecretBasic, Some("secret"))
+            .authenticate(http.post("https://auth.acme.test/token"), &mut form)
+            .build()
+            .unwrap();
+        assert!(request.headers().get("authorization").is_some());
+        assert!(form.is_empty());
+    }
+
+    #[test]
+    fn userinfo_identity_prefers_email_and_sub() {
+        assert_eq!(
+            identity_from_userinfo(&json!({ "sub": "u-1", "email": "a@b.test", "name": "A" })),
+            (Some("a@b.test".to_string()), Some("u-1".to_string()))
+        );
+        assert_eq!(
+            identity_from_userinfo(&json!({ "id": 42, "login": "octo" })),
+            (Some("octo".to_string()), Some("42".to_string()))
+        );
+    }
+
+    #[test]
+    fn scopes_accept_commas_and_spaces() {
+        assert_eq!(normalize_scopes(" read, write  admin,"), "read write admin");
+    }
+}
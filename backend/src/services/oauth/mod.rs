@@ -1,4 +1,5 @@
 pub mod account_service;
+pub mod custom_provider;
 pub mod github;
 pub mod google;
 pub mod workspace_service;
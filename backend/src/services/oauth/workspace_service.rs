@@ -74,6 +74,18 @@ pub trait WorkspaceTokenRefresher: Send + Sync {
         provider: ConnectedOAuthProvider,
         refresh_token: &str,
     ) -> Result<AuthorizationTokens, OAuthAccountError>;
+
+    /// Refreshes using the connection's metadata, which custom providers need
+    /// to locate their definition.
+    async fn refresh_connection_tokens(
+        &self,
+        provider: ConnectedOAuthProvider,
+        refresh_token: &str,
+        metadata: &serde_json::Value,
+    ) -> Result<AuthorizationTokens, OAuthAccountError> {
+        let _ = metadata;
+        self.refresh_access_token(provider, refresh_token).await
+    }
 }
 
 #[async_trait::async_trait]
@@ -85,6 +97,16 @@ impl WorkspaceTokenRefresher for OAuthAccountService {
     ) -> Result<AuthorizationTokens, OAuthAccountError> {
         OAuthAccountService::refresh_access_token(self, provider, refresh_token).await
     }
+
+    async fn refresh_connection_tokens(
+        &self,
+        provider: ConnectedOAuthProvider,
+        refresh_token: &str,
+        metadata: &serde_json::Value,
+    ) -> Result<AuthorizationTokens, OAuthAccountError> {
+        OAuthAccountService::refresh_connection_tokens(self, provider, refresh_token, metadata)
+            .await
+    }
 }
 
 #[derive(Clone)]
@@ -150,6 +172,14 @@ impl WorkspaceOAuthService {
         let existing_provider_user_id = existing_metadata.provider_user_id.clone();
         let mut provider_user_id = existing_provider_user_id.clone();
         let notion_meta = existing_metadata.notion.clone();
+        let custom_meta = existing_metadata.custom.clone();
+
+        // Custom providers belong to one workspace and cannot be shared elsewhere.
+        if provider == ConnectedOAuthProvider::Custom
+            && custom_meta.as_ref().map(|custom| custom.workspace_id) != Some(workspace_id)
+        {
+            return Err(WorkspaceOAuthError::Forbidden);
+        }
 
         if provider == ConnectedOAuthProvider::Slack {
             let refresh_plain = decrypt_secret(&self.encryption_key, &token.refresh_token)?;
@@ -178,6 +208,7 @@ impl WorkspaceOAuthService {
                 slack: slack_meta.clone(),
                 provider_user_id: provider_user_id.clone(),
                 notion: notion_meta.clone(),
+                custom: custom_meta.clone(),
             });
 
             let _ = self
@@ -214,6 +245,7 @@ impl WorkspaceOAuthService {
                 slack: None,
                 provider_user_id: provider_user_id.clone(),
                 notion: notion_meta.clone(),
+                custom: custom_meta.clone(),
             })
         };
 
@@ -248,6 +280,7 @@ impl WorkspaceOAuthService {
                 slack: clear_webhook(slack_meta.clone()),
                 provider_user_id: provider_user_id.clone(),
                 notion: notion_meta.clone(),
+                custom: custom_meta.clone(),
             });
 
             let _ = self
@@ -590,7 +623,11 @@ impl WorkspaceOAuthService {
         if decrypted.expires_at <= refresh_deadline {
             let refreshed = match self
                 .oauth_accounts
-                .refresh_access_token(decrypted.provider, &decrypted.refresh_token)
+                .refresh_connection_tokens(
+                    decrypted.provider,
+                    &decrypted.refresh_token,
+                    &record.metadata,
+                )
                 .await
             {
                 Ok(tokens) => tokens,
@@ -806,6 +843,8 @@ impl WorkspaceOAuthService {
             ConnectedOAuthProvider::Slack => 2,
             ConnectedOAuthProvider::Asana => 3,
             ConnectedOAuthProvider::Notion => 4,
+            ConnectedOAuthProvider::GitHub => 5,
+            ConnectedOAuthProvider::Custom => 6,
         }
     }
 
@@ -1043,6 +1082,13 @@ impl WorkspaceOAuthService {
                 Ok(Vec::new())
             }
 
+            async fn list_slack_by_team(
+                &self,
+                _slack_team_id: &str,
+            ) -> Result<Vec<WorkspaceConnection>, sqlx::Error> {
+                Ok(Vec::new())
+            }
+
             async fn list_for_workspace(
                 &self,
                 _workspace_id: Uuid,
@@ -1692,6 +1738,13 @@ mod tests {
                 .await
         }
 
+        async fn list_slack_by_team(
+            &self,
+            _slack_team_id: &str,
+        ) -> Result<Vec<WorkspaceConnection>, sqlx::Error> {
+            Ok(Vec::new())
+        }
+
         async fn list_for_workspace(
             &self,
             workspace_id: Uuid,
@@ -2426,6 +2479,13 @@ mod tests {
                 .await
         }
 
+        async fn list_slack_by_team(
+            &self,
+            _slack_team_id: &str,
+        ) -> Result<Vec<WorkspaceConnection>, sqlx::Error> {
+            Ok(Vec::new())
+        }
+
         async fn list_for_workspace(
             &self,
             _workspace_id: Uuid,
@@ -2758,6 +2818,58 @@ mod tests {
         assert!(matches!(err, WorkspaceOAuthError::SlackInstallRequired));
     }
 
+    #[tokio::test]
+    async fn promote_connection_with_token_rejects_custom_provider_from_other_workspace() {
+        let user_id = Uuid::new_v4();
+        let workspace_id = Uuid::new_v4();
+        let key = Arc::new(vec![7u8; 32]);
+        let token_id = Uuid::new_v4();
+
+        let user_repo = Arc::new(InMemoryUserRepo {
+            token: Mutex::new(Some(UserOAuthToken {
+                id: token_id,
+                user_id,
+                workspace_id: None,
+                provider: ConnectedOAuthProvider::Custom,
+                access_token: encrypt_secret(&key, "access").unwrap(),
+                refresh_token: encrypt_secret(&key, "refresh").unwrap(),
+                expires_at: OffsetDateTime::now_utc() + Duration::hours(1),
+                account_email: "user@acme.test".into(),
+                metadata: serde_json::json!({
+                    "custom": {
+                        "providerId": Uuid::new_v4(),
+                        "workspaceId": Uuid::new_v4(),
+                    }
+                }),
+                is_shared: false,
+                created_at: OffsetDateTime::now_utc(),
+                updated_at: OffsetDateTime::now_utc(),
+            })),
+            shared_flag: Mutex::new(false),
+        });
+        let workspace_repo = Arc::new(InMemoryWorkspaceRepo::new());
+        let service = WorkspaceOAuthService::new(
+            user_repo,
+            noop_membership_repo(),
+            workspace_repo.clone(),
+            OAuthAccountService::test_stub() as Arc<dyn WorkspaceTokenRefresher>,
+            key,
+        );
+
+        let err = service
+            .promote_connection_with_token(
+                workspace_id,
+                user_id,
+                ConnectedOAuthProvider::Custom,
+                Some(token_id),
+            )
+            .await
+            .expect_err("custom providers stay within their workspace");
+
+        assert!(matches!(err, WorkspaceOAuthError::Forbidden));
+        assert!(workspace_repo.connection.lock().unwrap().is_none());
+    }
+
     #[tokio::test]
     async fn install_slack_workspace_connection_requires_team_id() {
         let workspace_id = Uuid::new_v4();
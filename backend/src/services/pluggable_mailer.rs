@@ -1,16 +1,17 @@
 use async_trait::async_trait;
 use lettre::{
-    message::Mailbox,
     transport::smtp::{
         authentication::Credentials,
         client::{Tls, TlsParameters},
     },
-    AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor,
+    AsyncSmtpTransport, AsyncTransport, Tokio1Executor,
 };
 use reqwest::Client;
 use std::sync::Arc;
 
-use crate::services::smtp_mailer::{MailError, Mailer, SmtpConfig, TlsMode};
+use crate::services::smtp_mailer::{
+    build_message, MailAttachment, MailError, Mailer, SmtpConfig, TlsMode,
+};
 
 use super::mailjet_mailer::MailjetMailer;
 use super::sendgrid_mailer::SendgridMailer;
@@ -71,11 +72,12 @@ impl PluggableMailer {
         recipients: &[String],
         subject: &str,
         body: &str,
+        attachments: &[MailAttachment],
     ) -> Result<(), MailError> {
         // If we have an SMTP mailer (provider=smtp), delegate to its implementation
         if let Some(smtp) = &self.smtp_runtime {
             return smtp
-                .send_email_with_config(config, recipients, subject, body)
+                .send_email_with_attachments(config, recipients, subject, body, attachments)
                 .await;
         }
 
@@ -123,12 +125,7 @@ impl PluggableMailer {
 
         let transport = builder.build();
 
-        let from_mailbox: Mailbox = config.from.parse()?;
-        let mut msg_builder = Message::builder().from(from_mailbox);
-        for r in recipients {
-            msg_builder = msg_builder.to(r.parse()?);
-        }
-        let email = msg_builder.subject(subject).body(body.to_string())?;
+        let email = build_message(&config.from, recipients, subject, body, attachments)?;
 
         transport.send(email).await.map(|_| ()).map_err(|error| {
             tracing::error!(
@@ -193,7 +190,19 @@ impl Mailer for PluggableMailer {
         subject: &str,
         body: &str,
     ) -> Result<(), MailError> {
-        self.send_runtime_smtp(config, recipients, subject, body)
+        self.send_runtime_smtp(config, recipients, subject, body, &[])
+            .await
+    }
+
+    async fn send_email_with_attachments(
+        &self,
+        config: &SmtpConfig,
+        recipients: &[String],
+        subject: &str,
+        body: &str,
+        attachments: &[MailAttachment],
+    ) -> Result<(), MailError> {
+        self.send_runtime_smtp(config, recipients, subject, body, attachments)
             .await
     }
 
@@ -0,0 +1,373 @@
+//! Redis Streams and Pub/Sub publishing, plus consumer-group reads for the
+//! `redis.stream_message` trigger.
+//!
+//! Pub/Sub has no acknowledgements, so it is publish-only; triggers always
+//! read from a stream through a consumer group.
+
+use std::future::Future;
+use std::time::Duration;
+
+use redis::aio::MultiplexedConnection;
+use redis::{ConnectionAddr, Value as RedisValue};
+use serde_json::{Map, Value};
+use thiserror::Error;
+
+pub const REDIS_STREAM_MESSAGE: &str = "redis.stream_message";
+
+const OPERATION_TIMEOUT: Duration = Duration::from_secs(15);
+
+#[derive(Debug, Error)]
+pub enum RedisStreamError {
+    #[error("{0}")]
+    Config(String),
+    #[error("timed out talking to Redis")]
+    Timeout,
+    #[error("Redis error: {0}")]
+    Redis(#[from] redis::RedisError),
+    #[error("unexpected Redis reply: {0}")]
+    Reply(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StreamEntry {
+    pub id: String,
+    pub fields: Map<String, Value>,
+    /// Total size of field names and values, in bytes.
+    pub size: usize,
+}
+
+/// Host and port of a `redis://` or `rediss://` URL, for egress checks.
+/// Unix sockets are rejected because they bypass the egress policy.
+pub fn redis_address(url: &str) -> Result<(String, u16), RedisStreamError> {
+    let client = open(url)?;
+    match &client.get_connection_info().addr {
+        ConnectionAddr::Tcp(host, port) | ConnectionAddr::TcpTls { host, port, .. } => {
+            if host.is_empty() {
+                return Err(RedisStreamError::Config(
+                    "Redis connection URL has no host".to_string(),
+                ));
+            }
+            Ok((host.clone(), *port))
+        }
+        _ => Err(RedisStreamError::Config(
+            "Redis Unix socket connections are not supported".to_string(),
+        )),
+    }
+}
+
+fn open(url: &str) -> Result<redis::Client, RedisStreamError> {
+    let url = url.trim();
+    if !["redis://", "rediss://", "redis+unix://", "unix://"]
+        .iter()
+        .any(|scheme| url.starts_with(scheme))
+    {
+        return Err(RedisStreamError::Config(
+            "Redis connection URL must start with redis:// or rediss://".to_string(),
+        ));
+    }
+    redis::Client::open(url)
+        .map_err(|_| RedisStreamError::Config("Redis connection URL is not valid".to_string()))
+}
+
+async fn bounded<T, F>(fut: F) -> Result<T, RedisStreamError>
+where
+    F: Future<Output = redis::RedisResult<T>>,
+{
+    tokio::time::timeout(OPERATION_TIMEOUT, fut)
+        .await
+        .map_err(|_| RedisStreamError::Timeout)?
+        .map_err(RedisStreamError::from)
+}
+
+pub struct RedisSession {
+    con: MultiplexedConnection,
+}
+
+impl RedisSession {
+    pub async fn connect(url: &str) -> Result<Self, RedisStreamError> {
+        redis_address(url)?;
+        let client = open(url)?;
+        let con = bounded(client.get_multiplexed_async_connection()).await?;
+        Ok(Self { con })
+    }
+
+    /// Appends an entry and returns its id. `max_len` trims the stream
+    /// approximately (`MAXLEN ~`), which is much cheaper than exact trimming.
+    pub async fn xadd(
+        &mut self,
+        stream: &str,
+        fields: &[(String, String)],
+        max_len: Option<u64>,
+    ) -> Result<String, RedisStreamError> {
+        if fields.is_empty() {
+            return Err(RedisStreamError::Config(
+                "Stream entries need at least one field".to_string(),
+            ));
+        }
+        let mut cmd = redis::cmd("XADD");
+        cmd.arg(stream);
+        if let Some(max_len) = max_len {
+            cmd.arg("MAXLEN").arg("~").arg(max_len);
+        }
+        cmd.arg("*");
+        for (key, value) in fields {
+            cmd.arg(key).arg(value);
+        }
+        bounded(cmd.query_async::<String>(&mut self.con)).await
+    }
+
+    /// Publishes to a Pub/Sub channel and returns how many subscribers
+    /// received the message.
+    pub async fn publish(&mut self, channel: &str, message: &str) -> Result<i64, RedisStreamError> {
+        bounded(
+            redis::cmd("PUBLISH")
+                .arg(channel)
+                .arg(message)
+                .query_async::<i64>(&mut self.con),
+        )
+        .await
+    }
+
+    /// Creates the consumer group if it does not exist yet. New groups only
+    /// see entries added after they were created.
+    pub async fn ensure_group(
+        &mut self,
+        stream: &str,
+        group: &str,
+    ) -> Result<(), RedisStreamError> {
+        let created = bounded(
+            redis::cmd("XGROUP")
+                .arg("CREATE")
+                .arg(stream)
+                .arg(group)
+                .arg("$")
+                .arg("MKSTREAM")
+                .query_async::<()>(&mut self.con),
+        )
+        .await;
+        match created {
+            Err(RedisStreamError::Redis(err)) if err.code() == Some("BUSYGROUP") => Ok(()),
+            other => other,
+        }
+    }
+
+    /// Reads up to `count` entries for `consumer`. Entries delivered earlier
+    /// but never acknowledged come first, then new entries.
+    pub async fn read_group(
+        &mut self,
+        stream: &str,
+        group: &str,
+        consumer: &str,
+        count: usize,
+    ) -> Result<Vec<StreamEntry>, RedisStreamError> {
+        let mut entries = self.xreadgroup(stream, group, consumer, count, "0").await?;
+        if entries.len() < count {
+            let fresh = self
+                .xreadgroup(stream, group, consumer, count - entries.len(), ">")
+                .await?;
+            entries.extend(fresh);
+        }
+        Ok(entries)
+    }
+
+    async fn xreadgroup(
+        &mut self,
+        stream: &str,
+        group: &str,
+        consumer: &str,
+        count: usize,
+        start: &str,
+    ) -> Result<Vec<StreamEntry>, RedisStreamError> {
+        let reply = bounded(
+            redis::cmd("XREADGROUP")
+                .arg("GROUP")
+                .arg(group)
+                .arg(consumer)
+                .arg("COUNT")
+                .arg(count)
+                .arg("STREAMS")
+                .arg(stream)
+                .arg(start)
+                .query_async::<RedisValue>(&mut self.con),
+        )
+        .await?;
+        parse_read_reply(&reply)
+    }
+
+    pub async fn xack(
+        &mut self,
+        stream: &str,
+        group: &str,
+        ids: &[String],
+    ) -> Result<(), RedisStreamError> {
+        if ids.is_empty() {
+            return Ok(());
+        }
+        bounded(
+            redis::cmd("XACK")
+                .arg(stream)
+                .arg(group)
+                .arg(ids)
+                .query_async::<i64>(&mut self.con),
+        )
+        .await
+        .map(|_| ())
+    }
+}
+
+/// Parses an `XREADGROUP` reply for a single stream. Pending entries that
+/// were deleted from the stream come back without fields; they are returned
+/// with an empty map so they can still be acknowledged.
+fn parse_read_reply(reply: &RedisValue) -> Result<Vec<StreamEntry>, RedisStreamError> {
+    let streams = match reply {
+        RedisValue::Nil => return Ok(Vec::new()),
+        RedisValue::Array(streams) => streams,
+        other => return Err(RedisStreamError::Reply(format!("{other:?}"))),
+    };
+    let mut entries = Vec::new();
+    for stream in streams {
+        let Some(RedisValue::Array(items)) = as_array(stream).and_then(|s| s.get(1)) else {
+            return Err(RedisStreamError::Reply(
+                "stream without entries".to_string(),
+            ));
+        };
+        for item in items {
+            let parts = as_array(item)
+                .ok_or_else(|| RedisStreamError::Reply("malformed stream entry".to_string()))?;
+            let id = parts
+                .first()
+                .and_then(as_text)
+                .ok_or_else(|| RedisStreamError::Reply("stream entry without id".to_string()))?;
+            let mut fields = Map::new();
+            let mut size = 0;
+            if let Some(RedisValue::Array(values)) = parts.get(1) {
+                for pair in values.chunks(2) {
+                    let (Some(key), Some(value)) = (
+                        pair.first().and_then(as_text),
+                        pair.get(1).and_then(as_text),
+                    ) else {
+                        continue;
+                    };
+                    size += key.len() + value.len();
+                    fields.insert(key, Value::String(value));
+                }
+            }
+            entries.push(StreamEntry { id, fields, size });
+        }
+    }
+    Ok(entries)
+}
+
+fn as_array(value: &RedisValue) -> Option<&Vec<RedisValue>> {
+    match value {
+        RedisValue::Array(items) => Some(items),
+        _ => None,
+    }
+}
+
+fn as_text(value: &RedisValue) -> Option<String> {
+    match value {
+        RedisValue::BulkString(bytes) => Some(String::from_utf8_lossy(bytes).into_owned()),
+        RedisValue::SimpleString(s) => Some(s.clone()),
+        RedisValue::Int(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn bulk(s: &str) -> RedisValue {
+        RedisValue::BulkString(s.as_bytes().to_vec())
+    }
+
+    #[test]
+    fn redis_address_accepts_tcp_urls_only() {
+        assert_eq!(
+            redis_address("redis://:pw@cache.example.com:6380/0").unwrap(),
+            ("cache.example.com".to_string(), 6380)
+        );
+        assert_eq!(
+            redis_address("rediss://cache.example.com").unwrap(),
+            ("cache.example.com".to_string(), 6379)
+        );
+        assert!(redis_address("redis+unix:///tmp/redis.sock").is_err());
+        assert!(redis_address("unix:///tmp/redis.sock").is_err());
+        assert!(redis_address("http://cache.example.com").is_err());
+    }
+
+    #[test]
+    fn read_replies_parse_entries_and_deleted_pending_ids() {
+        let reply = RedisValue::Array(vec![RedisValue::Array(vec![
+            bulk("orders"),
+            RedisValue::Array(vec![
+                RedisValue::Array(vec![
+                    bulk("1700000000000-0"),
+                    RedisValue::Array(vec![
+                        bulk("order"),
+                        bulk("42"),
+                        bulk("status"),
+                        bulk("paid"),
+                    ]),
+                ]),
+                RedisValue::Array(vec![bulk("1700000000001-0"), RedisValue::Nil]),
+            ]),
+        ])]);
+        let entries = parse_read_reply(&reply).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].id, "1700000000000-0");
+        assert_eq!(
+            Value::Object(entries[0].fields.clone()),
+            json!({ "order": "42", "status": "paid" })
+        );
+        assert_eq!(entries[0].size, 17);
+        assert!(entries[1].fields.is_empty());
+
+        assert!(parse_read_reply(&RedisValue::Nil).unwrap().is_empty());
+        assert!(parse_read_reply(&RedisValue::Int(1)).is_err());
+    }
+
+    /// Round trip through a real server. Set `REDIS_TEST_URL`, e.g.
+    /// `redis://127.0.0.1:6379/15`; the test uses a throwaway stream key.
+    #[tokio::test]
+    #[ignore]
+    async fn adds_reads_and_acks_through_a_live_server() {
+        let url = std::env::var("REDIS_TEST_URL").expect("REDIS_TEST_URL");
+        let stream = format!("dsentr-test-{}", uuid::Uuid::new_v4());
+        let mut session = RedisSession::connect(&url).await.unwrap();
+        session.ensure_group(&stream, "dsentr").await.unwrap();
+        session.ensure_group(&stream, "dsentr").await.unwrap();
+        let id = session
+            .xadd(&stream, &[("order".into(), "42".into())], Some(1000))
+            .await
+            .unwrap();
+
+        let entries = session
+            .read_group(&stream, "dsentr", "worker", 10)
+            .await
+            .unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].id, id);
+
+        // Unacknowledged entries are delivered again.
+        let again = session
+            .read_group(&stream, "dsentr", "worker", 10)
+            .await
+            .unwrap();
+        assert_eq!(again.len(), 1);
+        session.xack(&stream, "dsentr", &[id]).await.unwrap();
+        assert!(session
+            .read_group(&stream, "dsentr", "worker", 10)
+            .await
+            .unwrap()
+            .is_empty());
+
+        let _: () = redis::cmd("DEL")
+            .arg(&stream)
+            .query_async(&mut session.con)
+            .await
+            .unwrap();
+    }
+}
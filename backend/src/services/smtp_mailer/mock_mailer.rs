@@ -1,4 +1,4 @@
-use crate::services::smtp_mailer::{MailError, Mailer, SmtpConfig};
+use crate::services::smtp_mailer::{MailAttachment, MailError, Mailer, SmtpConfig};
 use async_trait::async_trait;
 use std::sync::Mutex;
 
@@ -8,6 +8,7 @@ pub struct RecordedSmtpEmail {
     pub recipients: Vec<String>,
     pub subject: String,
     pub body: String,
+    pub attachments: Vec<String>,
 }
 
 /// A mock mailer that records sent emails for testing purposes.
@@ -71,6 +72,18 @@ impl Mailer for MockMailer {
         recipients: &[String],
         subject: &str,
         body: &str,
+    ) -> Result<(), MailError> {
+        self.send_email_with_attachments(config, recipients, subject, body, &[])
+            .await
+    }
+
+    async fn send_email_with_attachments(
+        &self,
+        config: &SmtpConfig,
+        recipients: &[String],
+        subject: &str,
+        body: &str,
+        attachments: &[MailAttachment],
     ) -> Result<(), MailError> {
         if self.fail_send {
             return Err(MailError::Other("mock fail".into()));
@@ -84,6 +97,10 @@ impl Mailer for MockMailer {
                 recipients: recipients.to_vec(),
                 subject: subject.to_string(),
                 body: body.to_string(),
+                attachments: attachments
+                    .iter()
+                    .map(|attachment| attachment.file_name.clone())
+                    .collect(),
             });
 
         Ok(())
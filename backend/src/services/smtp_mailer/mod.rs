@@ -67,10 +67,70 @@ pub trait Mailer: Send + Sync {
         subject: &str,
         body: &str,
     ) -> Result<(), MailError>;
+    /// Same as [`Mailer::send_email_with_config`] but with file attachments.
+    /// Mailers that cannot build multipart messages reject non-empty lists.
+    async fn send_email_with_attachments(
+        &self,
+        config: &SmtpConfig,
+        recipients: &[String],
+        subject: &str,
+        body: &str,
+        attachments: &[MailAttachment],
+    ) -> Result<(), MailError> {
+        if attachments.is_empty() {
+            return self
+                .send_email_with_config(config, recipients, subject, body)
+                .await;
+        }
+        Err(MailError::Other(
+            "attachments are not supported by this mailer".into(),
+        ))
+    }
     #[allow(dead_code)]
     fn as_any(&self) -> &dyn Any;
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MailAttachment {
+    pub file_name: String,
+    pub mime_type: String,
+    pub bytes: Vec<u8>,
+}
+
+/// Builds a plain-text message, switching to multipart/mixed when files are attached.
+pub fn build_message(
+    from: &str,
+    recipients: &[String],
+    subject: &str,
+    body: &str,
+    attachments: &[MailAttachment],
+) -> Result<lettre::Message, MailError> {
+    use lettre::message::{header::ContentType, Attachment, Mailbox, MultiPart, SinglePart};
+
+    let from_mailbox: Mailbox = from.parse()?;
+    let mut builder = lettre::Message::builder().from(from_mailbox);
+    for recipient in recipients {
+        let mailbox: Mailbox = recipient.parse()?;
+        builder = builder.to(mailbox);
+    }
+    let builder = builder.subject(subject);
+
+    if attachments.is_empty() {
+        return Ok(builder.body(body.to_string())?);
+    }
+
+    let mut multipart = MultiPart::mixed().singlepart(SinglePart::plain(body.to_string()));
+    for attachment in attachments {
+        let content_type = ContentType::parse(&attachment.mime_type)
+            .unwrap_or(ContentType::parse("application/octet-stream").expect("valid mime"));
+        multipart = multipart.singlepart(
+            Attachment::new(attachment.file_name.clone())
+                .body(attachment.bytes.clone(), content_type),
+        );
+    }
+    Ok(builder.multipart(multipart)?)
+}
+
 mod mock_mailer;
 mod smtp_impl;
 
@@ -10,7 +10,7 @@ use lettre::{
 };
 use std::sync::Arc;
 
-use crate::services::smtp_mailer::{Mailer, SmtpConfig, TlsMode};
+use crate::services::smtp_mailer::{build_message, MailAttachment, Mailer, SmtpConfig, TlsMode};
 
 use super::MailError;
 
@@ -156,15 +156,19 @@ impl Mailer for SmtpMailer {
         subject: &str,
         body: &str,
     ) -> Result<(), MailError> {
-        let from_mailbox: Mailbox = config.from.parse()?;
-        let mut builder = Message::builder().from(from_mailbox);
-
-        for recipient in recipients {
-            let mailbox: Mailbox = recipient.parse()?;
-            builder = builder.to(mailbox);
-        }
+        self.send_email_with_attachments(config, recipients, subject, body, &[])
+            .await
+    }
 
-        let email = builder.subject(subject).body(body.to_string())?;
+    async fn send_email_with_attachments(
+        &self,
+        config: &SmtpConfig,
+        recipients: &[String],
+        subject: &str,
+        body: &str,
+        attachments: &[MailAttachment],
+    ) -> Result<(), MailError> {
+        let email = build_message(&config.from, recipients, subject, body, attachments)?;
 
         let transport = build_dynamic_transport(config)?;
 
@@ -311,6 +315,40 @@ mod tests {
         assert!(transport.is_ok());
     }
 
+    #[test]
+    fn build_message_attaches_files_as_multipart() {
+        let recipients = vec!["ops@example.com".to_string()];
+        let plain = build_message(
+            "sender@example.com",
+            &recipients,
+            "Report",
+            "See attached",
+            &[],
+        )
+        .expect("plain message");
+        let plain = String::from_utf8(plain.formatted()).unwrap();
+        assert!(!plain.contains("multipart/mixed"));
+
+        let attachment = MailAttachment {
+            file_name: "report.csv".into(),
+            mime_type: "text/csv".into(),
+            bytes: b"id,total\n1,42\n".to_vec(),
+        };
+        let message = build_message(
+            "sender@example.com",
+            &recipients,
+            "Report",
+            "See attached",
+            &[attachment],
+        )
+        .expect("multipart message");
+        let formatted = String::from_utf8(message.formatted()).unwrap();
+        assert!(formatted.contains("multipart/mixed"));
+        assert!(formatted.contains("See attached"));
+        assert!(formatted.contains("filename=\"report.csv\""));
+        assert!(formatted.contains("Content-Type: text/csv"));
+    }
+
     #[tokio::test]
     async fn smtp_mailer_new_rejects_disabled_tls_env() {
         let _guard = EnvGuard::set(&[
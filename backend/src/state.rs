@@ -599,6 +599,11 @@ mod tests {
                     client_secret: "stub".into(),
                     redirect_uri: "http://localhost".into(),
                 },
+                github: crate::config::OAuthProviderConfig {
+                    client_id: "stub".into(),
+                    client_secret: "stub".into(),
+                    redirect_uri: "http://localhost".into(),
+                },
                 token_encryption_key: vec![0u8; 32],
             },
             stripe: crate::config::StripeSettings {
@@ -614,6 +619,9 @@ mod tests {
             workspace_member_limit: crate::config::DEFAULT_WORKSPACE_MEMBER_LIMIT,
             workspace_monthly_run_limit: crate::config::DEFAULT_WORKSPACE_MONTHLY_RUN_LIMIT,
             runaway_limit_5min: crate::config::RUNAWAY_LIMIT_5MIN,
+            slack_signing_secret: None,
+            public_api_base_url: None,
+            provider_base_urls: crate::config::ProviderBaseUrls::default(),
         });
 
         let state = AppState {
@@ -0,0 +1,986 @@
+//! Fake provider HTTP server shared by integration tests. It records every
+//! request it receives and answers with canned responses, so provider clients
+//! can be exercised end to end by pointing `Config::provider_base_urls` at it.
+//! Also builds the app state, config, runs and OAuth connection fixtures those
+//! tests run against.
+
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+
+use axum::{
+    body::{to_bytes, Body},
+    extract::{Request, State},
+    http::{header, HeaderMap, Method, Response, StatusCode, Uri},
+    Router,
+};
+use serde_json::{json, Value};
+use tokio::{
+    net::TcpListener,
+    sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender},
+    task::JoinHandle,
+};
+
+use time::{Duration as TimeDuration, OffsetDateTime};
+use uuid::Uuid;
+
+use async_trait::async_trait;
+use reqwest::Client;
+use sqlx::Error as SqlxError;
+
+use crate::config::{
+    Config, OAuthProviderConfig, OAuthSettings, ProviderBaseUrls, StripeSettings,
+    DEFAULT_WORKSPACE_MEMBER_LIMIT, DEFAULT_WORKSPACE_MONTHLY_RUN_LIMIT, RUNAWAY_LIMIT_5MIN,
+};
+use crate::db::{
+    mock_db::{
+        MockDb, NoopWorkflowRepository, NoopWorkspaceRepository,
+        StaticWorkspaceMembershipRepository,
+    },
+    mock_stripe_event_log_repository::MockStripeEventLogRepository,
+    oauth_token_repository::{NewUserOAuthToken, UserOAuthTokenRepository},
+    workspace_connection_repository::{
+        NoopWorkspaceConnectionRepository, WorkspaceConnectionRepository,
+    },
+    workspace_repository::WorkspaceRepository,
+};
+use crate::models::oauth_token::UserOAuthToken;
+use crate::models::oauth_token::{ConnectedOAuthProvider, WorkspaceConnection};
+use crate::models::workflow_run::WorkflowRun;
+use crate::services::oauth::account_service::OAuthAccountService;
+use crate::services::oauth::github::mock_github_oauth::MockGitHubOAuth;
+use crate::services::oauth::google::mock_google_oauth::MockGoogleOAuth;
+use crate::services::oauth::workspace_service::{WorkspaceOAuthService, WorkspaceTokenRefresher};
+use crate::services::smtp_mailer::{Mailer, MockMailer};
+use crate::state::test_pg_pool;
+use crate::state::AppState;
+use crate::utils::encryption::encrypt_secret;
+use crate::utils::jwt::JwtKeys;
+
+const MAX_RECORDED_BODY_BYTES: usize = 10 * 1024 * 1024;
+
+#[derive(Clone, Debug)]
+pub(crate) struct RecordedRequest {
+    pub method: Method,
+    pub uri: Uri,
+    pub headers: HeaderMap,
+    pub body: Vec<u8>,
+}
+
+impl RecordedRequest {
+    pub fn path(&self) -> &str {
+        self.uri.path()
+    }
+
+    pub fn header(&self, name: &str) -> Option<String> {
+        self.headers
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string)
+    }
+
+    /// Decoded value of the first `name` query parameter.
+    pub fn query_param(&self, name: &str) -> Option<String> {
+        let query = self.uri.query()?;
+        reqwest::Url::parse(&format!("http://stub/?{query}"))
+            .ok()?
+            .query_pairs()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.into_owned())
+    }
+
+    pub fn json(&self) -> Value {
+        serde_json::from_slice(&self.body).expect("request body should be JSON")
+    }
+}
+
+type Responder = Arc<dyn Fn(&RecordedRequest) -> Response<Body> + Send + Sync>;
+
+#[derive(Clone)]
+struct ServerState {
+    tx: UnboundedSender<RecordedRequest>,
+    responder: Responder,
+}
+
+async fn handle(State(state): State<ServerState>, request: Request<Body>) -> Response<Body> {
+    let (parts, body) = request.into_parts();
+    let body = to_bytes(body, MAX_RECORDED_BODY_BYTES)
+        .await
+        .unwrap_or_default();
+    let record = RecordedRequest {
+        method: parts.method,
+        uri: parts.uri,
+        headers: parts.headers,
+        body: body.to_vec(),
+    };
+    let response = (state.responder)(&record);
+    let _ = state.tx.send(record);
+    response
+}
+
+async fn serve(
+    responder: Responder,
+) -> (
+    SocketAddr,
+    UnboundedReceiver<RecordedRequest>,
+    JoinHandle<()>,
+) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let (tx, rx) = unbounded_channel();
+    let app = Router::new()
+        .fallback(handle)
+        .with_state(ServerState { tx, responder });
+
+    let handle = tokio::spawn(async move {
+        if let Err(err) = axum::serve(listener, app.into_make_service()).await {
+            eprintln!("stub server exited with error: {err}");
+        }
+    });
+
+    (addr, rx, handle)
+}
+
+/// Serves `response_factory()` for every request on any path and streams the
+/// recorded requests through the returned receiver.
+pub(crate) async fn spawn_stub_server<F>(
+    response_factory: F,
+) -> (
+    SocketAddr,
+    UnboundedReceiver<RecordedRequest>,
+    JoinHandle<()>,
+)
+where
+    F: Fn() -> Response<Body> + Send + Sync + 'static,
+{
+    serve(Arc::new(move |_: &RecordedRequest| response_factory())).await
+}
+
+pub(crate) fn json_response(status: StatusCode, body: Value) -> Response<Body> {
+    Response::builder()
+        .status(status)
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(body.to_string()))
+        .unwrap()
+}
+
+type CannedReply = Arc<dyn Fn(&RecordedRequest) -> (StatusCode, Value) + Send + Sync>;
+
+struct CannedResponse {
+    method: Method,
+    path: String,
+    reply: CannedReply,
+}
+
+/// Stand-in for every provider at once: each provider's base URL is a path
+/// prefix on this server (see [`ProviderBaseUrls::all_at`]). Unmatched
+/// requests get a 404 naming the route so a missing stub is obvious.
+pub(crate) struct FakeProvider {
+    addr: SocketAddr,
+    canned: Arc<Mutex<Vec<CannedResponse>>>,
+    requests: Arc<Mutex<Vec<RecordedRequest>>>,
+    handle: JoinHandle<()>,
+}
+
+impl FakeProvider {
+    pub async fn start() -> Self {
+        let canned: Arc<Mutex<Vec<CannedResponse>>> = Arc::default();
+        let requests: Arc<Mutex<Vec<RecordedRequest>>> = Arc::default();
+        let responder: Responder = {
+            let canned = Arc::clone(&canned);
+            let requests = Arc::clone(&requests);
+            Arc::new(move |request: &RecordedRequest| {
+                requests.lock().unwrap().push(request.clone());
+                let canned = canned.lock().unwrap();
+                let path_and_query = request
+                    .uri
+                    .path_and_query()
+                    .map(|value| value.as_str())
+                    .unwrap_or_default();
+                // A stub registered with a query string beats a path-only one,
+                // and the latest registration wins so a test can override an
+                // earlier stub.
+                let matching = |target: &str| {
+                    canned
+                        .iter()
+                        .rev()
+                        .find(|c| c.method == request.method && c.path == target)
+                };
+                match matching(path_and_query).or_else(|| matching(request.path())) {
+                    Some(c) => {
+                        let (status, body) = (c.reply)(request);
+                        json_response(status, body)
+                    }
+                    None => json_response(
+                        StatusCode::NOT_FOUND,
+                        json!({
+                            "error": format!(
+                                "no canned response for {} {}",
+                                request.method,
+                                request.path()
+                            )
+                        }),
+                    ),
+                }
+            })
+        };
+        let (addr, _rx, handle) = serve(responder).await;
+        Self {
+            addr,
+            canned,
+            requests,
+            handle,
+        }
+    }
+
+    pub fn url(&self, path: &str) -> String {
+        format!("http://{}{}", self.addr, path)
+    }
+
+    pub fn base_urls(&self) -> ProviderBaseUrls {
+        ProviderBaseUrls::all_at(&self.url(""))
+    }
+
+    /// Answers `method path` (path includes the provider prefix, e.g.
+    /// `/asana/tasks`) with `status` and a JSON body. A path with a query
+    /// string (`/graph/me/messages?$skiptoken=2`) only matches that exact
+    /// query; a bare path matches any query.
+    pub fn respond(&self, method: Method, path: &str, status: StatusCode, body: Value) {
+        self.respond_with(method, path, move |_| (status, body.clone()));
+    }
+
+    /// Like [`respond`](Self::respond), but builds the reply from the request,
+    /// e.g. to page through results keyed on a cursor in the request body.
+    pub fn respond_with<F>(&self, method: Method, path: &str, reply: F)
+    where
+        F: Fn(&RecordedRequest) -> (StatusCode, Value) + Send + Sync + 'static,
+    {
+        self.canned.lock().unwrap().push(CannedResponse {
+            method,
+            path: path.to_string(),
+            reply: Arc::new(reply),
+        });
+    }
+
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    pub fn requests_to(&self, method: Method, path: &str) -> Vec<RecordedRequest> {
+        self.requests()
+            .into_iter()
+            .filter(|request| request.method == method && request.path() == path)
+            .collect()
+    }
+}
+
+impl Drop for FakeProvider {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
+
+/// A live workspace connection for `provider` holding the access token
+/// `workspace-access`, encrypted with `key`. Override fields with struct
+/// update syntax.
+pub(crate) fn workspace_connection(
+    provider: ConnectedOAuthProvider,
+    key: &[u8],
+) -> WorkspaceConnection {
+    let creator_id = Uuid::new_v4();
+    let now = OffsetDateTime::now_utc();
+    WorkspaceConnection {
+        id: Uuid::new_v4(),
+        connection_id: None,
+        workspace_id: Uuid::new_v4(),
+        created_by: creator_id,
+        owner_user_id: creator_id,
+        user_oauth_token_id: None,
+        provider,
+        access_token: encrypt_secret(key, "workspace-access").unwrap(),
+        refresh_token: encrypt_secret(key, "workspace-refresh").unwrap(),
+        expires_at: now + TimeDuration::hours(1),
+        account_email: "workspace@example.com".into(),
+        created_at: now,
+        updated_at: now,
+        metadata: Value::Null,
+        bot_user_id: None,
+        incoming_webhook_url: None,
+        slack_team_id: (provider == ConnectedOAuthProvider::Slack).then(|| "T123".into()),
+    }
+}
+
+/// App state that can use one [`workspace_connection`] for `provider`, with
+/// provider clients pointed at `urls`. Returns the state, a run in the
+/// connection's workspace and the connection id.
+pub(crate) fn workspace_connection_fixture(
+    provider: ConnectedOAuthProvider,
+    urls: ProviderBaseUrls,
+) -> (AppState, WorkflowRun, Uuid) {
+    let config = test_config_with_urls(urls);
+    let key = Arc::new(config.oauth.token_encryption_key.clone());
+    let connection = workspace_connection(provider, &key);
+    let (connection_id, workspace_id) = (connection.id, connection.workspace_id);
+
+    let (workspace_oauth, repo) = workspace_oauth_with_connection(connection, key);
+    let mut state = test_state(
+        OAuthAccountService::test_stub(),
+        Arc::new(reqwest::Client::new()),
+        Arc::new(StaticWorkspaceMembershipRepository::allowing()),
+    );
+    state.config = config;
+    state.workspace_oauth = workspace_oauth;
+    state.workspace_connection_repo = repo;
+
+    let mut run = sample_run(Uuid::new_v4());
+    run.workspace_id = Some(workspace_id);
+    (state, run, connection_id)
+}
+
+#[derive(Default)]
+pub(crate) struct NoopUserTokenRepo;
+
+#[async_trait]
+impl UserOAuthTokenRepository for NoopUserTokenRepo {
+    async fn upsert_token(
+        &self,
+        _new_token: NewUserOAuthToken,
+    ) -> Result<UserOAuthToken, SqlxError> {
+        Err(SqlxError::RowNotFound)
+    }
+
+    async fn find_by_id(&self, _token_id: Uuid) -> Result<Option<UserOAuthToken>, SqlxError> {
+        Ok(None)
+    }
+
+    async fn find_by_user_and_provider(
+        &self,
+        _user_id: Uuid,
+        _provider: ConnectedOAuthProvider,
+    ) -> Result<Option<UserOAuthToken>, SqlxError> {
+        Ok(None)
+    }
+
+    async fn delete_token(
+        &self,
+        _user_id: Uuid,
+        _provider: ConnectedOAuthProvider,
+    ) -> Result<(), SqlxError> {
+        Ok(())
+    }
+
+    async fn list_tokens_for_user(&self, _user_id: Uuid) -> Result<Vec<UserOAuthToken>, SqlxError> {
+        Ok(vec![])
+    }
+
+    async fn mark_shared(
+        &self,
+        _user_id: Uuid,
+        _provider: ConnectedOAuthProvider,
+        _is_shared: bool,
+    ) -> Result<UserOAuthToken, SqlxError> {
+        Err(SqlxError::RowNotFound)
+    }
+
+    async fn list_by_user_and_provider(
+        &self,
+        _user_id: Uuid,
+        _provider: ConnectedOAuthProvider,
+    ) -> Result<Vec<UserOAuthToken>, SqlxError> {
+        Ok(vec![])
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct RecordingWorkspaceConnections {
+    connection: Mutex<Option<WorkspaceConnection>>,
+    find_calls: Mutex<Vec<Uuid>>,
+}
+
+impl RecordingWorkspaceConnections {
+    pub(crate) fn with_connection(connection: WorkspaceConnection) -> Self {
+        Self {
+            connection: Mutex::new(Some(connection)),
+            find_calls: Mutex::new(Vec::new()),
+        }
+    }
+
+    pub(crate) fn find_calls(&self) -> Vec<Uuid> {
+        self.find_calls.lock().unwrap().clone()
+    }
+}
+
+#[async_trait]
+impl WorkspaceConnectionRepository for RecordingWorkspaceConnections {
+    async fn insert_connection(
+        &self,
+        _new_connection: crate::db::workspace_connection_repository::NewWorkspaceConnection,
+    ) -> Result<WorkspaceConnection, SqlxError> {
+        Err(SqlxError::RowNotFound)
+    }
+
+    async fn find_by_id(
+        &self,
+        connection_id: Uuid,
+    ) -> Result<Option<WorkspaceConnection>, SqlxError> {
+        self.find_calls.lock().unwrap().push(connection_id);
+        let guard = self.connection.lock().unwrap();
+        Ok(guard.clone().filter(|conn| conn.id == connection_id))
+    }
+
+    async fn get_by_id(&self, connection_id: Uuid) -> Result<WorkspaceConnection, SqlxError> {
+        self.find_by_id(connection_id)
+            .await?
+            .ok_or(SqlxError::RowNotFound)
+    }
+
+    async fn list_for_workspace_provider(
+        &self,
+        workspace_id: Uuid,
+        provider: ConnectedOAuthProvider,
+    ) -> Result<Vec<WorkspaceConnection>, SqlxError> {
+        let guard = self.connection.lock().unwrap();
+        Ok(guard
+            .clone()
+            .filter(|conn| conn.workspace_id == workspace_id && conn.provider == provider)
+            .into_iter()
+            .collect())
+    }
+
+    async fn find_by_source_token(
+        &self,
+        user_oauth_token_id: Uuid,
+    ) -> Result<Vec<WorkspaceConnection>, SqlxError> {
+        let guard = self.connection.lock().unwrap();
+        Ok(guard
+            .clone()
+            .filter(|conn| conn.user_oauth_token_id == Some(user_oauth_token_id))
+            .into_iter()
+            .collect())
+    }
+
+    async fn list_by_workspace_and_provider(
+        &self,
+        workspace_id: Uuid,
+        provider: ConnectedOAuthProvider,
+    ) -> Result<Vec<WorkspaceConnection>, SqlxError> {
+        self.list_for_workspace_provider(workspace_id, provider)
+            .await
+    }
+
+    async fn list_slack_by_team(
+        &self,
+        _slack_team_id: &str,
+    ) -> Result<Vec<WorkspaceConnection>, SqlxError> {
+        Ok(Vec::new())
+    }
+
+    async fn list_for_workspace(
+        &self,
+        _workspace_id: Uuid,
+    ) -> Result<
+        Vec<crate::db::workspace_connection_repository::WorkspaceConnectionListing>,
+        SqlxError,
+    > {
+        Ok(Vec::new())
+    }
+
+    async fn list_for_user_memberships(
+        &self,
+        _user_id: Uuid,
+    ) -> Result<
+        Vec<crate::db::workspace_connection_repository::WorkspaceConnectionListing>,
+        SqlxError,
+    > {
+        Ok(Vec::new())
+    }
+
+    async fn list_by_workspace_creator(
+        &self,
+        _workspace_id: Uuid,
+        _creator_id: Uuid,
+    ) -> Result<Vec<WorkspaceConnection>, SqlxError> {
+        Ok(Vec::new())
+    }
+
+    async fn update_tokens_for_creator(
+        &self,
+        _creator_id: Uuid,
+        _provider: ConnectedOAuthProvider,
+        _access_token: String,
+        _refresh_token: String,
+        _expires_at: OffsetDateTime,
+        _account_email: String,
+        _bot_user_id: Option<String>,
+        _slack_team_id: Option<String>,
+        _incoming_webhook_url: Option<String>,
+    ) -> Result<(), SqlxError> {
+        Ok(())
+    }
+
+    async fn update_tokens_for_connection(
+        &self,
+        connection_id: Uuid,
+        access_token: String,
+        refresh_token: String,
+        expires_at: OffsetDateTime,
+        account_email: String,
+        bot_user_id: Option<String>,
+        slack_team_id: Option<String>,
+        incoming_webhook_url: Option<String>,
+    ) -> Result<WorkspaceConnection, SqlxError> {
+        let mut guard = self.connection.lock().unwrap();
+        if let Some(existing) = guard.as_mut() {
+            if existing.id == connection_id {
+                existing.access_token = access_token;
+                existing.refresh_token = refresh_token;
+                existing.expires_at = expires_at;
+                existing.account_email = account_email;
+                existing.bot_user_id = bot_user_id;
+                existing.slack_team_id = slack_team_id;
+                existing.incoming_webhook_url = incoming_webhook_url;
+                existing.updated_at = OffsetDateTime::now_utc();
+                return Ok(existing.clone());
+            }
+        }
+        Err(SqlxError::RowNotFound)
+    }
+
+    async fn update_tokens(
+        &self,
+        connection_id: Uuid,
+        access_token: String,
+        refresh_token: String,
+        expires_at: OffsetDateTime,
+        _bot_user_id: Option<String>,
+        _slack_team_id: Option<String>,
+        _incoming_webhook_url: Option<String>,
+    ) -> Result<WorkspaceConnection, SqlxError> {
+        let mut guard = self.connection.lock().unwrap();
+        if let Some(existing) = guard.as_mut() {
+            if existing.id == connection_id {
+                existing.access_token = access_token;
+                existing.refresh_token = refresh_token;
+                existing.expires_at = expires_at;
+                existing.updated_at = OffsetDateTime::now_utc();
+                return Ok(existing.clone());
+            }
+        }
+        Err(SqlxError::RowNotFound)
+    }
+
+    async fn delete_connection(&self, _connection_id: Uuid) -> Result<(), SqlxError> {
+        Ok(())
+    }
+
+    async fn delete_by_id(&self, _connection_id: Uuid) -> Result<(), SqlxError> {
+        Ok(())
+    }
+
+    async fn delete_by_owner_and_provider(
+        &self,
+        _workspace_id: Uuid,
+        _owner_user_id: Uuid,
+        _provider: ConnectedOAuthProvider,
+    ) -> Result<(), SqlxError> {
+        Ok(())
+    }
+
+    async fn delete_by_owner_and_provider_and_id(
+        &self,
+        workspace_id: Uuid,
+        owner_user_id: Uuid,
+        provider: ConnectedOAuthProvider,
+        connection_id: Uuid,
+    ) -> Result<(), SqlxError> {
+        let mut guard = self.connection.lock().unwrap();
+        if let Some(existing) = guard.as_ref() {
+            if existing.id == connection_id
+                && existing.workspace_id == workspace_id
+                && existing.owner_user_id == owner_user_id
+                && existing.provider == provider
+            {
+                *guard = None;
+            }
+        }
+        Ok(())
+    }
+
+    async fn has_connections_for_owner_provider(
+        &self,
+        _owner_user_id: Uuid,
+        _provider: ConnectedOAuthProvider,
+    ) -> Result<bool, SqlxError> {
+        Ok(false)
+    }
+
+    async fn mark_connections_stale_for_creator(
+        &self,
+        _creator_id: Uuid,
+        _provider: ConnectedOAuthProvider,
+    ) -> Result<Vec<crate::db::workspace_connection_repository::StaleWorkspaceConnection>, SqlxError>
+    {
+        Ok(Vec::new())
+    }
+
+    async fn record_audit_event(
+        &self,
+        _event: crate::db::workspace_connection_repository::NewWorkspaceAuditEvent,
+    ) -> Result<crate::models::oauth_token::WorkspaceAuditEvent, SqlxError> {
+        Err(SqlxError::RowNotFound)
+    }
+}
+
+pub(crate) fn workspace_oauth_with_connection(
+    connection: WorkspaceConnection,
+    key: Arc<Vec<u8>>,
+) -> (
+    Arc<WorkspaceOAuthService>,
+    Arc<RecordingWorkspaceConnections>,
+) {
+    let repo = Arc::new(RecordingWorkspaceConnections::with_connection(connection));
+    let membership_repo: Arc<dyn WorkspaceRepository> =
+        Arc::new(StaticWorkspaceMembershipRepository::allowing());
+    let service = Arc::new(WorkspaceOAuthService::new(
+        Arc::new(NoopUserTokenRepo),
+        membership_repo,
+        repo.clone(),
+        OAuthAccountService::test_stub() as Arc<dyn WorkspaceTokenRefresher>,
+        key,
+    ));
+    (service, repo)
+}
+
+pub(crate) fn test_config() -> Arc<Config> {
+    test_config_with_urls(ProviderBaseUrls::default())
+}
+
+pub(crate) fn test_config_with_urls(provider_base_urls: ProviderBaseUrls) -> Arc<Config> {
+    Arc::new(Config {
+        database_url: String::new(),
+        frontend_origin: "http://localhost".into(),
+        admin_origin: "http://localhost".into(),
+        oauth: OAuthSettings {
+            google: OAuthProviderConfig {
+                client_id: "stub".into(),
+                client_secret: "stub".into(),
+                redirect_uri: "http://localhost".into(),
+            },
+            microsoft: OAuthProviderConfig {
+                client_id: "stub".into(),
+                client_secret: "stub".into(),
+                redirect_uri: "http://localhost".into(),
+            },
+            slack: OAuthProviderConfig {
+                client_id: "stub".into(),
+                client_secret: "stub".into(),
+                redirect_uri: "http://localhost".into(),
+            },
+            asana: OAuthProviderConfig {
+                client_id: "stub".into(),
+                client_secret: "stub".into(),
+                redirect_uri: "http://localhost".into(),
+            },
+            notion: OAuthProviderConfig {
+                client_id: "stub".into(),
+                client_secret: "stub".into(),
+                redirect_uri: "http://localhost".into(),
+            },
+            github: OAuthProviderConfig {
+                client_id: "stub".into(),
+                client_secret: "stub".into(),
+                redirect_uri: "http://localhost".into(),
+            },
+            token_encryption_key: vec![0u8; 32],
+        },
+        api_secrets_encryption_key: vec![1u8; 32],
+        stripe: StripeSettings {
+            client_id: "stub".into(),
+            secret_key: "stub".into(),
+            webhook_secret: "0123456789abcdef0123456789ABCDEF".into(),
+        },
+        auth_cookie_secure: true,
+        webhook_secret: "0123456789abcdef0123456789ABCDEF".into(),
+        jwt_issuer: "test-issuer".into(),
+        jwt_audience: "test-audience".into(),
+        workspace_member_limit: DEFAULT_WORKSPACE_MEMBER_LIMIT,
+        workspace_monthly_run_limit: DEFAULT_WORKSPACE_MONTHLY_RUN_LIMIT,
+        runaway_limit_5min: RUNAWAY_LIMIT_5MIN,
+        slack_signing_secret: None,
+        public_api_base_url: None,
+        provider_base_urls,
+    })
+}
+
+pub(crate) fn test_jwt_keys() -> Arc<JwtKeys> {
+    Arc::new(
+        JwtKeys::from_secret("0123456789abcdef0123456789abcdef")
+            .expect("test JWT secret should be valid"),
+    )
+}
+
+pub(crate) fn sample_run(user_id: Uuid) -> WorkflowRun {
+    let now = OffsetDateTime::now_utc();
+    WorkflowRun {
+        id: Uuid::new_v4(),
+        user_id,
+        workflow_id: Uuid::new_v4(),
+        workspace_id: None,
+        snapshot: json!({}),
+        status: "pending".to_string(),
+        error: None,
+        idempotency_key: None,
+        started_at: now,
+        resume_at: now,
+        finished_at: None,
+        created_at: now,
+        updated_at: now,
+    }
+}
+
+pub(crate) fn test_state(
+    oauth_accounts: Arc<OAuthAccountService>,
+    http_client: Arc<Client>,
+    workspace_repo: Arc<dyn WorkspaceRepository>,
+) -> AppState {
+    AppState {
+        db: Arc::new(MockDb::default()),
+        workflow_repo: Arc::new(NoopWorkflowRepository),
+        workspace_repo,
+        workspace_connection_repo: Arc::new(NoopWorkspaceConnectionRepository),
+        stripe_event_log_repo: Arc::new(MockStripeEventLogRepository::default()),
+        db_pool: test_pg_pool(),
+        mailer: Arc::new(MockMailer::default()) as Arc<dyn Mailer>,
+        google_oauth: Arc::new(MockGoogleOAuth::default()),
+        github_oauth: Arc::new(MockGitHubOAuth::default()),
+        oauth_accounts,
+        workspace_oauth: WorkspaceOAuthService::test_stub(),
+        stripe: Arc::new(crate::services::stripe::MockStripeService::new()),
+        http_client,
+        config: test_config(),
+        worker_id: Arc::new("worker".to_string()),
+        worker_lease_seconds: 30,
+        jwt_keys: test_jwt_keys(),
+    }
+}
+
+pub(crate) fn oauth_service_with_token(
+    user_id: Uuid,
+    email: &str,
+) -> (Arc<OAuthAccountService>, Uuid) {
+    oauth_service_with_provider_token(user_id, email, ConnectedOAuthProvider::Google)
+}
+
+/// Personal connection for `provider` holding `access-token`.
+pub(crate) fn oauth_service_with_provider_token(
+    user_id: Uuid,
+    email: &str,
+    provider: ConnectedOAuthProvider,
+) -> (Arc<OAuthAccountService>, Uuid) {
+    #[derive(Clone)]
+    struct StaticRepo {
+        record: UserOAuthToken,
+    }
+
+    #[async_trait]
+    impl UserOAuthTokenRepository for StaticRepo {
+        async fn upsert_token(
+            &self,
+            _new_token: NewUserOAuthToken,
+        ) -> Result<UserOAuthToken, sqlx::Error> {
+            Ok(self.record.clone())
+        }
+
+        async fn find_by_id(&self, token_id: Uuid) -> Result<Option<UserOAuthToken>, sqlx::Error> {
+            if token_id == self.record.id {
+                Ok(Some(self.record.clone()))
+            } else {
+                Ok(None)
+            }
+        }
+
+        async fn find_by_user_and_provider(
+            &self,
+            user_id: Uuid,
+            provider: ConnectedOAuthProvider,
+        ) -> Result<Option<UserOAuthToken>, sqlx::Error> {
+            if provider == self.record.provider && user_id == self.record.user_id {
+                Ok(Some(self.record.clone()))
+            } else {
+                Ok(None)
+            }
+        }
+
+        async fn delete_token(
+            &self,
+            _user_id: Uuid,
+            _provider: ConnectedOAuthProvider,
+        ) -> Result<(), sqlx::Error> {
+            Ok(())
+        }
+
+        async fn list_tokens_for_user(
+            &self,
+            user_id: Uuid,
+        ) -> Result<Vec<UserOAuthToken>, sqlx::Error> {
+            if user_id == self.record.user_id {
+                Ok(vec![self.record.clone()])
+            } else {
+                Ok(vec![])
+            }
+        }
+
+        async fn mark_shared(
+            &self,
+            _user_id: Uuid,
+            _provider: ConnectedOAuthProvider,
+            _is_shared: bool,
+        ) -> Result<UserOAuthToken, sqlx::Error> {
+            Ok(self.record.clone())
+        }
+
+        async fn list_by_user_and_provider(
+            &self,
+            user_id: Uuid,
+            provider: ConnectedOAuthProvider,
+        ) -> Result<Vec<UserOAuthToken>, sqlx::Error> {
+            if provider == self.record.provider && user_id == self.record.user_id {
+                Ok(vec![self.record.clone()])
+            } else {
+                Ok(vec![])
+            }
+        }
+    }
+
+    let key = Arc::new(vec![1u8; 32]);
+    let encrypted_access = crate::utils::encryption::encrypt_secret(&key, "access-token").unwrap();
+    let encrypted_refresh =
+        crate::utils::encryption::encrypt_secret(&key, "refresh-token").unwrap();
+    let now = OffsetDateTime::now_utc();
+
+    let record_id = Uuid::new_v4();
+    let record = UserOAuthToken {
+        id: record_id,
+        user_id,
+        workspace_id: None,
+        provider,
+        access_token: encrypted_access,
+        refresh_token: encrypted_refresh,
+        expires_at: now + TimeDuration::hours(2),
+        account_email: email.to_string(),
+        metadata: serde_json::json!({}),
+        is_shared: false,
+        created_at: now,
+        updated_at: now,
+    };
+
+    let repo = Arc::new(StaticRepo { record });
+    let workspace_repo = Arc::new(RecordingWorkspaceConnections::default())
+        as Arc<dyn WorkspaceConnectionRepository>;
+    let client = Arc::new(Client::new());
+    let settings = OAuthSettings {
+        google: OAuthProviderConfig {
+            client_id: "stub".into(),
+            client_secret: "stub".into(),
+            redirect_uri: "http://localhost".into(),
+        },
+        microsoft: OAuthProviderConfig {
+            client_id: "stub".into(),
+            client_secret: "stub".into(),
+            redirect_uri: "http://localhost".into(),
+        },
+        slack: OAuthProviderConfig {
+            client_id: "stub".into(),
+            client_secret: "stub".into(),
+            redirect_uri: "http://localhost".into(),
+        },
+        asana: OAuthProviderConfig {
+            client_id: "stub".into(),
+            client_secret: "stub".into(),
+            redirect_uri: "http://localhost".into(),
+        },
+        notion: OAuthProviderConfig {
+            client_id: "stub".into(),
+            client_secret: "stub".into(),
+            redirect_uri: "http://localhost".into(),
+        },
+        github: OAuthProviderConfig {
+            client_id: "stub".into(),
+            client_secret: "stub".into(),
+            redirect_uri: "http://localhost".into(),
+        },
+        token_encryption_key: (*key).clone(),
+    };
+
+    (
+        Arc::new(OAuthAccountService::new(
+            repo,
+            workspace_repo,
+            key,
+            client,
+            &settings,
+        )),
+        record_id,
+    )
+}
+
+/// App state holding one personal connection for `provider` (access token
+/// `access-token`), with provider clients pointed at `urls`. Returns the
+/// state, a run owned by the connection's user and the connection id.
+pub(crate) fn personal_connection_fixture(
+    provider: ConnectedOAuthProvider,
+    urls: ProviderBaseUrls,
+) -> (AppState, WorkflowRun, Uuid) {
+    let user_id = Uuid::new_v4();
+    let (oauth_accounts, connection_id) =
+        oauth_service_with_provider_token(user_id, "user@example.com", provider);
+    let mut state = test_state(
+        oauth_accounts,
+        Arc::new(Client::new()),
+        Arc::new(NoopWorkspaceRepository),
+    );
+    state.config = test_config_with_urls(urls);
+    (state, sample_run(user_id), connection_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn fake_provider_serves_canned_responses_and_records_requests() {
+        let provider = FakeProvider::start().await;
+        provider.respond(
+            Method::POST,
+            "/slack/chat.postMessage",
+            StatusCode::OK,
+            json!({ "ok": true, "ts": "1.0" }),
+        );
+
+        let client = reqwest::Client::new();
+        let ok = client
+            .post(format!("{}/chat.postMessage", provider.base_urls().slack))
+            .bearer_auth("xoxb-test")
+            .json(&json!({ "channel": "C1" }))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(ok.status(), reqwest::StatusCode::OK);
+        assert_eq!(ok.json::<Value>().await.unwrap()["ts"], "1.0");
+
+        let missing = client
+            .get(format!("{}/tasks", provider.base_urls().asana))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(missing.status(), reqwest::StatusCode::NOT_FOUND);
+
+        let recorded = provider.requests_to(Method::POST, "/slack/chat.postMessage");
+        assert_eq!(recorded.len(), 1);
+        assert_eq!(
+            recorded[0].header("authorization").as_deref(),
+            Some("Bearer xoxb-test")
+        );
+        assert_eq!(recorded[0].json()["channel"], "C1");
+        assert_eq!(provider.requests().len(), 2);
+    }
+}
@@ -0,0 +1,102 @@
+//! AWS Signature Version 4 request signing, shared by the Amazon SES email
+//! action and the S3-compatible artifact store.
+
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
+
+type HmacSha256 = Hmac<Sha256>;
+
+pub struct AwsSignature {
+    pub authorization: String,
+    pub amz_date: String,
+    pub payload_hash: String,
+}
+
+fn derive_aws_signing_key(
+    secret_key: &str,
+    date_stamp: &str,
+    region: &str,
+    service: &str,
+) -> Result<Vec<u8>, String> {
+    let mut mac = HmacSha256::new_from_slice(format!("AWS4{}", secret_key).as_bytes())
+        .map_err(|_| "Invalid AWS secret key".to_string())?;
+    mac.update(date_stamp.as_bytes());
+    let k_date = mac.finalize().into_bytes();
+
+    let mut mac = HmacSha256::new_from_slice(k_date.as_slice())
+        .map_err(|_| "Invalid AWS signing key (date)".to_string())?;
+    mac.update(region.as_bytes());
+    let k_region = mac.finalize().into_bytes();
+
+    let mut mac = HmacSha256::new_from_slice(k_region.as_slice())
+        .map_err(|_| "Invalid AWS signing key (region)".to_string())?;
+    mac.update(service.as_bytes());
+    let k_service = mac.finalize().into_bytes();
+
+    let mut mac = HmacSha256::new_from_slice(k_service.as_slice())
+        .map_err(|_| "Invalid AWS signing key (service)".to_string())?;
+    mac.update(b"aws4_request");
+    Ok(mac.finalize().into_bytes().to_vec())
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn sign_aws_request(
+    access_key: &str,
+    secret_key: &str,
+    region: &str,
+    service: &str,
+    method: &str,
+    canonical_uri: &str,
+    canonical_query: &str,
+    host: &str,
+    payload: &[u8],
+) -> Result<AwsSignature, String> {
+    let now = Utc::now();
+    let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+    let date_stamp = now.format("%Y%m%d").to_string();
+
+    let mut payload_hasher = Sha256::new();
+    payload_hasher.update(payload);
+    let payload_hash = hex::encode(payload_hasher.finalize());
+
+    let canonical_headers = format!(
+        "host:{}\nx-amz-content-sha256:{}\nx-amz-date:{}\n",
+        host.to_lowercase(),
+        payload_hash,
+        amz_date
+    );
+    let signed_headers = "host;x-amz-content-sha256;x-amz-date";
+
+    let canonical_request = format!(
+        "{}\n{}\n{}\n{}\n{}\n{}",
+        method, canonical_uri, canonical_query, canonical_headers, signed_headers, payload_hash
+    );
+
+    let mut canonical_hasher = Sha256::new();
+    canonical_hasher.update(canonical_request.as_bytes());
+    let canonical_hash = hex::encode(canonical_hasher.finalize());
+
+    let credential_scope = format!("{}/{}/{}/aws4_request", date_stamp, region, service);
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+        amz_date, credential_scope, canonical_hash
+    );
+
+    let signing_key = derive_aws_signing_key(secret_key, &date_stamp, region, service)?;
+    let mut mac = HmacSha256::new_from_slice(&signing_key)
+        .map_err(|_| "Failed to derive AWS signature".to_string())?;
+    mac.update(string_to_sign.as_bytes());
+    let signature = hex::encode(mac.finalize().into_bytes());
+
+    let authorization = format!(
+        "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+        access_key, credential_scope, signed_headers, signature
+    );
+
+    Ok(AwsSignature {
+        authorization,
+        amz_date,
+        payload_hash,
+    })
+}
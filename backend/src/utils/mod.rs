@@ -1,10 +1,13 @@
+pub mod aws_sigv4;
 pub mod change_history;
 pub mod csrf;
 pub mod encryption;
 pub mod ip;
 pub mod jwt;
+pub mod multipart;
 pub mod password;
 pub mod plan_limits;
 pub mod schedule;
 pub mod secrets;
+pub mod webhook_signing;
 pub mod workflow_connection_metadata;
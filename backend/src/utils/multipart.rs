@@ -0,0 +1,73 @@
+//! Minimal `multipart/form-data` encoder for requests that upload files
+//! (HTTP node multipart bodies, Mailgun attachments).
+
+use uuid::Uuid;
+
+pub struct MultipartBody {
+    boundary: String,
+    body: Vec<u8>,
+}
+
+impl Default for MultipartBody {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MultipartBody {
+    pub fn new() -> Self {
+        Self {
+            boundary: format!("dsentr-{}", Uuid::new_v4().simple()),
+            body: Vec::new(),
+        }
+    }
+
+    pub fn text(mut self, name: &str, value: &str) -> Self {
+        self.start_part(name, None, None);
+        self.body.extend_from_slice(value.as_bytes());
+        self.body.extend_from_slice(b"\r\n");
+        self
+    }
+
+    pub fn file(mut self, name: &str, file_name: &str, mime_type: &str, bytes: &[u8]) -> Self {
+        self.start_part(name, Some(file_name), Some(mime_type));
+        self.body.extend_from_slice(bytes);
+        self.body.extend_from_slice(b"\r\n");
+        self
+    }
+
+    /// Returns the `Content-Type` header value and the encoded body.
+    pub fn finish(mut self) -> (String, Vec<u8>) {
+        self.body
+            .extend_from_slice(format!("--{}--\r\n", self.boundary).as_bytes());
+        (
+            format!("multipart/form-data; boundary={}", self.boundary),
+            self.body,
+        )
+    }
+
+    fn start_part(&mut self, name: &str, file_name: Option<&str>, mime_type: Option<&str>) {
+        let mut header = format!(
+            "--{}\r\nContent-Disposition: form-data; name=\"{}\"",
+            self.boundary,
+            escape_quoted(name)
+        );
+        if let Some(file_name) = file_name {
+            header.push_str(&format!("; filename=\"{}\"", escape_quoted(file_name)));
+        }
+        header.push_str("\r\n");
+        if let Some(mime_type) = mime_type {
+            header.push_str(&format!("Content-Type: {mime_type}\r\n"));
+        }
+        header.push_str("\r\n");
+        self.body.extend_from_slice(header.as_bytes());
+    }
+}
+
+/// Quoted header parameters cannot carry raw quotes or line breaks.
+fn escape_quoted(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace(['\r', '\n'], " ")
+}
@@ -153,10 +153,25 @@ fn trigger_type(node: &Value) -> Option<Cow<'_, str>> {
 fn is_notion_trigger_type(trigger: &str) -> bool {
     matches!(
         trigger.trim(),
-        "notion.new_database_row" | "notion.updated_database_row"
+        "notion.new_database_row" | "notion.updated_database_row" | "notion.property_changed"
     )
 }
 
+/// Polling and event triggers backed by workspace-plan integrations, other
+/// than Notion (which keeps its own message).
+fn premium_trigger_integration(trigger: &str) -> Option<&'static str> {
+    let trigger = trigger.trim();
+    if trigger.starts_with("teams.") {
+        Some("Microsoft Teams")
+    } else if trigger.starts_with("outlook.") {
+        Some("Outlook")
+    } else if trigger == "slack" {
+        Some("Slack")
+    } else {
+        None
+    }
+}
+
 pub fn assess_workflow_for_plan(graph: &Value) -> WorkflowAssessment {
     let nodes = graph
         .get("nodes")
@@ -169,6 +184,7 @@ pub fn assess_workflow_for_plan(graph: &Value) -> WorkflowAssessment {
     let mut premium_nodes: Vec<(Option<String>, &'static str)> = Vec::new();
     let mut schedule_nodes: Vec<Option<String>> = Vec::new();
     let mut notion_trigger_nodes: Vec<Option<String>> = Vec::new();
+    let mut integration_trigger_nodes: Vec<(Option<String>, &'static str)> = Vec::new();
 
     for node in &nodes {
         let node_type = node
@@ -182,6 +198,7 @@ pub fn assess_workflow_for_plan(graph: &Value) -> WorkflowAssessment {
                 match action.as_ref() {
                     "sheets" => premium_nodes.push((node_label(node), "Google Sheets")),
                     "notion" => premium_nodes.push((node_label(node), "Notion")),
+                    "outlook" => premium_nodes.push((node_label(node), "Outlook")),
                     "messaging" | "teams" | "slack" | "googlechat" | "microsoftteams" => {
                         match messaging_integration(node) {
                             Some(MessagingIntegration::Slack) => {
@@ -205,6 +222,8 @@ pub fn assess_workflow_for_plan(graph: &Value) -> WorkflowAssessment {
                     schedule_nodes.push(node_label(node));
                 } else if is_notion_trigger_type(trigger.as_ref()) {
                     notion_trigger_nodes.push(node_label(node));
+                } else if let Some(integration) = premium_trigger_integration(trigger.as_ref()) {
+                    integration_trigger_nodes.push((node_label(node), integration));
                 }
             }
             continue;
@@ -243,6 +262,16 @@ pub fn assess_workflow_for_plan(graph: &Value) -> WorkflowAssessment {
         }
     }
 
+    for (label, integration) in integration_trigger_nodes {
+        violations.push(PlanViolation::new(
+            "premium-trigger",
+            format!(
+                "{integration} triggers are available on workspace plans and above. Upgrade in Settings → Plan to keep this trigger running."
+            ),
+            label,
+        ));
+    }
+
     let node_count = nodes.len();
     if node_count > 10 {
         violations.push(PlanViolation::new(
@@ -386,4 +415,36 @@ mod tests {
             "Microsoft Teams actions are available on workspace plans and above. Upgrade in Settings → Plan to run this step."
         );
     }
+
+    #[test]
+    fn flags_integration_triggers_and_outlook_actions() {
+        let graph = json!({
+            "nodes": [
+                {
+                    "id": "trigger-1",
+                    "type": "trigger",
+                    "data": { "label": "New mail", "triggerType": "outlook.new_mail" }
+                },
+                {
+                    "id": "action-1",
+                    "type": "action",
+                    "data": { "label": "Invite", "actionType": "outlook" }
+                }
+            ]
+        });
+
+        let assessment = assess_workflow_for_plan(&graph);
+        let messages: Vec<&str> = assessment
+            .violations
+            .iter()
+            .map(|v| v.message.as_str())
+            .collect();
+        assert_eq!(messages.len(), 2);
+        assert!(messages.contains(
+            &"Outlook actions are available on workspace plans and above. Upgrade in Settings → Plan to run this step."
+        ));
+        assert!(messages.contains(
+            &"Outlook triggers are available on workspace plans and above. Upgrade in Settings → Plan to keep this trigger running."
+        ));
+    }
 }
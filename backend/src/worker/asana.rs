@@ -0,0 +1,322 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use time::{format_description::well_known::Rfc3339, OffsetDateTime};
+
+use crate::services::asana::{self, AsanaError, AsanaTriggerKind};
+
+/// Upper bound on tasks whose stories are inspected per poll when looking for
+/// new comments, so a busy project cannot fan out into hundreds of calls.
+const MAX_COMMENT_TASKS_PER_POLL: usize = 20;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct AsanaTriggerState {
+    #[serde(default)]
+    pub last_polled_at: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AsanaTriggerConfig {
+    #[serde(default)]
+    pub trigger_type: String,
+    #[serde(default)]
+    pub connection_scope: String,
+    #[serde(default)]
+    pub connection_id: String,
+    #[serde(default)]
+    pub project_gid: String,
+    #[serde(default)]
+    pub poll_interval_seconds: Option<i64>,
+    #[serde(default)]
+    pub state: AsanaTriggerState,
+}
+
+#[derive(Debug)]
+pub struct AsanaPollResult {
+    pub events: Vec<Value>,
+    pub state: AsanaTriggerState,
+}
+
+pub fn parse_trigger_config(config: &Value) -> Option<(AsanaTriggerKind, AsanaTriggerConfig)> {
+    let trigger_type = config.get("triggerType")?.as_str()?;
+    let kind = AsanaTriggerKind::parse(trigger_type)?;
+    let parsed: AsanaTriggerConfig = serde_json::from_value(config.clone()).ok()?;
+    if parsed.connection_id.trim().is_empty()
+        || parsed.connection_scope.trim().is_empty()
+        || parsed.project_gid.trim().is_empty()
+    {
+        return None;
+    }
+    Some((kind, parsed))
+}
+
+/// Polling fallback used when the Asana webhook cannot be registered: lists
+/// project tasks modified since the last poll and emits the ones whose
+/// creation, completion or new comment falls inside `(last_polled_at, now]`.
+/// The first poll only records the cursor.
+pub async fn poll_project(
+    client: &reqwest::Client,
+    base_url: &str,
+    access_token: &str,
+    config: &AsanaTriggerConfig,
+    kind: AsanaTriggerKind,
+    now: OffsetDateTime,
+) -> Result<AsanaPollResult, AsanaError> {
+    let now_str = now.format(&Rfc3339).unwrap_or_default();
+    let state = AsanaTriggerState {
+        last_polled_at: Some(now_str),
+    };
+    let Some(since) = config
+        .state
+        .last_polled_at
+        .as_deref()
+        .and_then(parse_timestamp)
+    else {
+        return Ok(AsanaPollResult {
+            events: Vec::new(),
+            state,
+        });
+    };
+    let since_str = since.format(&Rfc3339).unwrap_or_default();
+    let project_gid = config.project_gid.trim();
+    let in_window = |value: Option<&Value>| {
+        value
+            .and_then(|v| v.as_str())
+            .and_then(parse_timestamp)
+            .filter(|at| *at > since && *at <= now)
+    };
+
+    let tasks =
+        asana::list_tasks_modified_since(client, base_url, access_token, project_gid, &since_str)
+            .await?;
+
+    let mut dated: Vec<(OffsetDateTime, Value)> = Vec::new();
+    match kind {
+        AsanaTriggerKind::NewTask => {
+            for task in &tasks {
+                if let Some(at) = in_window(task.get("created_at")) {
+                    dated.push((
+                        at,
+                        asana::build_trigger_event(kind, project_gid, task, None, "poll"),
+                    ));
+                }
+            }
+        }
+        AsanaTriggerKind::TaskCompleted => {
+            for task in &tasks {
+                let completed = task
+                    .get("completed")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(false);
+                if !completed {
+                    continue;
+                }
+                if let Some(at) = in_window(task.get("completed_at")) {
+                    dated.push((
+                        at,
+                        asana::build_trigger_event(kind, project_gid, task, None, "poll"),
+                    ));
+                }
+            }
+        }
+        AsanaTriggerKind::CommentAdded => {
+            for task in tasks.iter().take(MAX_COMMENT_TASKS_PER_POLL) {
+                let Some(task_gid) = task.get("gid").and_then(|v| v.as_str()) else {
+                    continue;
+                };
+                let stories =
+                    asana::list_task_stories(client, base_url, access_token, task_gid).await?;
+                for story in &stories {
+                    let is_comment = story.get("resource_subtype").and_then(|v| v.as_str())
+                        == Some("comment_added");
+                    if !is_comment {
+                        continue;
+                    }
+                    if let Some(at) = in_window(story.get("created_at")) {
+                        dated.push((
+                            at,
+                            asana::build_trigger_event(
+                                kind,
+                                project_gid,
+                                task,
+                                Some(story),
+                                "poll",
+                            ),
+                        ));
+                    }
+                }
+            }
+        }
+    }
+    dated.sort_by_key(|(at, _)| *at);
+
+    Ok(AsanaPollResult {
+        events: dated.into_iter().map(|(_, event)| event).collect(),
+        state,
+    })
+}
+
+fn parse_timestamp(raw: &str) -> Option<OffsetDateTime> {
+    OffsetDateTime::parse(raw.trim(), &Rfc3339).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::FakeProvider;
+    use axum::http::{Method, StatusCode};
+    use serde_json::json;
+
+    fn config(kind: AsanaTriggerKind, last_polled_at: Option<&str>) -> AsanaTriggerConfig {
+        AsanaTriggerConfig {
+            trigger_type: kind.as_str().to_string(),
+            connection_scope: "user".into(),
+            connection_id: "conn".into(),
+            project_gid: "p1".into(),
+            poll_interval_seconds: None,
+            state: AsanaTriggerState {
+                last_polled_at: last_polled_at.map(str::to_string),
+            },
+        }
+    }
+
+    fn at(raw: &str) -> OffsetDateTime {
+        OffsetDateTime::parse(raw, &Rfc3339).unwrap()
+    }
+
+    #[test]
+    fn parse_trigger_config_requires_project() {
+        let cfg = json!({
+            "triggerType": "asana.task_completed",
+            "connectionScope": "user",
+            "connectionId": "conn",
+            "projectGid": "p1",
+        });
+        let (kind, parsed) = parse_trigger_config(&cfg).expect("config");
+        assert_eq!(kind, AsanaTriggerKind::TaskCompleted);
+        assert_eq!(parsed.project_gid, "p1");
+
+        let missing = json!({
+            "triggerType": "asana.task_completed",
+            "connectionScope": "user",
+            "connectionId": "conn",
+        });
+        assert!(parse_trigger_config(&missing).is_none());
+    }
+
+    #[tokio::test]
+    async fn first_poll_only_records_cursor() {
+        let provider = FakeProvider::start().await;
+        provider.respond(
+            Method::GET,
+            "/asana/tasks",
+            StatusCode::OK,
+            json!({ "data": [] }),
+        );
+
+        let now = at("2026-10-18T10:00:00Z");
+        let result = poll_project(
+            &reqwest::Client::new(),
+            &provider.base_urls().asana,
+            "token",
+            &config(AsanaTriggerKind::NewTask, None),
+            AsanaTriggerKind::NewTask,
+            now,
+        )
+        .await
+        .expect("poll");
+
+        assert!(provider.requests().is_empty());
+        assert!(result.events.is_empty());
+        assert_eq!(
+            result.state.last_polled_at.as_deref(),
+            Some("2026-10-18T10:00:00Z")
+        );
+    }
+
+    #[tokio::test]
+    async fn poll_emits_tasks_created_inside_window() {
+        let provider = FakeProvider::start().await;
+        provider.respond(
+            Method::GET,
+            "/asana/tasks",
+            StatusCode::OK,
+            json!({
+                "data": [
+                    { "gid": "t-old", "name": "Old", "created_at": "2026-10-17T08:00:00Z" },
+                    { "gid": "t-2", "name": "Second", "created_at": "2026-10-18T09:30:00Z" },
+                    { "gid": "t-1", "name": "First", "created_at": "2026-10-18T09:10:00Z" }
+                ]
+            }),
+        );
+
+        let result = poll_project(
+            &reqwest::Client::new(),
+            &provider.base_urls().asana,
+            "token",
+            &config(AsanaTriggerKind::NewTask, Some("2026-10-18T09:00:00Z")),
+            AsanaTriggerKind::NewTask,
+            at("2026-10-18T10:00:00Z"),
+        )
+        .await
+        .expect("poll");
+
+        let requests = provider.requests_to(Method::GET, "/asana/tasks");
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].query_param("project").as_deref(), Some("p1"));
+        assert_eq!(
+            requests[0].query_param("modified_since").as_deref(),
+            Some("2026-10-18T09:00:00Z")
+        );
+        let gids: Vec<_> = result
+            .events
+            .iter()
+            .map(|e| e["taskGid"].as_str().unwrap().to_string())
+            .collect();
+        assert_eq!(gids, vec!["t-1", "t-2"]);
+        assert_eq!(result.events[0]["source"], "poll");
+        assert_eq!(result.events[0]["trigger"], "asana.new_task");
+    }
+
+    #[tokio::test]
+    async fn poll_emits_new_comments() {
+        let provider = FakeProvider::start().await;
+        provider.respond(
+            Method::GET,
+            "/asana/tasks",
+            StatusCode::OK,
+            json!({
+                "data": [{ "gid": "t-1", "name": "Task", "created_at": "2026-10-01T00:00:00Z" }]
+            }),
+        );
+        provider.respond(
+            Method::GET,
+            "/asana/tasks/t-1/stories",
+            StatusCode::OK,
+            json!({
+                "data": [
+                    { "gid": "s-1", "resource_subtype": "comment_added", "text": "old", "created_at": "2026-10-18T08:00:00Z" },
+                    { "gid": "s-2", "resource_subtype": "assigned", "created_at": "2026-10-18T09:20:00Z" },
+                    { "gid": "s-3", "resource_subtype": "comment_added", "text": "hello", "created_by": { "name": "Ana" }, "created_at": "2026-10-18T09:40:00Z" }
+                ]
+            }),
+        );
+
+        let result = poll_project(
+            &reqwest::Client::new(),
+            &provider.base_urls().asana,
+            "token",
+            &config(AsanaTriggerKind::CommentAdded, Some("2026-10-18T09:00:00Z")),
+            AsanaTriggerKind::CommentAdded,
+            at("2026-10-18T10:00:00Z"),
+        )
+        .await
+        .expect("poll");
+
+        assert_eq!(result.events.len(), 1);
+        assert_eq!(result.events[0]["commentGid"], "s-3");
+        assert_eq!(result.events[0]["commentText"], "hello");
+        assert_eq!(result.events[0]["commentAuthor"], "Ana");
+    }
+}
@@ -1,10 +1,18 @@
+mod asana;
 mod notion;
+mod outlook;
+mod queue;
+mod rss;
+mod sftp;
+mod teams;
 
+use std::future::Future;
 use std::time::Duration;
 
 #[cfg(test)]
 use std::sync::Arc;
 
+use crate::engine::actions::http::TriggerEgress;
 use crate::engine::actions::{ensure_run_membership, ensure_workspace_plan};
 use crate::engine::{complete_run_with_retry, execute_run, ExecutorError};
 use crate::models::workflow::Workflow;
@@ -15,6 +23,8 @@ use crate::runaway_protection::{
     enforce_runaway_protection, runaway_protection_enabled, RunawayProtectionError,
     RUNAWAY_PROTECTION_ERROR,
 };
+use crate::services::artifacts::purge_orphaned_artifacts;
+use crate::services::asana::AsanaTriggerKind;
 use crate::state::{AppState, WorkspaceLimitError, WorkspaceRunQuotaTicket};
 #[cfg(test)]
 use crate::utils::jwt::JwtKeys;
@@ -23,6 +33,7 @@ use crate::utils::schedule::{
 };
 use crate::utils::workflow_connection_metadata;
 use chrono::{Duration as ChronoDuration, Utc};
+use serde::Serialize;
 use serde_json::{json, Value};
 use tokio::task::JoinSet;
 use tokio::time::{sleep, timeout};
@@ -38,9 +49,9 @@ fn test_jwt_keys() -> Arc<JwtKeys> {
     )
 }
 
-const DEFAULT_NOTION_POLL_INTERVAL_SECONDS: i64 = 300;
-const MIN_NOTION_POLL_INTERVAL_SECONDS: i64 = 30;
-const MAX_NOTION_POLL_INTERVAL_SECONDS: i64 = 3600;
+const DEFAULT_POLL_INTERVAL_SECONDS: i64 = 300;
+const MIN_POLL_INTERVAL_SECONDS: i64 = 30;
+const MAX_POLL_INTERVAL_SECONDS: i64 = 3600;
 
 pub async fn start_background_workers(state: AppState) {
     // Simple single-worker for now. Can be extended to multiple tasks.
@@ -90,6 +101,20 @@ async fn worker_loop(state: AppState) -> Result<(), ExecutorError> {
                     "worker: failed to purge old runs"
                 );
             }
+            if let Err(err) = state.workflow_repo.purge_expired_kv_entries().await {
+                warn!(
+                    worker_id = %state.worker_id,
+                    error = ?err,
+                    "worker: failed to purge expired store entries"
+                );
+            }
+            if let Err(err) = purge_orphaned_artifacts(&state).await {
+                warn!(
+                    worker_id = %state.worker_id,
+                    error = %err,
+                    "worker: failed to purge run artifacts"
+                );
+            }
             last_cleanup = std::time::Instant::now();
         }
 
@@ -365,6 +390,67 @@ async fn trigger_schedule(state: &AppState, schedule: WorkflowSchedule) -> Resul
         .await;
     }
 
+    if let Some(teams_config) = teams::parse_trigger_config(&schedule.config) {
+        return trigger_teams_schedule(
+            state,
+            schedule,
+            workflow,
+            &settings,
+            next_time,
+            teams_config,
+        )
+        .await;
+    }
+
+    if let Some((outlook_kind, outlook_config)) = outlook::parse_trigger_config(&schedule.config) {
+        return trigger_outlook_schedule(
+            state,
+            schedule,
+            workflow,
+            &settings,
+            next_time,
+            outlook_kind,
+            outlook_config,
+        )
+        .await;
+    }
+
+    if let Some(rss_config) = rss::parse_trigger_config(&schedule.config) {
+        return trigger_rss_schedule(state, schedule, workflow, &settings, next_time, rss_config)
+            .await;
+    }
+
+    if let Some(sftp_config) = sftp::parse_trigger_config(&schedule.config) {
+        return trigger_sftp_schedule(state, schedule, workflow, &settings, next_time, sftp_config)
+            .await;
+    }
+
+    if let Some((queue_kind, queue_config)) = queue::parse_trigger_config(&schedule.config) {
+        return trigger_queue_schedule(
+            state,
+            schedule,
+            workflow,
+            &settings,
+            next_time,
+            queue_kind,
+            queue_config,
+        )
+        .await;
+    }
+
+    if let Some((asana_kind, asana_config)) = asana::parse_trigger_config(&schedule.config) {
+        return trigger_asana_schedule(
+            state,
+            schedule,
+            workflow,
+            &settings,
+            next_time,
+            asana_kind,
+            asana_config,
+        )
+        .await;
+    }
+
     let last_run_utc = match offset_to_utc(next_time) {
         Some(dt) => dt,
         None => {
@@ -560,6 +646,162 @@ async fn trigger_schedule(state: &AppState, schedule: WorkflowSchedule) -> Resul
     Ok(())
 }
 
+/// Describes a polling trigger to [`run_polling_trigger`].
+struct PollingTrigger<'a> {
+    /// Provider name used in log messages.
+    label: &'static str,
+    trigger_type: &'a str,
+    interval_seconds: i64,
+    /// OAuth connection whose access token is handed to the poll.
+    connection: Option<PollingConnection<'a>>,
+}
+
+struct PollingConnection<'a> {
+    scope: &'a str,
+    id: &'a str,
+    provider: ConnectedOAuthProvider,
+}
+
+/// Shared body of the polling triggers. Resolves the connection's access
+/// token (when the trigger has one), runs `poll`, stores the state it
+/// returns under the schedule config's `state` key and enqueues one run per
+/// event. A failed poll is logged and retried on the next tick.
+async fn run_polling_trigger<P, F, S, E>(
+    state: &AppState,
+    schedule: &WorkflowSchedule,
+    workflow: &Workflow,
+    settings: &Value,
+    scheduled_for: time::OffsetDateTime,
+    trigger: PollingTrigger<'_>,
+    poll: P,
+) -> Result<(), sqlx::Error>
+where
+    P: FnOnce(Option<String>) -> F,
+    F: Future<Output = Result<(Vec<Value>, S), E>>,
+    S: Serialize,
+    E: std::fmt::Display,
+{
+    let Some((last_offset, next_offset)) =
+        polling_window(state, schedule, trigger.label, trigger.interval_seconds).await?
+    else {
+        return Ok(());
+    };
+    let next_offset = Some(next_offset);
+
+    let access_token = match &trigger.connection {
+        Some(connection) => {
+            let Some(token) = resolve_polling_access_token(
+                state,
+                schedule,
+                workflow,
+                connection.scope,
+                connection.id,
+                connection.provider,
+            )
+            .await?
+            else {
+                state
+                    .workflow_repo
+                    .mark_schedule_run(schedule.id, last_offset, next_offset)
+                    .await?;
+                return Ok(());
+            };
+            Some(token)
+        }
+        None => None,
+    };
+
+    let (events, poll_state) = match poll(access_token).await {
+        Ok(result) => result,
+        Err(err) => {
+            warn!(
+                schedule_id = %schedule.id,
+                workflow_id = %schedule.workflow_id,
+                trigger = trigger.trigger_type,
+                error = %err,
+                "worker: {} polling failed",
+                trigger.label
+            );
+            state
+                .workflow_repo
+                .mark_schedule_run(schedule.id, last_offset, next_offset)
+                .await?;
+            return Ok(());
+        }
+    };
+
+    let mut schedule_config = schedule.config.clone();
+    if let Some(updated) = update_config_state(&schedule.config, &poll_state) {
+        if updated != schedule.config {
+            state
+                .workflow_repo
+                .upsert_workflow_schedule(
+                    schedule.user_id,
+                    schedule.workflow_id,
+                    updated.clone(),
+                    next_offset,
+                )
+                .await?;
+        }
+        schedule_config = updated;
+    }
+
+    enqueue_polled_trigger_runs(
+        state,
+        schedule,
+        workflow,
+        settings,
+        scheduled_for,
+        trigger.trigger_type,
+        &schedule_config,
+        events,
+    )
+    .await?;
+
+    state
+        .workflow_repo
+        .mark_schedule_run(schedule.id, last_offset, next_offset)
+        .await?;
+
+    Ok(())
+}
+
+/// Returns this tick's `last_run_at` and the time of the next poll. When
+/// either cannot be represented the schedule is disabled and `None` is
+/// returned.
+async fn polling_window(
+    state: &AppState,
+    schedule: &WorkflowSchedule,
+    label: &str,
+    interval_seconds: i64,
+) -> Result<Option<(time::OffsetDateTime, time::OffsetDateTime)>, sqlx::Error> {
+    let now = Utc::now();
+    let window = utc_to_offset(now).zip(polling_next_run_offset(now, interval_seconds));
+    if window.is_none() {
+        warn!(
+            schedule_id = %schedule.id,
+            workflow_id = %schedule.workflow_id,
+            "worker: unable to compute next {} poll interval; disabling schedule",
+            label
+        );
+        state
+            .workflow_repo
+            .disable_workflow_schedule(schedule.workflow_id)
+            .await?;
+    }
+    Ok(window)
+}
+
+/// Stores a polling trigger's state under the schedule config's `state` key.
+fn update_config_state<S: Serialize>(config: &Value, state: &S) -> Option<Value> {
+    let mut updated = config.clone();
+    if let Value::Object(map) = &mut updated {
+        map.insert("state".to_string(), serde_json::to_value(state).ok()?);
+        return Some(updated);
+    }
+    None
+}
+
 async fn trigger_notion_schedule(
     state: &AppState,
     schedule: WorkflowSchedule,
@@ -569,59 +811,416 @@ async fn trigger_notion_schedule(
     notion_kind: notion::NotionTriggerKind,
     notion_config: notion::NotionTriggerConfig,
 ) -> Result<(), sqlx::Error> {
-    let now = Utc::now();
-    let last_offset = match utc_to_offset(now) {
-        Some(v) => v,
-        None => {
+    let config = &notion_config;
+    let schedule_id = schedule.id;
+    let trigger = PollingTrigger {
+        label: "Notion",
+        trigger_type: notion_kind.as_str(),
+        interval_seconds: poll_interval_seconds(
+            config.poll_interval_seconds,
+            "NOTION_POLL_INTERVAL_SECONDS",
+        ),
+        connection: Some(PollingConnection {
+            scope: &config.connection_scope,
+            id: &config.connection_id,
+            provider: ConnectedOAuthProvider::Notion,
+        }),
+    };
+    run_polling_trigger(
+        state,
+        &schedule,
+        &workflow,
+        settings,
+        scheduled_for,
+        trigger,
+        |token| async move {
+            let token = token.unwrap_or_default();
+            if notion_kind == notion::NotionTriggerKind::PropertyChanged {
+                return poll_notion_property_changes(state, schedule_id, &token, config).await;
+            }
+            let base_url = &state.config.provider_base_urls.notion;
+            notion::poll_database(&state.http_client, base_url, &token, config, notion_kind)
+                .await
+                .map(|result| (result.events, result.state))
+                .map_err(|err| err.to_string())
+        },
+    )
+    .await
+}
+
+/// Property-changed polls compare against page snapshots kept in their own
+/// table, so only pages that were added or changed are written back.
+async fn poll_notion_property_changes(
+    state: &AppState,
+    schedule_id: Uuid,
+    token: &str,
+    config: &notion::NotionTriggerConfig,
+) -> Result<(Vec<Value>, notion::NotionTriggerState), String> {
+    let base_url = &state.config.provider_base_urls.notion;
+    let scan = notion::scan_property_changes(&state.http_client, base_url, token, config)
+        .await
+        .map_err(|err| err.to_string())?;
+    let reset = scan.baseline;
+    let previous = if reset {
+        Vec::new()
+    } else {
+        state
+            .workflow_repo
+            .list_notion_page_snapshots(schedule_id, &scan.page_ids())
+            .await
+            .map_err(|err| err.to_string())?
+    };
+    let (result, changed) = notion::diff_property_changes(config, scan, previous);
+    state
+        .workflow_repo
+        .save_notion_page_snapshots(schedule_id, reset, &changed, notion::MAX_TRACKED_PAGES)
+        .await
+        .map_err(|err| err.to_string())?;
+    Ok((result.events, result.state))
+}
+
+async fn trigger_teams_schedule(
+    state: &AppState,
+    schedule: WorkflowSchedule,
+    workflow: Workflow,
+    settings: &Value,
+    scheduled_for: time::OffsetDateTime,
+    teams_config: teams::TeamsTriggerConfig,
+) -> Result<(), sqlx::Error> {
+    let config = &teams_config;
+    let trigger = PollingTrigger {
+        label: "Teams",
+        trigger_type: teams::TEAMS_NEW_CHANNEL_MESSAGE,
+        interval_seconds: poll_interval_seconds(
+            config.poll_interval_seconds,
+            "TEAMS_POLL_INTERVAL_SECONDS",
+        ),
+        connection: Some(PollingConnection {
+            scope: &config.connection_scope,
+            id: &config.connection_id,
+            provider: ConnectedOAuthProvider::Microsoft,
+        }),
+    };
+    run_polling_trigger(
+        state,
+        &schedule,
+        &workflow,
+        settings,
+        scheduled_for,
+        trigger,
+        |token| async move {
+            let token = token.unwrap_or_default();
+            let base_url = &state.config.provider_base_urls.microsoft_graph;
+            teams::poll_channel(&state.http_client, base_url, &token, config)
+                .await
+                .map(|result| (result.events, result.state))
+        },
+    )
+    .await
+}
+
+async fn trigger_outlook_schedule(
+    state: &AppState,
+    schedule: WorkflowSchedule,
+    workflow: Workflow,
+    settings: &Value,
+    scheduled_for: time::OffsetDateTime,
+    outlook_kind: outlook::OutlookTriggerKind,
+    outlook_config: outlook::OutlookTriggerConfig,
+) -> Result<(), sqlx::Error> {
+    let config = &outlook_config;
+    let trigger = PollingTrigger {
+        label: "Outlook",
+        trigger_type: outlook_kind.as_str(),
+        interval_seconds: poll_interval_seconds(
+            config.poll_interval_seconds,
+            "OUTLOOK_POLL_INTERVAL_SECONDS",
+        ),
+        connection: Some(PollingConnection {
+            scope: &config.connection_scope,
+            id: &config.connection_id,
+            provider: ConnectedOAuthProvider::Microsoft,
+        }),
+    };
+    run_polling_trigger(
+        state,
+        &schedule,
+        &workflow,
+        settings,
+        scheduled_for,
+        trigger,
+        |token| async move {
+            let token = token.unwrap_or_default();
+            let base_url = &state.config.provider_base_urls.microsoft_graph;
+            outlook::poll_mailbox(&state.http_client, base_url, &token, config, outlook_kind)
+                .await
+                .map(|result| (result.events, result.state))
+        },
+    )
+    .await
+}
+
+async fn trigger_rss_schedule(
+    state: &AppState,
+    schedule: WorkflowSchedule,
+    workflow: Workflow,
+    settings: &Value,
+    scheduled_for: time::OffsetDateTime,
+    rss_config: rss::RssTriggerConfig,
+) -> Result<(), sqlx::Error> {
+    let config = &rss_config;
+    let trigger = PollingTrigger {
+        label: "RSS",
+        trigger_type: rss::RSS_NEW_ITEM,
+        interval_seconds: poll_interval_seconds(
+            config.poll_interval_seconds,
+            "RSS_POLL_INTERVAL_SECONDS",
+        ),
+        connection: None,
+    };
+    run_polling_trigger(
+        state,
+        &schedule,
+        &workflow,
+        settings,
+        scheduled_for,
+        trigger,
+        |_| async move {
+            rss::poll_feed(config, &TriggerEgress::from_env())
+                .await
+                .map(|result| (result.events, result.state))
+        },
+    )
+    .await
+}
+
+async fn trigger_sftp_schedule(
+    state: &AppState,
+    schedule: WorkflowSchedule,
+    workflow: Workflow,
+    settings: &Value,
+    scheduled_for: time::OffsetDateTime,
+    sftp_config: sftp::SftpTriggerConfig,
+) -> Result<(), sqlx::Error> {
+    let config = &sftp_config;
+    let schedule_config = &schedule.config;
+    let trigger = PollingTrigger {
+        label: "SFTP",
+        trigger_type: sftp::SFTP_NEW_FILE,
+        interval_seconds: poll_interval_seconds(
+            config.poll_interval_seconds,
+            "SFTP_POLL_INTERVAL_SECONDS",
+        ),
+        connection: None,
+    };
+    run_polling_trigger(
+        state,
+        &schedule,
+        &workflow,
+        settings,
+        scheduled_for,
+        trigger,
+        |_| async move {
+            let target = sftp::resolve_target(
+                schedule_config,
+                settings,
+                &state.config.api_secrets_encryption_key,
+            )?;
+            sftp::poll_directory(config, &target, &TriggerEgress::from_env())
+                .await
+                .map(|result| (result.events, result.state))
+        },
+    )
+    .await
+}
+
+async fn trigger_queue_schedule(
+    state: &AppState,
+    schedule: WorkflowSchedule,
+    workflow: Workflow,
+    settings: &Value,
+    scheduled_for: time::OffsetDateTime,
+    kind: queue::QueueTriggerKind,
+    queue_config: queue::QueueTriggerConfig,
+) -> Result<(), sqlx::Error> {
+    let interval = poll_interval_seconds_or(
+        queue_config.poll_interval_seconds,
+        "QUEUE_POLL_INTERVAL_SECONDS",
+        queue::DEFAULT_POLL_INTERVAL_SECONDS,
+    );
+    let Some((last_offset, next_offset)) =
+        polling_window(state, &schedule, "queue", interval).await?
+    else {
+        return Ok(());
+    };
+
+    let receive = async {
+        let url = queue::resolve_connection_url(
+            &schedule.config,
+            settings,
+            &state.config.api_secrets_encryption_key,
+        )?;
+        queue::receive(
+            kind,
+            &queue_config,
+            &url,
+            schedule.workflow_id,
+            &TriggerEgress::from_env(),
+        )
+        .await
+    };
+    let batch = match receive.await {
+        Ok(batch) => batch,
+        Err(err) => {
+            warn!(
+                schedule_id = %schedule.id,
+                workflow_id = %schedule.workflow_id,
+                error = %err,
+                "worker: queue trigger polling failed"
+            );
             state
                 .workflow_repo
-                .disable_workflow_schedule(schedule.workflow_id)
+                .mark_schedule_run(schedule.id, last_offset, Some(next_offset))
                 .await?;
             return Ok(());
         }
     };
-    let next_offset = notion_next_run_offset(now, &notion_config);
-    if next_offset.is_none() {
+
+    let received = batch.len();
+    let enqueued = enqueue_keyed_trigger_runs(
+        state,
+        &schedule,
+        &workflow,
+        settings,
+        scheduled_for,
+        kind.trigger_type(),
+        &schedule.config,
+        batch.events(),
+    )
+    .await;
+    // Messages are acked only once their run exists; on any failure they
+    // go back to the queue and are delivered again.
+    let handed_off = *enqueued.as_ref().unwrap_or(&0);
+    if let Err(err) = batch.settle(handed_off).await {
         warn!(
             schedule_id = %schedule.id,
             workflow_id = %schedule.workflow_id,
-            "worker: unable to compute next Notion poll interval; disabling schedule"
+            error = %err,
+            "worker: failed to acknowledge queue messages; they will be redelivered"
         );
-        state
-            .workflow_repo
-            .disable_workflow_schedule(schedule.workflow_id)
-            .await?;
-        return Ok(());
     }
+    enqueued?;
 
-    let scope_raw = notion_config.connection_scope.trim().to_ascii_lowercase();
-    let connection_id = notion_config.connection_id.trim();
+    // A full batch likely means a backlog, so poll again right away.
+    let next_offset = if received >= queue_config.max_messages() && handed_off == received {
+        last_offset
+    } else {
+        next_offset
+    };
+    state
+        .workflow_repo
+        .mark_schedule_run(schedule.id, last_offset, Some(next_offset))
+        .await?;
+
+    Ok(())
+}
+
+async fn trigger_asana_schedule(
+    state: &AppState,
+    schedule: WorkflowSchedule,
+    workflow: Workflow,
+    settings: &Value,
+    scheduled_for: time::OffsetDateTime,
+    asana_kind: AsanaTriggerKind,
+    asana_config: asana::AsanaTriggerConfig,
+) -> Result<(), sqlx::Error> {
+    let config = &asana_config;
+    let trigger = PollingTrigger {
+        label: "Asana",
+        trigger_type: asana_kind.as_str(),
+        interval_seconds: poll_interval_seconds(
+            config.poll_interval_seconds,
+            "ASANA_POLL_INTERVAL_SECONDS",
+        ),
+        connection: Some(PollingConnection {
+            scope: &config.connection_scope,
+            id: &config.connection_id,
+            provider: ConnectedOAuthProvider::Asana,
+        }),
+    };
+    run_polling_trigger(
+        state,
+        &schedule,
+        &workflow,
+        settings,
+        scheduled_for,
+        trigger,
+        |token| async move {
+            let token = token.unwrap_or_default();
+            let base_url = &state.config.provider_base_urls.asana;
+            let now = time::OffsetDateTime::now_utc();
+            asana::poll_project(
+                &state.http_client,
+                base_url,
+                &token,
+                config,
+                asana_kind,
+                now,
+            )
+            .await
+            .map(|result| (result.events, result.state))
+        },
+    )
+    .await
+}
+
+/// Resolves the OAuth access token behind a polling trigger's connection.
+/// Returns `Ok(None)` (after logging why) when the trigger cannot poll this
+/// tick; only database failures are surfaced as errors.
+async fn resolve_polling_access_token(
+    state: &AppState,
+    schedule: &WorkflowSchedule,
+    workflow: &Workflow,
+    connection_scope: &str,
+    connection_id: &str,
+    provider: ConnectedOAuthProvider,
+) -> Result<Option<String>, sqlx::Error> {
+    let scope_raw = connection_scope.trim().to_ascii_lowercase();
+    let connection_id = connection_id.trim();
     if scope_raw.is_empty() || connection_id.is_empty() {
         warn!(
             schedule_id = %schedule.id,
             workflow_id = %schedule.workflow_id,
-            "worker: Notion trigger missing connection scope or connection id"
+            ?provider,
+            "worker: polling trigger missing connection scope or connection id"
         );
-        state
-            .workflow_repo
-            .mark_schedule_run(schedule.id, last_offset, next_offset)
-            .await?;
-        return Ok(());
+        return Ok(None);
     }
 
-    let access_token = match scope_raw.as_str() {
+    let parsed = match Uuid::parse_str(connection_id) {
+        Ok(id) => id,
+        Err(_) => {
+            warn!(
+                schedule_id = %schedule.id,
+                workflow_id = %schedule.workflow_id,
+                connection_id = %connection_id,
+                ?provider,
+                "worker: polling trigger connection id must be a UUID"
+            );
+            return Ok(None);
+        }
+    };
+
+    match scope_raw.as_str() {
         "workspace" => {
             let Some(workspace_id) = workflow.workspace_id else {
                 warn!(
                     schedule_id = %schedule.id,
                     workflow_id = %schedule.workflow_id,
-                    "worker: Notion workspace trigger requires workspace-bound workflow"
+                    ?provider,
+                    "worker: workspace-scoped polling trigger requires workspace-bound workflow"
                 );
-                state
-                    .workflow_repo
-                    .mark_schedule_run(schedule.id, last_offset, next_offset)
-                    .await?;
-                return Ok(());
+                return Ok(None);
             };
             if let Err(err) = ensure_run_membership(state, workspace_id, schedule.user_id).await {
                 warn!(
@@ -629,13 +1228,10 @@ async fn trigger_notion_schedule(
                     workflow_id = %schedule.workflow_id,
                     %workspace_id,
                     %err,
-                    "worker: Notion trigger workspace membership check failed"
+                    ?provider,
+                    "worker: polling trigger workspace membership check failed"
                 );
-                state
-                    .workflow_repo
-                    .mark_schedule_run(schedule.id, last_offset, next_offset)
-                    .await?;
-                return Ok(());
+                return Ok(None);
             }
             if let Err(err) = ensure_workspace_plan(state, workspace_id).await {
                 warn!(
@@ -643,32 +1239,12 @@ async fn trigger_notion_schedule(
                     workflow_id = %schedule.workflow_id,
                     %workspace_id,
                     %err,
-                    "worker: Notion trigger requires workspace plan"
+                    ?provider,
+                    "worker: polling trigger requires workspace plan"
                 );
-                state
-                    .workflow_repo
-                    .mark_schedule_run(schedule.id, last_offset, next_offset)
-                    .await?;
-                return Ok(());
+                return Ok(None);
             }
 
-            let parsed = match Uuid::parse_str(connection_id) {
-                Ok(id) => id,
-                Err(_) => {
-                    warn!(
-                        schedule_id = %schedule.id,
-                        workflow_id = %schedule.workflow_id,
-                        connection_id = %connection_id,
-                        "worker: Notion workspace connection id must be a UUID"
-                    );
-                    state
-                        .workflow_repo
-                        .mark_schedule_run(schedule.id, last_offset, next_offset)
-                        .await?;
-                    return Ok(());
-                }
-            };
-
             match state
                 .workspace_oauth
                 .ensure_valid_workspace_token(parsed)
@@ -680,104 +1256,68 @@ async fn trigger_notion_schedule(
                             schedule_id = %schedule.id,
                             workflow_id = %schedule.workflow_id,
                             %workspace_id,
-                            "worker: Notion workspace connection belongs to another workspace"
+                            ?provider,
+                            "worker: polling trigger connection belongs to another workspace"
                         );
-                        state
-                            .workflow_repo
-                            .mark_schedule_run(schedule.id, last_offset, next_offset)
-                            .await?;
-                        return Ok(());
+                        return Ok(None);
                     }
-                    if connection.provider != ConnectedOAuthProvider::Notion {
+                    if connection.provider != provider {
                         warn!(
                             schedule_id = %schedule.id,
                             workflow_id = %schedule.workflow_id,
-                            "worker: Notion trigger connection is not Notion provider"
+                            ?provider,
+                            "worker: polling trigger connection uses a different provider"
                         );
-                        state
-                            .workflow_repo
-                            .mark_schedule_run(schedule.id, last_offset, next_offset)
-                            .await?;
-                        return Ok(());
+                        return Ok(None);
                     }
-                    connection.access_token
+                    Ok(Some(connection.access_token))
                 }
-                Err(err) => match err {
-                    crate::services::oauth::workspace_service::WorkspaceOAuthError::Database(
-                        db_err,
-                    ) => return Err(db_err),
-                    other => {
-                        warn!(
-                            schedule_id = %schedule.id,
-                            workflow_id = %schedule.workflow_id,
-                            error = %other,
-                            "worker: failed to resolve Notion workspace access token"
-                        );
-                        state
-                            .workflow_repo
-                            .mark_schedule_run(schedule.id, last_offset, next_offset)
-                            .await?;
-                        return Ok(());
-                    }
-                },
-            }
-        }
-        "personal" | "user" => {
-            let parsed = match Uuid::parse_str(connection_id) {
-                Ok(id) => id,
-                Err(_) => {
+                Err(crate::services::oauth::workspace_service::WorkspaceOAuthError::Database(
+                    db_err,
+                )) => Err(db_err),
+                Err(other) => {
                     warn!(
                         schedule_id = %schedule.id,
                         workflow_id = %schedule.workflow_id,
-                        connection_id = %connection_id,
-                        "worker: Notion personal connection id must be a UUID"
+                        error = %other,
+                        ?provider,
+                        "worker: failed to resolve workspace access token for polling trigger"
                     );
-                    state
-                        .workflow_repo
-                        .mark_schedule_run(schedule.id, last_offset, next_offset)
-                        .await?;
-                    return Ok(());
+                    Ok(None)
                 }
-            };
-
+            }
+        }
+        "personal" | "user" => {
             match state
                 .oauth_accounts
                 .ensure_valid_access_token_for_connection(schedule.user_id, parsed)
                 .await
             {
                 Ok(token) => {
-                    if token.provider != ConnectedOAuthProvider::Notion {
+                    if token.provider != provider {
                         warn!(
                             schedule_id = %schedule.id,
                             workflow_id = %schedule.workflow_id,
-                            "worker: Notion trigger connection is not Notion provider"
+                            ?provider,
+                            "worker: polling trigger connection uses a different provider"
                         );
-                        state
-                            .workflow_repo
-                            .mark_schedule_run(schedule.id, last_offset, next_offset)
-                            .await?;
-                        return Ok(());
+                        return Ok(None);
                     }
-                    token.access_token
+                    Ok(Some(token.access_token))
+                }
+                Err(crate::services::oauth::account_service::OAuthAccountError::Database(
+                    db_err,
+                )) => Err(db_err),
+                Err(other) => {
+                    warn!(
+                        schedule_id = %schedule.id,
+                        workflow_id = %schedule.workflow_id,
+                        error = %other,
+                        ?provider,
+                        "worker: failed to resolve personal access token for polling trigger"
+                    );
+                    Ok(None)
                 }
-                Err(err) => match err {
-                    crate::services::oauth::account_service::OAuthAccountError::Database(
-                        db_err,
-                    ) => return Err(db_err),
-                    other => {
-                        warn!(
-                            schedule_id = %schedule.id,
-                            workflow_id = %schedule.workflow_id,
-                            error = %other,
-                            "worker: failed to resolve Notion personal access token"
-                        );
-                        state
-                            .workflow_repo
-                            .mark_schedule_run(schedule.id, last_offset, next_offset)
-                            .await?;
-                        return Ok(());
-                    }
-                },
             }
         }
         other => {
@@ -785,62 +1325,60 @@ async fn trigger_notion_schedule(
                 schedule_id = %schedule.id,
                 workflow_id = %schedule.workflow_id,
                 scope = %other,
-                "worker: Notion trigger has unsupported connection scope"
+                ?provider,
+                "worker: polling trigger has unsupported connection scope"
             );
-            state
-                .workflow_repo
-                .mark_schedule_run(schedule.id, last_offset, next_offset)
-                .await?;
-            return Ok(());
+            Ok(None)
         }
-    };
+    }
+}
 
-    let poll_result = match notion::poll_database(
-        &state.http_client,
-        &access_token,
-        &notion_config,
-        notion_kind,
+/// Creates one run per polled event, starting from the trigger node that
+/// matches `schedule_config`. Runaway protection and workspace run quotas are
+/// applied the same way as for scheduled runs.
+#[allow(clippy::too_many_arguments)]
+async fn enqueue_polled_trigger_runs(
+    state: &AppState,
+    schedule: &WorkflowSchedule,
+    workflow: &Workflow,
+    settings: &Value,
+    scheduled_for: time::OffsetDateTime,
+    trigger_type: &str,
+    schedule_config: &Value,
+    events: Vec<Value>,
+) -> Result<(), sqlx::Error> {
+    enqueue_keyed_trigger_runs(
+        state,
+        schedule,
+        workflow,
+        settings,
+        scheduled_for,
+        trigger_type,
+        schedule_config,
+        events.into_iter().map(|event| (event, None)).collect(),
     )
     .await
-    {
-        Ok(result) => result,
-        Err(err) => {
-            if err.is_auth_error() {
-                warn!(
-                    schedule_id = %schedule.id,
-                    workflow_id = %schedule.workflow_id,
-                    "worker: Notion auth error while polling"
-                );
-            } else {
-                warn!(
-                    schedule_id = %schedule.id,
-                    workflow_id = %schedule.workflow_id,
-                    error = %err,
-                    "worker: Notion polling failed"
-                );
-            }
-            state
-                .workflow_repo
-                .mark_schedule_run(schedule.id, last_offset, next_offset)
-                .await?;
-            return Ok(());
-        }
-    };
+    .map(|_| ())
+}
 
-    let mut schedule_config = schedule.config.clone();
-    if let Some(updated) = notion::update_config_state(&schedule.config, &poll_result.state) {
-        if updated != schedule.config {
-            state
-                .workflow_repo
-                .upsert_workflow_schedule(
-                    schedule.user_id,
-                    schedule.workflow_id,
-                    updated.clone(),
-                    next_offset,
-                )
-                .await?;
-        }
-        schedule_config = updated;
+/// Creates one run per event, using the paired key (if any) as the run's
+/// idempotency key. Returns how many events, in order, now have a run: an
+/// event whose key matched an existing run counts as handed off. Events
+/// after that count were skipped because of plan limits or runaway
+/// protection.
+#[allow(clippy::too_many_arguments)]
+async fn enqueue_keyed_trigger_runs(
+    state: &AppState,
+    schedule: &WorkflowSchedule,
+    workflow: &Workflow,
+    settings: &Value,
+    scheduled_for: time::OffsetDateTime,
+    trigger_type: &str,
+    schedule_config: &Value,
+    events: Vec<(Value, Option<String>)>,
+) -> Result<usize, sqlx::Error> {
+    if events.is_empty() {
+        return Ok(0);
     }
 
     let mut base_snapshot = workflow.data.clone();
@@ -856,11 +1394,9 @@ async fn trigger_notion_schedule(
         obj.remove("_trigger_context");
     }
 
-    if let Some(start_id) = find_trigger_start_node_by_type(
-        &base_snapshot,
-        notion_kind.as_str(),
-        Some(&schedule_config),
-    ) {
+    if let Some(start_id) =
+        find_trigger_start_node_by_type(&base_snapshot, trigger_type, Some(schedule_config))
+    {
         base_snapshot["_start_from_node"] = Value::String(start_id);
     }
 
@@ -868,7 +1404,6 @@ async fn trigger_notion_schedule(
     workflow_connection_metadata::embed(&mut base_snapshot, &connection_metadata);
 
     let triggered_by = format!("schedule:{}", schedule.id);
-    let mut skip_all_runs = false;
     if let Some(workspace_id) = workflow.workspace_id {
         match enforce_runaway_protection(state, workspace_id, settings).await {
             Ok(()) => {}
@@ -879,9 +1414,10 @@ async fn trigger_notion_schedule(
                     %schedule.id,
                     %count,
                     %limit,
-                    "runaway protection blocked Notion trigger runs"
+                    %trigger_type,
+                    "runaway protection blocked polling trigger runs"
                 );
-                skip_all_runs = true;
+                return Ok(0);
             }
             Err(RunawayProtectionError::Database(err)) => {
                 return Err(err);
@@ -889,17 +1425,13 @@ async fn trigger_notion_schedule(
         }
     }
 
-    for event in poll_result.events {
-        if skip_all_runs {
-            break;
-        }
-
+    let mut handed_off = 0;
+    for (event, idempotency_key) in events {
         let mut snapshot = base_snapshot.clone();
         snapshot["_trigger_context"] =
-            build_notion_trigger_context(event, &schedule, &schedule_config, scheduled_for);
+            build_polled_trigger_context(event, schedule, schedule_config, scheduled_for);
 
         let mut workspace_quota: Option<WorkspaceRunQuotaTicket> = None;
-        let mut skip_run = false;
         if let Some(workspace_id) = workflow.workspace_id {
             match state.consume_workspace_run_quota(workspace_id).await {
                 Ok(Some(ticket)) => {
@@ -911,7 +1443,8 @@ async fn trigger_notion_schedule(
                             run_count = ticket.run_count,
                             %schedule.id,
                             %ticket.limit,
-                            "workspace run overage recorded for Notion trigger run"
+                            %trigger_type,
+                            "workspace run overage recorded for polling trigger run"
                         );
                     }
                     workspace_quota = Some(ticket);
@@ -922,10 +1455,10 @@ async fn trigger_notion_schedule(
                         worker_id = %state.worker_id,
                         %workspace_id,
                         schedule_id = %schedule.id,
-                        "skipping Notion trigger run because workspace reverted to the Solo plan"
+                        %trigger_type,
+                        "skipping polling trigger run because workspace reverted to the Solo plan"
                     );
-                    skip_run = true;
-                    skip_all_runs = true;
+                    break;
                 }
                 Err(WorkspaceLimitError::RunLimitReached { limit }) => {
                     warn!(
@@ -942,10 +1475,10 @@ async fn trigger_notion_schedule(
                         %workspace_id,
                         schedule_id = %schedule.id,
                         %limit,
-                        "unexpected member limit error while triggering Notion schedule"
+                        %trigger_type,
+                        "unexpected member limit error while triggering polling schedule"
                     );
-                    skip_run = true;
-                    skip_all_runs = true;
+                    break;
                 }
                 Err(WorkspaceLimitError::Database(err)) => {
                     return Err(err);
@@ -953,10 +1486,6 @@ async fn trigger_notion_schedule(
             }
         }
 
-        if skip_run {
-            continue;
-        }
-
         let outcome = match state
             .workflow_repo
             .create_workflow_run(
@@ -964,7 +1493,7 @@ async fn trigger_notion_schedule(
                 schedule.workflow_id,
                 workflow.workspace_id,
                 snapshot,
-                None,
+                idempotency_key.as_deref(),
             )
             .await
         {
@@ -990,40 +1519,33 @@ async fn trigger_notion_schedule(
         for event in events {
             state.workflow_repo.record_run_event(event).await?;
         }
+        handed_off += 1;
     }
 
-    state
-        .workflow_repo
-        .mark_schedule_run(schedule.id, last_offset, next_offset)
-        .await?;
+    Ok(handed_off)
+}
 
-    Ok(())
+fn poll_interval_seconds(configured: Option<i64>, env_key: &str) -> i64 {
+    poll_interval_seconds_or(configured, env_key, DEFAULT_POLL_INTERVAL_SECONDS)
 }
 
-fn notion_poll_interval_seconds(config: &notion::NotionTriggerConfig) -> i64 {
-    let from_env = std::env::var("NOTION_POLL_INTERVAL_SECONDS")
+fn poll_interval_seconds_or(configured: Option<i64>, env_key: &str, default: i64) -> i64 {
+    let from_env = std::env::var(env_key)
         .ok()
         .and_then(|value| value.parse::<i64>().ok());
-    let raw = config
-        .poll_interval_seconds
-        .or(from_env)
-        .unwrap_or(DEFAULT_NOTION_POLL_INTERVAL_SECONDS);
-    raw.clamp(
-        MIN_NOTION_POLL_INTERVAL_SECONDS,
-        MAX_NOTION_POLL_INTERVAL_SECONDS,
-    )
+    let raw = configured.or(from_env).unwrap_or(default);
+    raw.clamp(MIN_POLL_INTERVAL_SECONDS, MAX_POLL_INTERVAL_SECONDS)
 }
 
-fn notion_next_run_offset(
+fn polling_next_run_offset(
     now: chrono::DateTime<Utc>,
-    config: &notion::NotionTriggerConfig,
+    interval_seconds: i64,
 ) -> Option<time::OffsetDateTime> {
-    let interval = notion_poll_interval_seconds(config).max(1);
-    let next = now.checked_add_signed(ChronoDuration::seconds(interval))?;
+    let next = now.checked_add_signed(ChronoDuration::seconds(interval_seconds.max(1)))?;
     utc_to_offset(next)
 }
 
-fn build_notion_trigger_context(
+fn build_polled_trigger_context(
     mut event: Value,
     schedule: &WorkflowSchedule,
     schedule_config: &Value,
@@ -1081,7 +1603,7 @@ fn find_trigger_start_node_by_type(
         }
 
         if let (Some(config), Some(map)) = (schedule_config, data) {
-            if notion_trigger_matches_config(map, config) {
+            if polled_trigger_matches_config(map, config) {
                 return Some(id.to_string());
             }
         }
@@ -1094,7 +1616,24 @@ fn find_trigger_start_node_by_type(
     fallback
 }
 
-fn notion_trigger_matches_config(
+/// Identifying fields copied from a polling trigger node into its schedule
+/// config; a node matches when every field present in the config agrees.
+const POLLED_TRIGGER_MATCH_KEYS: &[&str] = &[
+    "databaseId",
+    "teamId",
+    "channelId",
+    "folderId",
+    "projectGid",
+    "feedUrl",
+    "host",
+    "directory",
+    "queue",
+    "stream",
+    "connectionId",
+    "connectionScope",
+];
+
+fn polled_trigger_matches_config(
     node_data: &serde_json::Map<String, Value>,
     config: &Value,
 ) -> bool {
@@ -1102,31 +1641,14 @@ fn notion_trigger_matches_config(
         return false;
     };
 
-    let config_db = read_config_string(config_map.get("databaseId"));
-    if let Some(expected) = config_db.as_deref() {
-        let actual = read_config_string(node_data.get("databaseId"));
-        if actual.as_deref() != Some(expected) {
-            return false;
-        }
-    }
-
-    let config_conn = read_config_string(config_map.get("connectionId"));
-    if let Some(expected) = config_conn.as_deref() {
-        let actual = read_config_string(node_data.get("connectionId"));
-        if actual.as_deref() != Some(expected) {
-            return false;
-        }
-    }
-
-    let config_scope = read_config_string(config_map.get("connectionScope"));
-    if let Some(expected) = config_scope.as_deref() {
-        let actual = read_config_string(node_data.get("connectionScope"));
-        if actual.as_deref() != Some(expected) {
-            return false;
-        }
-    }
-
-    true
+    POLLED_TRIGGER_MATCH_KEYS
+        .iter()
+        .all(|key| match read_config_string(config_map.get(*key)) {
+            Some(expected) => {
+                read_config_string(node_data.get(*key)).as_deref() == Some(expected.as_str())
+            }
+            None => true,
+        })
 }
 
 fn read_config_string(value: Option<&Value>) -> Option<String> {
@@ -1178,8 +1700,8 @@ fn find_schedule_trigger_start_node(snapshot: &Value, schedule_config: &Value) -
 mod tests {
     use super::*;
     use crate::config::{
-        Config, OAuthProviderConfig, OAuthSettings, StripeSettings, DEFAULT_WORKSPACE_MEMBER_LIMIT,
-        DEFAULT_WORKSPACE_MONTHLY_RUN_LIMIT, RUNAWAY_LIMIT_5MIN,
+        Config, OAuthProviderConfig, OAuthSettings, ProviderBaseUrls, StripeSettings,
+        DEFAULT_WORKSPACE_MEMBER_LIMIT, DEFAULT_WORKSPACE_MONTHLY_RUN_LIMIT, RUNAWAY_LIMIT_5MIN,
     };
     use crate::db::mock_db::{
         MockDb, NoopWorkspaceRepository, StaticWorkspaceMembershipRepository,
@@ -1278,6 +1800,11 @@ mod tests {
                     client_secret: "stub".into(),
                     redirect_uri: "http://localhost".into(),
                 },
+                github: OAuthProviderConfig {
+                    client_id: "stub".into(),
+                    client_secret: "stub".into(),
+                    redirect_uri: "http://localhost".into(),
+                },
                 token_encryption_key: vec![0u8; 32],
             },
             api_secrets_encryption_key: vec![1u8; 32],
@@ -1293,6 +1820,9 @@ mod tests {
             workspace_member_limit: DEFAULT_WORKSPACE_MEMBER_LIMIT,
             workspace_monthly_run_limit: DEFAULT_WORKSPACE_MONTHLY_RUN_LIMIT,
             runaway_limit_5min: RUNAWAY_LIMIT_5MIN,
+            slack_signing_secret: None,
+            public_api_base_url: None,
+            provider_base_urls: ProviderBaseUrls::default(),
         });
 
         AppState {
@@ -1523,6 +2053,11 @@ mod tests {
                     client_secret: "stub".into(),
                     redirect_uri: "http://localhost".into(),
                 },
+                github: OAuthProviderConfig {
+                    client_id: "stub".into(),
+                    client_secret: "stub".into(),
+                    redirect_uri: "http://localhost".into(),
+                },
                 token_encryption_key: vec![0u8; 32],
             },
             api_secrets_encryption_key: vec![1u8; 32],
@@ -1538,6 +2073,9 @@ mod tests {
             workspace_member_limit: DEFAULT_WORKSPACE_MEMBER_LIMIT,
             workspace_monthly_run_limit: DEFAULT_WORKSPACE_MONTHLY_RUN_LIMIT,
             runaway_limit_5min: RUNAWAY_LIMIT_5MIN,
+            slack_signing_secret: None,
+            public_api_base_url: None,
+            provider_base_urls: ProviderBaseUrls::default(),
         });
 
         let state = AppState {
@@ -1726,6 +2264,11 @@ mod tests {
                     client_secret: "stub".into(),
                     redirect_uri: "http://localhost".into(),
                 },
+                github: OAuthProviderConfig {
+                    client_id: "stub".into(),
+                    client_secret: "stub".into(),
+                    redirect_uri: "http://localhost".into(),
+                },
                 token_encryption_key: vec![0u8; 32],
             },
             api_secrets_encryption_key: vec![1u8; 32],
@@ -1741,6 +2284,9 @@ mod tests {
             workspace_member_limit: DEFAULT_WORKSPACE_MEMBER_LIMIT,
             workspace_monthly_run_limit: DEFAULT_WORKSPACE_MONTHLY_RUN_LIMIT,
             runaway_limit_5min: RUNAWAY_LIMIT_5MIN,
+            slack_signing_secret: None,
+            public_api_base_url: None,
+            provider_base_urls: ProviderBaseUrls::default(),
         });
 
         let state = AppState {
@@ -1938,6 +2484,11 @@ mod tests {
                     client_secret: "stub".into(),
                     redirect_uri: "http://localhost".into(),
                 },
+                github: OAuthProviderConfig {
+                    client_id: "stub".into(),
+                    client_secret: "stub".into(),
+                    redirect_uri: "http://localhost".into(),
+                },
                 token_encryption_key: vec![0u8; 32],
             },
             api_secrets_encryption_key: vec![1u8; 32],
@@ -1953,6 +2504,9 @@ mod tests {
             workspace_member_limit: DEFAULT_WORKSPACE_MEMBER_LIMIT,
             workspace_monthly_run_limit: DEFAULT_WORKSPACE_MONTHLY_RUN_LIMIT,
             runaway_limit_5min: RUNAWAY_LIMIT_5MIN,
+            slack_signing_secret: None,
+            public_api_base_url: None,
+            provider_base_urls: ProviderBaseUrls::default(),
         });
 
         let state = AppState {
@@ -2111,6 +2665,11 @@ mod tests {
                     client_secret: "stub".into(),
                     redirect_uri: "http://localhost".into(),
                 },
+                github: OAuthProviderConfig {
+                    client_id: "stub".into(),
+                    client_secret: "stub".into(),
+                    redirect_uri: "http://localhost".into(),
+                },
                 token_encryption_key: vec![0u8; 32],
             },
             api_secrets_encryption_key: vec![1u8; 32],
@@ -2126,6 +2685,9 @@ mod tests {
             workspace_member_limit: DEFAULT_WORKSPACE_MEMBER_LIMIT,
             workspace_monthly_run_limit: DEFAULT_WORKSPACE_MONTHLY_RUN_LIMIT,
             runaway_limit_5min: RUNAWAY_LIMIT_5MIN,
+            slack_signing_secret: None,
+            public_api_base_url: None,
+            provider_base_urls: ProviderBaseUrls::default(),
         });
 
         let state = AppState {
@@ -2270,6 +2832,11 @@ mod tests {
                     client_secret: "stub".into(),
                     redirect_uri: "http://localhost".into(),
                 },
+                github: OAuthProviderConfig {
+                    client_id: "stub".into(),
+                    client_secret: "stub".into(),
+                    redirect_uri: "http://localhost".into(),
+                },
                 token_encryption_key: vec![0u8; 32],
             },
             api_secrets_encryption_key: vec![1u8; 32],
@@ -2285,6 +2852,9 @@ mod tests {
             workspace_member_limit: DEFAULT_WORKSPACE_MEMBER_LIMIT,
             workspace_monthly_run_limit: DEFAULT_WORKSPACE_MONTHLY_RUN_LIMIT,
             runaway_limit_5min: 1,
+            slack_signing_secret: None,
+            public_api_base_url: None,
+            provider_base_urls: ProviderBaseUrls::default(),
         });
 
         let state = AppState {
@@ -1,12 +1,19 @@
+use std::collections::HashMap;
+
 use serde::{Deserialize, Serialize};
-use serde_json::{json, Value};
+use serde_json::{json, Map, Value};
+use sha2::{Digest, Sha256};
 use time::{format_description::well_known::Rfc3339, OffsetDateTime};
 
+use crate::models::notion_page_snapshot::NotionPageSnapshot;
 use crate::services::notion;
 use crate::services::notion::NotionError;
 
 const DEFAULT_PAGE_SIZE: u32 = 50;
 const MAX_POLL_PAGES: usize = 10;
+/// Upper bound on page snapshots kept per property-changed schedule; the
+/// least recently edited pages are dropped.
+pub const MAX_TRACKED_PAGES: i64 = 1000;
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
@@ -19,6 +26,10 @@ pub struct NotionTriggerState {
     pub last_seen_page_id: Option<String>,
     #[serde(default)]
     pub cursor: Option<String>,
+    /// Property-changed trigger: fingerprint of the watched property list the
+    /// stored page snapshots were taken with. A different list re-baselines.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub watched_key: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -36,6 +47,10 @@ pub struct NotionTriggerConfig {
     pub page_size: Option<u32>,
     #[serde(default)]
     pub poll_interval_seconds: Option<i64>,
+    /// Property names watched by the property-changed trigger; empty means
+    /// every property.
+    #[serde(default)]
+    pub watched_properties: Vec<String>,
     #[serde(default)]
     pub state: NotionTriggerState,
 }
@@ -44,6 +59,7 @@ pub struct NotionTriggerConfig {
 pub enum NotionTriggerKind {
     NewDatabaseRow,
     UpdatedDatabaseRow,
+    PropertyChanged,
 }
 
 impl NotionTriggerKind {
@@ -51,6 +67,7 @@ impl NotionTriggerKind {
         match raw.trim().to_ascii_lowercase().as_str() {
             "notion.new_database_row" => Some(Self::NewDatabaseRow),
             "notion.updated_database_row" => Some(Self::UpdatedDatabaseRow),
+            "notion.property_changed" => Some(Self::PropertyChanged),
             _ => None,
         }
     }
@@ -59,13 +76,14 @@ impl NotionTriggerKind {
         match self {
             Self::NewDatabaseRow => "notion.new_database_row",
             Self::UpdatedDatabaseRow => "notion.updated_database_row",
+            Self::PropertyChanged => "notion.property_changed",
         }
     }
 
     fn timestamp_field(&self) -> &'static str {
         match self {
             Self::NewDatabaseRow => "created_time",
-            Self::UpdatedDatabaseRow => "last_edited_time",
+            Self::UpdatedDatabaseRow | Self::PropertyChanged => "last_edited_time",
         }
     }
 }
@@ -89,17 +107,11 @@ pub fn parse_trigger_config(config: &Value) -> Option<(NotionTriggerKind, Notion
     Some((kind, parsed))
 }
 
-pub fn update_config_state(config: &Value, state: &NotionTriggerState) -> Option<Value> {
-    let mut updated = config.clone();
-    if let Value::Object(map) = &mut updated {
-        map.insert("state".to_string(), serde_json::to_value(state).ok()?);
-        return Some(updated);
-    }
-    None
-}
-
+/// Polls the new and updated row triggers; property-changed triggers go
+/// through [`scan_property_changes`] instead.
 pub async fn poll_database(
     client: &reqwest::Client,
+    base_url: &str,
     access_token: &str,
     config: &NotionTriggerConfig,
     kind: NotionTriggerKind,
@@ -113,7 +125,9 @@ pub async fn poll_database(
 
     let last_seen = match kind {
         NotionTriggerKind::NewDatabaseRow => config.state.last_seen_created_time.as_deref(),
-        NotionTriggerKind::UpdatedDatabaseRow => config.state.last_seen_edited_time.as_deref(),
+        NotionTriggerKind::UpdatedDatabaseRow | NotionTriggerKind::PropertyChanged => {
+            config.state.last_seen_edited_time.as_deref()
+        }
     };
     let last_seen_dt = last_seen.and_then(parse_timestamp);
 
@@ -130,6 +144,7 @@ pub async fn poll_database(
         }
         let response = notion::query_database(
             client,
+            base_url,
             access_token,
             &config.database_id,
             None,
@@ -190,7 +205,7 @@ pub async fn poll_database(
             let formatted = newest.format(&Rfc3339).unwrap_or_default();
             match kind {
                 NotionTriggerKind::NewDatabaseRow => state.last_seen_created_time = Some(formatted),
-                NotionTriggerKind::UpdatedDatabaseRow => {
+                NotionTriggerKind::UpdatedDatabaseRow | NotionTriggerKind::PropertyChanged => {
                     state.last_seen_edited_time = Some(formatted)
                 }
             }
@@ -203,6 +218,269 @@ pub async fn poll_database(
     Ok(NotionPollResult { events, state })
 }
 
+/// Pages a property-changed poll found edited at or after the last poll,
+/// newest first, with the current values of their watched properties.
+#[derive(Debug)]
+pub struct PropertyScan {
+    /// Nothing usable is stored yet, or the watched list changed: the stored
+    /// snapshots are replaced and nothing fires.
+    pub baseline: bool,
+    watched_key: String,
+    pages: Vec<(Value, NotionPageSnapshot)>,
+}
+
+impl PropertyScan {
+    pub fn page_ids(&self) -> Vec<String> {
+        self.pages
+            .iter()
+            .map(|(_, snapshot)| snapshot.page_id.clone())
+            .collect()
+    }
+}
+
+/// Fetches the pages a property-changed poll has to compare. The caller
+/// loads their stored snapshots and hands both to [`diff_property_changes`].
+pub async fn scan_property_changes(
+    client: &reqwest::Client,
+    base_url: &str,
+    access_token: &str,
+    config: &NotionTriggerConfig,
+) -> Result<PropertyScan, NotionError> {
+    let page_size = config.page_size.unwrap_or(DEFAULT_PAGE_SIZE).min(100);
+    let sorts = json!([{ "timestamp": "last_edited_time", "direction": "descending" }]);
+    let watched = normalized_watched(&config.watched_properties);
+    let watched_key = watched_fingerprint(&watched);
+
+    let state = &config.state;
+    let baseline = state.watched_key.as_deref() != Some(watched_key.as_str())
+        || state.last_seen_edited_time.is_none();
+    // Notion reports edit times at minute precision, so pages edited in the
+    // same minute as the last poll are re-checked; the hash filters repeats.
+    let last_seen_dt = if baseline {
+        None
+    } else {
+        state
+            .last_seen_edited_time
+            .as_deref()
+            .and_then(parse_timestamp)
+    };
+
+    // A scan never collects more pages than the snapshot table keeps.
+    let max_pages = MAX_TRACKED_PAGES as usize;
+    let max_requests = max_pages.div_ceil(page_size.max(1) as usize);
+    let mut pages = Vec::new();
+    let mut cursor: Option<String> = None;
+
+    'pages: for _ in 0..max_requests {
+        let response = notion::query_database(
+            client,
+            base_url,
+            access_token,
+            &config.database_id,
+            None,
+            Some(sorts.clone()),
+            cursor.as_deref(),
+            Some(page_size),
+        )
+        .await?;
+
+        for page in response.results {
+            let Some(page_id) = page.get("id").and_then(|v| v.as_str()) else {
+                continue;
+            };
+            let Some(edited) = page
+                .get("last_edited_time")
+                .and_then(|v| v.as_str())
+                .and_then(parse_timestamp)
+            else {
+                continue;
+            };
+            if last_seen_dt.is_some_and(|last| edited < last) {
+                break 'pages;
+            }
+            let values = watched_values(&page, &watched);
+            let snapshot = NotionPageSnapshot {
+                page_id: page_id.to_string(),
+                hash: hash_values(&values),
+                property_values: Value::Object(values),
+                last_edited_time: edited,
+            };
+            pages.push((page, snapshot));
+            if pages.len() >= max_pages {
+                break 'pages;
+            }
+        }
+
+        if !response.has_more {
+            break;
+        }
+        cursor = response.next_cursor;
+        if cursor.is_none() {
+            break;
+        }
+    }
+
+    Ok(PropertyScan {
+        baseline,
+        watched_key,
+        pages,
+    })
+}
+
+/// Compares scanned pages with their stored snapshots and emits an event for
+/// each page whose watched properties hash differently. Pages seen for the
+/// first time are recorded without firing since there is nothing to compare
+/// against. Returns the snapshots to store: only new and changed pages.
+pub fn diff_property_changes(
+    config: &NotionTriggerConfig,
+    scan: PropertyScan,
+    previous: Vec<NotionPageSnapshot>,
+) -> (NotionPollResult, Vec<NotionPageSnapshot>) {
+    let previous: HashMap<String, NotionPageSnapshot> = previous
+        .into_iter()
+        .map(|snapshot| (snapshot.page_id.clone(), snapshot))
+        .collect();
+
+    let mut events = Vec::new();
+    let mut changed = Vec::new();
+    let mut newest: Option<(OffsetDateTime, String)> = None;
+    for (page, snapshot) in scan.pages {
+        if newest
+            .as_ref()
+            .is_none_or(|(current, _)| snapshot.last_edited_time > *current)
+        {
+            newest = Some((snapshot.last_edited_time, snapshot.page_id.clone()));
+        }
+        let stored = previous.get(&snapshot.page_id);
+        if stored.is_some_and(|stored| stored.hash == snapshot.hash) {
+            continue;
+        }
+        if let Some(stored) = stored.filter(|_| !scan.baseline) {
+            events.push(build_property_change_event(
+                &config.database_id,
+                &page,
+                stored.property_values.as_object().unwrap_or(&Map::new()),
+                snapshot.property_values.as_object().unwrap_or(&Map::new()),
+            ));
+        }
+        changed.push(snapshot);
+    }
+
+    let mut state = config.state.clone();
+    if let Some((edited, page_id)) = newest {
+        state.last_seen_edited_time = Some(edited.format(&Rfc3339).unwrap_or_default());
+        state.last_seen_page_id = Some(page_id);
+    } else if scan.baseline {
+        // Empty database: still mark the baseline as taken.
+        state.last_seen_edited_time = Some(
+            OffsetDateTime::now_utc()
+                .format(&Rfc3339)
+                .unwrap_or_default(),
+        );
+    }
+    state.watched_key = Some(scan.watched_key);
+    state.cursor = None;
+
+    // Oldest change first, matching the other Notion triggers' run order.
+    events.reverse();
+    (NotionPollResult { events, state }, changed)
+}
+
+fn normalized_watched(watched: &[String]) -> Vec<String> {
+    let mut names: Vec<String> = watched
+        .iter()
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+        .collect();
+    names.sort();
+    names.dedup();
+    names
+}
+
+fn watched_fingerprint(watched: &[String]) -> String {
+    hash_values(&Map::from_iter(
+        watched.iter().map(|name| (name.clone(), Value::Null)),
+    ))
+}
+
+fn watched_values(page: &Value, watched: &[String]) -> Map<String, Value> {
+    let all = notion::flatten_page_properties(page);
+    if watched.is_empty() {
+        return all;
+    }
+    watched
+        .iter()
+        .map(|name| (name.clone(), all.get(name).cloned().unwrap_or(Value::Null)))
+        .collect()
+}
+
+fn hash_values(values: &Map<String, Value>) -> String {
+    // serde_json maps are ordered by key, so serialization is canonical.
+    let serialized = serde_json::to_vec(values).unwrap_or_default();
+    hex::encode(Sha256::digest(serialized))
+}
+
+fn build_property_change_event(
+    database_id: &str,
+    page: &Value,
+    before: &Map<String, Value>,
+    after: &Map<String, Value>,
+) -> Value {
+    let mut changes = Vec::new();
+    for (property, new_value) in after {
+        let old_value = before.get(property).unwrap_or(&Value::Null);
+        if old_value == new_value {
+            continue;
+        }
+        changes.push(json!({
+            "property": property,
+            "before": old_value,
+            "after": new_value,
+            "summary": format!(
+                "{property} changed from {} to {}",
+                display_value(old_value),
+                display_value(new_value)
+            ),
+        }));
+    }
+    let changed_properties: Vec<Value> = changes
+        .iter()
+        .filter_map(|change| change.get("property").cloned())
+        .collect();
+    let summary = changes
+        .iter()
+        .filter_map(|change| change.get("summary").and_then(|v| v.as_str()))
+        .collect::<Vec<_>>()
+        .join("; ");
+
+    json!({
+        "trigger": NotionTriggerKind::PropertyChanged.as_str(),
+        "databaseId": database_id,
+        "pageId": page.get("id").cloned().unwrap_or(Value::Null),
+        "page": page,
+        "changedProperties": changed_properties,
+        "changes": changes,
+        "before": before,
+        "after": after,
+        "summary": summary,
+    })
+}
+
+fn display_value(value: &Value) -> String {
+    match value {
+        Value::Null => "(empty)".to_string(),
+        Value::String(s) if s.is_empty() => "(empty)".to_string(),
+        Value::String(s) => s.clone(),
+        Value::Array(items) if items.is_empty() => "(empty)".to_string(),
+        Value::Array(items) => items
+            .iter()
+            .map(display_value)
+            .collect::<Vec<_>>()
+            .join(", "),
+        other => other.to_string(),
+    }
+}
+
 fn parse_timestamp(raw: &str) -> Option<OffsetDateTime> {
     OffsetDateTime::parse(raw, &Rfc3339).ok()
 }
@@ -219,10 +497,12 @@ fn build_event(kind: NotionTriggerKind, database_id: &str, page: Value) -> Value
 mod tests {
     use super::*;
 
+    use axum::http::{Method, StatusCode};
     use once_cell::sync::Lazy;
-    use std::sync::MutexGuard;
     use tokio::sync::Mutex;
 
+    use crate::test_support::FakeProvider;
+
     static CLIENT: Lazy<Mutex<reqwest::Client>> = Lazy::new(|| {
         Mutex::new(
             reqwest::Client::builder()
@@ -231,36 +511,6 @@ mod tests {
                 .expect("client"),
         )
     });
-    static ENV_LOCK: Lazy<std::sync::Mutex<()>> = Lazy::new(|| std::sync::Mutex::new(()));
-
-    struct EnvGuard {
-        key: &'static str,
-        previous: Option<String>,
-        _lock: MutexGuard<'static, ()>,
-    }
-
-    impl EnvGuard {
-        fn set(key: &'static str, value: String) -> Self {
-            let lock = ENV_LOCK.lock().expect("env mutex poisoned");
-            let previous = std::env::var(key).ok();
-            std::env::set_var(key, value);
-            Self {
-                key,
-                previous,
-                _lock: lock,
-            }
-        }
-    }
-
-    impl Drop for EnvGuard {
-        fn drop(&mut self) {
-            if let Some(previous) = self.previous.take() {
-                std::env::set_var(self.key, previous);
-            } else {
-                std::env::remove_var(self.key);
-            }
-        }
-    }
 
     fn page(id: &str, created: &str, edited: &str) -> Value {
         json!({
@@ -274,7 +524,6 @@ mod tests {
     async fn poll_initializes_state_without_emitting() {
         let client = CLIENT.lock().await;
         let server = httpmock::MockServer::start();
-        let _env = EnvGuard::set("NOTION_API_BASE_URL", server.url(""));
         let mock = server.mock(|when, then| {
             when.method(httpmock::Method::POST)
                 .path("/databases/db-1/query");
@@ -300,12 +549,19 @@ mod tests {
             database_id: "db-1".into(),
             page_size: None,
             poll_interval_seconds: None,
+            watched_properties: Vec::new(),
             state: NotionTriggerState::default(),
         };
 
-        let result = poll_database(&client, "token", &config, NotionTriggerKind::NewDatabaseRow)
-            .await
-            .expect("poll");
+        let result = poll_database(
+            &client,
+            &server.url(""),
+            "token",
+            &config,
+            NotionTriggerKind::NewDatabaseRow,
+        )
+        .await
+        .expect("poll");
 
         mock.assert();
         assert!(result.events.is_empty());
@@ -320,7 +576,6 @@ mod tests {
     async fn poll_emits_new_pages_and_updates_state() {
         let client = CLIENT.lock().await;
         let server = httpmock::MockServer::start();
-        let _env = EnvGuard::set("NOTION_API_BASE_URL", server.url(""));
         let mock = server.mock(|when, then| {
             when.method(httpmock::Method::POST)
                 .path("/databases/db-2/query");
@@ -346,17 +601,25 @@ mod tests {
             database_id: "db-2".into(),
             page_size: None,
             poll_interval_seconds: None,
+            watched_properties: Vec::new(),
             state: NotionTriggerState {
                 last_seen_created_time: Some("2024-01-15T10:00:00Z".into()),
                 last_seen_edited_time: None,
                 last_seen_page_id: None,
                 cursor: None,
+                ..NotionTriggerState::default()
             },
         };
 
-        let result = poll_database(&client, "token", &config, NotionTriggerKind::NewDatabaseRow)
-            .await
-            .expect("poll");
+        let result = poll_database(
+            &client,
+            &server.url(""),
+            "token",
+            &config,
+            NotionTriggerKind::NewDatabaseRow,
+        )
+        .await
+        .expect("poll");
 
         mock.assert();
         assert_eq!(result.events.len(), 1);
@@ -365,4 +628,192 @@ mod tests {
             Some("2024-02-01T10:00:00Z")
         );
     }
+
+    fn row(id: &str, edited: &str, status: &str, notes: &str) -> Value {
+        json!({
+            "id": id,
+            "created_time": "2024-01-01T00:00:00Z",
+            "last_edited_time": edited,
+            "properties": {
+                "Status": { "type": "status", "status": { "name": status } },
+                "Notes": { "type": "rich_text", "rich_text": [{ "plain_text": notes }] }
+            }
+        })
+    }
+
+    fn watching_config(state: NotionTriggerState) -> NotionTriggerConfig {
+        NotionTriggerConfig {
+            trigger_type: "notion.property_changed".into(),
+            connection_scope: "personal".into(),
+            connection_id: "conn".into(),
+            database_id: "db-3".into(),
+            page_size: None,
+            poll_interval_seconds: None,
+            watched_properties: vec!["Status".into()],
+            state,
+        }
+    }
+
+    /// Runs a property-changed poll against `stored` the way the worker does
+    /// against the snapshot table. Returns the result and the pages written.
+    async fn poll_property_changes(
+        client: &reqwest::Client,
+        base_url: &str,
+        config: &NotionTriggerConfig,
+        stored: &mut Vec<NotionPageSnapshot>,
+    ) -> (NotionPollResult, Vec<String>) {
+        let scan = scan_property_changes(client, base_url, "token", config)
+            .await
+            .expect("scan");
+        if scan.baseline {
+            stored.clear();
+        }
+        let ids = scan.page_ids();
+        let previous = stored
+            .iter()
+            .filter(|snapshot| ids.contains(&snapshot.page_id))
+            .cloned()
+            .collect();
+        let (result, changed) = diff_property_changes(config, scan, previous);
+        let written = changed.iter().map(|s| s.page_id.clone()).collect();
+        for snapshot in changed {
+            stored.retain(|existing| existing.page_id != snapshot.page_id);
+            stored.push(snapshot);
+        }
+        (result, written)
+    }
+
+    #[tokio::test]
+    async fn property_changed_fires_only_for_watched_properties() {
+        let client = reqwest::Client::new();
+        let provider = FakeProvider::start().await;
+        let base_url = provider.base_urls().notion;
+        provider.respond(
+            Method::POST,
+            "/notion/databases/db-3/query",
+            StatusCode::OK,
+            json!({
+                "results": [
+                    row("p1", "2024-03-01T10:00:00Z", "In Progress", "a"),
+                    row("p2", "2024-03-01T09:00:00Z", "Todo", "b")
+                ],
+                "has_more": false,
+                "next_cursor": null
+            }),
+        );
+
+        let mut stored = Vec::new();
+        let (baseline, written) = poll_property_changes(
+            &client,
+            &base_url,
+            &watching_config(NotionTriggerState::default()),
+            &mut stored,
+        )
+        .await;
+
+        assert_eq!(provider.requests().len(), 1);
+        assert!(baseline.events.is_empty());
+        assert_eq!(written, vec!["p1", "p2"]);
+        assert_eq!(
+            baseline.state.last_seen_edited_time.as_deref(),
+            Some("2024-03-01T10:00:00Z")
+        );
+
+        provider.respond(
+            Method::POST,
+            "/notion/databases/db-3/query",
+            StatusCode::OK,
+            json!({
+                "results": [
+                    row("p2", "2024-03-01T11:05:00Z", "Todo", "edited notes"),
+                    row("p1", "2024-03-01T11:00:00Z", "Done", "a"),
+                    row("p0", "2024-02-01T00:00:00Z", "Todo", "old")
+                ],
+                "has_more": false,
+                "next_cursor": null
+            }),
+        );
+
+        let (result, written) = poll_property_changes(
+            &client,
+            &base_url,
+            &watching_config(baseline.state.clone()),
+            &mut stored,
+        )
+        .await;
+
+        assert_eq!(result.events.len(), 1, "only the Status edit should fire");
+        let event = &result.events[0];
+        assert_eq!(event["trigger"], "notion.property_changed");
+        assert_eq!(event["pageId"], "p1");
+        assert_eq!(event["changedProperties"], json!(["Status"]));
+        assert_eq!(event["before"]["Status"], "In Progress");
+        assert_eq!(event["after"]["Status"], "Done");
+        assert_eq!(event["summary"], "Status changed from In Progress to Done");
+        assert_eq!(
+            written,
+            vec!["p1"],
+            "unchanged pages and pages edited before the last poll are not written"
+        );
+        assert_eq!(
+            result.state.last_seen_edited_time.as_deref(),
+            Some("2024-03-01T11:05:00Z")
+        );
+    }
+
+    #[tokio::test]
+    async fn changing_watched_properties_rebaselines() {
+        let provider = FakeProvider::start().await;
+        provider.respond(
+            Method::POST,
+            "/notion/databases/db-3/query",
+            StatusCode::OK,
+            json!({
+                "results": [row("p1", "2024-03-01T12:00:00Z", "Done", "changed")],
+                "has_more": false,
+                "next_cursor": null
+            }),
+        );
+
+        let state = NotionTriggerState {
+            last_seen_edited_time: Some("2024-03-01T10:00:00Z".into()),
+            watched_key: Some(watched_fingerprint(&["Status".to_string()])),
+            ..NotionTriggerState::default()
+        };
+        let mut stored = vec![
+            NotionPageSnapshot {
+                page_id: "p1".into(),
+                hash: "stale".into(),
+                property_values: json!({}),
+                last_edited_time: parse_timestamp("2024-03-01T10:00:00Z").unwrap(),
+            },
+            NotionPageSnapshot {
+                page_id: "p9".into(),
+                hash: "stale".into(),
+                property_values: json!({}),
+                last_edited_time: parse_timestamp("2024-03-01T09:00:00Z").unwrap(),
+            },
+        ];
+        let mut config = watching_config(state);
+        config.watched_properties = vec!["Notes".into(), "Status".into()];
+
+        let (result, _) = poll_property_changes(
+            &reqwest::Client::new(),
+            &provider.base_urls().notion,
+            &config,
+            &mut stored,
+        )
+        .await;
+
+        assert!(result.events.is_empty());
+        assert_eq!(
+            result.state.watched_key,
+            Some(watched_fingerprint(&[
+                "Notes".to_string(),
+                "Status".to_string()
+            ]))
+        );
+        assert_eq!(stored.len(), 1, "snapshots are replaced on re-baseline");
+        assert_eq!(stored[0].property_values["Notes"], "changed");
+    }
 }
@@ -0,0 +1,388 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use time::{format_description::well_known::Rfc3339, OffsetDateTime};
+
+use crate::services::microsoft::{outlook, MicrosoftGraphError};
+
+const POLL_PAGE_SIZE: u32 = 25;
+/// Upper bound on pages followed in one poll so a cursor that Graph no longer
+/// returns cannot keep the poller walking the whole mailbox.
+const MAX_POLL_PAGES: usize = 20;
+const DEFAULT_MAIL_FOLDER: &str = "inbox";
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct OutlookTriggerState {
+    #[serde(default)]
+    pub last_seen_time: Option<String>,
+    #[serde(default)]
+    pub last_seen_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OutlookTriggerConfig {
+    #[serde(default)]
+    pub trigger_type: String,
+    #[serde(default)]
+    pub connection_scope: String,
+    #[serde(default)]
+    pub connection_id: String,
+    #[serde(default)]
+    pub folder_id: Option<String>,
+    #[serde(default)]
+    pub poll_interval_seconds: Option<i64>,
+    #[serde(default)]
+    pub state: OutlookTriggerState,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutlookTriggerKind {
+    NewMail,
+    NewCalendarEvent,
+}
+
+impl OutlookTriggerKind {
+    pub fn from_str(raw: &str) -> Option<Self> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "outlook.new_mail" => Some(Self::NewMail),
+            "outlook.new_calendar_event" => Some(Self::NewCalendarEvent),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::NewMail => "outlook.new_mail",
+            Self::NewCalendarEvent => "outlook.new_calendar_event",
+        }
+    }
+
+    fn timestamp_field(&self) -> &'static str {
+        match self {
+            Self::NewMail => "receivedDateTime",
+            Self::NewCalendarEvent => "createdDateTime",
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct OutlookPollResult {
+    pub events: Vec<Value>,
+    pub state: OutlookTriggerState,
+}
+
+pub fn parse_trigger_config(config: &Value) -> Option<(OutlookTriggerKind, OutlookTriggerConfig)> {
+    let trigger_type = config.get("triggerType")?.as_str()?;
+    let kind = OutlookTriggerKind::from_str(trigger_type)?;
+    let parsed: OutlookTriggerConfig = serde_json::from_value(config.clone()).ok()?;
+    if parsed.connection_id.trim().is_empty() || parsed.connection_scope.trim().is_empty() {
+        return None;
+    }
+    Some((kind, parsed))
+}
+
+/// Fetches the newest messages (or created events) page by page until it
+/// reaches the stored `(timestamp, id)` cursor and emits everything newer,
+/// oldest first. The first poll only records the cursor so existing mail does
+/// not flood the workflow.
+pub async fn poll_mailbox(
+    client: &reqwest::Client,
+    base_url: &str,
+    access_token: &str,
+    config: &OutlookTriggerConfig,
+    kind: OutlookTriggerKind,
+) -> Result<OutlookPollResult, MicrosoftGraphError> {
+    let mut state = config.state.clone();
+    let cursor = state
+        .last_seen_time
+        .as_deref()
+        .and_then(parse_timestamp)
+        .map(|at| (at, state.last_seen_id.clone().unwrap_or_default()));
+
+    let mut page = match kind {
+        OutlookTriggerKind::NewMail => {
+            let folder = config
+                .folder_id
+                .as_deref()
+                .map(str::trim)
+                .filter(|folder| !folder.is_empty())
+                .unwrap_or(DEFAULT_MAIL_FOLDER);
+            outlook::list_recent_messages(client, base_url, access_token, folder, POLL_PAGE_SIZE)
+                .await?
+        }
+        OutlookTriggerKind::NewCalendarEvent => {
+            outlook::list_recent_events(client, base_url, access_token, POLL_PAGE_SIZE).await?
+        }
+    };
+
+    let mut dated: Vec<(OffsetDateTime, String, Value)> = Vec::new();
+    for _ in 0..MAX_POLL_PAGES {
+        let mut reached_cursor = false;
+        for item in page.items {
+            let Some(id) = item.get("id").and_then(Value::as_str).map(str::to_string) else {
+                continue;
+            };
+            let Some(at) = item
+                .get(kind.timestamp_field())
+                .and_then(Value::as_str)
+                .and_then(parse_timestamp)
+            else {
+                continue;
+            };
+            match &cursor {
+                Some((seen_at, seen_id)) if (at, id.as_str()) <= (*seen_at, seen_id.as_str()) => {
+                    reached_cursor = true;
+                }
+                _ => dated.push((at, id, item)),
+            }
+        }
+
+        // Without a cursor only the newest page matters for initialization.
+        let Some(next_link) = page
+            .next_link
+            .filter(|_| cursor.is_some() && !reached_cursor)
+        else {
+            break;
+        };
+        page = outlook::list_next_page(client, base_url, access_token, &next_link).await?;
+    }
+    dated.sort_by(|(a_at, a_id, _), (b_at, b_id, _)| (a_at, a_id).cmp(&(b_at, b_id)));
+    dated.dedup_by(|(_, a_id, _), (_, b_id, _)| a_id == b_id);
+
+    if let Some((at, id, _)) = dated.last() {
+        state.last_seen_time = Some(at.format(&Rfc3339).unwrap_or_default());
+        state.last_seen_id = Some(id.clone());
+    }
+
+    let events = if cursor.is_some() {
+        dated
+            .into_iter()
+            .map(|(_, _, item)| build_event(kind, item))
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    Ok(OutlookPollResult { events, state })
+}
+
+fn parse_timestamp(raw: &str) -> Option<OffsetDateTime> {
+    OffsetDateTime::parse(raw, &Rfc3339).ok()
+}
+
+fn build_event(kind: OutlookTriggerKind, item: Value) -> Value {
+    match kind {
+        OutlookTriggerKind::NewMail => json!({
+            "trigger": kind.as_str(),
+            "messageId": item.get("id").cloned().unwrap_or(Value::Null),
+            "subject": item.get("subject").cloned().unwrap_or(Value::Null),
+            "from": item
+                .get("from")
+                .and_then(|f| f.get("emailAddress"))
+                .and_then(|e| e.get("address"))
+                .cloned()
+                .unwrap_or(Value::Null),
+            "preview": item.get("bodyPreview").cloned().unwrap_or(Value::Null),
+            "receivedAt": item.get("receivedDateTime").cloned().unwrap_or(Value::Null),
+            "message": item,
+        }),
+        OutlookTriggerKind::NewCalendarEvent => json!({
+            "trigger": kind.as_str(),
+            "eventId": item.get("id").cloned().unwrap_or(Value::Null),
+            "subject": item.get("subject").cloned().unwrap_or(Value::Null),
+            "start": item.get("start").cloned().unwrap_or(Value::Null),
+            "end": item.get("end").cloned().unwrap_or(Value::Null),
+            "organizer": item
+                .get("organizer")
+                .and_then(|o| o.get("emailAddress"))
+                .and_then(|e| e.get("address"))
+                .cloned()
+                .unwrap_or(Value::Null),
+            "teamsMeetingUrl": item
+                .get("onlineMeeting")
+                .and_then(|m| m.get("joinUrl"))
+                .cloned()
+                .unwrap_or(Value::Null),
+            "event": item,
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::FakeProvider;
+    use axum::http::{Method, StatusCode};
+
+    const INBOX_PATH: &str = "/graph/me/mailFolders/inbox/messages";
+
+    fn config(kind: OutlookTriggerKind, state: OutlookTriggerState) -> OutlookTriggerConfig {
+        OutlookTriggerConfig {
+            trigger_type: kind.as_str().into(),
+            connection_scope: "personal".into(),
+            connection_id: "conn".into(),
+            folder_id: None,
+            poll_interval_seconds: None,
+            state,
+        }
+    }
+
+    fn mail(id: &str, received: &str) -> Value {
+        json!({
+            "id": id,
+            "subject": format!("Subject {id}"),
+            "receivedDateTime": received,
+            "from": { "emailAddress": { "address": "ada@example.com" } }
+        })
+    }
+
+    fn message_ids(result: &OutlookPollResult) -> Vec<&str> {
+        result
+            .events
+            .iter()
+            .filter_map(|event| event["messageId"].as_str())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn new_mail_initializes_then_emits_newer_messages() {
+        let provider = FakeProvider::start().await;
+        provider.respond(
+            Method::GET,
+            INBOX_PATH,
+            StatusCode::OK,
+            json!({
+                "value": [
+                    mail("m3", "2024-05-01T10:10:00Z"),
+                    mail("m2", "2024-05-01T10:05:00Z"),
+                    mail("m1", "2024-05-01T10:00:00Z")
+                ]
+            }),
+        );
+        let client = reqwest::Client::new();
+        let base_url = provider.base_urls().microsoft_graph;
+
+        let first = poll_mailbox(
+            &client,
+            &base_url,
+            "token",
+            &config(OutlookTriggerKind::NewMail, OutlookTriggerState::default()),
+            OutlookTriggerKind::NewMail,
+        )
+        .await
+        .expect("first poll");
+        assert!(first.events.is_empty());
+        assert_eq!(first.state.last_seen_id.as_deref(), Some("m3"));
+
+        let resumed = OutlookTriggerState {
+            last_seen_time: Some("2024-05-01T10:00:00Z".into()),
+            last_seen_id: Some("m1".into()),
+        };
+        let second = poll_mailbox(
+            &client,
+            &base_url,
+            "token",
+            &config(OutlookTriggerKind::NewMail, resumed),
+            OutlookTriggerKind::NewMail,
+        )
+        .await
+        .expect("second poll");
+
+        let requests = provider.requests_to(Method::GET, INBOX_PATH);
+        assert_eq!(requests.len(), 2);
+        assert_eq!(
+            requests[0].query_param("$orderby").as_deref(),
+            Some("receivedDateTime desc")
+        );
+        assert_eq!(message_ids(&second), vec!["m2", "m3"]);
+        assert_eq!(second.events[0]["from"], "ada@example.com");
+        assert_eq!(
+            second.state.last_seen_time.as_deref(),
+            Some("2024-05-01T10:10:00Z")
+        );
+    }
+
+    #[tokio::test]
+    async fn new_mail_follows_next_link_until_cursor_and_keeps_timestamp_ties() {
+        let provider = FakeProvider::start().await;
+        provider.respond(
+            Method::GET,
+            INBOX_PATH,
+            StatusCode::OK,
+            json!({
+                "value": [mail("m4", "2024-05-01T10:20:00Z")],
+                "@odata.nextLink": provider.url(&format!("{INBOX_PATH}?$skiptoken=page2")),
+            }),
+        );
+        provider.respond(
+            Method::GET,
+            &format!("{INBOX_PATH}?$skiptoken=page2"),
+            StatusCode::OK,
+            json!({
+                "value": [
+                    mail("m3", "2024-05-01T10:05:00Z"),
+                    mail("m2", "2024-05-01T10:05:00Z"),
+                    mail("m1", "2024-05-01T10:00:00Z")
+                ]
+            }),
+        );
+
+        let state = OutlookTriggerState {
+            last_seen_time: Some("2024-05-01T10:05:00Z".into()),
+            last_seen_id: Some("m2".into()),
+        };
+        let result = poll_mailbox(
+            &reqwest::Client::new(),
+            &provider.base_urls().microsoft_graph,
+            "token",
+            &config(OutlookTriggerKind::NewMail, state),
+            OutlookTriggerKind::NewMail,
+        )
+        .await
+        .expect("poll");
+
+        assert_eq!(provider.requests_to(Method::GET, INBOX_PATH).len(), 2);
+        assert_eq!(message_ids(&result), vec!["m3", "m4"]);
+        assert_eq!(result.state.last_seen_id.as_deref(), Some("m4"));
+    }
+
+    #[tokio::test]
+    async fn new_calendar_event_uses_created_time() {
+        let provider = FakeProvider::start().await;
+        provider.respond(
+            Method::GET,
+            "/graph/me/events",
+            StatusCode::OK,
+            json!({
+                "value": [{
+                    "id": "evt-2",
+                    "subject": "Planning",
+                    "createdDateTime": "2024-05-02T08:00:00Z",
+                    "onlineMeeting": { "joinUrl": "https://teams.example/join" }
+                }]
+            }),
+        );
+
+        let state = OutlookTriggerState {
+            last_seen_time: Some("2024-05-01T08:00:00Z".into()),
+            last_seen_id: Some("evt-1".into()),
+        };
+        let result = poll_mailbox(
+            &reqwest::Client::new(),
+            &provider.base_urls().microsoft_graph,
+            "token",
+            &config(OutlookTriggerKind::NewCalendarEvent, state),
+            OutlookTriggerKind::NewCalendarEvent,
+        )
+        .await
+        .expect("poll");
+
+        assert_eq!(result.events.len(), 1);
+        assert_eq!(result.events[0]["eventId"], "evt-2");
+        assert_eq!(
+            result.events[0]["teamsMeetingUrl"],
+            "https://teams.example/join"
+        );
+    }
+}
@@ -0,0 +1,452 @@
+//! Message queue triggers: `rabbitmq.message` reads a RabbitMQ queue and
+//! `redis.stream_message` reads a Redis Stream through a consumer group.
+//!
+//! Each poll takes up to `maxMessagesPerPoll` messages without acking them.
+//! The worker acks a message only once its run is enqueued (or was already
+//! enqueued for the same message id); anything else goes back to the queue
+//! and is delivered again on a later poll. Delivery is at-least-once.
+
+use base64::Engine as _;
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+use thiserror::Error;
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::engine::actions::http::TriggerEgress;
+use crate::services::amqp::{self, AmqpError, AmqpSession, ReceivedMessage};
+use crate::services::redis_streams::{self, RedisSession, RedisStreamError, StreamEntry};
+use crate::utils::secrets::{hydrate_secrets_into_snapshot, read_secret_store};
+
+pub use crate::services::amqp::RABBITMQ_MESSAGE;
+pub use crate::services::redis_streams::REDIS_STREAM_MESSAGE;
+
+/// Queues are expected to be drained promptly, so they poll more often than
+/// the other polling triggers by default.
+pub const DEFAULT_POLL_INTERVAL_SECONDS: i64 = 30;
+const DEFAULT_MAX_MESSAGES_PER_POLL: u32 = 10;
+const MAX_MESSAGES_PER_POLL: u32 = 100;
+const DEFAULT_CONSUMER_GROUP: &str = "dsentr";
+/// Messages larger than this are dropped (RabbitMQ: rejected without
+/// requeue, so a dead-letter exchange receives them) instead of starting a
+/// run, since the whole message is copied into the run snapshot.
+const MAX_MESSAGE_BYTES: usize = 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QueueTriggerKind {
+    RabbitMq,
+    RedisStream,
+}
+
+impl QueueTriggerKind {
+    pub fn trigger_type(self) -> &'static str {
+        match self {
+            QueueTriggerKind::RabbitMq => RABBITMQ_MESSAGE,
+            QueueTriggerKind::RedisStream => REDIS_STREAM_MESSAGE,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueueTriggerConfig {
+    #[serde(default)]
+    pub queue: Option<String>,
+    #[serde(default)]
+    pub stream: Option<String>,
+    #[serde(default)]
+    pub consumer_group: Option<String>,
+    #[serde(default)]
+    pub poll_interval_seconds: Option<i64>,
+    #[serde(default)]
+    pub max_messages_per_poll: Option<u32>,
+}
+
+impl QueueTriggerConfig {
+    pub fn max_messages(&self) -> usize {
+        self.max_messages_per_poll
+            .unwrap_or(DEFAULT_MAX_MESSAGES_PER_POLL)
+            .clamp(1, MAX_MESSAGES_PER_POLL) as usize
+    }
+
+    fn consumer_group(&self) -> &str {
+        self.consumer_group
+            .as_deref()
+            .map(str::trim)
+            .filter(|g| !g.is_empty())
+            .unwrap_or(DEFAULT_CONSUMER_GROUP)
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum QueuePollError {
+    #[error("{0}")]
+    Egress(String),
+    #[error("failed to read broker credentials: {0}")]
+    Secrets(String),
+    #[error("broker connection secret is missing")]
+    MissingConnection,
+    #[error(transparent)]
+    Amqp(#[from] AmqpError),
+    #[error(transparent)]
+    Redis(#[from] RedisStreamError),
+}
+
+pub fn parse_trigger_config(config: &Value) -> Option<(QueueTriggerKind, QueueTriggerConfig)> {
+    let trigger_type = config.get("triggerType")?.as_str()?.trim();
+    let kind = if trigger_type.eq_ignore_ascii_case(RABBITMQ_MESSAGE) {
+        QueueTriggerKind::RabbitMq
+    } else if trigger_type.eq_ignore_ascii_case(REDIS_STREAM_MESSAGE) {
+        QueueTriggerKind::RedisStream
+    } else {
+        return None;
+    };
+    let parsed: QueueTriggerConfig = serde_json::from_value(config.clone()).ok()?;
+    let source = match kind {
+        QueueTriggerKind::RabbitMq => &parsed.queue,
+        QueueTriggerKind::RedisStream => &parsed.stream,
+    };
+    source.as_deref().map(str::trim).filter(|s| !s.is_empty())?;
+    Some((kind, parsed))
+}
+
+/// Reads `connectionUrl` from the schedule config, replacing a secret
+/// reference with the value from the owner's secret store.
+pub fn resolve_connection_url(
+    config: &Value,
+    settings: &Value,
+    encryption_key: &[u8],
+) -> Result<String, QueuePollError> {
+    let (store, _) = read_secret_store(settings, encryption_key)
+        .map_err(|err| QueuePollError::Secrets(err.to_string()))?;
+    let mut url = config.get("connectionUrl").cloned().unwrap_or(Value::Null);
+    hydrate_secrets_into_snapshot(&mut url, &store);
+    url.as_str()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+        .ok_or(QueuePollError::MissingConnection)
+}
+
+/// Messages taken from the broker but not yet settled. Dropping a batch
+/// without calling [`QueueBatch::settle`] leaves RabbitMQ messages to be
+/// requeued when the connection closes and Redis entries pending.
+pub enum QueueBatch {
+    RabbitMq {
+        session: Box<AmqpSession>,
+        queue: String,
+        messages: Vec<ReceivedMessage>,
+    },
+    Redis {
+        session: RedisSession,
+        stream: String,
+        group: String,
+        entries: Vec<StreamEntry>,
+    },
+}
+
+impl QueueBatch {
+    pub fn len(&self) -> usize {
+        match self {
+            QueueBatch::RabbitMq { messages, .. } => messages.len(),
+            QueueBatch::Redis { entries, .. } => entries.len(),
+        }
+    }
+
+    /// Trigger events in delivery order, each with the idempotency key used
+    /// to deduplicate redeliveries.
+    pub fn events(&self) -> Vec<(Value, Option<String>)> {
+        match self {
+            QueueBatch::RabbitMq {
+                queue, messages, ..
+            } => messages.iter().map(|m| rabbitmq_event(queue, m)).collect(),
+            QueueBatch::Redis {
+                stream,
+                group,
+                entries,
+                ..
+            } => entries
+                .iter()
+                .map(|e| redis_event(stream, group, e))
+                .collect(),
+        }
+    }
+
+    /// Acks the first `handed_off` messages and returns the rest to the
+    /// queue. Redis entries that are not acked stay pending and are read
+    /// again first on the next poll.
+    pub async fn settle(self, handed_off: usize) -> Result<(), QueuePollError> {
+        match self {
+            QueueBatch::RabbitMq {
+                session, messages, ..
+            } => {
+                let mut result = Ok(());
+                for (index, message) in messages.iter().enumerate() {
+                    let settled = if index < handed_off {
+                        session.ack(message.delivery_tag).await
+                    } else {
+                        session.requeue(message.delivery_tag).await
+                    };
+                    if let Err(err) = settled {
+                        result = Err(err.into());
+                        break;
+                    }
+                }
+                session.close().await;
+                result
+            }
+            QueueBatch::Redis {
+                mut session,
+                stream,
+                group,
+                entries,
+            } => {
+                let ids: Vec<String> = entries.into_iter().take(handed_off).map(|e| e.id).collect();
+                Ok(session.xack(&stream, &group, &ids).await?)
+            }
+        }
+    }
+}
+
+/// Connects to the broker and takes up to `maxMessagesPerPoll` messages.
+/// Messages that can never start a run (oversized, or stream entries that
+/// were deleted while pending) are settled here and left out of the batch.
+pub async fn receive(
+    kind: QueueTriggerKind,
+    config: &QueueTriggerConfig,
+    url: &str,
+    workflow_id: Uuid,
+    egress: &TriggerEgress,
+) -> Result<QueueBatch, QueuePollError> {
+    let max = config.max_messages();
+    match kind {
+        QueueTriggerKind::RabbitMq => {
+            let (host, _) = amqp::broker_address(url)?;
+            egress
+                .check_host("AMQP", &host)
+                .map_err(QueuePollError::Egress)?;
+            let queue = config
+                .queue
+                .as_deref()
+                .unwrap_or_default()
+                .trim()
+                .to_string();
+            let session = AmqpSession::connect(url).await?;
+            let fetched = match session.fetch(&queue, max).await {
+                Ok(messages) => messages,
+                Err(err) => {
+                    session.close().await;
+                    return Err(err.into());
+                }
+            };
+            let mut messages = Vec::with_capacity(fetched.len());
+            for message in fetched {
+                if message.data.len() > MAX_MESSAGE_BYTES {
+                    warn!(
+                        %workflow_id,
+                        %queue,
+                        size = message.data.len(),
+                        "queue trigger: rejecting oversized RabbitMQ message"
+                    );
+                    session.reject(message.delivery_tag).await?;
+                } else {
+                    messages.push(message);
+                }
+            }
+            Ok(QueueBatch::RabbitMq {
+                session: Box::new(session),
+                queue,
+                messages,
+            })
+        }
+        QueueTriggerKind::RedisStream => {
+            let (host, _) = redis_streams::redis_address(url)?;
+            egress
+                .check_host("Redis", &host)
+                .map_err(QueuePollError::Egress)?;
+            let stream = config
+                .stream
+                .as_deref()
+                .unwrap_or_default()
+                .trim()
+                .to_string();
+            let group = config.consumer_group().to_string();
+            let mut session = RedisSession::connect(url).await?;
+            session.ensure_group(&stream, &group).await?;
+            let consumer = format!("dsentr-{workflow_id}");
+            let read = session.read_group(&stream, &group, &consumer, max).await?;
+
+            let (entries, dropped): (Vec<StreamEntry>, Vec<StreamEntry>) = read
+                .into_iter()
+                .partition(|e| !e.fields.is_empty() && e.size <= MAX_MESSAGE_BYTES);
+            if !dropped.is_empty() {
+                warn!(
+                    %workflow_id,
+                    %stream,
+                    count = dropped.len(),
+                    "queue trigger: acknowledging deleted or oversized stream entries without a run"
+                );
+                let ids: Vec<String> = dropped.into_iter().map(|e| e.id).collect();
+                session.xack(&stream, &group, &ids).await?;
+            }
+            Ok(QueueBatch::Redis {
+                session,
+                stream,
+                group,
+                entries,
+            })
+        }
+    }
+}
+
+fn rabbitmq_event(queue: &str, message: &ReceivedMessage) -> (Value, Option<String>) {
+    let (body, encoding) = match std::str::from_utf8(&message.data) {
+        Ok(text) => (text.to_string(), "utf8"),
+        Err(_) => (
+            base64::engine::general_purpose::STANDARD.encode(&message.data),
+            "base64",
+        ),
+    };
+    let payload = if encoding == "utf8" {
+        serde_json::from_str::<Value>(&body).unwrap_or_else(|_| Value::String(body.clone()))
+    } else {
+        Value::Null
+    };
+    let key = message
+        .message_id
+        .as_deref()
+        .map(str::trim)
+        .filter(|id| !id.is_empty())
+        .map(|id| format!("rabbitmq:{queue}:{id}"));
+    let event = json!({
+        "trigger": RABBITMQ_MESSAGE,
+        "queue": queue,
+        "exchange": message.exchange,
+        "routingKey": message.routing_key,
+        "messageId": message.message_id,
+        "contentType": message.content_type,
+        "headers": message.headers,
+        "redelivered": message.redelivered,
+        "body": body,
+        "bodyEncoding": encoding,
+        "payload": payload,
+    });
+    (event, key)
+}
+
+fn redis_event(stream: &str, group: &str, entry: &StreamEntry) -> (Value, Option<String>) {
+    let mut event = Map::new();
+    event.insert("trigger".into(), json!(REDIS_STREAM_MESSAGE));
+    event.insert("stream".into(), json!(stream));
+    event.insert("group".into(), json!(group));
+    event.insert("id".into(), json!(entry.id));
+    event.insert("fields".into(), Value::Object(entry.fields.clone()));
+    // The `queue` action stores un-keyed messages in a `payload` field.
+    if let Some(Value::String(raw)) = entry.fields.get("payload") {
+        let payload = serde_json::from_str::<Value>(raw).unwrap_or_else(|_| json!(raw));
+        event.insert("payload".into(), payload);
+    }
+    let key = format!("redis:{stream}:{group}:{}", entry.id);
+    (Value::Object(event), Some(key))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trigger_configs_need_a_queue_or_stream() {
+        let (kind, config) = parse_trigger_config(&json!({
+            "triggerType": "rabbitmq.message",
+            "queue": "orders",
+            "connectionUrl": "queue:rabbitmq:prod",
+            "maxMessagesPerPoll": 500
+        }))
+        .unwrap();
+        assert_eq!(kind, QueueTriggerKind::RabbitMq);
+        assert_eq!(config.max_messages(), 100);
+
+        let (kind, config) = parse_trigger_config(&json!({
+            "triggerType": "redis.stream_message",
+            "stream": "orders"
+        }))
+        .unwrap();
+        assert_eq!(kind, QueueTriggerKind::RedisStream);
+        assert_eq!(config.consumer_group(), "dsentr");
+        assert_eq!(config.max_messages(), 10);
+
+        assert!(parse_trigger_config(&json!({
+            "triggerType": "rabbitmq.message",
+            "stream": "orders"
+        }))
+        .is_none());
+        assert!(parse_trigger_config(&json!({
+            "triggerType": "sftp.new_file",
+            "queue": "orders"
+        }))
+        .is_none());
+    }
+
+    #[test]
+    fn connection_urls_resolve_plain_values_and_require_one() {
+        let url = resolve_connection_url(
+            &json!({ "connectionUrl": " amqp://mq.example.com " }),
+            &json!({}),
+            &[0u8; 32],
+        )
+        .unwrap();
+        assert_eq!(url, "amqp://mq.example.com");
+
+        let err = resolve_connection_url(&json!({}), &json!({}), &[0u8; 32]).unwrap_err();
+        assert!(matches!(err, QueuePollError::MissingConnection));
+    }
+
+    #[test]
+    fn rabbitmq_events_decode_json_bodies_and_key_on_message_id() {
+        let message = ReceivedMessage {
+            delivery_tag: 7,
+            exchange: "orders".into(),
+            routing_key: "order.created".into(),
+            redelivered: true,
+            content_type: Some("application/json".into()),
+            message_id: Some("order-42".into()),
+            headers: json!({ "tenant": "acme" }),
+            data: br#"{"id":42}"#.to_vec(),
+        };
+        let (event, key) = rabbitmq_event("orders-in", &message);
+        assert_eq!(key.as_deref(), Some("rabbitmq:orders-in:order-42"));
+        assert_eq!(event["trigger"], RABBITMQ_MESSAGE);
+        assert_eq!(event["payload"], json!({ "id": 42 }));
+        assert_eq!(event["body"], r#"{"id":42}"#);
+        assert_eq!(event["headers"]["tenant"], "acme");
+        assert_eq!(event["redelivered"], true);
+
+        let binary = ReceivedMessage {
+            message_id: None,
+            data: vec![0xff, 0x00],
+            ..message
+        };
+        let (event, key) = rabbitmq_event("orders-in", &binary);
+        assert!(key.is_none());
+        assert_eq!(event["bodyEncoding"], "base64");
+        assert_eq!(event["body"], "/wA=");
+        assert!(event["payload"].is_null());
+    }
+
+    #[test]
+    fn redis_events_expose_fields_and_parse_payload() {
+        let mut fields = Map::new();
+        fields.insert("payload".into(), json!("{\"id\":42}"));
+        fields.insert("source".into(), json!("checkout"));
+        let entry = StreamEntry {
+            id: "1700000000000-0".into(),
+            fields,
+            size: 31,
+        };
+        let (event, key) = redis_event("orders", "dsentr", &entry);
+        assert_eq!(key.as_deref(), Some("redis:orders:dsentr:1700000000000-0"));
+        assert_eq!(event["id"], "1700000000000-0");
+        assert_eq!(event["fields"]["source"], "checkout");
+        assert_eq!(event["payload"], json!({ "id": 42 }));
+    }
+}
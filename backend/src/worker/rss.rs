@@ -0,0 +1,562 @@
+use std::collections::HashSet;
+use std::time::Duration;
+
+use chrono::DateTime;
+use reqwest::header::{ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED};
+use reqwest::{StatusCode, Url};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+
+use crate::engine::actions::http::TriggerEgress;
+
+pub const RSS_NEW_ITEM: &str = "rss.new_item";
+
+const DEFAULT_MAX_ITEMS_PER_POLL: u32 = 10;
+const MAX_ITEMS_PER_POLL: u32 = 50;
+/// Upper bound on item keys remembered in the schedule config. Keys still
+/// present in the feed are kept first, so feeds shorter than this never
+/// re-fire old items.
+const MAX_TRACKED_ITEMS: usize = 1000;
+const MAX_FEED_BYTES: usize = 5 * 1024 * 1024;
+const FEED_TIMEOUT: Duration = Duration::from_secs(20);
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RssTriggerState {
+    /// Feed URL the seen keys belong to; a different URL re-baselines.
+    #[serde(default)]
+    pub feed_url: Option<String>,
+    /// GUIDs (or links) of items already handled, most recent first.
+    #[serde(default)]
+    pub seen: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub etag: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_modified: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RssTriggerConfig {
+    #[serde(default)]
+    pub trigger_type: String,
+    #[serde(default)]
+    pub feed_url: String,
+    #[serde(default)]
+    pub poll_interval_seconds: Option<i64>,
+    #[serde(default)]
+    pub max_items_per_poll: Option<u32>,
+    #[serde(default)]
+    pub state: RssTriggerState,
+}
+
+#[derive(Debug, Error)]
+pub enum RssPollError {
+    #[error("{0}")]
+    Egress(String),
+    #[error("invalid feed URL: {0}")]
+    InvalidUrl(String),
+    #[error("feed request failed: {0}")]
+    Request(#[from] reqwest::Error),
+    #[error("feed responded with status {0}")]
+    Status(u16),
+    #[error("feed exceeds {MAX_FEED_BYTES} bytes")]
+    TooLarge,
+    #[error("failed to parse feed: {0}")]
+    Parse(String),
+}
+
+#[derive(Debug)]
+pub struct RssPollResult {
+    pub events: Vec<Value>,
+    pub state: RssTriggerState,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FeedItem {
+    pub guid: Option<String>,
+    pub title: Option<String>,
+    pub link: Option<String>,
+    pub summary: Option<String>,
+    pub author: Option<String>,
+    pub published: Option<String>,
+}
+
+impl FeedItem {
+    /// Stable identity for de-duplication: the GUID/Atom id, then the link,
+    /// then a hash of the visible fields.
+    fn key(&self) -> String {
+        if let Some(guid) = self.guid.as_deref().or(self.link.as_deref()) {
+            return guid.to_string();
+        }
+        let fingerprint = json!([self.title, self.published, self.summary]).to_string();
+        format!("sha256:{}", hex::encode(Sha256::digest(fingerprint)))
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Feed {
+    pub title: Option<String>,
+    pub items: Vec<FeedItem>,
+}
+
+pub fn parse_trigger_config(config: &Value) -> Option<RssTriggerConfig> {
+    let trigger_type = config.get("triggerType")?.as_str()?;
+    if !trigger_type.trim().eq_ignore_ascii_case(RSS_NEW_ITEM) {
+        return None;
+    }
+    let parsed: RssTriggerConfig = serde_json::from_value(config.clone()).ok()?;
+    if parsed.feed_url.trim().is_empty() {
+        return None;
+    }
+    Some(parsed)
+}
+
+/// Fetches the feed and emits one event per unseen item, oldest first.
+///
+/// At most `maxItemsPerPoll` items fire per poll. On the first poll (or after
+/// the feed URL changes) only the newest items up to that cap fire and the
+/// rest of the feed is marked seen; on later polls items over the cap stay
+/// unseen and fire on the next poll.
+pub async fn poll_feed(
+    config: &RssTriggerConfig,
+    egress: &TriggerEgress,
+) -> Result<RssPollResult, RssPollError> {
+    let feed_url = config.feed_url.trim();
+    let url = Url::parse(feed_url).map_err(|e| RssPollError::InvalidUrl(e.to_string()))?;
+    egress.check(&url).map_err(RssPollError::Egress)?;
+
+    let mut state = config.state.clone();
+    let baseline = state.feed_url.as_deref() != Some(feed_url);
+    if baseline {
+        state = RssTriggerState {
+            feed_url: Some(feed_url.to_string()),
+            ..RssTriggerState::default()
+        };
+    }
+
+    let client = reqwest::Client::builder()
+        .redirect(egress.redirect_policy())
+        .timeout(FEED_TIMEOUT)
+        .build()?;
+    let mut request = client.get(url).header(
+        "accept",
+        "application/rss+xml, application/atom+xml, application/xml;q=0.9, text/xml;q=0.8",
+    );
+    if let Some(etag) = &state.etag {
+        request = request.header(IF_NONE_MATCH, etag);
+    }
+    if let Some(last_modified) = &state.last_modified {
+        request = request.header(IF_MODIFIED_SINCE, last_modified);
+    }
+
+    let mut response = request.send().await?;
+    if response.status() == StatusCode::NOT_MODIFIED {
+        return Ok(RssPollResult {
+            events: Vec::new(),
+            state,
+        });
+    }
+    if !response.status().is_success() {
+        return Err(RssPollError::Status(response.status().as_u16()));
+    }
+    let header = |name| {
+        response
+            .headers()
+            .get(&name)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string)
+    };
+    let etag = header(ETAG);
+    let last_modified = header(LAST_MODIFIED);
+
+    if response
+        .content_length()
+        .is_some_and(|len| len > MAX_FEED_BYTES as u64)
+    {
+        return Err(RssPollError::TooLarge);
+    }
+    let mut body = Vec::new();
+    while let Some(chunk) = response.chunk().await? {
+        if body.len() + chunk.len() > MAX_FEED_BYTES {
+            return Err(RssPollError::TooLarge);
+        }
+        body.extend_from_slice(&chunk);
+    }
+    let text = String::from_utf8_lossy(&body);
+    let feed = parse_feed(&text).map_err(RssPollError::Parse)?;
+
+    let cap = config
+        .max_items_per_poll
+        .unwrap_or(DEFAULT_MAX_ITEMS_PER_POLL)
+        .clamp(1, MAX_ITEMS_PER_POLL) as usize;
+    let (fired, handled) = select_new_items(&feed.items, &state.seen, cap, baseline);
+
+    let events = fired
+        .into_iter()
+        .map(|item| build_event(feed_url, feed.title.as_deref(), item))
+        .collect();
+    state.seen = merge_seen(&feed.items, &state.seen, &handled);
+    // Items deferred by the cap must come back on the next poll, so only keep
+    // the validators once nothing in this copy of the feed is left unseen.
+    let seen: HashSet<&str> = state.seen.iter().map(String::as_str).collect();
+    let drained = feed
+        .items
+        .iter()
+        .all(|item| seen.contains(item.key().as_str()));
+    (state.etag, state.last_modified) = if drained {
+        (etag, last_modified)
+    } else {
+        (None, None)
+    };
+
+    Ok(RssPollResult { events, state })
+}
+
+/// Returns the items to fire (oldest first) and every key to mark seen.
+/// Feeds list items newest first, so the oldest unseen items sit at the end.
+fn select_new_items<'a>(
+    items: &'a [FeedItem],
+    seen: &[String],
+    cap: usize,
+    baseline: bool,
+) -> (Vec<&'a FeedItem>, HashSet<String>) {
+    let seen: HashSet<&str> = seen.iter().map(String::as_str).collect();
+    let mut keys = HashSet::new();
+    let unseen: Vec<&FeedItem> = items
+        .iter()
+        .filter(|item| {
+            let key = item.key();
+            !seen.contains(key.as_str()) && keys.insert(key)
+        })
+        .collect();
+
+    if baseline {
+        let fired = unseen.iter().take(cap).rev().copied().collect();
+        return (fired, keys);
+    }
+
+    let fired: Vec<&FeedItem> = unseen.iter().rev().take(cap).copied().collect();
+    let handled = fired.iter().map(|item| item.key()).collect();
+    (fired, handled)
+}
+
+fn merge_seen(items: &[FeedItem], previous: &[String], handled: &HashSet<String>) -> Vec<String> {
+    let previous_set: HashSet<&str> = previous.iter().map(String::as_str).collect();
+    let mut merged = Vec::new();
+    let mut included = HashSet::new();
+    for item in items {
+        let key = item.key();
+        if (handled.contains(&key) || previous_set.contains(key.as_str()))
+            && included.insert(key.clone())
+        {
+            merged.push(key);
+        }
+    }
+    for key in previous {
+        if included.insert(key.clone()) {
+            merged.push(key.clone());
+        }
+    }
+    merged.truncate(MAX_TRACKED_ITEMS);
+    merged
+}
+
+fn build_event(feed_url: &str, feed_title: Option<&str>, item: &FeedItem) -> Value {
+    json!({
+        "trigger": RSS_NEW_ITEM,
+        "feedUrl": feed_url,
+        "feedTitle": feed_title,
+        "guid": item.key(),
+        "title": item.title,
+        "link": item.link,
+        "summary": item.summary,
+        "author": item.author,
+        "published": item.published,
+    })
+}
+
+/// Parses RSS 2.0, RSS 1.0 (RDF) and Atom documents. Elements are matched by
+/// local name, so namespaced fields such as `dc:creator` and
+/// `content:encoded` are picked up without namespace bookkeeping.
+pub fn parse_feed(text: &str) -> Result<Feed, String> {
+    let doc = roxmltree::Document::parse(text).map_err(|e| e.to_string())?;
+    let root = doc.root_element();
+    match root.tag_name().name() {
+        "feed" => Ok(Feed {
+            title: child_text(root, "title"),
+            items: children(root, "entry").map(parse_atom_entry).collect(),
+        }),
+        "rss" => {
+            let channel = children(root, "channel")
+                .next()
+                .ok_or_else(|| "RSS document has no channel".to_string())?;
+            Ok(Feed {
+                title: child_text(channel, "title"),
+                items: children(channel, "item").map(parse_rss_item).collect(),
+            })
+        }
+        "RDF" => Ok(Feed {
+            title: children(root, "channel")
+                .next()
+                .and_then(|channel| child_text(channel, "title")),
+            items: children(root, "item").map(parse_rss_item).collect(),
+        }),
+        other => Err(format!("unsupported feed root element `{other}`")),
+    }
+}
+
+fn parse_rss_item(item: roxmltree::Node) -> FeedItem {
+    FeedItem {
+        guid: child_text(item, "guid").or_else(|| {
+            item.attribute(("http://www.w3.org/1999/02/22-rdf-syntax-ns#", "about"))
+                .map(str::to_string)
+        }),
+        title: child_text(item, "title"),
+        link: child_text(item, "link"),
+        summary: child_text(item, "description").or_else(|| child_text(item, "encoded")),
+        author: child_text(item, "author").or_else(|| child_text(item, "creator")),
+        published: child_text(item, "pubDate")
+            .or_else(|| child_text(item, "date"))
+            .map(|raw| normalize_date(&raw)),
+    }
+}
+
+fn parse_atom_entry(entry: roxmltree::Node) -> FeedItem {
+    let links: Vec<roxmltree::Node> = children(entry, "link").collect();
+    let link = links
+        .iter()
+        .find(|link| matches!(link.attribute("rel"), None | Some("alternate")))
+        .or(links.first())
+        .and_then(|link| link.attribute("href"))
+        .map(str::to_string);
+    FeedItem {
+        guid: child_text(entry, "id"),
+        title: child_text(entry, "title"),
+        link,
+        summary: child_text(entry, "summary").or_else(|| child_text(entry, "content")),
+        author: children(entry, "author")
+            .next()
+            .and_then(|author| child_text(author, "name")),
+        published: child_text(entry, "published")
+            .or_else(|| child_text(entry, "updated"))
+            .map(|raw| normalize_date(&raw)),
+    }
+}
+
+fn children<'a, 'input>(
+    node: roxmltree::Node<'a, 'input>,
+    name: &'static str,
+) -> impl Iterator<Item = roxmltree::Node<'a, 'input>> {
+    node.children()
+        .filter(move |child| child.is_element() && child.tag_name().name() == name)
+}
+
+fn child_text(node: roxmltree::Node, name: &'static str) -> Option<String> {
+    let child = children(node, name).next()?;
+    let text: String = child
+        .descendants()
+        .filter(|n| n.is_text())
+        .filter_map(|n| n.text())
+        .collect();
+    let text = text.trim();
+    (!text.is_empty()).then(|| text.to_string())
+}
+
+/// RSS dates are RFC 2822 and Atom dates RFC 3339; both become RFC 3339.
+/// Anything else is passed through untouched.
+fn normalize_date(raw: &str) -> String {
+    DateTime::parse_from_rfc3339(raw)
+        .or_else(|_| DateTime::parse_from_rfc2822(raw))
+        .map(|dt| dt.to_rfc3339())
+        .unwrap_or_else(|_| raw.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use httpmock::{Method::GET, MockServer};
+
+    const RSS: &str = r#"<?xml version="1.0"?>
+<rss version="2.0" xmlns:dc="http://purl.org/dc/elements/1.1/">
+  <channel>
+    <title>Release notes</title>
+    <item>
+      <title>v3</title>
+      <link>https://example.com/v3</link>
+      <guid isPermaLink="false">release-3</guid>
+      <description><![CDATA[<p>Third</p>]]></description>
+      <dc:creator>Ada</dc:creator>
+      <pubDate>Tue, 03 Sep 2024 10:00:00 GMT</pubDate>
+    </item>
+    <item>
+      <title>v2</title>
+      <link>https://example.com/v2</link>
+      <guid>release-2</guid>
+    </item>
+    <item>
+      <title>v1</title>
+      <link>https://example.com/v1</link>
+    </item>
+  </channel>
+</rss>"#;
+
+    fn config(url: String, state: RssTriggerState, cap: Option<u32>) -> RssTriggerConfig {
+        RssTriggerConfig {
+            trigger_type: RSS_NEW_ITEM.into(),
+            feed_url: url,
+            poll_interval_seconds: None,
+            max_items_per_poll: cap,
+            state,
+        }
+    }
+
+    #[test]
+    fn parses_rss_and_atom_items() {
+        let feed = parse_feed(RSS).expect("rss");
+        assert_eq!(feed.title.as_deref(), Some("Release notes"));
+        assert_eq!(feed.items.len(), 3);
+        let first = &feed.items[0];
+        assert_eq!(first.key(), "release-3");
+        assert_eq!(first.summary.as_deref(), Some("<p>Third</p>"));
+        assert_eq!(first.author.as_deref(), Some("Ada"));
+        assert_eq!(
+            first.published.as_deref(),
+            Some("2024-09-03T10:00:00+00:00")
+        );
+        assert_eq!(feed.items[2].key(), "https://example.com/v1");
+
+        let atom = r#"<feed xmlns="http://www.w3.org/2005/Atom">
+  <title>Blog</title>
+  <entry>
+    <id>tag:example.com,2024:1</id>
+    <title>Hello</title>
+    <link rel="edit" href="https://example.com/edit/1"/>
+    <link href="https://example.com/posts/1"/>
+    <author><name>Grace</name></author>
+    <updated>2024-09-01T08:30:00Z</updated>
+    <content type="html">Body</content>
+  </entry>
+</feed>"#;
+        let feed = parse_feed(atom).expect("atom");
+        assert_eq!(feed.title.as_deref(), Some("Blog"));
+        assert_eq!(
+            feed.items[0],
+            FeedItem {
+                guid: Some("tag:example.com,2024:1".into()),
+                title: Some("Hello".into()),
+                link: Some("https://example.com/posts/1".into()),
+                summary: Some("Body".into()),
+                author: Some("Grace".into()),
+                published: Some("2024-09-01T08:30:00+00:00".into()),
+            }
+        );
+
+        assert!(parse_feed("<html></html>").is_err());
+        assert!(parse_feed(r#"<!DOCTYPE rss [<!ENTITY x "y">]><rss/>"#).is_err());
+    }
+
+    #[tokio::test]
+    async fn first_poll_caps_runs_and_later_polls_fire_new_items_oldest_first() {
+        let server = MockServer::start();
+        let mut feed = server.mock(|when, then| {
+            when.method(GET).path("/feed.xml");
+            then.status(200)
+                .header("content-type", "application/rss+xml")
+                .header("etag", "\"v3\"")
+                .body(RSS);
+        });
+        let egress = TriggerEgress::from_env();
+        let url = server.url("/feed.xml");
+
+        let first = poll_feed(
+            &config(url.clone(), RssTriggerState::default(), Some(2)),
+            &egress,
+        )
+        .await
+        .expect("first poll");
+        let titles: Vec<&str> = first
+            .events
+            .iter()
+            .map(|event| event["title"].as_str().unwrap())
+            .collect();
+        assert_eq!(titles, vec!["v2", "v3"]);
+        assert_eq!(first.events[1]["author"], "Ada");
+        assert_eq!(first.events[1]["feedTitle"], "Release notes");
+        assert_eq!(
+            first.state.seen,
+            vec!["release-3", "release-2", "https://example.com/v1"]
+        );
+        assert_eq!(first.state.etag.as_deref(), Some("\"v3\""));
+        feed.delete();
+
+        let updated = RSS.replace(
+            "<item>\n      <title>v3</title>",
+            "<item><title>v5</title><guid>release-5</guid></item>\n    \
+             <item><title>v4</title><guid>release-4</guid></item>\n    \
+             <item>\n      <title>v3</title>",
+        );
+        let mut feed = server.mock(|when, then| {
+            when.method(GET)
+                .path("/feed.xml")
+                .header("if-none-match", "\"v3\"");
+            then.status(200).body(updated.clone());
+        });
+        let second = poll_feed(&config(url.clone(), first.state, Some(1)), &egress)
+            .await
+            .expect("second poll");
+        assert_eq!(second.events.len(), 1);
+        assert_eq!(second.events[0]["title"], "v4");
+        assert_eq!(second.state.seen[0], "release-4");
+        assert_eq!(second.state.etag, None);
+        feed.delete();
+
+        server.mock(|when, then| {
+            when.method(GET).path("/feed.xml");
+            then.status(200).body(updated.clone());
+        });
+        let third = poll_feed(&config(url, second.state, Some(1)), &egress)
+            .await
+            .expect("third poll");
+        assert_eq!(third.events.len(), 1);
+        assert_eq!(third.events[0]["guid"], "release-5");
+    }
+
+    #[tokio::test]
+    async fn not_modified_feeds_keep_state_and_non_http_urls_are_refused() {
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.method(GET).path("/feed.xml");
+            then.status(304);
+        });
+        let url = server.url("/feed.xml");
+        let state = RssTriggerState {
+            feed_url: Some(url.clone()),
+            seen: vec!["release-3".into()],
+            etag: Some("\"v3\"".into()),
+            last_modified: None,
+        };
+        let result = poll_feed(
+            &config(url, state.clone(), None),
+            &TriggerEgress::from_env(),
+        )
+        .await
+        .expect("poll");
+        assert!(result.events.is_empty());
+        assert_eq!(result.state, state);
+
+        let err = poll_feed(
+            &config(
+                "file:///etc/passwd".into(),
+                RssTriggerState::default(),
+                None,
+            ),
+            &TriggerEgress::from_env(),
+        )
+        .await
+        .unwrap_err();
+        assert!(matches!(err, RssPollError::Egress(_)));
+    }
+}
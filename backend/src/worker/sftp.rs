@@ -0,0 +1,319 @@
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use thiserror::Error;
+
+use crate::engine::actions::http::TriggerEgress;
+use crate::services::sftp::{self, RemoteEntry, RemoteEntryKind, SftpError, SftpTarget};
+use crate::utils::secrets::{hydrate_secrets_into_snapshot, read_secret_store};
+
+pub use crate::services::sftp::SFTP_NEW_FILE;
+
+const DEFAULT_MAX_FILES_PER_POLL: u32 = 10;
+const MAX_FILES_PER_POLL: u32 = 50;
+/// Upper bound on file keys remembered in the schedule config. Only keys of
+/// files still in the directory are kept, so this only matters for very
+/// large drop folders.
+const MAX_TRACKED_FILES: usize = 5000;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SftpTriggerState {
+    /// Directory the keys belong to; a different directory re-baselines.
+    #[serde(default)]
+    pub directory: Option<String>,
+    /// `name|size|modified` keys of files already handled.
+    #[serde(default)]
+    pub seen: Vec<String>,
+    /// Keys first listed on the previous poll. A file fires once its key is
+    /// unchanged across two polls, so uploads still in progress are skipped.
+    #[serde(default)]
+    pub pending: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SftpTriggerConfig {
+    #[serde(default)]
+    pub trigger_type: String,
+    #[serde(default)]
+    pub directory: String,
+    #[serde(default)]
+    pub pattern: Option<String>,
+    #[serde(default)]
+    pub poll_interval_seconds: Option<i64>,
+    #[serde(default)]
+    pub max_files_per_poll: Option<u32>,
+    #[serde(default)]
+    pub state: SftpTriggerState,
+}
+
+#[derive(Debug, Error)]
+pub enum SftpPollError {
+    #[error("{0}")]
+    Egress(String),
+    #[error("failed to read SFTP credentials: {0}")]
+    Secrets(String),
+    #[error(transparent)]
+    Sftp(#[from] SftpError),
+}
+
+#[derive(Debug)]
+pub struct SftpPollResult {
+    pub events: Vec<Value>,
+    pub state: SftpTriggerState,
+}
+
+pub fn parse_trigger_config(config: &Value) -> Option<SftpTriggerConfig> {
+    let trigger_type = config.get("triggerType")?.as_str()?;
+    if !trigger_type.trim().eq_ignore_ascii_case(SFTP_NEW_FILE) {
+        return None;
+    }
+    let parsed: SftpTriggerConfig = serde_json::from_value(config.clone()).ok()?;
+    if parsed.directory.trim().is_empty() {
+        return None;
+    }
+    Some(parsed)
+}
+
+/// Resolves the connection stored in the schedule config, replacing secret
+/// references with values from the owner's secret store.
+pub fn resolve_target(
+    config: &Value,
+    settings: &Value,
+    encryption_key: &[u8],
+) -> Result<SftpTarget, SftpPollError> {
+    let (store, _) = read_secret_store(settings, encryption_key)
+        .map_err(|err| SftpPollError::Secrets(err.to_string()))?;
+    let mut hydrated = config.clone();
+    hydrate_secrets_into_snapshot(&mut hydrated, &store);
+    Ok(SftpTarget::from_params(&hydrated)?)
+}
+
+/// Lists the directory and emits one event per new file, oldest first.
+///
+/// The first poll (or the first after the directory changes) only records
+/// what is already there. Afterwards a file fires once it has been listed
+/// with the same size and timestamp on two consecutive polls, and at most
+/// `maxFilesPerPoll` files fire per poll; the rest wait for the next one.
+/// A file that is replaced with different content fires again.
+pub async fn poll_directory(
+    config: &SftpTriggerConfig,
+    target: &SftpTarget,
+    egress: &TriggerEgress,
+) -> Result<SftpPollResult, SftpPollError> {
+    egress
+        .check_host("SFTP", &target.host)
+        .map_err(SftpPollError::Egress)?;
+    let directory = config.directory.trim();
+    let entries = sftp::list_dir(target, directory).await?;
+    Ok(plan_poll(config, &target.host, entries))
+}
+
+fn plan_poll(config: &SftpTriggerConfig, host: &str, entries: Vec<RemoteEntry>) -> SftpPollResult {
+    let directory = config.directory.trim();
+    let pattern = config
+        .pattern
+        .as_deref()
+        .map(str::trim)
+        .filter(|p| !p.is_empty());
+    let mut files: Vec<RemoteEntry> = entries
+        .into_iter()
+        .filter(|e| e.kind == RemoteEntryKind::File)
+        .filter(|e| pattern.is_none_or(|p| sftp::matches_pattern(p, &e.name)))
+        .collect();
+    files.sort_by(|a, b| (&a.modified, &a.name).cmp(&(&b.modified, &b.name)));
+
+    let previous = &config.state;
+    if previous.directory.as_deref() != Some(directory) {
+        return SftpPollResult {
+            events: Vec::new(),
+            state: SftpTriggerState {
+                directory: Some(directory.to_string()),
+                seen: files.iter().map(file_key).take(MAX_TRACKED_FILES).collect(),
+                pending: Vec::new(),
+            },
+        };
+    }
+
+    let cap = config
+        .max_files_per_poll
+        .unwrap_or(DEFAULT_MAX_FILES_PER_POLL)
+        .clamp(1, MAX_FILES_PER_POLL) as usize;
+    let seen: HashSet<&str> = previous.seen.iter().map(String::as_str).collect();
+    let pending: HashSet<&str> = previous.pending.iter().map(String::as_str).collect();
+
+    let mut events = Vec::new();
+    let mut next_seen = Vec::new();
+    let mut next_pending = Vec::new();
+    for file in &files {
+        let key = file_key(file);
+        if seen.contains(key.as_str()) {
+            next_seen.push(key);
+        } else if pending.contains(key.as_str()) && events.len() < cap {
+            events.push(json!({
+                "trigger": SFTP_NEW_FILE,
+                "host": host,
+                "directory": directory,
+                "path": file.path,
+                "name": file.name,
+                "size": file.size,
+                "modified": file.modified,
+            }));
+            next_seen.push(key);
+        } else {
+            next_pending.push(key);
+        }
+    }
+    next_seen.truncate(MAX_TRACKED_FILES);
+    next_pending.truncate(MAX_TRACKED_FILES);
+
+    SftpPollResult {
+        events,
+        state: SftpTriggerState {
+            directory: Some(directory.to_string()),
+            seen: next_seen,
+            pending: next_pending,
+        },
+    }
+}
+
+fn file_key(entry: &RemoteEntry) -> String {
+    format!(
+        "{}|{}|{}",
+        entry.name,
+        entry.size,
+        entry.modified.as_deref().unwrap_or("")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(name: &str, size: u64, modified: &str) -> RemoteEntry {
+        RemoteEntry {
+            name: name.to_string(),
+            path: format!("/in/{name}"),
+            size,
+            modified: Some(modified.to_string()),
+            kind: RemoteEntryKind::File,
+        }
+    }
+
+    fn config(state: SftpTriggerState, max: Option<u32>) -> SftpTriggerConfig {
+        SftpTriggerConfig {
+            trigger_type: SFTP_NEW_FILE.to_string(),
+            directory: "/in".to_string(),
+            pattern: Some("*.csv".to_string()),
+            poll_interval_seconds: None,
+            max_files_per_poll: max,
+            state,
+        }
+    }
+
+    fn names(result: &SftpPollResult) -> Vec<&str> {
+        result
+            .events
+            .iter()
+            .map(|e| e["name"].as_str().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn new_files_fire_once_they_stop_changing() {
+        let t0 = "2024-03-09T08:00:00+00:00";
+        let t1 = "2024-03-09T08:05:00+00:00";
+
+        // Baseline: existing files are recorded, nothing fires.
+        let first = plan_poll(
+            &config(SftpTriggerState::default(), None),
+            "sftp.example",
+            vec![file("old.csv", 10, t0), file("notes.txt", 5, t0)],
+        );
+        assert!(first.events.is_empty());
+        assert_eq!(
+            first.state.seen,
+            vec!["old.csv|10|2024-03-09T08:00:00+00:00"]
+        );
+
+        // A new upload is first seen mid-transfer and only remembered.
+        let second = plan_poll(
+            &config(first.state, None),
+            "sftp.example",
+            vec![file("old.csv", 10, t0), file("orders.csv", 512, t1)],
+        );
+        assert!(second.events.is_empty());
+        assert_eq!(second.state.pending.len(), 1);
+
+        // It grew, so it is still not settled.
+        let third = plan_poll(
+            &config(second.state, None),
+            "sftp.example",
+            vec![file("old.csv", 10, t0), file("orders.csv", 900, t1)],
+        );
+        assert!(third.events.is_empty());
+
+        // Unchanged across two polls: fires once with its metadata.
+        let fourth = plan_poll(
+            &config(third.state, None),
+            "sftp.example",
+            vec![file("old.csv", 10, t0), file("orders.csv", 900, t1)],
+        );
+        assert_eq!(names(&fourth), vec!["orders.csv"]);
+        assert_eq!(fourth.events[0]["path"], "/in/orders.csv");
+        assert_eq!(fourth.events[0]["size"], 900);
+        assert_eq!(fourth.events[0]["trigger"], SFTP_NEW_FILE);
+        assert!(fourth.state.pending.is_empty());
+
+        let fifth = plan_poll(
+            &config(fourth.state, None),
+            "sftp.example",
+            vec![file("orders.csv", 900, t1)],
+        );
+        assert!(fifth.events.is_empty());
+        assert_eq!(fifth.state.seen.len(), 1, "deleted files are forgotten");
+    }
+
+    #[test]
+    fn settled_files_over_the_cap_wait_for_the_next_poll() {
+        let state = SftpTriggerState {
+            directory: Some("/in".to_string()),
+            seen: Vec::new(),
+            pending: vec![
+                "a.csv|1|2024-03-09T08:00:00+00:00".to_string(),
+                "b.csv|1|2024-03-09T08:01:00+00:00".to_string(),
+                "c.csv|1|2024-03-09T08:02:00+00:00".to_string(),
+            ],
+        };
+        let listing = || {
+            vec![
+                file("c.csv", 1, "2024-03-09T08:02:00+00:00"),
+                file("a.csv", 1, "2024-03-09T08:00:00+00:00"),
+                file("b.csv", 1, "2024-03-09T08:01:00+00:00"),
+            ]
+        };
+        let first = plan_poll(&config(state, Some(2)), "sftp.example", listing());
+        assert_eq!(names(&first), vec!["a.csv", "b.csv"]);
+
+        let second = plan_poll(&config(first.state, Some(2)), "sftp.example", listing());
+        assert_eq!(names(&second), vec!["c.csv"]);
+    }
+
+    #[test]
+    fn trigger_config_requires_a_directory() {
+        assert!(parse_trigger_config(&json!({
+            "triggerType": "sftp.new_file",
+            "directory": "/in",
+            "host": "sftp.example",
+        }))
+        .is_some());
+        assert!(parse_trigger_config(&json!({ "triggerType": "sftp.new_file" })).is_none());
+        assert!(parse_trigger_config(&json!({
+            "triggerType": "rss.new_item",
+            "directory": "/in",
+        }))
+        .is_none());
+    }
+}
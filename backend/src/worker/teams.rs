@@ -0,0 +1,396 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use thiserror::Error;
+use time::{format_description::well_known::Rfc3339, OffsetDateTime};
+
+use crate::services::microsoft::{
+    fetch_channel_messages_page, fetch_joined_teams, fetch_team_channels, MicrosoftChannelMessage,
+    MicrosoftGraphError,
+};
+
+pub const TEAMS_NEW_CHANNEL_MESSAGE: &str = "teams.new_channel_message";
+
+const POLL_PAGE_SIZE: u32 = 50;
+/// Upper bound on pages followed in one poll so a cursor that Graph no longer
+/// returns cannot keep the poller walking the whole channel history.
+const MAX_POLL_PAGES: usize = 20;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TeamsTriggerState {
+    #[serde(default)]
+    pub last_seen_created_time: Option<String>,
+    #[serde(default)]
+    pub last_seen_message_id: Option<String>,
+    #[serde(default)]
+    pub team_name: Option<String>,
+    #[serde(default)]
+    pub channel_name: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TeamsTriggerConfig {
+    #[serde(default)]
+    pub trigger_type: String,
+    #[serde(default)]
+    pub connection_scope: String,
+    #[serde(default)]
+    pub connection_id: String,
+    #[serde(default)]
+    pub team_id: String,
+    #[serde(default)]
+    pub channel_id: String,
+    #[serde(default)]
+    pub poll_interval_seconds: Option<i64>,
+    #[serde(default)]
+    pub state: TeamsTriggerState,
+}
+
+#[derive(Debug, Error)]
+pub enum TeamsPollError {
+    #[error(transparent)]
+    Graph(#[from] MicrosoftGraphError),
+    #[error("team {0} is not joined by the connected Microsoft account")]
+    TeamNotFound(String),
+    #[error("channel {0} was not found in the selected team")]
+    ChannelNotFound(String),
+}
+
+#[derive(Debug)]
+pub struct TeamsPollResult {
+    pub events: Vec<Value>,
+    pub state: TeamsTriggerState,
+}
+
+pub fn parse_trigger_config(config: &Value) -> Option<TeamsTriggerConfig> {
+    let trigger_type = config.get("triggerType")?.as_str()?;
+    if !trigger_type
+        .trim()
+        .eq_ignore_ascii_case(TEAMS_NEW_CHANNEL_MESSAGE)
+    {
+        return None;
+    }
+    let parsed: TeamsTriggerConfig = serde_json::from_value(config.clone()).ok()?;
+    if parsed.team_id.trim().is_empty()
+        || parsed.channel_id.trim().is_empty()
+        || parsed.connection_id.trim().is_empty()
+        || parsed.connection_scope.trim().is_empty()
+    {
+        return None;
+    }
+    Some(parsed)
+}
+
+/// Polls the configured channel for root messages created after the stored
+/// `(createdDateTime, id)` cursor, following `@odata.nextLink` until a page
+/// reaches the cursor. The first poll resolves the team and channel through
+/// the joined teams listing (so a connection that lost access fails loudly)
+/// and records the newest message without emitting events.
+pub async fn poll_channel(
+    client: &reqwest::Client,
+    base_url: &str,
+    access_token: &str,
+    config: &TeamsTriggerConfig,
+) -> Result<TeamsPollResult, TeamsPollError> {
+    let team_id = config.team_id.trim();
+    let channel_id = config.channel_id.trim();
+    let mut state = config.state.clone();
+
+    if state.team_name.is_none() || state.channel_name.is_none() {
+        let teams = fetch_joined_teams(client, base_url, access_token).await?;
+        let team = teams
+            .into_iter()
+            .find(|team| team.id == team_id)
+            .ok_or_else(|| TeamsPollError::TeamNotFound(team_id.to_string()))?;
+        let channels = fetch_team_channels(client, base_url, access_token, team_id).await?;
+        let channel = channels
+            .into_iter()
+            .find(|channel| channel.id == channel_id)
+            .ok_or_else(|| TeamsPollError::ChannelNotFound(channel_id.to_string()))?;
+        state.team_name = Some(team.display_name);
+        state.channel_name = Some(channel.display_name);
+    }
+
+    let cursor = state
+        .last_seen_created_time
+        .as_deref()
+        .and_then(parse_timestamp)
+        .map(|created| {
+            (
+                created,
+                state.last_seen_message_id.clone().unwrap_or_default(),
+            )
+        });
+
+    let mut messages: Vec<(OffsetDateTime, MicrosoftChannelMessage)> = Vec::new();
+    let mut next_link: Option<String> = None;
+    for _ in 0..MAX_POLL_PAGES {
+        let page = fetch_channel_messages_page(
+            client,
+            base_url,
+            access_token,
+            team_id,
+            channel_id,
+            POLL_PAGE_SIZE,
+            next_link.as_deref(),
+        )
+        .await?;
+
+        let mut reached_cursor = false;
+        for message in page.messages {
+            let Some(created) = message
+                .created_date_time
+                .as_deref()
+                .and_then(parse_timestamp)
+            else {
+                continue;
+            };
+            match &cursor {
+                Some((seen_at, seen_id))
+                    if (created, message.id.as_str()) <= (*seen_at, seen_id.as_str()) =>
+                {
+                    reached_cursor = true;
+                }
+                _ => messages.push((created, message)),
+            }
+        }
+
+        // Without a cursor only the newest page matters for initialization.
+        next_link = page.next_link;
+        if cursor.is_none() || reached_cursor || next_link.is_none() {
+            break;
+        }
+    }
+    messages.sort_by(|(a_at, a), (b_at, b)| (a_at, &a.id).cmp(&(b_at, &b.id)));
+    messages.dedup_by(|(_, a), (_, b)| a.id == b.id);
+
+    if let Some((created, message)) = messages.last() {
+        state.last_seen_created_time = Some(created.format(&Rfc3339).unwrap_or_default());
+        state.last_seen_message_id = Some(message.id.clone());
+    }
+
+    let events = if cursor.is_some() {
+        messages
+            .into_iter()
+            .map(|(_, message)| build_event(config, &state, message))
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    Ok(TeamsPollResult { events, state })
+}
+
+fn parse_timestamp(raw: &str) -> Option<OffsetDateTime> {
+    OffsetDateTime::parse(raw, &Rfc3339).ok()
+}
+
+fn build_event(
+    config: &TeamsTriggerConfig,
+    state: &TeamsTriggerState,
+    message: MicrosoftChannelMessage,
+) -> Value {
+    json!({
+        "trigger": TEAMS_NEW_CHANNEL_MESSAGE,
+        "teamId": config.team_id.trim(),
+        "teamName": state.team_name,
+        "channelId": config.channel_id.trim(),
+        "channelName": state.channel_name,
+        "messageId": message.id,
+        "text": message.body_content,
+        "from": message.from_display_name,
+        "message": message,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::FakeProvider;
+    use axum::http::{Method, StatusCode};
+
+    const MESSAGES_PATH: &str = "/graph/teams/team-1/channels/channel-1/messages";
+
+    fn config(state: TeamsTriggerState) -> TeamsTriggerConfig {
+        TeamsTriggerConfig {
+            trigger_type: TEAMS_NEW_CHANNEL_MESSAGE.into(),
+            connection_scope: "workspace".into(),
+            connection_id: "conn".into(),
+            team_id: "team-1".into(),
+            channel_id: "channel-1".into(),
+            poll_interval_seconds: None,
+            state,
+        }
+    }
+
+    fn resolved_state(created: &str, id: &str) -> TeamsTriggerState {
+        TeamsTriggerState {
+            last_seen_created_time: Some(created.into()),
+            last_seen_message_id: Some(id.into()),
+            team_name: Some("Ops".into()),
+            channel_name: Some("Alerts".into()),
+        }
+    }
+
+    fn message(id: &str, created: &str, text: &str) -> Value {
+        json!({
+            "id": id,
+            "messageType": "message",
+            "createdDateTime": created,
+            "body": { "contentType": "text", "content": text },
+            "from": { "user": { "id": "u1", "displayName": "Ada" } }
+        })
+    }
+
+    fn event_ids(result: &TeamsPollResult) -> Vec<&str> {
+        result
+            .events
+            .iter()
+            .filter_map(|event| event["messageId"].as_str())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn first_poll_resolves_names_and_initializes_cursor() {
+        let provider = FakeProvider::start().await;
+        provider.respond(
+            Method::GET,
+            "/graph/me/joinedTeams",
+            StatusCode::OK,
+            json!({ "value": [{ "id": "team-1", "displayName": "Ops" }] }),
+        );
+        provider.respond(
+            Method::GET,
+            "/graph/teams/team-1/channels",
+            StatusCode::OK,
+            json!({ "value": [{ "id": "channel-1", "displayName": "Alerts" }] }),
+        );
+        provider.respond(
+            Method::GET,
+            MESSAGES_PATH,
+            StatusCode::OK,
+            json!({ "value": [
+                message("m2", "2024-05-01T10:05:00Z", "second"),
+                message("m1", "2024-05-01T10:00:00Z", "first"),
+            ] }),
+        );
+
+        let result = poll_channel(
+            &reqwest::Client::new(),
+            &provider.base_urls().microsoft_graph,
+            "token",
+            &config(TeamsTriggerState::default()),
+        )
+        .await
+        .expect("poll");
+
+        assert_eq!(provider.requests_to(Method::GET, MESSAGES_PATH).len(), 1);
+        assert!(result.events.is_empty());
+        assert_eq!(result.state.team_name.as_deref(), Some("Ops"));
+        assert_eq!(result.state.channel_name.as_deref(), Some("Alerts"));
+        assert_eq!(
+            result.state.last_seen_created_time.as_deref(),
+            Some("2024-05-01T10:05:00Z")
+        );
+        assert_eq!(result.state.last_seen_message_id.as_deref(), Some("m2"));
+    }
+
+    #[tokio::test]
+    async fn poll_emits_messages_newer_than_cursor_in_order() {
+        let provider = FakeProvider::start().await;
+        provider.respond(
+            Method::GET,
+            MESSAGES_PATH,
+            StatusCode::OK,
+            json!({ "value": [
+                message("m4", "2024-05-01T10:20:00Z", "fourth"),
+                message("m3", "2024-05-01T10:10:00Z", "third"),
+                message("m2", "2024-05-01T10:05:00Z", "second"),
+            ] }),
+        );
+
+        let result = poll_channel(
+            &reqwest::Client::new(),
+            &provider.base_urls().microsoft_graph,
+            "token",
+            &config(resolved_state("2024-05-01T10:05:00Z", "m2")),
+        )
+        .await
+        .expect("poll");
+
+        let requests = provider.requests_to(Method::GET, MESSAGES_PATH);
+        assert_eq!(requests.len(), 1);
+        assert_eq!(
+            requests[0].header("authorization").as_deref(),
+            Some("Bearer token")
+        );
+        assert_eq!(event_ids(&result), vec!["m3", "m4"]);
+        assert_eq!(result.events[0]["text"], "third");
+        assert_eq!(result.events[0]["channelName"], "Alerts");
+        assert_eq!(result.state.last_seen_message_id.as_deref(), Some("m4"));
+    }
+
+    #[tokio::test]
+    async fn poll_follows_next_link_until_cursor_and_keeps_timestamp_ties() {
+        let provider = FakeProvider::start().await;
+        provider.respond(
+            Method::GET,
+            MESSAGES_PATH,
+            StatusCode::OK,
+            json!({
+                "value": [message("m4", "2024-05-01T10:20:00Z", "fourth")],
+                "@odata.nextLink": provider.url(&format!("{MESSAGES_PATH}?$skiptoken=page2")),
+            }),
+        );
+        provider.respond(
+            Method::GET,
+            &format!("{MESSAGES_PATH}?$skiptoken=page2"),
+            StatusCode::OK,
+            json!({ "value": [
+                message("m3", "2024-05-01T10:05:00Z", "same second"),
+                message("m2", "2024-05-01T10:05:00Z", "second"),
+                message("m1", "2024-05-01T10:00:00Z", "first"),
+            ] }),
+        );
+
+        let result = poll_channel(
+            &reqwest::Client::new(),
+            &provider.base_urls().microsoft_graph,
+            "token",
+            &config(resolved_state("2024-05-01T10:05:00Z", "m2")),
+        )
+        .await
+        .expect("poll");
+
+        assert_eq!(provider.requests_to(Method::GET, MESSAGES_PATH).len(), 2);
+        assert_eq!(event_ids(&result), vec!["m3", "m4"]);
+        assert_eq!(
+            result.state.last_seen_created_time.as_deref(),
+            Some("2024-05-01T10:20:00Z")
+        );
+        assert_eq!(result.state.last_seen_message_id.as_deref(), Some("m4"));
+    }
+
+    #[tokio::test]
+    async fn poll_fails_when_team_is_no_longer_joined() {
+        let provider = FakeProvider::start().await;
+        provider.respond(
+            Method::GET,
+            "/graph/me/joinedTeams",
+            StatusCode::OK,
+            json!({ "value": [] }),
+        );
+
+        let err = poll_channel(
+            &reqwest::Client::new(),
+            &provider.base_urls().microsoft_graph,
+            "token",
+            &config(TeamsTriggerState::default()),
+        )
+        .await
+        .expect_err("missing team should fail");
+
+        assert!(matches!(err, TeamsPollError::TeamNotFound(_)));
+    }
+}
@@ -5,6 +5,16 @@ export type FormatterOperation =
   | 'string.replace'
   | 'string.split'
   | 'string.substring'
+  | 'string.regex_extract'
+  | 'string.regex_replace'
+  | 'string.regex_test'
+  | 'string.url_encode'
+  | 'string.url_decode'
+  | 'string.base64_encode'
+  | 'string.base64_decode'
+  | 'string.html_escape'
+  | 'string.strip_html'
+  | 'string.slugify'
   | 'number.add'
   | 'number.subtract'
   | 'number.multiply'
@@ -16,6 +26,21 @@ export type FormatterOperation =
   | 'json.merge'
   | 'json.to_array'
   | 'json.to_object'
+  | 'array.map'
+  | 'array.filter'
+  | 'array.sort'
+  | 'array.unique'
+  | 'array.join'
+  | 'array.length'
+  | 'array.first'
+  | 'array.last'
+  | 'array.chunk'
+  | 'array.group_by'
+  | 'array.sum'
+  | 'array.avg'
+  | 'array.min'
+  | 'array.max'
+  | 'array.zip'
   | 'date.parse'
   | 'date.format'
   | 'date.adjust'
@@ -23,6 +48,10 @@ export type FormatterOperation =
   | 'bool.to_boolean'
   | 'bool.is_empty'
   | 'type.to_string'
+  | 'hash.sha256'
+  | 'hash.hmac_sha256'
+  | 'csv.parse'
+  | 'csv.stringify'
 
 export interface FormatterConfig {
   operation: string
@@ -44,14 +73,26 @@ const NUMERIC_FIELDS = new Set([
   'decimal_places',
   'days',
   'hours',
-  'minutes'
+  'minutes',
+  'size'
 ])
 
-const BOOLEAN_FIELDS = new Set(['trim_items'])
+const BOOLEAN_FIELDS = new Set([
+  'trim_items',
+  'all_matches',
+  'url_safe',
+  'has_header'
+])
 
 const DEFAULT_FIELDS: Partial<Record<FormatterOperation, Record<string, any>>> =
   {
     'json.to_array': { trim_items: true },
+    'string.regex_replace': { all_matches: true },
+    'csv.parse': { delimiter: ',', has_header: true },
+    'csv.stringify': { delimiter: ',' },
+    'hash.sha256': { encoding: 'hex' },
+    'hash.hmac_sha256': { encoding: 'hex' },
+    'array.sort': { direction: 'asc' },
     'date.parse': { format: 'rfc3339' },
     'date.format': { output_format: 'rfc3339' },
     'date.extract': { component: 'year', timezone: 'UTC' }
@@ -64,6 +105,16 @@ const ALLOWED_FIELDS: Record<FormatterOperation, string[]> = {
   'string.replace': ['search_for', 'replace_with'],
   'string.split': ['delimiter', 'index'],
   'string.substring': ['start', 'length'],
+  'string.regex_extract': ['pattern', 'flags', 'all_matches'],
+  'string.regex_replace': ['pattern', 'flags', 'replace_with', 'all_matches'],
+  'string.regex_test': ['pattern', 'flags'],
+  'string.url_encode': [],
+  'string.url_decode': [],
+  'string.base64_encode': ['url_safe'],
+  'string.base64_decode': ['url_safe'],
+  'string.html_escape': [],
+  'string.strip_html': [],
+  'string.slugify': [],
   'number.add': ['value'],
   'number.subtract': ['value'],
   'number.multiply': ['value'],
@@ -75,13 +126,32 @@ const ALLOWED_FIELDS: Record<FormatterOperation, string[]> = {
   'json.merge': ['input_two'],
   'json.to_array': ['delimiter', 'trim_items'],
   'json.to_object': ['key_name'],
+  'array.map': ['field'],
+  'array.filter': ['expression'],
+  'array.sort': ['field', 'direction'],
+  'array.unique': ['field'],
+  'array.join': ['field', 'delimiter'],
+  'array.length': [],
+  'array.first': [],
+  'array.last': [],
+  'array.chunk': ['size'],
+  'array.group_by': ['field'],
+  'array.sum': ['field'],
+  'array.avg': ['field'],
+  'array.min': ['field'],
+  'array.max': ['field'],
+  'array.zip': ['input_two'],
   'date.parse': ['format'],
   'date.format': ['output_format'],
   'date.adjust': ['days', 'hours', 'minutes'],
   'date.extract': ['component', 'timezone'],
   'bool.to_boolean': [],
   'bool.is_empty': [],
-  'type.to_string': []
+  'type.to_string': [],
+  'hash.sha256': ['encoding'],
+  'hash.hmac_sha256': ['key', 'encoding'],
+  'csv.parse': ['delimiter', 'has_header'],
+  'csv.stringify': ['delimiter']
 }
 
 export const OPERATION_GROUPS: {
@@ -96,7 +166,17 @@ export const OPERATION_GROUPS: {
       { label: 'Uppercase', value: 'string.uppercase' },
       { label: 'Replace', value: 'string.replace' },
       { label: 'Split', value: 'string.split' },
-      { label: 'Substring', value: 'string.substring' }
+      { label: 'Substring', value: 'string.substring' },
+      { label: 'Regex Extract', value: 'string.regex_extract' },
+      { label: 'Regex Replace', value: 'string.regex_replace' },
+      { label: 'Regex Test', value: 'string.regex_test' },
+      { label: 'URL Encode', value: 'string.url_encode' },
+      { label: 'URL Decode', value: 'string.url_decode' },
+      { label: 'Base64 Encode', value: 'string.base64_encode' },
+      { label: 'Base64 Decode', value: 'string.base64_decode' },
+      { label: 'Escape HTML', value: 'string.html_escape' },
+      { label: 'Strip HTML Tags', value: 'string.strip_html' },
+      { label: 'Slugify', value: 'string.slugify' }
     ]
   },
   {
@@ -120,6 +200,26 @@ export const OPERATION_GROUPS: {
       { label: 'Convert to Array', value: 'json.to_array' }
     ]
   },
+  {
+    label: 'Arrays',
+    options: [
+      { label: 'Pluck Field', value: 'array.map' },
+      { label: 'Filter', value: 'array.filter' },
+      { label: 'Sort', value: 'array.sort' },
+      { label: 'Unique', value: 'array.unique' },
+      { label: 'Join', value: 'array.join' },
+      { label: 'Length', value: 'array.length' },
+      { label: 'First Item', value: 'array.first' },
+      { label: 'Last Item', value: 'array.last' },
+      { label: 'Chunk', value: 'array.chunk' },
+      { label: 'Group By', value: 'array.group_by' },
+      { label: 'Sum', value: 'array.sum' },
+      { label: 'Average', value: 'array.avg' },
+      { label: 'Minimum', value: 'array.min' },
+      { label: 'Maximum', value: 'array.max' },
+      { label: 'Zip', value: 'array.zip' }
+    ]
+  },
   {
     label: 'Dates',
     options: [
@@ -139,9 +239,28 @@ export const OPERATION_GROUPS: {
   {
     label: 'Type Conversion',
     options: [{ label: 'Convert to String', value: 'type.to_string' }]
+  },
+  {
+    label: 'Hashing',
+    options: [
+      { label: 'SHA-256', value: 'hash.sha256' },
+      { label: 'HMAC SHA-256', value: 'hash.hmac_sha256' }
+    ]
+  },
+  {
+    label: 'CSV',
+    options: [
+      { label: 'Parse CSV', value: 'csv.parse' },
+      { label: 'Convert to CSV', value: 'csv.stringify' }
+    ]
   }
 ]
 
+export const DIGEST_ENCODING_OPTIONS = [
+  { label: 'Hex', value: 'hex' },
+  { label: 'Base64', value: 'base64' }
+] as const
+
 export const DATE_FORMAT_OPTIONS = [
   { label: 'ISO 8601 / RFC3339', value: 'rfc3339' },
   { label: 'RFC2822', value: 'rfc2822' },
@@ -303,6 +422,18 @@ export const validateFormatterConfig = (
         }
         break
       }
+      case 'string.regex_extract':
+      case 'string.regex_replace':
+      case 'string.regex_test':
+        if (!fields.pattern || !String(fields.pattern).trim()) {
+          messages.push('Regex pattern is required.')
+        }
+        break
+      case 'hash.hmac_sha256':
+        if (!fields.key || !String(fields.key).trim()) {
+          messages.push('HMAC key is required.')
+        }
+        break
       case 'number.add':
       case 'number.subtract':
       case 'number.multiply':
@@ -336,6 +467,27 @@ export const validateFormatterConfig = (
           messages.push('Key name is required.')
         }
         break
+      case 'array.map':
+      case 'array.group_by':
+        if (!fields.field || !String(fields.field).trim()) {
+          messages.push('Field is required.')
+        }
+        break
+      case 'array.filter':
+        if (!fields.expression || !String(fields.expression).trim()) {
+          messages.push('Filter expression is required.')
+        }
+        break
+      case 'array.chunk':
+        if (!hasNumericValue(fields.size) || (fields.size as number) < 1) {
+          messages.push('Chunk size must be at least 1.')
+        }
+        break
+      case 'array.zip':
+        if (!fields.input_two || !String(fields.input_two).trim()) {
+          messages.push('Provide the second JSON array.')
+        }
+        break
       case 'date.parse':
         if (!fields.format || !String(fields.format).trim()) {
           messages.push('Select an input format.')
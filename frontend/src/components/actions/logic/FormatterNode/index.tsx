@@ -5,6 +5,7 @@ import NodeCheckBoxField from '@/components/ui/InputFields/NodeCheckboxField'
 import {
   DATE_COMPONENT_OPTIONS,
   DATE_FORMAT_OPTIONS,
+  DIGEST_ENCODING_OPTIONS,
   OPERATION_GROUPS,
   TIMEZONE_OPTIONS,
   createEmptyFormatterConfig,
@@ -65,6 +66,97 @@ const FIELD_DEFINITIONS: Partial<
       placeholder: '4 or {{template}}'
     }
   ],
+  'string.regex_extract': [
+    {
+      key: 'pattern',
+      label: 'Pattern',
+      type: 'text',
+      placeholder: '(?P<id>\\d+)'
+    },
+    {
+      key: 'flags',
+      label: 'Flags',
+      type: 'text',
+      placeholder: 'i, m, s or x'
+    },
+    { key: 'all_matches', label: 'All Matches', type: 'toggle' }
+  ],
+  'string.regex_replace': [
+    {
+      key: 'pattern',
+      label: 'Pattern',
+      type: 'text',
+      placeholder: '(?P<id>\\d+)'
+    },
+    {
+      key: 'flags',
+      label: 'Flags',
+      type: 'text',
+      placeholder: 'i, m, s or x'
+    },
+    {
+      key: 'replace_with',
+      label: 'Replace With',
+      type: 'text',
+      placeholder: '$1 or ${name}'
+    },
+    { key: 'all_matches', label: 'Replace All', type: 'toggle' }
+  ],
+  'string.regex_test': [
+    {
+      key: 'pattern',
+      label: 'Pattern',
+      type: 'text',
+      placeholder: '(?P<id>\\d+)'
+    },
+    {
+      key: 'flags',
+      label: 'Flags',
+      type: 'text',
+      placeholder: 'i, m, s or x'
+    }
+  ],
+  'string.base64_encode': [
+    { key: 'url_safe', label: 'URL-safe Alphabet', type: 'toggle' }
+  ],
+  'string.base64_decode': [
+    { key: 'url_safe', label: 'URL-safe Alphabet', type: 'toggle' }
+  ],
+  'hash.sha256': [
+    {
+      key: 'encoding',
+      label: 'Encoding',
+      type: 'select',
+      options: DIGEST_ENCODING_OPTIONS as unknown as {
+        label: string
+        value: string
+      }[]
+    }
+  ],
+  'hash.hmac_sha256': [
+    {
+      key: 'key',
+      label: 'Secret Key',
+      type: 'text',
+      placeholder: 'Signing key or {{template}}'
+    },
+    {
+      key: 'encoding',
+      label: 'Encoding',
+      type: 'select',
+      options: DIGEST_ENCODING_OPTIONS as unknown as {
+        label: string
+        value: string
+      }[]
+    }
+  ],
+  'csv.parse': [
+    { key: 'delimiter', label: 'Delimiter', type: 'text', placeholder: ',' },
+    { key: 'has_header', label: 'First Row Is Header', type: 'toggle' }
+  ],
+  'csv.stringify': [
+    { key: 'delimiter', label: 'Delimiter', type: 'text', placeholder: ',' }
+  ],
   'number.add': [
     {
       key: 'value',
@@ -128,6 +220,112 @@ const FIELD_DEFINITIONS: Partial<
   'json.to_object': [
     { key: 'key_name', label: 'Key Name', type: 'text', placeholder: 'key' }
   ],
+  'array.map': [
+    {
+      key: 'field',
+      label: 'Field',
+      type: 'text',
+      placeholder: 'user.email'
+    }
+  ],
+  'array.filter': [
+    {
+      key: 'expression',
+      label: 'Keep Items Where',
+      type: 'text',
+      placeholder: 'item.status == open'
+    }
+  ],
+  'array.sort': [
+    {
+      key: 'field',
+      label: 'Field',
+      type: 'text',
+      placeholder: 'Optional, e.g. createdAt'
+    },
+    {
+      key: 'direction',
+      label: 'Direction',
+      type: 'select',
+      options: [
+        { label: 'Ascending', value: 'asc' },
+        { label: 'Descending', value: 'desc' }
+      ]
+    }
+  ],
+  'array.unique': [
+    {
+      key: 'field',
+      label: 'Field',
+      type: 'text',
+      placeholder: 'Optional, e.g. id'
+    }
+  ],
+  'array.join': [
+    {
+      key: 'field',
+      label: 'Field',
+      type: 'text',
+      placeholder: 'Optional, e.g. name'
+    },
+    { key: 'delimiter', label: 'Delimiter', type: 'text', placeholder: ',' }
+  ],
+  'array.chunk': [
+    {
+      key: 'size',
+      label: 'Chunk Size',
+      type: 'number',
+      placeholder: '10 or {{template}}'
+    }
+  ],
+  'array.group_by': [
+    {
+      key: 'field',
+      label: 'Field',
+      type: 'text',
+      placeholder: 'team'
+    }
+  ],
+  'array.sum': [
+    {
+      key: 'field',
+      label: 'Field',
+      type: 'text',
+      placeholder: 'Optional, e.g. amount'
+    }
+  ],
+  'array.avg': [
+    {
+      key: 'field',
+      label: 'Field',
+      type: 'text',
+      placeholder: 'Optional, e.g. amount'
+    }
+  ],
+  'array.min': [
+    {
+      key: 'field',
+      label: 'Field',
+      type: 'text',
+      placeholder: 'Optional, e.g. amount'
+    }
+  ],
+  'array.max': [
+    {
+      key: 'field',
+      label: 'Field',
+      type: 'text',
+      placeholder: 'Optional, e.g. amount'
+    }
+  ],
+  'array.zip': [
+    {
+      key: 'input_two',
+      label: 'Second Array',
+      type: 'text',
+      placeholder: '["a", "b"]'
+    }
+  ],
   'date.parse': [
     {
       key: 'format',
@@ -64,7 +64,7 @@ const PROVIDERS: ProviderMeta[] = [
     description:
       'Connect your Google Workspace account to enable actions that call Gmail, Calendar, and other Google APIs on your behalf.',
     scopes:
-      'openid email profile userinfo ./auth/drive.file ./auth/spreadsheets'
+      'openid email profile userinfo ./auth/drive ./auth/spreadsheets ./auth/calendar'
   },
   {
     key: 'slack',